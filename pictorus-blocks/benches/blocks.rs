@@ -0,0 +1,57 @@
+//! Benchmarks for the hot core blocks.
+//!
+//! The cases in `common` are also run on hardware by the cycle counter in `benches/cortex-m`.
+//! For output that CI tooling can compare between runs, use criterion's bencher format:
+//! ```text
+//! cargo bench -p pictorus-blocks --bench blocks --features alloc -- --output-format bencher
+//! ```
+use criterion::{criterion_group, criterion_main, Criterion};
+
+mod common;
+
+fn bench_common(c: &mut Criterion) {
+    common::for_each_case(|name, run| {
+        c.bench_function(name, |b| b.iter(&mut *run));
+    });
+}
+
+#[cfg(feature = "alloc")]
+fn bench_bytes(c: &mut Criterion) {
+    use criterion::black_box;
+    use pictorus_blocks::{BytesPackBlock, BytesUnpackBlock};
+    use pictorus_test_utils::StubContext;
+    use pictorus_traits::ProcessBlock;
+
+    const SPEC: [&str; 4] = [
+        "F32:BigEndian",
+        "U16:LittleEndian",
+        "I32:BigEndian",
+        "F64:LittleEndian",
+    ];
+
+    let context = StubContext::default();
+    let mut pack = BytesPackBlock::<(f64, f64, f64, f64)>::default();
+    let pack_parameters =
+        <BytesPackBlock<(f64, f64, f64, f64)> as ProcessBlock>::Parameters::new(&SPEC);
+    let inputs = (1.5, 513.0, -70000.0, 0.125);
+    c.bench_function("bytes_pack_4_fields", |b| {
+        b.iter(|| {
+            pack.process(&pack_parameters, &context, black_box(inputs));
+        })
+    });
+
+    let packed = pack.buffer().to_vec();
+    let mut unpack = BytesUnpackBlock::<5>::default();
+    let unpack_parameters = <BytesUnpackBlock<5> as ProcessBlock>::Parameters::new(&SPEC, 1000.0);
+    c.bench_function("bytes_unpack_4_fields", |b| {
+        b.iter(|| {
+            unpack.process(&unpack_parameters, &context, black_box(&packed));
+        })
+    });
+}
+
+#[cfg(not(feature = "alloc"))]
+fn bench_bytes(_c: &mut Criterion) {}
+
+criterion_group!(benches, bench_common, bench_bytes);
+criterion_main!(benches);
@@ -0,0 +1,75 @@
+//! Benchmark cases shared by the criterion benches (`benches/blocks.rs`) and the Cortex-M cycle
+//! counter (`benches/cortex-m`), so host and target numbers measure the same work. This module
+//! must stay `no_std` and alloc-free.
+use core::hint::black_box;
+
+use pictorus_blocks::{FFTBlock, MatrixMultiply, ProductBlock, SumBlock, TrigonometryBlock};
+use pictorus_test_utils::StubContext;
+use pictorus_traits::{Matrix, ProcessBlock};
+
+/// Samples fed to the FFT block per iteration. Each iteration performs exactly one transform.
+pub const FFT_SIZE: usize = 256;
+
+/// Calls `bench` once per case with the case name and a closure that runs one iteration
+pub fn for_each_case(mut bench: impl FnMut(&'static str, &mut dyn FnMut())) {
+    let context = StubContext::default();
+
+    {
+        type Mat = Matrix<8, 8, f32>;
+        type Block = ProductBlock<(Mat, Mat), MatrixMultiply>;
+        let mut block = Block::default();
+        let parameters = <Block as ProcessBlock>::Parameters::new();
+        let (a, b) = (ramp::<8, 8>(1.0), ramp::<8, 8>(-0.5));
+        bench("product_matrix_multiply_8x8_f32", &mut || {
+            black_box(block.process(&parameters, &context, black_box((&a, &b))));
+        });
+    }
+
+    {
+        type Mat = Matrix<16, 16, f32>;
+        type Block = SumBlock<(Mat, Mat, Mat)>;
+        let mut block = Block::default();
+        let parameters = <Block as ProcessBlock>::Parameters::new([1.0, -1.0, 1.0]);
+        let (a, b, c) = (ramp(1.0), ramp(2.0), ramp(3.0));
+        bench("sum_3x_16x16_f32", &mut || {
+            black_box(block.process(&parameters, &context, black_box((&a, &b, &c))));
+        });
+    }
+
+    {
+        type Block = FFTBlock<f32, FFT_SIZE>;
+        let mut block = Block::default();
+        let parameters = <Block as ProcessBlock>::Parameters::new();
+        let samples = ramp::<1, FFT_SIZE>(0.0);
+        bench("fft_256_f32", &mut || {
+            for sample in samples.data.as_flattened() {
+                block.process(&parameters, &context, black_box(*sample));
+            }
+            black_box(block.buffer());
+        });
+    }
+
+    {
+        // Compare with and without the `fast-math` feature
+        type Mat = Matrix<1, 64, f32>;
+        type Block = TrigonometryBlock<Mat>;
+        let mut block = Block::default();
+        let parameters = <Block as ProcessBlock>::Parameters::new("Sine");
+        let angles = ramp(-0.3);
+        bench("trigonometry_sine_64_f32", &mut || {
+            black_box(block.process(&parameters, &context, black_box(&angles)));
+        });
+    }
+}
+
+/// A matrix of distinct, non-trivial values so no case hits a fast path for zeros
+pub fn ramp<const NROWS: usize, const NCOLS: usize>(offset: f32) -> Matrix<NROWS, NCOLS, f32> {
+    let mut matrix = Matrix::zeroed();
+    matrix
+        .data
+        .as_flattened_mut()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, v)| *v = i as f32 * 0.01 + offset);
+    matrix
+}
@@ -0,0 +1,6 @@
+/* STM32F429ZI. Adjust for the board being measured. */
+MEMORY
+{
+  FLASH : ORIGIN = 0x08000000, LENGTH = 2048K
+  RAM   : ORIGIN = 0x20000000, LENGTH = 192K
+}
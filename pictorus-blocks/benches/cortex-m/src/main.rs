@@ -0,0 +1,53 @@
+//! Counts the CPU cycles each benchmark case in `benches/common` takes on a Cortex-M core, using
+//! the DWT cycle counter. Run it on a board with:
+//! ```text
+//! cd pictorus-blocks/benches/cortex-m
+//! cargo run --release
+//! ```
+//! Results are printed over semihosting in the libtest bencher format used by
+//! `cargo bench -- --output-format bencher`, reporting the fastest of [`RUNS`] runs and the
+//! spread between the fastest and slowest, so they can be compared between builds by the same
+//! tooling as the host benchmarks.
+#![no_std]
+#![no_main]
+
+use cortex_m::peripheral::{Peripherals, DWT};
+use cortex_m_rt::entry;
+use cortex_m_semihosting::{debug, hprintln};
+use panic_halt as _;
+
+#[path = "../../common/mod.rs"]
+mod common;
+
+/// Timed runs per case, after one untimed run to warm the caches and the block state
+const RUNS: usize = 8;
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    peripherals.DCB.enable_trace();
+    peripherals.DWT.enable_cycle_counter();
+
+    common::for_each_case(|name, run| {
+        run();
+        let (mut min, mut max) = (u32::MAX, 0);
+        for _ in 0..RUNS {
+            let start = DWT::cycle_count();
+            run();
+            let cycles = DWT::cycle_count().wrapping_sub(start);
+            min = min.min(cycles);
+            max = max.max(cycles);
+        }
+        hprintln!(
+            "test {} ... bench: {} cycles/iter (+/- {})",
+            name,
+            min,
+            max - min
+        );
+    });
+
+    debug::exit(debug::EXIT_SUCCESS);
+    loop {
+        cortex_m::asm::nop();
+    }
+}
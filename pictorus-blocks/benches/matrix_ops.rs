@@ -0,0 +1,65 @@
+//! Benchmarks for the element-wise matrix paths of the Gain, Sum and Product blocks.
+//!
+//! Run with:
+//! ```text
+//! cargo bench -p pictorus-blocks --bench matrix_ops
+//! ```
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use pictorus_blocks::{ComponentWise, GainBlock, ProductBlock, SumBlock};
+use pictorus_test_utils::StubContext;
+use pictorus_traits::{Matrix, ProcessBlock};
+
+const N: usize = 32;
+
+type Mat = Matrix<N, N, f32>;
+
+fn input(offset: f32) -> Mat {
+    let mut matrix = Mat::zeroed();
+    matrix
+        .data
+        .as_flattened_mut()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, v)| *v = i as f32 * 0.01 + offset);
+    matrix
+}
+
+fn bench_gain(c: &mut Criterion) {
+    let context = StubContext::default();
+    let mut block = GainBlock::<f32, Mat>::default();
+    let parameters = <GainBlock<f32, Mat> as ProcessBlock>::Parameters::new(1.5);
+    let a = input(1.0);
+    c.bench_function("gain_32x32_f32", |b| {
+        b.iter(|| {
+            block.process(&parameters, &context, black_box(&a));
+        })
+    });
+}
+
+fn bench_sum(c: &mut Criterion) {
+    let context = StubContext::default();
+    let mut block = SumBlock::<(Mat, Mat, Mat)>::default();
+    let parameters = <SumBlock<(Mat, Mat, Mat)> as ProcessBlock>::Parameters::new([1.0, -1.0, 1.0]);
+    let (a, b, c_in) = (input(1.0), input(2.0), input(3.0));
+    c.bench_function("sum_3x_32x32_f32", |bench| {
+        bench.iter(|| {
+            block.process(&parameters, &context, black_box((&a, &b, &c_in)));
+        })
+    });
+}
+
+fn bench_product(c: &mut Criterion) {
+    let context = StubContext::default();
+    let mut block = ProductBlock::<(Mat, Mat), ComponentWise>::default();
+    let parameters =
+        <ProductBlock<(Mat, Mat), ComponentWise> as ProcessBlock>::Parameters::new([1.0, 1.0]);
+    let (a, b) = (input(1.0), input(2.0));
+    c.bench_function("product_component_wise_32x32_f32", |bench| {
+        bench.iter(|| {
+            block.process(&parameters, &context, black_box((&a, &b)));
+        })
+    });
+}
+
+criterion_group!(benches, bench_gain, bench_sum, bench_product);
+criterion_main!(benches);
@@ -0,0 +1,616 @@
+//! Instantiates every block available in a `no_std`, alloc-free `panic-free` build of
+//! pictorus-blocks, and fails to link if any of them can still reach a panic. Check it with:
+//! ```text
+//! cd pictorus-blocks/panic-check
+//! cargo build --release
+//! ```
+//! The panic handler calls a function that's never defined, so the link only succeeds if LTO
+//! removed every call to it, i.e. no panic survived optimization. The linker can only point at
+//! the handler, so to find the block responsible, make the handler `loop {}` so the build
+//! links, and look for calls into `core::panicking` in the disassembly of each `run` instance
+//! (building with `-C symbol-mangling-version=v0` keeps the block type in their names).
+//!
+//! Parameters are built from literals like generated code does, then hidden from the optimizer
+//! along with the block state, inputs and context, so every runtime path of `process` is checked.
+//!
+//! A few blocks only reach panics in code outside this repo, so they're left out:
+//! - `CliBlock` and `StringParseBlock` parse floats with `core`'s parser, which keeps its
+//!   bounds checks
+//! - `MatrixInverseBlock` with the `Svd` method uses nalgebra's SVD, which asserts internally
+//!
+//! The `encryption` feature's blocks aren't covered yet.
+#![no_std]
+#![no_main]
+
+use core::hint::black_box;
+use core::panic::PanicInfo;
+use core::time::Duration;
+
+use embedded_can::{Id, StandardId};
+use pictorus_blocks::*;
+use pictorus_test_utils::StubContext;
+use pictorus_traits::{
+    signal_enum, EnumSignal, GeneratorBlock, HasIc, InputBlock, Matrix, OutputBlock, PassBy,
+    ProcessBlock,
+};
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    extern "Rust" {
+        fn a_panic_is_reachable_from_a_block() -> !;
+    }
+    // SAFETY: never called if the build links
+    unsafe { a_panic_is_reachable_from_a_block() }
+}
+
+#[no_mangle]
+pub extern "C" fn _start() -> ! {
+    check_blocks();
+    loop {
+        black_box(());
+    }
+}
+
+type M23 = Matrix<2, 3, f64>;
+type M33 = Matrix<3, 3, f64>;
+
+signal_enum! {
+    enum Mode {
+        Manual = 0,
+        Stabilized = 1,
+        Mission = 4,
+    }
+}
+
+static TOPIC: signal_bus::Topic<f64> = signal_bus::Topic::new("topic", 0.0);
+
+/// A classic CAN frame, like a HAL provides
+struct Frame {
+    id: Id,
+    data: [u8; 8],
+    dlc: usize,
+}
+
+impl embedded_can::Frame for Frame {
+    fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+        let mut frame = Self {
+            id: id.into(),
+            data: [0; 8],
+            dlc: data.len(),
+        };
+        frame.data.get_mut(..data.len())?.copy_from_slice(data);
+        Some(frame)
+    }
+
+    fn new_remote(_id: impl Into<Id>, _dlc: usize) -> Option<Self> {
+        None
+    }
+
+    fn is_extended(&self) -> bool {
+        matches!(self.id, Id::Extended(_))
+    }
+
+    fn is_remote_frame(&self) -> bool {
+        false
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn dlc(&self) -> usize {
+        self.dlc
+    }
+
+    fn data(&self) -> &[u8] {
+        self.data.get(..self.dlc).unwrap_or_default()
+    }
+}
+
+/// Decodes each byte of a frame as a signal, like a generated receive callback
+fn decode_frame(frame: &Frame, signals: &mut [f64]) {
+    use embedded_can::Frame as _;
+    for (signal, byte) in signals.iter_mut().zip(frame.data()) {
+        *signal = f64::from(*byte);
+    }
+}
+
+fn context() -> StubContext {
+    black_box(StubContext::new(
+        Duration::from_micros(1_000),
+        Some(Duration::from_micros(1_000)),
+        Duration::from_micros(1_000),
+    ))
+}
+
+#[inline(never)]
+fn run<B: ProcessBlock>(block: B, parameters: B::Parameters, inputs: PassBy<'_, B::Inputs>) {
+    let mut block = black_box(block);
+    let parameters = black_box(parameters);
+    black_box(block.process(&parameters, &context(), black_box(inputs)));
+}
+
+#[inline(never)]
+fn generate<B: GeneratorBlock>(parameters: B::Parameters) {
+    let mut block = black_box(B::default());
+    let parameters = black_box(parameters);
+    black_box(block.generate(&parameters, &context()));
+}
+
+/// `process!(Block, new(..), inputs)` runs a block with parameters built by `new`, or by
+/// `try_new`, skipping the block if they're invalid
+macro_rules! process {
+    ($block:ty, try_new($($arg:expr),* $(,)?), $inputs:expr) => {
+        if let Ok(parameters) = <$block as ProcessBlock>::Parameters::try_new($($arg),*) {
+            run(<$block>::default(), parameters, $inputs);
+        }
+    };
+    ($block:ty, $new:ident($($arg:expr),* $(,)?), $inputs:expr) => {
+        run(
+            <$block>::default(),
+            <$block as ProcessBlock>::Parameters::$new($($arg),*),
+            $inputs,
+        );
+    };
+}
+
+/// Like [`process!`], for blocks constructed from their parameters with [`HasIc`]
+macro_rules! process_ic {
+    ($block:ty, try_new($($arg:expr),* $(,)?), $inputs:expr) => {
+        if let Ok(parameters) = <$block as ProcessBlock>::Parameters::try_new($($arg),*) {
+            run(<$block as HasIc>::new(&parameters), parameters, $inputs);
+        }
+    };
+    ($block:ty, $new:ident($($arg:expr),* $(,)?), $inputs:expr) => {
+        let parameters = <$block as ProcessBlock>::Parameters::$new($($arg),*);
+        run(<$block as HasIc>::new(&parameters), parameters, $inputs);
+    };
+}
+
+fn check_blocks() {
+    let x = black_box(0.5);
+    let b = black_box(true);
+    let m = black_box(Matrix::<2, 3, f64>::zeroed());
+    let m33 = black_box(Matrix::<3, 3, f64>::zeroed());
+    let row = black_box(Matrix::<1, 3, f64>::zeroed());
+    let bytes: &[u8] = black_box(&[0; 32]);
+    let text: &str = black_box("1.5");
+
+    process!(AbsBlock<f64>, new(), x);
+    process!(AbsBlock<M23>, new(), &m);
+    process!(AdcBlock<u16, f64>, new(), black_box(1_000));
+    process!(AggregateBlock<M23>, try_new("Mean"), &m);
+    process!(ArgMinMaxBlock<M23>, try_new("Max"), &m);
+    generate::<AppTimeBlock<f64>>(<AppTimeBlock<f64> as GeneratorBlock>::Parameters::new());
+    process!(AssertBlock<f64>, try_new("Range", -1.0, 1.0, 1.0, false), x);
+    process!(AssertBlock<M23>, try_new("Rate", -1.0, 1.0, 1.0, false), &m);
+    process!(
+        BandEnergyBlock<f64, 16, 2>,
+        new([1.0, 10.0], [5.0, 50.0]),
+        x
+    );
+    process!(BiasBlock<f64, f64>, new(1.0), x);
+    process!(BiasBlock<f64, M23>, new(1.0), &m);
+    process!(BitShiftBlock<f64>, try_new("Left", 2), x);
+    process!(BitShiftBlock<i16>, try_new("Right", 3), black_box(-300));
+    process!(
+        BitwiseOperatorBlock<(u16, u16)>,
+        try_new("Xor"),
+        (black_box(3), black_box(5))
+    );
+    if let Ok(parameters) = <BuildInfoBlock<f64> as GeneratorBlock>::Parameters::try_new(
+        "9f86d081884c7d659a2feaa0c55ad015",
+        "1.4.0",
+        1_767_225_600.0,
+    ) {
+        generate::<BuildInfoBlock<f64>>(parameters);
+    }
+    {
+        let mut block = black_box(BusPublishBlock::<f64>::default());
+        let parameters = black_box(BusPublishBlockParams::new(&TOPIC));
+        block.output(&parameters, &context(), x);
+    }
+    generate::<BusSubscribeBlock<f64>>(BusSubscribeBlockParams::new(&TOPIC, 50.0));
+    generate::<BytesLiteralBlock<4>>(<BytesLiteralBlock<4> as GeneratorBlock>::Parameters::new(
+        *b"test",
+    ));
+    run(
+        CanReceiveBlock::<2, f64, Frame, (f64, f64)>::new(decode_frame),
+        CanReceiveBlockParams::new(Id::Standard(StandardId::ZERO), 2, 50.0),
+        bytes,
+    );
+    process_ic!(ChangeDetectionBlock<f64>, try_new(0.0, "Any"), x);
+    if let Ok(parameters) =
+        <ChangeDetectionBlock<f64> as ProcessBlock>::Parameters::try_with_tolerance(0.0, "Any", 0.1)
+    {
+        run(ChangeDetectionBlock::<f64>::new(&parameters), parameters, x);
+    }
+    process_ic!(
+        ChangeDetectionBlock<M23>,
+        try_new(M23::zeroed(), "Rising"),
+        &m
+    );
+    process!(ClampBlock<f64>, new(-1.0, 1.0), x);
+    process!(ClampBlock<M23>, new(-1.0, 1.0), &m);
+    process!(ClarkeBlock<(f64, f64, f64), f64>, new(), (x, x, x));
+    process!(CompareToValueBlock<f64>, try_new("GreaterThan", 1.0), x);
+    process!(CompareToValueBlock<M23>, try_new("Equal", 1.0), &m);
+    process!(ComparisonBlock<(f64, f64)>, try_new("LessThan"), (x, x));
+    generate::<ConstantBlock<f64>>(<ConstantBlock<f64> as GeneratorBlock>::Parameters::new(1.0));
+    process!(
+        ControlAllocationBlock<f64, 2, 3>,
+        new(
+            Matrix {
+                data: [[1.0, 0.0], [0.0, 1.0], [1.0, 1.0]]
+            },
+            [-1.0; 3],
+            [1.0; 3]
+        ),
+        &black_box(Matrix::<1, 2, f64>::zeroed())
+    );
+    process!(
+        ConvolutionBlock<(Matrix<1, 3, f64>, Matrix<1, 2, f64>), Matrix<1, 4, f64>>,
+        new(),
+        (&row, &black_box(Matrix::<1, 2, f64>::zeroed()))
+    );
+    process!(
+        CorrelationBlock<(Matrix<1, 3, f64>, Matrix<1, 3, f64>), Matrix<1, 5, f64>, f64>,
+        try_new("Unbiased"),
+        (&row, &row)
+    );
+    process_ic!(
+        CoulombCounterBlock<f64, 3>,
+        new(
+            1.0,
+            2.0,
+            0.99,
+            [3.0, 3.7, 4.2],
+            [0.0, 0.5, 1.0],
+            0.05,
+            60.0,
+            0.5,
+            0.2,
+            0.05
+        ),
+        (x, x)
+    );
+    process!(CounterBlock<(f64, bool), f64>, new(), (x, b));
+    process!(
+        CrossProductBlock<(Matrix<1, 3, f64>, Matrix<1, 3, f64>)>,
+        new(),
+        (&row, &row)
+    );
+    process!(
+        DacBlock<Matrix<1, 2, f64>>,
+        new(),
+        &black_box(Matrix::<1, 2, f64>::zeroed())
+    );
+    // Built directly, as parsing the DBC syntax uses `core`'s float parser
+    run(
+        DbcDecodeBlock::<2>::default(),
+        DbcDecodeBlockParams {
+            signals: [
+                DbcSignal {
+                    start_bit: 0,
+                    length: 8,
+                    little_endian: true,
+                    signed: false,
+                    scale: 0.1,
+                    offset: 0.0,
+                },
+                DbcSignal {
+                    start_bit: 15,
+                    length: 16,
+                    little_endian: false,
+                    signed: true,
+                    scale: 1.0,
+                    offset: 0.0,
+                },
+            ],
+            stale_age: Duration::from_millis(100),
+        },
+        bytes,
+    );
+    process!(DeadbandBlock<f64>, new(-1.0, 1.0), x);
+    process!(DeadbandBlock<M23>, new(-1.0, 1.0), &m);
+    process_ic!(DelayBlock<f64, 3>, new(0.0, true), x);
+    process!(DelayControlBlock<f64>, try_new(0.5, "Debounce"), x);
+    process_ic!(DerivativeBlock<f64, 3>, new(0.0), x);
+    process_ic!(DerivativeBlock<M23, 3>, new(M23::zeroed()), &m);
+    process!(DeterminantBlock<f64, M33>, new(), &m33);
+    process!(
+        DotProductBlock<(Matrix<1, 3, f64>, Matrix<1, 3, f64>)>,
+        new(),
+        (&row, &row)
+    );
+    process!(
+        EnumCompareBlock<Mode>,
+        try_new("Mission"),
+        black_box(Mode::Stabilized)
+    );
+    process!(
+        EnumSwitchBlock<(EnumSignal<Mode>, f64, f64, f64)>,
+        try_new(["Stabilized", "Mission", "Manual"]),
+        (black_box(Mode::Mission), x, x, x)
+    );
+    process!(ExponentBlock<f64>, new(2.0, true), x);
+    process!(ExponentBlock<M23>, new(0.5, false), &m);
+    process!(FFTBlock<f64, 16>, new(), x);
+    process!(EquationBlock<f64>, new(), x);
+    process_ic!(FrequencyFilterBlock<f64>, try_new(0.0, 10.0, "LowPass"), x);
+    process_ic!(
+        FrequencyFilterBlock<M23>,
+        try_new(M23::zeroed(), 10.0, "HighPass"),
+        &m
+    );
+    process!(GainBlock<f64, f64>, new(2.0), x);
+    process!(GainBlock<f64, M23>, new(2.0), &m);
+    process!(
+        GeoDistanceBlock<f64>,
+        new(),
+        (
+            &black_box(Matrix::<1, 2, f64>::zeroed()),
+            &black_box(Matrix::<1, 2, f64>::zeroed())
+        )
+    );
+    process!(
+        GeofenceBlock<f64, 3, 1>,
+        new(
+            Matrix {
+                data: [[0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+            },
+            Matrix {
+                data: [[0.0], [0.0], [1.0]]
+            },
+            [true]
+        ),
+        (&black_box(Matrix::<1, 2, f64>::zeroed()), b)
+    );
+    process!(GpioOutputBlock<f64>, new(), x);
+    process!(HistogramBlock<f64, 4, 2>, new(0.0, 4.0, [0.5, 0.9]), x);
+    process!(HistogramBlock<M23, 4, 1>, new(0.0, 4.0, [0.5]), &m);
+    process!(I2tBlock<f64>, new(1.0, 50.0, 10.0), x);
+    process_ic!(IirFilterBlock<f64>, new(0.0, 0.1), x);
+    process_ic!(IirFilterBlock<M23>, new(M23::zeroed(), 0.1), &m);
+    process!(
+        ImageCentroidBlock<f64, u8, 4, 4>,
+        new(),
+        &black_box(Matrix::<4, 4, u8>::zeroed())
+    );
+    process!(
+        ImageDownsampleBlock<4, 4, 2, 2>,
+        try_new("Max"),
+        &black_box(Matrix::<4, 4, u8>::zeroed())
+    );
+    process!(
+        ImageThresholdBlock<4, 4>,
+        new(127, false),
+        &black_box(Matrix::<4, 4, u8>::zeroed())
+    );
+    process_ic!(
+        IntegralBlock<(f64, bool)>,
+        try_new(0.0, 10.0, "Trapezoidal"),
+        (x, b)
+    );
+    process_ic!(
+        IntegralBlock<(M23, bool)>,
+        try_new(M23::zeroed(), 10.0, "Rectangle"),
+        (&m, b)
+    );
+    process!(
+        InterlockBlock<3>,
+        try_new([1.0, 1.0, 0.0], 10.0, 10.0, 5.0),
+        &black_box(Matrix::<1, 3, bool>::zeroed())
+    );
+    process!(InverseParkBlock<f64>, new(), (x, x, x));
+    process!(
+        LatencyCompensationBlock<f64>,
+        try_new("Derivative"),
+        (x, x, x)
+    );
+    process!(LatencyCompensationBlock<M23, 8>, try_new("Replay"), (&m, x, &m));
+    process!(LlaToEcefBlock<f64>, new(), &row);
+    process!(LlaToNedBlock<f64>, new(47.0, 8.0, 400.0), &row);
+    process!(LogTriggerBlock<f64>, try_new(250.0), x);
+    process!(LogicalBlock<(f64, f64, f64)>, try_new("Or"), (x, x, x));
+    process!(
+        Lookup1DBlock<3, f64, f64>,
+        try_new("Linear", [0.0, 1.0, 2.0], [-1.0, 1.0, 10.0]),
+        x
+    );
+    process!(
+        Lookup2DBlock<3, 3, f64, f64>,
+        try_new("Nearest", [0.0, 1.0, 2.0], [0.0, 1.0, 2.0], M33::zeroed()),
+        (x, x)
+    );
+    process!(MatrixInverseBlock<M33, Inverse>, new(), &m33);
+    process!(MinMaxBlock<(f64, f64)>, try_new("Max"), (x, x));
+    process!(
+        MixerBlock<f64, 2, 2>,
+        try_new("Saturate", Matrix::zeroed(), [-1.0; 2], [1.0; 2]),
+        &black_box(Matrix::<1, 2, f64>::zeroed())
+    );
+    process!(
+        NearestPointBlock<f64, 3, 2>,
+        new(Matrix {
+            data: [[0.0, 1.0, 2.0], [0.0, 1.0, 0.0]]
+        }),
+        &black_box(Matrix::<1, 2, f64>::zeroed())
+    );
+    process!(NedToLlaBlock<f64>, new(-33.0, 151.0, 50.0), &row);
+    {
+        type Input = NoOpInputBlock<f64>;
+        let mut block = black_box(Input::default());
+        let parameters = black_box(<Input as InputBlock>::Parameters::new());
+        black_box(block.input(&parameters, &context()));
+        type Output = NoOpOutputBlock<f64>;
+        let mut block = black_box(Output::default());
+        let parameters = black_box(<Output as OutputBlock>::Parameters::new());
+        block.output(&parameters, &context(), x);
+    }
+    process!(NotBlock<f64>, try_new("Bitwise"), x);
+    process!(ParkBlock<f64>, new(), (x, x, x));
+    process!(DataReadBlock<f64>, new(), x);
+    process_ic!(PidBlock<f64, bool, 2>, new(0.0, 1.0, 2.0, 3.0, 10.0), (x, b));
+    process_ic!(
+        PidBlock<M23, bool, 2>,
+        new(M23::zeroed(), 1.0, 2.0, 3.0, 10.0),
+        (&m, b)
+    );
+    process!(PolyfitBlock<f64, 5, 2>, new(), (x, x));
+    process!(PolyvalBlock<f64, 3>, new([2.0, -3.0, 1.0]), x);
+    process!(PolyvalBlock<M23, 3>, new([2.0, -3.0, 1.0]), &m);
+    process!(PrechargeBlock<f64>, new(0.9, 1.0, 0.2, 0.1), (b, x, x));
+    process!(
+        ProductBlock<(f64, f64), ComponentWise>,
+        new([1.0, -1.0]),
+        (x, x)
+    );
+    process!(ProductBlock<(M33, M33), MatrixMultiply>, new(), (&m33, &m33));
+    process!(Pt100Block<f64>, try_new(100.0), x);
+    process!(
+        PurePursuitBlock<f64, 3>,
+        new(4.0, 2.0, 3.0, 0.5, 0.5),
+        (&row, &black_box(Matrix::<3, 2, f64>::zeroed()))
+    );
+    process!(PwmBlock<f64, (f64, f64)>, new(), (x, x));
+    process!(PwmDacBlock<f64>, new(3.3, 0.01), x);
+    process!(QuantizeBlock<f64, f64>, new(0.5), x);
+    generate::<RampBlock<f64>>(<RampBlock<f64> as GeneratorBlock>::Parameters::new(
+        0.0, 1.0,
+    ));
+    generate::<RandomNumberBlock<f64>>(
+        <RandomNumberBlock<f64> as GeneratorBlock>::Parameters::new(0.0, 1.0),
+    );
+    process!(RateLimitBlock<f64>, new(1.0, -1.0), x);
+    process!(RateLimitBlock<M23>, new(1.0, -1.0), &m);
+    process!(ResampleBlock<f64>, try_new("Linear", 0.01), (x, x));
+    process!(ResampleBlock<M23>, try_new("ZeroOrderHold", 0.01), (&m, x));
+    process!(RollingStatsBlock<f64, 4>, new(), x);
+    process!(RollingStatsBlock<M23, 4>, new(), &m);
+    process!(
+        RotaryKnobBlock<f64>,
+        try_new(4.0, 0.5, -2.0, 2.0, 0.0, 100.0, 4.0),
+        (x, b)
+    );
+    process!(SanitizeBlock<f64>, try_new("HoldLast", -1.0), x);
+    process!(SanitizeBlock<M23>, try_new("Constant", -1.0), &m);
+    generate::<SawtoothwaveBlock<f64>>(
+        <SawtoothwaveBlock<f64> as GeneratorBlock>::Parameters::new(1.0, 2.0, 0.0, 0.0),
+    );
+    process!(
+        ServoOutputBlock<f64, 2>,
+        new(
+            50.0,
+            [1000.0; 2],
+            [1500.0; 2],
+            [2000.0; 2],
+            [false, true],
+            [1500.0; 2],
+            100.0
+        ),
+        (&black_box(Matrix::<1, 2, f64>::zeroed()), b)
+    );
+    generate::<SinewaveBlock<f64>>(<SinewaveBlock<f64> as GeneratorBlock>::Parameters::new(
+        1.0, 2.0, 0.0, 0.0,
+    ));
+    process_ic!(
+        SlidingWindowBlock<3, f64, Matrix<1, 3, f64>>,
+        new(Matrix::zeroed()),
+        x
+    );
+    run(
+        SparseMatrixMultiplyBlock::<f64, 3, 4>::default(),
+        SparseMatrixMultiplyBlockParams::new(CsrMatrix::new(
+            &[1.0, 2.0, 3.0, 4.0],
+            &[0, 3, 1, 2],
+            &[0, 2, 2, 4],
+        )),
+        &black_box(Matrix::<4, 1, f64>::zeroed()),
+    );
+    process!(
+        SpectrumBlock<f64, 8, 5>,
+        try_new("Hann", "Magnitude", 2, 4),
+        x
+    );
+    process!(
+        SplineBlock<f64, 3, 2, f64>,
+        try_new(
+            "Cubic",
+            [0.0, 1.0, 2.0],
+            Matrix {
+                data: [[0.0, 1.0, 4.0], [0.0, 2.0, 1.0]]
+            }
+        ),
+        x
+    );
+    generate::<SquarewaveBlock<f64>>(<SquarewaveBlock<f64> as GeneratorBlock>::Parameters::new(
+        1.0, 0.5, 0.5, 0.0, 0.0,
+    ));
+    process!(
+        StanleyBlock<f64, 3>,
+        new(2.0, 1.0, 1.5, 3.0, 0.6, 0.5),
+        (&row, &black_box(Matrix::<3, 2, f64>::zeroed()))
+    );
+    process!(StepperBlock<f64>, try_new("Position", 1000.0, 2000.0), x);
+    process!(
+        StringCompareBlock,
+        try_new("StartsWith", true),
+        (text, black_box("1"))
+    );
+    process!(SumBlock<(f64, f64, f64)>, new([1.0, -1.0, 1.0]), (x, x, x));
+    process!(SumBlock<(M23, M23)>, new([1.0, -1.0]), (&m, &m));
+    process!(SvpwmBlock<f64>, new(), (x, x, x));
+    process!(TareScaleBlock<f64>, try_new(0.5, 1000.0, 10.0), (x, b));
+    process!(ThermocoupleBlock<f64>, try_new("K"), (x, x));
+    process!(TimerBlock<f64>, try_new("CountDown", true, 5.0), x);
+    process!(
+        TransferFunctionBlock<2, 3, f64, f64>,
+        new([1.0, 0.5], [1.0, 0.2, 0.1]),
+        x
+    );
+    process!(TransposeBlock<M23>, new(), &m);
+    generate::<TrianglewaveBlock<f64>>(
+        <TrianglewaveBlock<f64> as GeneratorBlock>::Parameters::new(1.0, 2.0, 0.0, 0.0),
+    );
+    process!(TrigonometryBlock<f64>, try_new("ArcTangent"), x);
+    process!(TrigonometryBlock<M23>, try_new("Sine"), &m);
+    process!(
+        DegToRadBlock<f64>,
+        new(),
+        black_box(pictorus_traits::units::UnitTagged::new(90.0))
+    );
+    process!(
+        VectorIndexBlock<2, f64, M23>,
+        try_new(&["0:1", "1:2"]),
+        &m
+    );
+    process!(
+        VectorMergeBlock<Matrix<1, 3, f64>, (f64, Matrix<1, 2, f64>)>,
+        new(),
+        (x, &black_box(Matrix::<1, 2, f64>::zeroed()))
+    );
+    process!(VectorNormBlock<M23>, new(), &m);
+    process!(VectorReshapeBlock<M23, Matrix<3, 2, f64>>, new(), &m);
+    process!(VectorSliceBlock<M33, Matrix<2, 2, f64>>, try_new(1.0, 1.0), &m33);
+    process!(
+        VectorSortBlock<M33, Matrix<1, 9, f64>>,
+        try_new("Descending"),
+        &m33
+    );
+    process!(
+        VoterBlock<3>,
+        try_new("Median", 0.5),
+        (&row, &black_box(Matrix::<1, 3, bool>::zeroed()))
+    );
+    process!(
+        WaypointFollowerBlock<f64, 2>,
+        new(
+            Matrix {
+                data: [[0.0, 10.0], [0.0, 0.0], [0.0, 5.0]]
+            },
+            1.0
+        ),
+        &row
+    );
+}
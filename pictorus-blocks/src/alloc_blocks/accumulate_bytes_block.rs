@@ -0,0 +1,166 @@
+use crate::traits::Scalar;
+use alloc::vec::Vec;
+use pictorus_traits::{ByteSliceSignal, PassBy, ProcessBlock};
+
+/// Parameters for the AccumulateBytesBlock
+pub struct Parameters {
+    /// Maximum number of bytes held in the window. Once full, the oldest bytes are dropped.
+    pub max_bytes: usize,
+}
+
+impl Parameters {
+    pub fn new(max_bytes: f64) -> Self {
+        Self {
+            max_bytes: max_bytes as usize,
+        }
+    }
+}
+
+/// Concatenates incoming byte slices across ticks into a bounded window.
+///
+/// This makes it possible to parse protocols whose messages arrive split over several ticks
+/// within the graph. The block takes three inputs:
+/// - The bytes received this tick, which are appended to the window
+/// - Flush: when truthy, the window (including this tick's bytes) is output one last time and
+///   then emptied before the next tick's bytes are appended
+/// - Clear: when truthy, the window is emptied before this tick's bytes are appended
+///
+/// The window never grows beyond `max_bytes`; when it would, the oldest bytes are discarded.
+pub struct AccumulateBytesBlock<S: Scalar> {
+    buffer: Vec<u8>,
+    flush_pending: bool,
+    _unused: core::marker::PhantomData<S>,
+}
+
+impl<S: Scalar> Default for AccumulateBytesBlock<S> {
+    fn default() -> Self {
+        Self {
+            buffer: Vec::new(),
+            flush_pending: false,
+            _unused: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<S: Scalar> ProcessBlock for AccumulateBytesBlock<S> {
+    type Parameters = Parameters;
+    type Inputs = (ByteSliceSignal, S, S);
+    type Output = ByteSliceSignal;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (data, flush, clear) = inputs;
+        if self.flush_pending || clear.is_truthy() {
+            self.buffer.clear();
+        }
+
+        // Only the newest `max_bytes` of the input can ever be kept
+        let data = &data[data.len().saturating_sub(parameters.max_bytes)..];
+        let overflow = (self.buffer.len() + data.len()).saturating_sub(parameters.max_bytes);
+        if overflow > 0 {
+            self.buffer.drain(..overflow);
+        }
+        self.buffer.extend_from_slice(data);
+
+        self.flush_pending = flush.is_truthy();
+        &self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        &self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+
+    #[test]
+    fn test_accumulate_bytes_default_buffer_no_panic() {
+        let block = AccumulateBytesBlock::<f64>::default();
+        assert_eq!(block.buffer(), b"");
+    }
+
+    #[test]
+    fn test_accumulate_bytes_across_ticks() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(16.0);
+        let mut block = AccumulateBytesBlock::<f64>::default();
+
+        assert_eq!(
+            block.process(&parameters, &context, (b"$GP".as_slice(), 0.0, 0.0)),
+            b"$GP"
+        );
+        assert_eq!(
+            block.process(&parameters, &context, (b"".as_slice(), 0.0, 0.0)),
+            b"$GP"
+        );
+        assert_eq!(
+            block.process(&parameters, &context, (b"GGA,1".as_slice(), 0.0, 0.0)),
+            b"$GPGGA,1"
+        );
+        assert_eq!(block.buffer(), b"$GPGGA,1");
+    }
+
+    #[test]
+    fn test_accumulate_bytes_drops_oldest_when_full() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(4.0);
+        let mut block = AccumulateBytesBlock::<f64>::default();
+
+        block.process(&parameters, &context, (b"abc".as_slice(), 0.0, 0.0));
+        assert_eq!(
+            block.process(&parameters, &context, (b"de".as_slice(), 0.0, 0.0)),
+            b"bcde"
+        );
+
+        // A single input larger than the window keeps only its newest bytes
+        assert_eq!(
+            block.process(&parameters, &context, (b"0123456".as_slice(), 0.0, 0.0)),
+            b"3456"
+        );
+    }
+
+    #[test]
+    fn test_accumulate_bytes_flush() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(16.0);
+        let mut block = AccumulateBytesBlock::<bool>::default();
+
+        block.process(&parameters, &context, (b"abc".as_slice(), false, false));
+        // The flushed window is still output on the tick the flush is requested
+        assert_eq!(
+            block.process(&parameters, &context, (b"def".as_slice(), true, false)),
+            b"abcdef"
+        );
+        assert_eq!(block.buffer(), b"abcdef");
+
+        // It is emptied before the next tick's bytes are appended
+        assert_eq!(
+            block.process(&parameters, &context, (b"ghi".as_slice(), false, false)),
+            b"ghi"
+        );
+    }
+
+    #[test]
+    fn test_accumulate_bytes_clear() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(16.0);
+        let mut block = AccumulateBytesBlock::<f64>::default();
+
+        block.process(&parameters, &context, (b"garbage".as_slice(), 0.0, 0.0));
+        assert_eq!(
+            block.process(&parameters, &context, (b"$GP".as_slice(), 0.0, 1.0)),
+            b"$GP"
+        );
+        assert_eq!(
+            block.process(&parameters, &context, (b"".as_slice(), 0.0, 1.0)),
+            b""
+        );
+    }
+}
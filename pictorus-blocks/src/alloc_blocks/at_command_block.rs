@@ -0,0 +1,570 @@
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use log::debug;
+use pictorus_traits::{ByteSliceSignal, Context, PassBy, ProcessBlock};
+
+use crate::byte_data::BUFF_SIZE_BYTES;
+use crate::stale_tracker::duration_from_ms_f64;
+use crate::traits::Float;
+
+/// Query sent periodically to track network registration
+const REGISTRATION_QUERY: &[u8] = b"AT+CREG?\r";
+/// Placeholder in the send command that is replaced by the payload length
+const LENGTH_PLACEHOLDER: &str = "{len}";
+
+/// Parameters for the AtCommandBlock
+pub struct Parameters {
+    /// Commands sent in order at startup (e.g. `ATE0`), each of which must return `OK`
+    pub init_commands: Vec<Vec<u8>>,
+    /// Command that opens a payload transfer, split around the payload length
+    pub send_command: (Vec<u8>, Vec<u8>),
+    /// Prefix of the URC announcing incoming payload data (e.g. `+IPD`)
+    pub receive_urc: Vec<u8>,
+    /// How long to wait for a command's result before retrying it
+    pub response_timeout: Duration,
+    /// Number of times a failed or timed out command is retried before giving up on it
+    pub max_retries: u8,
+    /// How often to poll the modem's network registration status
+    pub registration_poll: Duration,
+}
+
+impl Parameters {
+    /// `send_command` may contain a `{len}` placeholder for the payload length, as in
+    /// `AT+CIPSEND=0,{len}`. Without one the length is appended to the command.
+    pub fn new<S: AsRef<str>>(
+        init_commands: &[S],
+        send_command: &str,
+        receive_urc: &str,
+        response_timeout_ms: f64,
+        max_retries: f64,
+        registration_poll_ms: f64,
+    ) -> Self {
+        let send_command = send_command
+            .split_once(LENGTH_PLACEHOLDER)
+            .unwrap_or((send_command, ""));
+        Self {
+            init_commands: init_commands
+                .iter()
+                .map(|command| command.as_ref().as_bytes().to_vec())
+                .collect(),
+            send_command: (
+                send_command.0.as_bytes().to_vec(),
+                send_command.1.as_bytes().to_vec(),
+            ),
+            receive_urc: receive_urc.as_bytes().to_vec(),
+            response_timeout: duration_from_ms_f64(response_timeout_ms),
+            max_retries: max_retries as u8,
+            registration_poll: duration_from_ms_f64(registration_poll_ms),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Command {
+    Init(usize),
+    RegistrationQuery,
+    Send,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum State {
+    /// Ready to issue the next command
+    Idle,
+    /// Waiting for the final result code (`OK`, `ERROR`, ...) of a command
+    AwaitingResult(Command),
+    /// Waiting for the `>` prompt that follows a send command
+    AwaitingPrompt,
+}
+
+/// Manages request/response AT command dialogs with a cellular modem (e.g. SIM7600 or Quectel)
+/// over serial.
+///
+/// The block takes the bytes read from the serial port and a payload to transmit, and outputs
+/// the bytes to write to the serial port, the network registration status, the most recently
+/// received payload and whether the modem is ready to send.
+///
+/// At startup the configured init commands are sent one at a time, each waiting for `OK`.
+/// Afterwards the registration status (`+CREG`) is polled periodically, and any non-empty
+/// payload input is sent using the send command once the modem is registered: the block
+/// sends the command, waits for the `>` prompt and then writes the payload. Commands that fail
+/// or time out are retried up to `max_retries` times, after which a failed init restarts the
+/// init sequence and a failed send drops the payload.
+///
+/// Unsolicited result codes (URCs) are handled as they arrive. Registration URCs update the
+/// registration status, and a line starting with the receive URC announces a payload whose
+/// length is the line's trailing number (e.g. `+IPD12`); that many bytes following the line are
+/// collected and output as the received payload.
+///
+/// The registration status output is the `<stat>` value reported by the modem: 1 when registered
+/// on the home network, 5 when roaming, and 0, 2, 3 or 4 when not registered.
+pub struct AtCommandBlock<F: Float> {
+    state: State,
+    init_step: usize,
+    retries: u8,
+    sent_at: Duration,
+    last_registration_query: Option<Duration>,
+    registration: F,
+    rx_buffer: Vec<u8>,
+    payload_remaining: usize,
+    partial_payload: Vec<u8>,
+    received_payload: Vec<u8>,
+    pending_payload: Vec<u8>,
+    payload_pending: bool,
+    tx_buffer: Vec<u8>,
+    ready: bool,
+}
+
+impl<F: Float> Default for AtCommandBlock<F> {
+    fn default() -> Self {
+        Self {
+            state: State::Idle,
+            init_step: 0,
+            retries: 0,
+            sent_at: Duration::ZERO,
+            last_registration_query: None,
+            registration: F::zero(),
+            rx_buffer: Vec::new(),
+            payload_remaining: 0,
+            partial_payload: Vec::new(),
+            received_payload: Vec::new(),
+            pending_payload: Vec::new(),
+            payload_pending: false,
+            tx_buffer: Vec::new(),
+            ready: false,
+        }
+    }
+}
+
+impl<F: Float> AtCommandBlock<F> {
+    fn is_registered(&self) -> bool {
+        let stat = self.registration.to_u8();
+        stat == Some(1) || stat == Some(5)
+    }
+
+    fn is_ready(&self, parameters: &Parameters) -> bool {
+        self.init_step >= parameters.init_commands.len() && self.is_registered()
+    }
+
+    fn transmit(&mut self, command: Command, parameters: &Parameters, now: Duration) {
+        match command {
+            Command::Init(step) => {
+                self.tx_buffer
+                    .extend_from_slice(&parameters.init_commands[step]);
+                self.tx_buffer.push(b'\r');
+                self.state = State::AwaitingResult(command);
+            }
+            Command::RegistrationQuery => {
+                self.tx_buffer.extend_from_slice(REGISTRATION_QUERY);
+                self.last_registration_query = Some(now);
+                self.state = State::AwaitingResult(command);
+            }
+            Command::Send => {
+                let len = alloc::format!("{}", self.pending_payload.len());
+                self.tx_buffer.extend_from_slice(&parameters.send_command.0);
+                self.tx_buffer.extend_from_slice(len.as_bytes());
+                self.tx_buffer.extend_from_slice(&parameters.send_command.1);
+                self.tx_buffer.push(b'\r');
+                self.state = State::AwaitingPrompt;
+            }
+        }
+        self.sent_at = now;
+    }
+
+    fn issue_next_command(&mut self, parameters: &Parameters, now: Duration) {
+        let poll_due = self
+            .last_registration_query
+            .is_none_or(|last| now.saturating_sub(last) >= parameters.registration_poll);
+
+        if self.init_step < parameters.init_commands.len() {
+            self.transmit(Command::Init(self.init_step), parameters, now);
+        } else if poll_due {
+            self.transmit(Command::RegistrationQuery, parameters, now);
+        } else if self.payload_pending && self.is_registered() {
+            self.transmit(Command::Send, parameters, now);
+        }
+    }
+
+    fn pending_command(&self) -> Option<Command> {
+        match self.state {
+            State::Idle => None,
+            State::AwaitingResult(command) => Some(command),
+            State::AwaitingPrompt => Some(Command::Send),
+        }
+    }
+
+    fn on_result(&mut self, success: bool, parameters: &Parameters, now: Duration) {
+        let Some(command) = self.pending_command() else {
+            // Result codes that don't belong to a command we sent are ignored
+            return;
+        };
+
+        if success {
+            self.state = State::Idle;
+            self.retries = 0;
+            match command {
+                Command::Init(step) => self.init_step = step + 1,
+                Command::Send => self.payload_pending = false,
+                Command::RegistrationQuery => {}
+            }
+        } else {
+            self.retry(command, parameters, now);
+        }
+    }
+
+    fn retry(&mut self, command: Command, parameters: &Parameters, now: Duration) {
+        if self.retries < parameters.max_retries {
+            self.retries += 1;
+            debug!("Retrying AT command {command:?} (attempt {})", self.retries);
+            self.transmit(command, parameters, now);
+            return;
+        }
+
+        debug!(
+            "AT command {command:?} failed after {} retries",
+            self.retries
+        );
+        self.state = State::Idle;
+        self.retries = 0;
+        match command {
+            Command::Init(_) => self.init_step = 0,
+            Command::Send => self.payload_pending = false,
+            Command::RegistrationQuery => {}
+        }
+    }
+
+    fn on_registration(&mut self, line: &[u8]) {
+        let Some(colon) = line.iter().position(|&b| b == b':') else {
+            return;
+        };
+        let fields = &line[colon + 1..];
+        // Query responses are `<n>,<stat>`, while URCs only carry `<stat>`
+        let mut fields = fields.split(|&b| b == b',');
+        let stat = if self.state == State::AwaitingResult(Command::RegistrationQuery) {
+            fields.nth(1)
+        } else {
+            fields.next()
+        };
+        if let Some(stat) = stat.and_then(parse_number) {
+            self.registration = F::from(stat).unwrap_or(F::zero());
+        }
+    }
+
+    fn handle_line(&mut self, line: &[u8], parameters: &Parameters, now: Duration) {
+        let line = line.trim_ascii();
+        if !parameters.receive_urc.is_empty() && line.starts_with(&parameters.receive_urc) {
+            if let Some(len) = trailing_number(line) {
+                self.payload_remaining = len;
+                self.partial_payload.clear();
+            }
+        } else if line.starts_with(b"+C") && line.windows(4).any(|w| w == b"REG:") {
+            self.on_registration(line);
+        } else if line == b"OK" || line.ends_with(b" OK") {
+            self.on_result(true, parameters, now);
+        } else if line == b"ERROR"
+            || line.starts_with(b"+CME ERROR")
+            || line.starts_with(b"+CMS ERROR")
+            || line.ends_with(b" FAIL")
+        {
+            self.on_result(false, parameters, now);
+        }
+    }
+
+    fn parse_rx(&mut self, parameters: &Parameters, now: Duration) {
+        let rx_buffer = core::mem::take(&mut self.rx_buffer);
+        let mut consumed = 0;
+        loop {
+            let pending = &rx_buffer[consumed..];
+            if self.payload_remaining > 0 {
+                let n = self.payload_remaining.min(pending.len());
+                if n == 0 {
+                    break;
+                }
+                self.partial_payload.extend_from_slice(&pending[..n]);
+                self.payload_remaining -= n;
+                consumed += n;
+                if self.payload_remaining == 0 {
+                    core::mem::swap(&mut self.received_payload, &mut self.partial_payload);
+                }
+                continue;
+            }
+
+            let blank = pending
+                .iter()
+                .take_while(|b| matches!(b, b'\r' | b'\n'))
+                .count();
+            if blank > 0 {
+                consumed += blank;
+                continue;
+            }
+
+            if self.state == State::AwaitingPrompt && pending.first() == Some(&b'>') {
+                consumed += 1;
+                self.tx_buffer.extend_from_slice(&self.pending_payload);
+                self.state = State::AwaitingResult(Command::Send);
+                self.sent_at = now;
+                continue;
+            }
+
+            let Some(end) = pending.iter().position(|&b| b == b'\n') else {
+                break;
+            };
+            consumed += end + 1;
+            self.handle_line(&pending[..end], parameters, now);
+        }
+
+        self.rx_buffer = rx_buffer;
+        self.rx_buffer.drain(..consumed);
+        if self.rx_buffer.len() >= BUFF_SIZE_BYTES * 2 {
+            debug!("Read too many bytes without a complete line. Clearing buffer");
+            self.rx_buffer.clear();
+        }
+    }
+}
+
+impl<F: Float> ProcessBlock for AtCommandBlock<F> {
+    type Parameters = Parameters;
+    type Inputs = (ByteSliceSignal, ByteSliceSignal);
+    type Output = (ByteSliceSignal, F, ByteSliceSignal, bool);
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (rx, payload) = inputs;
+        let now = context.time();
+        self.tx_buffer.clear();
+
+        // Only the most recent payload is kept while waiting to send. Once its length has been
+        // sent to the modem it can no longer be replaced.
+        if !payload.is_empty() && self.pending_command() != Some(Command::Send) {
+            self.pending_payload.clear();
+            self.pending_payload.extend_from_slice(payload);
+            self.payload_pending = true;
+        }
+
+        self.rx_buffer.extend_from_slice(rx);
+        self.parse_rx(parameters, now);
+
+        if let Some(command) = self.pending_command() {
+            if now.saturating_sub(self.sent_at) >= parameters.response_timeout {
+                debug!("Timed out waiting for response to AT command {command:?}");
+                self.retry(command, parameters, now);
+            }
+        }
+
+        if self.state == State::Idle {
+            self.issue_next_command(parameters, now);
+        }
+
+        self.ready = self.is_ready(parameters);
+        (
+            &self.tx_buffer,
+            self.registration,
+            &self.received_payload,
+            self.ready,
+        )
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        (
+            &self.tx_buffer,
+            self.registration,
+            &self.received_payload,
+            self.ready,
+        )
+    }
+}
+
+/// Parses an ASCII decimal number, ignoring surrounding whitespace
+fn parse_number(field: &[u8]) -> Option<usize> {
+    core::str::from_utf8(field.trim_ascii()).ok()?.parse().ok()
+}
+
+/// Parses the decimal number at the end of a line, such as the length in `+IPD12`
+fn trailing_number(line: &[u8]) -> Option<usize> {
+    let digits = line.iter().rev().take_while(|b| b.is_ascii_digit()).count();
+    parse_number(&line[line.len() - digits..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+
+    fn parameters() -> Parameters {
+        Parameters::new(
+            &["ATE0", "AT+CREG=1"],
+            "AT+CIPSEND=0,{len}",
+            "+IPD",
+            500.0,
+            2.0,
+            1000.0,
+        )
+    }
+
+    /// Runs the block through the init sequence and a registration query
+    fn initialized_block(parameters: &Parameters, runtime: &StubRuntime) -> AtCommandBlock<f64> {
+        let mut block = AtCommandBlock::<f64>::default();
+        let context = runtime.context();
+        block.process(parameters, &context, (b"", b""));
+        block.process(parameters, &context, (b"ATE0\r\r\nOK\r\n", b""));
+        block.process(parameters, &context, (b"\r\nOK\r\n", b""));
+        block.process(parameters, &context, (b"\r\n+CREG: 1,1\r\n\r\nOK\r\n", b""));
+        block
+    }
+
+    #[test]
+    fn test_at_command_default_buffer_no_panic() {
+        let block = AtCommandBlock::<f64>::default();
+        assert_eq!(block.buffer(), (b"".as_ref(), 0.0, b"".as_ref(), false));
+    }
+
+    #[test]
+    fn test_at_command_init_and_registration() {
+        let parameters = parameters();
+        let context = StubRuntime::default().context();
+        let mut block = AtCommandBlock::<f64>::default();
+
+        let output = block.process(&parameters, &context, (b"", b""));
+        assert_eq!(output, (b"ATE0\r".as_ref(), 0.0, b"".as_ref(), false));
+
+        // The echoed command is ignored and OK advances to the next init command
+        let output = block.process(&parameters, &context, (b"ATE0\r\r\nOK\r\n", b""));
+        assert_eq!(output.0, b"AT+CREG=1\r");
+
+        // Once init completes the registration status is queried
+        let output = block.process(&parameters, &context, (b"\r\nOK\r\n", b""));
+        assert_eq!(output.0, b"AT+CREG?\r");
+        assert!(!output.3);
+
+        let output = block.process(&parameters, &context, (b"\r\n+CREG: 1,5\r\n", b""));
+        assert_eq!(output, (b"".as_ref(), 5.0, b"".as_ref(), true));
+
+        // Registration URCs only carry the status
+        let output = block.process(&parameters, &context, (b"\r\nOK\r\n\r\n+CREG: 2\r\n", b""));
+        assert_eq!(output, (b"".as_ref(), 2.0, b"".as_ref(), false));
+    }
+
+    #[test]
+    fn test_at_command_polls_registration() {
+        let parameters = parameters();
+        let mut runtime = StubRuntime::default();
+        let mut block = initialized_block(&parameters, &runtime);
+
+        runtime.set_time(Duration::from_millis(999));
+        let output = block.process(&parameters, &runtime.context(), (b"", b""));
+        assert_eq!(output.0, b"");
+
+        runtime.set_time(Duration::from_millis(1000));
+        let output = block.process(&parameters, &runtime.context(), (b"", b""));
+        assert_eq!(output.0, b"AT+CREG?\r");
+    }
+
+    #[test]
+    fn test_at_command_send_payload() {
+        let parameters = parameters();
+        let runtime = StubRuntime::default();
+        let mut block = initialized_block(&parameters, &runtime);
+        let context = runtime.context();
+
+        let output = block.process(&parameters, &context, (b"", b"hello"));
+        assert_eq!(output.0, b"AT+CIPSEND=0,5\r");
+
+        // A new payload can't replace the one whose length was already sent
+        let output = block.process(&parameters, &context, (b"\r\n", b"other"));
+        assert_eq!(output.0, b"");
+
+        let output = block.process(&parameters, &context, (b"> ", b""));
+        assert_eq!(output.0, b"hello");
+
+        let output = block.process(&parameters, &context, (b"\r\nOK\r\n", b""));
+        assert_eq!(output.0, b"");
+        let output = block.process(&parameters, &context, (b"", b""));
+        assert_eq!(output.0, b"");
+    }
+
+    #[test]
+    fn test_at_command_receive_payload_across_ticks() {
+        let parameters = parameters();
+        let runtime = StubRuntime::default();
+        let mut block = initialized_block(&parameters, &runtime);
+        let context = runtime.context();
+
+        let output = block.process(&parameters, &context, (b"\r\n+IPD7\r\nwo", b""));
+        assert_eq!(output.2, b"");
+
+        // Payload bytes may contain line endings and result codes
+        let output = block.process(&parameters, &context, (b"r\nOKd\r\n+CREG: 5\r\n", b""));
+        assert_eq!(output.2, b"wor\nOKd");
+        assert_eq!(output.1, 5.0);
+    }
+
+    #[test]
+    fn test_at_command_retries_then_restarts_init() {
+        let parameters = parameters();
+        let mut runtime = StubRuntime::default();
+        let mut block = AtCommandBlock::<f64>::default();
+
+        let output = block.process(&parameters, &runtime.context(), (b"", b""));
+        assert_eq!(output.0, b"ATE0\r");
+
+        // An error is retried immediately
+        let output = block.process(&parameters, &runtime.context(), (b"\r\nERROR\r\n", b""));
+        assert_eq!(output.0, b"ATE0\r");
+
+        // As is a timeout
+        runtime.set_time(Duration::from_millis(499));
+        let output = block.process(&parameters, &runtime.context(), (b"", b""));
+        assert_eq!(output.0, b"");
+        runtime.set_time(Duration::from_millis(500));
+        let output = block.process(&parameters, &runtime.context(), (b"", b""));
+        assert_eq!(output.0, b"ATE0\r");
+
+        // The first init command succeeds, but the second exhausts its retries
+        let output = block.process(&parameters, &runtime.context(), (b"\r\nOK\r\n", b""));
+        assert_eq!(output.0, b"AT+CREG=1\r");
+        for _ in 0..2 {
+            let output = block.process(
+                &parameters,
+                &runtime.context(),
+                (b"\r\n+CME ERROR: 3\r\n", b""),
+            );
+            assert_eq!(output.0, b"AT+CREG=1\r");
+        }
+
+        // The init sequence starts over
+        let output = block.process(&parameters, &runtime.context(), (b"\r\nERROR\r\n", b""));
+        assert_eq!(output.0, b"ATE0\r");
+    }
+
+    #[test]
+    fn test_at_command_failed_send_drops_payload() {
+        let parameters = parameters();
+        let runtime = StubRuntime::default();
+        let mut block = initialized_block(&parameters, &runtime);
+        let context = runtime.context();
+
+        let output = block.process(&parameters, &context, (b"", b"hi"));
+        assert_eq!(output.0, b"AT+CIPSEND=0,2\r");
+        let output = block.process(&parameters, &context, (b"\r\nERROR\r\n", b""));
+        assert_eq!(output.0, b"AT+CIPSEND=0,2\r");
+        let output = block.process(&parameters, &context, (b"> ", b""));
+        assert_eq!(output.0, b"hi");
+        let output = block.process(&parameters, &context, (b"\r\nSEND FAIL\r\n", b""));
+        assert_eq!(output.0, b"AT+CIPSEND=0,2\r");
+        let output = block.process(&parameters, &context, (b"\r\nERROR\r\n", b""));
+        assert_eq!(output.0, b"");
+        let output = block.process(&parameters, &context, (b"", b""));
+        assert_eq!(output.0, b"");
+    }
+
+    #[test]
+    fn test_at_command_send_without_length_placeholder() {
+        let parameters = Parameters::new(&[] as &[&str], "AT+SEND=", "", 500.0, 0.0, 1000.0);
+        assert_eq!(parameters.send_command, (b"AT+SEND=".to_vec(), Vec::new()));
+        assert!(parameters.init_commands.is_empty());
+    }
+}
@@ -1,25 +1,30 @@
-use crate::byte_data::{parse_byte_data_spec, try_pack_data, ByteOrderSpec, DataType};
+use crate::byte_data::{try_pack_data, try_parse_byte_data_spec, ByteOrderSpec, DataType};
 use crate::traits::{Float, Scalar};
+use crate::ParameterError;
 use alloc::vec::Vec;
 use num_traits::AsPrimitive;
-use pictorus_traits::{ByteSliceSignal, Pass, PassBy, ProcessBlock};
+use pictorus_traits::{BufferProvider, ByteSliceSignal, Pass, PassBy, ProcessBlock};
 
 /// Packs scalar inputs into a byte buffer according to the provided data spec.
-pub struct BytesPackBlock<T: Apply> {
-    buffer: Vec<u8>,
+///
+/// The packed bytes are written into `B`, which is reused across ticks. Use a
+/// [`FixedBuffer`](pictorus_traits::FixedBuffer) to avoid heap allocation entirely; packed
+/// data that exceeds its capacity is truncated.
+pub struct BytesPackBlock<T: Apply, B: BufferProvider = Vec<u8>> {
+    buffer: B,
     _unused: core::marker::PhantomData<T>,
 }
 
-impl<T: Apply> Default for BytesPackBlock<T> {
+impl<T: Apply, B: BufferProvider> Default for BytesPackBlock<T, B> {
     fn default() -> Self {
         Self {
-            buffer: Vec::new(),
+            buffer: B::default(),
             _unused: core::marker::PhantomData,
         }
     }
 }
 
-impl<T: Apply> ProcessBlock for BytesPackBlock<T> {
+impl<T: Apply, B: BufferProvider> ProcessBlock for BytesPackBlock<T, B> {
     type Inputs = T;
     type Output = ByteSliceSignal;
     type Parameters = T::Params;
@@ -30,7 +35,8 @@ impl<T: Apply> ProcessBlock for BytesPackBlock<T> {
         _context: &dyn pictorus_traits::Context,
         inputs: PassBy<'_, Self::Inputs>,
     ) -> PassBy<'b, Self::Output> {
-        self.buffer = T::pack_bytes(inputs, parameters);
+        self.buffer.clear();
+        T::pack_bytes(inputs, parameters, &mut self.buffer);
         self.buffer.as_slice()
     }
 
@@ -48,15 +54,31 @@ pub struct Parameters<const N: usize> {
 impl<const N: usize> Parameters<N> {
     /// This constructor takes a slice of strings that represent the data spec for each input.
     pub fn new<S: AsRef<str>>(pack_spec_str: &[S]) -> Self {
-        let pack_spec = parse_byte_data_spec(pack_spec_str)
+        Self::try_new(pack_spec_str).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new<S: AsRef<str>>(pack_spec_str: &[S]) -> Result<Self, ParameterError> {
+        let pack_spec = try_parse_byte_data_spec(pack_spec_str)?
             .try_into()
-            .expect("Bytes Data Spec is incorrectly sized for the number of inputs");
+            .map_err(|_| {
+                ParameterError("Bytes Data Spec is incorrectly sized for the number of inputs")
+            })?;
+        Ok(Self { pack_spec })
+    }
+
+    /// Builds the parameters from an already-resolved data spec, without parsing
+    pub const fn from_pack_spec(pack_spec: [(DataType, ByteOrderSpec); N]) -> Self {
         Self { pack_spec }
     }
 }
 
 pub trait AppendBytes: Scalar {
-    fn append_bytes(&self, data_spec: (DataType, ByteOrderSpec), buffer: &mut Vec<u8>) -> usize;
+    fn append_bytes<B: BufferProvider>(
+        &self,
+        data_spec: (DataType, ByteOrderSpec),
+        buffer: &mut B,
+    ) -> usize;
 }
 
 impl<F> AppendBytes for F
@@ -75,7 +97,11 @@ where
         + AsPrimitive<f32>
         + AsPrimitive<f64>,
 {
-    fn append_bytes(&self, data_spec: (DataType, ByteOrderSpec), buffer: &mut Vec<u8>) -> usize {
+    fn append_bytes<B: BufferProvider>(
+        &self,
+        data_spec: (DataType, ByteOrderSpec),
+        buffer: &mut B,
+    ) -> usize {
         let mut scratch = [0u8; 16]; // 16 bytes is the size of i128 which is the largest output spec we support
         let n = match data_spec.1 {
             ByteOrderSpec::BigEndian => {
@@ -86,44 +112,38 @@ where
             }
         }
         .expect("Scratch should always be big enough, which is the only way to produce an error");
-        buffer.extend_from_slice(scratch[..n].as_ref());
-        n
+        buffer.extend_from_slice(scratch[..n].as_ref())
     }
 }
 
 pub trait Apply: Pass {
     type Params;
-    fn pack_bytes(input: PassBy<Self>, params: &Self::Params) -> Vec<u8>;
+    /// Appends the packed bytes for `input` to `buffer`
+    fn pack_bytes<B: BufferProvider>(input: PassBy<Self>, params: &Self::Params, buffer: &mut B);
 }
 
 impl<S: AppendBytes> Apply for S {
     type Params = Parameters<1>;
-    fn pack_bytes(input: PassBy<Self>, params: &Self::Params) -> Vec<u8> {
-        let mut buffer = Vec::new();
-        input.append_bytes(params.pack_spec[0], &mut buffer);
-        buffer
+    fn pack_bytes<B: BufferProvider>(input: PassBy<Self>, params: &Self::Params, buffer: &mut B) {
+        input.append_bytes(params.pack_spec[0], buffer);
     }
 }
 
 impl<S1: AppendBytes, S2: AppendBytes> Apply for (S1, S2) {
     type Params = Parameters<2>;
-    fn pack_bytes(input: PassBy<Self>, params: &Self::Params) -> Vec<u8> {
-        let mut buffer = Vec::new();
+    fn pack_bytes<B: BufferProvider>(input: PassBy<Self>, params: &Self::Params, buffer: &mut B) {
         seq_macro::seq!(N in 0..2 {
-            input.N.append_bytes(params.pack_spec[N], &mut buffer);
+            input.N.append_bytes(params.pack_spec[N], buffer);
         });
-        buffer
     }
 }
 
 impl<S1: AppendBytes, S2: AppendBytes, S3: AppendBytes> Apply for (S1, S2, S3) {
     type Params = Parameters<3>;
-    fn pack_bytes(input: PassBy<Self>, params: &Self::Params) -> Vec<u8> {
-        let mut buffer = Vec::new();
+    fn pack_bytes<B: BufferProvider>(input: PassBy<Self>, params: &Self::Params, buffer: &mut B) {
         seq_macro::seq!(N in 0..3 {
-            input.N.append_bytes(params.pack_spec[N], &mut buffer);
+            input.N.append_bytes(params.pack_spec[N], buffer);
         });
-        buffer
     }
 }
 
@@ -131,12 +151,10 @@ impl<S1: AppendBytes, S2: AppendBytes, S3: AppendBytes, S4: AppendBytes> Apply
     for (S1, S2, S3, S4)
 {
     type Params = Parameters<4>;
-    fn pack_bytes(input: PassBy<Self>, params: &Self::Params) -> Vec<u8> {
-        let mut buffer = Vec::new();
+    fn pack_bytes<B: BufferProvider>(input: PassBy<Self>, params: &Self::Params, buffer: &mut B) {
         seq_macro::seq!(N in 0..4 {
-            input.N.append_bytes(params.pack_spec[N], &mut buffer);
+            input.N.append_bytes(params.pack_spec[N], buffer);
         });
-        buffer
     }
 }
 
@@ -144,12 +162,10 @@ impl<S1: AppendBytes, S2: AppendBytes, S3: AppendBytes, S4: AppendBytes, S5: App
     for (S1, S2, S3, S4, S5)
 {
     type Params = Parameters<5>;
-    fn pack_bytes(input: PassBy<Self>, params: &Self::Params) -> Vec<u8> {
-        let mut buffer = Vec::new();
+    fn pack_bytes<B: BufferProvider>(input: PassBy<Self>, params: &Self::Params, buffer: &mut B) {
         seq_macro::seq!(N in 0..5 {
-            input.N.append_bytes(params.pack_spec[N], &mut buffer);
+            input.N.append_bytes(params.pack_spec[N], buffer);
         });
-        buffer
     }
 }
 
@@ -163,12 +179,10 @@ impl<
     > Apply for (S1, S2, S3, S4, S5, S6)
 {
     type Params = Parameters<6>;
-    fn pack_bytes(input: PassBy<Self>, params: &Self::Params) -> Vec<u8> {
-        let mut buffer = Vec::new();
+    fn pack_bytes<B: BufferProvider>(input: PassBy<Self>, params: &Self::Params, buffer: &mut B) {
         seq_macro::seq!(N in 0..6 {
-            input.N.append_bytes(params.pack_spec[N], &mut buffer);
+            input.N.append_bytes(params.pack_spec[N], buffer);
         });
-        buffer
     }
 }
 
@@ -183,12 +197,10 @@ impl<
     > Apply for (S1, S2, S3, S4, S5, S6, S7)
 {
     type Params = Parameters<7>;
-    fn pack_bytes(input: PassBy<Self>, params: &Self::Params) -> Vec<u8> {
-        let mut buffer = Vec::new();
+    fn pack_bytes<B: BufferProvider>(input: PassBy<Self>, params: &Self::Params, buffer: &mut B) {
         seq_macro::seq!(N in 0..7 {
-            input.N.append_bytes(params.pack_spec[N], &mut buffer);
+            input.N.append_bytes(params.pack_spec[N], buffer);
         });
-        buffer
     }
 }
 
@@ -204,12 +216,10 @@ impl<
     > Apply for (S1, S2, S3, S4, S5, S6, S7, S8)
 {
     type Params = Parameters<8>;
-    fn pack_bytes(input: PassBy<Self>, params: &Self::Params) -> Vec<u8> {
-        let mut buffer = Vec::new();
+    fn pack_bytes<B: BufferProvider>(input: PassBy<Self>, params: &Self::Params, buffer: &mut B) {
         seq_macro::seq!(N in 0..8 {
-            input.N.append_bytes(params.pack_spec[N], &mut buffer);
+            input.N.append_bytes(params.pack_spec[N], buffer);
         });
-        buffer
     }
 }
 
@@ -218,6 +228,7 @@ mod tests {
     use super::*;
     use crate::testing::StubContext;
     use byteorder::WriteBytesExt;
+    use pictorus_traits::FixedBuffer;
 
     #[test]
     fn test_bytes_pack_default_buffer_no_panic() {
@@ -243,6 +254,21 @@ mod tests {
         assert_eq!(block.buffer(), expected.as_slice());
     }
 
+    #[test]
+    fn test_bytes_pack_block_fixed_buffer() {
+        let context = StubContext::default();
+        let params = Parameters::new(&["U16:BigEndian", "U32:LittleEndian"]);
+        let mut block = BytesPackBlock::<(f64, f64), FixedBuffer<4>>::default();
+
+        // Only the first four of the six packed bytes fit in the buffer
+        let output = block.process(&params, &context, (258.0, 84281096.0));
+        assert_eq!(output, &[0x01, 0x02, 0x08, 0x07]);
+
+        let output = block.process(&params, &context, (1.0, 0.0));
+        assert_eq!(output, &[0x00, 0x01, 0x00, 0x00]);
+        assert_eq!(block.buffer(), &[0x00, 0x01, 0x00, 0x00]);
+    }
+
     #[test]
     fn test_bytes_pack_block_1_input_f32() {
         let context = StubContext::default();
@@ -1,6 +1,7 @@
 use crate::byte_data::{find_all_bytes_idx, parse_string_to_read_delimiter};
 use crate::stale_tracker::{duration_from_ms_f64, StaleTracker};
 use crate::traits::{DefaultStorage, Scalar};
+use crate::ParameterError;
 use alloc::borrow::ToOwned;
 use alloc::{string::String, vec::Vec};
 use core::time::Duration;
@@ -32,26 +33,39 @@ pub struct Parameters {
 
 impl Parameters {
     pub fn new<S: AsRef<str>>(delimiter: &str, desired_outputs: &[S], stale_age_ms: f64) -> Self {
+        Self::try_new(delimiter, desired_outputs, stale_age_ms)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new<S: AsRef<str>>(
+        delimiter: &str,
+        desired_outputs: &[S],
+        stale_age_ms: f64,
+    ) -> Result<Self, ParameterError> {
         // TODO: For now this accepts the normal desired output spec even though it only uses the indexes from it
         // The actual Datatypes are encoded as part of the type of the block, would make sense to address this more completely when
         // we rework codegen
-        let desired_output_idx = Self::parse_desired_outputs(desired_outputs);
-        Self {
+        let desired_output_idx = Self::parse_desired_outputs(desired_outputs)?;
+        Ok(Self {
             delimiter: delimiter.to_owned(),
             desired_output_idx,
             stale_age: duration_from_ms_f64(stale_age_ms),
-        }
+        })
     }
 
-    fn parse_desired_outputs<S: AsRef<str>>(desired_outputs: &[S]) -> Vec<usize> {
+    fn parse_desired_outputs<S: AsRef<str>>(
+        desired_outputs: &[S],
+    ) -> Result<Vec<usize>, ParameterError> {
         desired_outputs
             .iter()
             .map(|output_spec| {
                 let (_data_type, idx) = output_spec
                     .as_ref()
                     .split_once(':')
-                    .expect("Invalid output spec");
-                idx.parse().expect("Invalid index, must be a number")
+                    .ok_or(ParameterError("Invalid output spec"))?;
+                idx.parse()
+                    .map_err(|_| ParameterError("Invalid index, must be a number"))
             })
             .collect()
     }
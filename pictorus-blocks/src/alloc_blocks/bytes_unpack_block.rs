@@ -8,7 +8,8 @@ use generic_array::{ArrayLength, GenericArray};
 use num_traits::AsPrimitive;
 use typenum::{Const, NonZero, Sub1, ToUInt, B1, U};
 
-use crate::byte_data::{parse_byte_data_spec, try_unpack_data, ByteOrderSpec, DataType};
+use crate::byte_data::{try_parse_byte_data_spec, try_unpack_data, ByteOrderSpec, DataType};
+use crate::ParameterError;
 use pictorus_traits::{ByteSliceSignal, PassBy, ProcessBlock};
 
 /// Unpacks a byte slice into a specified number of outputs based on the provided data types and byte order.
@@ -93,12 +94,33 @@ where
 impl<N: ArrayLength> Parameters<N> {
     /// This constructor takes a slice of strings that represent the data spec for each input.
     pub fn new<S: AsRef<str>>(pack_spec_str: &[S], stale_age_ms: f64) -> Self {
-        let pack_spec = parse_byte_data_spec(pack_spec_str)
+        Self::try_new(pack_spec_str, stale_age_ms).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new<S: AsRef<str>>(
+        pack_spec_str: &[S],
+        stale_age_ms: f64,
+    ) -> Result<Self, ParameterError> {
+        let pack_spec = try_parse_byte_data_spec(pack_spec_str)?
             .try_into()
-            .expect("Bytes Data Spec is incorrectly sized for the number of inputs");
-        Self {
+            .map_err(|_| {
+                ParameterError("Bytes Data Spec is incorrectly sized for the number of inputs")
+            })?;
+        Ok(Self {
             pack_spec,
             stale_age: duration_from_ms_f64(stale_age_ms),
+        })
+    }
+
+    /// Builds the parameters from an already-resolved data spec, without parsing
+    pub const fn from_pack_spec(
+        pack_spec: GenericArray<(DataType, ByteOrderSpec), N>,
+        stale_age: Duration,
+    ) -> Self {
+        Self {
+            pack_spec,
+            stale_age,
         }
     }
 }
@@ -0,0 +1,206 @@
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use log::debug;
+use pictorus_traits::{ByteSliceSignal, Context, Matrix, PassBy, ProcessBlock};
+
+use crate::hil::{write_frame, HilFrame, HilFrameKind, HilFrameReader};
+use crate::stale_tracker::{duration_from_ms_f64, StaleTracker};
+
+/// Parameters for the HilHostBlock
+pub struct Parameters {
+    /// How long the target can go without answering before it's considered disconnected
+    pub timeout: Duration,
+}
+
+impl Parameters {
+    pub fn new(timeout_ms: f64) -> Self {
+        Self {
+            timeout: duration_from_ms_f64(timeout_ms),
+        }
+    }
+}
+
+/// Simulator side of a hardware-in-the-loop (HIL) bridge, streaming simulated sensor values to
+/// a target running the generated model and reading its actuator values back.
+///
+/// The block takes the bytes received from the transport and the `SENSORS` simulated sensor
+/// values, and outputs the bytes to transmit, the `ACTUATORS` actuator values last received
+/// from the target, whether the target answered within the timeout and the lag: how many
+/// sensor frames were sent after the one the target last answered, or -1 before it first
+/// answers. It's meant to sit between a receive and a transmit block (UDP or serial) in the
+/// simulated plant model, with a [`HilTargetBlock`](crate::HilTargetBlock) on the target.
+///
+/// A sensor frame is sent every tick. Actuator values hold their last value, starting at zero,
+/// and frames with the wrong number of values are dropped. Since the target answers each sensor
+/// frame it receives, a lag of 1 means each frame is answered before the next one is sent; larger
+/// values mean the target or the link can't keep up with the simulation rate.
+pub struct HilHostBlock<const SENSORS: usize, const ACTUATORS: usize> {
+    reader: HilFrameReader,
+    frame: HilFrame,
+    sequence: u16,
+    last_answered: Option<u16>,
+    stale_check: StaleTracker,
+    tx_buffer: Vec<u8>,
+    buffer: (Matrix<1, ACTUATORS, f64>, bool, f64),
+}
+
+impl<const SENSORS: usize, const ACTUATORS: usize> Default for HilHostBlock<SENSORS, ACTUATORS> {
+    fn default() -> Self {
+        Self {
+            reader: HilFrameReader::default(),
+            frame: HilFrame::default(),
+            sequence: 0,
+            last_answered: None,
+            stale_check: StaleTracker::default(),
+            tx_buffer: Vec::new(),
+            buffer: (Matrix::zeroed(), false, -1.0),
+        }
+    }
+}
+
+impl<const SENSORS: usize, const ACTUATORS: usize> ProcessBlock
+    for HilHostBlock<SENSORS, ACTUATORS>
+{
+    type Parameters = Parameters;
+    type Inputs = (ByteSliceSignal, Matrix<1, SENSORS, f64>);
+    type Output = (ByteSliceSignal, Matrix<1, ACTUATORS, f64>, bool, f64);
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (rx, sensors) = inputs;
+        let now = context.time();
+
+        self.reader.push(rx);
+        while self.reader.next_frame(&mut self.frame) {
+            if self.frame.kind != HilFrameKind::Actuators {
+                continue;
+            }
+            if self.frame.values.len() != ACTUATORS {
+                debug!(
+                    "Dropping HIL actuator frame with {} values, expected {ACTUATORS}",
+                    self.frame.values.len()
+                );
+                continue;
+            }
+            for (actuator, value) in self.buffer.0.data.iter_mut().zip(&self.frame.values) {
+                actuator[0] = *value;
+            }
+            self.last_answered = Some(self.frame.sequence);
+            self.stale_check.mark_updated(now);
+        }
+
+        self.sequence = self.sequence.wrapping_add(1);
+        let values: Vec<f64> = sensors.data.iter().map(|value| value[0]).collect();
+        self.tx_buffer.clear();
+        write_frame(
+            HilFrameKind::Sensors,
+            self.sequence,
+            &values,
+            &mut self.tx_buffer,
+        );
+
+        self.buffer.1 = self.stale_check.is_valid(now, parameters.timeout);
+        self.buffer.2 = self.last_answered.map_or(-1.0, |answered| {
+            f64::from(self.sequence.wrapping_sub(answered))
+        });
+
+        self.buffer()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        (
+            &self.tx_buffer,
+            &self.buffer.0,
+            self.buffer.1,
+            self.buffer.2,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+
+    fn actuators(sequence: u16, values: &[f64]) -> Vec<u8> {
+        let mut frame = Vec::new();
+        write_frame(HilFrameKind::Actuators, sequence, values, &mut frame);
+        frame
+    }
+
+    #[test]
+    fn test_hil_host_default_buffer_no_panic() {
+        let block = HilHostBlock::<2, 1>::default();
+        let (tx, actuators, connected, lag) = block.buffer();
+        assert_eq!(tx, b"");
+        assert_eq!(actuators, &Matrix::zeroed());
+        assert_eq!((connected, lag), (false, -1.0));
+    }
+
+    #[test]
+    fn test_hil_host_sends_sensors_and_reads_actuators() {
+        let parameters = Parameters::new(50.0);
+        let mut runtime = StubRuntime::default();
+        let mut block = HilHostBlock::<2, 1>::default();
+        let sensors = Matrix {
+            data: [[1.0], [2.0]],
+        };
+
+        let (tx, _, connected, lag) =
+            block.process(&parameters, &runtime.context(), (b"", &sensors));
+        let mut expected = Vec::new();
+        write_frame(HilFrameKind::Sensors, 1, &[1.0, 2.0], &mut expected);
+        assert_eq!(tx, expected.as_slice());
+        assert_eq!((connected, lag), (false, -1.0));
+
+        // The target answers the first frame while the second is sent
+        runtime.set_time(Duration::from_millis(10));
+        let (_, output, connected, lag) = block.process(
+            &parameters,
+            &runtime.context(),
+            (&actuators(1, &[0.5]), &sensors),
+        );
+        assert_eq!(output.data, [[0.5]]);
+        assert_eq!((connected, lag), (true, 1.0));
+
+        // Frames with the wrong number of values are dropped
+        runtime.set_time(Duration::from_millis(70));
+        let (_, output, connected, lag) = block.process(
+            &parameters,
+            &runtime.context(),
+            (&actuators(3, &[0.1, 0.2]), &sensors),
+        );
+        assert_eq!(output.data, [[0.5]]);
+        assert_eq!((connected, lag), (false, 2.0));
+    }
+
+    #[test]
+    fn test_hil_host_target_round_trip() {
+        let context = StubRuntime::default().context();
+        let parameters = Parameters::new(50.0);
+        let mut host = HilHostBlock::<1, 2>::default();
+        let target_parameters = crate::HilTargetBlockParams::new(50.0);
+        let mut target = crate::HilTargetBlock::<1, 2>::default();
+        let commands = Matrix {
+            data: [[3.0], [4.0]],
+        };
+
+        let tx = host
+            .process(&parameters, &context, (b"", &Matrix { data: [[9.0]] }))
+            .0
+            .to_vec();
+        let (reply, sensors, fresh, _) =
+            target.process(&target_parameters, &context, (&tx, &commands));
+        assert_eq!((sensors.data, fresh), ([[9.0]], true));
+        let reply = reply.to_vec();
+        let (_, actuators, connected, lag) =
+            host.process(&parameters, &context, (&reply, &Matrix { data: [[9.5]] }));
+        assert_eq!(actuators.data, [[3.0], [4.0]]);
+        assert_eq!((connected, lag), (true, 1.0));
+    }
+}
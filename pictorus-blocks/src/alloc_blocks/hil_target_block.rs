@@ -0,0 +1,170 @@
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use log::debug;
+use pictorus_traits::{ByteSliceSignal, Context, Matrix, PassBy, ProcessBlock};
+
+use crate::hil::{write_frame, HilFrame, HilFrameKind, HilFrameReader};
+use crate::stale_tracker::{duration_from_ms_f64, StaleTracker};
+
+/// Parameters for the HilTargetBlock
+pub struct Parameters {
+    /// How long the simulator can go without sending sensor values before it's considered
+    /// disconnected
+    pub timeout: Duration,
+}
+
+impl Parameters {
+    pub fn new(timeout_ms: f64) -> Self {
+        Self {
+            timeout: duration_from_ms_f64(timeout_ms),
+        }
+    }
+}
+
+/// Target side of a hardware-in-the-loop (HIL) bridge, reading simulated sensor values from a
+/// [`HilHostBlock`](crate::HilHostBlock) in the simulator and answering with actuator values.
+///
+/// The block takes the bytes received from the transport and the `ACTUATORS` actuator values,
+/// and outputs the bytes to transmit, the `SENSORS` sensor values last received from the
+/// simulator, whether new sensor values arrived this tick and whether the simulator sent any
+/// within the timeout. It's meant to sit between a receive and a transmit block (serial or UDP)
+/// on the target, standing in for the sensor and actuator blocks of the real hardware.
+///
+/// Every sensor frame is answered the tick it arrives with the current actuator values, tagged
+/// with the sensor frame's sequence number, and nothing is sent on ticks without one. The
+/// actuator values are usually computed from the sensor output, so they're fed back through a
+/// delay and answer the previous sensor values. Sensor values hold their last value, starting
+/// at zero, and frames with the wrong number of values are dropped.
+pub struct HilTargetBlock<const SENSORS: usize, const ACTUATORS: usize> {
+    reader: HilFrameReader,
+    frame: HilFrame,
+    stale_check: StaleTracker,
+    tx_buffer: Vec<u8>,
+    buffer: (Matrix<1, SENSORS, f64>, bool, bool),
+}
+
+impl<const SENSORS: usize, const ACTUATORS: usize> Default for HilTargetBlock<SENSORS, ACTUATORS> {
+    fn default() -> Self {
+        Self {
+            reader: HilFrameReader::default(),
+            frame: HilFrame::default(),
+            stale_check: StaleTracker::default(),
+            tx_buffer: Vec::new(),
+            buffer: (Matrix::zeroed(), false, false),
+        }
+    }
+}
+
+impl<const SENSORS: usize, const ACTUATORS: usize> ProcessBlock
+    for HilTargetBlock<SENSORS, ACTUATORS>
+{
+    type Parameters = Parameters;
+    type Inputs = (ByteSliceSignal, Matrix<1, ACTUATORS, f64>);
+    type Output = (ByteSliceSignal, Matrix<1, SENSORS, f64>, bool, bool);
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (rx, actuators) = inputs;
+        let now = context.time();
+        self.tx_buffer.clear();
+        self.buffer.1 = false;
+
+        let values: Vec<f64> = actuators.data.iter().map(|value| value[0]).collect();
+        self.reader.push(rx);
+        while self.reader.next_frame(&mut self.frame) {
+            if self.frame.kind != HilFrameKind::Sensors {
+                continue;
+            }
+            if self.frame.values.len() != SENSORS {
+                debug!(
+                    "Dropping HIL sensor frame with {} values, expected {SENSORS}",
+                    self.frame.values.len()
+                );
+                continue;
+            }
+            for (sensor, value) in self.buffer.0.data.iter_mut().zip(&self.frame.values) {
+                sensor[0] = *value;
+            }
+            self.buffer.1 = true;
+            self.stale_check.mark_updated(now);
+            write_frame(
+                HilFrameKind::Actuators,
+                self.frame.sequence,
+                &values,
+                &mut self.tx_buffer,
+            );
+        }
+
+        self.buffer.2 = self.stale_check.is_valid(now, parameters.timeout);
+        self.buffer()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        (
+            &self.tx_buffer,
+            &self.buffer.0,
+            self.buffer.1,
+            self.buffer.2,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+
+    fn sensors(sequence: u16, values: &[f64]) -> Vec<u8> {
+        let mut frame = Vec::new();
+        write_frame(HilFrameKind::Sensors, sequence, values, &mut frame);
+        frame
+    }
+
+    #[test]
+    fn test_hil_target_default_buffer_no_panic() {
+        let block = HilTargetBlock::<2, 1>::default();
+        let (tx, sensors, fresh, connected) = block.buffer();
+        assert_eq!(tx, b"");
+        assert_eq!(sensors, &Matrix::zeroed());
+        assert_eq!((fresh, connected), (false, false));
+    }
+
+    #[test]
+    fn test_hil_target_answers_each_sensor_frame() {
+        let parameters = Parameters::new(50.0);
+        let mut runtime = StubRuntime::default();
+        let mut block = HilTargetBlock::<1, 1>::default();
+        let actuators = Matrix { data: [[0.25]] };
+
+        let output = block.process(&parameters, &runtime.context(), (b"", &actuators));
+        assert_eq!((output.0, output.2, output.3), (b"".as_ref(), false, false));
+
+        // Both frames are answered, and the newest sensor values are output
+        let mut rx = sensors(4, &[1.0]);
+        rx.extend_from_slice(&sensors(5, &[2.0]));
+        let (tx, output, fresh, connected) =
+            block.process(&parameters, &runtime.context(), (&rx, &actuators));
+        let mut expected = Vec::new();
+        write_frame(HilFrameKind::Actuators, 4, &[0.25], &mut expected);
+        write_frame(HilFrameKind::Actuators, 5, &[0.25], &mut expected);
+        assert_eq!(tx, expected.as_slice());
+        assert_eq!(output.data, [[2.0]]);
+        assert_eq!((fresh, connected), (true, true));
+
+        // Frames with the wrong number of values are dropped
+        runtime.set_time(Duration::from_millis(60));
+        let (tx, output, fresh, connected) = block.process(
+            &parameters,
+            &runtime.context(),
+            (&sensors(6, &[1.0, 2.0]), &actuators),
+        );
+        assert_eq!(tx, b"");
+        assert_eq!(output.data, [[2.0]]);
+        assert_eq!((fresh, connected), (false, false));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::traits::serialize::{ByteSliceFormat, Serialize};
+use crate::ParameterError;
 use alloc::{string::String, vec::Vec};
 use miniserde::json::{self, Value};
 use pictorus_traits::{ByteSliceSignal, Pass, PassBy, ProcessBlock};
@@ -69,14 +70,31 @@ pub struct Parameters {
 
 impl Parameters {
     pub fn new(encoding_spec: &[String]) -> Self {
-        let encoding_spec = Self::parse_output_spec(encoding_spec);
+        Self::try_new(encoding_spec).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(encoding_spec: &[String]) -> Result<Self, ParameterError> {
+        let encoding_spec = Self::parse_output_spec(encoding_spec)?;
+        Ok(Self { encoding_spec })
+    }
+
+    /// Builds the parameters from already-resolved encoding types, without parsing
+    pub const fn from_encoding_spec(encoding_spec: Vec<(EncodingType, String)>) -> Self {
         Self { encoding_spec }
     }
 
-    fn parse_output_spec(data: &[String]) -> Vec<(EncodingType, String)> {
+    fn parse_output_spec(data: &[String]) -> Result<Vec<(EncodingType, String)>, ParameterError> {
         data.iter()
-            .map(|d| d.split_once(':').expect("Invalid output data format"))
-            .map(|(dt, field)| (dt.parse::<EncodingType>().unwrap(), field.into()))
+            .map(|d| {
+                let (dt, field) = d
+                    .split_once(':')
+                    .ok_or(ParameterError("Invalid output data format"))?;
+                let dt = dt
+                    .parse::<EncodingType>()
+                    .map_err(|_| ParameterError("Invalid output encoding type"))?;
+                Ok((dt, field.into()))
+            })
             .collect()
     }
 }
@@ -9,6 +9,7 @@ use pictorus_traits::{ByteSliceSignal, Context, Matrix, Pass, PassBy, ProcessBlo
 use crate::{
     stale_tracker::{duration_from_ms_f64, StaleTracker},
     traits::{DefaultStorage, Float},
+    ParameterError,
 };
 
 /// Block-output data shape, parsed from the user-supplied select-data spec strings.
@@ -100,17 +101,40 @@ impl Parameters {
     // `<S: AsRef<str>>` to allow for both &str and String. It's tricky to do that here because
     // we allow empty select_data, which would require us to specify a generic type.
     pub fn new(select_data: &[String], stale_age_ms: f64) -> Self {
-        let select_data = Self::parse_select_spec(select_data);
-        Self {
+        Self::try_new(select_data, stale_age_ms).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(select_data: &[String], stale_age_ms: f64) -> Result<Self, ParameterError> {
+        let select_data = Self::parse_select_spec(select_data)?;
+        Ok(Self {
             select_data,
             stale_age: duration_from_ms_f64(stale_age_ms),
+        })
+    }
+
+    /// Builds the parameters from already-resolved data types, without parsing
+    pub const fn from_select_data(
+        select_data: Vec<(BlockDataType, String)>,
+        stale_age: Duration,
+    ) -> Self {
+        Self {
+            select_data,
+            stale_age,
         }
     }
 
-    fn parse_select_spec(data: &[String]) -> Vec<(BlockDataType, String)> {
+    fn parse_select_spec(data: &[String]) -> Result<Vec<(BlockDataType, String)>, ParameterError> {
         data.iter()
-            .map(|d| d.split_once(':').expect("Invalid select data format"))
-            .map(|(dt, field)| (dt.parse::<BlockDataType>().unwrap(), field.into()))
+            .map(|d| {
+                let (dt, field) = d
+                    .split_once(':')
+                    .ok_or(ParameterError("Invalid select data format"))?;
+                let dt = dt
+                    .parse::<BlockDataType>()
+                    .map_err(|_| ParameterError("Invalid select data type"))?;
+                Ok((dt, field.into()))
+            })
             .collect()
     }
 }
@@ -2,6 +2,12 @@
 //!
 //! These blocks are only compiled when the `alloc` feature is enabled.
 
+mod accumulate_bytes_block;
+pub use accumulate_bytes_block::AccumulateBytesBlock;
+
+mod at_command_block;
+pub use at_command_block::AtCommandBlock;
+
 mod bytes_join_block;
 pub use bytes_join_block::BytesJoinBlock;
 
@@ -19,6 +25,16 @@ pub use can_transmit_block::CanTransmitBlock;
 #[doc(hidden)]
 pub use can_transmit_block::Parameters as CanTransmitBlockParams;
 
+mod hil_host_block;
+pub use hil_host_block::HilHostBlock;
+#[doc(hidden)]
+pub use hil_host_block::Parameters as HilHostBlockParams;
+
+mod hil_target_block;
+pub use hil_target_block::HilTargetBlock;
+#[doc(hidden)]
+pub use hil_target_block::Parameters as HilTargetBlockParams;
+
 mod i2c_input_block;
 pub use i2c_input_block::I2cInputBlock;
 #[doc(hidden)]
@@ -30,10 +46,20 @@ pub use i2c_output_block::I2cOutputBlock;
 pub use i2c_output_block::Parameters as I2cOutputBlockParams;
 
 mod json_dump_block;
-pub use json_dump_block::JsonDumpBlock;
+pub use json_dump_block::{EncodingType, JsonDumpBlock};
 
 mod json_load_block;
-pub use json_load_block::JsonLoadBlock;
+pub use json_load_block::{BlockDataType, JsonLoadBlock};
+
+mod rpc_client_block;
+#[doc(hidden)]
+pub use rpc_client_block::Parameters as RpcClientBlockParams;
+pub use rpc_client_block::RpcClientBlock;
+
+mod rpc_server_block;
+#[doc(hidden)]
+pub use rpc_server_block::Parameters as RpcServerBlockParams;
+pub use rpc_server_block::RpcServerBlock;
 
 mod serial_receive_block;
 #[doc(hidden)]
@@ -50,8 +76,31 @@ mod spi_receive_block;
 pub use spi_receive_block::Parameters as SpiReceiveBlockParams;
 pub use spi_receive_block::SpiReceiveBlock;
 
+mod string_concat_block;
+pub use string_concat_block::StringConcatBlock;
+
 mod string_format_block;
 pub use string_format_block::StringFormatBlock;
 
 mod switch_block;
 pub use switch_block::SwitchBlock;
+
+mod udp_receive_block;
+#[doc(hidden)]
+pub use udp_receive_block::Parameters as UdpReceiveBlockParams;
+pub use udp_receive_block::UdpReceiveBlock;
+
+mod udp_transmit_block;
+#[doc(hidden)]
+pub use udp_transmit_block::Parameters as UdpTransmitBlockParams;
+pub use udp_transmit_block::UdpTransmitBlock;
+
+mod xbee_decode_block;
+#[doc(hidden)]
+pub use xbee_decode_block::Parameters as XBeeDecodeBlockParams;
+pub use xbee_decode_block::XBeeDecodeBlock;
+
+mod xbee_encode_block;
+#[doc(hidden)]
+pub use xbee_encode_block::Parameters as XBeeEncodeBlockParams;
+pub use xbee_encode_block::{XBeeEncodeBlock, XBeeRequest};
@@ -0,0 +1,246 @@
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use log::debug;
+use pictorus_traits::{ByteSliceSignal, Context, PassBy, ProcessBlock};
+
+use crate::stale_tracker::duration_from_ms_f64;
+
+/// First byte of every RPC frame, to ignore unrelated traffic
+const MAGIC: u8 = b'R';
+/// Magic, kind and big endian u16 request ID
+const HEADER_LEN: usize = 4;
+
+/// Kinds of RPC frame
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum FrameKind {
+    Request = 0,
+    Response = 1,
+}
+
+/// Writes a frame carrying `payload` to `buffer`
+pub(super) fn encode_frame(buffer: &mut Vec<u8>, kind: FrameKind, id: u16, payload: &[u8]) {
+    buffer.extend_from_slice(&[MAGIC, kind as u8]);
+    buffer.extend_from_slice(&id.to_be_bytes());
+    buffer.extend_from_slice(payload);
+}
+
+/// Splits a frame into its kind, ID and payload
+pub(super) fn decode_frame(frame: &[u8]) -> Option<(FrameKind, u16, &[u8])> {
+    if frame.len() < HEADER_LEN || frame[0] != MAGIC {
+        return None;
+    }
+    let kind = match frame[1] {
+        0 => FrameKind::Request,
+        1 => FrameKind::Response,
+        _ => return None,
+    };
+    Some((
+        kind,
+        u16::from_be_bytes([frame[2], frame[3]]),
+        &frame[HEADER_LEN..],
+    ))
+}
+
+/// Parameters for the RpcClientBlock
+pub struct Parameters {
+    /// How long to wait for a response before resending the request
+    pub timeout: Duration,
+    /// Number of times a request is resent before giving up on it
+    pub max_retries: u8,
+}
+
+impl Parameters {
+    pub fn new(timeout_ms: f64, max_retries: f64) -> Self {
+        Self {
+            timeout: duration_from_ms_f64(timeout_ms),
+            max_retries: max_retries as u8,
+        }
+    }
+}
+
+/// Sends requests to a remote service and matches up its responses, for models that query
+/// external services (e.g. a planner or a database) over UDP.
+///
+/// The block takes the bytes received from the transport, a request payload and a send flag,
+/// and outputs the bytes to transmit, the payload of the most recent response, whether a
+/// response arrived this tick and whether a request failed this tick. It's meant to sit between
+/// a UDP receive block and a UDP transmit block addressed to the service, with an
+/// [`RpcServerBlock`](crate::RpcServerBlock) (or anything speaking the same framing) on the
+/// other end.
+///
+/// Each request is framed with a header of the byte `R`, a kind byte (0 for requests, 1 for
+/// responses) and a big endian u16 ID, followed by the payload; the response to a request
+/// carries its ID. One request is in flight at a time: the request input is sent when the send
+/// flag is true and no request is pending, so holding the flag true polls the service as fast
+/// as it responds. A request that goes unanswered for the timeout is resent with the same ID,
+/// up to `max_retries` times, after which it's dropped and the failed flag is true for a tick.
+/// Responses to earlier requests are ignored.
+#[derive(Default)]
+pub struct RpcClientBlock {
+    /// ID of the request in flight
+    pending: Option<u16>,
+    next_id: u16,
+    retries: u8,
+    sent_at: Duration,
+    request: Vec<u8>,
+    tx_buffer: Vec<u8>,
+    response: Vec<u8>,
+    received: bool,
+    failed: bool,
+}
+
+impl RpcClientBlock {
+    fn transmit(&mut self, id: u16, now: Duration) {
+        encode_frame(&mut self.tx_buffer, FrameKind::Request, id, &self.request);
+        self.sent_at = now;
+    }
+}
+
+impl ProcessBlock for RpcClientBlock {
+    type Parameters = Parameters;
+    type Inputs = (ByteSliceSignal, ByteSliceSignal, bool);
+    type Output = (ByteSliceSignal, ByteSliceSignal, bool, bool);
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (rx, request, send) = inputs;
+        let now = context.time();
+        self.tx_buffer.clear();
+        self.received = false;
+        self.failed = false;
+
+        if let Some((FrameKind::Response, id, payload)) = decode_frame(rx) {
+            if self.pending == Some(id) {
+                self.response.clear();
+                self.response.extend_from_slice(payload);
+                self.received = true;
+                self.pending = None;
+            }
+        }
+
+        if let Some(id) = self.pending {
+            if now.saturating_sub(self.sent_at) >= parameters.timeout {
+                if self.retries < parameters.max_retries {
+                    self.retries += 1;
+                    debug!("Retrying RPC request {id} (attempt {})", self.retries);
+                    self.transmit(id, now);
+                } else {
+                    debug!("RPC request {id} failed after {} retries", self.retries);
+                    self.pending = None;
+                    self.failed = true;
+                }
+            }
+        }
+
+        if self.pending.is_none() && send {
+            let id = self.next_id;
+            self.next_id = self.next_id.wrapping_add(1);
+            self.request.clear();
+            self.request.extend_from_slice(request);
+            self.retries = 0;
+            self.pending = Some(id);
+            self.transmit(id, now);
+        }
+
+        (&self.tx_buffer, &self.response, self.received, self.failed)
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        (&self.tx_buffer, &self.response, self.received, self.failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+
+    fn response(id: u16, payload: &[u8]) -> Vec<u8> {
+        let mut frame = Vec::new();
+        encode_frame(&mut frame, FrameKind::Response, id, payload);
+        frame
+    }
+
+    #[test]
+    fn test_rpc_client_default_buffer_no_panic() {
+        let block = RpcClientBlock::default();
+        assert_eq!(block.buffer(), (b"".as_ref(), b"".as_ref(), false, false));
+    }
+
+    #[test]
+    fn test_rpc_frame_encoding() {
+        let mut frame = Vec::new();
+        encode_frame(&mut frame, FrameKind::Request, 0x0102, b"hi");
+        assert_eq!(frame, b"R\x00\x01\x02hi");
+        assert_eq!(
+            decode_frame(&frame),
+            Some((FrameKind::Request, 0x0102, b"hi".as_ref()))
+        );
+        assert_eq!(decode_frame(b"R\x02\x00\x00"), None);
+        assert_eq!(decode_frame(b"R\x01\x00"), None);
+        assert_eq!(decode_frame(b"X\x01\x00\x00"), None);
+    }
+
+    #[test]
+    fn test_rpc_client_request_response() {
+        let parameters = Parameters::new(100.0, 1.0);
+        let context = StubRuntime::default().context();
+        let mut block = RpcClientBlock::default();
+
+        let output = block.process(&parameters, &context, (b"", b"get", false));
+        assert_eq!(output, (b"".as_ref(), b"".as_ref(), false, false));
+
+        let output = block.process(&parameters, &context, (b"", b"get", true));
+        assert_eq!(output.0, b"R\x00\x00\x00get");
+
+        // Only one request is in flight, and stale responses are ignored
+        let output = block.process(&parameters, &context, (&response(7, b"old"), b"", true));
+        assert_eq!(output, (b"".as_ref(), b"".as_ref(), false, false));
+
+        let output = block.process(&parameters, &context, (&response(0, b"42"), b"", false));
+        assert_eq!(output, (b"".as_ref(), b"42".as_ref(), true, false));
+
+        // The response is held, and the next request gets a new ID
+        let output = block.process(&parameters, &context, (b"", b"set", true));
+        assert_eq!(
+            output,
+            (b"R\x00\x00\x01set".as_ref(), b"42".as_ref(), false, false)
+        );
+    }
+
+    #[test]
+    fn test_rpc_client_retries_then_fails() {
+        let parameters = Parameters::new(100.0, 1.0);
+        let mut runtime = StubRuntime::default();
+        let mut block = RpcClientBlock::default();
+
+        let output = block.process(&parameters, &runtime.context(), (b"", b"get", true));
+        assert_eq!(output.0, b"R\x00\x00\x00get");
+
+        runtime.set_time(Duration::from_millis(99));
+        let output = block.process(&parameters, &runtime.context(), (b"", b"", false));
+        assert_eq!(output.0, b"");
+
+        // Resent with the same ID and payload
+        runtime.set_time(Duration::from_millis(100));
+        let output = block.process(&parameters, &runtime.context(), (b"", b"", false));
+        assert_eq!(output.0, b"R\x00\x00\x00get");
+
+        runtime.set_time(Duration::from_millis(200));
+        let output = block.process(&parameters, &runtime.context(), (b"", b"", false));
+        assert_eq!(output, (b"".as_ref(), b"".as_ref(), false, true));
+
+        // A late response to the dropped request is ignored
+        let output = block.process(
+            &parameters,
+            &runtime.context(),
+            (&response(0, b"1"), b"", false),
+        );
+        assert_eq!(output, (b"".as_ref(), b"".as_ref(), false, false));
+    }
+}
@@ -0,0 +1,169 @@
+use alloc::vec::Vec;
+
+use pictorus_traits::{ByteSliceSignal, Context, PassBy, ProcessBlock};
+
+use super::rpc_client_block::{decode_frame, encode_frame, FrameKind};
+
+/// Parameters for the RpcServerBlock
+pub struct Parameters {}
+
+impl Default for Parameters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parameters {
+    pub fn new() -> Parameters {
+        Parameters {}
+    }
+}
+
+/// Serves requests from an [`RpcClientBlock`](crate::RpcClientBlock), letting a model act as
+/// a service for other apps.
+///
+/// The block takes the bytes received from the transport, a reply payload and a respond flag,
+/// and outputs the bytes to transmit, the payload of the most recent request and whether a new
+/// request arrived this tick. It's meant to sit between a UDP receive block and a UDP transmit
+/// block addressed to the client.
+///
+/// Once a request arrives, the reply input is sent back on the first tick the respond flag is
+/// true, tagged with the request's ID. Since the reply is computed from the request output, it's
+/// usually fed back through a delay, so replies go out a tick or more after their requests.
+/// Requests that are resent while their reply is being computed are ignored, and a request
+/// resent after it was answered (because the reply was lost) gets the same reply again, so each
+/// request is only handled once. A new request replaces one that hasn't been answered yet.
+#[derive(Default)]
+pub struct RpcServerBlock {
+    /// ID of the request awaiting a reply
+    pending: Option<u16>,
+    /// ID of the last request answered, and the frame it was answered with
+    answered: Option<(u16, Vec<u8>)>,
+    request: Vec<u8>,
+    tx_buffer: Vec<u8>,
+    new_request: bool,
+}
+
+impl ProcessBlock for RpcServerBlock {
+    type Parameters = Parameters;
+    type Inputs = (ByteSliceSignal, ByteSliceSignal, bool);
+    type Output = (ByteSliceSignal, ByteSliceSignal, bool);
+
+    fn process<'b>(
+        &'b mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (rx, reply, respond) = inputs;
+        self.tx_buffer.clear();
+        self.new_request = false;
+
+        if respond {
+            if let Some(id) = self.pending.take() {
+                encode_frame(&mut self.tx_buffer, FrameKind::Response, id, reply);
+                self.answered = Some((id, self.tx_buffer.clone()));
+            }
+        }
+
+        if let Some((FrameKind::Request, id, payload)) = decode_frame(rx) {
+            match &self.answered {
+                Some((answered, frame)) if *answered == id => {
+                    if self.tx_buffer.is_empty() {
+                        self.tx_buffer.extend_from_slice(frame);
+                    }
+                }
+                _ if self.pending == Some(id) => {}
+                _ => {
+                    self.pending = Some(id);
+                    self.request.clear();
+                    self.request.extend_from_slice(payload);
+                    self.new_request = true;
+                }
+            }
+        }
+
+        (&self.tx_buffer, &self.request, self.new_request)
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        (&self.tx_buffer, &self.request, self.new_request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+
+    fn request(id: u16, payload: &[u8]) -> Vec<u8> {
+        let mut frame = Vec::new();
+        encode_frame(&mut frame, FrameKind::Request, id, payload);
+        frame
+    }
+
+    #[test]
+    fn test_rpc_server_default_buffer_no_panic() {
+        let block = RpcServerBlock::default();
+        assert_eq!(block.buffer(), (b"".as_ref(), b"".as_ref(), false));
+    }
+
+    #[test]
+    fn test_rpc_server_replies_once_per_request() {
+        let parameters = Parameters::new();
+        let context = StubRuntime::default().context();
+        let mut block = RpcServerBlock::default();
+
+        // Nothing to reply to yet
+        let output = block.process(&parameters, &context, (b"", b"x", true));
+        assert_eq!(output, (b"".as_ref(), b"".as_ref(), false));
+
+        let output = block.process(&parameters, &context, (&request(5, b"get"), b"", false));
+        assert_eq!(output, (b"".as_ref(), b"get".as_ref(), true));
+
+        // A resend while the reply is computed isn't a new request
+        let output = block.process(&parameters, &context, (&request(5, b"get"), b"", false));
+        assert_eq!(output, (b"".as_ref(), b"get".as_ref(), false));
+
+        let output = block.process(&parameters, &context, (b"", b"42", true));
+        assert_eq!(
+            output,
+            (b"R\x01\x00\x0542".as_ref(), b"get".as_ref(), false)
+        );
+        let output = block.process(&parameters, &context, (b"", b"43", true));
+        assert_eq!(output.0, b"");
+
+        // A resend after the reply was lost gets the same reply
+        let output = block.process(&parameters, &context, (&request(5, b"get"), b"", true));
+        assert_eq!(
+            output,
+            (b"R\x01\x00\x0542".as_ref(), b"get".as_ref(), false)
+        );
+
+        let output = block.process(&parameters, &context, (&request(6, b"set"), b"", true));
+        assert_eq!(output, (b"".as_ref(), b"set".as_ref(), true));
+    }
+
+    #[test]
+    fn test_rpc_client_server_round_trip() {
+        let context = StubRuntime::default().context();
+        let client_parameters = crate::RpcClientBlockParams::new(100.0, 0.0);
+        let mut client = crate::RpcClientBlock::default();
+        let parameters = Parameters::new();
+        let mut server = RpcServerBlock::default();
+
+        let request = client
+            .process(&client_parameters, &context, (b"", b"ping", true))
+            .0
+            .to_vec();
+        let (_, received, new_request) =
+            server.process(&parameters, &context, (&request, b"", false));
+        assert_eq!((received, new_request), (b"ping".as_ref(), true));
+        let reply = server
+            .process(&parameters, &context, (b"", b"pong", true))
+            .0
+            .to_vec();
+        let output = client.process(&client_parameters, &context, (&reply, b"", false));
+        assert_eq!(output, (b"".as_ref(), b"pong".as_ref(), true, false));
+    }
+}
@@ -219,7 +219,9 @@ impl ProcessBlock for SerialReceiveBlock {
             if start_idx != 0 {
                 debug!("Discarding {start_idx} bytes");
             }
-            self.output = val.to_vec();
+            // Reuse the output storage rather than allocating a new Vec every tick
+            self.output.clear();
+            self.output.extend_from_slice(val);
 
             // TODO: Drain is coming to heapless vec soon! - https://github.com/rust-embedded/heapless/pull/444
             self.buffer
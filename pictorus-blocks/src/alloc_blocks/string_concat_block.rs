@@ -0,0 +1,124 @@
+use alloc::string::String;
+use pictorus_traits::{Pass, PassBy, ProcessBlock, StrSliceSignal};
+
+/// Parameters for the StringConcatBlock
+pub struct Parameters {
+    /// Inserted between each pair of inputs
+    pub separator: String,
+}
+
+impl Parameters {
+    pub fn new(separator: &str) -> Self {
+        Self {
+            separator: String::from(separator),
+        }
+    }
+}
+
+/// Concatenates two or more strings, optionally separated by a fixed separator.
+pub struct StringConcatBlock<T: Apply> {
+    buffer: String,
+    _unused: core::marker::PhantomData<T>,
+}
+
+impl<T: Apply> Default for StringConcatBlock<T> {
+    fn default() -> Self {
+        Self {
+            buffer: String::new(),
+            _unused: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<T: Apply> ProcessBlock for StringConcatBlock<T> {
+    type Parameters = Parameters;
+    type Inputs = T;
+    type Output = StrSliceSignal;
+
+    fn process(
+        &mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'_, Self::Output> {
+        self.buffer.clear();
+        T::apply(&mut self.buffer, inputs, parameters);
+        &self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        &self.buffer
+    }
+}
+
+pub trait Apply: Pass {
+    fn apply(dest: &mut String, input: PassBy<Self>, params: &Parameters);
+}
+
+/// Implements `Apply` for tuples of `StrSliceSignal`s
+macro_rules! impl_concat_apply {
+    ($( ( $first:ident $first_i:tt $(, $rest:ident $i:tt)+ ) ),+ $(,)?) => {
+        $(
+            impl Apply for ($first $(, $rest)+) {
+                fn apply(dest: &mut String, input: PassBy<Self>, params: &Parameters) {
+                    dest.push_str(input.$first_i);
+                    $(
+                        dest.push_str(&params.separator);
+                        dest.push_str(input.$i);
+                    )+
+                }
+            }
+        )+
+    };
+}
+
+type S = StrSliceSignal;
+
+impl_concat_apply!(
+    (S 0, S 1),
+    (S 0, S 1, S 2),
+    (S 0, S 1, S 2, S 3),
+    (S 0, S 1, S 2, S 3, S 4),
+    (S 0, S 1, S 2, S 3, S 4, S 5),
+    (S 0, S 1, S 2, S 3, S 4, S 5, S 6),
+    (S 0, S 1, S 2, S 3, S 4, S 5, S 6, S 7),
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+
+    #[test]
+    fn test_string_concat_default_buffer_no_panic() {
+        let block = StringConcatBlock::<(StrSliceSignal, StrSliceSignal)>::default();
+        assert_eq!(block.buffer(), "");
+    }
+
+    #[test]
+    fn test_string_concat_block() {
+        let context = StubContext::default();
+        let parameters = Parameters::new("");
+        let mut block = StringConcatBlock::<(StrSliceSignal, StrSliceSignal)>::default();
+        assert_eq!(
+            block.process(&parameters, &context, ("AT+", "CSQ")),
+            "AT+CSQ"
+        );
+        assert_eq!(block.buffer(), "AT+CSQ");
+
+        // The previous output doesn't leak into the next one
+        assert_eq!(block.process(&parameters, &context, ("", "")), "");
+    }
+
+    #[test]
+    fn test_string_concat_separator() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(", ");
+        let mut block =
+            StringConcatBlock::<(StrSliceSignal, StrSliceSignal, StrSliceSignal)>::default();
+        assert_eq!(
+            block.process(&parameters, &context, ("état", "armed", "ok")),
+            "état, armed, ok"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::traits::{Scalar, Serialize};
 use alloc::{string::String, vec::Vec};
-use pictorus_traits::{ByteSliceSignal, Matrix, Pass, PassBy, ProcessBlock};
+use pictorus_traits::{ByteSliceSignal, Matrix, Pass, PassBy, ProcessBlock, StrSliceSignal};
 
 pub struct Parameters<const N: usize> {
     pub formatter_function: fn(&[&str; N]) -> String,
@@ -81,6 +81,12 @@ impl ToString for ByteSliceSignal {
     }
 }
 
+impl ToString for StrSliceSignal {
+    fn to_string(input: PassBy<Self>) -> String {
+        String::from(input)
+    }
+}
+
 pub trait Apply: Pass {
     type Parameters;
     fn apply(input: PassBy<Self>, parameters: &Self::Parameters) -> String;
@@ -308,6 +314,17 @@ mod tests {
         assert_eq!(block.buffer(), output.as_slice());
     }
 
+    #[test]
+    fn test_string_format_block_str() {
+        let formatter = |inputs: &[&str; 2]| format!("{}={}", inputs[0], inputs[1]);
+        let parameters = Parameters::new(formatter);
+        let mut block = StringFormatBlock::<(StrSliceSignal, f64)>::default();
+
+        let context = StubContext::default();
+        let output = block.process(&parameters, &context, ("mode", 2.0));
+        assert_eq!(output, b"mode=2.0");
+    }
+
     #[test]
     fn test_string_format_block_matrix() {
         let formatter = |inputs: &[&str; 1]| format!("Matrix: {}", inputs[0]);
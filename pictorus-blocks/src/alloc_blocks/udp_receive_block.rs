@@ -48,7 +48,8 @@ impl ProcessBlock for UdpReceiveBlock {
         // something has gone wrong.
         if !input.is_empty() {
             self.stale_check.mark_updated(context.time());
-            self.buffer = input.to_vec();
+            self.buffer.clear();
+            self.buffer.extend_from_slice(input);
         }
 
         self.last_valid = self
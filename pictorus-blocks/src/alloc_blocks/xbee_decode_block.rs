@@ -0,0 +1,235 @@
+use alloc::vec::Vec;
+use log::debug;
+use pictorus_traits::{ByteSliceSignal, PassBy, ProcessBlock};
+
+use crate::traits::Float;
+use crate::xbee::{FrameReader, API_AT_COMMAND_RESPONSE, API_RECEIVE_PACKET, API_TRANSMIT_STATUS};
+
+/// Parameters for the XBeeDecodeBlock
+pub struct Parameters {
+    /// Whether the radio is in API mode 2, which escapes control characters
+    pub escaped: bool,
+}
+
+impl Parameters {
+    pub fn new(escaped: bool) -> Self {
+        Self { escaped }
+    }
+}
+
+/// Decodes XBee API frames read from an XBee radio's serial port.
+///
+/// The block takes the bytes read from the serial port, which may split frames across ticks,
+/// and outputs:
+/// - The RF data of a receive packet (API frame 0x90)
+/// - The 64-bit source address of that packet, as 8 big-endian bytes
+/// - The value returned by an AT command response (API frame 0x88)
+/// - The status of the most recent AT command response: 0 for OK, 1 for ERROR, 2 for an invalid
+///   command and 3 for an invalid parameter
+/// - The delivery status of the most recent transmit status (API frame 0x8B), where 0 means the
+///   packet was delivered
+///
+/// The byte outputs are only non-empty on the tick their frame is decoded, while the statuses
+/// hold their last value and are -1 until the first response arrives. At most one receive packet
+/// or AT command response is output per tick; any further frames are decoded on later ticks.
+/// Frames with bad checksums and unsupported frame types are dropped.
+pub struct XBeeDecodeBlock<F: Float> {
+    reader: FrameReader,
+    frame_data: Vec<u8>,
+    data: Vec<u8>,
+    source_address: Vec<u8>,
+    at_response: Vec<u8>,
+    at_status: F,
+    delivery_status: F,
+}
+
+impl<F: Float> Default for XBeeDecodeBlock<F> {
+    fn default() -> Self {
+        Self {
+            reader: FrameReader::default(),
+            frame_data: Vec::new(),
+            data: Vec::new(),
+            source_address: Vec::new(),
+            at_response: Vec::new(),
+            at_status: -F::one(),
+            delivery_status: -F::one(),
+        }
+    }
+}
+
+impl<F: Float> XBeeDecodeBlock<F> {
+    /// Handles the frame in `frame_data`, returning true if it produced a byte output
+    fn handle_frame(&mut self, frame: &[u8]) -> bool {
+        match frame {
+            // API ID, 64-bit source, 16-bit source, options, data
+            [API_RECEIVE_PACKET, fields @ ..] if fields.len() >= 11 => {
+                self.source_address.extend_from_slice(&fields[..8]);
+                self.data.extend_from_slice(&fields[11..]);
+                true
+            }
+            // API ID, frame ID, AT command, status, value
+            [API_AT_COMMAND_RESPONSE, _, _, _, status, value @ ..] => {
+                self.at_status = F::from(*status).unwrap_or_else(F::zero);
+                self.at_response.extend_from_slice(value);
+                true
+            }
+            // API ID, frame ID, 16-bit destination, retry count, delivery status, discovery
+            [API_TRANSMIT_STATUS, _, _, _, _, status, _] => {
+                self.delivery_status = F::from(*status).unwrap_or_else(F::zero);
+                false
+            }
+            _ => {
+                debug!("Dropping unsupported or truncated XBee frame: {frame:02X?}");
+                false
+            }
+        }
+    }
+}
+
+impl<F: Float> ProcessBlock for XBeeDecodeBlock<F> {
+    type Parameters = Parameters;
+    type Inputs = ByteSliceSignal;
+    type Output = (ByteSliceSignal, ByteSliceSignal, ByteSliceSignal, F, F);
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        self.data.clear();
+        self.source_address.clear();
+        self.at_response.clear();
+
+        self.reader.push(inputs, parameters.escaped);
+        let mut frame_data = core::mem::take(&mut self.frame_data);
+        while self.reader.next_frame(&mut frame_data) {
+            if self.handle_frame(&frame_data) {
+                break;
+            }
+        }
+        self.frame_data = frame_data;
+
+        self.buffer()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        (
+            &self.data,
+            &self.source_address,
+            &self.at_response,
+            self.at_status,
+            self.delivery_status,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use crate::xbee::write_frame;
+
+    fn frame(frame_data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_frame(frame_data, false, &mut out);
+        out
+    }
+
+    #[test]
+    fn test_xbee_decode_default_buffer_no_panic() {
+        let block = XBeeDecodeBlock::<f64>::default();
+        assert_eq!(
+            block.buffer(),
+            (b"".as_slice(), b"".as_slice(), b"".as_slice(), -1.0, -1.0)
+        );
+    }
+
+    #[test]
+    fn test_xbee_decode_receive_packet() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(false);
+        let mut block = XBeeDecodeBlock::<f64>::default();
+
+        let encoded = frame(&[
+            0x90, 0x00, 0x13, 0xA2, 0x00, 0x40, 0x52, 0x2B, 0xAA, 0x7D, 0x84, 0x01, b'h', b'i',
+        ]);
+        // Nothing is output until the whole frame has arrived
+        let (data, ..) = block.process(&parameters, &context, &encoded[..6]);
+        assert_eq!(data, b"");
+
+        let (data, source, at_response, at_status, delivery_status) =
+            block.process(&parameters, &context, &encoded[6..]);
+        assert_eq!(data, b"hi");
+        assert_eq!(source, [0x00, 0x13, 0xA2, 0x00, 0x40, 0x52, 0x2B, 0xAA]);
+        assert_eq!(at_response, b"");
+        assert_eq!(at_status, -1.0);
+        assert_eq!(delivery_status, -1.0);
+
+        // The packet is only output on the tick it's decoded
+        let (data, source, ..) = block.process(&parameters, &context, b"");
+        assert_eq!(data, b"");
+        assert_eq!(source, b"");
+    }
+
+    #[test]
+    fn test_xbee_decode_statuses() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(false);
+        let mut block = XBeeDecodeBlock::<f64>::default();
+
+        let mut encoded = frame(&[0x8B, 0x01, 0x7D, 0x84, 0x00, 0x00, 0x01]);
+        encoded.extend(frame(&[0x88, 0x02, b'D', b'B', 0x00, 0x28]));
+        let (data, _, at_response, at_status, delivery_status) =
+            block.process(&parameters, &context, &encoded);
+        assert_eq!(data, b"");
+        assert_eq!(at_response, [0x28]);
+        assert_eq!(at_status, 0.0);
+        assert_eq!(delivery_status, 0.0);
+
+        // Statuses are held, and a failed delivery is reported
+        let encoded = frame(&[0x8B, 0x03, 0x7D, 0x84, 0x02, 0x21, 0x00]);
+        let (_, _, at_response, at_status, delivery_status) =
+            block.process(&parameters, &context, &encoded);
+        assert_eq!(at_response, b"");
+        assert_eq!(at_status, 0.0);
+        assert_eq!(delivery_status, 33.0);
+    }
+
+    #[test]
+    fn test_xbee_decode_one_packet_per_tick() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(true);
+        let mut block = XBeeDecodeBlock::<f64>::default();
+
+        let mut encoded = Vec::new();
+        for payload in [b"one", b"two"] {
+            let mut frame_data = alloc::vec![0x90, 0, 0, 0, 0, 0, 0, 0, 0x01, 0xFF, 0xFE, 0x01];
+            frame_data.extend_from_slice(payload);
+            write_frame(&frame_data, true, &mut encoded);
+        }
+
+        let (data, ..) = block.process(&parameters, &context, &encoded);
+        assert_eq!(data, b"one");
+        let (data, source, ..) = block.process(&parameters, &context, b"");
+        assert_eq!(data, b"two");
+        assert_eq!(source, [0, 0, 0, 0, 0, 0, 0, 0x01]);
+    }
+
+    #[test]
+    fn test_xbee_decode_drops_unsupported_frames() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(false);
+        let mut block = XBeeDecodeBlock::<f64>::default();
+
+        // Modem status frame, followed by a truncated receive packet
+        let mut encoded = frame(&[0x8A, 0x06]);
+        encoded.extend(frame(&[0x90, 0x00, 0x01]));
+        let (data, _, at_response, at_status, delivery_status) =
+            block.process(&parameters, &context, &encoded);
+        assert_eq!(data, b"");
+        assert_eq!(at_response, b"");
+        assert_eq!(at_status, -1.0);
+        assert_eq!(delivery_status, -1.0);
+    }
+}
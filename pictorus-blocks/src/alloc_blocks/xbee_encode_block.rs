@@ -0,0 +1,242 @@
+use alloc::vec::Vec;
+use pictorus_traits::{ByteSliceSignal, PassBy, ProcessBlock};
+
+use crate::traits::Scalar;
+use crate::xbee::{write_frame, API_AT_COMMAND, API_TRANSMIT_REQUEST};
+use crate::ParameterError;
+
+/// 16-bit network address used when only the 64-bit destination address is known
+const UNKNOWN_NETWORK_ADDRESS: [u8; 2] = [0xFF, 0xFE];
+
+/// The kind of API frame built by the XBeeEncodeBlock
+#[derive(strum::EnumString, Clone, Copy, Debug, PartialEq)]
+pub enum XBeeRequest {
+    /// Sends the input as RF data to the destination address (API frame 0x10)
+    TransmitRequest,
+    /// Issues an AT command to the local radio, with the input as its parameter (API frame 0x08)
+    AtCommand,
+}
+
+/// Parameters for the XBeeEncodeBlock
+pub struct Parameters {
+    pub request: XBeeRequest,
+    /// 64-bit destination address for transmit requests
+    pub destination: u64,
+    /// Two character AT command (e.g. `DB`) for AT command requests
+    pub at_command: [u8; 2],
+    /// Whether the radio is in API mode 2, which escapes control characters
+    pub escaped: bool,
+}
+
+impl Parameters {
+    /// `destination` is the hex 64-bit address of the remote radio, such as `0013A20040A1B2C3`,
+    /// or `000000000000FFFF` to broadcast. `at_command` is ignored for transmit requests.
+    pub fn new(request: &str, destination: &str, at_command: &str, escaped: bool) -> Self {
+        Self::try_new(request, destination, at_command, escaped)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(
+        request: &str,
+        destination: &str,
+        at_command: &str,
+        escaped: bool,
+    ) -> Result<Self, ParameterError> {
+        let request: XBeeRequest = request
+            .parse()
+            .map_err(|_| ParameterError("Failed to parse XBeeRequest"))?;
+        let destination = destination.trim_start_matches("0x");
+        let destination = u64::from_str_radix(destination, 16)
+            .map_err(|_| ParameterError("XBee destination must be a hex 64-bit address"))?;
+        let at_command = match (request, at_command.as_bytes()) {
+            (XBeeRequest::AtCommand, &[a, b]) => [a, b],
+            (XBeeRequest::AtCommand, _) => {
+                return Err(ParameterError("XBee AT command must be two characters"));
+            }
+            (XBeeRequest::TransmitRequest, _) => [0; 2],
+        };
+        Ok(Self::from_request(
+            request,
+            destination,
+            at_command,
+            escaped,
+        ))
+    }
+
+    /// Builds the parameters from an already-resolved request type and address, without parsing
+    pub const fn from_request(
+        request: XBeeRequest,
+        destination: u64,
+        at_command: [u8; 2],
+        escaped: bool,
+    ) -> Self {
+        let at_command = match request {
+            XBeeRequest::AtCommand => at_command,
+            XBeeRequest::TransmitRequest => [0; 2],
+        };
+        Self {
+            request,
+            destination,
+            at_command,
+            escaped,
+        }
+    }
+}
+
+/// Builds XBee API frames to write to an XBee radio's serial port.
+///
+/// The block takes the frame's payload and a send input. On ticks where send is truthy it
+/// outputs a complete frame: either a transmit request carrying the payload as RF data to the
+/// destination address, or a local AT command using the payload as its parameter (an empty
+/// payload queries the setting). On other ticks the output is empty.
+///
+/// Each frame gets a frame ID cycling from 1 to 255, so the radio answers every frame with a
+/// transmit status or AT command response that the XBeeDecodeBlock can report.
+pub struct XBeeEncodeBlock<S: Scalar> {
+    buffer: Vec<u8>,
+    frame_data: Vec<u8>,
+    frame_id: u8,
+    _unused: core::marker::PhantomData<S>,
+}
+
+impl<S: Scalar> Default for XBeeEncodeBlock<S> {
+    fn default() -> Self {
+        Self {
+            buffer: Vec::new(),
+            frame_data: Vec::new(),
+            frame_id: 0,
+            _unused: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<S: Scalar> ProcessBlock for XBeeEncodeBlock<S> {
+    type Parameters = Parameters;
+    type Inputs = (ByteSliceSignal, S);
+    type Output = ByteSliceSignal;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (payload, send) = inputs;
+        self.buffer.clear();
+        if !send.is_truthy() {
+            return &self.buffer;
+        }
+
+        // Frame ID 0 tells the radio not to respond, so it's skipped
+        self.frame_id = self.frame_id.checked_add(1).unwrap_or(1);
+        self.frame_data.clear();
+        match parameters.request {
+            XBeeRequest::TransmitRequest => {
+                self.frame_data
+                    .extend_from_slice(&[API_TRANSMIT_REQUEST, self.frame_id]);
+                self.frame_data
+                    .extend_from_slice(&parameters.destination.to_be_bytes());
+                self.frame_data.extend_from_slice(&UNKNOWN_NETWORK_ADDRESS);
+                // Maximum broadcast radius, default transmit options
+                self.frame_data.extend_from_slice(&[0x00, 0x00]);
+            }
+            XBeeRequest::AtCommand => {
+                self.frame_data
+                    .extend_from_slice(&[API_AT_COMMAND, self.frame_id]);
+                self.frame_data.extend_from_slice(&parameters.at_command);
+            }
+        }
+        self.frame_data.extend_from_slice(payload);
+
+        write_frame(&self.frame_data, parameters.escaped, &mut self.buffer);
+        &self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        &self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+
+    #[test]
+    fn test_xbee_encode_transmit_request() {
+        let context = StubContext::default();
+        let parameters = Parameters::new("TransmitRequest", "0013A20040A1B2C3", "", false);
+        let mut block = XBeeEncodeBlock::<bool>::default();
+
+        assert_eq!(
+            block.process(&parameters, &context, (b"hi".as_slice(), false)),
+            b""
+        );
+
+        let frame = block.process(&parameters, &context, (b"hi".as_slice(), true));
+        assert_eq!(
+            frame,
+            [
+                0x7E, 0x00, 0x10, 0x10, 0x01, 0x00, 0x13, 0xA2, 0x00, 0x40, 0xA1, 0xB2, 0xC3, 0xFF,
+                0xFE, 0x00, 0x00, b'h', b'i', 0x15
+            ]
+        );
+
+        // The frame ID advances with each frame
+        let frame = block.process(&parameters, &context, (b"hi".as_slice(), true));
+        assert_eq!(frame[4], 0x02);
+    }
+
+    #[test]
+    fn test_xbee_encode_at_command() {
+        let context = StubContext::default();
+        let parameters = Parameters::new("AtCommand", "0", "NJ", false);
+        let mut block = XBeeEncodeBlock::<f64> {
+            frame_id: 0x51,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            block.process(&parameters, &context, (b"".as_slice(), 1.0)),
+            [0x7E, 0x00, 0x04, 0x08, 0x52, 0x4E, 0x4A, 0x0D]
+        );
+        assert_eq!(
+            block.buffer(),
+            [0x7E, 0x00, 0x04, 0x08, 0x52, 0x4E, 0x4A, 0x0D]
+        );
+    }
+
+    #[test]
+    fn test_xbee_encode_frame_id_wraps() {
+        let context = StubContext::default();
+        let parameters = Parameters::new("AtCommand", "0", "DB", true);
+        let mut block = XBeeEncodeBlock::<f64> {
+            frame_id: 255,
+            ..Default::default()
+        };
+
+        let frame = block.process(&parameters, &context, (b"".as_slice(), 1.0));
+        assert_eq!(frame[4], 0x01);
+    }
+
+    #[test]
+    fn test_xbee_encode_parameters() {
+        assert_eq!(
+            Parameters::try_new("TransmitRequest", "0x000000000000FFFF", "", false)
+                .unwrap()
+                .destination,
+            0xFFFF
+        );
+        assert!(Parameters::try_new("Transmit", "0", "", false).is_err());
+        assert!(Parameters::try_new("TransmitRequest", "not hex", "", false).is_err());
+        assert!(Parameters::try_new("AtCommand", "0", "DBX", false).is_err());
+
+        // The AT command only applies to AT command requests
+        static PARAMETERS: Parameters =
+            Parameters::from_request(XBeeRequest::TransmitRequest, 0xFFFF, *b"DB", false);
+        assert_eq!(PARAMETERS.at_command, [0; 2]);
+        let parameters = Parameters::from_request(XBeeRequest::AtCommand, 0, *b"DB", false);
+        assert_eq!(parameters.at_command, *b"DB");
+    }
+}
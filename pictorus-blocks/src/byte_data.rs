@@ -1,5 +1,7 @@
 use alloc::vec::Vec;
 
+use crate::ParameterError;
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum ByteDataError {
     FindByteError,
@@ -60,17 +62,25 @@ pub enum ByteOrderSpec {
     LittleEndian,
 }
 pub fn parse_byte_data_spec<S: AsRef<str>>(data: &[S]) -> Vec<(DataType, ByteOrderSpec)> {
+    try_parse_byte_data_spec(data).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Fallible version of [`parse_byte_data_spec`], for builds where panics are unacceptable
+pub fn try_parse_byte_data_spec<S: AsRef<str>>(
+    data: &[S],
+) -> Result<Vec<(DataType, ByteOrderSpec)>, ParameterError> {
     data.iter()
         .map(|d| {
-            d.as_ref()
+            let (dt, bo) = d
+                .as_ref()
                 .split_once(':')
-                .expect("Invalid byte data format")
-        })
-        .map(|(dt, bo)| {
-            (
-                dt.parse::<DataType>().unwrap(),
-                bo.parse::<ByteOrderSpec>().unwrap(),
-            )
+                .ok_or(ParameterError("Invalid byte data format"))?;
+            Ok((
+                dt.parse::<DataType>()
+                    .map_err(|_| ParameterError("Invalid byte data type"))?,
+                bo.parse::<ByteOrderSpec>()
+                    .map_err(|_| ParameterError("Invalid byte order"))?,
+            ))
         })
         .collect()
 }
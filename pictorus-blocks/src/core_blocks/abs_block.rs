@@ -1,5 +1,7 @@
 use crate::matrix_ext::MatrixNalgebraExt;
 use num_traits::Float;
+#[cfg(feature = "alloc")]
+use pictorus_traits::DMatrix;
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock, Scalar};
 
 pub struct Parameter {}
@@ -88,6 +90,40 @@ macro_rules! impl_abs_block {
 impl_abs_block!(f32);
 impl_abs_block!(f64);
 
+#[cfg(feature = "alloc")]
+macro_rules! impl_abs_block_dmatrix {
+    ($type:ty) => {
+        impl ProcessBlock for AbsBlock<DMatrix<$type>> {
+            type Inputs = DMatrix<$type>;
+            type Output = DMatrix<$type>;
+            type Parameters = Parameter;
+
+            fn process(
+                &mut self,
+                _parameters: &Self::Parameters,
+                _context: &dyn pictorus_traits::Context,
+                input: PassBy<Self::Inputs>,
+            ) -> PassBy<'_, Self::Output> {
+                self.buffer.copy_from(input);
+                self.buffer
+                    .as_mut_slice()
+                    .iter_mut()
+                    .for_each(|x| *x = Float::abs(*x));
+                &self.buffer
+            }
+
+            fn buffer(&self) -> PassBy<'_, Self::Output> {
+                &self.buffer
+            }
+        }
+    };
+}
+
+#[cfg(feature = "alloc")]
+impl_abs_block_dmatrix!(f32);
+#[cfg(feature = "alloc")]
+impl_abs_block_dmatrix!(f64);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -181,4 +217,16 @@ mod tests {
 
     test_abs_block!(f32, f32);
     test_abs_block!(f64, f64);
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_abs_block_dmatrix() {
+        let mut block = AbsBlock::<DMatrix<f64>>::default();
+        let context = StubContext::default();
+        let input = DMatrix::from_column_slice(2, 1, &[-1.5, 2.0]).unwrap();
+
+        let output = block.process(&Parameter::new(), &context, &input);
+        assert_eq!(output.as_slice(), &[1.5, 2.0]);
+        assert_eq!(block.buffer().as_slice(), &[1.5, 2.0]);
+    }
 }
@@ -1,4 +1,5 @@
 use crate::matrix_ext::MatrixNalgebraExt;
+use crate::ParameterError;
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock, Scalar};
 
 /// Block for performing an aggregation operation (i.e. sum, min, max) on input data.
@@ -6,6 +7,15 @@ pub struct AggregateBlock<T: Apply> {
     buffer: T::Output,
 }
 
+/// Orders two values for the median. NaNs are not supported, and panic unless the `panic-free`
+/// feature is enabled, in which case they are treated as equal to everything.
+fn compare<T: PartialOrd>(a: &T, b: &T) -> core::cmp::Ordering {
+    #[cfg(not(feature = "panic-free"))]
+    return a.partial_cmp(b).expect("NaNs are not supported");
+    #[cfg(feature = "panic-free")]
+    return a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal);
+}
+
 impl<T: Apply> Default for AggregateBlock<T>
 where
     T: Pass + Default,
@@ -92,9 +102,7 @@ macro_rules! float_matrix_impl {
                         let mut data = *input;
                         let data = data.data.as_flattened_mut();
                         view.iter().enumerate().for_each(|(i, &x)| data[i] = x);
-                        data.sort_unstable_by(|a, b| {
-                            a.partial_cmp(b).expect("NaNs are not supported")
-                        });
+                        data.sort_unstable_by(|a, b| compare(a, b));
                         let mid = data.len() / 2;
                         if data.len() % 2 == 0 {
                             (data[mid - 1] + data[mid]) / Self::Output::from(2u8)
@@ -135,9 +143,27 @@ pub struct Parameters {
 }
 impl Parameters {
     pub fn new(method: &str) -> Self {
-        Self {
-            method: method.parse().expect("Invalid aggregate method"),
-        }
+        Self::try_new(method).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(method: &str) -> Result<Self, ParameterError> {
+        Ok(Self {
+            method: method
+                .parse()
+                .map_err(|_| ParameterError("Invalid aggregate method"))?,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved aggregate method, without parsing
+    pub const fn from_method(method: AggregateMethod) -> Self {
+        Self { method }
+    }
+}
+
+impl From<AggregateMethod> for Parameters {
+    fn from(method: AggregateMethod) -> Self {
+        Self::from_method(method)
     }
 }
 
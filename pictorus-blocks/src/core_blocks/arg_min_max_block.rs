@@ -1,3 +1,4 @@
+use crate::ParameterError;
 use num_traits::{FromPrimitive, Zero};
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock, Scalar};
 
@@ -16,6 +17,15 @@ pub struct ArgMinMaxBlock<T: Apply> {
     buffer: T::Output,
 }
 
+/// Orders two elements. NaNs panic unless the `panic-free` feature is enabled, in which case
+/// they are treated as equal to everything.
+fn compare<T: PartialOrd>(a: &T, b: &T) -> core::cmp::Ordering {
+    #[cfg(not(feature = "panic-free"))]
+    return a.partial_cmp(b).expect("Why did you give me a NaN!?");
+    #[cfg(feature = "panic-free")]
+    return a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal);
+}
+
 impl<T: Apply> Default for ArgMinMaxBlock<T>
 where
     T: Pass + Default,
@@ -99,7 +109,7 @@ impl<const NROWS: usize, const NCOLS: usize, T: ArgMinMaxScalar> Apply for Matri
                     .as_flattened()
                     .iter()
                     .enumerate()
-                    .min_by(|(_, a), (_, b)| a.partial_cmp(b).expect("Why did you give me a NaN!?"))
+                    .min_by(|(_, a), (_, b)| compare(a, b))
                     .expect("This iterator will never be empty")
                     .0
             }
@@ -109,7 +119,7 @@ impl<const NROWS: usize, const NCOLS: usize, T: ArgMinMaxScalar> Apply for Matri
                     .as_flattened()
                     .iter()
                     .enumerate()
-                    .max_by(|(_, a), (_, b)| a.partial_cmp(b).expect("Why did you give me a NaN!?"))
+                    .max_by(|(_, a), (_, b)| compare(a, b))
                     .expect("This iterator will never be empty")
                     .0
             }
@@ -130,9 +140,27 @@ pub struct Parameters {
 }
 impl Parameters {
     pub fn new(method: &str) -> Self {
-        Self {
-            method: method.parse().expect("Invalid min/max method"),
-        }
+        Self::try_new(method).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(method: &str) -> Result<Self, ParameterError> {
+        Ok(Self {
+            method: method
+                .parse()
+                .map_err(|_| ParameterError("Invalid min/max method"))?,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved method, without parsing
+    pub const fn from_method(method: ArgMethod) -> Self {
+        Self { method }
+    }
+}
+
+impl From<ArgMethod> for Parameters {
+    fn from(method: ArgMethod) -> Self {
+        Self::from_method(method)
     }
 }
 
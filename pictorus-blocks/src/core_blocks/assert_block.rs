@@ -0,0 +1,374 @@
+use crate::traits::Float;
+use crate::ParameterError;
+use core::time::Duration;
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+/// Whether assertions are evaluated in this build. Checks only run in tests or when the
+/// `sim` feature is enabled, so the block compiles down to a no-op on deployed targets.
+const ENABLED: bool = cfg!(any(test, feature = "sim"));
+
+/// The maximum number of violations recorded by a single [`AssertBlock`].
+/// Violations past this limit are still counted, but their details are discarded.
+pub const MAX_RECORDED_VIOLATIONS: usize = 16;
+
+/// The condition checked by the assert block
+#[derive(Clone, Copy, Debug, PartialEq, strum::EnumString)]
+pub enum AssertCondition {
+    /// Every element must be within `[lower, upper]`. NaN values are treated as out of range.
+    Range,
+    /// The absolute rate of change of every element (units per second) must not exceed `max_rate`.
+    RateOfChange,
+    /// Every element must be finite (not NaN or Inf)
+    Finite,
+}
+
+/// Parameters for the assert block
+pub struct Parameters<F: Float> {
+    /// The condition to check each tick
+    pub condition: AssertCondition,
+    /// Lower bound for the `Range` condition
+    pub lower: F,
+    /// Upper bound for the `Range` condition
+    pub upper: F,
+    /// Maximum absolute rate of change per second for the `RateOfChange` condition
+    pub max_rate: F,
+    /// Whether to halt the simulation (by panicking) on the first violation
+    pub halt_on_violation: bool,
+}
+
+impl<F: Float> Parameters<F> {
+    pub fn new(condition: &str, lower: F, upper: F, max_rate: F, halt_on_violation: bool) -> Self {
+        Self::try_new(condition, lower, upper, max_rate, halt_on_violation)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(
+        condition: &str,
+        lower: F,
+        upper: F,
+        max_rate: F,
+        halt_on_violation: bool,
+    ) -> Result<Self, ParameterError> {
+        Ok(Self {
+            condition: condition
+                .parse()
+                .map_err(|_| ParameterError("Failed to parse assert condition."))?,
+            lower,
+            upper,
+            max_rate,
+            halt_on_violation,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved condition, without parsing
+    pub const fn from_condition(
+        condition: AssertCondition,
+        lower: F,
+        upper: F,
+        max_rate: F,
+        halt_on_violation: bool,
+    ) -> Self {
+        Self {
+            condition,
+            lower,
+            upper,
+            max_rate,
+            halt_on_violation,
+        }
+    }
+}
+
+/// A single recorded assertion violation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Violation<F> {
+    /// The application time at which the violation occurred
+    pub time: Duration,
+    /// The first offending element value. For `RateOfChange` this is the computed rate.
+    pub value: F,
+}
+
+/// Checks a condition on its input each tick and records any violations along with the time
+/// they occurred. Outputs `true` on ticks where the condition was violated.
+///
+/// Supported conditions:
+/// - Range
+/// - RateOfChange
+/// - Finite
+///
+/// This block is only active in sim and test builds (the `sim` feature). In all other builds
+/// it never checks its input and always outputs `false`.
+pub struct AssertBlock<T: AssertInput> {
+    buffer: bool,
+    previous: Option<T>,
+    violations: heapless::Vec<Violation<T::Float>, MAX_RECORDED_VIOLATIONS>,
+    violation_count: usize,
+}
+
+impl<T: AssertInput> Default for AssertBlock<T> {
+    fn default() -> Self {
+        Self {
+            buffer: false,
+            previous: None,
+            violations: heapless::Vec::new(),
+            violation_count: 0,
+        }
+    }
+}
+
+impl<T: AssertInput> AssertBlock<T> {
+    /// The first [`MAX_RECORDED_VIOLATIONS`] violations, in the order they occurred
+    pub fn violations(&self) -> &[Violation<T::Float>] {
+        &self.violations
+    }
+
+    /// The total number of ticks on which the condition was violated
+    pub fn violation_count(&self) -> usize {
+        self.violation_count
+    }
+
+    fn check(
+        &self,
+        parameters: &Parameters<T::Float>,
+        context: &dyn pictorus_traits::Context,
+        input: &T,
+    ) -> Option<T::Float> {
+        let mut offending = None;
+        match parameters.condition {
+            AssertCondition::Range => input.for_each(|_, value| {
+                if offending.is_none() && !(parameters.lower..=parameters.upper).contains(&value) {
+                    offending = Some(value);
+                }
+            }),
+            AssertCondition::Finite => input.for_each(|_, value| {
+                if offending.is_none() && !num_traits::Float::is_finite(value) {
+                    offending = Some(value);
+                }
+            }),
+            AssertCondition::RateOfChange => {
+                let (Some(previous), Some(timestep)) = (&self.previous, context.timestep()) else {
+                    return None;
+                };
+                let timestep_s = T::Float::from_duration(timestep);
+                if timestep_s <= <T::Float as num_traits::Zero>::zero() {
+                    return None;
+                }
+                input.for_each(|index, value| {
+                    let rate = (value - previous.get(index)) / timestep_s;
+                    let exceeded = num_traits::Float::abs(rate) > parameters.max_rate
+                        || num_traits::Float::is_nan(rate);
+                    if offending.is_none() && exceeded {
+                        offending = Some(rate);
+                    }
+                });
+            }
+        }
+        offending
+    }
+}
+
+impl<T: AssertInput> ProcessBlock for AssertBlock<T> {
+    type Inputs = T;
+    type Output = bool;
+    type Parameters = Parameters<T::Float>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        if !ENABLED {
+            return self.buffer;
+        }
+
+        let input = T::to_owned(input);
+        let offending = self.check(parameters, context, &input);
+        self.previous = Some(input);
+
+        let violated = offending.is_some();
+        if let Some(value) = offending {
+            let time = context.time();
+            if !self.buffer {
+                log::warn!(
+                    "Assertion failed at t={:?}: {:?} check violated with value {:?}",
+                    time,
+                    parameters.condition,
+                    value
+                );
+            }
+            if parameters.halt_on_violation {
+                panic!(
+                    "Assertion failed at t={:?}: {:?} check violated with value {:?}",
+                    time, parameters.condition, value
+                );
+            }
+            self.violation_count += 1;
+            // Only the earliest violations are kept once the record is full
+            let _ = self.violations.push(Violation { time, value });
+        }
+
+        self.buffer = violated;
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+/// Input types that can be checked by the [`AssertBlock`]
+pub trait AssertInput: Pass + Copy {
+    type Float: Float;
+
+    fn to_owned(input: PassBy<Self>) -> Self;
+
+    /// Calls `f` with the flat index and value of each element
+    fn for_each(&self, f: impl FnMut(usize, Self::Float));
+
+    fn get(&self, index: usize) -> Self::Float;
+}
+
+impl<F: Float> AssertInput for F {
+    type Float = F;
+
+    fn to_owned(input: PassBy<Self>) -> Self {
+        input
+    }
+
+    fn for_each(&self, mut f: impl FnMut(usize, Self::Float)) {
+        f(0, *self);
+    }
+
+    fn get(&self, _index: usize) -> Self::Float {
+        *self
+    }
+}
+
+impl<const NROWS: usize, const NCOLS: usize, F: Float> AssertInput for Matrix<NROWS, NCOLS, F> {
+    type Float = F;
+
+    fn to_owned(input: PassBy<Self>) -> Self {
+        *input
+    }
+
+    fn for_each(&self, mut f: impl FnMut(usize, Self::Float)) {
+        self.data
+            .iter()
+            .flatten()
+            .enumerate()
+            .for_each(|(index, value)| f(index, *value));
+    }
+
+    fn get(&self, index: usize) -> Self::Float {
+        self.data[index / NROWS][index % NROWS]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+
+    #[test]
+    fn test_assert_range_scalar() {
+        let mut runtime = StubRuntime::default();
+        let params = Parameters::new("Range", -1.0, 1.0, 0.0, false);
+        let mut block = AssertBlock::<f64>::default();
+
+        assert!(!block.process(&params, &runtime.context(), 0.5));
+        runtime.tick();
+        assert!(block.process(&params, &runtime.context(), 1.5));
+        assert!(block.buffer());
+        runtime.tick();
+        assert!(block.process(&params, &runtime.context(), f64::NAN));
+        runtime.tick();
+        assert!(!block.process(&params, &runtime.context(), -1.0));
+
+        assert_eq!(block.violation_count(), 2);
+        assert_eq!(
+            block.violations()[0],
+            Violation {
+                time: Duration::from_millis(100),
+                value: 1.5
+            }
+        );
+        assert_eq!(block.violations()[1].time, Duration::from_millis(200));
+        assert!(block.violations()[1].value.is_nan());
+    }
+
+    #[test]
+    fn test_assert_finite_matrix() {
+        let runtime = StubRuntime::default();
+        let params = Parameters::new("Finite", 0.0, 0.0, 0.0, false);
+        let mut block = AssertBlock::<Matrix<2, 2, f32>>::default();
+
+        let input = Matrix {
+            data: [[1.0, 2.0], [3.0, 4.0]],
+        };
+        assert!(!block.process(&params, &runtime.context(), &input));
+
+        let input = Matrix {
+            data: [[1.0, 2.0], [f32::INFINITY, 4.0]],
+        };
+        assert!(block.process(&params, &runtime.context(), &input));
+        assert_eq!(block.violations()[0].value, f32::INFINITY);
+    }
+
+    #[test]
+    fn test_assert_rate_of_change() {
+        let mut runtime = StubRuntime::default();
+        let params = Parameters::new("RateOfChange", 0.0, 0.0, 10.0, false);
+        let mut block = AssertBlock::<Matrix<1, 2, f64>>::default();
+
+        // No previous value on the first tick, so a large value can't violate the rate
+        let input = Matrix {
+            data: [[100.0], [0.0]],
+        };
+        assert!(!block.process(&params, &runtime.context(), &input));
+
+        // 0.5 units in 0.1s = 5 units/s
+        runtime.tick();
+        let input = Matrix {
+            data: [[100.5], [0.0]],
+        };
+        assert!(!block.process(&params, &runtime.context(), &input));
+
+        // -2 units in 0.1s = -20 units/s
+        runtime.tick();
+        let input = Matrix {
+            data: [[100.5], [-2.0]],
+        };
+        assert!(block.process(&params, &runtime.context(), &input));
+        assert_eq!(block.violation_count(), 1);
+        assert_eq!(block.violations()[0].time, Duration::from_millis(200));
+        assert!((block.violations()[0].value + 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_assert_records_limited_violations() {
+        let mut runtime = StubRuntime::default();
+        let params = Parameters::new("Range", 0.0, 1.0, 0.0, false);
+        let mut block = AssertBlock::<f64>::default();
+
+        for _ in 0..MAX_RECORDED_VIOLATIONS + 5 {
+            runtime.tick();
+            block.process(&params, &runtime.context(), 2.0);
+        }
+        assert_eq!(block.violation_count(), MAX_RECORDED_VIOLATIONS + 5);
+        assert_eq!(block.violations().len(), MAX_RECORDED_VIOLATIONS);
+        assert_eq!(block.violations()[0].time, Duration::from_millis(100));
+    }
+
+    #[test]
+    #[should_panic(expected = "Assertion failed at t=100ms: Range check violated with value 2.0")]
+    fn test_assert_halt_on_violation() {
+        let mut runtime = StubRuntime::default();
+        let params = Parameters::new("Range", 0.0, 1.0, 0.0, true);
+        let mut block = AssertBlock::<f64>::default();
+
+        block.process(&params, &runtime.context(), 0.5);
+        runtime.tick();
+        block.process(&params, &runtime.context(), 2.0);
+    }
+}
@@ -0,0 +1,228 @@
+use crate::dsp::DspFloat;
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+/// Parameters for the band energy block
+pub struct Parameters<T: DspFloat, const BANDS: usize> {
+    /// Lower edge of each band, in Hz
+    pub lower: [T; BANDS],
+    /// Upper edge of each band, in Hz
+    pub upper: [T; BANDS],
+}
+
+impl<T: DspFloat, const BANDS: usize> Parameters<T, BANDS> {
+    pub fn new(lower: [T; BANDS], upper: [T; BANDS]) -> Self {
+        Self { lower, upper }
+    }
+}
+
+/// Computes the RMS energy of its input within each of `BANDS` frequency bands.
+///
+/// Samples are taken in blocks of `N`. Every DFT bin whose frequency (`k / (N *
+/// fundamental_timestep)`) lies within a band has its own Goertzel filter, which each sample
+/// updates as it arrives, and at the end of each block the band output is the RMS of the signal
+/// content in those bins. A sinusoid of amplitude `A` that falls on a bin inside a band
+/// therefore reads as `A / sqrt(2)`, and a constant offset `C` reads as `|C|` in a band that
+/// includes 0 Hz. Bands that contain no bins output zero.
+///
+/// This avoids a full FFT, and since the work is spread over the block, each tick costs about
+/// the same, scaling with the number of bins covered by the bands. The bins are picked at the
+/// start of each block. The output holds its previous value between blocks.
+pub struct BandEnergyBlock<T: DspFloat, const N: usize, const BANDS: usize> {
+    /// Filter state for each DFT bin, of which the first `N / 2 + 1` are used
+    bins: [Bin<T>; N],
+    sample_index: usize,
+    output: Matrix<1, BANDS, T>,
+}
+
+impl<T: DspFloat, const N: usize, const BANDS: usize> Default for BandEnergyBlock<T, N, BANDS> {
+    fn default() -> Self {
+        Self {
+            bins: core::array::from_fn(|k| Bin::new(k, N)),
+            sample_index: 0,
+            output: Matrix::zeroed(),
+        }
+    }
+}
+
+fn cast<T: DspFloat>(value: usize) -> T {
+    <T as num_traits::NumCast>::from(value).unwrap_or_default()
+}
+
+/// Goertzel filter for one DFT bin
+#[derive(Default)]
+struct Bin<T> {
+    cos: T,
+    sin: T,
+    s1: T,
+    s2: T,
+    /// Whether the bin lies within a band this block
+    active: bool,
+}
+
+impl<T: DspFloat> Bin<T> {
+    /// Filter for bin `k` of a DFT of `len` samples
+    fn new(k: usize, len: usize) -> Self {
+        let omega = T::TAU * cast::<T>(k) / cast::<T>(len);
+        let (sin, cos) = num_traits::Float::sin_cos(omega);
+        Self {
+            cos,
+            sin,
+            ..Default::default()
+        }
+    }
+
+    fn update(&mut self, sample: T) {
+        let s0 = sample + (self.cos + self.cos) * self.s1 - self.s2;
+        self.s2 = self.s1;
+        self.s1 = s0;
+    }
+
+    /// Squared magnitude of the bin, over the samples since the filter was reset
+    fn power(&self) -> T {
+        let re = self.s1 - self.s2 * self.cos;
+        let im = self.s2 * self.sin;
+        re * re + im * im
+    }
+}
+
+impl<T: DspFloat, const N: usize, const BANDS: usize> ProcessBlock
+    for BandEnergyBlock<T, N, BANDS>
+{
+    type Inputs = T;
+    type Output = Matrix<1, BANDS, T>;
+    type Parameters = Parameters<T, BANDS>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let n = cast::<T>(N);
+        let bin_width =
+            <T as num_traits::One>::one() / (n * T::from_duration(context.fundamental_timestep()));
+        let bands = || parameters.lower.iter().zip(&parameters.upper);
+        // Bins above N / 2 mirror the ones below
+        let bins = self.bins.iter_mut().take(N / 2 + 1).enumerate();
+
+        if self.sample_index == 0 {
+            for (k, bin) in bins {
+                let frequency = cast::<T>(k) * bin_width;
+                bin.active =
+                    bands().any(|(lower, upper)| frequency >= *lower && frequency <= *upper);
+                bin.s1 = T::default();
+                bin.s2 = T::default();
+            }
+        }
+        self.bins
+            .iter_mut()
+            .filter(|bin| bin.active)
+            .for_each(|bin| bin.update(input));
+        self.sample_index += 1;
+        if self.sample_index < N {
+            return self.output.as_by();
+        }
+        self.sample_index = 0;
+
+        let one = <T as num_traits::One>::one();
+        for (output, (lower, upper)) in self.output.data.iter_mut().zip(bands()) {
+            let mut mean_square = T::default();
+            for (k, bin) in self.bins.iter().take(N / 2 + 1).enumerate() {
+                let frequency = cast::<T>(k) * bin_width;
+                if frequency < *lower || frequency > *upper {
+                    continue;
+                }
+                // DC and Nyquist have no negative frequency mirror, every other bin holds half
+                // of its sinusoid's power
+                let fold = if k == 0 || 2 * k == N { one } else { one + one };
+                mean_square += fold * bin.power() / (n * n);
+            }
+            output[0] = num_traits::Float::sqrt(mean_square);
+        }
+
+        self.output.as_by()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.output.as_by()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+    use approx::assert_relative_eq;
+    use core::time::Duration;
+
+    #[test]
+    fn test_goertzel_matches_dft() {
+        let power = |k| {
+            let mut bin = Bin::<f64>::new(k, 4);
+            [1.0, 2.0, 0.0, -1.0]
+                .into_iter()
+                .for_each(|sample| bin.update(sample));
+            bin.power()
+        };
+        // From the DFT: X = [2, 1-3j, 0, 1+3j]
+        assert_relative_eq!(power(0), 4.0, epsilon = 1e-12);
+        assert_relative_eq!(power(1), 10.0, epsilon = 1e-12);
+        assert_relative_eq!(power(2), 0.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_band_energy_block() {
+        let mut runtime = StubRuntime::default();
+        runtime.context.fundamental_timestep = Duration::from_millis(10);
+
+        // 100 samples at 100Hz gives 1Hz bins
+        let params = Parameters::new([0.0, 4.0, 15.0, 30.0], [1.0, 6.0, 25.0, 50.0]);
+        let mut block = BandEnergyBlock::<f64, 100, 4>::default();
+
+        let signal = |t: f64| {
+            0.5 + 2.0 * (5.0 * core::f64::consts::TAU * t).sin()
+                + (20.0 * core::f64::consts::TAU * t).cos()
+        };
+        for i in 0..99 {
+            block.process(&params, &runtime.context(), signal(i as f64 * 0.01));
+            runtime.tick();
+        }
+        assert_eq!(block.buffer(), &Matrix::zeroed());
+
+        let output = block.process(&params, &runtime.context(), signal(0.99));
+        assert_relative_eq!(output.data[0][0], 0.5, epsilon = 1e-9);
+        assert_relative_eq!(output.data[1][0], 2.0 / 2.0f64.sqrt(), epsilon = 1e-9);
+        assert_relative_eq!(output.data[2][0], 1.0 / 2.0f64.sqrt(), epsilon = 1e-9);
+        assert_relative_eq!(output.data[3][0], 0.0, epsilon = 1e-9);
+        // Only the 37 bins inside a band are filtered
+        assert_eq!(block.bins.iter().filter(|bin| bin.active).count(), 37);
+
+        // The filters restart with each block
+        runtime.tick();
+        for i in 100..200 {
+            block.process(&params, &runtime.context(), 2.0 * signal(i as f64 * 0.01));
+            runtime.tick();
+        }
+        assert_relative_eq!(
+            block.buffer().data[1][0],
+            2.0 * 2.0f64.sqrt(),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn test_band_energy_nyquist_and_empty_band() {
+        let runtime = StubRuntime::default();
+        let params = Parameters::new([0.0, 0.1], [f32::MAX, 0.2]);
+        let mut block = BandEnergyBlock::<f32, 4, 2>::default();
+
+        // Alternating +/-3 is a Nyquist tone, whose RMS is its amplitude
+        let mut output = Matrix::zeroed();
+        for value in [3.0, -3.0, 3.0, -3.0] {
+            output = *block.process(&params, &runtime.context(), value);
+        }
+        assert_relative_eq!(output.data[0][0], 3.0, epsilon = 1e-5);
+        // With a 100ms timestep the bins are 2.5Hz apart, so the second band has none
+        assert_eq!(output.data[1][0], 0.0);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::ParameterError;
 use num_traits::NumCast;
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
 
@@ -37,10 +38,24 @@ pub struct Parameters {
 
 impl Parameters {
     pub fn new(direction: &str, bits: impl NumCast) -> Self {
-        Self {
-            direction: direction.parse().expect("Failed to parse direction"),
-            bits: bits.to_u8().expect("Failed to cast bits to u8"),
-        }
+        Self::try_new(direction, bits).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(direction: &str, bits: impl NumCast) -> Result<Self, ParameterError> {
+        Ok(Self {
+            direction: direction
+                .parse()
+                .map_err(|_| ParameterError("Failed to parse direction"))?,
+            bits: bits
+                .to_u8()
+                .ok_or(ParameterError("Failed to cast bits to u8"))?,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved direction, without parsing
+    pub const fn from_direction(direction: ShiftDirection, bits: u8) -> Self {
+        Self { direction, bits }
     }
 }
 
@@ -1,4 +1,5 @@
 use crate::traits::{CopyInto, Scalar, SizePromotion};
+use crate::ParameterError;
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
 
 /// Performs a bitwise operation on the input values.
@@ -57,7 +58,14 @@ pub enum Operation {
 
 impl Operation {
     pub fn new(value: &str) -> Self {
-        value.parse().expect("Failed to parse operation")
+        Self::try_new(value).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Operation::new`], for builds where panics are unacceptable
+    pub fn try_new(value: &str) -> Result<Self, ParameterError> {
+        value
+            .parse()
+            .map_err(|_| ParameterError("Failed to parse operation"))
     }
 }
 
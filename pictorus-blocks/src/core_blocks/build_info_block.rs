@@ -0,0 +1,126 @@
+use core::fmt;
+
+use pictorus_traits::{ByteSliceSignal, GeneratorBlock, PassBy};
+
+use crate::traits::Float;
+use crate::ParameterError;
+
+/// Parameters for the BuildInfoBlock, baked in by the code generator when the model is built
+#[derive(Debug, Clone)]
+pub struct Parameters {
+    /// Leading 32 bits of the model's content hash
+    pub model_hash: u32,
+    pub version: &'static str,
+    /// Seconds since the Unix epoch
+    pub build_timestamp: f64,
+}
+
+impl Parameters {
+    /// `model_hash` is the model's content hash in hex, of which only the first 8 digits are
+    /// kept so the hash can be output exactly as a float
+    pub fn new(model_hash: &str, version: &'static str, build_timestamp: f64) -> Self {
+        Self::try_new(model_hash, version, build_timestamp).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(
+        model_hash: &str,
+        version: &'static str,
+        build_timestamp: f64,
+    ) -> Result<Self, ParameterError> {
+        let model_hash = model_hash.strip_prefix("0x").unwrap_or(model_hash);
+        let model_hash = model_hash.get(..8).unwrap_or(model_hash);
+        let model_hash = u32::from_str_radix(model_hash, 16)
+            .map_err(|_| ParameterError("Model hash must be hexadecimal"))?;
+        Ok(Self {
+            model_hash,
+            version,
+            build_timestamp,
+        })
+    }
+}
+
+/// Describes the build on one line, for logs and crash reports
+impl fmt::Display for Parameters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (model {:08x}, built at {})",
+            self.version, self.model_hash, self.build_timestamp
+        )
+    }
+}
+
+/// Outputs which build of the model is running, so telemetry and logs can be tagged with it.
+///
+/// Outputs the model hash, the version string as bytes and the build timestamp in seconds since
+/// the Unix epoch.
+pub struct BuildInfoBlock<F: Float> {
+    model_hash: F,
+    version: &'static [u8],
+    build_timestamp: F,
+}
+
+impl<F: Float> Default for BuildInfoBlock<F> {
+    fn default() -> Self {
+        Self {
+            model_hash: F::zero(),
+            version: b"",
+            build_timestamp: F::zero(),
+        }
+    }
+}
+
+impl<F: Float> GeneratorBlock for BuildInfoBlock<F> {
+    type Parameters = Parameters;
+    type Output = (F, ByteSliceSignal, F);
+
+    fn generate(
+        &mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+    ) -> PassBy<'_, Self::Output> {
+        self.model_hash = F::from(parameters.model_hash).unwrap();
+        self.version = parameters.version.as_bytes();
+        self.build_timestamp = F::from(parameters.build_timestamp).unwrap();
+        self.buffer()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        (self.model_hash, self.version, self.build_timestamp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_build_info_default_buffer_no_panic() {
+        let block = BuildInfoBlock::<f64>::default();
+        assert_eq!(block.buffer(), (0.0, b"".as_slice(), 0.0));
+    }
+
+    #[test]
+    fn test_build_info_block() {
+        let context = StubContext::default();
+        let parameters =
+            Parameters::new("9f86d081884c7d659a2feaa0c55ad015", "1.4.0", 1_767_225_600.0);
+        let mut block = BuildInfoBlock::<f64>::default();
+
+        let expected = (2_676_412_545.0, b"1.4.0".as_slice(), 1_767_225_600.0);
+        assert_eq!(block.generate(&parameters, &context), expected);
+        assert_eq!(block.buffer(), expected);
+    }
+
+    #[test]
+    fn test_build_info_parameters() {
+        let parameters = Parameters::new("0x00ab", "dev", 0.0);
+        assert_eq!(parameters.model_hash, 0xab);
+        assert_eq!(parameters.to_string(), "dev (model 000000ab, built at 0)");
+        assert!(Parameters::try_new("not a hash", "dev", 0.0).is_err());
+        assert!(Parameters::try_new("", "dev", 0.0).is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use pictorus_traits::{OutputBlock, Pass, PassBy};
+
+use crate::signal_bus::{Plain, Topic};
+use crate::traits::CopyInto;
+
+/// Parameters for the BusPublishBlock
+pub struct Parameters<T: Plain> {
+    pub topic: &'static Topic<T>,
+}
+
+impl<T: Plain> Parameters<T> {
+    pub fn new(topic: &'static Topic<T>) -> Self {
+        Self { topic }
+    }
+}
+
+/// Publishes its input to a signal bus topic, so models in the same app can read it with a
+/// BusSubscribeBlock without an explicit connection.
+///
+/// Each topic must have exactly one BusPublishBlock.
+pub struct BusPublishBlock<T: Pass + Plain + Default> {
+    sample: T,
+}
+
+impl<T: Pass + Plain + Default> Default for BusPublishBlock<T> {
+    fn default() -> Self {
+        Self {
+            sample: T::default(),
+        }
+    }
+}
+
+impl<T: Pass + Plain + Default + CopyInto<T>> OutputBlock for BusPublishBlock<T> {
+    type Inputs = T;
+    type Parameters = Parameters<T>;
+
+    fn output(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) {
+        T::copy_into(inputs, &mut self.sample);
+        parameters.topic.publish(self.sample, context.time());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use core::time::Duration;
+    use pictorus_traits::Matrix;
+
+    #[test]
+    fn test_bus_publish_scalar() {
+        static TOPIC: Topic<f64> = Topic::new("throttle", 0.0);
+        let parameters = Parameters::new(&TOPIC);
+        let context = StubContext::new(Duration::from_millis(30), None, Duration::from_millis(10));
+        let mut block = BusPublishBlock::<f64>::default();
+
+        block.output(&parameters, &context, 0.75);
+        assert_eq!(TOPIC.read(), Some((0.75, Some(Duration::from_millis(30)))));
+    }
+
+    #[test]
+    fn test_bus_publish_matrix() {
+        static TOPIC: Topic<Matrix<2, 1, f32>> =
+            Topic::new("setpoint", Matrix { data: [[0.0; 2]] });
+        let parameters = Parameters::new(&TOPIC);
+        let context = StubContext::default();
+        let mut block = BusPublishBlock::<Matrix<2, 1, f32>>::default();
+
+        let input = Matrix {
+            data: [[1.0, -1.0]],
+        };
+        block.output(&parameters, &context, &input);
+        assert_eq!(TOPIC.read().unwrap().0, input);
+    }
+}
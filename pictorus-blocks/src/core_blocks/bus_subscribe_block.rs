@@ -0,0 +1,131 @@
+use core::time::Duration;
+
+use pictorus_traits::{GeneratorBlock, Pass, PassBy};
+
+use crate::signal_bus::{Plain, Topic};
+use crate::stale_tracker::{duration_from_ms_f64, StaleTracker};
+
+/// Parameters for the BusSubscribeBlock
+pub struct Parameters<T: Plain> {
+    pub topic: &'static Topic<T>,
+    /// How long after being published a sample is still considered fresh
+    pub stale_age: Duration,
+}
+
+impl<T: Plain> Parameters<T> {
+    pub fn new(topic: &'static Topic<T>, stale_age_ms: f64) -> Self {
+        Self {
+            topic,
+            stale_age: duration_from_ms_f64(stale_age_ms),
+        }
+    }
+}
+
+/// Reads the latest sample published to a signal bus topic by a BusPublishBlock, which may be
+/// in another model of the same app.
+///
+/// Outputs the sample and whether it's fresh, i.e. it was published no more than the stale age
+/// ago. Until the topic is first published, the output is the topic's initial value and isn't
+/// fresh.
+pub struct BusSubscribeBlock<T: Pass + Plain + Default> {
+    value: T,
+    tracker: StaleTracker,
+    fresh: bool,
+}
+
+impl<T: Pass + Plain + Default> Default for BusSubscribeBlock<T> {
+    fn default() -> Self {
+        Self {
+            value: T::default(),
+            tracker: StaleTracker::default(),
+            fresh: false,
+        }
+    }
+}
+
+impl<T: Pass + Plain + Default> GeneratorBlock for BusSubscribeBlock<T> {
+    type Parameters = Parameters<T>;
+    type Output = (T, bool);
+
+    fn generate(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn pictorus_traits::Context,
+    ) -> PassBy<'_, Self::Output> {
+        // If the read keeps colliding with the publisher, the previous sample is held
+        if let Some((value, published_at)) = parameters.topic.read() {
+            self.value = value;
+            if let Some(published_at) = published_at {
+                self.tracker.mark_updated(published_at);
+            }
+        }
+        self.fresh = self.tracker.is_valid(context.time(), parameters.stale_age);
+        self.buffer()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        (self.value.as_by(), self.fresh)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use pictorus_traits::Matrix;
+
+    fn context_at(time_ms: u64) -> StubContext {
+        StubContext::new(
+            Duration::from_millis(time_ms),
+            None,
+            Duration::from_millis(10),
+        )
+    }
+
+    #[test]
+    fn test_bus_subscribe_default_buffer_no_panic() {
+        let block = BusSubscribeBlock::<Matrix<2, 2, f64>>::default();
+        assert_eq!(block.buffer(), (&Matrix::zeroed(), false));
+    }
+
+    #[test]
+    fn test_bus_subscribe_freshness() {
+        static TOPIC: Topic<f64> = Topic::new("altitude", -1.0);
+        let parameters = Parameters::new(&TOPIC, 50.0);
+        let mut block = BusSubscribeBlock::<f64>::default();
+
+        assert_eq!(block.generate(&parameters, &context_at(0)), (-1.0, false));
+
+        TOPIC.publish(12.5, Duration::from_millis(10));
+        assert_eq!(block.generate(&parameters, &context_at(10)), (12.5, true));
+        assert_eq!(block.generate(&parameters, &context_at(60)), (12.5, true));
+        assert_eq!(block.generate(&parameters, &context_at(70)), (12.5, false));
+        assert_eq!(block.buffer(), (12.5, false));
+
+        TOPIC.publish(13.0, Duration::from_millis(80));
+        assert_eq!(block.generate(&parameters, &context_at(80)), (13.0, true));
+    }
+
+    #[test]
+    fn test_bus_between_models() {
+        use crate::core_blocks::bus_publish_block::{
+            BusPublishBlock, Parameters as PublishParameters,
+        };
+        use pictorus_traits::OutputBlock;
+
+        static TOPIC: Topic<Matrix<1, 3, f64>> =
+            Topic::new("attitude", Matrix { data: [[0.0]; 3] });
+        let mut publisher = BusPublishBlock::<Matrix<1, 3, f64>>::default();
+        let mut subscriber = BusSubscribeBlock::<Matrix<1, 3, f64>>::default();
+        let publish_parameters = PublishParameters::new(&TOPIC);
+        let subscribe_parameters = Parameters::new(&TOPIC, 100.0);
+
+        let attitude = Matrix {
+            data: [[0.1], [0.2], [0.3]],
+        };
+        publisher.output(&publish_parameters, &context_at(20), &attitude);
+        let (value, fresh) = subscriber.generate(&subscribe_parameters, &context_at(40));
+        assert_eq!(value, &attitude);
+        assert!(fresh);
+    }
+}
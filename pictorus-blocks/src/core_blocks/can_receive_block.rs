@@ -51,6 +51,12 @@ impl<const N: usize, S: Float, C, O: Pass + Default + ToTupleOutput<S>> Default
     for CanReceiveBlock<N, S, C, O>
 {
     fn default() -> Self {
+        // Fail the build rather than at runtime in panic-free builds
+        #[cfg(feature = "panic-free")]
+        const {
+            panic!("CanReceiveBlock must be initialized using the ::new method");
+        }
+        #[cfg(not(feature = "panic-free"))]
         panic!("CanReceiveBlock must be initialized using the ::new method");
     }
 }
@@ -98,8 +104,14 @@ where
         let valid = self
             .stale_check
             .is_valid(context.time(), parameters.stale_age);
-        self.output_buffer = O::to_tuple(&self.cache, valid)
-            .expect("parameters.signal_count is shorter than output tuple type");
+        match O::to_tuple(&self.cache, valid) {
+            Ok(output) => self.output_buffer = output,
+            // With `panic-free`, a mismatched signal count leaves the previous output in place
+            #[cfg(feature = "panic-free")]
+            Err(()) => {}
+            #[cfg(not(feature = "panic-free"))]
+            Err(()) => panic!("parameters.signal_count is shorter than output tuple type"),
+        }
         self.output_buffer.as_by()
     }
 
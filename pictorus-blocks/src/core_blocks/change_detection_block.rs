@@ -1,4 +1,5 @@
 use crate::traits::Scalar;
+use crate::ParameterError;
 use pictorus_traits::{HasIc, Matrix, Pass, PassBy, ProcessBlock};
 use strum::EnumString;
 
@@ -14,11 +15,18 @@ use strum::EnumString;
 ///  - Falling: Only triggers if the input value got smaller
 ///  - Any: Triggers if the input value changed at all
 ///
-/// The first execution of this block has no value to compare with
-/// so it will always emit `false`
+/// With a tolerance, only moves of more than the tolerance count as changes. Each value is
+/// compared against the input at the last such move rather than on the previous tick, so a
+/// signal drifting slowly by less than the tolerance per tick still triggers once it has moved
+/// further than the tolerance. Integer and boolean inputs are compared through `f64`, so a
+/// tolerance below 1 makes any change count. NaNs compare equal to each other here, so a signal
+/// that stays NaN doesn't trigger on every tick.
+///
+/// The first execution of this block compares the input with the initial condition
 pub struct ChangeDetectionBlock<T: Apply<O>, O: Scalar = f64> {
     buffer: T::Output,
-    last_input: Option<T>,
+    /// The input at the last move beyond the tolerance
+    reference: Option<T>,
 }
 
 impl<T, O: Scalar> Default for ChangeDetectionBlock<T, O>
@@ -42,7 +50,7 @@ where
     fn new(parameters: &Self::Parameters) -> Self {
         ChangeDetectionBlock::<T, O> {
             buffer: T::Output::default(),
-            last_input: Some(parameters.ic),
+            reference: Some(parameters.ic),
         }
     }
 }
@@ -61,8 +69,7 @@ where
         _context: &dyn pictorus_traits::Context,
         inputs: PassBy<'_, Self::Inputs>,
     ) -> PassBy<'b, Self::Output> {
-        let output = T::apply(&mut self.buffer, inputs, parameters, &mut self.last_input);
-        output
+        T::apply(&mut self.buffer, inputs, parameters, &mut self.reference)
     }
 
     fn buffer(&self) -> PassBy<'_, Self::Output> {
@@ -77,7 +84,7 @@ pub trait Apply<O>: Pass + Sized + Copy {
         store: &'s mut Self::Output,
         input: PassBy<Self>,
         params: &Parameters<Self>,
-        last_input: &mut Option<Self>,
+        reference: &mut Option<Self>,
     ) -> PassBy<'s, Self::Output>;
 }
 
@@ -88,13 +95,10 @@ impl<C: ChangeDetect, O: Scalar> Apply<O> for C {
         store: &'s mut Self::Output,
         input: PassBy<Self>,
         params: &Parameters<Self>,
-        last_input: &mut Option<Self>,
+        reference: &mut Option<Self>,
     ) -> PassBy<'s, Self::Output> {
-        // Store a copy of input in `last_input` while grabbing what was there as `old_last_input`
-        let old_last_input = last_input.replace(input);
-        let old_last_input = old_last_input.unwrap_or(params.ic);
-        let res = Self::change_detect(input, old_last_input, params.change_mode);
-        *store = O::from_bool(res);
+        let reference = reference.get_or_insert(params.ic);
+        *store = O::from_bool(Self::change_detect(input, reference, params));
         store.as_by()
     }
 }
@@ -108,20 +112,15 @@ impl<const NROWS: usize, const NCOLS: usize, C: ChangeDetect, O: Scalar> Apply<O
         store: &'s mut Self::Output,
         input: PassBy<Self>,
         params: &Parameters<Self>,
-        last_input: &mut Option<Self>,
+        reference: &mut Option<Self>,
     ) -> PassBy<'s, Self::Output> {
-        // Store a copy of input in `last_input` while grabbing what was there as `old_last_input`
-        let old_last_input = last_input.replace(*input);
-        let old_last_input = old_last_input.unwrap_or(params.ic);
-        // Initialize Output to a Zeroed (i.e. all `false`) state.
-        *store = Matrix::zeroed();
-        // Make a immutable iterator of each element of `input` and `old_last_input`
-
+        let reference = reference.get_or_insert(params.ic);
+        // Make a immutable iterator of each element of `input` and its reference
         let inputs = input
             .data
             .as_flattened()
             .iter()
-            .zip(old_last_input.data.as_flattened().iter());
+            .zip(reference.data.as_flattened_mut().iter_mut());
         // Zip that iterator with a mutable iterator over the output matrix
         // and then perform the operation on each set of three values
         store
@@ -129,19 +128,32 @@ impl<const NROWS: usize, const NCOLS: usize, C: ChangeDetect, O: Scalar> Apply<O
             .as_flattened_mut()
             .iter_mut()
             .zip(inputs)
-            .for_each(|(output, (lh, rh))| {
-                *output = O::from_bool(C::change_detect(*lh, *rh, params.change_mode));
+            .for_each(|(output, (input, reference))| {
+                *output = O::from_bool(C::change_detect(*input, reference, params));
             });
         store
     }
 }
 
-trait ChangeDetect: Scalar + for<'a> Pass<By<'a> = Self> + PartialEq + PartialOrd {
-    fn change_detect(left_hand: PassBy<Self>, right_hand: PassBy<Self>, mode: ChangeMode) -> bool {
-        match mode {
-            ChangeMode::Any => left_hand != right_hand,
-            ChangeMode::Rising => left_hand > right_hand,
-            ChangeMode::Falling => left_hand < right_hand,
+trait ChangeDetect: Scalar + for<'a> Pass<By<'a> = Self> {
+    /// Whether `input` moved away from `reference` by more than the tolerance, in the direction
+    /// of the change mode. Any such move, in either direction, replaces `reference`.
+    fn change_detect<T>(input: Self, reference: &mut Self, params: &Parameters<T>) -> bool {
+        let (current, previous): (f64, f64) = (input.into(), (*reference).into());
+        let moved = if current.is_nan() || previous.is_nan() {
+            current.is_nan() != previous.is_nan()
+        } else {
+            // Checking equality first keeps infinities of the same sign unchanged
+            current != previous && (current - previous).abs() > params.tolerance
+        };
+        if !moved {
+            return false;
+        }
+        *reference = input;
+        match params.change_mode {
+            ChangeMode::Any => true,
+            ChangeMode::Rising => current > previous,
+            ChangeMode::Falling => current < previous,
         }
     }
 }
@@ -170,12 +182,52 @@ pub enum ChangeMode {
 pub struct Parameters<T> {
     ic: T,
     change_mode: ChangeMode,
+    /// Largest move that doesn't count as a change
+    tolerance: f64,
 }
 
 impl<T> Parameters<T> {
     pub fn new(ic: T, change_mode: &str) -> Self {
-        let change_mode = change_mode.parse().expect("Failed to parse ChangeMode");
-        Self { ic, change_mode }
+        Self::try_new(ic, change_mode).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(ic: T, change_mode: &str) -> Result<Self, ParameterError> {
+        Self::try_with_tolerance(ic, change_mode, 0.0)
+    }
+
+    /// Like [`Parameters::new`], but moves of up to `tolerance` don't count as changes
+    pub fn with_tolerance(ic: T, change_mode: &str, tolerance: f64) -> Self {
+        Self::try_with_tolerance(ic, change_mode, tolerance).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::with_tolerance`], for builds where panics are
+    /// unacceptable
+    pub fn try_with_tolerance(
+        ic: T,
+        change_mode: &str,
+        tolerance: f64,
+    ) -> Result<Self, ParameterError> {
+        let change_mode = change_mode
+            .parse()
+            .map_err(|_| ParameterError("Failed to parse ChangeMode"))?;
+        if tolerance.is_nan() || tolerance < 0.0 {
+            return Err(ParameterError("Tolerance must be non-negative"));
+        }
+        Ok(Self {
+            ic,
+            change_mode,
+            tolerance,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved change mode, without parsing
+    pub const fn from_mode(ic: T, change_mode: ChangeMode) -> Self {
+        Self {
+            ic,
+            change_mode,
+            tolerance: 0.0,
+        }
     }
 }
 
@@ -495,6 +547,61 @@ mod tests {
         assert_eq!(output, 1.0f32);
     }
 
+    #[test]
+    fn test_scalar_tolerance() {
+        let context = StubContext::default();
+        let params = Parameters::with_tolerance(1.0, "Any", 0.1);
+        let mut block = ChangeDetectionBlock::<f64>::new(&params);
+
+        assert!(!block.process(&params, &context, 1.05).is_truthy());
+        // Drift accumulates against the last detected change
+        assert!(!block.process(&params, &context, 1.09).is_truthy());
+        assert!(block.process(&params, &context, 1.15).is_truthy());
+        assert!(!block.process(&params, &context, 1.2).is_truthy());
+        assert!(block.process(&params, &context, 0.9).is_truthy());
+
+        // Steady NaN or infinity doesn't count as a change, entering or leaving it does
+        assert!(block.process(&params, &context, f64::NAN).is_truthy());
+        assert!(!block.process(&params, &context, f64::NAN).is_truthy());
+        assert!(block.process(&params, &context, f64::INFINITY).is_truthy());
+        assert!(!block.process(&params, &context, f64::INFINITY).is_truthy());
+        assert!(block.process(&params, &context, 0.0).is_truthy());
+    }
+
+    #[test]
+    fn test_scalar_tolerance_rising() {
+        let context = StubContext::default();
+        let params = Parameters::with_tolerance(0.0, "Rising", 0.5);
+        let mut block = ChangeDetectionBlock::<f64>::new(&params);
+
+        assert!(!block.process(&params, &context, 0.4).is_truthy());
+        assert!(block.process(&params, &context, 0.8).is_truthy());
+        // Falling beyond the tolerance moves the reference without triggering
+        assert!(!block.process(&params, &context, 0.2).is_truthy());
+        assert!(block.process(&params, &context, 0.8).is_truthy());
+    }
+
+    #[test]
+    fn test_matrix_tolerance() {
+        let context = StubContext::default();
+        let params = Parameters::with_tolerance(Matrix::<2, 1, f32>::zeroed(), "Any", 0.5);
+        let mut block = ChangeDetectionBlock::<Matrix<2, 1, f32>>::new(&params);
+
+        let output = block.process(&params, &context, &Matrix { data: [[0.4, 0.6]] });
+        assert_eq!(output, &Matrix { data: [[0.0, 1.0]] });
+        // Each element has its own reference
+        let output = block.process(&params, &context, &Matrix { data: [[0.6, 0.6]] });
+        assert_eq!(output, &Matrix { data: [[1.0, 0.0]] });
+    }
+
+    #[test]
+    fn test_tolerance_parameters() {
+        assert!(Parameters::try_with_tolerance(0.0, "Any", 0.0).is_ok());
+        assert!(Parameters::try_with_tolerance(0.0, "Any", -1.0).is_err());
+        assert!(Parameters::try_with_tolerance(0.0, "Any", f64::NAN).is_err());
+        assert!(Parameters::try_with_tolerance(0.0, "Sideways", 1.0).is_err());
+    }
+
     #[test]
     fn test_f32_output_type_matrix() {
         let context = StubContext::default();
@@ -13,6 +13,21 @@ impl<T> Parameters<T> {
     }
 }
 
+/// Clamps `value` to the range from `min` to `max`. Like the std `clamp`, a min greater than the
+/// max (or a NaN bound) panics unless the `panic-free` feature is enabled, in which case the max
+/// wins.
+pub(crate) fn clamp<T: PartialOrd>(value: T, min: T, max: T) -> T {
+    #[cfg(not(feature = "panic-free"))]
+    assert!(min <= max, "Clamp min must not be greater than the max");
+    if value > max {
+        max
+    } else if value < min {
+        min
+    } else {
+        value
+    }
+}
+
 /// Clamps an input based on the min and max values provided.
 ///
 /// If an input is larger than the max value, it will be set to the max value. If
@@ -45,7 +60,7 @@ macro_rules! impl_clamp_block {
                 _context: &dyn pictorus_traits::Context,
                 input: PassBy<Self::Inputs>,
             ) -> PassBy<'_, Self::Output> {
-                self.buffer = input.clamp(parameters.min, parameters.max);
+                self.buffer = clamp(input, parameters.min, parameters.max);
                 self.buffer
             }
 
@@ -70,7 +85,7 @@ macro_rules! impl_clamp_block {
                 for r in 0..ROWS {
                     for c in 0..COLS {
                         self.buffer.data[c][r] =
-                            input.data[c][r].clamp(parameters.min, parameters.max);
+                            clamp(input.data[c][r], parameters.min, parameters.max);
                     }
                 }
                 &self.buffer
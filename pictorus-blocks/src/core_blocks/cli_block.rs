@@ -0,0 +1,295 @@
+use core::fmt::Write;
+
+use pictorus_traits::{ByteSliceSignal, Context, Matrix, PassBy, ProcessBlock};
+
+use crate::ParameterError;
+
+/// Longest parameter name
+const NAME_CAPACITY: usize = 32;
+/// Longest command line, excluding the line ending
+const LINE_CAPACITY: usize = 128;
+/// Longest response of a tick without `alloc`; longer responses are cut short
+#[cfg(not(feature = "alloc"))]
+const RESPONSE_CAPACITY: usize = 512;
+
+#[cfg(feature = "alloc")]
+type Response = alloc::string::String;
+#[cfg(not(feature = "alloc"))]
+type Response = heapless::String<RESPONSE_CAPACITY>;
+
+/// Parameters for the CliBlock
+pub struct Parameters<const N: usize> {
+    /// Names of the parameters the console can set
+    pub names: [heapless::String<NAME_CAPACITY>; N],
+    /// Values of the parameters before they're first set
+    pub initial_values: [f64; N],
+}
+
+impl<const N: usize> Parameters<N> {
+    pub fn new(names: &str, initial_values: [f64; N]) -> Self {
+        Self::try_new(names, initial_values).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(names: &str, initial_values: [f64; N]) -> Result<Self, ParameterError> {
+        let mut parsed = core::array::from_fn(|_| heapless::String::new());
+        let mut count = 0;
+        for name in names.split(',').map(str::trim) {
+            let slot = parsed.get_mut(count).ok_or(ParameterError(
+                "CLI block has more parameter names than values",
+            ))?;
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(ParameterError(
+                    "CLI parameter names must be non-empty and contain no whitespace",
+                ));
+            }
+            slot.push_str(name)
+                .map_err(|_| ParameterError("CLI parameter names must be at most 32 bytes"))?;
+            count += 1;
+        }
+        if count != N {
+            return Err(ParameterError(
+                "CLI block has fewer parameter names than values",
+            ));
+        }
+        if (1..N).any(|i| parsed[..i].contains(&parsed[i])) {
+            return Err(ParameterError("CLI parameter names must be unique"));
+        }
+        Ok(Self {
+            names: parsed,
+            initial_values,
+        })
+    }
+}
+
+/// Serial console that sets named parameters from simple line-based commands, for commissioning
+/// a device in the field with nothing but a terminal.
+///
+/// The block takes the bytes received from any serial or byte input, which may split lines
+/// across ticks, and outputs the responses to transmit, the current value of each of the `N`
+/// parameters and whether any was set this tick. The parameter values are meant to be wired to
+/// the gains and setpoints they tune. Lines end with `\n` or `\r`, and the commands are:
+/// - `set <name> <value>`: sets a parameter, answering `ok <name> = <value>`
+/// - `get <name>`: answers `<name> = <value>`
+/// - `status`: answers `<name> = <value>` for every parameter, and the app time
+/// - `help`: lists the commands and parameters
+///
+/// Commands and names are case sensitive. Invalid commands are answered with a line starting
+/// with `error:`, and lines longer than 128 bytes are dropped. Every line of a response ends
+/// with `\r\n`. Without the `alloc` feature, each tick's responses are limited to 512 bytes and
+/// cut short beyond that.
+pub struct CliBlock<const N: usize> {
+    line: heapless::String<LINE_CAPACITY>,
+    /// Whether the line being received is too long, and is dropped
+    overflowed: bool,
+    response: Response,
+    values: Matrix<1, N, f64>,
+    /// Whether the values have been set to their initial values
+    initialized: bool,
+    updated: bool,
+}
+
+impl<const N: usize> Default for CliBlock<N> {
+    fn default() -> Self {
+        Self {
+            line: heapless::String::new(),
+            overflowed: false,
+            response: Response::new(),
+            values: Matrix::zeroed(),
+            initialized: false,
+            updated: false,
+        }
+    }
+}
+
+impl<const N: usize> CliBlock<N> {
+    fn execute(&mut self, parameters: &Parameters<N>, context: &dyn Context) {
+        let values = &mut self.values;
+        let find = |name: &str| parameters.names.iter().position(|known| known == name);
+        let out = &mut self.response;
+        let mut words = self.line.split_whitespace();
+        // Writes only fail once a response without `alloc` is full, cutting it short
+        let _ = match (words.next(), words.next(), words.next(), words.next()) {
+            (None, ..) => Ok(()),
+            (Some("set"), Some(name), Some(value), None) => {
+                match (find(name), value.parse::<f64>()) {
+                    (Some(index), Ok(value)) if value.is_finite() => {
+                        values.data[index][0] = value;
+                        self.updated = true;
+                        write!(out, "ok {name} = {value}\r\n")
+                    }
+                    (Some(_), _) => write!(out, "error: invalid value '{value}'\r\n"),
+                    (None, _) => write!(out, "error: unknown parameter '{name}'\r\n"),
+                }
+            }
+            (Some("get"), Some(name), None, None) => match find(name) {
+                Some(index) => write!(out, "{name} = {}\r\n", values.data[index][0]),
+                None => write!(out, "error: unknown parameter '{name}'\r\n"),
+            },
+            (Some("status"), None, ..) => parameters
+                .names
+                .iter()
+                .zip(&values.data)
+                .try_for_each(|(name, value)| write!(out, "{name} = {}\r\n", value[0]))
+                .and_then(|_| write!(out, "time = {}\r\n", context.time().as_secs_f64())),
+            (Some("help"), None, ..) => out
+                .write_str("commands: set <name> <value>, get <name>, status, help\r\nparameters:")
+                .and_then(|_| {
+                    parameters
+                        .names
+                        .iter()
+                        .try_for_each(|name| write!(out, " {name}"))
+                })
+                .and_then(|_| out.write_str("\r\n")),
+            (Some(command @ ("set" | "get" | "status" | "help")), ..) => {
+                write!(
+                    out,
+                    "error: wrong arguments for '{command}', try 'help'\r\n"
+                )
+            }
+            (Some(command), ..) => {
+                write!(out, "error: unknown command '{command}', try 'help'\r\n")
+            }
+        };
+    }
+}
+
+impl<const N: usize> ProcessBlock for CliBlock<N> {
+    type Parameters = Parameters<N>;
+    type Inputs = ByteSliceSignal;
+    type Output = (ByteSliceSignal, Matrix<1, N, f64>, bool);
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        self.response.clear();
+        self.updated = false;
+        if !self.initialized {
+            self.values.data = parameters.initial_values.map(|value| [value]);
+            self.initialized = true;
+        }
+
+        for &byte in inputs {
+            match byte {
+                b'\n' | b'\r' => {
+                    if self.overflowed {
+                        let _ = self.response.write_str("error: line too long\r\n");
+                    } else {
+                        self.execute(parameters, context);
+                    }
+                    self.line.clear();
+                    self.overflowed = false;
+                }
+                // Anything else that isn't printable ASCII can't be part of a command
+                byte if !byte.is_ascii() || byte.is_ascii_control() && byte != b'\t' => {}
+                byte => {
+                    if self.line.push(byte as char).is_err() {
+                        self.overflowed = true;
+                    }
+                }
+            }
+        }
+
+        self.buffer()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        (self.response.as_bytes(), &self.values, self.updated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+    use core::time::Duration;
+
+    #[test]
+    fn test_cli_default_buffer_no_panic() {
+        let block = CliBlock::<2>::default();
+        let (response, values, updated) = block.buffer();
+        assert_eq!(response, b"");
+        assert_eq!(values, &Matrix::zeroed());
+        assert!(!updated);
+    }
+
+    #[test]
+    fn test_cli_parameters_validation() {
+        assert!(Parameters::try_new("gain, offset", [1.0, 0.0]).is_ok());
+        assert!(Parameters::try_new("gain", [1.0, 0.0]).is_err());
+        assert!(Parameters::try_new("gain, offset, rate", [1.0, 0.0]).is_err());
+        assert!(Parameters::try_new("gain, gain", [1.0, 0.0]).is_err());
+        assert!(Parameters::try_new("gain, ", [1.0, 0.0]).is_err());
+        assert!(Parameters::try_new("gain, max rate", [1.0, 0.0]).is_err());
+    }
+
+    #[test]
+    fn test_cli_set_and_get() {
+        let parameters = Parameters::new("gain, offset", [1.0, 0.0]);
+        let mut runtime = StubRuntime::default();
+        let mut block = CliBlock::<2>::default();
+
+        let (response, values, updated) = block.process(&parameters, &runtime.context(), b"");
+        assert_eq!(response, b"");
+        assert_eq!(values.data, [[1.0], [0.0]]);
+        assert!(!updated);
+
+        // Lines may be split across ticks
+        block.process(&parameters, &runtime.context(), b"set ga");
+        let (response, values, updated) =
+            block.process(&parameters, &runtime.context(), b"in 1.5\r\n");
+        assert_eq!(response, b"ok gain = 1.5\r\n");
+        assert_eq!(values.data, [[1.5], [0.0]]);
+        assert!(updated);
+
+        let (response, _, updated) =
+            block.process(&parameters, &runtime.context(), b"get gain\nget rate\n");
+        assert_eq!(
+            core::str::from_utf8(response).unwrap(),
+            "gain = 1.5\r\nerror: unknown parameter 'rate'\r\n"
+        );
+        assert!(!updated);
+
+        runtime.set_time(Duration::from_millis(2500));
+        let (response, _, _) = block.process(&parameters, &runtime.context(), b"status\n");
+        assert_eq!(
+            core::str::from_utf8(response).unwrap(),
+            "gain = 1.5\r\noffset = 0\r\ntime = 2.5\r\n"
+        );
+    }
+
+    #[test]
+    fn test_cli_errors() {
+        let parameters = Parameters::new("gain", [1.0]);
+        let context = StubRuntime::default().context();
+        let mut block = CliBlock::<1>::default();
+
+        let (response, values, updated) = block.process(
+            &parameters,
+            &context,
+            b"set gain abc\nset gain inf\nset gain\nreboot\n",
+        );
+        assert_eq!(
+            core::str::from_utf8(response).unwrap(),
+            "error: invalid value 'abc'\r\n\
+             error: invalid value 'inf'\r\n\
+             error: wrong arguments for 'set', try 'help'\r\n\
+             error: unknown command 'reboot', try 'help'\r\n"
+        );
+        assert_eq!(values.data, [[1.0]]);
+        assert!(!updated);
+
+        let long_line = [b'x'; LINE_CAPACITY + 1];
+        block.process(&parameters, &context, &long_line);
+        let (response, _, _) = block.process(&parameters, &context, b"\nhelp\n");
+        assert_eq!(
+            core::str::from_utf8(response).unwrap(),
+            "error: line too long\r\n\
+             commands: set <name> <value>, get <name>, status, help\r\n\
+             parameters: gain\r\n"
+        );
+    }
+}
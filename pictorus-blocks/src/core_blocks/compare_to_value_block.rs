@@ -1,3 +1,4 @@
+use crate::ParameterError;
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock, Scalar};
 
 use super::comparison_block::ComparisonType;
@@ -14,10 +15,23 @@ where
     S: Scalar,
 {
     pub fn new(comparison_type: &str, value: S) -> Self {
-        Self {
+        Self::try_new(comparison_type, value).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameter::new`], for builds where panics are unacceptable
+    pub fn try_new(comparison_type: &str, value: S) -> Result<Self, ParameterError> {
+        Ok(Self {
             comparison_type: comparison_type
                 .parse()
-                .expect("Failed to parse comparison type"),
+                .map_err(|_| ParameterError("Failed to parse comparison type"))?,
+            value,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved comparison type, without parsing
+    pub const fn from_type(comparison_type: ComparisonType, value: S) -> Self {
+        Self {
+            comparison_type,
             value,
         }
     }
@@ -1,4 +1,5 @@
 use crate::traits::{Apply, ApplyInto, MatrixOps, Scalar};
+use crate::ParameterError;
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
 
 /// The type of comparison operation to perform
@@ -25,11 +26,28 @@ pub struct Parameters {
 
 impl Parameters {
     pub fn new(comparison_type: &str) -> Self {
-        Self {
+        Self::try_new(comparison_type).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(comparison_type: &str) -> Result<Self, ParameterError> {
+        Ok(Self {
             comparison_type: comparison_type
                 .parse()
-                .expect("Failed to parse comparison method."),
-        }
+                .map_err(|_| ParameterError("Failed to parse comparison method."))?,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved comparison type. Unlike [`Parameters::new`]
+    /// this is a `const fn`, so generated code can declare the parameters as a `static`.
+    pub const fn from_type(comparison_type: ComparisonType) -> Self {
+        Self { comparison_type }
+    }
+}
+
+impl From<ComparisonType> for Parameters {
+    fn from(comparison_type: ComparisonType) -> Self {
+        Self::from_type(comparison_type)
     }
 }
 
@@ -225,6 +243,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_comparison_block_typed_parameters() {
+        let c = StubContext::default();
+        let mut block = ComparisonBlock::<(f64, f64)>::default();
+        let parameters = Parameters::from(ComparisonType::LessThan);
+        assert_eq!(parameters.comparison_type, ComparisonType::LessThan);
+
+        let output = block.process(&parameters, &c, (0., 1.));
+        assert_eq!(output, 1.0);
+
+        let output = block.process(&parameters, &c, (1., 0.));
+        assert_eq!(output, 0.0);
+    }
+
+    #[test]
+    fn test_comparison_block_static_parameters() {
+        static PARAMETERS: Parameters = Parameters::from_type(ComparisonType::GreaterOrEqual);
+
+        let c = StubContext::default();
+        let mut block = ComparisonBlock::<(f64, f64)>::default();
+        let output = block.process(&PARAMETERS, &c, (1., 1.));
+        assert_eq!(output, 1.0);
+    }
+
     #[test]
     fn test_comparison_block_scalar() {
         let c = StubContext::default();
@@ -6,7 +6,7 @@ pub struct Parameters<T> {
 }
 
 impl<T> Parameters<T> {
-    pub fn new(constant: T) -> Self {
+    pub const fn new(constant: T) -> Self {
         Self { constant }
     }
 }
@@ -0,0 +1,245 @@
+use crate::core_blocks::clamp_block::clamp;
+use crate::traits::Float;
+use nalgebra::{Cholesky, SMatrix, SVector};
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+/// Parameters for the ControlAllocationBlock
+pub struct Parameters<F: Float, const M: usize, const N: usize> {
+    /// Effectiveness matrix, mapping actuator commands to the `M` generalized forces/torques.
+    /// Each column holds the effect of one actuator.
+    pub effectiveness: Matrix<M, N, F>,
+    /// Lower limit of each actuator
+    pub min: [F; N],
+    /// Upper limit of each actuator
+    pub max: [F; N],
+}
+
+impl<F: Float, const M: usize, const N: usize> Parameters<F, M, N> {
+    pub fn new(effectiveness: Matrix<M, N, F>, min: [F; N], max: [F; N]) -> Self {
+        Self {
+            effectiveness,
+            min,
+            max,
+        }
+    }
+}
+
+/// Allocates `M` desired forces/torques to `N` actuators with individual limits.
+///
+/// The allocation uses the redistributed pseudo-inverse: the minimum-norm least-squares solution is
+/// computed for the actuators that are still free, any of them that exceed their limits are fixed
+/// at the limit, and the remaining demand is redistributed over the rest. This takes at most `N`
+/// iterations, each solving an `M` x `M` system, so it is bounded and allocation-free.
+///
+/// When the demand is achievable within the limits, the output is the least-squares solution.
+/// Otherwise the result is a close, but not necessarily optimal, approximation.
+///
+/// The output is a tuple of the (1, N) actuator commands, the (1, M) forces/torques they produce,
+/// and a flag that is true when any actuator is at a limit.
+pub struct ControlAllocationBlock<F: Float, const M: usize, const N: usize> {
+    buffer: (Matrix<1, N, F>, Matrix<1, M, F>, bool),
+}
+
+impl<F: Float, const M: usize, const N: usize> Default for ControlAllocationBlock<F, M, N> {
+    fn default() -> Self {
+        Self {
+            buffer: (Matrix::zeroed(), Matrix::zeroed(), false),
+        }
+    }
+}
+
+/// Runs the redistributed pseudo-inverse allocation, returning the commands and whether any
+/// actuator was fixed at a limit
+fn allocate<F: Float, const M: usize, const N: usize>(
+    parameters: &Parameters<F, M, N>,
+    desired: &SVector<F, M>,
+) -> (SVector<F, N>, bool) {
+    let zero = <F as num_traits::Zero>::zero();
+    let effectiveness =
+        SMatrix::<F, M, N>::from_fn(|row, col| parameters.effectiveness.data[col][row]);
+    // A small regularization keeps the solve well-posed when the free actuators can't
+    // span every axis, scaled to the effectiveness so it doesn't bias the result
+    let regularization = num_traits::Float::sqrt(F::epsilon())
+        * num_traits::Float::max(
+            (effectiveness * effectiveness.transpose()).trace(),
+            <F as num_traits::One>::one(),
+        );
+
+    let mut commands = SVector::<F, N>::zeros();
+    let mut free = [true; N];
+    for _ in 0..N {
+        let mut free_effectiveness = effectiveness;
+        for (col, is_free) in free.iter().enumerate() {
+            if !is_free {
+                free_effectiveness.column_mut(col).fill(zero);
+            }
+        }
+        // Fixed actuators have their limit in `commands` and free ones are zero here
+        let residual = desired - effectiveness * commands;
+        let gram = free_effectiveness * free_effectiveness.transpose()
+            + SMatrix::<F, M, M>::identity() * regularization;
+        let Some(cholesky) = Cholesky::new(gram) else {
+            break;
+        };
+        let solution = free_effectiveness.transpose() * cholesky.solve(&residual);
+
+        let mut violated = false;
+        let limits = parameters.min.iter().zip(&parameters.max);
+        let actuators = free
+            .iter_mut()
+            .zip(solution.iter())
+            .zip(commands.iter_mut())
+            .zip(limits);
+        for (((is_free, &command), limited), (&min, &max)) in actuators {
+            if !*is_free {
+                continue;
+            }
+            *limited = clamp(command, min, max);
+            if *limited != command {
+                *is_free = false;
+                violated = true;
+            }
+        }
+        if !violated {
+            break;
+        }
+        // Free actuators are re-solved from scratch on the next iteration
+        for (is_free, command) in free.iter().zip(commands.iter_mut()) {
+            if *is_free {
+                *command = zero;
+            }
+        }
+    }
+
+    (commands, free.iter().any(|is_free| !is_free))
+}
+
+impl<F: Float, const M: usize, const N: usize> ProcessBlock for ControlAllocationBlock<F, M, N> {
+    type Inputs = Matrix<1, M, F>;
+    type Output = (Matrix<1, N, F>, Matrix<1, M, F>, bool);
+    type Parameters = Parameters<F, M, N>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let desired = SVector::<F, M>::from_fn(|row, _| input.data[row][0]);
+        let (commands, saturated) = allocate(parameters, &desired);
+
+        let (output, achieved, output_saturated) = &mut self.buffer;
+        for (index, command) in commands.iter().enumerate() {
+            output.data[index][0] = *command;
+        }
+        for (row, value) in achieved.data.iter_mut().enumerate() {
+            value[0] = parameters
+                .effectiveness
+                .data
+                .iter()
+                .zip(commands.iter())
+                .fold(<F as num_traits::Zero>::zero(), |acc, (column, command)| {
+                    acc + column[row] * *command
+                });
+        }
+        *output_saturated = saturated;
+
+        (self.buffer.0.as_by(), self.buffer.1.as_by(), self.buffer.2)
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        (self.buffer.0.as_by(), self.buffer.1.as_by(), self.buffer.2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_allocation_default_buffer() {
+        let block = ControlAllocationBlock::<f64, 2, 3>::default();
+        assert_eq!(
+            block.buffer(),
+            (&Matrix::zeroed(), &Matrix::zeroed(), false)
+        );
+    }
+
+    #[test]
+    fn test_allocation_unconstrained_least_squares() {
+        let ctxt = StubContext::default();
+        // Thrust and roll torque from three actuators
+        let params = Parameters::new(
+            Matrix {
+                data: [[1.0, -1.0], [1.0, 0.0], [1.0, 1.0]],
+            },
+            [-10.0; 3],
+            [10.0; 3],
+        );
+        let mut block = ControlAllocationBlock::<f64, 2, 3>::default();
+        let input = Matrix {
+            data: [[3.0], [1.0]],
+        };
+        let (commands, achieved, saturated) = block.process(&params, &ctxt, &input);
+        // Minimum-norm solution
+        assert_relative_eq!(commands.data[0][0], 0.5, epsilon = 1e-6);
+        assert_relative_eq!(commands.data[1][0], 1.0, epsilon = 1e-6);
+        assert_relative_eq!(commands.data[2][0], 1.5, epsilon = 1e-6);
+        assert_relative_eq!(achieved.data[0][0], 3.0, epsilon = 1e-6);
+        assert_relative_eq!(achieved.data[1][0], 1.0, epsilon = 1e-6);
+        assert!(!saturated);
+    }
+
+    #[test]
+    fn test_allocation_redistributes() {
+        let ctxt = StubContext::default();
+        let params = Parameters::new(
+            Matrix {
+                data: [[1.0], [1.0]],
+            },
+            [0.0; 2],
+            [0.5, 2.0],
+        );
+        let mut block = ControlAllocationBlock::<f64, 1, 2>::default();
+
+        // The first actuator saturates and the second picks up the rest
+        let (commands, achieved, saturated) =
+            block.process(&params, &ctxt, &Matrix { data: [[2.0]] });
+        assert_relative_eq!(commands.data[0][0], 0.5, epsilon = 1e-6);
+        assert_relative_eq!(commands.data[1][0], 1.5, epsilon = 1e-6);
+        assert_relative_eq!(achieved.data[0][0], 2.0, epsilon = 1e-6);
+        assert!(saturated);
+
+        // Infeasible demand leaves every actuator at its limit
+        let (commands, achieved, saturated) =
+            block.process(&params, &ctxt, &Matrix { data: [[10.0]] });
+        assert_eq!(commands.data, [[0.5], [2.0]]);
+        assert_relative_eq!(achieved.data[0][0], 2.5);
+        assert!(saturated);
+    }
+
+    #[test]
+    fn test_allocation_rank_deficient_f32() {
+        let ctxt = StubContext::default();
+        // The second axis has no effective actuators, so only the first can be achieved
+        let params = Parameters::new(
+            Matrix {
+                data: [[2.0, 0.0], [2.0, 0.0]],
+            },
+            [-1.0f32; 2],
+            [1.0; 2],
+        );
+        let mut block = ControlAllocationBlock::<f32, 2, 2>::default();
+        let input = Matrix {
+            data: [[2.0], [5.0]],
+        };
+        let (commands, achieved, saturated) = block.process(&params, &ctxt, &input);
+        assert_relative_eq!(commands.data[0][0], 0.5, epsilon = 1e-3);
+        assert_relative_eq!(commands.data[1][0], 0.5, epsilon = 1e-3);
+        assert_relative_eq!(achieved.data[0][0], 2.0, epsilon = 1e-3);
+        assert_eq!(achieved.data[1][0], 0.0);
+        assert!(!saturated);
+    }
+}
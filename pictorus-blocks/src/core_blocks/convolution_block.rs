@@ -0,0 +1,128 @@
+use core::ops::{AddAssign, Mul};
+
+use crate::traits::Scalar;
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+/// Parameters for the ConvolutionBlock
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Parameters;
+
+impl Parameters {
+    pub fn new() -> Self {
+        Parameters {}
+    }
+}
+
+/// Computes the full discrete convolution of two vectors.
+///
+/// The elements of each input are taken in column-major order, so inputs may be row or column vectors
+/// (or any matrix shape). For inputs with `A` and `B` elements the output MUST be a (1, A+B-1) matrix:
+/// `y[k] = sum_i a[i] * b[k - i]`.
+///
+/// Convolving a signal with the time-reversed template of a known pulse gives a matched filter, whose
+/// output peaks where the pulse occurs in the signal.
+pub struct ConvolutionBlock<I, O> {
+    buffer: O,
+    _phantom: core::marker::PhantomData<I>,
+}
+
+impl<I, O> Default for ConvolutionBlock<I, O>
+where
+    O: Default + Pass,
+{
+    fn default() -> Self {
+        Self {
+            buffer: O::default(),
+            _phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<
+        const AROWS: usize,
+        const ACOLS: usize,
+        const BROWS: usize,
+        const BCOLS: usize,
+        const OCOLS: usize,
+        T,
+    > ProcessBlock
+    for ConvolutionBlock<(Matrix<AROWS, ACOLS, T>, Matrix<BROWS, BCOLS, T>), Matrix<1, OCOLS, T>>
+where
+    T: Scalar + num_traits::Zero + AddAssign<T> + Mul<T, Output = T>,
+{
+    type Inputs = (Matrix<AROWS, ACOLS, T>, Matrix<BROWS, BCOLS, T>);
+    type Output = Matrix<1, OCOLS, T>;
+    type Parameters = Parameters;
+
+    fn process<'b>(
+        &'b mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        const {
+            assert!(
+                AROWS * ACOLS + BROWS * BCOLS == OCOLS + 1,
+                "Output length must be the sum of the input lengths minus one in ConvolutionBlock"
+            );
+        }
+
+        let (a, b) = inputs;
+        let (a, b) = (a.data.as_flattened(), b.data.as_flattened());
+        let output = self.buffer.data.as_flattened_mut();
+        output.fill(T::zero());
+        for (i, a) in a.iter().enumerate() {
+            for (j, b) in b.iter().enumerate() {
+                output[i + j] += *a * *b;
+            }
+        }
+
+        self.buffer.as_by()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer.as_by()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+
+    #[test]
+    fn test_convolution_default_buffer_no_panic() {
+        let block =
+            ConvolutionBlock::<(Matrix<1, 3, f64>, Matrix<1, 2, f64>), Matrix<1, 4, f64>>::default(
+            );
+        assert_eq!(block.buffer(), &Matrix::zeroed());
+    }
+
+    #[test]
+    fn test_convolution_block() {
+        let context = StubContext::default();
+        let parameters = Parameters::new();
+
+        let mut block =
+            ConvolutionBlock::<(Matrix<1, 3, f64>, Matrix<1, 2, f64>), Matrix<1, 4, f64>>::default(
+            );
+        let a = Matrix {
+            data: [[1.0], [2.0], [3.0]],
+        };
+        let b = Matrix {
+            data: [[0.5], [-1.0]],
+        };
+        let output = block.process(&parameters, &context, (&a, &b));
+        assert_eq!(output.data, [[0.5], [0.0], [-0.5], [-3.0]]);
+
+        // Column vectors and integer types work too, and the result doesn't accumulate between ticks
+        let mut block =
+            ConvolutionBlock::<(Matrix<2, 1, i32>, Matrix<2, 1, i32>), Matrix<1, 3, i32>>::default(
+            );
+        let a = Matrix { data: [[1, 1]] };
+        let b = Matrix { data: [[2, 3]] };
+        block.process(&parameters, &context, (&a, &b));
+        let output = block.process(&parameters, &context, (&a, &b));
+        assert_eq!(output.data, [[2], [5], [3]]);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::traits::Float;
+use crate::ParameterError;
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+/// How the raw cross-correlation sums are scaled
+#[derive(Clone, Copy, Debug, PartialEq, strum::EnumString)]
+pub enum CorrelationScaling {
+    /// Raw sums of products
+    None,
+    /// Divide every lag by the length of the longer input
+    Biased,
+    /// Divide each lag by the number of overlapping samples at that lag
+    Unbiased,
+    /// Normalize by the input energies so that identical inputs correlate to 1.0 at zero lag
+    Coefficient,
+}
+
+/// Parameters for the CorrelationBlock
+pub struct Parameters {
+    pub scaling: CorrelationScaling,
+}
+
+impl Parameters {
+    pub fn new(scaling: &str) -> Self {
+        Self::try_new(scaling).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(scaling: &str) -> Result<Self, ParameterError> {
+        Ok(Self {
+            scaling: scaling
+                .parse()
+                .map_err(|_| ParameterError("Failed to parse CorrelationScaling"))?,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved scaling, without parsing
+    pub const fn from_scaling(scaling: CorrelationScaling) -> Self {
+        Self { scaling }
+    }
+}
+
+impl From<CorrelationScaling> for Parameters {
+    fn from(scaling: CorrelationScaling) -> Self {
+        Self::from_scaling(scaling)
+    }
+}
+
+/// Computes the full cross-correlation of two vectors and the lag at which it peaks.
+///
+/// The elements of each input are taken in column-major order, so inputs may be row or column vectors
+/// (or any matrix shape). For inputs `a` and `b` with `A` and `B` elements the first output MUST be a
+/// (1, A+B-1) matrix holding `r[m] = sum_n a[n + m] * b[n]` for lags `m` from `-(B-1)` to `A-1`,
+/// so output index `i` corresponds to lag `i - (B-1)`.
+///
+/// The second output is the lag (in samples) with the largest correlation. For time-delay
+/// estimation a positive lag means `a` is delayed relative to `b` by that many samples.
+///
+/// Supported scaling:
+/// - None
+/// - Biased
+/// - Unbiased
+/// - Coefficient
+pub struct CorrelationBlock<I, O: Pass, F> {
+    buffer: (O, F),
+    _phantom: core::marker::PhantomData<I>,
+}
+
+impl<I, O, F> Default for CorrelationBlock<I, O, F>
+where
+    O: Default + Pass,
+    F: Default,
+{
+    fn default() -> Self {
+        Self {
+            buffer: (O::default(), F::default()),
+            _phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<
+        const AROWS: usize,
+        const ACOLS: usize,
+        const BROWS: usize,
+        const BCOLS: usize,
+        const OCOLS: usize,
+        F: Float,
+    > ProcessBlock
+    for CorrelationBlock<(Matrix<AROWS, ACOLS, F>, Matrix<BROWS, BCOLS, F>), Matrix<1, OCOLS, F>, F>
+{
+    type Inputs = (Matrix<AROWS, ACOLS, F>, Matrix<BROWS, BCOLS, F>);
+    type Output = (Matrix<1, OCOLS, F>, F);
+    type Parameters = Parameters;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        const {
+            assert!(
+                AROWS * ACOLS + BROWS * BCOLS == OCOLS + 1,
+                "Output length must be the sum of the input lengths minus one in CorrelationBlock"
+            );
+        }
+
+        let (a, b) = inputs;
+        let (a, b) = (a.data.as_flattened(), b.data.as_flattened());
+        let cast = |value: usize| F::from(value).expect("Length fits in float");
+        let zero = <F as num_traits::Zero>::zero();
+        let energy = |v: &[F]| v.iter().fold(zero, |acc, x| acc + *x * *x);
+        let coefficient_scale = num_traits::Float::sqrt(energy(a) * energy(b));
+
+        let output = self.buffer.0.data.as_flattened_mut();
+        let mut peak = (0, F::neg_infinity());
+        for (i, value) in output.iter_mut().enumerate() {
+            // Output index i pairs b[n] with a[n + i - (B-1)]
+            let offset = i as isize - (b.len() as isize - 1);
+            let mut sum = zero;
+            let mut overlap = 0;
+            for (n, b) in b.iter().enumerate() {
+                let Some(a) = usize::try_from(n as isize + offset)
+                    .ok()
+                    .and_then(|index| a.get(index))
+                else {
+                    continue;
+                };
+                sum += *a * *b;
+                overlap += 1;
+            }
+
+            *value = match parameters.scaling {
+                CorrelationScaling::None => sum,
+                CorrelationScaling::Biased => sum / cast(a.len().max(b.len())),
+                CorrelationScaling::Unbiased => sum / cast(overlap),
+                CorrelationScaling::Coefficient if coefficient_scale > zero => {
+                    sum / coefficient_scale
+                }
+                CorrelationScaling::Coefficient => zero,
+            };
+            if *value > peak.1 {
+                peak = (i, *value);
+            }
+        }
+        self.buffer.1 = cast(peak.0) - cast(b.len() - 1);
+
+        (self.buffer.0.as_by(), self.buffer.1)
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        (self.buffer.0.as_by(), self.buffer.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use approx::assert_relative_eq;
+
+    type Block5x3 =
+        CorrelationBlock<(Matrix<1, 5, f64>, Matrix<1, 3, f64>), Matrix<1, 7, f64>, f64>;
+
+    #[test]
+    fn test_correlation_default_buffer_no_panic() {
+        let block = Block5x3::default();
+        assert_eq!(block.buffer(), (&Matrix::zeroed(), 0.0));
+    }
+
+    #[test]
+    fn test_correlation_scaling() {
+        let context = StubContext::default();
+        let a = Matrix {
+            data: [[1.0], [2.0], [3.0], [4.0], [5.0]],
+        };
+        let b = Matrix {
+            data: [[1.0], [0.0], [-1.0]],
+        };
+
+        let mut block = Block5x3::default();
+        let (output, _) = block.process(&Parameters::new("None"), &context, (&a, &b));
+        // Lags -2..=4
+        assert_eq!(
+            output.data,
+            [[-1.0], [-2.0], [-2.0], [-2.0], [-2.0], [4.0], [5.0]]
+        );
+
+        let (output, _) = block.process(&Parameters::new("Biased"), &context, (&a, &b));
+        assert_eq!(output.data[6][0], 1.0);
+
+        // Lag 4 only overlaps a[4] with b[0], while lag 0 overlaps all of b
+        let (output, _) = block.process(&Parameters::new("Unbiased"), &context, (&a, &b));
+        assert_eq!(output.data[6][0], 5.0);
+        assert_relative_eq!(output.data[2][0], -2.0 / 3.0);
+
+        let zeros = Matrix::zeroed();
+        let (output, lag) = block.process(&Parameters::new("Coefficient"), &context, (&zeros, &b));
+        assert_eq!(output, &Matrix::zeroed());
+        assert_eq!(lag, -2.0);
+    }
+
+    #[test]
+    fn test_correlation_time_delay() {
+        let context = StubContext::default();
+        let pulse = Matrix {
+            data: [[1.0], [2.0], [1.0]],
+        };
+        // The pulse delayed by 2 samples
+        let signal = Matrix {
+            data: [[0.0], [0.0], [1.0], [2.0], [1.0]],
+        };
+
+        let mut block = Block5x3::default();
+        let (output, lag) =
+            block.process(&Parameters::new("Coefficient"), &context, (&signal, &pulse));
+        assert_eq!(lag, 2.0);
+        assert_relative_eq!(output.data[4][0], 1.0, epsilon = 1e-12);
+        assert_eq!(block.buffer().1, 2.0);
+    }
+}
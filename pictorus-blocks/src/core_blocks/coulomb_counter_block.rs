@@ -0,0 +1,264 @@
+use core::time::Duration;
+
+use crate::traits::Float;
+use pictorus_traits::{HasIc, PassBy, ProcessBlock};
+
+/// Parameters for the CoulombCounterBlock
+pub struct Parameters<F: Float, const L: usize> {
+    /// State of charge at startup, from 0 (empty) to 1 (full)
+    pub initial_soc: F,
+    /// Usable capacity of the battery, in amp-hours
+    pub capacity_ah: F,
+    /// Coulombic efficiency (0 to 1) applied to charging current
+    pub charge_efficiency: F,
+    /// Open circuit voltage breakpoints, in ascending order
+    pub ocv_voltage: [F; L],
+    /// State of charge at each of the `ocv_voltage` breakpoints
+    pub ocv_soc: [F; L],
+    /// Current magnitude (amps) below which the battery is considered to be at rest
+    pub rest_current: F,
+    /// Time (seconds) the battery must be at rest before the OCV correction is applied
+    pub rest_time: F,
+    /// Fraction (0 to 1) of the difference to the OCV estimate removed each tick while at rest.
+    /// Zero disables the correction.
+    pub ocv_weight: F,
+    /// State of charge at or below which the low flag is set
+    pub low_soc: F,
+    /// State of charge at or below which the critical flag is set
+    pub critical_soc: F,
+}
+
+impl<F: Float, const L: usize> Parameters<F, L> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        initial_soc: F,
+        capacity_ah: F,
+        charge_efficiency: F,
+        ocv_voltage: [F; L],
+        ocv_soc: [F; L],
+        rest_current: F,
+        rest_time: F,
+        ocv_weight: F,
+        low_soc: F,
+        critical_soc: F,
+    ) -> Self {
+        Self {
+            initial_soc,
+            capacity_ah,
+            charge_efficiency,
+            ocv_voltage,
+            ocv_soc,
+            rest_current,
+            rest_time,
+            ocv_weight,
+            low_soc,
+            critical_soc,
+        }
+    }
+
+    /// State of charge for an open circuit voltage, linearly interpolated and clamped to the
+    /// ends of the table
+    fn soc_from_ocv(&self, voltage: F) -> Option<F> {
+        let (first, last) = (self.ocv_voltage.first()?, self.ocv_voltage.last()?);
+        if voltage <= *first {
+            return Some(self.ocv_soc[0]);
+        }
+        if voltage >= *last {
+            return Some(self.ocv_soc[L - 1]);
+        }
+        let upper = self.ocv_voltage.iter().position(|v| voltage < *v)?;
+        let (v0, v1) = (self.ocv_voltage[upper - 1], self.ocv_voltage[upper]);
+        let (s0, s1) = (self.ocv_soc[upper - 1], self.ocv_soc[upper]);
+        Some(s0 + (s1 - s0) * (voltage - v0) / (v1 - v0))
+    }
+}
+
+/// Estimates battery state of charge (SOC) by integrating current (coulomb counting), with an
+/// optional correction from an open circuit voltage (OCV) lookup table.
+///
+/// Inputs are `(current, voltage)`, where positive current (amps) charges the battery. Charging
+/// current is scaled by the charge efficiency before being integrated against the capacity.
+///
+/// Coulomb counting drifts with current sensor bias, so once the current has stayed below
+/// `rest_current` for `rest_time` seconds the terminal voltage is close to the OCV. From then on,
+/// while the battery remains at rest, the SOC is pulled towards the OCV table estimate by
+/// `ocv_weight` each tick.
+///
+/// The output is a tuple of (soc, low, critical), where SOC is clamped to [0, 1] and the flags
+/// are set when the SOC is at or below the `low_soc` and `critical_soc` thresholds.
+pub struct CoulombCounterBlock<F: Float, const L: usize> {
+    soc: F,
+    /// Seconds spent below `rest_current`
+    rest_seconds: F,
+    buffer: (F, bool, bool),
+}
+
+impl<F: Float, const L: usize> Default for CoulombCounterBlock<F, L> {
+    fn default() -> Self {
+        const {
+            panic!(
+                "CoulombCounterBlock has initial conditions and must be constructed with \
+                 CoulombCounterBlock::new(&parameters) (HasIc trait), not Default::default()."
+            )
+        }
+    }
+}
+
+impl<F: Float, const L: usize> CoulombCounterBlock<F, L> {
+    fn update_output(&mut self, parameters: &Parameters<F, L>) {
+        self.buffer = (
+            self.soc,
+            self.soc <= parameters.low_soc,
+            self.soc <= parameters.critical_soc,
+        );
+    }
+}
+
+impl<F: Float, const L: usize> ProcessBlock for CoulombCounterBlock<F, L> {
+    type Inputs = (F, F);
+    type Output = (F, bool, bool);
+    type Parameters = Parameters<F, L>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (current, voltage) = inputs;
+        let timestep = context.timestep().unwrap_or(Duration::ZERO);
+        let zero = <F as num_traits::Zero>::zero();
+        let one = <F as num_traits::One>::one();
+
+        let current = if current > zero {
+            current * parameters.charge_efficiency
+        } else {
+            current
+        };
+        let seconds_per_hour = F::from(3600.0).unwrap_or_else(F::infinity);
+        let capacity_as = parameters.capacity_ah * seconds_per_hour;
+        let timestep_s = F::from_duration(timestep);
+        if capacity_as > zero {
+            self.soc += current * timestep_s / capacity_as;
+        }
+
+        if num_traits::Float::abs(inputs.0) < parameters.rest_current {
+            self.rest_seconds += timestep_s;
+        } else {
+            self.rest_seconds = zero;
+        }
+        if self.rest_seconds >= parameters.rest_time {
+            if let Some(ocv_soc) = parameters.soc_from_ocv(voltage) {
+                self.soc += (ocv_soc - self.soc) * parameters.ocv_weight;
+            }
+        }
+
+        self.soc = num_traits::Float::clamp(self.soc, zero, one);
+        self.update_output(parameters);
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+impl<F: Float, const L: usize> HasIc for CoulombCounterBlock<F, L> {
+    fn new(parameters: &Self::Parameters) -> Self {
+        let mut block = Self {
+            soc: parameters.initial_soc,
+            rest_seconds: <F as num_traits::Zero>::zero(),
+            buffer: (parameters.initial_soc, false, false),
+        };
+        block.update_output(parameters);
+        block
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+    use approx::assert_relative_eq;
+
+    fn params(initial_soc: f64, ocv_weight: f64) -> Parameters<f64, 3> {
+        Parameters::new(
+            initial_soc,
+            1.0,
+            0.9,
+            [3.0, 3.7, 4.2],
+            [0.0, 0.5, 1.0],
+            0.05,
+            1.0,
+            ocv_weight,
+            0.2,
+            0.05,
+        )
+    }
+
+    #[test]
+    fn test_coulomb_counter_initial_buffer() {
+        let block = CoulombCounterBlock::new(&params(0.1, 0.0));
+        assert_eq!(block.buffer(), (0.1, true, false));
+    }
+
+    #[test]
+    fn test_coulomb_counter_integrates_current() {
+        let mut runtime = StubRuntime::default();
+        runtime.context.fundamental_timestep = Duration::from_secs(36);
+        let params = params(0.5, 0.0);
+        let mut block = CoulombCounterBlock::new(&params);
+
+        // No timestep on the first tick
+        assert_eq!(
+            block.process(&params, &runtime.context(), (-10.0, 3.7)).0,
+            0.5
+        );
+
+        // 10A for 36s is 0.1Ah, a tenth of the capacity
+        runtime.tick();
+        let (soc, ..) = block.process(&params, &runtime.context(), (-10.0, 3.7));
+        assert_relative_eq!(soc, 0.4, epsilon = 1e-12);
+
+        // Charging is scaled by the efficiency
+        runtime.tick();
+        let (soc, ..) = block.process(&params, &runtime.context(), (10.0, 3.7));
+        assert_relative_eq!(soc, 0.49, epsilon = 1e-12);
+
+        // Clamped at empty, with both flags set
+        for _ in 0..10 {
+            runtime.tick();
+            block.process(&params, &runtime.context(), (-10.0, 3.7));
+        }
+        assert_eq!(block.buffer(), (0.0, true, true));
+    }
+
+    #[test]
+    fn test_coulomb_counter_ocv_correction() {
+        let mut runtime = StubRuntime::default();
+        runtime.context.fundamental_timestep = Duration::from_millis(500);
+        let params = params(0.9, 0.5);
+        let mut block = CoulombCounterBlock::new(&params);
+
+        // 3.95V is 0.75 SOC in the table, but the correction waits for a second of rest
+        block.process(&params, &runtime.context(), (0.0, 3.95));
+        runtime.tick();
+        assert_eq!(
+            block.process(&params, &runtime.context(), (0.0, 3.95)).0,
+            0.9
+        );
+        runtime.tick();
+        let (soc, ..) = block.process(&params, &runtime.context(), (0.0, 3.95));
+        assert_relative_eq!(soc, 0.825, epsilon = 1e-12);
+        runtime.tick();
+        let (soc, ..) = block.process(&params, &runtime.context(), (0.0, 3.95));
+        assert_relative_eq!(soc, 0.7875, epsilon = 1e-12);
+
+        // Any load restarts the rest timer
+        runtime.tick();
+        block.process(&params, &runtime.context(), (-1.0, 3.5));
+        runtime.tick();
+        let (soc, ..) = block.process(&params, &runtime.context(), (0.0, 3.0));
+        assert_relative_eq!(soc, 0.7875 - 0.5 / 3600.0, epsilon = 1e-12);
+    }
+}
@@ -0,0 +1,299 @@
+use core::time::Duration;
+
+use pictorus_traits::{ByteSliceSignal, Context, PassBy, ProcessBlock};
+
+use crate::{
+    stale_tracker::{duration_from_ms_f64, StaleTracker},
+    traits::Float,
+    ParameterError,
+};
+
+/// Layout of a signal in a CAN frame, as described by an `SG_` line of a DBC file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DbcSignal {
+    /// Bit the signal starts at: its least significant bit for little endian signals, and its
+    /// most significant bit for big endian ones, numbered as in DBC files
+    pub start_bit: u16,
+    /// Number of bits, from 1 to 64
+    pub length: u8,
+    /// Whether the signal is little endian (Intel, `@1`) rather than big endian (Motorola, `@0`)
+    pub little_endian: bool,
+    /// Whether the raw value is a two's complement signed integer (`-`)
+    pub signed: bool,
+    pub scale: f64,
+    pub offset: f64,
+}
+
+impl DbcSignal {
+    /// Parses the layout of a signal from DBC syntax, e.g. `24|16@1+ (0.125,0)`. A whole `SG_`
+    /// line is accepted too, and the scale and offset default to 1 and 0 when left out.
+    pub fn parse(spec: &str) -> Result<Self, ParameterError> {
+        let invalid = ParameterError(
+            "DBC signal specs must be written as start|length@order+/- (scale,offset)",
+        );
+        // Skip the `SG_ name :` of a whole line
+        let spec = spec
+            .split_once(':')
+            .map_or(spec, |(_, layout)| layout)
+            .trim();
+        let (position, rest) = spec.split_once('@').ok_or(invalid)?;
+        let (start_bit, length) = position.split_once('|').ok_or(invalid)?;
+        let start_bit: u16 = start_bit.trim().parse().map_err(|_| invalid)?;
+        let length: u8 = length.trim().parse().map_err(|_| invalid)?;
+
+        let mut flags = rest.chars();
+        let little_endian = match flags.next() {
+            Some('1') => true,
+            Some('0') => false,
+            _ => return Err(invalid),
+        };
+        let signed = match flags.next() {
+            Some('-') => true,
+            Some('+') => false,
+            _ => return Err(invalid),
+        };
+
+        let factors = flags.as_str().trim();
+        let (scale, offset) = if let Some(factors) = factors.strip_prefix('(') {
+            let (factors, _) = factors.split_once(')').ok_or(invalid)?;
+            let (scale, offset) = factors.split_once(',').ok_or(invalid)?;
+            (
+                scale.trim().parse().map_err(|_| invalid)?,
+                offset.trim().parse().map_err(|_| invalid)?,
+            )
+        } else {
+            (1.0, 0.0)
+        };
+
+        if !(1..=64).contains(&length) {
+            return Err(ParameterError("DBC signals must be 1 to 64 bits long"));
+        }
+        Ok(Self {
+            start_bit,
+            length,
+            little_endian,
+            signed,
+            scale,
+            offset,
+        })
+    }
+
+    /// Decodes the signal from a frame's data, or returns None if the frame is too short
+    pub fn decode(&self, data: &[u8]) -> Option<f64> {
+        let bit = |position: usize| {
+            data.get(position / 8)
+                .map(|byte| u64::from(byte >> (position % 8) & 1))
+        };
+        let length = usize::from(self.length);
+        let mut raw = 0u64;
+        if self.little_endian {
+            for i in 0..length {
+                raw |= bit(usize::from(self.start_bit) + i)? << i;
+            }
+        } else {
+            // Big endian signals run from the most significant bit of each byte to the next
+            // byte's least significant one
+            let mut position = usize::from(self.start_bit);
+            for i in 0..length {
+                raw = raw << 1 | bit(position)?;
+                if i + 1 < length {
+                    position = if position % 8 == 0 {
+                        position + 15
+                    } else {
+                        position - 1
+                    };
+                }
+            }
+        }
+
+        let value = if self.signed && length < 64 && raw >> (length - 1) & 1 == 1 {
+            (raw | u64::MAX << length) as i64 as f64
+        } else if self.signed {
+            raw as i64 as f64
+        } else {
+            raw as f64
+        };
+        Some(value * self.scale + self.offset)
+    }
+}
+
+/// Parameters for the DbcDecodeBlock
+pub struct Parameters<const N: usize> {
+    pub signals: [DbcSignal; N],
+    /// The age before the decoded values are considered stale
+    pub stale_age: Duration,
+}
+
+impl<const N: usize> Parameters<N> {
+    /// Takes the layout of each signal in DBC syntax, see [`DbcSignal::parse`]
+    pub fn new<S: AsRef<str>>(signals: &[S], stale_age_ms: f64) -> Self {
+        Self::try_new(signals, stale_age_ms).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new<S: AsRef<str>>(
+        signals: &[S],
+        stale_age_ms: f64,
+    ) -> Result<Self, ParameterError> {
+        if signals.len() != N {
+            return Err(ParameterError(
+                "DBC signal specs are incorrectly sized for the number of outputs",
+            ));
+        }
+        let mut parsed = [DbcSignal::parse("0|1@1+")?; N];
+        for (signal, spec) in parsed.iter_mut().zip(signals) {
+            *signal = DbcSignal::parse(spec.as_ref())?;
+        }
+        Ok(Self {
+            signals: parsed,
+            stale_age: duration_from_ms_f64(stale_age_ms),
+        })
+    }
+}
+
+/// Decodes the signals of a CAN frame, laid out as in a DBC file, without generating a codec
+/// for the message.
+///
+/// The block takes the frame's data and outputs the `N` decoded, scaled values, and whether
+/// they're valid: true while the last frame long enough to hold every signal arrived within the
+/// stale age. Shorter frames (including empty input, when no frame arrived) are ignored, and
+/// the values of the last decoded frame are held.
+pub struct DbcDecodeBlock<const N: usize, F: Float = f64> {
+    buffer: ([F; N], bool),
+    stale_check: StaleTracker,
+}
+
+impl<const N: usize, F: Float> Default for DbcDecodeBlock<N, F> {
+    fn default() -> Self {
+        Self {
+            buffer: ([F::zero(); N], false),
+            stale_check: StaleTracker::default(),
+        }
+    }
+}
+
+impl<const N: usize, F: Float> ProcessBlock for DbcDecodeBlock<N, F> {
+    type Parameters = Parameters<N>;
+    type Inputs = ByteSliceSignal;
+    type Output = ([F; N], bool);
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let mut values = [F::zero(); N];
+        let decoded =
+            values
+                .iter_mut()
+                .zip(&parameters.signals)
+                .all(|(value, signal)| match signal.decode(inputs) {
+                    Some(decoded) => {
+                        *value = F::from(decoded).unwrap_or_else(F::zero);
+                        true
+                    }
+                    None => false,
+                });
+        if decoded && !inputs.is_empty() {
+            self.buffer.0 = values;
+            self.stale_check.mark_updated(context.time());
+        }
+        self.buffer.1 = self
+            .stale_check
+            .is_valid(context.time(), parameters.stale_age);
+        self.buffer()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        (&self.buffer.0, self.buffer.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+
+    #[test]
+    fn test_dbc_decode_default_buffer_no_panic() {
+        let block = DbcDecodeBlock::<2>::default();
+        assert_eq!(block.buffer(), (&[0.0, 0.0], false));
+    }
+
+    #[test]
+    fn test_dbc_signal_parse() {
+        assert_eq!(
+            DbcSignal::parse(r#" SG_ EngineSpeed : 24|16@1+ (0.125,0) [0|8031.875] "rpm" X"#),
+            Ok(DbcSignal {
+                start_bit: 24,
+                length: 16,
+                little_endian: true,
+                signed: false,
+                scale: 0.125,
+                offset: 0.0,
+            })
+        );
+        assert_eq!(
+            DbcSignal::parse("7|12@0-"),
+            Ok(DbcSignal {
+                start_bit: 7,
+                length: 12,
+                little_endian: false,
+                signed: true,
+                scale: 1.0,
+                offset: 0.0,
+            })
+        );
+        assert!(DbcSignal::parse("7|12@2+").is_err());
+        assert!(DbcSignal::parse("7|0@1+").is_err());
+        assert!(DbcSignal::parse("7|65@1+").is_err());
+        assert!(DbcSignal::parse("7-12@1+").is_err());
+        assert!(DbcSignal::parse("7|12@1+ (0.5)").is_err());
+    }
+
+    #[test]
+    fn test_dbc_signal_decode() {
+        let data = [0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0];
+        // Little endian, 16 bits from bit 8: bytes 1-2
+        let signal = DbcSignal::parse("8|16@1+").unwrap();
+        assert_eq!(signal.decode(&data), Some(0x5634 as f64));
+        // Little endian, 4 bits from bit 4: high nibble of byte 0
+        let signal = DbcSignal::parse("4|4@1+").unwrap();
+        assert_eq!(signal.decode(&data), Some(0x1 as f64));
+        // Big endian, 16 bits with the MSB at bit 7: bytes 0-1
+        let signal = DbcSignal::parse("7|16@0+").unwrap();
+        assert_eq!(signal.decode(&data), Some(0x1234 as f64));
+        // Big endian, 12 bits with the MSB at bit 3: low nibble of byte 0, then byte 1
+        let signal = DbcSignal::parse("3|12@0+").unwrap();
+        assert_eq!(signal.decode(&data), Some(0x234 as f64));
+        // Signed and scaled
+        let signal = DbcSignal::parse("56|8@1- (0.5,-10)").unwrap();
+        assert_eq!(signal.decode(&data), Some(-16.0 * 0.5 - 10.0));
+        let signal = DbcSignal::parse("0|64@1-").unwrap();
+        assert_eq!(signal.decode(&data), Some(i64::from_le_bytes(data) as f64));
+        // Past the end of the frame
+        let signal = DbcSignal::parse("60|8@1+").unwrap();
+        assert_eq!(signal.decode(&data), None);
+    }
+
+    #[test]
+    fn test_dbc_decode_block() {
+        let parameters = Parameters::<2>::new(&["0|8@1+ (0.1,0)", "15|16@0-"], 100.0);
+        let mut runtime = StubRuntime::default();
+        let mut block = DbcDecodeBlock::<2>::default();
+
+        let output = block.process(&parameters, &runtime.context(), &[20, 0xFF, 0xFE]);
+        assert_eq!(output, (&[2.0, -2.0], true));
+
+        // Short frames and empty input hold the last values
+        runtime.set_time(Duration::from_millis(50));
+        let output = block.process(&parameters, &runtime.context(), &[30, 0x00]);
+        assert_eq!(output, (&[2.0, -2.0], true));
+        runtime.set_time(Duration::from_millis(150));
+        let output = block.process(&parameters, &runtime.context(), &[]);
+        assert_eq!(output, (&[2.0, -2.0], false));
+
+        assert!(Parameters::<2>::try_new(&["0|8@1+"], 100.0).is_err());
+    }
+}
@@ -10,7 +10,7 @@ pub struct Parameters<T> {
 }
 
 impl<T> Parameters<T> {
-    pub fn new(lower_limit: T, upper_limit: T) -> Self {
+    pub const fn new(lower_limit: T, upper_limit: T) -> Self {
         Self {
             lower_limit,
             upper_limit,
@@ -0,0 +1,281 @@
+use chacha20poly1305::{AeadInPlace, ChaCha20Poly1305, Key, KeyInit, Nonce, Tag};
+use log::debug;
+use pictorus_traits::{BufferProvider, ByteSliceSignal, PassBy, ProcessBlock};
+
+use crate::encryption::{parse_key, split_nonce, NONCE_BYTES, TAG_BYTES};
+use crate::ParameterError;
+
+/// How many nonce prefixes the DecryptBlock remembers the highest accepted counter of
+const TRACKED_PREFIXES: usize = 8;
+
+/// Parameters for the DecryptBlock
+pub struct Parameters {
+    pub key: [u8; 32],
+}
+
+impl Parameters {
+    /// `key` is the 256-bit key shared with the sender, as 64 hex characters
+    pub fn new(key: &str) -> Self {
+        Self::try_new(key).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(key: &str) -> Result<Self, ParameterError> {
+        Ok(Self {
+            key: parse_key(key)?,
+        })
+    }
+}
+
+/// Verifies and decrypts packets sealed by the EncryptBlock.
+///
+/// Outputs the decrypted payload of each valid packet, and an empty output when the input is
+/// empty or the packet is rejected. Packets are rejected if they're truncated, fail
+/// authentication (wrong key or tampered with), don't fit in the output buffer, or replay a
+/// message counter at or below the highest one accepted from the same nonce prefix.
+///
+/// A sender that restarts keeps being accepted as long as its counter keeps increasing, which
+/// the EncryptBlock ensures by putting its session number in the counter's upper bits. The
+/// highest counter is tracked separately for each of the eight most recently accepted nonce
+/// prefixes, so senders that draw a random prefix each time they start (such as the UDP
+/// telemetry logger) can reconnect, and packets interleaved from several senders are all
+/// accepted. A packet with an untracked prefix is accepted and evicts the least recently accepted
+/// prefix, whose packets could then be replayed.
+pub struct DecryptBlock<B: BufferProvider> {
+    buffer: B,
+    /// Prefixes and their highest accepted counter, most recently accepted first
+    high_water: [Option<(u32, u64)>; TRACKED_PREFIXES],
+}
+
+impl<B: BufferProvider> Default for DecryptBlock<B> {
+    fn default() -> Self {
+        Self {
+            buffer: B::default(),
+            high_water: [None; TRACKED_PREFIXES],
+        }
+    }
+}
+
+impl<B: BufferProvider> ProcessBlock for DecryptBlock<B> {
+    type Parameters = Parameters;
+    type Inputs = ByteSliceSignal;
+    type Output = ByteSliceSignal;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        self.buffer.clear();
+        if inputs.is_empty() {
+            return self.buffer.as_slice();
+        }
+        // Either split fails for packets shorter than `ENCRYPTION_OVERHEAD_BYTES`
+        let Some((nonce, (ciphertext, tag))) = inputs
+            .split_first_chunk::<NONCE_BYTES>()
+            .and_then(|(nonce, body)| Some((nonce, body.split_last_chunk::<TAG_BYTES>()?)))
+        else {
+            debug!(
+                "Dropping truncated encrypted packet of {} bytes",
+                inputs.len()
+            );
+            return self.buffer.as_slice();
+        };
+        let (prefix, counter) = split_nonce(nonce);
+        let tracked = self
+            .high_water
+            .iter()
+            .position(|entry| entry.is_some_and(|(tracked, _)| tracked == prefix));
+        if let Some((_, highest)) = tracked.and_then(|index| self.high_water.get(index)?.as_ref()) {
+            if counter <= *highest {
+                debug!("Dropping replayed encrypted packet with counter {counter}");
+                return self.buffer.as_slice();
+            }
+        }
+
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&parameters.key));
+        let written = self.buffer.write_with(ciphertext.len(), |plaintext| {
+            let Some(plaintext) = plaintext.get_mut(..ciphertext.len()) else {
+                return 0;
+            };
+            plaintext.copy_from_slice(ciphertext);
+            match cipher.decrypt_in_place_detached(
+                Nonce::from_slice(nonce),
+                b"",
+                plaintext,
+                Tag::from_slice(tag),
+            ) {
+                Ok(()) => ciphertext.len(),
+                Err(_) => 0,
+            }
+        });
+
+        if written == ciphertext.len() && written > 0 {
+            // Move the prefix to the front, evicting the last one if it's new
+            let end = tracked.unwrap_or(TRACKED_PREFIXES - 1);
+            if let Some(recent) = self.high_water.get_mut(..=end) {
+                recent.rotate_right(1);
+                if let Some(front) = recent.first_mut() {
+                    *front = Some((prefix, counter));
+                }
+            }
+        } else {
+            debug!("Dropping encrypted packet that failed authentication or doesn't fit");
+            // Never output unauthenticated bytes
+            self.buffer.clear();
+        }
+        self.buffer.as_slice()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer.as_slice()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_blocks::encrypt_block::{EncryptBlock, Parameters as EncryptParameters};
+    use crate::testing::StubContext;
+    use alloc::vec::Vec;
+    use pictorus_traits::FixedBuffer;
+
+    const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+    const OTHER_KEY: &str = "ff0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    fn seal(
+        block: &mut EncryptBlock<FixedBuffer<128>>,
+        prefix: f64,
+        session: f64,
+        payload: &[u8],
+    ) -> Vec<u8> {
+        let context = StubContext::default();
+        block
+            .process(
+                &EncryptParameters::new(KEY, prefix),
+                &context,
+                (payload, session),
+            )
+            .to_vec()
+    }
+
+    #[test]
+    fn test_decrypt_default_buffer_no_panic() {
+        let block = DecryptBlock::<FixedBuffer<64>>::default();
+        assert_eq!(block.buffer(), b"");
+    }
+
+    #[test]
+    fn test_decrypt_round_trip() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(KEY);
+        let mut encrypt = EncryptBlock::<FixedBuffer<128>>::default();
+        let mut block = DecryptBlock::<FixedBuffer<64>>::default();
+
+        let packet = seal(&mut encrypt, 3.0, 1.0, b"telemetry");
+        assert_eq!(block.process(&parameters, &context, &packet), b"telemetry");
+        assert_eq!(block.buffer(), b"telemetry");
+        assert_eq!(block.process(&parameters, &context, b""), b"");
+    }
+
+    #[test]
+    fn test_decrypt_rejects_tampered_and_wrong_key() {
+        let context = StubContext::default();
+        let mut encrypt = EncryptBlock::<FixedBuffer<128>>::default();
+        let mut block = DecryptBlock::<FixedBuffer<128>>::default();
+
+        let mut packet = seal(&mut encrypt, 3.0, 1.0, b"telemetry");
+        assert_eq!(
+            block.process(&Parameters::new(OTHER_KEY), &context, &packet),
+            b""
+        );
+
+        packet[14] ^= 0x01;
+        let parameters = Parameters::new(KEY);
+        assert_eq!(block.process(&parameters, &context, &packet), b"");
+        assert_eq!(block.process(&parameters, &context, &packet[..20]), b"");
+    }
+
+    #[test]
+    fn test_decrypt_rejects_replays() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(KEY);
+        let mut encrypt = EncryptBlock::<FixedBuffer<128>>::default();
+        let mut block = DecryptBlock::<FixedBuffer<128>>::default();
+
+        let first = seal(&mut encrypt, 3.0, 1.0, b"one");
+        let second = seal(&mut encrypt, 3.0, 1.0, b"two");
+        assert_eq!(block.process(&parameters, &context, &second), b"two");
+        assert_eq!(block.process(&parameters, &context, &first), b"");
+        assert_eq!(block.process(&parameters, &context, &second), b"");
+    }
+
+    #[test]
+    fn test_decrypt_accepts_restarted_sender() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(KEY);
+        let mut block = DecryptBlock::<FixedBuffer<128>>::default();
+
+        let mut encrypt = EncryptBlock::<FixedBuffer<128>>::default();
+        let first_session: Vec<_> = (0..3)
+            .map(|_| seal(&mut encrypt, 3.0, 1.0, b"before"))
+            .collect();
+        for packet in &first_session {
+            assert_eq!(block.process(&parameters, &context, packet), b"before");
+        }
+
+        // The sender restarts with the next boot count as its session
+        let mut restarted = EncryptBlock::<FixedBuffer<128>>::default();
+        let packet = seal(&mut restarted, 3.0, 2.0, b"after");
+        assert_eq!(block.process(&parameters, &context, &packet), b"after");
+        let packet = seal(&mut restarted, 3.0, 2.0, b"again");
+        assert_eq!(block.process(&parameters, &context, &packet), b"again");
+        // The previous session can't be replayed
+        assert_eq!(block.process(&parameters, &context, &first_session[2]), b"");
+
+        // A sender with a new prefix starts a new session
+        let mut other = EncryptBlock::<FixedBuffer<128>>::default();
+        let packet = seal(&mut other, 4.0, 1.0, b"other");
+        assert_eq!(block.process(&parameters, &context, &packet), b"other");
+    }
+
+    #[test]
+    fn test_decrypt_tracks_interleaved_senders() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(KEY);
+        let mut block = DecryptBlock::<FixedBuffer<128>>::default();
+        let mut first = EncryptBlock::<FixedBuffer<128>>::default();
+        let mut second = EncryptBlock::<FixedBuffer<128>>::default();
+
+        let old = seal(&mut first, 3.0, 1.0, b"old");
+        assert_eq!(block.process(&parameters, &context, &old), b"old");
+        let packet = seal(&mut second, 4.0, 1.0, b"second");
+        assert_eq!(block.process(&parameters, &context, &packet), b"second");
+        let packet = seal(&mut first, 3.0, 1.0, b"first");
+        assert_eq!(block.process(&parameters, &context, &packet), b"first");
+        // Switching prefixes doesn't forget the first sender's counter
+        assert_eq!(block.process(&parameters, &context, &old), b"");
+
+        // Once enough other prefixes have been accepted, the first one is evicted
+        for prefix in 0..TRACKED_PREFIXES {
+            let mut sender = EncryptBlock::<FixedBuffer<128>>::default();
+            let packet = seal(&mut sender, 10.0 + prefix as f64, 1.0, b"new");
+            assert_eq!(block.process(&parameters, &context, &packet), b"new");
+        }
+        assert_eq!(block.process(&parameters, &context, &old), b"old");
+    }
+
+    #[test]
+    fn test_decrypt_drops_payload_too_large_for_buffer() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(KEY);
+        let mut encrypt = EncryptBlock::<FixedBuffer<128>>::default();
+        let mut block = DecryptBlock::<FixedBuffer<4>>::default();
+
+        let packet = seal(&mut encrypt, 3.0, 1.0, b"too long");
+        assert_eq!(block.process(&parameters, &context, &packet), b"");
+        let packet = seal(&mut encrypt, 3.0, 1.0, b"fits");
+        assert_eq!(block.process(&parameters, &context, &packet), b"fits");
+    }
+}
@@ -68,7 +68,9 @@ impl<T: Pass + Default + Copy + CopyInto<T>, const N: usize> ProcessBlock for De
         }
 
         // Now store the current input in the sample buffer
-        T::copy_into(inputs, &mut self.samples[self.sample_index]);
+        if let Some(sample) = self.samples.get_mut(self.sample_index) {
+            T::copy_into(inputs, sample);
+        }
 
         // Increment the sample index, wrapping at N (and setting initial_accumulation to false)
         self.sample_index += 1;
@@ -1,4 +1,6 @@
+use crate::stale_tracker::elapsed;
 use crate::traits::Scalar;
+use crate::ParameterError;
 use core::time::Duration;
 use pictorus_traits::{Context, Matrix, Pass, PassBy, ProcessBlock};
 
@@ -153,7 +155,7 @@ fn debounce(
     if input {
         *state = Some(curr_time);
     } else if let Some(d) = state {
-        if curr_time - *d >= delay {
+        if elapsed(curr_time, *d).unwrap_or_default() >= delay {
             output = true;
             *state = None;
         }
@@ -172,7 +174,7 @@ fn throttle(
 ) -> bool {
     let mut output = false;
     if let Some(d) = state {
-        if curr_time - *d >= delay {
+        if elapsed(curr_time, *d).unwrap_or_default() >= delay {
             *state = None;
         }
     }
@@ -200,10 +202,23 @@ pub struct Parameters {
 
 impl Parameters {
     pub fn new(delay: f64, method: &str) -> Self {
-        Self {
-            delay: Duration::from_secs_f64(delay),
-            method: method.parse().unwrap(),
-        }
+        Self::try_new(delay, method).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(delay: f64, method: &str) -> Result<Self, ParameterError> {
+        Ok(Self {
+            delay: Duration::try_from_secs_f64(delay)
+                .map_err(|_| ParameterError("Delay must be a non-negative number of seconds"))?,
+            method: method
+                .parse()
+                .map_err(|_| ParameterError("Failed to parse method"))?,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved delay and method, without parsing
+    pub const fn from_method(delay: Duration, method: DelayControlMethod) -> Self {
+        Self { delay, method }
     }
 }
 
@@ -221,6 +236,14 @@ mod tests {
         assert_eq!(block.buffer(), &Matrix::<2, 2, f64>::zeroed());
     }
 
+    #[test]
+    fn test_delay_control_parameters_validation() {
+        assert!(Parameters::try_new(0.5, "Debounce").is_ok());
+        assert!(Parameters::try_new(-0.5, "Debounce").is_err());
+        assert!(Parameters::try_new(f64::NAN, "Throttle").is_err());
+        assert!(Parameters::try_new(0.5, "Sample").is_err());
+    }
+
     #[test]
     fn test_scalar_throttle() {
         let mut runtime = StubRuntime::default(); // Time is 0 timestep is 100ms
@@ -9,6 +9,19 @@ pub struct DerivativeBlock<T: Pass + Default + Copy, const N: usize> {
     output: T,
 }
 
+/// The timestep used once initial accumulation is done. With `panic-free`, a missing timestep
+/// holds the previous output instead of panicking.
+fn timestep(context: &dyn pictorus_traits::Context) -> Option<core::time::Duration> {
+    #[cfg(not(feature = "panic-free"))]
+    return Some(
+        context
+            .timestep()
+            .expect("timestep should never be None outside of Initial Accumulation phase"),
+    );
+    #[cfg(feature = "panic-free")]
+    return context.timestep();
+}
+
 impl<const N: usize, T: Pass + Default + Copy> Default for DerivativeBlock<T, N> {
     fn default() -> Self {
         const {
@@ -32,7 +45,9 @@ impl<T: Float, const N: usize> ProcessBlock for DerivativeBlock<T, N> {
         inputs: pictorus_traits::PassBy<'_, Self::Inputs>,
     ) -> pictorus_traits::PassBy<'b, Self::Output> {
         // store the current input in the sample buffer
-        self.samples[self.sample_index] = inputs;
+        if let Some(sample) = self.samples.get_mut(self.sample_index) {
+            *sample = inputs;
+        }
 
         // increment the sample index, wrapping at N (and setting initial_accumulation to false)
         self.sample_index += 1;
@@ -43,11 +58,10 @@ impl<T: Float, const N: usize> ProcessBlock for DerivativeBlock<T, N> {
 
         // Only set the output when initial accumulation is done, otherwise use the IC
         if !self.initial_accumulation {
-            self.output = (inputs - self.samples[self.sample_index])
-                / ((T::from_usize(N).unwrap() - T::one())
-                    * T::from_duration(context.timestep().expect(
-                        "timestep should never be None outside of Initial Accumulation phase",
-                    )));
+            if let Some(timestep) = timestep(context) {
+                self.output = (inputs - self.samples[self.sample_index])
+                    / ((T::from_usize(N).unwrap() - T::one()) * T::from_duration(timestep));
+            }
         }
 
         self.output.as_by()
@@ -83,7 +97,9 @@ impl<T: Float, const N: usize, const NCOLS: usize, const NROWS: usize> ProcessBl
         inputs: pictorus_traits::PassBy<'_, Self::Inputs>,
     ) -> pictorus_traits::PassBy<'b, Self::Output> {
         // store the current input in the sample buffer
-        self.samples[self.sample_index] = *inputs;
+        if let Some(sample) = self.samples.get_mut(self.sample_index) {
+            *sample = *inputs;
+        }
 
         // increment the sample index, wrapping at N (and setting initial_accumulation to false)
         self.sample_index += 1;
@@ -94,12 +110,11 @@ impl<T: Float, const N: usize, const NCOLS: usize, const NROWS: usize> ProcessBl
 
         // Only set the output when initial accumulation is done, otherwise use the IC
         if !self.initial_accumulation {
-            let output = (inputs.as_view() - self.samples[self.sample_index].as_view())
-                / ((T::from_usize(N).unwrap() - T::one())
-                    * T::from_duration(context.timestep().expect(
-                        "timestep should never be None outside of Initial Accumulation phase",
-                    )));
-            self.output.as_view_mut().copy_from(&output);
+            if let Some(timestep) = timestep(context) {
+                let output = (inputs.as_view() - self.samples[self.sample_index].as_view())
+                    / ((T::from_usize(N).unwrap() - T::one()) * T::from_duration(timestep));
+                self.output.as_view_mut().copy_from(&output);
+            }
         }
 
         &self.output
@@ -0,0 +1,217 @@
+use chacha20poly1305::{AeadInPlace, ChaCha20Poly1305, Key, KeyInit, Nonce};
+use log::warn;
+use pictorus_traits::{BufferProvider, ByteSliceSignal, PassBy, ProcessBlock};
+
+use crate::encryption::{nonce, parse_key, ENCRYPTION_OVERHEAD_BYTES, NONCE_BYTES};
+use crate::ParameterError;
+
+/// Parameters for the EncryptBlock
+pub struct Parameters {
+    pub key: [u8; 32],
+    /// Identifies this sender in the nonce of each packet
+    pub nonce_prefix: u32,
+}
+
+impl Parameters {
+    /// `key` is the 256-bit key shared with the receiver, as 64 hex characters
+    pub fn new(key: &str, nonce_prefix: f64) -> Self {
+        Self::try_new(key, nonce_prefix).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(key: &str, nonce_prefix: f64) -> Result<Self, ParameterError> {
+        Ok(Self {
+            key: parse_key(key)?,
+            nonce_prefix: nonce_prefix as u32,
+        })
+    }
+}
+
+/// Seals byte payloads with ChaCha20-Poly1305 so they can be sent over untrusted links.
+///
+/// The block takes the payload and a session number, which must be different every time the
+/// app starts, typically the boot count from a PersistentCounterBlock. Sessions must be whole
+/// numbers from 1 to 2^32 - 1: a payload with any other session, including NaN or infinity, is
+/// dropped, as the session couldn't be told apart from another one. Each non-empty payload is
+/// output as a packet containing the nonce, the encrypted payload and an authentication tag
+/// (28 bytes longer than the payload), which the DecryptBlock verifies and decrypts. Empty
+/// payloads produce an empty output. Payloads that don't fit in the output buffer are dropped.
+///
+/// A nonce must never be reused with the same key. The nonce is the configured prefix, which
+/// every sender sharing a key needs its own of, followed by a counter whose upper 32 bits are
+/// the session and lower 32 bits count the packets sent this session. Restarts with an
+/// increasing session therefore keep the counter increasing, so the DecryptBlock accepts the
+/// restarted sender without losing its replay protection. Once a session has sent 2^32 packets,
+/// further payloads are dropped.
+pub struct EncryptBlock<B: BufferProvider> {
+    buffer: B,
+    packets: u32,
+}
+
+impl<B: BufferProvider> Default for EncryptBlock<B> {
+    fn default() -> Self {
+        Self {
+            buffer: B::default(),
+            packets: 0,
+        }
+    }
+}
+
+impl<B: BufferProvider> ProcessBlock for EncryptBlock<B> {
+    type Parameters = Parameters;
+    type Inputs = (ByteSliceSignal, f64);
+    type Output = ByteSliceSignal;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (inputs, session) = inputs;
+        self.buffer.clear();
+        if inputs.is_empty() {
+            return self.buffer.as_slice();
+        }
+
+        if !(1.0..=f64::from(u32::MAX)).contains(&session) {
+            warn!("Invalid encryption session {session}, dropping payload");
+            return self.buffer.as_slice();
+        }
+        let Some(packets) = self.packets.checked_add(1) else {
+            warn!("Encryption nonce counter exhausted, dropping payload");
+            return self.buffer.as_slice();
+        };
+        let counter = u64::from(session as u32) << 32 | u64::from(packets);
+        let nonce = nonce(parameters.nonce_prefix, counter);
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&parameters.key));
+        let packet_len = inputs.len() + ENCRYPTION_OVERHEAD_BYTES;
+
+        let written = self.buffer.write_with(packet_len, |packet| {
+            if packet.len() < packet_len {
+                return 0;
+            }
+            let (header, body) = packet.split_at_mut(NONCE_BYTES);
+            let (ciphertext, tag_bytes) = body.split_at_mut(inputs.len());
+            header.copy_from_slice(&nonce);
+            ciphertext.copy_from_slice(inputs);
+            match cipher.encrypt_in_place_detached(Nonce::from_slice(&nonce), b"", ciphertext) {
+                Ok(tag) => {
+                    tag_bytes.copy_from_slice(tag.as_slice());
+                    packet_len
+                }
+                Err(_) => 0,
+            }
+        });
+
+        if written == 0 {
+            warn!(
+                "Payload of {} bytes doesn't fit in the encryption buffer",
+                inputs.len()
+            );
+        } else {
+            self.packets = packets;
+        }
+        self.buffer.as_slice()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer.as_slice()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use pictorus_traits::FixedBuffer;
+
+    const KEY: &str = "808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f";
+
+    #[test]
+    fn test_encrypt_default_buffer_no_panic() {
+        let block = EncryptBlock::<FixedBuffer<64>>::default();
+        assert_eq!(block.buffer(), b"");
+    }
+
+    #[test]
+    fn test_encrypt_rfc8439_vector() {
+        // AEAD test vector from RFC 8439 section 2.8.2, without the associated data
+        let context = StubContext::default();
+        let parameters = Parameters::new(KEY, 0x07000000 as f64);
+        let mut block = EncryptBlock::<FixedBuffer<128>> {
+            packets: 0x44454647 - 1,
+            ..Default::default()
+        };
+
+        let plaintext = b"Ladies and Gentlemen of the class of '99";
+        let packet = block.process(&parameters, &context, (plaintext, 0x40414243 as f64));
+        assert_eq!(packet.len(), plaintext.len() + ENCRYPTION_OVERHEAD_BYTES);
+        assert_eq!(
+            &packet[..12],
+            &[0x07, 0, 0, 0, 0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47]
+        );
+        // The keystream matches the RFC's ciphertext regardless of the associated data
+        assert_eq!(&packet[12..16], &[0xd3, 0x1a, 0x8d, 0x34]);
+    }
+
+    #[test]
+    fn test_encrypt_counter_advances() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(KEY, 1.0);
+        let mut block = EncryptBlock::<FixedBuffer<64>>::default();
+
+        assert_eq!(block.process(&parameters, &context, (b"", 5.0)), b"");
+
+        let first = block
+            .process(&parameters, &context, (b"hello", 5.0))
+            .to_vec();
+        let second = block
+            .process(&parameters, &context, (b"hello", 5.0))
+            .to_vec();
+        assert_eq!(&first[..12], &[0, 0, 0, 1, 0, 0, 0, 5, 0, 0, 0, 1]);
+        assert_eq!(&second[..12], &[0, 0, 0, 1, 0, 0, 0, 5, 0, 0, 0, 2]);
+        // The same payload never encrypts to the same bytes
+        assert_ne!(first[12..], second[12..]);
+
+        // A restarted sender's counter continues above the previous session's
+        let mut restarted = EncryptBlock::<FixedBuffer<64>>::default();
+        let third = restarted.process(&parameters, &context, (b"hello", 6.0));
+        assert_eq!(&third[..12], &[0, 0, 0, 1, 0, 0, 0, 6, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_encrypt_drops_payload_too_large_for_buffer() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(KEY, 1.0);
+        let mut block = EncryptBlock::<FixedBuffer<32>>::default();
+
+        assert_eq!(block.process(&parameters, &context, (b"12345", 1.0)), b"");
+        assert_eq!(
+            block.process(&parameters, &context, (b"1234", 1.0)).len(),
+            32
+        );
+    }
+
+    #[test]
+    fn test_encrypt_drops_invalid_session() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(KEY, 1.0);
+        let mut block = EncryptBlock::<FixedBuffer<64>>::default();
+
+        for session in [0.0, -1.0, 0.5, f64::NAN, f64::INFINITY, 2f64.powi(32)] {
+            assert_eq!(
+                block.process(&parameters, &context, (b"hello", session)),
+                b""
+            );
+        }
+        // Dropped payloads don't use up the counter
+        let packet = block.process(&parameters, &context, (b"hello", 1.0));
+        assert_eq!(&packet[..12], &[0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_encrypt_invalid_key() {
+        assert!(Parameters::try_new("not a key", 0.0).is_err());
+    }
+}
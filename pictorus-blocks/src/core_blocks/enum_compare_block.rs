@@ -0,0 +1,98 @@
+use pictorus_traits::{EnumSignal, PassBy, ProcessBlock, SignalEnum};
+
+use crate::ParameterError;
+
+/// Parameters for the EnumCompareBlock
+pub struct Parameters<E: SignalEnum> {
+    /// The value the input is compared against
+    pub value: E,
+}
+
+impl<E: SignalEnum> Parameters<E> {
+    /// `value` is the name of one of the enum's variants
+    pub fn new(value: &str) -> Self {
+        Self::try_new(value).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(value: &str) -> Result<Self, ParameterError> {
+        Ok(Self {
+            value: E::from_name(value).ok_or(ParameterError("Unknown enum value"))?,
+        })
+    }
+}
+
+/// Outputs true while the input enum signal equals a given value, e.g. while the flight mode is
+/// `Mission`.
+pub struct EnumCompareBlock<E: SignalEnum> {
+    buffer: bool,
+    phantom: core::marker::PhantomData<E>,
+}
+
+impl<E: SignalEnum> Default for EnumCompareBlock<E> {
+    fn default() -> Self {
+        Self {
+            buffer: false,
+            phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<E: SignalEnum> ProcessBlock for EnumCompareBlock<E> {
+    type Inputs = EnumSignal<E>;
+    type Output = bool;
+    type Parameters = Parameters<E>;
+
+    fn process(
+        &mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<Self::Inputs>,
+    ) -> PassBy<'_, Self::Output> {
+        self.buffer = input == parameters.value;
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use pictorus_traits::signal_enum;
+
+    signal_enum! {
+        enum FlightMode {
+            Manual = 0,
+            Stabilized = 1,
+            Mission = 4,
+        }
+    }
+
+    #[test]
+    fn test_enum_compare_default_buffer_no_panic() {
+        let block = EnumCompareBlock::<FlightMode>::default();
+        assert!(!block.buffer());
+    }
+
+    #[test]
+    fn test_enum_compare_block() {
+        let context = StubContext::default();
+        let parameters = Parameters::new("Mission");
+        let mut block = EnumCompareBlock::<FlightMode>::default();
+
+        assert!(!block.process(&parameters, &context, FlightMode::Manual));
+        assert!(block.process(&parameters, &context, FlightMode::Mission));
+        assert!(block.buffer());
+        assert!(!block.process(&parameters, &context, FlightMode::Stabilized));
+    }
+
+    #[test]
+    fn test_enum_compare_parameters() {
+        assert!(Parameters::<FlightMode>::try_new("Stabilized").is_ok());
+        assert!(Parameters::<FlightMode>::try_new("Acro").is_err());
+    }
+}
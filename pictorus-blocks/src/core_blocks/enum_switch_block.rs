@@ -0,0 +1,197 @@
+use pictorus_traits::{EnumSignal, Pass, PassBy, ProcessBlock, SignalEnum};
+
+use crate::traits::{CopyInto, DefaultStorage};
+use crate::ParameterError;
+
+/// Parameters for the EnumSwitchBlock
+pub struct Parameters<E: SignalEnum, const N: usize> {
+    /// The enum value that selects each input. The last input is also output for values that
+    /// don't match any case.
+    pub cases: [E; N],
+}
+
+impl<E: SignalEnum, const N: usize> Parameters<E, N> {
+    /// `cases` are names of the enum's variants
+    pub fn new(cases: [&str; N]) -> Self {
+        Self::try_new(cases).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(cases: [&str; N]) -> Result<Self, ParameterError> {
+        let mut values = [E::default(); N];
+        for (value, case) in values.iter_mut().zip(cases) {
+            *value = E::from_name(case).ok_or(ParameterError("Unknown enum value"))?;
+        }
+        Ok(Self { cases: values })
+    }
+}
+
+/// Switches between input signals based on the value of an enum signal, e.g. picking the
+/// controller output for the current flight mode.
+///
+/// The enum signal is the first input, and the rest are the signals to switch between. The block
+/// outputs the input whose case matches the enum value, or the last input if none match.
+pub struct EnumSwitchBlock<T: Apply>
+where
+    T::Output: DefaultStorage,
+{
+    buffer: <T::Output as DefaultStorage>::Storage,
+}
+
+impl<T: Apply> Default for EnumSwitchBlock<T>
+where
+    T::Output: DefaultStorage,
+{
+    fn default() -> Self {
+        Self {
+            buffer: T::Output::default_storage(),
+        }
+    }
+}
+
+impl<T: Apply> ProcessBlock for EnumSwitchBlock<T>
+where
+    T::Output: DefaultStorage,
+{
+    type Inputs = T;
+    type Output = T::Output;
+    type Parameters = T::Parameters;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        T::apply(inputs, parameters, &mut self.buffer);
+        T::Output::from_storage(&self.buffer)
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        T::Output::from_storage(&self.buffer)
+    }
+}
+
+pub trait Apply: Pass {
+    type Parameters;
+    type Output: Pass + DefaultStorage;
+
+    fn apply(
+        input: PassBy<Self>,
+        params: &Self::Parameters,
+        buffer: &mut <Self::Output as DefaultStorage>::Storage,
+    );
+}
+
+/// Implements `Apply` for an enum signal followed by `N` inputs of the same type
+macro_rules! impl_enum_switch_apply {
+    ($( $n:literal => ( $($i:tt),+ ) ),+ $(,)?) => {
+        $(
+            impl<E, T> Apply for (EnumSignal<E>, $(impl_enum_switch_apply!(@input $i T)),+)
+            where
+                E: SignalEnum,
+                T: Pass + DefaultStorage + CopyInto<<T as DefaultStorage>::Storage>,
+            {
+                type Output = T;
+                type Parameters = Parameters<E, $n>;
+
+                fn apply(
+                    input: PassBy<Self>,
+                    params: &Self::Parameters,
+                    buffer: &mut <Self::Output as DefaultStorage>::Storage,
+                ) {
+                    let inputs = [$(input.$i),+];
+                    let idx = params.cases
+                        .iter()
+                        .position(|case| *case == input.0)
+                        .unwrap_or($n - 1);
+                    T::copy_into(inputs[idx], buffer);
+                }
+            }
+        )+
+    };
+    (@input $i:tt $T:ident) => { $T };
+}
+
+impl_enum_switch_apply!(
+    2 => (1, 2),
+    3 => (1, 2, 3),
+    4 => (1, 2, 3, 4),
+    5 => (1, 2, 3, 4, 5),
+    6 => (1, 2, 3, 4, 5, 6),
+    7 => (1, 2, 3, 4, 5, 6, 7),
+    8 => (1, 2, 3, 4, 5, 6, 7, 8),
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use pictorus_traits::{signal_enum, Matrix};
+
+    signal_enum! {
+        enum FlightMode {
+            Manual = 0,
+            Stabilized = 1,
+            Mission = 4,
+        }
+    }
+
+    #[test]
+    fn test_enum_switch_default_buffer_no_panic() {
+        let block = EnumSwitchBlock::<(EnumSignal<FlightMode>, f64, f64)>::default();
+        assert_eq!(block.buffer(), 0.0);
+    }
+
+    #[test]
+    fn test_enum_switch_scalar() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(["Stabilized", "Mission", "Manual"]);
+        let mut block = EnumSwitchBlock::<(EnumSignal<FlightMode>, f64, f64, f64)>::default();
+
+        let inputs = |mode| (mode, 1.0, 2.0, 3.0);
+        assert_eq!(
+            block.process(&parameters, &context, inputs(FlightMode::Stabilized)),
+            1.0
+        );
+        assert_eq!(
+            block.process(&parameters, &context, inputs(FlightMode::Mission)),
+            2.0
+        );
+        assert_eq!(
+            block.process(&parameters, &context, inputs(FlightMode::Manual)),
+            3.0
+        );
+        assert_eq!(block.buffer(), 3.0);
+    }
+
+    #[test]
+    fn test_enum_switch_default_case() {
+        let context = StubContext::default();
+        // Manual isn't listed, so it selects the last input
+        let parameters = Parameters::new(["Mission", "Mission"]);
+        let mut block = EnumSwitchBlock::<(
+            EnumSignal<FlightMode>,
+            Matrix<1, 2, f64>,
+            Matrix<1, 2, f64>,
+        )>::default();
+
+        let hold = Matrix {
+            data: [[0.0], [0.0]],
+        };
+        let track = Matrix {
+            data: [[1.0], [2.0]],
+        };
+        let output = block.process(&parameters, &context, (FlightMode::Mission, &track, &hold));
+        assert_eq!(output.data, track.data);
+        let output = block.process(&parameters, &context, (FlightMode::Manual, &track, &hold));
+        assert_eq!(output.data, hold.data);
+    }
+
+    #[test]
+    fn test_enum_switch_parameters() {
+        let parameters = Parameters::<FlightMode, 2>::new(["Mission", "Manual"]);
+        assert_eq!(parameters.cases, [FlightMode::Mission, FlightMode::Manual]);
+        assert!(Parameters::<FlightMode, 2>::try_new(["Mission", "Acro"]).is_err());
+    }
+}
@@ -39,10 +39,12 @@ impl<S: Scalar + num_traits::Float + num_traits::Zero> ProcessBlock for Exponent
     ) -> PassBy<'b, Self::Output> {
         let mut inputs_local = inputs;
         if (inputs < S::zero()) && (parameters.coefficient < S::one()) {
-            if !parameters.preserve_sign {
-                panic!("Negative input to Exponent with coefficient < 1.0!");
-            } else {
+            if parameters.preserve_sign {
                 inputs_local = inputs_local.abs();
+            } else {
+                // With `panic-free` the result is NaN, as for any fractional power of a negative
+                #[cfg(not(feature = "panic-free"))]
+                panic!("Negative input to Exponent with coefficient < 1.0!");
             }
         }
         self.output = inputs_local.powf(parameters.coefficient);
@@ -81,10 +83,11 @@ impl<S: Scalar + num_traits::Float + num_traits::Zero, const NROWS: usize, const
             .for_each(|x| {
                 let mut x_local = *x;
                 if (x_local < S::zero()) && (parameters.coefficient < S::one()) {
-                    if !parameters.preserve_sign {
-                        panic!("Negative input to Exponent with coefficient < 1.0!");
-                    } else {
+                    if parameters.preserve_sign {
                         x_local = x_local.abs();
+                    } else {
+                        #[cfg(not(feature = "panic-free"))]
+                        panic!("Negative input to Exponent with coefficient < 1.0!");
                     }
                 }
                 x_local = x_local.powf(parameters.coefficient);
@@ -180,6 +183,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(feature = "panic-free"))]
     #[should_panic]
     fn test_root_negative_input_no_preserve_sign_panic() {
         let context = StubContext::default();
@@ -189,6 +193,16 @@ mod tests {
         block.process(&parameters, &context, input.as_by());
     }
 
+    #[test]
+    #[cfg(feature = "panic-free")]
+    fn test_root_negative_input_no_preserve_sign_nan() {
+        let context = StubContext::default();
+        let mut block = ExponentBlock::<f64>::default();
+        let parameters = Parameters::new(0.5, false);
+        let output = block.process(&parameters, &context, -4.0);
+        assert!(output.is_nan());
+    }
+
     #[test]
     fn test_exponent_block_matrix() {
         let context = StubContext::default();
@@ -228,6 +242,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(feature = "panic-free"))]
     #[should_panic]
     fn test_root_matrix_negative_input_no_preserve_sign_panic() {
         let context = StubContext::default();
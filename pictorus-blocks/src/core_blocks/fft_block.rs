@@ -1,37 +1,33 @@
-use crate::traits::Float;
+use crate::dsp::DspFloat;
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
-use rustfft::{num_complex::Complex, Fft, FftPlanner};
-use std::sync::Arc;
 
 /// FFT Block performs FFT on samples it accumulates.
 ///
 /// On each time step a new sample is added to the buffer. When the buffer is full, FFT is performed on the samples.
-/// The size of this buffer is set by the generic parameter `N`.
-pub struct FftBlock<T: Float, const N: usize> {
+/// The size of this buffer is set by the generic parameter `N`. Power of two sizes use a radix-2 FFT,
+/// other sizes fall back to a direct DFT.
+///
+/// For windowed, single-sided or averaged spectra see the [`SpectrumBlock`](crate::SpectrumBlock).
+pub struct FftBlock<T: DspFloat, const N: usize> {
     /// Samples buffer that stores samples as we accumulate them.
     samples: [T; N],
     /// Index of the next sample to be added to the buffer.
     sample_index: usize,
     /// Output of the FFT block, only updated when the buffer is full.
     output: Matrix<2, N, T>,
-    /// FFT planner used to create the FFT object.
-    fft: Arc<dyn Fft<T>>,
 }
 
-impl<T: Float, const N: usize> Default for FftBlock<T, N> {
+impl<T: DspFloat, const N: usize> Default for FftBlock<T, N> {
     fn default() -> Self {
-        let mut planner = FftPlanner::<T>::new();
-        let fft = planner.plan_fft_forward(N);
         Self {
             samples: [T::default(); N],
             sample_index: 0,
             output: Matrix::zeroed(),
-            fft,
         }
     }
 }
 
-impl<T: Float, const N: usize> ProcessBlock for FftBlock<T, N> {
+impl<T: DspFloat, const N: usize> ProcessBlock for FftBlock<T, N> {
     type Inputs = T;
     type Output = Matrix<2, N, T>;
     type Parameters = Parameters;
@@ -42,18 +38,17 @@ impl<T: Float, const N: usize> ProcessBlock for FftBlock<T, N> {
         _context: &dyn pictorus_traits::Context,
         inputs: pictorus_traits::PassBy<'_, Self::Inputs>,
     ) -> pictorus_traits::PassBy<'b, Self::Output> {
-        self.samples[self.sample_index] = inputs;
+        if let Some(sample) = self.samples.get_mut(self.sample_index) {
+            *sample = inputs;
+        }
 
         if self.sample_index >= N - 1 {
             self.sample_index = 0;
-            let mut data_buf: [Complex<T>; N] = [Complex::new(T::zero(), T::zero()); N];
-            self.samples.iter().enumerate().for_each(|(i, &sample)| {
-                data_buf[i].re = sample;
-            });
-            self.fft.process(&mut data_buf);
-            for (i, buf_val) in data_buf.iter().enumerate().take(N) {
-                self.output.data[i][0] = buf_val.re;
-                self.output.data[i][1] = buf_val.im;
+            let mut re = [T::default(); N];
+            let mut im = [T::default(); N];
+            T::real_fft(&self.samples, &mut re, &mut im);
+            for (i, bin) in self.output.data.iter_mut().enumerate() {
+                *bin = [re[i], im[i]];
             }
         } else {
             self.sample_index += 1;
@@ -1,5 +1,8 @@
+use crate::dsp::{first_order, DspFloat};
+use crate::stale_tracker::elapsed;
 use crate::traits::Float;
 use crate::traits::{MatrixOps, Scalar};
+use crate::ParameterError;
 use core::time::Duration;
 use pictorus_traits::{HasIc, Matrix, Pass, PassBy, ProcessBlock};
 
@@ -36,7 +39,7 @@ impl<T: Pass + Default> Default for FrequencyFilterBlock<T> {
 
 impl<T> HasIc for FrequencyFilterBlock<T>
 where
-    T: Pass + Default + Float,
+    T: Pass + Default + DspFloat,
 {
     fn new(parameters: &Self::Parameters) -> Self {
         FrequencyFilterBlock::<T> {
@@ -53,7 +56,7 @@ where
 impl<T, const NROWS: usize, const NCOLS: usize> HasIc
     for FrequencyFilterBlock<Matrix<NROWS, NCOLS, T>>
 where
-    T: Pass + Default + Float + Scalar,
+    T: Pass + Default + DspFloat + Scalar,
 {
     fn new(parameters: &Self::Parameters) -> Self {
         FrequencyFilterBlock::<pictorus_traits::Matrix<NROWS, NCOLS, T>> {
@@ -69,7 +72,7 @@ where
 
 impl<T> ProcessBlock for FrequencyFilterBlock<T>
 where
-    T: Pass + Default + Float,
+    T: Pass + Default + DspFloat,
 {
     type Inputs = T;
     type Output = T;
@@ -82,16 +85,14 @@ where
         inputs: PassBy<'_, Self::Inputs>,
     ) -> PassBy<'b, Self::Output> {
         if let Some(previous_data) = &self.prev_data {
-            let timestep = context.time() - previous_data.prev_time;
+            let timestep = elapsed(context.time(), previous_data.prev_time).unwrap_or_default();
             let alpha = compute_alpha(parameters.method, parameters.cutoff_frequency, timestep);
-            self.output = match parameters.method {
-                FrequencyFilterEnum::HighPass => {
-                    alpha * (previous_data.prev_output + inputs - previous_data.prev_input)
-                }
-                FrequencyFilterEnum::LowPass => {
-                    previous_data.prev_output + alpha * (inputs - previous_data.prev_output)
-                }
-            };
+            self.output = first_order(
+                coefficients(parameters.method, alpha),
+                inputs,
+                previous_data.prev_input,
+                previous_data.prev_output,
+            );
         } else {
             self.output = inputs;
         }
@@ -111,7 +112,7 @@ where
 impl<T, const NROWS: usize, const NCOLS: usize> ProcessBlock
     for FrequencyFilterBlock<Matrix<NROWS, NCOLS, T>>
 where
-    T: Pass + Default + Float + Scalar,
+    T: Pass + Default + DspFloat + Scalar,
 {
     type Inputs = Matrix<NROWS, NCOLS, T>;
     type Output = Matrix<NROWS, NCOLS, T>;
@@ -124,16 +125,16 @@ where
         inputs: PassBy<'_, Self::Inputs>,
     ) -> PassBy<'b, Self::Output> {
         if let Some(previous_data) = &self.prev_data {
-            let timestep = context.time() - previous_data.prev_time;
+            let timestep = elapsed(context.time(), previous_data.prev_time).unwrap_or_default();
             let alpha = compute_alpha(parameters.method, parameters.cutoff_frequency, timestep);
+            let coeffs = coefficients(parameters.method, alpha);
             inputs.for_each(|input, col, row| {
-                let prev_output = previous_data.prev_output.data[col][row];
-                let prev_input = previous_data.prev_input.data[col][row];
-                let output = match parameters.method {
-                    FrequencyFilterEnum::HighPass => alpha * (prev_output + input - prev_input),
-                    FrequencyFilterEnum::LowPass => prev_output + alpha * (input - prev_output),
-                };
-                self.output.data[col][row] = output;
+                self.output.data[col][row] = first_order(
+                    coeffs,
+                    input,
+                    previous_data.prev_input.data[col][row],
+                    previous_data.prev_output.data[col][row],
+                );
             });
         } else {
             self.output.data = inputs.data;
@@ -169,6 +170,16 @@ fn compute_alpha<T: Scalar + Float>(
     }
 }
 
+/// Coefficients of the first order section for the filter, see [`first_order`]. The high pass
+/// filter is `y[n] = alpha * (y[n-1] + x[n] - x[n-1])`, and the low pass filter is
+/// `y[n] = y[n-1] + alpha * (x[n] - y[n-1])`
+fn coefficients<T: Float>(method: FrequencyFilterEnum, alpha: T) -> [T; 3] {
+    match method {
+        FrequencyFilterEnum::HighPass => [alpha, -alpha, alpha],
+        FrequencyFilterEnum::LowPass => [alpha, T::zero(), T::one() - alpha],
+    }
+}
+
 /// Parameters for the FrequencyFilterBlock
 #[derive(Debug, Clone, Copy)]
 pub struct Parameters<T, C: Float> {
@@ -181,10 +192,26 @@ pub struct Parameters<T, C: Float> {
 
 impl<T: Pass, C: Float> Parameters<T, C> {
     pub fn new(ic: T, cutoff_frequency: C, method: &str) -> Self {
+        Self::try_new(ic, cutoff_frequency, method).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(ic: T, cutoff_frequency: C, method: &str) -> Result<Self, ParameterError> {
+        Ok(Self {
+            ic,
+            cutoff_frequency,
+            method: method
+                .parse()
+                .map_err(|_| ParameterError("Failed to parse method"))?,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved filter type, without parsing
+    pub const fn from_method(ic: T, cutoff_frequency: C, method: FrequencyFilterEnum) -> Self {
         Self {
             ic,
             cutoff_frequency,
-            method: method.parse().unwrap(),
+            method,
         }
     }
 }
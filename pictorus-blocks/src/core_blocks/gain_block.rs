@@ -1,6 +1,11 @@
-use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock, Promote, Promotion, Scalar};
+#[cfg(feature = "alloc")]
+use pictorus_traits::DMatrix;
+use pictorus_traits::{Fixed, Matrix, Pass, PassBy, ProcessBlock, Promote, Promotion, Scalar};
 
 /// Multiplies the input by a gain factor.
+///
+/// The input can also be a bundle (tuple) of signals, e.g. `(Matrix<3, 1, f32>, Matrix<4, 4, f32>)`,
+/// which are all multiplied by the same gain.
 pub struct GainBlock<G, T>
 where
     G: Scalar,
@@ -89,6 +94,23 @@ where
     }
 }
 
+impl<G, const FRAC: u32> Apply<G> for Fixed<FRAC>
+where
+    G: Promote<Fixed<FRAC>> + Scalar,
+{
+    type Output = Promotion<G, Fixed<FRAC>>;
+    fn apply<'s>(
+        store: &'s mut Self::Output,
+        input: PassBy<Self>,
+        gain: G,
+    ) -> PassBy<'s, Self::Output> {
+        let output = <G as Promote<Fixed<FRAC>>>::promote_left(gain)
+            * <G as Promote<Fixed<FRAC>>>::promote_right(input);
+        *store = output;
+        output
+    }
+}
+
 impl<const NROWS: usize, const NCOLS: usize, G, T> Apply<G> for Matrix<NROWS, NCOLS, T>
 where
     T: Scalar,
@@ -102,26 +124,78 @@ where
         input: PassBy<Self>,
         gain: G,
     ) -> PassBy<'s, Self::Output> {
-        *store = Matrix::zeroed();
+        let gain = <G as Promote<T>>::promote_left(gain);
         store
             .data
             .as_flattened_mut()
             .iter_mut()
-            .enumerate()
-            .for_each(|(i, lhs)| {
-                *lhs = <G as Promote<T>>::promote_right(input.data.as_flattened()[i])
-                    * <G as Promote<T>>::promote_left(gain);
-            });
+            .zip(input.data.as_flattened())
+            .for_each(|(lhs, rhs)| *lhs = <G as Promote<T>>::promote_right(*rhs) * gain);
+        store
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<G, T> Apply<G> for DMatrix<T>
+where
+    T: Scalar,
+    G: Promote<T>,
+    T: Promote<G>,
+{
+    type Output = DMatrix<Promotion<G, T>>;
+
+    fn apply<'s>(
+        store: &'s mut Self::Output,
+        input: PassBy<Self>,
+        gain: G,
+    ) -> PassBy<'s, Self::Output> {
+        store.reshape_zeroed(input.nrows(), input.ncols());
+        let gain = <G as Promote<T>>::promote_left(gain);
+        store
+            .as_mut_slice()
+            .iter_mut()
+            .zip(input.as_slice())
+            .for_each(|(lhs, rhs)| *lhs = gain * <G as Promote<T>>::promote_right(*rhs));
         store
     }
 }
 
+/// Applies the same gain to every signal in a bundle (tuple), so one block can replace several
+/// identical gain blocks
+macro_rules! impl_bundle_apply {
+    ($( ( $( $T:ident $i:tt ),+ ) ),+ $(,)?) => {
+        $(
+            impl<G: Scalar, $( $T: Apply<G> ),+> Apply<G> for ( $( $T ),+ ) {
+                type Output = ( $( $T::Output ),+ );
+
+                fn apply<'s>(
+                    store: &'s mut Self::Output,
+                    input: PassBy<Self>,
+                    gain: G,
+                ) -> PassBy<'s, Self::Output> {
+                    ( $( $T::apply(&mut store.$i, input.$i, gain) ),+ )
+                }
+            }
+        )+
+    };
+}
+
+impl_bundle_apply!(
+    (T1 0, T2 1),
+    (T1 0, T2 1, T3 2),
+    (T1 0, T2 1, T3 2, T4 3),
+    (T1 0, T2 1, T3 2, T4 3, T5 4),
+    (T1 0, T2 1, T3 2, T4 3, T5 4, T6 5),
+    (T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6),
+    (T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7),
+);
+
 pub struct Parameters<G: Scalar> {
     pub gain: G,
 }
 
 impl<G: Scalar> Parameters<G> {
-    pub fn new(gain: G) -> Self {
+    pub const fn new(gain: G) -> Self {
         Self { gain }
     }
 }
@@ -130,6 +204,7 @@ impl<G: Scalar> Parameters<G> {
 mod tests {
     use super::*;
     use crate::testing::StubContext;
+    use pictorus_traits::Q15;
 
     #[test]
     fn test_gain_default_buffer_no_panic() {
@@ -151,6 +226,16 @@ mod tests {
         assert_eq!(block.buffer(), output);
     }
 
+    #[test]
+    fn test_gain_static_parameters() {
+        static PARAMETERS: Parameters<f64> = Parameters::new(-0.5);
+
+        let mut block = GainBlock::<f64, f64>::default();
+        let context = StubContext::default();
+        let output = block.process(&PARAMETERS, &context, 4.0);
+        assert_eq!(output, -2.0);
+    }
+
     #[test]
     fn test_gain_matrix() {
         let mut block = GainBlock::<f64, Matrix<2, 2, f64>>::default();
@@ -164,6 +249,24 @@ mod tests {
         assert_eq!(block.buffer().data, [[2.0, 4.0], [6.0, 8.0]]);
     }
 
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_gain_dmatrix() {
+        let mut block = GainBlock::<f64, DMatrix<f64>>::default();
+        let context = StubContext::default();
+        let parameters = Parameters::new(2.0);
+
+        let input = DMatrix::from_column_slice(3, 1, &[1.0, 2.0, 3.0]).unwrap();
+        let output = block.process(&parameters, &context, &input);
+        assert_eq!(output.as_slice(), &[2.0, 4.0, 6.0]);
+
+        // The output follows the size of the input
+        let input = DMatrix::from_column_slice(1, 2, &[-1.0, 0.5]).unwrap();
+        let output = block.process(&parameters, &context, &input);
+        assert_eq!((output.nrows(), output.ncols()), (1, 2));
+        assert_eq!(block.buffer().as_slice(), &[-2.0, 1.0]);
+    }
+
     #[test]
     fn test_scalar_with_to_pass() {
         let mut block = GainBlock::<f64, f64>::default();
@@ -187,4 +290,39 @@ mod tests {
         assert_eq!(output.data, [[2.0, 6.0], [4.0, 8.0]]);
         assert_eq!(block.buffer().data, [[2.0, 6.0], [4.0, 8.0]]);
     }
+
+    #[test]
+    fn test_gain_fixed_point() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(Q15::from_f64(-1.5));
+
+        let mut block = GainBlock::<Q15, Q15>::default();
+        let output = block.process(&parameters, &context, Q15::from_f64(2.5));
+        assert_eq!(output, Q15::from_f64(-3.75));
+        assert_eq!(block.buffer(), output);
+
+        let mut block = GainBlock::<Q15, Matrix<1, 2, Q15>>::default();
+        let input = Matrix {
+            data: [[Q15::from_f64(1.0)], [Q15::from_f64(-0.5)]],
+        };
+        let output = block.process(&parameters, &context, &input);
+        assert_eq!(output.data, [[Q15::from_f64(-1.5)], [Q15::from_f64(0.75)]]);
+    }
+    #[test]
+    fn test_gain_bundle() {
+        let mut block = GainBlock::<f64, (Matrix<2, 1, f64>, f64, Matrix<1, 3, f64>)>::default();
+        let context = StubContext::default();
+        let parameters = Parameters::new(-2.0);
+        assert_eq!(block.buffer().1, 0.0);
+
+        let a = Matrix { data: [[1.0, 2.0]] };
+        let c = Matrix {
+            data: [[1.0], [0.0], [-0.5]],
+        };
+        let (out_a, out_b, out_c) = block.process(&parameters, &context, (&a, 3.0, &c));
+        assert_eq!(out_a.data, [[-2.0, -4.0]]);
+        assert_eq!(out_b, -6.0);
+        assert_eq!(out_c.data, [[-2.0], [0.0], [1.0]]);
+        assert_eq!(block.buffer().2.data, [[-2.0], [0.0], [1.0]]);
+    }
 }
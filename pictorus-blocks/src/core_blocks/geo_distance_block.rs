@@ -0,0 +1,108 @@
+use crate::geodesy::haversine;
+use crate::traits::Float;
+use pictorus_traits::{Matrix, PassBy, ProcessBlock};
+
+/// Parameters for the GeoDistanceBlock
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Parameters;
+
+impl Parameters {
+    pub fn new() -> Self {
+        Parameters {}
+    }
+}
+
+/// Computes the great-circle distance and bearing between two positions.
+///
+/// The inputs are the `(latitude, longitude)` of the start and end positions, each as a (1, 2)
+/// matrix in degrees. The output is a tuple of (distance, bearing): the distance in meters along
+/// the surface of a sphere with the Earth's mean radius, and the initial bearing from the start
+/// towards the end in radians, clockwise from north in `[0, 2 * pi)`.
+///
+/// The spherical model is accurate to within about 0.5% of the WGS-84 ellipsoid distance, which
+/// is usually enough for geofencing and navigation logic. Use the [`LlaToNedBlock`](crate::LlaToNedBlock)
+/// when exact local offsets are needed.
+pub struct GeoDistanceBlock<F: Float> {
+    buffer: (F, F),
+}
+
+impl<F: Float> Default for GeoDistanceBlock<F> {
+    fn default() -> Self {
+        Self {
+            buffer: (F::default(), F::default()),
+        }
+    }
+}
+
+impl<F: Float> ProcessBlock for GeoDistanceBlock<F> {
+    type Inputs = (Matrix<1, 2, F>, Matrix<1, 2, F>);
+    type Output = (F, F);
+    type Parameters = Parameters;
+
+    fn process<'b>(
+        &'b mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let to_f64 = |position: &Matrix<1, 2, F>| -> [f64; 2] {
+            core::array::from_fn(|i| {
+                num_traits::ToPrimitive::to_f64(&position.data[i][0]).unwrap_or(f64::NAN)
+            })
+        };
+        let from_f64 =
+            |value: f64| <F as num_traits::NumCast>::from(value).expect("f64 converts to float");
+
+        let (distance, bearing) = haversine(to_f64(inputs.0), to_f64(inputs.1));
+        self.buffer = (from_f64(distance), from_f64(bearing));
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_geo_distance() {
+        let ctxt = StubContext::default();
+        let params = Parameters::new();
+        let mut block = GeoDistanceBlock::<f64>::default();
+        assert_eq!(block.buffer(), (0.0, 0.0));
+
+        // Paris to London
+        let paris = Matrix {
+            data: [[48.8566], [2.3522]],
+        };
+        let london = Matrix {
+            data: [[51.5074], [-0.1278]],
+        };
+        let (distance, bearing) = block.process(&params, &ctxt, (&paris, &london));
+        assert_relative_eq!(distance, 343_556.0, epsilon = 1.0);
+        assert_relative_eq!(bearing.to_degrees(), 330.0, epsilon = 0.5);
+
+        let (distance, bearing) = block.process(&params, &ctxt, (&london, &london));
+        assert_eq!((distance, bearing), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_geo_distance_f32() {
+        let ctxt = StubContext::default();
+        let mut block = GeoDistanceBlock::<f32>::default();
+        let from = Matrix {
+            data: [[0.0], [0.0]],
+        };
+        let to = Matrix {
+            data: [[-1.0], [0.0]],
+        };
+        let (distance, bearing) = block.process(&Parameters::new(), &ctxt, (&from, &to));
+        assert_relative_eq!(distance, 111_195.08, epsilon = 0.1);
+        assert_relative_eq!(bearing, core::f32::consts::PI);
+    }
+}
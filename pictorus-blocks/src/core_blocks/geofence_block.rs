@@ -0,0 +1,223 @@
+use crate::traits::Float;
+use pictorus_traits::{Matrix, PassBy, ProcessBlock};
+
+/// Parameters for the GeofenceBlock
+pub struct Parameters<F: Float, const V: usize, const C: usize> {
+    /// Vertices of the polygon the position must stay inside, one `(x, y)` row per vertex.
+    /// Polygons with fewer than three vertices are ignored.
+    pub polygon: Matrix<V, 2, F>,
+    /// Circular fences, one `(x, y, radius)` row per circle
+    pub circles: Matrix<C, 3, F>,
+    /// Whether the position must stay inside (true) or outside (false) each circle
+    pub circle_inclusion: [bool; C],
+}
+
+impl<F: Float, const V: usize, const C: usize> Parameters<F, V, C> {
+    pub fn new(
+        polygon: Matrix<V, 2, F>,
+        circles: Matrix<C, 3, F>,
+        circle_inclusion: [bool; C],
+    ) -> Self {
+        Self {
+            polygon,
+            circles,
+            circle_inclusion,
+        }
+    }
+
+    /// Distance from `position` to the polygon boundary, positive inside and negative outside
+    fn polygon_margin(&self, position: [F; 2]) -> Option<F> {
+        if V < 3 {
+            return None;
+        }
+        let zero = <F as num_traits::Zero>::zero();
+        let one = <F as num_traits::One>::one();
+        let [x, y] = position;
+        let mut inside = false;
+        let mut nearest = num_traits::Float::infinity();
+        for index in 0..V {
+            let (x1, y1) = (self.polygon.data[0][index], self.polygon.data[1][index]);
+            let next = (index + 1) % V;
+            let (x2, y2) = (self.polygon.data[0][next], self.polygon.data[1][next]);
+
+            // Even-odd ray cast towards +x
+            if (y1 > y) != (y2 > y) && x < x1 + (y - y1) * (x2 - x1) / (y2 - y1) {
+                inside = !inside;
+            }
+
+            let (dx, dy) = (x2 - x1, y2 - y1);
+            let length_squared = dx * dx + dy * dy;
+            let fraction = if length_squared > zero {
+                num_traits::Float::clamp(
+                    ((x - x1) * dx + (y - y1) * dy) / length_squared,
+                    zero,
+                    one,
+                )
+            } else {
+                zero
+            };
+            let distance =
+                num_traits::Float::hypot(x - (x1 + fraction * dx), y - (y1 + fraction * dy));
+            nearest = num_traits::Float::min(nearest, distance);
+        }
+        Some(if inside { nearest } else { -nearest })
+    }
+}
+
+/// Tests a position against a polygon fence and any number of circular fences.
+///
+/// The inputs are the `(x, y)` position as a (1, 2) matrix and a reset flag. Positions and fences
+/// share a local planar frame, such as the north/east output of the
+/// [`LlaToNedBlock`](crate::LlaToNedBlock). The position must stay inside the polygon, inside every
+/// inclusion circle, and outside every exclusion circle.
+///
+/// The output is a tuple of (inside, distance, breached):
+/// - Inside is true while the position satisfies every fence
+/// - Distance is the distance to the nearest fence boundary, positive while inside and negative
+///   once outside. It is infinite when no fences are configured.
+/// - Breached latches true the first time the position leaves the fences, and stays true until
+///   the reset input is set while the position is inside
+pub struct GeofenceBlock<F: Float, const V: usize, const C: usize> {
+    buffer: (bool, F, bool),
+}
+
+impl<F: Float, const V: usize, const C: usize> Default for GeofenceBlock<F, V, C> {
+    fn default() -> Self {
+        Self {
+            buffer: (true, num_traits::Float::infinity(), false),
+        }
+    }
+}
+
+impl<F: Float, const V: usize, const C: usize> ProcessBlock for GeofenceBlock<F, V, C> {
+    type Inputs = (Matrix<1, 2, F>, bool);
+    type Output = (bool, F, bool);
+    type Parameters = Parameters<F, V, C>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (position, reset) = inputs;
+        let position = [position.data[0][0], position.data[1][0]];
+
+        let mut margin = parameters
+            .polygon_margin(position)
+            .unwrap_or(num_traits::Float::infinity());
+        for (circle, inclusion) in parameters.circle_inclusion.iter().enumerate() {
+            let distance = num_traits::Float::hypot(
+                position[0] - parameters.circles.data[0][circle],
+                position[1] - parameters.circles.data[1][circle],
+            );
+            let radius = parameters.circles.data[2][circle];
+            let circle_margin = if *inclusion {
+                radius - distance
+            } else {
+                distance - radius
+            };
+            margin = num_traits::Float::min(margin, circle_margin);
+        }
+
+        // Invalid positions count as outside
+        if position
+            .iter()
+            .any(|value| num_traits::Float::is_nan(*value))
+        {
+            margin = num_traits::Float::nan();
+        }
+        let inside = margin >= <F as num_traits::Zero>::zero();
+        let (_, _, breached) = self.buffer;
+        let breached = !inside || (breached && !reset);
+        self.buffer = (inside, margin, breached);
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use approx::assert_relative_eq;
+
+    fn position(x: f64, y: f64) -> Matrix<1, 2, f64> {
+        Matrix { data: [[x], [y]] }
+    }
+
+    #[test]
+    fn test_geofence_default_buffer() {
+        let block = GeofenceBlock::<f64, 0, 0>::default();
+        assert_eq!(block.buffer(), (true, f64::INFINITY, false));
+    }
+
+    #[test]
+    fn test_geofence_polygon() {
+        let ctxt = StubContext::default();
+        // A concave L-shaped polygon, missing the top right quadrant of a 10x10 square
+        let polygon = Matrix {
+            data: [
+                [0.0, 10.0, 10.0, 5.0, 5.0, 0.0],
+                [0.0, 0.0, 5.0, 5.0, 10.0, 10.0],
+            ],
+        };
+        let params = Parameters::new(polygon, Matrix::zeroed(), []);
+        let mut block = GeofenceBlock::<f64, 6, 0>::default();
+
+        let (inside, distance, breached) =
+            block.process(&params, &ctxt, (&position(2.0, 3.0), false));
+        assert!(inside);
+        assert_relative_eq!(distance, 2.0);
+        assert!(!breached);
+
+        // In the notch
+        let (inside, distance, breached) =
+            block.process(&params, &ctxt, (&position(8.0, 6.0), false));
+        assert!(!inside);
+        assert_relative_eq!(distance, -1.0);
+        assert!(breached);
+
+        // Breach latches until reset
+        let (inside, _, breached) = block.process(&params, &ctxt, (&position(2.0, 2.0), false));
+        assert!(inside);
+        assert!(breached);
+        let (_, _, breached) = block.process(&params, &ctxt, (&position(2.0, 2.0), true));
+        assert!(!breached);
+
+        // Reset doesn't clear a breach while still outside
+        let (_, _, breached) = block.process(&params, &ctxt, (&position(-1.0, 2.0), true));
+        assert!(breached);
+    }
+
+    #[test]
+    fn test_geofence_circles() {
+        let ctxt = StubContext::default();
+        // Stay within 100 of the origin, and out of 10 around (50, 0)
+        let circles = Matrix {
+            data: [[0.0, 50.0], [0.0, 0.0], [100.0, 10.0]],
+        };
+        let params = Parameters::new(Matrix::zeroed(), circles, [true, false]);
+        let mut block = GeofenceBlock::<f64, 0, 2>::default();
+
+        let (inside, distance, _) = block.process(&params, &ctxt, (&position(0.0, 90.0), false));
+        assert!(inside);
+        assert_relative_eq!(distance, 10.0);
+
+        let (inside, distance, _) = block.process(&params, &ctxt, (&position(45.0, 0.0), false));
+        assert!(!inside);
+        assert_relative_eq!(distance, -5.0);
+
+        let (inside, distance, breached) =
+            block.process(&params, &ctxt, (&position(0.0, 0.0), true));
+        assert!(inside);
+        assert_relative_eq!(distance, 40.0);
+        assert!(!breached);
+
+        let (inside, _, _) = block.process(&params, &ctxt, (&position(f64::NAN, 0.0), false));
+        assert!(!inside);
+    }
+}
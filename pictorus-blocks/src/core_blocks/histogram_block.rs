@@ -0,0 +1,214 @@
+use crate::traits::Float;
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+/// Parameters for the HistogramBlock
+pub struct Parameters<F: Float, const P: usize> {
+    /// Lower edge of the first bin
+    pub lower: F,
+    /// Upper edge of the last bin
+    pub upper: F,
+    /// Percentiles (0 to 100) to estimate from the histogram
+    pub percentiles: [F; P],
+}
+
+impl<F: Float, const P: usize> Parameters<F, P> {
+    pub fn new(lower: F, upper: F, percentiles: [F; P]) -> Self {
+        Self {
+            lower,
+            upper,
+            percentiles,
+        }
+    }
+}
+
+/// Accumulates a histogram of every sample of its input and estimates selected percentiles
+/// from it. Useful for characterizing loop timing jitter or sensor noise on-target without
+/// storing the samples themselves.
+///
+/// The range `[lower, upper)` is split into `BINS` equal-width bins. Samples below or above the
+/// range are counted in the first or last bin respectively, and NaN samples are ignored. Matrix
+/// inputs add every element as a separate sample.
+///
+/// The first output holds the count of samples in each bin. The second holds the estimate of
+/// each requested percentile, linearly interpolated within the bin it falls in, or zero before
+/// any samples have been seen.
+pub struct HistogramBlock<T: Apply, const BINS: usize, const P: usize> {
+    counts: [u64; BINS],
+    total: u64,
+    buffer: (Matrix<1, BINS, T::Float>, Matrix<1, P, T::Float>),
+}
+
+impl<T: Apply, const BINS: usize, const P: usize> Default for HistogramBlock<T, BINS, P> {
+    fn default() -> Self {
+        Self {
+            counts: [0; BINS],
+            total: 0,
+            buffer: (Matrix::zeroed(), Matrix::zeroed()),
+        }
+    }
+}
+
+impl<T: Apply, const BINS: usize, const P: usize> HistogramBlock<T, BINS, P> {
+    /// Total number of samples counted so far
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    fn add_sample(&mut self, parameters: &Parameters<T::Float, P>, value: T::Float) {
+        if num_traits::Float::is_nan(value) || BINS == 0 {
+            return;
+        }
+        let bins = <T::Float as num_traits::NumCast>::from(BINS).expect("Bin count fits in float");
+        let position = (value - parameters.lower) / (parameters.upper - parameters.lower) * bins;
+        // Out of range samples land in the edge bins
+        let bin = if position >= bins {
+            BINS - 1
+        } else {
+            num_traits::ToPrimitive::to_usize(&num_traits::Float::floor(position)).unwrap_or(0)
+        };
+        if let Some(count) = self.counts.get_mut(bin) {
+            *count += 1;
+            self.total += 1;
+        }
+    }
+
+    fn percentile(&self, parameters: &Parameters<T::Float, P>, percentile: T::Float) -> T::Float {
+        let cast = |value: u64| {
+            <T::Float as num_traits::NumCast>::from(value).expect("Count fits in float")
+        };
+        let zero = <T::Float as num_traits::Zero>::zero();
+        if self.total == 0 {
+            return zero;
+        }
+        let hundred = <T::Float as num_traits::NumCast>::from(100.0).expect("100 fits in float");
+        let fraction = num_traits::Float::clamp(
+            percentile / hundred,
+            zero,
+            <T::Float as num_traits::One>::one(),
+        );
+        let target = fraction * cast(self.total);
+        let width = (parameters.upper - parameters.lower)
+            / <T::Float as num_traits::NumCast>::from(BINS).expect("Bin count fits in float");
+
+        let mut below = 0;
+        for (bin, count) in self.counts.iter().enumerate() {
+            if *count > 0 && cast(below + count) >= target {
+                let within = (target - cast(below)) / cast(*count);
+                let bin =
+                    <T::Float as num_traits::NumCast>::from(bin).expect("Bin index fits in float");
+                return parameters.lower + width * (bin + within);
+            }
+            below += count;
+        }
+        parameters.upper
+    }
+}
+
+impl<T: Apply, const BINS: usize, const P: usize> ProcessBlock for HistogramBlock<T, BINS, P> {
+    type Inputs = T;
+    type Output = (Matrix<1, BINS, T::Float>, Matrix<1, P, T::Float>);
+    type Parameters = Parameters<T::Float, P>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        T::for_each(input, |value| self.add_sample(parameters, value));
+
+        for (output, count) in self.buffer.0.data.iter_mut().zip(self.counts) {
+            output[0] =
+                <T::Float as num_traits::NumCast>::from(count).expect("Count fits in float");
+        }
+        for (bin, percentile) in parameters.percentiles.iter().enumerate() {
+            self.buffer.1.data[bin][0] = self.percentile(parameters, *percentile);
+        }
+
+        (self.buffer.0.as_by(), self.buffer.1.as_by())
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        (self.buffer.0.as_by(), self.buffer.1.as_by())
+    }
+}
+
+pub trait Apply: Pass {
+    type Float: Float;
+
+    /// Calls `f` with each element of the input
+    fn for_each(input: PassBy<Self>, f: impl FnMut(Self::Float));
+}
+
+impl<F: Float> Apply for F {
+    type Float = F;
+
+    fn for_each(input: PassBy<Self>, mut f: impl FnMut(Self::Float)) {
+        f(input);
+    }
+}
+
+impl<const NROWS: usize, const NCOLS: usize, F: Float> Apply for Matrix<NROWS, NCOLS, F> {
+    type Float = F;
+
+    fn for_each(input: PassBy<Self>, f: impl FnMut(Self::Float)) {
+        input.data.as_flattened().iter().copied().for_each(f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_histogram_default_buffer_no_panic() {
+        let block = HistogramBlock::<f64, 4, 2>::default();
+        assert_eq!(block.buffer(), (&Matrix::zeroed(), &Matrix::zeroed()));
+    }
+
+    #[test]
+    fn test_histogram_counts() {
+        let context = StubContext::default();
+        let params = Parameters::new(0.0, 4.0, []);
+        let mut block = HistogramBlock::<f64, 4, 0>::default();
+
+        for value in [
+            0.0,
+            0.5,
+            1.0,
+            2.5,
+            3.99,
+            4.0,
+            -10.0,
+            f64::INFINITY,
+            f64::NAN,
+        ] {
+            block.process(&params, &context, value);
+        }
+        let (counts, _) = block.buffer();
+        // Out of range values are counted in the edge bins, NaN is ignored
+        assert_eq!(counts.data, [[3.0], [1.0], [1.0], [3.0]]);
+        assert_eq!(block.total(), 8);
+    }
+
+    #[test]
+    fn test_histogram_percentiles() {
+        let context = StubContext::default();
+        let params = Parameters::new(0.0, 10.0, [0.0, 50.0, 90.0, 100.0]);
+        let mut block = HistogramBlock::<Matrix<1, 10, f32>, 10, 4>::default();
+
+        // One sample in each bin per tick
+        let input = Matrix {
+            data: core::array::from_fn(|i| [i as f32 + 0.5]),
+        };
+        block.process(&params, &context, &input);
+        let (counts, percentiles) = block.process(&params, &context, &input);
+        assert_eq!(counts.data, [[2.0]; 10]);
+        assert_relative_eq!(percentiles.data[0][0], 0.0);
+        assert_relative_eq!(percentiles.data[1][0], 5.0);
+        assert_relative_eq!(percentiles.data[2][0], 9.0);
+        assert_relative_eq!(percentiles.data[3][0], 10.0);
+    }
+}
@@ -0,0 +1,172 @@
+use core::time::Duration;
+
+use crate::fast_math::FastMath;
+use crate::traits::Float;
+use pictorus_traits::{PassBy, ProcessBlock};
+
+/// Parameters for the I2tBlock
+pub struct Parameters<F: Float> {
+    /// Cooling time constant in seconds. The accumulator decays towards zero with this time
+    /// constant, so a steady current `i` settles at `i^2 * time_constant`. Zero disables cooling.
+    pub time_constant: F,
+    /// Accumulator value (A^2*s) above which the trip output is set
+    pub limit: F,
+    /// Once tripped, the trip output stays set until the accumulator falls below this value.
+    /// Values above `limit` behave the same as `limit` (no hysteresis).
+    pub reset_level: F,
+}
+
+impl<F: Float> Parameters<F> {
+    pub fn new(time_constant: F, limit: F, reset_level: F) -> Self {
+        Self {
+            time_constant,
+            limit,
+            reset_level,
+        }
+    }
+}
+
+/// Thermal protection model that accumulates `i^2 * dt` from a current input, with exponential
+/// cooling, and trips when a limit is exceeded. Typically used to protect motor windings and
+/// FETs from sustained overcurrent while still allowing short peaks.
+///
+/// With a cooling time constant `tau` the accumulator follows `dE/dt = i^2 - E / tau`, which is
+/// integrated exactly over each timestep assuming the current is constant within it. A steady
+/// current trips the block only if `i^2 * tau > limit`, so the continuous current rating is
+/// `sqrt(limit / tau)`.
+///
+/// The output is a tuple of (accumulator, tripped).
+pub struct I2tBlock<F: Float> {
+    buffer: (F, bool),
+}
+
+impl<F: Float> Default for I2tBlock<F> {
+    fn default() -> Self {
+        Self {
+            buffer: (<F as num_traits::Zero>::zero(), false),
+        }
+    }
+}
+
+impl<F: Float> ProcessBlock for I2tBlock<F> {
+    type Inputs = F;
+    type Output = (F, bool);
+    type Parameters = Parameters<F>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (accumulator, tripped) = &mut self.buffer;
+        let dt = F::from_duration(context.timestep().unwrap_or(Duration::ZERO));
+        let heating = input * input;
+
+        let zero = <F as num_traits::Zero>::zero();
+        if parameters.time_constant > zero {
+            // Exact solution of dE/dt = i^2 - E / tau over dt
+            let decay = FastMath::fast_exp(-dt / parameters.time_constant);
+            let steady_state = heating * parameters.time_constant;
+            *accumulator = steady_state + (*accumulator - steady_state) * decay;
+        } else {
+            *accumulator += heating * dt;
+        }
+
+        if *accumulator > parameters.limit {
+            *tripped = true;
+        } else if *accumulator < num_traits::Float::min(parameters.reset_level, parameters.limit) {
+            *tripped = false;
+        }
+
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_i2t_default_buffer_no_panic() {
+        let block = I2tBlock::<f64>::default();
+        assert_eq!(block.buffer(), (0.0, false));
+    }
+
+    #[test]
+    fn test_i2t_without_cooling() {
+        let mut runtime = StubRuntime::default();
+        runtime.context.fundamental_timestep = Duration::from_secs(1);
+        let params = Parameters::new(0.0, 50.0, 0.0);
+        let mut block = I2tBlock::<f64>::default();
+
+        // First tick has no timestep
+        assert_eq!(
+            block.process(&params, &runtime.context(), 5.0),
+            (0.0, false)
+        );
+        runtime.tick();
+        assert_eq!(
+            block.process(&params, &runtime.context(), -5.0),
+            (25.0, false)
+        );
+        runtime.tick();
+        assert_eq!(
+            block.process(&params, &runtime.context(), 5.0),
+            (50.0, false)
+        );
+        runtime.tick();
+        assert_eq!(
+            block.process(&params, &runtime.context(), 1.0),
+            (51.0, true)
+        );
+        // Without cooling the trip never clears
+        runtime.tick();
+        assert_eq!(
+            block.process(&params, &runtime.context(), 0.0),
+            (51.0, true)
+        );
+    }
+
+    #[test]
+    fn test_i2t_cooling_and_hysteresis() {
+        let mut runtime = StubRuntime::default();
+        runtime.context.fundamental_timestep = Duration::from_millis(100);
+        let params = Parameters::new(2.0, 100.0, 50.0);
+        let mut block = I2tBlock::<f64>::default();
+
+        // 8A settles at 128, which exceeds the limit. Crosses 100 after -2 * ln(1 - 100/128) = 3.03s
+        let mut trip_time = None;
+        for i in 0..100 {
+            runtime.tick();
+            let (_, tripped) = block.process(&params, &runtime.context(), 8.0);
+            if tripped && trip_time.is_none() {
+                trip_time = Some(i + 1);
+            }
+        }
+        assert_eq!(trip_time, Some(31));
+        assert_relative_eq!(
+            block.buffer().0,
+            128.0 * (1.0 - (-5.0f64).exp()),
+            epsilon = 1e-9
+        );
+
+        // At zero current it decays, staying tripped until it falls below 50
+        let mut clear_time = None;
+        for i in 0..100 {
+            runtime.tick();
+            let (_, tripped) = block.process(&params, &runtime.context(), 0.0);
+            if !tripped && clear_time.is_none() {
+                clear_time = Some(i + 1);
+            }
+        }
+        // 127.1 * exp(-t / 2) < 50 once t > 1.87s
+        assert_eq!(clear_time, Some(19));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::matrix_ext::MatrixNalgebraExt;
+use crate::dsp::{first_order, DspFloat};
 use crate::traits::Float;
 use core::time::Duration;
 use pictorus_traits::{HasIc, Matrix, Pass, PassBy, ProcessBlock};
@@ -19,7 +19,7 @@ impl<T: Pass + Default> Default for IirFilterBlock<T> {
     }
 }
 
-impl<T: Float> HasIc for IirFilterBlock<T> {
+impl<T: DspFloat> HasIc for IirFilterBlock<T> {
     fn new(parameters: &Self::Parameters) -> Self {
         IirFilterBlock::<T> {
             buffer: parameters.ic,
@@ -29,7 +29,7 @@ impl<T: Float> HasIc for IirFilterBlock<T> {
 
 impl<T, const NROWS: usize, const NCOLS: usize> HasIc for IirFilterBlock<Matrix<NROWS, NCOLS, T>>
 where
-    T: Float,
+    T: DspFloat,
 {
     fn new(parameters: &Self::Parameters) -> Self {
         IirFilterBlock::<pictorus_traits::Matrix<NROWS, NCOLS, T>> {
@@ -40,7 +40,7 @@ where
 
 impl<T> ProcessBlock for IirFilterBlock<T>
 where
-    T: Float,
+    T: DspFloat,
 {
     type Inputs = T;
     type Output = T;
@@ -53,9 +53,13 @@ where
     ) -> PassBy<'b, Self::Output> {
         let timestep_s = T::from_duration(context.timestep().unwrap_or(Duration::from_secs(0)));
         let alpha = timestep_s / (timestep_s + parameters.time_constant_s);
-        let res = alpha * inputs + ((T::one() - alpha) * self.buffer);
-        self.buffer = res;
-        res
+        self.buffer = first_order(
+            [alpha, T::zero(), T::one() - alpha],
+            inputs,
+            T::zero(),
+            self.buffer,
+        );
+        self.buffer
     }
 
     fn buffer(&self) -> PassBy<'_, Self::Output> {
@@ -66,7 +70,7 @@ where
 impl<T, const NROWS: usize, const NCOLS: usize> ProcessBlock
     for IirFilterBlock<Matrix<NROWS, NCOLS, T>>
 where
-    T: Float,
+    T: DspFloat,
 {
     type Inputs = Matrix<NROWS, NCOLS, T>;
     type Output = Matrix<NROWS, NCOLS, T>;
@@ -80,9 +84,16 @@ where
     ) -> PassBy<'b, Self::Output> {
         let timestep_s = T::from_duration(context.timestep().unwrap_or(Duration::from_secs(0)));
         let alpha = timestep_s / (timestep_s + parameters.time_constant_s);
-        let input = inputs.as_view();
-        let res = input * alpha + (self.buffer.as_view() * (T::one() - alpha));
-        self.buffer = Self::Output::from_view(&res.as_view());
+        let coeffs = [alpha, T::zero(), T::one() - alpha];
+        for (output, input) in self
+            .buffer
+            .data
+            .as_flattened_mut()
+            .iter_mut()
+            .zip(inputs.data.as_flattened())
+        {
+            *output = first_order(coeffs, *input, T::zero(), *output);
+        }
         &self.buffer
     }
 
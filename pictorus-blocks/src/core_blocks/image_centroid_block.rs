@@ -0,0 +1,135 @@
+use crate::traits::Float;
+use pictorus_traits::{Matrix, PassBy, ProcessBlock, Scalar};
+
+/// Parameters for the ImageCentroidBlock
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Parameters;
+
+impl Parameters {
+    pub fn new() -> Self {
+        Parameters {}
+    }
+}
+
+/// Pixel types the centroid can be weighted by
+pub trait Pixel: Scalar {
+    fn weight(self) -> u32;
+}
+
+impl Pixel for bool {
+    fn weight(self) -> u32 {
+        self.into()
+    }
+}
+
+impl Pixel for u8 {
+    fn weight(self) -> u32 {
+        self.into()
+    }
+}
+
+/// Finds the center of mass of an image, such as the blob in a thresholded mask.
+///
+/// Each pixel is weighted by its value; a binary mask from the
+/// [`ImageThresholdBlock`](crate::ImageThresholdBlock) weights every set pixel equally, while a
+/// grayscale image weights pixels by their intensity.
+///
+/// The output is a tuple of (row, column, mass, found). The row and column are in pixels from
+/// the first row and column, with fractional values between pixel centers. The mass is the sum of
+/// the pixel weights (the pixel count, for a mask). When the image is empty, found is false
+/// and the previous row and column are held.
+pub struct ImageCentroidBlock<F: Float, T: Pixel, const H: usize, const W: usize> {
+    buffer: (F, F, F, bool),
+    _pixel: core::marker::PhantomData<T>,
+}
+
+impl<F: Float, T: Pixel, const H: usize, const W: usize> Default
+    for ImageCentroidBlock<F, T, H, W>
+{
+    fn default() -> Self {
+        Self {
+            buffer: (F::default(), F::default(), F::default(), false),
+            _pixel: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<F: Float, T: Pixel, const H: usize, const W: usize> ProcessBlock
+    for ImageCentroidBlock<F, T, H, W>
+{
+    type Inputs = Matrix<H, W, T>;
+    type Output = (F, F, F, bool);
+    type Parameters = Parameters;
+
+    fn process<'b>(
+        &'b mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        // Integer sums are exact, and can't overflow for any image that fits in memory
+        let (mut mass, mut row_moment, mut col_moment) = (0u64, 0u64, 0u64);
+        for (col, column) in input.data.iter().enumerate() {
+            for (row, pixel) in column.iter().enumerate() {
+                let weight = u64::from(pixel.weight());
+                mass += weight;
+                row_moment += weight * row as u64;
+                col_moment += weight * col as u64;
+            }
+        }
+
+        let cast = |value: u64| <F as num_traits::NumCast>::from(value).expect("Sum fits in float");
+        let (row, col, _, _) = self.buffer;
+        self.buffer = if mass > 0 {
+            (
+                cast(row_moment) / cast(mass),
+                cast(col_moment) / cast(mass),
+                cast(mass),
+                true,
+            )
+        } else {
+            (row, col, F::default(), false)
+        };
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+
+    #[test]
+    fn test_image_centroid_mask() {
+        let ctxt = StubContext::default();
+        let mut block = ImageCentroidBlock::<f64, bool, 3, 4>::default();
+        assert_eq!(block.buffer(), (0.0, 0.0, 0.0, false));
+
+        // Set pixels at (row, col) = (1, 2), (1, 3), (2, 2), (2, 3)
+        let mut mask = Matrix::<3, 4, bool>::zeroed();
+        for (row, col) in [(1, 2), (1, 3), (2, 2), (2, 3)] {
+            mask.data[col][row] = true;
+        }
+        let output = block.process(&Parameters::new(), &ctxt, &mask);
+        assert_eq!(output, (1.5, 2.5, 4.0, true));
+
+        // An empty mask holds the last position
+        let output = block.process(&Parameters::new(), &ctxt, &Matrix::zeroed());
+        assert_eq!(output, (1.5, 2.5, 0.0, false));
+    }
+
+    #[test]
+    fn test_image_centroid_grayscale() {
+        let ctxt = StubContext::default();
+        let mut block = ImageCentroidBlock::<f32, u8, 1, 3>::default();
+        let image = Matrix {
+            data: [[100], [0], [100u8]],
+        };
+        let output = block.process(&Parameters::new(), &ctxt, &image);
+        assert_eq!(output, (0.0, 1.0, 200.0, true));
+    }
+}
@@ -0,0 +1,151 @@
+use crate::ParameterError;
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+/// How each output pixel is computed from its block of input pixels
+#[derive(Clone, Copy, Debug, PartialEq, strum::EnumString)]
+pub enum DownsampleMethod {
+    /// Average of the block, which reduces noise
+    Average,
+    /// Top left pixel of the block, which is cheapest
+    Nearest,
+    /// Brightest pixel of the block, which preserves thin bright features
+    Max,
+}
+
+/// Parameters for the ImageDownsampleBlock
+pub struct Parameters {
+    pub method: DownsampleMethod,
+}
+
+impl Parameters {
+    pub fn new(method: &str) -> Self {
+        Self::try_new(method).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(method: &str) -> Result<Self, ParameterError> {
+        Ok(Self {
+            method: method
+                .parse()
+                .map_err(|_| ParameterError("Failed to parse downsample method."))?,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved downsample method, without parsing
+    pub const fn from_method(method: DownsampleMethod) -> Self {
+        Self { method }
+    }
+}
+
+impl From<DownsampleMethod> for Parameters {
+    fn from(method: DownsampleMethod) -> Self {
+        Self::from_method(method)
+    }
+}
+
+/// Reduces an `H` x `W` grayscale image to `OH` x `OW`.
+///
+/// Each output pixel covers a block of about `H / OH` rows by `W / OW` columns of the input,
+/// combined according to the method. Output dimensions must not be larger than the input dimensions.
+///
+/// Supported methods:
+/// - Average
+/// - Nearest
+/// - Max
+pub struct ImageDownsampleBlock<const H: usize, const W: usize, const OH: usize, const OW: usize> {
+    buffer: Matrix<OH, OW, u8>,
+}
+
+impl<const H: usize, const W: usize, const OH: usize, const OW: usize> Default
+    for ImageDownsampleBlock<H, W, OH, OW>
+{
+    fn default() -> Self {
+        const {
+            assert!(
+                OH > 0 && OW > 0 && OH <= H && OW <= W,
+                "ImageDownsampleBlock output must be non-empty and no larger than its input"
+            );
+        }
+        Self {
+            buffer: Matrix::zeroed(),
+        }
+    }
+}
+
+impl<const H: usize, const W: usize, const OH: usize, const OW: usize> ProcessBlock
+    for ImageDownsampleBlock<H, W, OH, OW>
+{
+    type Inputs = Matrix<H, W, u8>;
+    type Output = Matrix<OH, OW, u8>;
+    type Parameters = Parameters;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        for (out_col, output_column) in self.buffer.data.iter_mut().enumerate() {
+            let cols = out_col * W / OW..(out_col + 1) * W / OW;
+            for (out_row, output) in output_column.iter_mut().enumerate() {
+                let rows = out_row * H / OH..(out_row + 1) * H / OH;
+                let mut pixels = input.data[cols.clone()]
+                    .iter()
+                    .flat_map(|column| column[rows.clone()].iter().copied());
+                *output = match parameters.method {
+                    DownsampleMethod::Nearest => input.data[cols.start][rows.start],
+                    DownsampleMethod::Max => pixels.max().unwrap_or_default(),
+                    DownsampleMethod::Average => {
+                        let (sum, count) =
+                            pixels.by_ref().fold((0u32, 0u32), |(sum, count), pixel| {
+                                (sum + u32::from(pixel), count + 1)
+                            });
+                        // Round to nearest
+                        ((sum + count / 2) / count.max(1)) as u8
+                    }
+                };
+            }
+        }
+        self.buffer.as_by()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer.as_by()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+
+    #[test]
+    fn test_image_downsample() {
+        let ctxt = StubContext::default();
+        // A 2x4 image: row 0 = [10, 20, 30, 40], row 1 = [50, 60, 70, 255]
+        let image = Matrix {
+            data: [[10, 50], [20, 60], [30, 70], [40, 255]],
+        };
+        let mut block = ImageDownsampleBlock::<2, 4, 1, 2>::default();
+        assert_eq!(block.buffer(), &Matrix::zeroed());
+
+        let output = block.process(&Parameters::new("Average"), &ctxt, &image);
+        assert_eq!(output.data, [[35], [99]]);
+        let output = block.process(&Parameters::new("Nearest"), &ctxt, &image);
+        assert_eq!(output.data, [[10], [30]]);
+        let output = block.process(&Parameters::new("Max"), &ctxt, &image);
+        assert_eq!(output.data, [[60], [255]]);
+    }
+
+    #[test]
+    fn test_image_downsample_uneven() {
+        let ctxt = StubContext::default();
+        // 3 columns into 2 splits as [0..1] and [1..3]
+        let image = Matrix {
+            data: [[9], [1], [2]],
+        };
+        let mut block = ImageDownsampleBlock::<1, 3, 1, 2>::default();
+        let output = block.process(&Parameters::new("Average"), &ctxt, &image);
+        assert_eq!(output.data, [[9], [2]]);
+    }
+}
@@ -0,0 +1,82 @@
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+/// Parameters for the ImageThresholdBlock
+pub struct Parameters {
+    /// Pixels brighter than this are set in the output mask
+    pub threshold: u8,
+    /// Sets the pixels at or below the threshold instead, for dark features on a light background
+    pub invert: bool,
+}
+
+impl Parameters {
+    pub fn new(threshold: u8, invert: bool) -> Self {
+        Self { threshold, invert }
+    }
+}
+
+/// Converts a grayscale image to a binary mask.
+///
+/// Each output element is true where the input pixel is greater than the threshold (or at or below it,
+/// when inverted). The mask can be fed to the [`ImageCentroidBlock`](crate::ImageCentroidBlock) to
+/// locate a bright or dark feature, such as a line or a target.
+pub struct ImageThresholdBlock<const H: usize, const W: usize> {
+    buffer: Matrix<H, W, bool>,
+}
+
+impl<const H: usize, const W: usize> Default for ImageThresholdBlock<H, W> {
+    fn default() -> Self {
+        Self {
+            buffer: Matrix::zeroed(),
+        }
+    }
+}
+
+impl<const H: usize, const W: usize> ProcessBlock for ImageThresholdBlock<H, W> {
+    type Inputs = Matrix<H, W, u8>;
+    type Output = Matrix<H, W, bool>;
+    type Parameters = Parameters;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        for (output, pixel) in self
+            .buffer
+            .data
+            .as_flattened_mut()
+            .iter_mut()
+            .zip(input.data.as_flattened())
+        {
+            *output = (*pixel > parameters.threshold) != parameters.invert;
+        }
+        self.buffer.as_by()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer.as_by()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+
+    #[test]
+    fn test_image_threshold() {
+        let ctxt = StubContext::default();
+        let mut block = ImageThresholdBlock::<2, 3>::default();
+        assert_eq!(block.buffer(), &Matrix::zeroed());
+
+        let image = Matrix {
+            data: [[0, 127], [128, 200], [255, 10]],
+        };
+        let output = block.process(&Parameters::new(127, false), &ctxt, &image);
+        assert_eq!(output.data, [[false, false], [true, true], [true, false]]);
+
+        let output = block.process(&Parameters::new(127, true), &ctxt, &image);
+        assert_eq!(output.data, [[true, true], [false, false], [false, true]]);
+    }
+}
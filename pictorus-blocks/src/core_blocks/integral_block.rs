@@ -1,3 +1,4 @@
+use crate::ParameterError;
 use core::time::Duration;
 
 use crate::{
@@ -190,10 +191,30 @@ pub struct Parameters<T: Apply> {
 /// method: Method of integration (See [`IntgeralMethod`])
 impl<T: Apply> Parameters<T> {
     pub fn new(ic: T::Output, clamp_limit: T::Float, method: &str) -> Self {
+        Self::try_new(ic, clamp_limit, method).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(
+        ic: T::Output,
+        clamp_limit: T::Float,
+        method: &str,
+    ) -> Result<Self, ParameterError> {
+        Ok(Parameters {
+            clamp_limit,
+            ic,
+            method: method
+                .parse()
+                .map_err(|_| ParameterError("Failed to parse method"))?,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved integration method, without parsing
+    pub const fn from_method(ic: T::Output, clamp_limit: T::Float, method: IntgeralMethod) -> Self {
         Parameters {
             clamp_limit,
             ic,
-            method: method.parse().unwrap(),
+            method,
         }
     }
 }
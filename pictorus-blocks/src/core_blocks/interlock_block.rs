@@ -0,0 +1,269 @@
+use core::time::Duration;
+
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+use crate::stale_tracker::{duration_from_ms_f64, elapsed};
+use crate::ParameterError;
+
+/// Parameters for the InterlockBlock
+pub struct Parameters<const N: usize> {
+    /// Interlock group of each output. Outputs sharing a nonzero group are mutually exclusive,
+    /// and group 0 outputs are independent.
+    pub groups: [u32; N],
+    /// Shortest time an output stays on once switched on
+    pub min_on_time: Duration,
+    /// Shortest time an output stays off once switched off
+    pub min_off_time: Duration,
+    /// Time after an output switches off before another output in its group may switch on,
+    /// e.g. for a contactor's arc to clear
+    pub changeover_time: Duration,
+}
+
+impl<const N: usize> Parameters<N> {
+    pub fn new(
+        groups: [f64; N],
+        min_on_time_ms: f64,
+        min_off_time_ms: f64,
+        changeover_time_ms: f64,
+    ) -> Self {
+        Self::try_new(groups, min_on_time_ms, min_off_time_ms, changeover_time_ms)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(
+        groups: [f64; N],
+        min_on_time_ms: f64,
+        min_off_time_ms: f64,
+        changeover_time_ms: f64,
+    ) -> Result<Self, ParameterError> {
+        if groups
+            .iter()
+            .any(|group| f64::from(*group as u32) != *group)
+        {
+            return Err(ParameterError(
+                "Interlock groups must be non-negative integers",
+            ));
+        }
+        let times = [min_on_time_ms, min_off_time_ms, changeover_time_ms];
+        if times.iter().any(|time| !time.is_finite() || *time < 0.0) {
+            return Err(ParameterError(
+                "Interlock times must be non-negative numbers of milliseconds",
+            ));
+        }
+        Ok(Self {
+            groups: groups.map(|group| group as u32),
+            min_on_time: duration_from_ms_f64(min_on_time_ms),
+            min_off_time: duration_from_ms_f64(min_off_time_ms),
+            changeover_time: duration_from_ms_f64(changeover_time_ms),
+        })
+    }
+}
+
+/// Sequences N relays or contactors from boolean commands, refusing commands that would break
+/// their interlocks.
+///
+/// Outputs in the same interlock group (e.g. the forward and reverse contactors of a motor) are
+/// never on together. An output in a group only switches on once every other output in the
+/// group is off, isn't commanded on, and has been off for the changeover time, so commanding
+/// two outputs of a group on at once switches neither. Each output also keeps its state for the
+/// minimum on or off time after switching, before following its command.
+///
+/// The output is a tuple of (outputs, violation), where violation is true while any output
+/// differs from its command because it was refused or held.
+pub struct InterlockBlock<const N: usize> {
+    buffer: (Matrix<1, N, bool>, bool),
+    /// When each output last switched, None if it never has
+    switched: [Option<Duration>; N],
+}
+
+impl<const N: usize> Default for InterlockBlock<N> {
+    fn default() -> Self {
+        Self {
+            buffer: (Matrix::zeroed(), false),
+            switched: [None; N],
+        }
+    }
+}
+
+impl<const N: usize> ProcessBlock for InterlockBlock<N> {
+    type Inputs = Matrix<1, N, bool>;
+    type Output = (Matrix<1, N, bool>, bool);
+    type Parameters = Parameters<N>;
+
+    fn process(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn pictorus_traits::Context,
+        input: PassBy<Self::Inputs>,
+    ) -> PassBy<'_, Self::Output> {
+        let time = context.time();
+        let commands = input.data.map(|command| command[0]);
+        let outputs = &mut self.buffer.0.data;
+        let switched = &mut self.switched;
+        let dwelled = |switched: Option<Duration>, dwell: Duration| {
+            switched.is_none_or(|switched| elapsed(time, switched).unwrap_or_default() >= dwell)
+        };
+
+        // Switch off first, so a changeover with no delay can happen in one tick
+        for index in 0..N {
+            if outputs[index][0]
+                && !commands[index]
+                && dwelled(switched[index], parameters.min_on_time)
+            {
+                outputs[index][0] = false;
+                switched[index] = Some(time);
+            }
+        }
+
+        for index in 0..N {
+            if outputs[index][0]
+                || !commands[index]
+                || !dwelled(switched[index], parameters.min_off_time)
+            {
+                continue;
+            }
+            let group = parameters.groups[index];
+            let interlocked = group != 0
+                && (0..N).any(|other| {
+                    other != index
+                        && parameters.groups[other] == group
+                        && (outputs[other][0]
+                            || commands[other]
+                            || !dwelled(switched[other], parameters.changeover_time))
+                });
+            if !interlocked {
+                outputs[index][0] = true;
+                switched[index] = Some(time);
+            }
+        }
+
+        self.buffer.1 = outputs
+            .iter()
+            .zip(&commands)
+            .any(|(output, command)| output[0] != *command);
+        self.buffer.as_by()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer.as_by()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+
+    fn commands<const N: usize>(commands: [bool; N]) -> Matrix<1, N, bool> {
+        Matrix {
+            data: commands.map(|command| [command]),
+        }
+    }
+
+    fn process<const N: usize>(
+        block: &mut InterlockBlock<N>,
+        parameters: &Parameters<N>,
+        runtime: &StubRuntime,
+        input: [bool; N],
+    ) -> ([bool; N], bool) {
+        let (outputs, violation) = block.process(parameters, &runtime.context(), &commands(input));
+        (outputs.data.map(|output| output[0]), violation)
+    }
+
+    #[test]
+    fn test_interlock_default_buffer_no_panic() {
+        let block = InterlockBlock::<2>::default();
+        assert_eq!(block.buffer(), (&Matrix::zeroed(), false));
+    }
+
+    #[test]
+    fn test_interlock_mutual_exclusion() {
+        let runtime = StubRuntime::default();
+        // Forward and reverse contactors, and an independent fan
+        let parameters = Parameters::new([1.0, 1.0, 0.0], 0.0, 0.0, 0.0);
+        let mut block = InterlockBlock::<3>::default();
+
+        // Conflicting commands switch neither contactor
+        assert_eq!(
+            process(&mut block, &parameters, &runtime, [true, true, true]),
+            ([false, false, true], true)
+        );
+        assert_eq!(
+            process(&mut block, &parameters, &runtime, [true, false, true]),
+            ([true, false, true], false)
+        );
+        // The contactor that is already on keeps priority
+        assert_eq!(
+            process(&mut block, &parameters, &runtime, [true, true, false]),
+            ([true, false, false], true)
+        );
+        // Without a changeover time, reversing takes one tick
+        assert_eq!(
+            process(&mut block, &parameters, &runtime, [false, true, false]),
+            ([false, true, false], false)
+        );
+    }
+
+    #[test]
+    fn test_interlock_dwell_times() {
+        let mut runtime = StubRuntime::default();
+        let parameters = Parameters::new([1.0, 1.0], 100.0, 50.0, 200.0);
+        let mut block = InterlockBlock::<2>::default();
+
+        assert_eq!(
+            process(&mut block, &parameters, &runtime, [true, false]),
+            ([true, false], false)
+        );
+
+        // Held on for the minimum on time
+        runtime.set_time(Duration::from_millis(50));
+        assert_eq!(
+            process(&mut block, &parameters, &runtime, [false, true]),
+            ([true, false], true)
+        );
+        runtime.set_time(Duration::from_millis(100));
+        assert_eq!(
+            process(&mut block, &parameters, &runtime, [false, true]),
+            ([false, false], true)
+        );
+
+        // The other contactor waits for the changeover time
+        runtime.set_time(Duration::from_millis(250));
+        assert_eq!(
+            process(&mut block, &parameters, &runtime, [false, true]),
+            ([false, false], true)
+        );
+        runtime.set_time(Duration::from_millis(300));
+        assert_eq!(
+            process(&mut block, &parameters, &runtime, [false, true]),
+            ([false, true], false)
+        );
+
+        // Held off for the minimum off time
+        runtime.set_time(Duration::from_millis(400));
+        assert_eq!(
+            process(&mut block, &parameters, &runtime, [false, false]),
+            ([false, false], false)
+        );
+        runtime.set_time(Duration::from_millis(420));
+        assert_eq!(
+            process(&mut block, &parameters, &runtime, [false, true]),
+            ([false, false], true)
+        );
+        runtime.set_time(Duration::from_millis(450));
+        assert_eq!(
+            process(&mut block, &parameters, &runtime, [false, true]),
+            ([false, true], false)
+        );
+    }
+
+    #[test]
+    fn test_interlock_invalid_parameters() {
+        assert!(Parameters::try_new([1.5], 0.0, 0.0, 0.0).is_err());
+        assert!(Parameters::try_new([-1.0], 0.0, 0.0, 0.0).is_err());
+        assert!(Parameters::try_new([f64::NAN], 0.0, 0.0, 0.0).is_err());
+        assert!(Parameters::try_new([1.0], 0.0, -1.0, 0.0).is_err());
+        assert!(Parameters::try_new([1.0], 0.0, 0.0, f64::INFINITY).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+use crate::fast_math::FastMath;
+use crate::traits::Float;
+use pictorus_traits::{PassBy, ProcessBlock};
+
+/// Parameters for the InverseParkBlock
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Parameters;
+
+impl Parameters {
+    pub fn new() -> Self {
+        Parameters {}
+    }
+}
+
+/// Inverse Park transform, rotating `(d, q)` quantities in the rotor frame back into the
+/// stationary `(alpha, beta)` frame.
+///
+/// Inputs are `(d, q, theta)` where `theta` is the electrical angle in radians, and the output is
+/// `(alpha, beta)`. Typically used to turn the d/q voltage commands of the current controllers
+/// into the input of the [`SvpwmBlock`](crate::SvpwmBlock).
+pub struct InverseParkBlock<F: Float> {
+    buffer: (F, F),
+}
+
+impl<F: Float> Default for InverseParkBlock<F> {
+    fn default() -> Self {
+        Self {
+            buffer: (F::default(), F::default()),
+        }
+    }
+}
+
+impl<F: Float> ProcessBlock for InverseParkBlock<F> {
+    type Inputs = (F, F, F);
+    type Output = (F, F);
+    type Parameters = Parameters;
+
+    fn process<'b>(
+        &'b mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (d, q, theta) = inputs;
+        let (sin, cos) = FastMath::fast_sin_cos(theta);
+        self.buffer = (d * cos - q * sin, d * sin + q * cos);
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{fast_math_epsilon, StubContext};
+    use crate::ParkBlock;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_inverse_park_default_buffer_no_panic() {
+        let block = InverseParkBlock::<f32>::default();
+        assert_eq!(block.buffer(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_inverse_park_round_trip() {
+        let context = StubContext::default();
+        let mut inverse = InverseParkBlock::<f64>::default();
+        let mut park = ParkBlock::<f64>::default();
+
+        let (alpha, beta) = inverse.process(&Parameters::new(), &context, (0.0, 2.0, 0.0));
+        assert_eq!((alpha, beta), (0.0, 2.0));
+
+        for theta in [0.25f64, 1.5, -3.0] {
+            let (alpha, beta) = inverse.process(&Parameters::new(), &context, (1.5, -0.5, theta));
+            let park_params = <ParkBlock<f64> as ProcessBlock>::Parameters::new();
+            let (d, q) = park.process(&park_params, &context, (alpha, beta, theta));
+            assert_relative_eq!(d, 1.5, epsilon = fast_math_epsilon(1e-12));
+            assert_relative_eq!(q, -0.5, epsilon = fast_math_epsilon(1e-12));
+        }
+    }
+}
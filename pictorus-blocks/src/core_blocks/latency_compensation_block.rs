@@ -0,0 +1,293 @@
+use crate::traits::Float;
+use crate::ParameterError;
+use pictorus_traits::{Context, Matrix, Pass, PassBy, ProcessBlock};
+
+#[derive(strum::EnumString, Clone, Copy, Debug, PartialEq)]
+/// How the measurement is propagated from its timestamp to the current time
+pub enum CompensationMethod {
+    /// Integrates the derivative input recorded on each tick since the measurement was taken
+    Derivative,
+    /// Extrapolates with the rate of change between the last two measurements. The derivative
+    /// input is ignored.
+    ConstantVelocity,
+}
+
+/// Parameters for the LatencyCompensationBlock
+#[derive(Clone, Copy, Debug)]
+pub struct Parameters {
+    pub method: CompensationMethod,
+}
+
+impl Parameters {
+    pub fn new(method: &str) -> Self {
+        Self::try_new(method).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(method: &str) -> Result<Self, ParameterError> {
+        Ok(Self {
+            method: method
+                .parse()
+                .map_err(|_| ParameterError("Failed to parse method"))?,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved compensation method, without parsing
+    pub const fn from_method(method: CompensationMethod) -> Self {
+        Self { method }
+    }
+}
+
+/// Compensates the transport delay of a measurement by shifting it forward to the current time.
+///
+/// The inputs are the measurement, the time it was taken (in seconds, on the same clock as the
+/// app time) and the derivative of the measured state, e.g. velocity from an IMU to compensate a
+/// delayed GPS position. Each tick the block records the derivative, and outputs
+///
+/// `measurement + ∫ derivative dt` from the measurement's timestamp to now
+///
+/// using the recorded derivatives, each held until the next tick. The last `N` ticks are kept, so
+/// `N` times the tick period must cover the largest expected latency; older parts of the
+/// interval use the oldest recorded derivative. With the `ConstantVelocity` method the block
+/// instead extrapolates along the line through the last two measurements, for states without a
+/// derivative signal (`N` can then be 0).
+///
+/// Timestamps in the future are treated as current, so the output is never shifted backwards.
+/// The inputs can be scalars or matrices, which are compensated element-wise.
+pub struct LatencyCompensationBlock<T: Apply, const N: usize = 32> {
+    buffer: T,
+    /// Derivative recorded on each tick as (app time, derivative), oldest first
+    history: [(f64, T); N],
+    len: usize,
+    /// Last two distinct measurements as (timestamp, value), newest last
+    measurements: [Option<(f64, T)>; 2],
+}
+
+impl<T: Apply, const N: usize> Default for LatencyCompensationBlock<T, N> {
+    fn default() -> Self {
+        Self {
+            buffer: T::default(),
+            history: [(0.0, T::default()); N],
+            len: 0,
+            measurements: [None; 2],
+        }
+    }
+}
+
+impl<T: Apply, const N: usize> LatencyCompensationBlock<T, N> {
+    fn record_derivative(&mut self, time: f64, derivative: T) {
+        if N == 0 {
+            return;
+        }
+        if self.len == N {
+            self.history.rotate_left(1);
+            self.len -= 1;
+        }
+        if let Some(entry) = self.history.get_mut(self.len) {
+            *entry = (time, derivative);
+            self.len += 1;
+        }
+    }
+
+    fn record_measurement(&mut self, timestamp: f64, measurement: T) {
+        match self.measurements[1] {
+            Some((last, _)) if timestamp <= last => {}
+            _ if timestamp.is_nan() => {}
+            _ => self.measurements = [self.measurements[1], Some((timestamp, measurement))],
+        }
+    }
+
+    /// Adds `∫ derivative dt` from `from` to `now` to `store`
+    fn integrate_derivative(&self, store: &mut T, from: f64, now: f64) {
+        let history = self.history.get(..self.len).unwrap_or_default();
+        for (k, (start, derivative)) in history.iter().enumerate() {
+            // The oldest derivative also stands in for the ticks before the history
+            let start = if k == 0 { from.min(*start) } else { *start };
+            let end = history.get(k + 1).map_or(now, |(next, _)| *next);
+            let overlap = end.min(now) - start.max(from);
+            if overlap > 0.0 {
+                store.add_scaled(derivative, overlap);
+            }
+        }
+    }
+}
+
+impl<T: Apply, const N: usize> ProcessBlock for LatencyCompensationBlock<T, N> {
+    type Inputs = (T, f64, T);
+    type Output = T;
+    type Parameters = Parameters;
+
+    fn process(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+        inputs: PassBy<Self::Inputs>,
+    ) -> PassBy<'_, Self::Output> {
+        let (measurement, timestamp, derivative) = inputs;
+        let now = context.time().as_secs_f64();
+        let measurement = T::to_owned(measurement);
+        self.record_derivative(now, T::to_owned(derivative));
+        self.record_measurement(timestamp, measurement);
+
+        self.buffer = measurement;
+        let latency = now - timestamp;
+        if latency > 0.0 {
+            match parameters.method {
+                CompensationMethod::Derivative => {
+                    let mut buffer = self.buffer;
+                    self.integrate_derivative(&mut buffer, timestamp, now);
+                    self.buffer = buffer;
+                }
+                CompensationMethod::ConstantVelocity => {
+                    if let [Some((t0, m0)), Some((t1, m1))] = self.measurements {
+                        // Velocity * latency, as (m1 - m0) * latency / (t1 - t0)
+                        let scale = latency / (t1 - t0);
+                        self.buffer.add_scaled(&m1, scale);
+                        self.buffer.add_scaled(&m0, -scale);
+                    }
+                }
+            }
+        }
+        self.buffer.as_by()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer.as_by()
+    }
+}
+
+pub trait Apply: Pass + Default + Copy {
+    fn to_owned(input: PassBy<Self>) -> Self;
+
+    /// `self += other * scale`
+    fn add_scaled(&mut self, other: &Self, scale: f64);
+}
+
+impl<F: Float> Apply for F {
+    fn to_owned(input: PassBy<Self>) -> Self {
+        input
+    }
+
+    fn add_scaled(&mut self, other: &Self, scale: f64) {
+        *self += *other * F::from(scale).unwrap_or_else(F::zero);
+    }
+}
+
+impl<const NROWS: usize, const NCOLS: usize, F: Float> Apply for Matrix<NROWS, NCOLS, F> {
+    fn to_owned(input: PassBy<Self>) -> Self {
+        *input
+    }
+
+    fn add_scaled(&mut self, other: &Self, scale: f64) {
+        let scale = F::from(scale).unwrap_or_else(F::zero);
+        self.data
+            .as_flattened_mut()
+            .iter_mut()
+            .zip(other.data.as_flattened())
+            .for_each(|(lhs, rhs)| *lhs += *rhs * scale);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+    use approx::assert_relative_eq;
+    use core::time::Duration;
+
+    #[test]
+    fn test_latency_compensation_default_buffer_no_panic() {
+        let block = LatencyCompensationBlock::<f64>::default();
+        assert_eq!(block.buffer(), 0.0);
+
+        let block = LatencyCompensationBlock::<Matrix<3, 1, f32>, 8>::default();
+        assert_eq!(block.buffer(), &Matrix::zeroed());
+    }
+
+    #[test]
+    fn test_latency_compensation_derivative() {
+        // Position of a body accelerating at 1 m/s^2, measured with 0.3s of latency. The
+        // velocity input is current.
+        let mut runtime = StubRuntime::with_timestep(Duration::from_millis(100));
+        let parameters = Parameters::new("Derivative");
+        let mut block = LatencyCompensationBlock::<f64, 10>::default();
+        let position = |t: f64| 0.5 * t * t;
+
+        for _ in 0..20 {
+            let now = runtime.context().time().as_secs_f64();
+            let stamp = (now - 0.3).max(0.0);
+            let output = block.process(
+                &parameters,
+                &runtime.context(),
+                (position(stamp), stamp, now),
+            );
+            if now >= 0.3 {
+                // Integrating a velocity held over each tick lags the true position by
+                // 0.5 * latency * tick
+                assert_relative_eq!(output, position(now) - 0.5 * 0.3 * 0.1, epsilon = 1e-9);
+            }
+            runtime.tick();
+        }
+    }
+
+    #[test]
+    fn test_latency_compensation_beyond_history() {
+        let mut runtime = StubRuntime::with_timestep(Duration::from_millis(100));
+        let parameters = Parameters::new("Derivative");
+        let mut block = LatencyCompensationBlock::<f64, 2>::default();
+
+        runtime.tick_n(10);
+        block.process(&parameters, &runtime.context(), (0.0, 0.0, 1.0));
+        runtime.tick();
+        block.process(&parameters, &runtime.context(), (0.0, 0.0, 2.0));
+        runtime.tick();
+        // The derivative of 1 has dropped out of the history, and the oldest one left (2, from
+        // 1.1s) covers everything before it. This tick's derivative only applies from now on.
+        let output = block.process(&parameters, &runtime.context(), (0.0, 0.0, 3.0));
+        assert_relative_eq!(output, 2.0 * 1.2, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_latency_compensation_constant_velocity() {
+        let mut runtime = StubRuntime::with_timestep(Duration::from_millis(100));
+        let parameters = Parameters::new("ConstantVelocity");
+        let mut block = LatencyCompensationBlock::<Matrix<2, 1, f64>, 0>::default();
+        let unused = Matrix::zeroed();
+
+        // A single measurement can't be extrapolated
+        let m0 = Matrix { data: [[1.0, 2.0]] };
+        runtime.tick_n(2);
+        let output = block.process(&parameters, &runtime.context(), (&m0, 0.0, &unused));
+        assert_eq!(output.data, [[1.0, 2.0]]);
+
+        // Moving at [2, -1] per second, measured 0.2s late
+        let m1 = Matrix { data: [[1.2, 1.9]] };
+        runtime.tick_n(1);
+        let output = block.process(&parameters, &runtime.context(), (&m1, 0.1, &unused));
+        assert_relative_eq!(output.data[0][0], 1.6, epsilon = 1e-9);
+        assert_relative_eq!(output.data[0][1], 1.7, epsilon = 1e-9);
+
+        // The measurement keeps being extrapolated until a new one arrives
+        runtime.tick();
+        let output = block.process(&parameters, &runtime.context(), (&m1, 0.1, &unused));
+        assert_relative_eq!(output.data[0][0], 1.8, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_latency_compensation_future_timestamp() {
+        let runtime = StubRuntime::default();
+        let parameters = Parameters::new("Derivative");
+        let mut block = LatencyCompensationBlock::<f32>::default();
+        let output = block.process(&parameters, &runtime.context(), (5.0, 1.0, 10.0));
+        assert_eq!(output, 5.0);
+    }
+
+    #[test]
+    fn test_latency_compensation_parameters() {
+        assert_eq!(
+            Parameters::new("ConstantVelocity").method,
+            CompensationMethod::ConstantVelocity
+        );
+        assert!(Parameters::try_new("Kalman").is_err());
+    }
+}
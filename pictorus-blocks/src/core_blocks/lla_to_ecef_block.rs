@@ -0,0 +1,71 @@
+use crate::geodesy::{lla_to_ecef, vector_from_f64, vector_to_f64};
+use crate::traits::Float;
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+/// Parameters for the LlaToEcefBlock
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Parameters;
+
+impl Parameters {
+    pub fn new() -> Self {
+        Parameters {}
+    }
+}
+
+/// Converts a WGS-84 geodetic position to Earth-centered, Earth-fixed (ECEF) coordinates.
+///
+/// The input is `(latitude, longitude, altitude)` as a (1, 3) matrix, with latitude and longitude in
+/// degrees and altitude in meters above the ellipsoid. The output is `(x, y, z)` in meters.
+/// The conversion is computed in double precision regardless of the block's float type.
+pub struct LlaToEcefBlock<F: Float> {
+    buffer: Matrix<1, 3, F>,
+}
+
+impl<F: Float> Default for LlaToEcefBlock<F> {
+    fn default() -> Self {
+        Self {
+            buffer: Matrix::zeroed(),
+        }
+    }
+}
+
+impl<F: Float> ProcessBlock for LlaToEcefBlock<F> {
+    type Inputs = Matrix<1, 3, F>;
+    type Output = Matrix<1, 3, F>;
+    type Parameters = Parameters;
+
+    fn process<'b>(
+        &'b mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        vector_from_f64(lla_to_ecef(vector_to_f64(input)), &mut self.buffer);
+        self.buffer.as_by()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer.as_by()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_lla_to_ecef() {
+        let ctxt = StubContext::default();
+        let mut block = LlaToEcefBlock::<f64>::default();
+        let input = Matrix {
+            data: [[0.0], [90.0], [10.0]],
+        };
+        let output = *block.process(&Parameters::new(), &ctxt, &input);
+        assert_relative_eq!(output.data[0][0], 0.0, epsilon = 1e-6);
+        assert_relative_eq!(output.data[1][0], 6_378_147.0, epsilon = 1e-6);
+        assert_relative_eq!(output.data[2][0], 0.0, epsilon = 1e-6);
+        assert_eq!(block.buffer(), &output);
+    }
+}
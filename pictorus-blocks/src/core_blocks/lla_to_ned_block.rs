@@ -0,0 +1,102 @@
+use crate::geodesy::{lla_to_ecef, vector_from_f64, vector_to_f64, NedFrame};
+use crate::traits::Float;
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+/// Parameters for the LlaToNedBlock
+pub struct Parameters {
+    frame: NedFrame,
+}
+
+impl Parameters {
+    /// Creates the parameters for a local frame at the given origin, with latitude and longitude in
+    /// degrees and altitude in meters above the ellipsoid
+    pub fn new(origin_latitude: f64, origin_longitude: f64, origin_altitude: f64) -> Self {
+        Self {
+            frame: NedFrame::new([origin_latitude, origin_longitude, origin_altitude]),
+        }
+    }
+}
+
+/// Converts a WGS-84 geodetic position to north-east-down (NED) coordinates relative to a local origin.
+///
+/// The input is `(latitude, longitude, altitude)` as a (1, 3) matrix, with latitude and longitude in
+/// degrees and altitude in meters above the ellipsoid. The output is `(north, east, down)` in meters,
+/// in the plane tangent to the ellipsoid at the origin. The conversion goes through ECEF, so it
+/// is exact at any distance from the origin, and is computed in double precision regardless of the
+/// block's float type.
+pub struct LlaToNedBlock<F: Float> {
+    buffer: Matrix<1, 3, F>,
+}
+
+impl<F: Float> Default for LlaToNedBlock<F> {
+    fn default() -> Self {
+        Self {
+            buffer: Matrix::zeroed(),
+        }
+    }
+}
+
+impl<F: Float> ProcessBlock for LlaToNedBlock<F> {
+    type Inputs = Matrix<1, 3, F>;
+    type Output = Matrix<1, 3, F>;
+    type Parameters = Parameters;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let ecef = lla_to_ecef(vector_to_f64(input));
+        vector_from_f64(parameters.frame.ecef_to_ned(ecef), &mut self.buffer);
+        self.buffer.as_by()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer.as_by()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_lla_to_ned() {
+        let ctxt = StubContext::default();
+        let params = Parameters::new(47.0, 8.0, 400.0);
+        let mut block = LlaToNedBlock::<f64>::default();
+
+        // A thousandth of a degree of latitude is about 111m north, and going up is negative down
+        let input = Matrix {
+            data: [[47.001], [8.0], [410.0]],
+        };
+        let output = block.process(&params, &ctxt, &input);
+        assert_relative_eq!(output.data[0][0], 111.2, epsilon = 0.1);
+        assert_relative_eq!(output.data[1][0], 0.0, epsilon = 1e-6);
+        assert_relative_eq!(output.data[2][0], -10.0, epsilon = 0.01);
+
+        // A thousandth of a degree of longitude is shorter by cos(latitude)
+        let input = Matrix {
+            data: [[47.0], [8.001], [400.0]],
+        };
+        let output = block.process(&params, &ctxt, &input);
+        assert_relative_eq!(output.data[0][0], 0.0, epsilon = 0.01);
+        assert_relative_eq!(output.data[1][0], 76.0, epsilon = 0.1);
+    }
+
+    #[test]
+    fn test_lla_to_ned_f32_precision() {
+        let ctxt = StubContext::default();
+        let params = Parameters::new(37.0, -122.0, 0.0);
+        let mut block = LlaToNedBlock::<f32>::default();
+        let input = Matrix {
+            data: [[37.0], [-122.0], [1.5]],
+        };
+        // Working in f32 throughout would lose the altitude in ECEF rounding
+        let output = block.process(&params, &ctxt, &input);
+        assert_relative_eq!(output.data[2][0], -1.5, epsilon = 1e-3);
+    }
+}
@@ -0,0 +1,129 @@
+use core::time::Duration;
+
+use pictorus_traits::{PassBy, ProcessBlock};
+
+use crate::stale_tracker::{duration_from_ms_f64, elapsed};
+use crate::traits::Scalar;
+use crate::ParameterError;
+
+/// Parameters for the LogTriggerBlock
+pub struct Parameters {
+    /// How long recording continues after the trigger goes false
+    pub post_trigger: Duration,
+}
+
+impl Parameters {
+    pub fn new(post_trigger_ms: f64) -> Self {
+        Self::try_new(post_trigger_ms).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(post_trigger_ms: f64) -> Result<Self, ParameterError> {
+        if !post_trigger_ms.is_finite() || post_trigger_ms < 0.0 {
+            return Err(ParameterError(
+                "Post trigger window must be a non-negative number of milliseconds",
+            ));
+        }
+        Ok(Self {
+            post_trigger: duration_from_ms_f64(post_trigger_ms),
+        })
+    }
+}
+
+/// Decides when high-rate data should be recorded, so logs only hold the data around events of
+/// interest.
+///
+/// Outputs true while the trigger input is truthy and for the post trigger window after it goes
+/// false. A trigger during the post trigger window extends the recording. The output is meant to
+/// drive the app's logger, which keeps the samples from the pre trigger window before the
+/// recording starts; see `TriggeredLogger` in pictorus-internal.
+#[derive(Default)]
+pub struct LogTriggerBlock<T> {
+    buffer: bool,
+    last_triggered: Option<Duration>,
+    phantom: core::marker::PhantomData<T>,
+}
+
+impl<T: Scalar> ProcessBlock for LogTriggerBlock<T> {
+    type Inputs = T;
+    type Output = bool;
+    type Parameters = Parameters;
+
+    fn process(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn pictorus_traits::Context,
+        input: PassBy<Self::Inputs>,
+    ) -> PassBy<'_, Self::Output> {
+        let time = context.time();
+        if input.is_truthy() {
+            self.last_triggered = Some(time);
+        }
+        self.buffer = self
+            .last_triggered
+            .and_then(|triggered| elapsed(time, triggered))
+            .is_some_and(|elapsed| elapsed <= parameters.post_trigger);
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+
+    #[test]
+    fn test_log_trigger_default_buffer_no_panic() {
+        let block = LogTriggerBlock::<f64>::default();
+        assert!(!block.buffer());
+    }
+
+    #[test]
+    fn test_log_trigger_post_window() {
+        let parameters = Parameters::new(250.0);
+        let mut block = LogTriggerBlock::<f64>::default();
+
+        let inputs = [0.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+        let expected = [
+            false, true, true, true, true, false, false, true, true, true,
+        ];
+        for (tick, (input, expected)) in inputs.iter().zip(expected).enumerate() {
+            let context = StubContext::new(
+                Duration::from_millis(100 * tick as u64),
+                None,
+                Duration::from_millis(100),
+            );
+            assert_eq!(
+                block.process(&parameters, &context, *input),
+                expected,
+                "tick {tick}"
+            );
+            assert_eq!(block.buffer(), expected);
+        }
+    }
+
+    #[test]
+    fn test_log_trigger_retrigger_extends_window() {
+        let parameters = Parameters::new(200.0);
+        let mut block = LogTriggerBlock::<bool>::default();
+        let at = |ms| StubContext::new(Duration::from_millis(ms), None, Duration::from_millis(100));
+
+        assert!(block.process(&parameters, &at(0), true));
+        assert!(block.process(&parameters, &at(100), false));
+        assert!(block.process(&parameters, &at(200), true));
+        assert!(block.process(&parameters, &at(300), false));
+        assert!(block.process(&parameters, &at(400), false));
+        assert!(!block.process(&parameters, &at(500), false));
+    }
+
+    #[test]
+    fn test_log_trigger_parameters() {
+        assert_eq!(Parameters::new(0.0).post_trigger, Duration::ZERO);
+        assert!(Parameters::try_new(-1.0).is_err());
+        assert!(Parameters::try_new(f64::NAN).is_err());
+    }
+}
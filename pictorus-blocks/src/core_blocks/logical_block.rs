@@ -1,3 +1,4 @@
+use crate::ParameterError;
 use core::ops::Sub;
 use num_traits::One;
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
@@ -201,9 +202,27 @@ pub struct Parameters {
 
 impl Parameters {
     pub fn new(method: &str) -> Self {
-        Self {
-            method: method.parse().expect("Failed to parse logical method."),
-        }
+        Self::try_new(method).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(method: &str) -> Result<Self, ParameterError> {
+        Ok(Self {
+            method: method
+                .parse()
+                .map_err(|_| ParameterError("Failed to parse logical method."))?,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved logical method, without parsing
+    pub const fn from_method(method: LogicalMethod) -> Self {
+        Self { method }
+    }
+}
+
+impl From<LogicalMethod> for Parameters {
+    fn from(method: LogicalMethod) -> Self {
+        Self::from_method(method)
     }
 }
 
@@ -1,3 +1,4 @@
+use crate::ParameterError;
 use core::marker::PhantomData;
 
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
@@ -67,10 +68,33 @@ pub struct Parameters<const N: usize, S: Float> {
 
 impl<const N: usize, S: Float> Parameters<N, S> {
     pub fn new(interp_method: &str, break_points_u1: [S; N], data_points: [S; N]) -> Self {
-        Self {
+        Self::try_new(interp_method, break_points_u1, data_points)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(
+        interp_method: &str,
+        break_points_u1: [S; N],
+        data_points: [S; N],
+    ) -> Result<Self, ParameterError> {
+        Ok(Self {
             interp_method: interp_method
                 .parse()
-                .expect("Invalid interp method. Must be Linear or Nearest"),
+                .map_err(|_| ParameterError("Invalid interp method. Must be Linear or Nearest"))?,
+            break_points_u1,
+            data_points,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved interpolation method, without parsing
+    pub const fn from_interp_method(
+        interp_method: InterpMethod,
+        break_points_u1: [S; N],
+        data_points: [S; N],
+    ) -> Self {
+        Self {
+            interp_method,
             break_points_u1,
             data_points,
         }
@@ -125,40 +149,46 @@ impl<const N: usize, const NROWS: usize, const NCOLS: usize, S: Float> Apply<N,
     }
 }
 
+/// The breakpoint segment `value` falls in, as `((x0, y0), (x1, y1))`. This is `None` for a NaN
+/// `value`, since it isn't below any breakpoint.
+fn segment<const N: usize, S: Float>(
+    value: S,
+    params: &Parameters<N, S>,
+) -> Option<((S, S), (S, S))> {
+    params
+        .break_points_u1
+        .windows(2)
+        .zip(params.data_points.windows(2))
+        .find_map(|(x, y)| match (x, y) {
+            (&[x0, x1], &[y0, y1]) if value < x1 => Some(((x0, y0), (x1, y1))),
+            _ => None,
+        })
+}
+
 fn linear_interpolation<const N: usize, S: Float>(
     lookup_point_val: S,
     params: &Parameters<N, S>,
 ) -> S {
-    let mut idx: usize = 0;
-    for (i, break_point) in params.break_points_u1.iter().enumerate() {
-        if lookup_point_val < *break_point {
-            idx = i;
-            break;
-        }
-    }
-
-    let k = (lookup_point_val - params.break_points_u1[idx - 1])
-        / (params.break_points_u1[idx] - params.break_points_u1[idx - 1]);
-    params.data_points[idx - 1] + k * (params.data_points[idx] - params.data_points[idx - 1])
+    let Some(((x0, y0), (x1, y1))) = segment(lookup_point_val, params) else {
+        return S::nan();
+    };
+    let k = (lookup_point_val - x0) / (x1 - x0);
+    y0 + k * (y1 - y0)
 }
 
 fn nearest_interpolation<const N: usize, S: Float>(
     lookup_point_val: S,
     params: &Parameters<N, S>,
 ) -> S {
-    let mut idx: usize = 0;
-    for (i, break_point) in params.break_points_u1.iter().enumerate() {
-        if lookup_point_val < *break_point {
-            idx = i;
-            break;
-        }
-    }
-    let delt_high = params.break_points_u1[idx] - lookup_point_val;
-    let delt_low = lookup_point_val - params.break_points_u1[idx - 1];
+    let Some(((x0, y0), (x1, y1))) = segment(lookup_point_val, params) else {
+        return S::nan();
+    };
+    let delt_high = x1 - lookup_point_val;
+    let delt_low = lookup_point_val - x0;
 
     match delt_high > delt_low {
-        true => params.data_points[idx - 1],
-        false => params.data_points[idx],
+        true => y0,
+        false => y1,
     }
 }
 
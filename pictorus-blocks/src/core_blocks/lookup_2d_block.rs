@@ -1,3 +1,4 @@
+use crate::ParameterError;
 use core::marker::PhantomData;
 
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
@@ -78,10 +79,36 @@ impl<const NX: usize, const NY: usize, S: Float> Parameters<NX, NY, S> {
         break_points_u2: [S; NY],
         data_points: Matrix<NX, NY, S>,
     ) -> Self {
-        Self {
+        Self::try_new(interp_method, break_points_u1, break_points_u2, data_points)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(
+        interp_method: &str,
+        break_points_u1: [S; NX],
+        break_points_u2: [S; NY],
+        data_points: Matrix<NX, NY, S>,
+    ) -> Result<Self, ParameterError> {
+        Ok(Self {
             interp_method: interp_method
                 .parse()
-                .expect("Invalid interp method. Must be Linear or Nearest"),
+                .map_err(|_| ParameterError("Invalid interp method. Must be Linear or Nearest"))?,
+            break_points_u1,
+            break_points_u2,
+            data_points,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved interpolation method, without parsing
+    pub const fn from_interp_method(
+        interp_method: InterpMethod,
+        break_points_u1: [S; NX],
+        break_points_u2: [S; NY],
+        data_points: Matrix<NX, NY, S>,
+    ) -> Self {
+        Self {
+            interp_method,
             break_points_u1,
             break_points_u2,
             data_points,
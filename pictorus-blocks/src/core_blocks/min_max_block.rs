@@ -1,5 +1,6 @@
 use crate::matrix_ext::MatrixNalgebraExt;
 use crate::traits::{Apply, ApplyInto, MatrixOps, Scalar};
+use crate::ParameterError;
 use nalgebra::SMatrix;
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
 
@@ -19,9 +20,27 @@ pub struct Parameters {
 
 impl Parameters {
     pub fn new(method: &str) -> Self {
-        Parameters {
-            method: method.parse().expect("Invalid method, must be Min or Max"),
-        }
+        Self::try_new(method).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(method: &str) -> Result<Self, ParameterError> {
+        Ok(Parameters {
+            method: method
+                .parse()
+                .map_err(|_| ParameterError("Invalid method, must be Min or Max"))?,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved method, without parsing
+    pub const fn from_method(method: MinMaxMethod) -> Self {
+        Self { method }
+    }
+}
+
+impl From<MinMaxMethod> for Parameters {
+    fn from(method: MinMaxMethod) -> Self {
+        Self::from_method(method)
     }
 }
 
@@ -0,0 +1,214 @@
+use crate::core_blocks::clamp_block::clamp;
+use crate::ParameterError;
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+use crate::traits::Float;
+
+/// The mixing matrix used by the MixerBlock
+#[derive(Clone, Copy, Debug, PartialEq, strum::EnumString)]
+pub enum MixerType {
+    /// Inputs `(pitch, roll)`, outputs `(left, right)`: `left = pitch + roll`, `right = pitch - roll`
+    Elevon,
+    /// Inputs `(pitch, yaw)`, outputs `(left, right)`: `left = pitch + yaw`, `right = pitch - yaw`
+    VTail,
+    /// Inputs `(throttle, yaw)`, outputs `(left, right)`: `left = throttle + yaw`, `right = throttle - yaw`
+    Differential,
+    /// Uses the `matrix` parameter
+    Custom,
+}
+
+/// Parameters for the MixerBlock
+pub struct Parameters<F: Float, const IN: usize, const OUT: usize> {
+    pub mixer_type: MixerType,
+    /// Mixing matrix with one row per output and one column per input
+    pub matrix: Matrix<OUT, IN, F>,
+    /// Lower limit of each output
+    pub min: [F; OUT],
+    /// Upper limit of each output
+    pub max: [F; OUT],
+}
+
+impl<F: Float, const IN: usize, const OUT: usize> Parameters<F, IN, OUT> {
+    /// Creates the parameters for a mixer. The `matrix` parameter is only used by the
+    /// `Custom` mixer type; the presets require two inputs and two outputs.
+    pub fn new(mixer_type: &str, matrix: Matrix<OUT, IN, F>, min: [F; OUT], max: [F; OUT]) -> Self {
+        Self::try_new(mixer_type, matrix, min, max).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(
+        mixer_type: &str,
+        matrix: Matrix<OUT, IN, F>,
+        min: [F; OUT],
+        max: [F; OUT],
+    ) -> Result<Self, ParameterError> {
+        let mixer_type = mixer_type
+            .parse()
+            .map_err(|_| ParameterError("Failed to parse mixer type."))?;
+        Self::try_from_mixer_type(mixer_type, matrix, min, max)
+    }
+
+    /// Like [`Parameters::new`], but takes an already-resolved mixer type
+    pub fn from_mixer_type(
+        mixer_type: MixerType,
+        matrix: Matrix<OUT, IN, F>,
+        min: [F; OUT],
+        max: [F; OUT],
+    ) -> Self {
+        Self::try_from_mixer_type(mixer_type, matrix, min, max)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::from_mixer_type`], for builds where panics are
+    /// unacceptable
+    pub fn try_from_mixer_type(
+        mixer_type: MixerType,
+        matrix: Matrix<OUT, IN, F>,
+        min: [F; OUT],
+        max: [F; OUT],
+    ) -> Result<Self, ParameterError> {
+        let matrix = match mixer_type {
+            MixerType::Custom => matrix,
+            _ => {
+                if IN != 2 || OUT != 2 {
+                    return Err(ParameterError(
+                        "Preset mixers require two inputs and two outputs",
+                    ));
+                }
+                let one = <F as num_traits::One>::one();
+                let mut preset = Matrix::zeroed();
+                // Both presets share the sum/difference form, column-major indexing is [input][output]
+                preset.data[0][0] = one;
+                preset.data[0][1] = one;
+                preset.data[1][0] = one;
+                preset.data[1][1] = -one;
+                preset
+            }
+        };
+        Ok(Self {
+            mixer_type,
+            matrix,
+            min,
+            max,
+        })
+    }
+}
+
+/// Mixes `IN` control inputs (e.g. pitch, roll, yaw, throttle) into `OUT` actuator commands.
+///
+/// Each output is the dot product of the inputs with its row of the mixing matrix, clamped to
+/// that output's `[min, max]` limits. The mixing matrix is either one of the two-channel presets
+/// (elevon, v-tail or differential thrust) or a custom matrix, such as a multirotor motor mix.
+///
+/// The output is a tuple of the (1, OUT) actuator commands and a flag that is true when any
+/// output was limited.
+pub struct MixerBlock<F: Float, const IN: usize, const OUT: usize> {
+    buffer: (Matrix<1, OUT, F>, bool),
+}
+
+impl<F: Float, const IN: usize, const OUT: usize> Default for MixerBlock<F, IN, OUT> {
+    fn default() -> Self {
+        Self {
+            buffer: (Matrix::zeroed(), false),
+        }
+    }
+}
+
+impl<F: Float, const IN: usize, const OUT: usize> ProcessBlock for MixerBlock<F, IN, OUT> {
+    type Inputs = Matrix<1, IN, F>;
+    type Output = (Matrix<1, OUT, F>, bool);
+    type Parameters = Parameters<F, IN, OUT>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (outputs, saturated) = &mut self.buffer;
+        *saturated = false;
+        for (row, output) in outputs.data.iter_mut().enumerate() {
+            let mixed = input
+                .data
+                .iter()
+                .zip(parameters.matrix.data.iter())
+                .fold(<F as num_traits::Zero>::zero(), |acc, (input, column)| {
+                    acc + input[0] * column[row]
+                });
+            let limited = clamp(mixed, parameters.min[row], parameters.max[row]);
+            *saturated |= limited != mixed;
+            output[0] = limited;
+        }
+
+        self.buffer.as_by()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer.as_by()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+
+    #[test]
+    fn test_mixer_default_buffer() {
+        let block = MixerBlock::<f64, 2, 2>::default();
+        assert_eq!(block.buffer(), (&Matrix::zeroed(), false));
+    }
+
+    #[test]
+    fn test_mixer_presets() {
+        let ctxt = StubContext::default();
+        let input = Matrix {
+            data: [[0.25], [0.5]],
+        };
+        for mixer_type in ["Elevon", "VTail", "Differential"] {
+            let params =
+                Parameters::<f64, 2, 2>::new(mixer_type, Matrix::zeroed(), [-1.0; 2], [1.0; 2]);
+            let mut block = MixerBlock::<f64, 2, 2>::default();
+            let (output, saturated) = block.process(&params, &ctxt, &input);
+            assert_eq!(output.data, [[0.75], [-0.25]]);
+            assert!(!saturated);
+        }
+    }
+
+    #[test]
+    fn test_mixer_custom_with_limits() {
+        let ctxt = StubContext::default();
+        // Quad X motor mix, inputs (throttle, roll, pitch, yaw), outputs are 4 motors
+        let matrix = Matrix {
+            data: [
+                [1.0, 1.0, 1.0, 1.0],
+                [-1.0, 1.0, 1.0, -1.0],
+                [1.0, -1.0, 1.0, -1.0],
+                [1.0, 1.0, -1.0, -1.0],
+            ],
+        };
+        let params = Parameters::<f64, 4, 4>::new("Custom", matrix, [0.0; 4], [1.0; 4]);
+        assert_eq!(params.mixer_type, MixerType::Custom);
+        let mut block = MixerBlock::<f64, 4, 4>::default();
+
+        let input = Matrix {
+            data: [[0.5], [0.1], [0.0], [0.0]],
+        };
+        let (output, saturated) = block.process(&params, &ctxt, &input);
+        assert_eq!(output.data, [[0.4], [0.6], [0.6], [0.4]]);
+        assert!(!saturated);
+
+        let input = Matrix {
+            data: [[0.75], [0.0], [0.0], [0.5]],
+        };
+        let (output, saturated) = block.process(&params, &ctxt, &input);
+        assert_eq!(output.data, [[1.0], [1.0], [0.25], [0.25]]);
+        assert!(saturated);
+    }
+
+    #[test]
+    #[should_panic(expected = "Preset mixers require two inputs and two outputs")]
+    fn test_mixer_preset_size_mismatch() {
+        Parameters::<f64, 3, 2>::new("Elevon", Matrix::zeroed(), [-1.0; 2], [1.0; 2]);
+    }
+}
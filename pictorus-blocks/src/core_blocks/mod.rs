@@ -8,39 +8,68 @@ pub use adc_block::AdcBlock;
 pub use adc_block::Parameters as AdcBlockParams;
 
 mod aggregate_block;
-pub use aggregate_block::AggregateBlock;
+pub use aggregate_block::{AggregateBlock, AggregateMethod};
 
 mod app_time_block;
 pub use app_time_block::AppTimeBlock;
 
 mod arg_min_max_block;
-pub use arg_min_max_block::ArgMinMaxBlock;
+pub use arg_min_max_block::{ArgMethod, ArgMinMaxBlock};
+
+mod assert_block;
+pub use assert_block::{AssertBlock, AssertCondition};
+
+mod band_energy_block;
+pub use band_energy_block::BandEnergyBlock;
 
 mod bias_block;
 pub use bias_block::BiasBlock;
 
 mod bit_shift_block;
-pub use bit_shift_block::BitShiftBlock;
+pub use bit_shift_block::{BitShiftBlock, ShiftDirection};
 
 mod bitwise_operator_block;
-pub use bitwise_operator_block::BitwiseOperatorBlock;
+pub use bitwise_operator_block::{BitwiseOperatorBlock, Operation as BitwiseOperation};
 
 mod bytes_literal_block;
 pub use bytes_literal_block::BytesLiteralBlock;
 
+mod build_info_block;
+pub use build_info_block::BuildInfoBlock;
+#[doc(hidden)]
+pub use build_info_block::Parameters as BuildInfoBlockParams;
+
+mod bus_publish_block;
+pub use bus_publish_block::BusPublishBlock;
+#[doc(hidden)]
+pub use bus_publish_block::Parameters as BusPublishBlockParams;
+
+mod bus_subscribe_block;
+pub use bus_subscribe_block::BusSubscribeBlock;
+#[doc(hidden)]
+pub use bus_subscribe_block::Parameters as BusSubscribeBlockParams;
+
 mod can_receive_block;
 pub use can_receive_block::CanReceiveBlock;
 #[doc(hidden)]
 pub use can_receive_block::Parameters as CanReceiveBlockParams;
 
 mod change_detection_block;
-pub use change_detection_block::ChangeDetectionBlock;
+pub use change_detection_block::{ChangeDetectionBlock, ChangeMode};
 
 mod clamp_block;
 pub use clamp_block::ClampBlock;
 
+mod clarke_block;
+pub use clarke_block::ClarkeBlock;
+
+mod cli_block;
+pub use cli_block::CliBlock;
+#[doc(hidden)]
+pub use cli_block::Parameters as CliBlockParams;
+
 mod comparison_block;
-pub use comparison_block::ComparisonBlock;
+pub use comparison_block::{ComparisonBlock, ComparisonType};
 
 mod compare_to_value_block;
 pub use compare_to_value_block::CompareToValueBlock;
@@ -48,6 +77,18 @@ pub use compare_to_value_block::CompareToValueBlock;
 mod constant_block;
 pub use constant_block::ConstantBlock;
 
+mod control_allocation_block;
+pub use control_allocation_block::ControlAllocationBlock;
+
+mod convolution_block;
+pub use convolution_block::ConvolutionBlock;
+
+mod correlation_block;
+pub use correlation_block::{CorrelationBlock, CorrelationScaling};
+
+mod coulomb_counter_block;
+pub use coulomb_counter_block::CoulombCounterBlock;
+
 mod counter_block;
 pub use counter_block::CounterBlock;
 
@@ -59,14 +100,27 @@ pub use dac_block::DacBlock;
 #[doc(hidden)]
 pub use dac_block::Parameters as DacBlockParams;
 
+mod dbc_decode_block;
+#[doc(hidden)]
+pub use dbc_decode_block::Parameters as DbcDecodeBlockParams;
+pub use dbc_decode_block::{DbcDecodeBlock, DbcSignal};
+
 mod deadband_block;
 pub use deadband_block::DeadbandBlock;
 
+#[cfg(feature = "encryption")]
+mod decrypt_block;
+#[cfg(feature = "encryption")]
+pub use decrypt_block::DecryptBlock;
+#[cfg(feature = "encryption")]
+#[doc(hidden)]
+pub use decrypt_block::Parameters as DecryptBlockParams;
+
 mod delay_block;
 pub use delay_block::DelayBlock;
 
 mod delay_control_block;
-pub use delay_control_block::DelayControlBlock;
+pub use delay_control_block::{DelayControlBlock, DelayControlMethod};
 
 mod determinant_block;
 pub use determinant_block::DeterminantBlock;
@@ -77,9 +131,26 @@ pub use derivative_block::DerivativeBlock;
 mod dot_product_block;
 pub use dot_product_block::DotProductBlock;
 
+#[cfg(feature = "encryption")]
+mod encrypt_block;
+#[cfg(feature = "encryption")]
+pub use encrypt_block::EncryptBlock;
+#[cfg(feature = "encryption")]
+#[doc(hidden)]
+pub use encrypt_block::Parameters as EncryptBlockParams;
+
+mod enum_compare_block;
+pub use enum_compare_block::EnumCompareBlock;
+
+mod enum_switch_block;
+pub use enum_switch_block::EnumSwitchBlock;
+
 mod exponent_block;
 pub use exponent_block::ExponentBlock;
 
+mod fft_block;
+pub use fft_block::FftBlock as FFTBlock;
+
 // These blocks are special versions of passthrough blocks that are
 // used to handle user-input functions that might return non-finite data
 mod fix_non_finite_block;
@@ -91,37 +162,93 @@ pub use fix_non_finite_block::FixNonFiniteBlock as RustCodeBlock;
 pub use fix_non_finite_block::FixNonFiniteBlock as EquationBlock;
 
 mod frequency_filter_block;
-pub use frequency_filter_block::FrequencyFilterBlock;
+pub use frequency_filter_block::{FrequencyFilterBlock, FrequencyFilterEnum};
 
 mod gain_block;
 pub use gain_block::GainBlock;
 
+mod geo_distance_block;
+pub use geo_distance_block::GeoDistanceBlock;
+
+mod geofence_block;
+pub use geofence_block::GeofenceBlock;
+
 mod gpio_output_block;
 pub use gpio_output_block::GpioOutputBlock;
 #[doc(hidden)]
 pub use gpio_output_block::Parameters as GpioOutputBlockParams;
 
+mod histogram_block;
+pub use histogram_block::HistogramBlock;
+
+mod i2t_block;
+pub use i2t_block::I2tBlock;
+
 mod iir_filter_block;
 pub use iir_filter_block::IirFilterBlock;
 
+mod image_centroid_block;
+pub use image_centroid_block::ImageCentroidBlock;
+
+mod image_downsample_block;
+pub use image_downsample_block::{DownsampleMethod, ImageDownsampleBlock};
+
+mod image_threshold_block;
+pub use image_threshold_block::ImageThresholdBlock;
+
 mod integral_block;
-pub use integral_block::IntegralBlock;
+pub use integral_block::{IntegralBlock, IntgeralMethod};
+
+mod interlock_block;
+pub use interlock_block::InterlockBlock;
+#[doc(hidden)]
+pub use interlock_block::Parameters as InterlockBlockParams;
+
+mod inverse_park_block;
+pub use inverse_park_block::InverseParkBlock;
+
+mod latency_compensation_block;
+#[doc(hidden)]
+pub use latency_compensation_block::Parameters as LatencyCompensationBlockParams;
+pub use latency_compensation_block::{CompensationMethod, LatencyCompensationBlock};
+
+mod lla_to_ecef_block;
+pub use lla_to_ecef_block::LlaToEcefBlock;
+
+mod lla_to_ned_block;
+pub use lla_to_ned_block::LlaToNedBlock;
+
+mod log_trigger_block;
+pub use log_trigger_block::LogTriggerBlock;
+#[doc(hidden)]
+pub use log_trigger_block::Parameters as LogTriggerBlockParams;
 
 mod logical_block;
-pub use logical_block::LogicalBlock;
+pub use logical_block::{LogicalBlock, LogicalMethod};
 
 mod lookup_2d_block;
-pub use lookup_2d_block::Lookup2DBlock;
+pub use lookup_2d_block::{InterpMethod as Lookup2DInterpMethod, Lookup2DBlock};
 
 mod lookup_1d_block;
-pub use lookup_1d_block::Lookup1DBlock;
+pub use lookup_1d_block::{InterpMethod as Lookup1DInterpMethod, Lookup1DBlock};
 
 mod min_max_block;
-pub use min_max_block::MinMaxBlock;
+pub use min_max_block::{MinMaxBlock, MinMaxMethod};
 
 mod matrix_inverse_block;
 pub use matrix_inverse_block::{Inverse, MatrixInverseBlock, Svd};
 
+mod mixer_block;
+pub use mixer_block::{MixerBlock, MixerType};
+
+mod nearest_point_block;
+pub use nearest_point_block::NearestPointBlock;
+#[doc(hidden)]
+pub use nearest_point_block::Parameters as NearestPointBlockParams;
+
+mod ned_to_lla_block;
+pub use ned_to_lla_block::NedToLlaBlock;
+
 mod noop_input_block;
 pub use noop_input_block::NoOpInputBlock;
 
@@ -129,7 +256,10 @@ mod noop_output_block;
 pub use noop_output_block::NoOpOutputBlock;
 
 mod not_block;
-pub use not_block::NotBlock;
+pub use not_block::{NotBlock, NotMethod};
+
+mod park_block;
+pub use park_block::ParkBlock;
 
 // There are several blocks that just compute a value external to the block
 // and pass it through.
@@ -155,14 +285,38 @@ pub use passthrough_block::PassthroughBlock as SpiTransmitBlock;
 mod pid_block;
 pub use pid_block::PidBlock;
 
+mod polyfit_block;
+pub use polyfit_block::PolyfitBlock;
+
+mod polyval_block;
+pub use polyval_block::PolyvalBlock;
+
+mod precharge_block;
+#[doc(hidden)]
+pub use precharge_block::Parameters as PrechargeBlockParams;
+pub use precharge_block::PrechargeBlock;
+
 mod product_block;
 pub use product_block::{ComponentWise, MatrixMultiply, ProductBlock};
 
+mod pt100_block;
+#[doc(hidden)]
+pub use pt100_block::Parameters as Pt100BlockParams;
+pub use pt100_block::Pt100Block;
+
+mod pure_pursuit_block;
+pub use pure_pursuit_block::PurePursuitBlock;
+
 mod pwm_block;
 #[doc(hidden)]
 pub use pwm_block::Parameters as PwmBlockParams;
 pub use pwm_block::PwmBlock;
 
+mod pwm_dac_block;
+#[doc(hidden)]
+pub use pwm_dac_block::Parameters as PwmDacBlockParams;
+pub use pwm_dac_block::PwmDacBlock;
+
 mod quantize_block;
 pub use quantize_block::QuantizeBlock;
 
@@ -175,9 +329,30 @@ pub use random_number_block::RandomNumberBlock;
 mod rate_limit_block;
 pub use rate_limit_block::RateLimitBlock;
 
+mod resample_block;
+#[doc(hidden)]
+pub use resample_block::Parameters as ResampleBlockParams;
+pub use resample_block::{ResampleBlock, ResampleMethod};
+
+mod rolling_stats_block;
+#[doc(hidden)]
+pub use rolling_stats_block::Parameters as RollingStatsBlockParams;
+pub use rolling_stats_block::RollingStatsBlock;
+
+mod rotary_knob_block;
+#[doc(hidden)]
+pub use rotary_knob_block::Parameters as RotaryKnobBlockParams;
+pub use rotary_knob_block::RotaryKnobBlock;
+
+mod sanitize_block;
+pub use sanitize_block::{SanitizeBlock, SanitizeFallback};
+
 mod sawtoothwave_block;
 pub use sawtoothwave_block::SawtoothwaveBlock;
 
+mod servo_output_block;
+pub use servo_output_block::ServoOutputBlock;
+
 mod sinewave_block;
 pub use sinewave_block::SinewaveBlock;
 
@@ -186,14 +361,54 @@ mod sliding_window_block;
 pub use sliding_window_block::Parameters as SlidingWindowBlockParams;
 pub use sliding_window_block::SlidingWindowBlock;
 
+mod sparse_matrix_multiply_block;
+#[doc(hidden)]
+pub use sparse_matrix_multiply_block::Parameters as SparseMatrixMultiplyBlockParams;
+pub use sparse_matrix_multiply_block::{CsrMatrix, SparseMatrixMultiplyBlock};
+
+mod spectrum_block;
+pub use spectrum_block::{SpectrumBlock, SpectrumOutput, Window as SpectrumWindow};
+
+mod spline_block;
+#[doc(hidden)]
+pub use spline_block::Parameters as SplineBlockParams;
+pub use spline_block::{SplineBlock, SplineMethod};
+
 mod squarewave_block;
 pub use squarewave_block::SquarewaveBlock;
 
+mod stanley_block;
+pub use stanley_block::StanleyBlock;
+
+mod stepper_block;
+#[doc(hidden)]
+pub use stepper_block::Parameters as StepperBlockParams;
+pub use stepper_block::{StepperBlock, StepperMode};
+
+mod string_compare_block;
+pub use string_compare_block::{StringCompareBlock, StringCompareMethod};
+
+mod string_parse_block;
+pub use string_parse_block::StringParseBlock;
+
 mod sum_block;
 pub use sum_block::SumBlock;
 
+mod svpwm_block;
+pub use svpwm_block::SvpwmBlock;
+
+mod tare_scale_block;
+#[doc(hidden)]
+pub use tare_scale_block::Parameters as TareScaleBlockParams;
+pub use tare_scale_block::TareScaleBlock;
+
+mod thermocouple_block;
+#[doc(hidden)]
+pub use thermocouple_block::Parameters as ThermocoupleBlockParams;
+pub use thermocouple_block::{ThermocoupleBlock, ThermocoupleType};
+
 mod timer_block;
-pub use timer_block::TimerBlock;
+pub use timer_block::{Method as TimerMethod, TimerBlock};
 
 mod transpose_block;
 pub use transpose_block::TransposeBlock;
@@ -205,7 +420,12 @@ mod trianglewave_block;
 pub use trianglewave_block::TrianglewaveBlock;
 
 mod trigonometry_block;
-pub use trigonometry_block::TrigonometryBlock;
+pub use trigonometry_block::{TrigonometryBlock, TrigonometryFunction};
+
+mod unit_conversion_block;
+pub use unit_conversion_block::{
+    DegToRadBlock, KnotToMpsBlock, MpsToKnotBlock, RadToDegBlock, UnitConversionBlock,
+};
 
 mod vector_index_block;
 pub use vector_index_block::VectorIndexBlock;
@@ -223,4 +443,12 @@ mod vector_slice_block;
 pub use vector_slice_block::VectorSliceBlock;
 
 mod vector_sort_block;
-pub use vector_sort_block::VectorSortBlock;
+pub use vector_sort_block::{VectorSortBlock, VectorSortDirection};
+
+mod voter_block;
+#[doc(hidden)]
+pub use voter_block::Parameters as VoterBlockParams;
+pub use voter_block::{VoterBlock, VoterMethod};
+
+mod waypoint_follower_block;
+pub use waypoint_follower_block::WaypointFollowerBlock;
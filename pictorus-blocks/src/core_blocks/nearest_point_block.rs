@@ -0,0 +1,183 @@
+use crate::traits::Float;
+use pictorus_traits::{Matrix, PassBy, ProcessBlock};
+
+/// Parameters for the NearestPointBlock
+pub struct Parameters<F: Float, const N: usize, const D: usize> {
+    /// Points to search, one row per point, e.g. `(x, y)` or `(x, y, z)`
+    pub points: Matrix<N, D, F>,
+}
+
+impl<F: Float, const N: usize, const D: usize> Parameters<F, N, D> {
+    pub fn new(points: Matrix<N, D, F>) -> Self {
+        Self { points }
+    }
+}
+
+/// Finds the point of a stored set closest to the input point.
+///
+/// The input is a point as a (1, D) matrix, and the points to search are the rows of an (N, D)
+/// matrix parameter, typically a stored route to localize against. The output is a tuple of
+/// (index, distance): the zero-based row index of the nearest point and its Euclidean distance
+/// from the input. Ties go to the lowest index.
+///
+/// Points that are NaN, or an input containing NaN, never match. If no point matches, the
+/// previous output is held.
+pub struct NearestPointBlock<F: Float, const N: usize, const D: usize> {
+    buffer: (F, F),
+}
+
+impl<F: Float, const N: usize, const D: usize> Default for NearestPointBlock<F, N, D> {
+    fn default() -> Self {
+        const {
+            assert!(N > 0, "NearestPointBlock requires at least one point");
+        }
+        Self {
+            buffer: (F::default(), F::default()),
+        }
+    }
+}
+
+impl<F: Float, const N: usize, const D: usize> ProcessBlock for NearestPointBlock<F, N, D> {
+    type Inputs = Matrix<1, D, F>;
+    type Output = (F, F);
+    type Parameters = Parameters<F, N, D>;
+
+    fn process(
+        &mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<Self::Inputs>,
+    ) -> PassBy<'_, Self::Output> {
+        let mut nearest: Option<(usize, F)> = None;
+        for index in 0..N {
+            let distance_squared = parameters.points.data.iter().zip(&input.data).fold(
+                <F as num_traits::Zero>::zero(),
+                |acc, (column, value)| {
+                    let delta = column[index] - value[0];
+                    acc + delta * delta
+                },
+            );
+            // Points with a NaN distance are skipped
+            if nearest.is_none_or(|(_, best)| distance_squared < best)
+                && !num_traits::Float::is_nan(distance_squared)
+            {
+                nearest = Some((index, distance_squared));
+            }
+        }
+        if let Some((index, distance_squared)) = nearest {
+            self.buffer = (
+                F::from(index).expect("Couldn't convert usize to F"),
+                num_traits::Float::sqrt(distance_squared),
+            );
+        }
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_nearest_point_default_buffer_no_panic() {
+        let block = NearestPointBlock::<f64, 3, 2>::default();
+        assert_eq!(block.buffer(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_nearest_point_2d() {
+        let context = StubContext::default();
+        // Rows (0, 0), (1, 0), (2, 1), (3, 3)
+        let parameters = Parameters::new(Matrix {
+            data: [[0.0, 1.0, 2.0, 3.0], [0.0, 0.0, 1.0, 3.0]],
+        });
+        let mut block = NearestPointBlock::<f64, 4, 2>::default();
+
+        let (index, distance) = block.process(
+            &parameters,
+            &context,
+            &Matrix {
+                data: [[1.9], [1.5]],
+            },
+        );
+        assert_eq!(index, 2.0);
+        assert_relative_eq!(distance, (0.01f64 + 0.25).sqrt(), epsilon = 1e-12);
+
+        let (index, distance) = block.process(
+            &parameters,
+            &context,
+            &Matrix {
+                data: [[-4.0], [3.0]],
+            },
+        );
+        assert_eq!(index, 0.0);
+        assert_relative_eq!(distance, 5.0, epsilon = 1e-12);
+
+        // Halfway between two points picks the first
+        let (index, _) = block.process(
+            &parameters,
+            &context,
+            &Matrix {
+                data: [[0.5], [0.0]],
+            },
+        );
+        assert_eq!(index, 0.0);
+    }
+
+    #[test]
+    fn test_nearest_point_3d() {
+        let context = StubContext::default();
+        // Rows (0, 0, 0), (0, 0, 10)
+        let parameters = Parameters::new(Matrix {
+            data: [[0.0, 0.0], [0.0, 0.0], [0.0, 10.0]],
+        });
+        let mut block = NearestPointBlock::<f32, 2, 3>::default();
+
+        let (index, distance) = block.process(
+            &parameters,
+            &context,
+            &Matrix {
+                data: [[3.0], [0.0], [6.0]],
+            },
+        );
+        assert_eq!(index, 1.0);
+        assert_relative_eq!(distance, 5.0);
+    }
+
+    #[test]
+    fn test_nearest_point_nan() {
+        let context = StubContext::default();
+        // Rows (NaN, 0), (5, 5)
+        let parameters = Parameters::new(Matrix {
+            data: [[f64::NAN, 5.0], [0.0, 5.0]],
+        });
+        let mut block = NearestPointBlock::<f64, 2, 2>::default();
+
+        // The NaN point is skipped
+        let (index, distance) = block.process(
+            &parameters,
+            &context,
+            &Matrix {
+                data: [[0.0], [0.0]],
+            },
+        );
+        assert_eq!(index, 1.0);
+        assert_relative_eq!(distance, 50.0f64.sqrt());
+
+        // A NaN input holds the previous output
+        let output = block.process(
+            &parameters,
+            &context,
+            &Matrix {
+                data: [[f64::NAN], [0.0]],
+            },
+        );
+        assert_eq!(output, (index, distance));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::geodesy::{ecef_to_lla, vector_from_f64, vector_to_f64, NedFrame};
+use crate::traits::Float;
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+/// Parameters for the NedToLlaBlock
+pub struct Parameters {
+    frame: NedFrame,
+}
+
+impl Parameters {
+    /// Creates the parameters for a local frame at the given origin, with latitude and longitude in
+    /// degrees and altitude in meters above the ellipsoid
+    pub fn new(origin_latitude: f64, origin_longitude: f64, origin_altitude: f64) -> Self {
+        Self {
+            frame: NedFrame::new([origin_latitude, origin_longitude, origin_altitude]),
+        }
+    }
+}
+
+/// Converts north-east-down (NED) coordinates relative to a local origin to a WGS-84 geodetic position.
+///
+/// This is the inverse of the [`LlaToNedBlock`](crate::LlaToNedBlock). The input is
+/// `(north, east, down)` in meters as a (1, 3) matrix, and the output is
+/// `(latitude, longitude, altitude)` with latitude and longitude in degrees and altitude in meters
+/// above the ellipsoid.
+pub struct NedToLlaBlock<F: Float> {
+    buffer: Matrix<1, 3, F>,
+}
+
+impl<F: Float> Default for NedToLlaBlock<F> {
+    fn default() -> Self {
+        Self {
+            buffer: Matrix::zeroed(),
+        }
+    }
+}
+
+impl<F: Float> ProcessBlock for NedToLlaBlock<F> {
+    type Inputs = Matrix<1, 3, F>;
+    type Output = Matrix<1, 3, F>;
+    type Parameters = Parameters;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let ecef = parameters.frame.ned_to_ecef(vector_to_f64(input));
+        vector_from_f64(ecef_to_lla(ecef), &mut self.buffer);
+        self.buffer.as_by()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer.as_by()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_blocks::lla_to_ned_block;
+    use crate::testing::StubContext;
+    use crate::LlaToNedBlock;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_ned_to_lla_round_trip() {
+        let ctxt = StubContext::default();
+        let params = Parameters::new(-33.0, 151.0, 50.0);
+        let mut block = NedToLlaBlock::<f64>::default();
+
+        let origin = block.process(&params, &ctxt, &Matrix::zeroed());
+        assert_relative_eq!(origin.data[0][0], -33.0, epsilon = 1e-9);
+        assert_relative_eq!(origin.data[1][0], 151.0, epsilon = 1e-9);
+        assert_relative_eq!(origin.data[2][0], 50.0, epsilon = 1e-6);
+
+        let ned = Matrix {
+            data: [[1500.0], [-2500.0], [20.0]],
+        };
+        let lla = *block.process(&params, &ctxt, &ned);
+        let mut inverse = LlaToNedBlock::<f64>::default();
+        let result = inverse.process(
+            &lla_to_ned_block::Parameters::new(-33.0, 151.0, 50.0),
+            &ctxt,
+            &lla,
+        );
+        for (actual, expected) in result.data.iter().zip(ned.data) {
+            assert_relative_eq!(actual[0], expected[0], epsilon = 1e-6);
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::ParameterError;
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
 
 #[derive(strum::EnumString, Clone, Copy)]
@@ -165,11 +166,27 @@ pub struct Parameters {
 
 impl Parameters {
     pub fn new(method: &str) -> Self {
-        Self {
-            method: method
-                .parse()
-                .expect("Failed to parse NotMethod, expected 'Logical' or 'Bitwise'"),
-        }
+        Self::try_new(method).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(method: &str) -> Result<Self, ParameterError> {
+        Ok(Self {
+            method: method.parse().map_err(|_| {
+                ParameterError("Failed to parse NotMethod, expected 'Logical' or 'Bitwise'")
+            })?,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved method, without parsing
+    pub const fn from_method(method: NotMethod) -> Self {
+        Self { method }
+    }
+}
+
+impl From<NotMethod> for Parameters {
+    fn from(method: NotMethod) -> Self {
+        Self::from_method(method)
     }
 }
 
@@ -0,0 +1,84 @@
+use crate::fast_math::FastMath;
+use crate::traits::Float;
+use pictorus_traits::{PassBy, ProcessBlock};
+
+/// Parameters for the ParkBlock
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Parameters;
+
+impl Parameters {
+    pub fn new() -> Self {
+        Parameters {}
+    }
+}
+
+/// Park transform, rotating stationary `(alpha, beta)` quantities into the `(d, q)` frame that
+/// rotates with the rotor.
+///
+/// Inputs are `(alpha, beta, theta)` where `theta` is the electrical angle in radians, and the
+/// output is `(d, q)`. A vector aligned with `theta` appears entirely on the d axis. This is the
+/// inverse of the [`InverseParkBlock`](crate::InverseParkBlock).
+pub struct ParkBlock<F: Float> {
+    buffer: (F, F),
+}
+
+impl<F: Float> Default for ParkBlock<F> {
+    fn default() -> Self {
+        Self {
+            buffer: (F::default(), F::default()),
+        }
+    }
+}
+
+impl<F: Float> ProcessBlock for ParkBlock<F> {
+    type Inputs = (F, F, F);
+    type Output = (F, F);
+    type Parameters = Parameters;
+
+    fn process<'b>(
+        &'b mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (alpha, beta, theta) = inputs;
+        let (sin, cos) = FastMath::fast_sin_cos(theta);
+        self.buffer = (alpha * cos + beta * sin, beta * cos - alpha * sin);
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{fast_math_epsilon, StubContext};
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_park_default_buffer_no_panic() {
+        let block = ParkBlock::<f64>::default();
+        assert_eq!(block.buffer(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_park_block() {
+        let context = StubContext::default();
+        let params = Parameters::new();
+        let mut block = ParkBlock::<f64>::default();
+
+        // A vector rotating with theta is constant in the rotating frame
+        for theta in [0.0f64, 0.5, 2.0, -1.0] {
+            let (alpha, beta) = (
+                3.0 * theta.cos() - 1.0 * theta.sin(),
+                3.0 * theta.sin() + 1.0 * theta.cos(),
+            );
+            let (d, q) = block.process(&params, &context, (alpha, beta, theta));
+            assert_relative_eq!(d, 3.0, epsilon = fast_math_epsilon(1e-12));
+            assert_relative_eq!(q, 1.0, epsilon = fast_math_epsilon(1e-12));
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use crate::traits::Float;
+use heapless::Deque;
+use nalgebra::{Cholesky, SMatrix, SVector};
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+/// Parameters for the PolyfitBlock
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Parameters;
+
+impl Parameters {
+    pub fn new() -> Self {
+        Parameters {}
+    }
+}
+
+/// Least-squares fit of a polynomial with `C` coefficients (degree `C - 1`) to the most recent `N`
+/// `(x, y)` input samples.
+///
+/// The first output holds the fitted coefficients, highest order first, in the same order
+/// used by the [`PolyvalBlock`](crate::PolyvalBlock). The second output is `true` when the
+/// coefficients were updated this tick. The fit is skipped (and the previous coefficients held)
+/// until at least `C` samples have been collected, or when the samples don't determine a unique
+/// fit, such as when every `x` is the same.
+///
+/// For trend estimation, wire time into `x`; the linear coefficient of a degree 1 fit is then the
+/// rate of change of `y`. Samples are centered and scaled internally before fitting, so large
+/// `x` values (e.g. app time) don't degrade the fit.
+pub struct PolyfitBlock<F: Float, const N: usize, const C: usize> {
+    samples: Deque<(F, F), N>,
+    buffer: (Matrix<1, C, F>, bool),
+}
+
+impl<F: Float, const N: usize, const C: usize> Default for PolyfitBlock<F, N, C> {
+    fn default() -> Self {
+        Self {
+            samples: Deque::new(),
+            buffer: (Matrix::zeroed(), false),
+        }
+    }
+}
+
+impl<F: Float, const N: usize, const C: usize> PolyfitBlock<F, N, C> {
+    /// Fits the collected samples, returning the coefficients lowest order first
+    fn fit(&self) -> Option<[F; C]> {
+        if self.samples.len() < C {
+            return None;
+        }
+
+        let zero = <F as num_traits::Zero>::zero();
+        let one = <F as num_traits::One>::one();
+        let count = F::from(self.samples.len()).expect("Sample count fits in float");
+        let mean = self.samples.iter().fold(zero, |acc, (x, _)| acc + *x) / count;
+        let scale = self.samples.iter().fold(zero, |acc, (x, _)| {
+            num_traits::Float::max(acc, num_traits::Float::abs(*x - mean))
+        });
+        if !num_traits::Float::is_finite(scale) {
+            return None;
+        }
+        let scale = if scale > zero { scale } else { one };
+
+        // Normal equations in the scaled variable u = (x - mean) / scale, with the
+        // basis ordered lowest order first
+        let mut lhs = SMatrix::<F, C, C>::zeros();
+        let mut rhs = SVector::<F, C>::zeros();
+        for (x, y) in self.samples.iter() {
+            let u = (*x - mean) / scale;
+            let mut basis = SVector::<F, C>::zeros();
+            let mut power = one;
+            for value in basis.iter_mut() {
+                *value = power;
+                power *= u;
+            }
+            lhs += basis * basis.transpose();
+            rhs += basis * *y;
+        }
+        let q = Cholesky::new(lhs)?.solve(&rhs);
+
+        // Expand q((x - mean) / scale) back into coefficients of x with Horner's method
+        let (a, b) = (one / scale, -mean / scale);
+        let mut coefficients = [zero; C];
+        for q in q.iter().rev() {
+            for k in (0..C).rev() {
+                let lower = if k > 0 { coefficients[k - 1] } else { zero };
+                coefficients[k] = coefficients[k] * b + lower * a;
+            }
+            coefficients[0] += *q;
+        }
+
+        coefficients
+            .iter()
+            .all(|c| num_traits::Float::is_finite(*c))
+            .then_some(coefficients)
+    }
+}
+
+impl<F: Float, const N: usize, const C: usize> ProcessBlock for PolyfitBlock<F, N, C> {
+    type Inputs = (F, F);
+    type Output = (Matrix<1, C, F>, bool);
+    type Parameters = Parameters;
+
+    fn process<'b>(
+        &'b mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        if self.samples.is_full() {
+            self.samples.pop_front();
+        }
+        // Can't fail, there is always room after the pop above
+        let _ = self.samples.push_back(input);
+
+        self.buffer.1 = match self.fit() {
+            Some(coefficients) => {
+                for (output, c) in self.buffer.0.data.iter_mut().zip(coefficients.iter().rev()) {
+                    output[0] = *c;
+                }
+                true
+            }
+            None => false,
+        };
+
+        (self.buffer.0.as_by(), self.buffer.1)
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        (self.buffer.0.as_by(), self.buffer.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_polyfit_default_buffer_no_panic() {
+        let block = PolyfitBlock::<f64, 5, 2>::default();
+        assert_eq!(block.buffer(), (&Matrix::zeroed(), false));
+    }
+
+    #[test]
+    fn test_polyfit_exact_quadratic() {
+        let context = StubContext::default();
+        let params = Parameters::new();
+        let mut block = PolyfitBlock::<f64, 8, 3>::default();
+
+        // y = 0.5x^2 - 2x + 3, with x offset far from the origin
+        let poly = |x: f64| 0.5 * x * x - 2.0 * x + 3.0;
+        assert!(!block.process(&params, &context, (1000.0, poly(1000.0))).1);
+        assert!(!block.process(&params, &context, (1001.0, poly(1001.0))).1);
+        for i in 2..20 {
+            let x = 1000.0 + i as f64;
+            let (output, valid) = block.process(&params, &context, (x, poly(x)));
+            assert!(valid);
+            assert_relative_eq!(output.data[0][0], 0.5, epsilon = 1e-6);
+            assert_relative_eq!(output.data[1][0], -2.0, epsilon = 1e-3);
+            assert_relative_eq!(output.data[2][0], 3.0, max_relative = 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_polyfit_least_squares_line() {
+        let context = StubContext::default();
+        let params = Parameters::new();
+        let mut block = PolyfitBlock::<f64, 4, 2>::default();
+
+        // The best fit line through (0, 0), (1, 1), (2, 1), (3, 2) is y = 0.6x + 0.1
+        for (x, y) in [(0.0, 0.0), (1.0, 1.0), (2.0, 1.0), (3.0, 2.0)] {
+            block.process(&params, &context, (x, y));
+        }
+        let (output, valid) = block.buffer();
+        assert!(valid);
+        assert_relative_eq!(output.data[0][0], 0.6, epsilon = 1e-12);
+        assert_relative_eq!(output.data[1][0], 0.1, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_polyfit_degenerate_holds_previous() {
+        let context = StubContext::default();
+        let params = Parameters::new();
+        let mut block = PolyfitBlock::<f32, 2, 2>::default();
+
+        block.process(&params, &context, (0.0, 1.0));
+        let (output, valid) = block.process(&params, &context, (1.0, 3.0));
+        assert!(valid);
+        assert_eq!(output.data, [[2.0], [1.0]]);
+
+        // Only the last two samples are kept, and they share an x value
+        block.process(&params, &context, (1.0, 5.0));
+        let (output, valid) = block.buffer();
+        assert!(!valid);
+        assert_eq!(output.data, [[2.0], [1.0]]);
+    }
+}
@@ -0,0 +1,130 @@
+use crate::traits::Float;
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+/// Parameters for the PolyvalBlock
+pub struct Parameters<F: Float, const C: usize> {
+    /// Polynomial coefficients, highest order first
+    pub coefficients: [F; C],
+}
+
+impl<F: Float, const C: usize> Parameters<F, C> {
+    pub fn new(coefficients: [F; C]) -> Self {
+        Self { coefficients }
+    }
+}
+
+/// Evaluates a polynomial at the input using Horner's method.
+///
+/// The coefficients are ordered highest order first, so `[a, b, c]` evaluates `a*x^2 + b*x + c`
+/// (matching the output of the [`PolyfitBlock`](crate::PolyfitBlock)). Matrix inputs are
+/// evaluated element-wise. A common use is linearizing a sensor with a calibration polynomial.
+pub struct PolyvalBlock<T: Apply, const C: usize> {
+    buffer: T,
+}
+
+impl<T: Apply, const C: usize> Default for PolyvalBlock<T, C> {
+    fn default() -> Self {
+        Self {
+            buffer: T::default(),
+        }
+    }
+}
+
+impl<T: Apply, const C: usize> ProcessBlock for PolyvalBlock<T, C> {
+    type Inputs = T;
+    type Output = T;
+    type Parameters = Parameters<T::Float, C>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        T::apply(&mut self.buffer, input, &parameters.coefficients);
+        self.buffer.as_by()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer.as_by()
+    }
+}
+
+/// Evaluates the polynomial with coefficients `coefficients` (highest order first) at `x`
+fn horner<F: Float>(coefficients: &[F], x: F) -> F {
+    coefficients
+        .iter()
+        .fold(<F as num_traits::Zero>::zero(), |acc, c| acc * x + *c)
+}
+
+pub trait Apply: Pass + Default {
+    type Float: Float;
+
+    fn apply(store: &mut Self, input: PassBy<Self>, coefficients: &[Self::Float]);
+}
+
+impl<F: Float> Apply for F {
+    type Float = F;
+
+    fn apply(store: &mut Self, input: PassBy<Self>, coefficients: &[Self::Float]) {
+        *store = horner(coefficients, input);
+    }
+}
+
+impl<const NROWS: usize, const NCOLS: usize, F: Float> Apply for Matrix<NROWS, NCOLS, F> {
+    type Float = F;
+
+    fn apply(store: &mut Self, input: PassBy<Self>, coefficients: &[Self::Float]) {
+        for (output, x) in store
+            .data
+            .as_flattened_mut()
+            .iter_mut()
+            .zip(input.data.as_flattened())
+        {
+            *output = horner(coefficients, *x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+
+    #[test]
+    fn test_polyval_default_buffer_no_panic() {
+        let block = PolyvalBlock::<f64, 3>::default();
+        assert_eq!(block.buffer(), 0.0);
+    }
+
+    #[test]
+    fn test_polyval_scalar() {
+        let context = StubContext::default();
+        let mut block = PolyvalBlock::<f64, 3>::default();
+
+        // 2x^2 - 3x + 1
+        let params = Parameters::new([2.0, -3.0, 1.0]);
+        assert_eq!(block.process(&params, &context, 0.0), 1.0);
+        assert_eq!(block.process(&params, &context, 2.0), 3.0);
+        assert_eq!(block.process(&params, &context, -1.5), 10.0);
+        assert_eq!(block.buffer(), 10.0);
+
+        // An empty polynomial is zero everywhere
+        let mut block = PolyvalBlock::<f32, 0>::default();
+        assert_eq!(block.process(&Parameters::new([]), &context, 4.0), 0.0);
+    }
+
+    #[test]
+    fn test_polyval_matrix() {
+        let context = StubContext::default();
+        let mut block = PolyvalBlock::<Matrix<2, 2, f32>, 2>::default();
+
+        // 0.5x + 10
+        let params = Parameters::new([0.5, 10.0]);
+        let input = Matrix {
+            data: [[0.0, 2.0], [-4.0, 100.0]],
+        };
+        let output = block.process(&params, &context, &input);
+        assert_eq!(output.data, [[10.0, 11.0], [8.0, 60.0]]);
+    }
+}
@@ -0,0 +1,235 @@
+use core::time::Duration;
+
+use crate::stale_tracker::elapsed;
+use crate::traits::Float;
+use pictorus_traits::{PassBy, ProcessBlock};
+
+/// The bus didn't reach the threshold within the timeout, e.g. a short on the bus or an open
+/// precharge circuit
+const FAULT_TIMEOUT: f64 = 1.0;
+/// The bus reached the threshold sooner than the minimum precharge time, e.g. a welded main
+/// contactor or a missing bus capacitance
+const FAULT_TOO_FAST: f64 = 2.0;
+/// The bus fell below the threshold after the main contactor closed, e.g. a contactor that
+/// dropped out
+const FAULT_VOLTAGE_LOST: f64 = 3.0;
+
+/// Parameters for the PrechargeBlock
+pub struct Parameters<F: Float> {
+    /// Fraction (0 to 1) of the source voltage the bus must reach before the main contactor
+    /// closes, typically 0.9 to 0.95
+    pub threshold: F,
+    /// Time (seconds) the bus has to reach the threshold before a timeout fault
+    pub timeout: F,
+    /// Time (seconds) the precharge must take at least, or zero to disable the check
+    pub min_time: F,
+    /// Time (seconds) both the precharge relay and the main contactor stay closed before the
+    /// precharge relay opens
+    pub overlap: F,
+}
+
+impl<F: Float> Parameters<F> {
+    pub fn new(threshold: F, timeout: F, min_time: F, overlap: F) -> Self {
+        Self {
+            threshold,
+            timeout,
+            min_time,
+            overlap,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Idle,
+    Precharging { since: Duration },
+    Closing { since: Duration },
+    Ready,
+    Fault,
+}
+
+/// Sequences the precharge of a capacitive DC bus (e.g. a motor inverter on a battery) before
+/// closing the main contactor, so the contactor doesn't switch the inrush current.
+///
+/// The inputs are (enable, bus voltage, source voltage). While enabled, the block closes the
+/// precharge relay and waits for the bus to reach the threshold fraction of the source voltage,
+/// then closes the main contactor and opens the precharge relay after the overlap time. The bus
+/// voltage is checked against the threshold throughout: too slow a precharge, too fast a
+/// precharge, and losing the bus once connected are all faults, which open both contactors.
+/// Faults are latched until enable goes false.
+///
+/// The output is a tuple of (precharge relay, main contactor, ready, fault, fault code), where
+/// the fault code is 0 without a fault, 1 for a precharge timeout, 2 for a precharge faster
+/// than the minimum time, and 3 for losing the bus voltage once connected.
+pub struct PrechargeBlock<F: Float> {
+    buffer: (bool, bool, bool, bool, F),
+    state: State,
+}
+
+impl<F: Float> Default for PrechargeBlock<F> {
+    fn default() -> Self {
+        Self {
+            buffer: (false, false, false, false, F::zero()),
+            state: State::Idle,
+        }
+    }
+}
+
+impl<F: Float> PrechargeBlock<F> {
+    fn fault(&mut self, code: f64) -> State {
+        self.buffer.4 = F::from(code).expect("Couldn't convert fault code to F");
+        State::Fault
+    }
+}
+
+impl<F: Float> ProcessBlock for PrechargeBlock<F> {
+    type Inputs = (bool, F, F);
+    type Output = (bool, bool, bool, bool, F);
+    type Parameters = Parameters<F>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (enable, bus_voltage, source_voltage) = inputs;
+        let time = context.time();
+        let elapsed = |since: Duration| F::from_duration(elapsed(time, since).unwrap_or_default());
+        let charged =
+            source_voltage > F::zero() && bus_voltage >= source_voltage * parameters.threshold;
+
+        self.state = match self.state {
+            _ if !enable => {
+                self.buffer.4 = F::zero();
+                State::Idle
+            }
+            State::Idle => State::Precharging { since: time },
+            State::Precharging { since } if charged => {
+                if elapsed(since) < parameters.min_time {
+                    self.fault(FAULT_TOO_FAST)
+                } else {
+                    State::Closing { since: time }
+                }
+            }
+            State::Precharging { since } if elapsed(since) >= parameters.timeout => {
+                self.fault(FAULT_TIMEOUT)
+            }
+            State::Closing { since } if elapsed(since) >= parameters.overlap => State::Ready,
+            State::Closing { .. } | State::Ready if !charged => self.fault(FAULT_VOLTAGE_LOST),
+            state => state,
+        };
+
+        let (precharge, main) = match self.state {
+            State::Precharging { .. } => (true, false),
+            State::Closing { .. } => (true, true),
+            State::Ready => (false, true),
+            State::Idle | State::Fault => (false, false),
+        };
+        self.buffer.0 = precharge;
+        self.buffer.1 = main;
+        self.buffer.2 = self.state == State::Ready;
+        self.buffer.3 = self.state == State::Fault;
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+
+    fn params() -> Parameters<f64> {
+        Parameters::new(0.9, 1.0, 0.2, 0.1)
+    }
+
+    #[test]
+    fn test_precharge_default_buffer_no_panic() {
+        let block = PrechargeBlock::<f64>::default();
+        assert_eq!(block.buffer(), (false, false, false, false, 0.0));
+    }
+
+    #[test]
+    fn test_precharge_sequence() {
+        let mut runtime = StubRuntime::default();
+        let params = params();
+        let mut block = PrechargeBlock::<f64>::default();
+
+        let mut run = |time_ms, enable, bus_voltage| {
+            runtime.set_time(Duration::from_millis(time_ms));
+            block.process(&params, &runtime.context(), (enable, bus_voltage, 400.0))
+        };
+
+        assert_eq!(run(0, false, 0.0), (false, false, false, false, 0.0));
+        assert_eq!(run(10, true, 0.0), (true, false, false, false, 0.0));
+        assert_eq!(run(200, true, 300.0), (true, false, false, false, 0.0));
+        // Main contactor closes at 90%, then the precharge relay opens after the overlap
+        assert_eq!(run(300, true, 360.0), (true, true, false, false, 0.0));
+        assert_eq!(run(350, true, 398.0), (true, true, false, false, 0.0));
+        assert_eq!(run(400, true, 398.0), (false, true, true, false, 0.0));
+        assert_eq!(run(5000, true, 395.0), (false, true, true, false, 0.0));
+
+        // Disabling opens everything
+        assert_eq!(run(5010, false, 395.0), (false, false, false, false, 0.0));
+    }
+
+    #[test]
+    fn test_precharge_faults() {
+        let mut runtime = StubRuntime::default();
+        let params = params();
+        let mut block = PrechargeBlock::<f64>::default();
+
+        let mut run = |time_ms, enable, bus_voltage| {
+            runtime.set_time(Duration::from_millis(time_ms));
+            block.process(&params, &runtime.context(), (enable, bus_voltage, 400.0))
+        };
+
+        // Timeout
+        run(0, true, 0.0);
+        assert_eq!(run(999, true, 100.0), (true, false, false, false, 0.0));
+        assert_eq!(
+            run(1000, true, 100.0),
+            (false, false, false, true, FAULT_TIMEOUT)
+        );
+        // Latched until disabled
+        assert_eq!(
+            run(1100, true, 400.0),
+            (false, false, false, true, FAULT_TIMEOUT)
+        );
+        assert_eq!(run(1200, false, 0.0), (false, false, false, false, 0.0));
+
+        // Too fast
+        run(2000, true, 0.0);
+        assert_eq!(
+            run(2100, true, 380.0),
+            (false, false, false, true, FAULT_TOO_FAST)
+        );
+        run(2200, false, 0.0);
+
+        // Voltage lost once connected
+        run(3000, true, 0.0);
+        run(3500, true, 390.0);
+        run(3600, true, 390.0);
+        assert_eq!(
+            run(3700, true, 100.0),
+            (false, false, false, true, FAULT_VOLTAGE_LOST)
+        );
+    }
+
+    #[test]
+    fn test_precharge_no_source_voltage() {
+        let mut runtime = StubRuntime::default();
+        let params = params();
+        let mut block = PrechargeBlock::<f64>::default();
+
+        // A bus at 0V doesn't count as charged when the source is also at 0V
+        block.process(&params, &runtime.context(), (true, 0.0, 0.0));
+        runtime.set_time(Duration::from_millis(500));
+        let output = block.process(&params, &runtime.context(), (true, 0.0, 0.0));
+        assert_eq!(output, (true, false, false, false, 0.0));
+    }
+}
@@ -11,7 +11,8 @@ use matrix::{ApplyMatMul, ParametersMatrixMult};
 /// Calculates the product of all of its input signals.
 ///
 /// The product can be calculated in two ways:
-/// - ComponentWise: Accepts Scalars, Same Size Matrices, or Scalars and Same Size Matrices
+/// - ComponentWise: Accepts Scalars, Same Size Matrices, or Scalars and Same Size Matrices.
+///   This includes fixed-point ([`pictorus_traits::Fixed`]) signals
 /// - MatrixMultiply: Accepts all matrices, using standard matrix multiplication sizing rules (i.e. (A, B) * (B, C) = (A, C))
 pub struct ProductBlock<T: Apply<M>, M: ProductMethod> {
     _method: PhantomData<M>,
@@ -101,7 +102,7 @@ mod tests {
     use super::*;
     use crate::testing::StubContext;
     use component::ParametersComponentWise;
-    use pictorus_traits::Matrix;
+    use pictorus_traits::{Matrix, Q15, Q31};
 
     #[test]
     fn test_product_default_buffer_no_panic() {
@@ -238,4 +239,28 @@ mod tests {
         assert_eq!(output, &expected);
         assert_eq!(block.buffer(), &expected);
     }
+
+    #[test]
+    fn test_component_wise_fixed_point() {
+        let context = StubContext::default();
+        let q = Q31::from_f64;
+
+        let mut block = ProductBlock::<(Q31, Q31), ComponentWise>::default();
+        let parameters = ParametersComponentWise::new([1.0, 1.0]);
+        let output = block.process(&parameters, &context, (q(0.5), q(-0.25)));
+        assert_eq!(output, q(-0.125));
+
+        // Dividing by a value larger in magnitude than the numerator stays in range for Q31
+        let parameters = ParametersComponentWise::new([1.0, -1.0]);
+        let output = block.process(&parameters, &context, (q(0.25), q(0.5)));
+        assert_eq!(output, q(0.5));
+
+        let mut block = ProductBlock::<(Q15, Matrix<1, 2, Q15>), ComponentWise>::default();
+        let parameters = ParametersComponentWise::new([1.0, -1.0]);
+        let input = Matrix {
+            data: [[Q15::from_f64(2.0)], [Q15::from_f64(-4.0)]],
+        };
+        let output = block.process(&parameters, &context, (Q15::from_f64(3.0), &input));
+        assert_eq!(output.data, [[Q15::from_f64(1.5)], [Q15::from_f64(-0.75)]]);
+    }
 }
@@ -1,6 +1,6 @@
 /// Functionality for componentwise mode of the ProductBlock.
 use crate::traits::{ApplyInto, MatrixOps, Scalar, SizePromotion};
-use pictorus_traits::{Matrix, Pass, PassBy};
+use pictorus_traits::{Fixed, Matrix, Pass, PassBy};
 
 // For the ComponentWise method the PArameters needs a multiply/divide parameter for each
 /// input signal
@@ -92,6 +92,96 @@ where
     }
 }
 
+// Fixed-point scalars can't implement `num_traits::One`, so instead of starting from one the
+// first input initializes the destination. This also avoids error for Q31, where one is not representable.
+fn fixed_first<const FRAC: u32>(input: Fixed<FRAC>, params: &ProductOperation) -> Fixed<FRAC> {
+    match params {
+        ProductOperation::Multiply => input,
+        ProductOperation::Divide => Fixed::ONE / input,
+    }
+}
+
+fn fixed_apply<const FRAC: u32>(
+    input: Fixed<FRAC>,
+    params: &ProductOperation,
+    dest: &mut Fixed<FRAC>,
+) {
+    match params {
+        ProductOperation::Multiply => *dest *= input,
+        ProductOperation::Divide => *dest /= input,
+    }
+}
+
+// Fixed-point Scalar into Scalar
+impl<const FRAC: u32> ApplyInto<Fixed<FRAC>, ProductOperation> for Fixed<FRAC> {
+    fn apply_into<'a>(
+        input: PassBy<Self>,
+        params: &ProductOperation,
+        dest: &'a mut Option<Fixed<FRAC>>,
+    ) -> PassBy<'a, Fixed<FRAC>> {
+        match dest {
+            Some(dest) => fixed_apply(input, params, dest),
+            None => *dest = Some(fixed_first(input, params)),
+        }
+        dest.expect("dest was initialized above")
+    }
+}
+
+// Fixed-point Matrix into Matrix
+impl<const FRAC: u32, const R: usize, const C: usize>
+    ApplyInto<Matrix<R, C, Fixed<FRAC>>, ProductOperation> for Matrix<R, C, Fixed<FRAC>>
+{
+    fn apply_into<'a>(
+        input: PassBy<Self>,
+        params: &ProductOperation,
+        dest: &'a mut Option<Matrix<R, C, Fixed<FRAC>>>,
+    ) -> PassBy<'a, Matrix<R, C, Fixed<FRAC>>> {
+        match dest {
+            Some(dest) => dest
+                .data
+                .as_flattened_mut()
+                .iter_mut()
+                .zip(input.data.as_flattened())
+                .for_each(|(dest, val)| fixed_apply(*val, params, dest)),
+            None => {
+                let mut first = *input;
+                first
+                    .data
+                    .as_flattened_mut()
+                    .iter_mut()
+                    .for_each(|val| *val = fixed_first(*val, params));
+                *dest = Some(first);
+            }
+        }
+        dest.as_ref().expect("dest was initialized above")
+    }
+}
+
+// Fixed-point Scalar into Matrix
+impl<const FRAC: u32, const R: usize, const C: usize>
+    ApplyInto<Matrix<R, C, Fixed<FRAC>>, ProductOperation> for Fixed<FRAC>
+{
+    fn apply_into<'a>(
+        input: PassBy<Self>,
+        params: &ProductOperation,
+        dest: &'a mut Option<Matrix<R, C, Fixed<FRAC>>>,
+    ) -> PassBy<'a, Matrix<R, C, Fixed<FRAC>>> {
+        match dest {
+            Some(dest) => dest
+                .data
+                .as_flattened_mut()
+                .iter_mut()
+                .for_each(|dest| fixed_apply(input, params, dest)),
+            None => {
+                *dest = Some(Matrix {
+                    data: [[fixed_first(input, params); R]; C],
+                })
+            }
+        }
+        dest.as_ref().expect("dest was initialized above")
+    }
+}
+
 /// This is a remix of the [`crate::traits::Apply`] trait. It has been extended to support per signal parameter specification
 pub trait ApplyComponentWise: Pass {
     type Parameters;
@@ -0,0 +1,163 @@
+use crate::traits::Float;
+use crate::ParameterError;
+use pictorus_traits::{PassBy, ProcessBlock};
+
+/// Callendar–Van Dusen coefficients of IEC 60751 platinum RTDs
+const A: f64 = 3.9083e-3;
+const B: f64 = -5.775e-7;
+const C: f64 = -4.183e-12;
+
+/// Temperature range (°C) of IEC 60751
+const RANGE: (f64, f64) = (-200.0, 850.0);
+
+/// Resistance ratio `R / R0` at a temperature (°C)
+fn resistance_ratio(temperature: f64) -> f64 {
+    let ratio = 1.0 + A * temperature + B * temperature * temperature;
+    if temperature < 0.0 {
+        ratio + C * (temperature - 100.0) * temperature * temperature * temperature
+    } else {
+        ratio
+    }
+}
+
+/// Temperature (°C) at a resistance ratio `R / R0`
+fn temperature(ratio: f64) -> f64 {
+    // Above 0°C the equation is quadratic, which also makes a good first guess below
+    let mut temperature =
+        (-A + num_traits::Float::sqrt(A * A - 4.0 * B * (1.0 - ratio))) / (2.0 * B);
+    if ratio < 1.0 {
+        // Below 0°C the C term makes it quartic, so refine with Newton's method. This converges
+        // to well under a millidegree in a handful of iterations over the whole range.
+        for _ in 0..8 {
+            let t = temperature;
+            let derivative = A + 2.0 * B * t + C * (4.0 * t * t * t - 300.0 * t * t);
+            let step = (resistance_ratio(t) - ratio) / derivative;
+            temperature -= step;
+            if num_traits::Float::abs(step) < 1e-9 {
+                break;
+            }
+        }
+    }
+    temperature
+}
+
+/// Parameters for the Pt100Block
+pub struct Parameters {
+    /// Resistance (ohms) at 0°C, e.g. 100 for a PT100 or 1000 for a PT1000
+    pub r0: f64,
+}
+
+impl Parameters {
+    pub fn new(r0: f64) -> Self {
+        Self::try_new(r0).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(r0: f64) -> Result<Self, ParameterError> {
+        if !r0.is_finite() || r0 <= 0.0 {
+            return Err(ParameterError("RTD resistance at 0°C must be positive"));
+        }
+        Ok(Self { r0 })
+    }
+}
+
+/// Converts the resistance of a platinum RTD (PT100, PT1000, ...) to temperature using the
+/// Callendar–Van Dusen equation with the IEC 60751 coefficients.
+///
+/// The input is the measured resistance in ohms, e.g. from a bridge or a current source and an
+/// ADC. The output is a tuple of (temperature in °C, in range). Outside -200°C to 850°C the
+/// temperature is extrapolated, and in range is false.
+pub struct Pt100Block<F: Float> {
+    buffer: (F, bool),
+}
+
+impl<F: Float> Default for Pt100Block<F> {
+    fn default() -> Self {
+        Self {
+            buffer: (F::zero(), false),
+        }
+    }
+}
+
+impl<F: Float> ProcessBlock for Pt100Block<F> {
+    type Inputs = F;
+    type Output = (F, bool);
+    type Parameters = Parameters;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let resistance = input.to_f64().unwrap_or(f64::NAN);
+        let temperature = temperature(resistance / parameters.r0);
+        let (lower, upper) = RANGE;
+        self.buffer = (
+            F::from(temperature).unwrap_or(F::nan()),
+            (lower..=upper).contains(&temperature),
+        );
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use approx::assert_abs_diff_eq;
+
+    fn process(r0: f64, resistance: f64) -> (f64, bool) {
+        let params = Parameters::new(r0);
+        let mut block = Pt100Block::<f64>::default();
+        block.process(&params, &StubContext::default(), resistance)
+    }
+
+    #[test]
+    fn test_pt100_default_buffer_no_panic() {
+        let block = Pt100Block::<f64>::default();
+        assert_eq!(block.buffer(), (0.0, false));
+    }
+
+    #[test]
+    fn test_pt100_reference_tables() {
+        // Points from the IEC 60751 tables
+        let points = [
+            (22.83, -190.0),
+            (60.26, -100.0),
+            (100.0, 0.0),
+            (138.51, 100.0),
+            (280.98, 500.0),
+            (375.70, 800.0),
+        ];
+        for (resistance, expected) in points {
+            let (temperature, in_range) = process(100.0, resistance);
+            assert_abs_diff_eq!(temperature, expected, epsilon = 0.05);
+            assert!(in_range);
+            let (temperature, _) = process(1000.0, resistance * 10.0);
+            assert_abs_diff_eq!(temperature, expected, epsilon = 0.05);
+        }
+    }
+
+    #[test]
+    fn test_pt100_round_trip() {
+        for expected in [-200.0, -150.0, -40.0, -0.5, 25.0, 600.0] {
+            let (temperature, _) = process(100.0, 100.0 * resistance_ratio(expected));
+            assert_abs_diff_eq!(temperature, expected, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_pt100_range() {
+        let (_, in_range) = process(100.0, 400.0);
+        assert!(!in_range);
+        let (temperature, in_range) = process(100.0, f64::NAN);
+        assert!(temperature.is_nan());
+        assert!(!in_range);
+        assert!(Parameters::try_new(0.0).is_err());
+    }
+}
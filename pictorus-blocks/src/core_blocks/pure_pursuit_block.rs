@@ -0,0 +1,171 @@
+use crate::core_blocks::clamp_block::clamp;
+use crate::path_tracking::{distance, lookahead_point, point, project, wrap_angle};
+use crate::traits::Float;
+use pictorus_traits::{Matrix, PassBy, ProcessBlock};
+
+/// Parameters for the PurePursuitBlock
+pub struct Parameters<F: Float> {
+    /// Distance along the path to the point being steered towards
+    pub lookahead: F,
+    /// Distance between the front and rear axles
+    pub wheelbase: F,
+    /// Velocity command while following the path
+    pub velocity: F,
+    /// Maximum steering angle magnitude, in radians
+    pub max_steering: F,
+    /// Distance from the end of the path at which the vehicle stops
+    pub goal_tolerance: F,
+}
+
+impl<F: Float> Parameters<F> {
+    pub fn new(
+        lookahead: F,
+        wheelbase: F,
+        velocity: F,
+        max_steering: F,
+        goal_tolerance: F,
+    ) -> Self {
+        Self {
+            lookahead,
+            wheelbase,
+            velocity,
+            max_steering,
+            goal_tolerance,
+        }
+    }
+}
+
+/// Pure pursuit path-tracking controller for car-like (bicycle model) ground vehicles.
+///
+/// The inputs are the vehicle pose `(x, y, heading)` as a (1, 3) matrix, measured at the rear axle,
+/// and the path as a (P, 2) matrix of `(x, y)` points. The block finds the closest point on the path,
+/// walks `lookahead` further along it, and outputs the steering angle of the circular arc from the
+/// rear axle through that point. Headings and steering angles are in radians, counter-clockwise
+/// (left) positive.
+///
+/// The output is a tuple of (steering angle, velocity). The velocity is the configured cruise
+/// velocity, dropping to zero (with zero steering) once the vehicle is within the goal tolerance
+/// of the end of the path.
+pub struct PurePursuitBlock<F: Float, const P: usize> {
+    buffer: (F, F),
+}
+
+impl<F: Float, const P: usize> Default for PurePursuitBlock<F, P> {
+    fn default() -> Self {
+        const {
+            assert!(
+                P >= 2,
+                "PurePursuitBlock requires a path of at least two points"
+            );
+        }
+        Self {
+            buffer: (F::default(), F::default()),
+        }
+    }
+}
+
+impl<F: Float, const P: usize> ProcessBlock for PurePursuitBlock<F, P> {
+    type Inputs = (Matrix<1, 3, F>, Matrix<P, 2, F>);
+    type Output = (F, F);
+    type Parameters = Parameters<F>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (pose, path) = inputs;
+        let position = [pose.data[0][0], pose.data[1][0]];
+        let heading = pose.data[2][0];
+        let zero = <F as num_traits::Zero>::zero();
+
+        if distance(&position, &point(path, P - 1)) <= parameters.goal_tolerance {
+            self.buffer = (zero, zero);
+            return self.buffer;
+        }
+
+        let projection = project(path, &position);
+        let target = lookahead_point(path, &projection, parameters.lookahead);
+        let target_distance = distance(&position, &target);
+        let steering = if target_distance > zero {
+            let alpha = wrap_angle(
+                num_traits::Float::atan2(target[1] - position[1], target[0] - position[0])
+                    - heading,
+            );
+            let two = <F as num_traits::One>::one() + <F as num_traits::One>::one();
+            num_traits::Float::atan(
+                two * parameters.wheelbase * num_traits::Float::sin(alpha) / target_distance,
+            )
+        } else {
+            zero
+        };
+        let steering = clamp(steering, -parameters.max_steering, parameters.max_steering);
+
+        self.buffer = (steering, parameters.velocity);
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use approx::assert_relative_eq;
+
+    const PATH: Matrix<3, 2, f64> = Matrix {
+        data: [[0.0, 10.0, 20.0], [0.0, 0.0, 0.0]],
+    };
+
+    fn pose(x: f64, y: f64, heading: f64) -> Matrix<1, 3, f64> {
+        Matrix {
+            data: [[x], [y], [heading]],
+        }
+    }
+
+    #[test]
+    fn test_pure_pursuit_default_buffer() {
+        let block = PurePursuitBlock::<f64, 3>::default();
+        assert_eq!(block.buffer(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_pure_pursuit_steering() {
+        let ctxt = StubContext::default();
+        let params = Parameters::new(4.0, 2.0, 3.0, 0.5, 0.5);
+        let mut block = PurePursuitBlock::<f64, 3>::default();
+
+        // On the path and aligned with it
+        let (steering, velocity) = block.process(&params, &ctxt, (&pose(5.0, 0.0, 0.0), &PATH));
+        assert_relative_eq!(steering, 0.0);
+        assert_eq!(velocity, 3.0);
+
+        // 3m right of the path, the target is (9, 0), 5m away at an angle alpha with
+        // sin(alpha) = 3/5, so the steering is atan(2 * 2 * 0.6 / 5)
+        let (steering, _) = block.process(&params, &ctxt, (&pose(5.0, -3.0, 0.0), &PATH));
+        assert_relative_eq!(steering, (2.4f64 / 5.0).atan());
+
+        // Steering is limited
+        let (steering, _) = block.process(&params, &ctxt, (&pose(5.0, 3.0, 1.5), &PATH));
+        assert_eq!(steering, -0.5);
+    }
+
+    #[test]
+    fn test_pure_pursuit_stops_at_goal() {
+        let ctxt = StubContext::default();
+        let params = Parameters::new(4.0, 2.0, 3.0, 0.5, 0.5);
+        let mut block = PurePursuitBlock::<f64, 3>::default();
+
+        // Near the end the target clamps to the final point
+        let (steering, velocity) = block.process(&params, &ctxt, (&pose(18.0, 0.0, 0.0), &PATH));
+        assert_relative_eq!(steering, 0.0);
+        assert_eq!(velocity, 3.0);
+
+        let (steering, velocity) = block.process(&params, &ctxt, (&pose(19.8, 0.1, 0.0), &PATH));
+        assert_eq!((steering, velocity), (0.0, 0.0));
+    }
+}
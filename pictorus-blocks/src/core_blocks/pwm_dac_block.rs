@@ -0,0 +1,164 @@
+use core::time::Duration;
+
+use crate::core_blocks::clamp_block::clamp;
+use crate::fast_math::FastMath;
+use crate::traits::Float;
+use pictorus_traits::{PassBy, ProcessBlock};
+
+/// Parameters for the PwmDacBlock
+pub struct Parameters<F: Float> {
+    /// High level of the PWM output in volts, i.e. the filter output at 100% duty
+    pub supply_voltage: F,
+    /// Time constant (seconds) of the RC filter on the PWM output, `R * C`. Zero disables the
+    /// compensation, so the duty cycle is just the target over the supply voltage.
+    pub time_constant: F,
+}
+
+impl<F: Float> Parameters<F> {
+    pub fn new(supply_voltage: F, time_constant: F) -> Self {
+        Self {
+            supply_voltage,
+            time_constant,
+        }
+    }
+}
+
+/// Converts a desired analog voltage into a PWM duty cycle, for generating analog signals with
+/// a PWM output and an RC low-pass filter on MCUs without a DAC.
+///
+/// The duty cycle alone settles the filter output on the target only after several time
+/// constants. With the filter's time constant set, the block inverts a first-order model of the
+/// filter, boosting the duty cycle so the modeled output reaches the target by the next tick,
+/// within what 0% to 100% duty can achieve. The PWM frequency should be well above the filter
+/// cutoff, `1 / (2 * pi * R * C)`, so the ripple is small, and the cutoff well above the model
+/// rate for the compensation to have an effect.
+///
+/// The input is the target voltage, clamped between 0 and the supply voltage. The output is a
+/// tuple of (duty cycle, modeled filter output in volts). The duty cycle is between 0 and 1,
+/// ready for the PWM block. A NaN target holds the previous duty cycle.
+pub struct PwmDacBlock<F: Float> {
+    buffer: (F, F),
+}
+
+impl<F: Float> Default for PwmDacBlock<F> {
+    fn default() -> Self {
+        Self {
+            buffer: (F::zero(), F::zero()),
+        }
+    }
+}
+
+impl<F: Float> ProcessBlock for PwmDacBlock<F> {
+    type Inputs = F;
+    type Output = (F, F);
+    type Parameters = Parameters<F>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let zero = F::zero();
+        let supply = parameters.supply_voltage;
+        let (duty, filter_output) = &mut self.buffer;
+        if supply <= zero {
+            *duty = zero;
+            return self.buffer;
+        }
+
+        // Fraction of the filter state left after a tick, assuming the duty is constant within it
+        let dt = F::from_duration(context.timestep().unwrap_or(Duration::ZERO));
+        let decay = if parameters.time_constant > zero {
+            FastMath::fast_exp(-dt / parameters.time_constant)
+        } else {
+            zero
+        };
+
+        let drive = if num_traits::Float::is_nan(input) {
+            *duty * supply
+        } else {
+            let target = clamp(input, zero, supply);
+            if decay < F::one() {
+                // Exact inverse of the filter over the tick
+                (target - decay * *filter_output) / (F::one() - decay)
+            } else {
+                target
+            }
+        };
+        let drive = clamp(drive, zero, supply);
+
+        *duty = drive / supply;
+        *filter_output = drive + (*filter_output - drive) * decay;
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{fast_math_epsilon, StubRuntime};
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_pwm_dac_default_buffer_no_panic() {
+        let block = PwmDacBlock::<f64>::default();
+        assert_eq!(block.buffer(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_pwm_dac_uncompensated() {
+        let runtime = StubRuntime::default();
+        let params = Parameters::new(3.3, 0.0);
+        let mut block = PwmDacBlock::<f64>::default();
+
+        let (duty, output) = block.process(&params, &runtime.context(), 1.65);
+        assert_relative_eq!(duty, 0.5);
+        assert_relative_eq!(output, 1.65);
+
+        // Clamped to what the PWM can produce
+        assert_eq!(block.process(&params, &runtime.context(), 5.0), (1.0, 3.3));
+        assert_eq!(block.process(&params, &runtime.context(), -1.0), (0.0, 0.0));
+
+        // NaN holds the previous duty
+        assert_eq!(
+            block.process(&params, &runtime.context(), f64::NAN),
+            (0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_pwm_dac_compensated() {
+        let mut runtime = StubRuntime::with_timestep(Duration::from_millis(10));
+        let params = Parameters::new(5.0, 0.01);
+        let mut block = PwmDacBlock::<f64>::default();
+        let epsilon = fast_math_epsilon(1e-9);
+
+        // The first tick has no timestep, so there is no boost yet
+        let (duty, _) = block.process(&params, &runtime.context(), 1.0);
+        assert_relative_eq!(duty, 0.2);
+
+        // Boosted to reach 1V in one tick: e^-1 of the way there still to go
+        runtime.tick();
+        let decay = (-1.0f64).exp();
+        let (duty, output) = block.process(&params, &runtime.context(), 1.0);
+        assert_relative_eq!(duty, 1.0 / (1.0 - decay) / 5.0, epsilon = epsilon);
+        assert_relative_eq!(output, 1.0, epsilon = epsilon);
+
+        // Settled, so the duty is back to the plain ratio
+        runtime.tick();
+        let (duty, output) = block.process(&params, &runtime.context(), 1.0);
+        assert_relative_eq!(duty, 0.2, epsilon = epsilon);
+        assert_relative_eq!(output, 1.0, epsilon = epsilon);
+
+        // A step the PWM can't reach in one tick saturates at 100% duty
+        runtime.tick();
+        let (duty, output) = block.process(&params, &runtime.context(), 4.0);
+        assert_eq!(duty, 1.0);
+        assert_relative_eq!(output, 5.0 - 4.0 * decay, epsilon = epsilon);
+    }
+}
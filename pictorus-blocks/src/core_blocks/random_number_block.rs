@@ -1,41 +1,51 @@
 use num_traits::Float;
 use pictorus_traits::{GeneratorBlock, PassBy, Scalar};
-use rand::{rngs::SmallRng, Rng, SeedableRng};
+use rand::{rngs::SmallRng, Rng, RngCore, SeedableRng};
 use rand_distr::{Distribution, Normal, StandardNormal};
 
 #[derive(Debug, Clone)]
 /// Generates random numbers from a normal distribution with specified mean and standard deviation.
-pub struct RandomNumberBlock<T>
+///
+/// The sequence is fully determined by the seed parameter. `R` is the random number generator;
+/// use one whose algorithm doesn't vary between targets (such as `pictorus_internal::rand::Rng`)
+/// when a seed must reproduce the same sequence in simulation and on hardware.
+pub struct RandomNumberBlock<T, R = SmallRng>
 where
     T: Scalar + Float,
     f64: From<T>,
     StandardNormal: Distribution<T>,
+    R: RngCore + SeedableRng,
 {
     phantom: core::marker::PhantomData<T>,
-    rng: SmallRng,
+    rng: R,
+    /// Seed `rng` was last seeded with
+    seed: u64,
     buffer: T,
 }
 
-impl<T> Default for RandomNumberBlock<T>
+impl<T, R> Default for RandomNumberBlock<T, R>
 where
     T: Scalar + Float,
     f64: From<T>,
     StandardNormal: Distribution<T>,
+    R: RngCore + SeedableRng,
 {
     fn default() -> Self {
         Self {
             phantom: core::marker::PhantomData,
-            rng: SmallRng::seed_from_u64(0u64),
+            rng: R::seed_from_u64(0u64),
+            seed: 0,
             buffer: T::default(),
         }
     }
 }
 
-impl<T> GeneratorBlock for RandomNumberBlock<T>
+impl<T, R> GeneratorBlock for RandomNumberBlock<T, R>
 where
     T: Scalar + Float,
     f64: From<T>,
     StandardNormal: Distribution<T>,
+    R: RngCore + SeedableRng,
 {
     type Output = T;
     type Parameters = Parameters<T>;
@@ -45,10 +55,20 @@ where
         parameters: &Self::Parameters,
         _context: &dyn pictorus_traits::Context,
     ) -> pictorus_traits::PassBy<'_, Self::Output> {
-        let val = self
-            .rng
-            //Will Fail if std2 is infinite: https://docs.rs/rand_distr/latest/src/rand_distr/normal.rs.html#156-161
-            .sample(Normal::new(parameters.mean, parameters.std2).unwrap());
+        // Restart the sequence if the seed is changed, e.g. by a parameter reload
+        if parameters.seed != self.seed {
+            self.rng = R::seed_from_u64(parameters.seed);
+            self.seed = parameters.seed;
+        }
+        //Will Fail if std2 is infinite: https://docs.rs/rand_distr/latest/src/rand_distr/normal.rs.html#156-161
+        let val = match Normal::new(parameters.mean, parameters.std2) {
+            Ok(normal) => self.rng.sample(normal),
+            // With `panic-free`, an invalid distribution outputs the mean
+            #[cfg(feature = "panic-free")]
+            Err(_) => parameters.mean,
+            #[cfg(not(feature = "panic-free"))]
+            Err(err) => panic!("Invalid normal distribution: {err}"),
+        };
         self.buffer = val;
         val
     }
@@ -61,11 +81,18 @@ where
 pub struct Parameters<T: Scalar> {
     pub mean: T,
     pub std2: T,
+    /// Seed of the random number generator. Derive it from the run's seed (see
+    /// `pictorus_internal::rand::RunSeed`) so the whole run can be reproduced.
+    pub seed: u64,
 }
 
 impl<T: Scalar> Parameters<T> {
     pub fn new(mean: T, std2: T) -> Self {
-        Self { mean, std2 }
+        Self::with_seed(mean, std2, 0)
+    }
+
+    pub fn with_seed(mean: T, std2: T, seed: u64) -> Self {
+        Self { mean, std2, seed }
     }
 }
 
@@ -94,4 +121,22 @@ mod tests {
         let mut block = RandomNumberBlock::<f64>::default();
         block.generate(&Parameters::new(1.0, 2.0), &stub_context);
     }
+    #[test]
+    fn test_random_number_seed() {
+        let stub_context = StubContext::default();
+        let sequence = |block: &mut RandomNumberBlock<f64>, seed| -> [f64; 4] {
+            let parameters = Parameters::with_seed(0.0, 1.0, seed);
+            core::array::from_fn(|_| block.generate(&parameters, &stub_context))
+        };
+
+        let mut block = RandomNumberBlock::<f64>::default();
+        let seeded = sequence(&mut block, 7);
+        // The same seed always reproduces the same sequence
+        assert_eq!(sequence(&mut RandomNumberBlock::default(), 7), seeded);
+        assert_ne!(sequence(&mut RandomNumberBlock::default(), 8), seeded);
+        // Changing the seed restarts the sequence
+        let reseeded = sequence(&mut block, 8);
+        assert_eq!(sequence(&mut block, 7), seeded);
+        assert_ne!(reseeded, seeded);
+    }
 }
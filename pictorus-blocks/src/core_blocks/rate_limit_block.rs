@@ -1,3 +1,4 @@
+use crate::core_blocks::clamp_block::clamp;
 use crate::traits::{Float, MatrixOps};
 use num_traits::Zero;
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock, Scalar};
@@ -60,7 +61,7 @@ macro_rules! impl_rate_limit_block {
                     let timestep_s = <$type>::from_duration(timestep_duration);
                     let change_rate = (input - self.buffer) / timestep_s;
                     let clamped_change_rate =
-                        change_rate.clamp(parameters.falling_rate, parameters.rising_rate);
+                        clamp(change_rate, parameters.falling_rate, parameters.rising_rate);
 
                     self.buffer = if change_rate.is_nan() {
                         // This can happen if the timestep is zero and `input - self.buffer` == 0)
@@ -100,7 +101,7 @@ macro_rules! impl_rate_limit_block {
                     input.for_each(|v, c, r| {
                         let change_rate = (v - self.buffer.data[c][r]) / timestep_s;
                         let clamped_change_rate =
-                            change_rate.clamp(parameters.falling_rate, parameters.rising_rate);
+                            clamp(change_rate, parameters.falling_rate, parameters.rising_rate);
                         output.data[c][r] = if change_rate.is_nan() {
                             // This can happen if the timestep is zero and `v - self.buffer.data[c][r]` == 0)
                             self.buffer.data[c][r]
@@ -0,0 +1,343 @@
+use crate::traits::Float;
+use crate::ParameterError;
+use pictorus_traits::{Context, Matrix, Pass, PassBy, ProcessBlock};
+
+/// Number of samples kept, enough for cubic interpolation
+const HISTORY: usize = 4;
+
+#[derive(strum::EnumString, Clone, Copy, Debug, PartialEq)]
+/// How values between samples are reconstructed
+pub enum ResampleMethod {
+    /// Holds the latest sample at or before the output time
+    ZeroOrderHold,
+    /// Straight line between the samples either side of the output time
+    Linear,
+    /// Cubic Hermite spline through the samples, with tangents from the neighbouring samples
+    /// (Catmull-Rom, adjusted for uneven spacing). Smooth, but can overshoot around steps.
+    Cubic,
+}
+
+/// Parameters for the ResampleBlock
+#[derive(Clone, Copy, Debug)]
+pub struct Parameters {
+    pub method: ResampleMethod,
+    /// How far behind the model time the output is, in seconds
+    pub delay: f64,
+}
+
+impl Parameters {
+    pub fn new(method: &str, delay: f64) -> Self {
+        Self::try_new(method, delay).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(method: &str, delay: f64) -> Result<Self, ParameterError> {
+        let method = method
+            .parse()
+            .map_err(|_| ParameterError("Failed to parse method"))?;
+        Self::try_from_method(method, delay)
+    }
+
+    /// Like [`Parameters::new`], but takes an already-resolved method
+    pub fn from_method(method: ResampleMethod, delay: f64) -> Self {
+        Self::try_from_method(method, delay).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::from_method`], for builds where panics are unacceptable
+    pub const fn try_from_method(
+        method: ResampleMethod,
+        delay: f64,
+    ) -> Result<Self, ParameterError> {
+        if delay.is_nan() || delay < 0.0 {
+            return Err(ParameterError("Delay must be non-negative"));
+        }
+        Ok(Self { method, delay })
+    }
+}
+
+/// Resamples a signal sampled at its own (possibly irregular) rate to the model tick rate.
+///
+/// The inputs are the signal and the time it was sampled at, in seconds on the same clock as the
+/// app time. A new sample is recorded whenever the timestamp increases; repeated or out of order
+/// timestamps are ignored. On each tick the output is the signal reconstructed at the app time
+/// minus `delay`, using zero-order hold, linear or cubic interpolation between the recorded
+/// samples. This lets sensors running asynchronously to the model be fused on a common time base.
+///
+/// Interpolation needs samples on both sides of the output time, so `delay` should be at least
+/// the sensor's sample period plus its latency, and another period for cubic interpolation to
+/// have a sample beyond the interval. Outside the recorded samples the output holds the nearest
+/// one rather than extrapolating.
+///
+/// The input can be a scalar or a matrix, which is interpolated element-wise.
+pub struct ResampleBlock<T: Apply> {
+    buffer: T,
+    /// Recorded samples as (timestamp, value), oldest first
+    samples: [(f64, T); HISTORY],
+    len: usize,
+}
+
+impl<T: Apply> Default for ResampleBlock<T> {
+    fn default() -> Self {
+        Self {
+            buffer: T::default(),
+            samples: [(0.0, T::default()); HISTORY],
+            len: 0,
+        }
+    }
+}
+
+impl<T: Apply> ResampleBlock<T> {
+    /// The recorded samples, oldest first
+    fn recorded(&self) -> &[(f64, T)] {
+        self.samples.get(..self.len).unwrap_or_default()
+    }
+
+    fn record(&mut self, timestamp: f64, value: T) {
+        let last = self.recorded().last().map(|(last, _)| *last);
+        if timestamp.is_nan() || last.is_some_and(|last| timestamp <= last) {
+            return;
+        }
+        if self.len == HISTORY {
+            self.samples.rotate_left(1);
+            self.len -= 1;
+        }
+        if let Some(sample) = self.samples.get_mut(self.len) {
+            *sample = (timestamp, value);
+            self.len += 1;
+        }
+    }
+
+    /// Weights of the recorded samples that reconstruct the signal at `time`
+    fn weights(&self, method: ResampleMethod, time: f64) -> [f64; HISTORY] {
+        let samples = self.recorded();
+        let timestamp = |index: usize| samples.get(index).map_or(0.0, |(t, _)| *t);
+        let mut weights = [0.0; HISTORY];
+        let mut add = |index: usize, weight: f64| {
+            if let Some(slot) = weights.get_mut(index) {
+                *slot += weight;
+            }
+        };
+        // Index of the last sample at or before `time`
+        let Some(i) = samples.iter().rposition(|(t, _)| *t <= time) else {
+            add(0, 1.0);
+            return weights;
+        };
+        if i + 1 == samples.len() || method == ResampleMethod::ZeroOrderHold {
+            add(i, 1.0);
+            return weights;
+        }
+
+        let (t1, t2) = (timestamp(i), timestamp(i + 1));
+        let dt = t2 - t1;
+        let s = (time - t1) / dt;
+        if method == ResampleMethod::Linear {
+            add(i, 1.0 - s);
+            add(i + 1, s);
+            return weights;
+        }
+
+        // Hermite basis: p(s) = h00 p1 + h01 p2 + dt (h10 m1 + h11 m2), where the tangents m1
+        // and m2 are finite differences over the neighbouring samples, or over the interval
+        // itself at the ends of the history
+        let (s2, s3) = (s * s, s * s * s);
+        add(i, 2.0 * s3 - 3.0 * s2 + 1.0);
+        add(i + 1, -2.0 * s3 + 3.0 * s2);
+        let mut add_tangent = |scale: f64, from: usize, to: usize| {
+            let k = scale * dt / (timestamp(to) - timestamp(from));
+            add(to, k);
+            add(from, -k);
+        };
+        add_tangent(s3 - 2.0 * s2 + s, i.saturating_sub(1), i + 1);
+        add_tangent(s3 - s2, i, (i + 2).min(samples.len() - 1));
+        weights
+    }
+}
+
+impl<T: Apply> ProcessBlock for ResampleBlock<T> {
+    type Inputs = (T, f64);
+    type Output = T;
+    type Parameters = Parameters;
+
+    fn process(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+        inputs: PassBy<Self::Inputs>,
+    ) -> PassBy<'_, Self::Output> {
+        let (value, timestamp) = inputs;
+        self.record(timestamp, T::to_owned(value));
+        if self.len > 0 {
+            let time = context.time().as_secs_f64() - parameters.delay;
+            let weights = self.weights(parameters.method, time);
+            T::weighted_sum(&mut self.buffer, &self.samples, &weights);
+        }
+        self.buffer.as_by()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer.as_by()
+    }
+}
+
+pub trait Apply: Pass + Default + Copy {
+    fn to_owned(input: PassBy<Self>) -> Self;
+
+    /// Sets `store` to the sum of the sample values scaled by `weights`
+    fn weighted_sum(store: &mut Self, samples: &[(f64, Self); HISTORY], weights: &[f64; HISTORY]);
+}
+
+impl<F: Float> Apply for F {
+    fn to_owned(input: PassBy<Self>) -> Self {
+        input
+    }
+
+    fn weighted_sum(store: &mut Self, samples: &[(f64, Self); HISTORY], weights: &[f64; HISTORY]) {
+        *store = samples
+            .iter()
+            .zip(weights)
+            .filter(|(_, weight)| **weight != 0.0)
+            .fold(F::zero(), |acc, ((_, value), weight)| {
+                acc + *value * F::from(*weight).unwrap_or_else(F::zero)
+            });
+    }
+}
+
+impl<const NROWS: usize, const NCOLS: usize, F: Float> Apply for Matrix<NROWS, NCOLS, F> {
+    fn to_owned(input: PassBy<Self>) -> Self {
+        *input
+    }
+
+    fn weighted_sum(store: &mut Self, samples: &[(f64, Self); HISTORY], weights: &[f64; HISTORY]) {
+        *store = Matrix::zeroed();
+        for ((_, value), weight) in samples.iter().zip(weights) {
+            if *weight == 0.0 {
+                continue;
+            }
+            let weight = F::from(*weight).unwrap_or_else(F::zero);
+            for (output, value) in store
+                .data
+                .as_flattened_mut()
+                .iter_mut()
+                .zip(value.data.as_flattened())
+            {
+                *output += *value * weight;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+    use approx::assert_relative_eq;
+    use core::time::Duration;
+
+    /// Feeds a signal sampled every 0.25s with the given function into a block ticking every
+    /// 0.1s, returning the output at each tick from 1s onwards
+    fn run(method: &str, delay: f64, signal: impl Fn(f64) -> f64) -> [(f64, f64); 20] {
+        let mut runtime = StubRuntime::with_timestep(Duration::from_millis(100));
+        let mut block = ResampleBlock::<f64>::default();
+        let parameters = Parameters::new(method, delay);
+        let mut outputs = [(0.0, 0.0); 20];
+        for tick in 0..30 {
+            let time = runtime.context().time().as_secs_f64();
+            let sample_time = (time / 0.25).floor() * 0.25;
+            let output = block.process(
+                &parameters,
+                &runtime.context(),
+                (signal(sample_time), sample_time),
+            );
+            if tick >= 10 {
+                outputs[tick - 10] = (time - delay, output);
+            }
+            runtime.tick();
+        }
+        outputs
+    }
+
+    #[test]
+    fn test_resample_default_buffer_no_panic() {
+        let block = ResampleBlock::<f64>::default();
+        assert_eq!(block.buffer(), 0.0);
+
+        let block = ResampleBlock::<Matrix<2, 2, f32>>::default();
+        assert_eq!(block.buffer(), &Matrix::zeroed());
+    }
+
+    #[test]
+    fn test_resample_zero_order_hold() {
+        for (time, output) in run("ZeroOrderHold", 0.0, |t| t) {
+            assert_relative_eq!(output, (time / 0.25).floor() * 0.25, epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_resample_linear() {
+        // Lines are reconstructed exactly once the delay covers the sample period
+        for (time, output) in run("Linear", 0.25, |t| 2.0 * t - 1.0) {
+            assert_relative_eq!(output, 2.0 * time - 1.0, epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_resample_cubic() {
+        // Quadratics are reconstructed exactly with a sample either side of the interval
+        for (time, output) in run("Cubic", 0.5, |t| t * t) {
+            assert_relative_eq!(output, time * time, epsilon = 1e-9);
+        }
+        // And smooth signals closely
+        for (time, output) in run("Cubic", 0.5, f64::sin) {
+            assert_relative_eq!(output, time.sin(), epsilon = 2e-3);
+        }
+    }
+
+    #[test]
+    fn test_resample_holds_outside_samples() {
+        let runtime = StubRuntime::default();
+        let parameters = Parameters::new("Linear", 0.0);
+        let mut block = ResampleBlock::<f64>::default();
+
+        // A sample from the future is held until time catches up
+        assert_eq!(
+            block.process(&parameters, &runtime.context(), (3.0, 1.0)),
+            3.0
+        );
+        // Out of order samples are ignored
+        assert_eq!(
+            block.process(&parameters, &runtime.context(), (5.0, 0.5)),
+            3.0
+        );
+        assert_eq!(
+            block.process(&parameters, &runtime.context(), (5.0, 2.0)),
+            3.0
+        );
+    }
+
+    #[test]
+    fn test_resample_matrix() {
+        let mut runtime = StubRuntime::with_timestep(Duration::from_millis(500));
+        let parameters = Parameters::new("Linear", 1.0);
+        let mut block = ResampleBlock::<Matrix<2, 1, f32>>::default();
+
+        let a = Matrix { data: [[0.0, 4.0]] };
+        let b = Matrix { data: [[2.0, 0.0]] };
+        block.process(&parameters, &runtime.context(), (&a, 0.0));
+        runtime.tick();
+        block.process(&parameters, &runtime.context(), (&b, 1.0));
+        runtime.tick();
+        // Output times are 1.0 - 1.0 = 0.0, then 0.5
+        let output = block.process(&parameters, &runtime.context(), (&b, 1.0));
+        assert_eq!(output.data, [[0.0, 4.0]]);
+        runtime.tick();
+        let output = block.process(&parameters, &runtime.context(), (&b, 1.0));
+        assert_eq!(output.data, [[1.0, 2.0]]);
+    }
+
+    #[test]
+    fn test_resample_parameters() {
+        assert_eq!(Parameters::new("Cubic", 0.1).method, ResampleMethod::Cubic);
+        assert!(Parameters::try_new("Quintic", 0.1).is_err());
+        assert!(Parameters::try_new("Linear", -0.1).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+use heapless::Deque;
+use num_traits::Float;
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+/// Parameters for the RollingStatsBlock
+pub struct Parameters {}
+
+impl Default for Parameters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parameters {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Computes statistics over the last N samples of a scalar, vector, or matrix, e.g. to smooth
+/// a noisy sensor with a moving average.
+///
+/// The output is a tuple of (mean, min, max, variance), computed element-wise over the samples
+/// in the window. The variance is the population variance of those samples. Until N samples
+/// have been processed, the statistics cover the samples seen so far. The window is a fixed
+/// size ring buffer, so the block doesn't allocate.
+pub struct RollingStatsBlock<T: Pass + Default, const N: usize> {
+    window: Deque<T, N>,
+    buffer: (T, T, T, T),
+}
+
+impl<T: Pass + Default, const N: usize> Default for RollingStatsBlock<T, N> {
+    fn default() -> Self {
+        const {
+            assert!(
+                N > 0,
+                "RollingStatsBlock needs a window of at least one sample"
+            )
+        };
+        Self {
+            window: Deque::new(),
+            buffer: Default::default(),
+        }
+    }
+}
+
+impl<T: Pass + Default, const N: usize> RollingStatsBlock<T, N> {
+    /// Adds a sample to the window, dropping the oldest one once it's full
+    fn push(&mut self, sample: T) {
+        if self.window.is_full() {
+            self.window.pop_front();
+        }
+        // Can't fail, there's room after dropping the oldest sample
+        let _ = self.window.push_back(sample);
+    }
+}
+
+/// Mean, min, max and population variance of a non-empty set of values
+fn stats<F: Float>(values: impl Iterator<Item = F> + Clone) -> (F, F, F, F) {
+    let mut count = F::zero();
+    let mut sum = F::zero();
+    let mut min = F::infinity();
+    let mut max = F::neg_infinity();
+    for value in values.clone() {
+        count = count + F::one();
+        sum = sum + value;
+        min = min.min(value);
+        max = max.max(value);
+    }
+    let mean = sum / count;
+    // Summing squared deviations from the mean is steadier than the sum of squares when the
+    // values are large compared to their spread
+    let variance = values
+        .map(|value| (value - mean).powi(2))
+        .fold(F::zero(), |sum, deviation| sum + deviation)
+        / count;
+    (mean, min, max, variance)
+}
+
+macro_rules! impl_rolling_stats_block {
+    ($type:ty) => {
+        impl<const N: usize> ProcessBlock for RollingStatsBlock<$type, N> {
+            type Inputs = $type;
+            type Output = ($type, $type, $type, $type);
+            type Parameters = Parameters;
+
+            fn process(
+                &mut self,
+                _parameters: &Self::Parameters,
+                _context: &dyn pictorus_traits::Context,
+                input: PassBy<Self::Inputs>,
+            ) -> PassBy<'_, Self::Output> {
+                self.push(input);
+                self.buffer = stats(self.window.iter().copied());
+                self.buffer
+            }
+
+            fn buffer(&self) -> PassBy<'_, Self::Output> {
+                self.buffer.as_by()
+            }
+        }
+
+        impl<const ROWS: usize, const COLS: usize, const N: usize> ProcessBlock
+            for RollingStatsBlock<Matrix<ROWS, COLS, $type>, N>
+        {
+            type Inputs = Matrix<ROWS, COLS, $type>;
+            type Output = (
+                Matrix<ROWS, COLS, $type>,
+                Matrix<ROWS, COLS, $type>,
+                Matrix<ROWS, COLS, $type>,
+                Matrix<ROWS, COLS, $type>,
+            );
+            type Parameters = Parameters;
+
+            fn process(
+                &mut self,
+                _parameters: &Self::Parameters,
+                _context: &dyn pictorus_traits::Context,
+                input: PassBy<Self::Inputs>,
+            ) -> PassBy<'_, Self::Output> {
+                self.push(*input);
+                let (mean, min, max, variance) = &mut self.buffer;
+                for c in 0..COLS {
+                    for r in 0..ROWS {
+                        (
+                            mean.data[c][r],
+                            min.data[c][r],
+                            max.data[c][r],
+                            variance.data[c][r],
+                        ) = stats(self.window.iter().map(|sample| sample.data[c][r]));
+                    }
+                }
+                self.buffer.as_by()
+            }
+
+            fn buffer(&self) -> PassBy<'_, Self::Output> {
+                self.buffer.as_by()
+            }
+        }
+    };
+}
+
+impl_rolling_stats_block!(f32);
+impl_rolling_stats_block!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+
+    #[test]
+    fn test_rolling_stats_default_buffer_no_panic() {
+        let block = RollingStatsBlock::<f64, 4>::default();
+        assert_eq!(block.buffer(), (0.0, 0.0, 0.0, 0.0));
+
+        let block = RollingStatsBlock::<Matrix<2, 2, f32>, 4>::default();
+        let zeroed = Matrix::zeroed();
+        assert_eq!(block.buffer(), (&zeroed, &zeroed, &zeroed, &zeroed));
+    }
+
+    #[test]
+    fn test_rolling_stats_scalar() {
+        let context = StubContext::default();
+        let parameters = Parameters::new();
+        let mut block = RollingStatsBlock::<f64, 3>::default();
+
+        // Stats cover the samples seen so far until the window fills
+        assert_eq!(
+            block.process(&parameters, &context, 2.0),
+            (2.0, 2.0, 2.0, 0.0)
+        );
+        assert_eq!(
+            block.process(&parameters, &context, 4.0),
+            (3.0, 2.0, 4.0, 1.0)
+        );
+        assert_eq!(
+            block.process(&parameters, &context, 9.0),
+            (5.0, 2.0, 9.0, 26.0 / 3.0)
+        );
+
+        // Then the oldest sample drops out
+        assert_eq!(
+            block.process(&parameters, &context, 5.0),
+            (6.0, 4.0, 9.0, 14.0 / 3.0)
+        );
+        assert_eq!(block.buffer(), (6.0, 4.0, 9.0, 14.0 / 3.0));
+    }
+
+    #[test]
+    fn test_rolling_stats_matrix() {
+        let context = StubContext::default();
+        let parameters = Parameters::new();
+        let mut block = RollingStatsBlock::<Matrix<1, 2, f32>, 2>::default();
+
+        block.process(
+            &parameters,
+            &context,
+            &Matrix {
+                data: [[1.0], [-1.0]],
+            },
+        );
+        block.process(
+            &parameters,
+            &context,
+            &Matrix {
+                data: [[3.0], [-5.0]],
+            },
+        );
+        let (mean, min, max, variance) = block.process(
+            &parameters,
+            &context,
+            &Matrix {
+                data: [[5.0], [-1.0]],
+            },
+        );
+        assert_eq!(mean.data, [[4.0], [-3.0]]);
+        assert_eq!(min.data, [[3.0], [-5.0]]);
+        assert_eq!(max.data, [[5.0], [-1.0]]);
+        assert_eq!(variance.data, [[1.0], [4.0]]);
+    }
+}
@@ -0,0 +1,244 @@
+use core::time::Duration;
+
+use crate::core_blocks::clamp_block::clamp;
+use crate::stale_tracker::{duration_from_ms_f64, elapsed};
+use crate::traits::Float;
+use crate::ParameterError;
+use pictorus_traits::{PassBy, ProcessBlock};
+
+/// Parameters for the RotaryKnobBlock
+pub struct Parameters<F: Float> {
+    /// Encoder counts between detents, e.g. 4 for most quadrature knobs decoded at 4x
+    pub counts_per_detent: F,
+    /// Change of the value per detent, before acceleration
+    pub step: F,
+    pub min: F,
+    pub max: F,
+    /// Value at startup and on reset
+    pub initial: F,
+    /// Detents closer together than this are accelerated, or zero to disable acceleration
+    pub acceleration_time: Duration,
+    /// Step multiplier for detents that come together
+    pub max_acceleration: F,
+}
+
+impl<F: Float> Parameters<F> {
+    pub fn new(
+        counts_per_detent: F,
+        step: F,
+        min: F,
+        max: F,
+        initial: F,
+        acceleration_time_ms: f64,
+        max_acceleration: F,
+    ) -> Self {
+        Self::try_new(
+            counts_per_detent,
+            step,
+            min,
+            max,
+            initial,
+            acceleration_time_ms,
+            max_acceleration,
+        )
+        .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(
+        counts_per_detent: F,
+        step: F,
+        min: F,
+        max: F,
+        initial: F,
+        acceleration_time_ms: f64,
+        max_acceleration: F,
+    ) -> Result<Self, ParameterError> {
+        if num_traits::Float::is_nan(counts_per_detent) || counts_per_detent <= F::zero() {
+            return Err(ParameterError("Counts per detent must be positive"));
+        }
+        if num_traits::Float::is_nan(min) || num_traits::Float::is_nan(max) || min > max {
+            return Err(ParameterError("Knob minimum must not exceed its maximum"));
+        }
+        if !acceleration_time_ms.is_finite() || acceleration_time_ms < 0.0 {
+            return Err(ParameterError(
+                "Acceleration time must be a non-negative number of milliseconds",
+            ));
+        }
+        if num_traits::Float::is_nan(max_acceleration) || max_acceleration < F::one() {
+            return Err(ParameterError("Maximum acceleration must be at least 1"));
+        }
+        Ok(Self {
+            counts_per_detent,
+            step,
+            min,
+            max,
+            initial,
+            acceleration_time: duration_from_ms_f64(acceleration_time_ms),
+            max_acceleration,
+        })
+    }
+}
+
+/// Turns the count of a rotary encoder knob into a value adjusted in detent sized steps, for
+/// building HMI panels.
+///
+/// The inputs are (encoder count, reset), where the count is the running count of an encoder
+/// input, and reset sets the value back to the initial value. The first count is taken as the
+/// knob's resting position. Partial detents are carried over, so a knob resting between detents
+/// doesn't jitter the value.
+///
+/// Turning the knob quickly accelerates it: a detent that follows the previous one in the same
+/// direction within the acceleration time moves the value by up to the maximum acceleration
+/// times the step, scaling linearly with how soon it came. The multiplier is rounded to a whole
+/// number, so the value stays on the step grid.
+///
+/// The output is a tuple of (value, detents), where the value is clamped between the minimum
+/// and maximum, and detents is the signed number of detents turned this tick, before
+/// acceleration, e.g. for scrolling menus.
+pub struct RotaryKnobBlock<F: Float> {
+    buffer: (F, F),
+    /// Value, None until the initial value is read on the first tick
+    value: Option<F>,
+    /// Count the last whole detent ended at, None until the first count
+    detent_count: Option<F>,
+    /// Time and direction of the last detent
+    last_detent: Option<(Duration, bool)>,
+}
+
+impl<F: Float> Default for RotaryKnobBlock<F> {
+    fn default() -> Self {
+        Self {
+            buffer: (F::zero(), F::zero()),
+            value: None,
+            detent_count: None,
+            last_detent: None,
+        }
+    }
+}
+
+impl<F: Float> RotaryKnobBlock<F> {
+    /// Step multiplier for a detent in `direction` at `time`
+    fn acceleration(&self, parameters: &Parameters<F>, time: Duration, direction: bool) -> F {
+        let Some((last_time, last_direction)) = self.last_detent else {
+            return F::one();
+        };
+        let interval = elapsed(time, last_time).unwrap_or_default();
+        if last_direction != direction || interval >= parameters.acceleration_time {
+            return F::one();
+        }
+        let speed =
+            F::one() - F::from_duration(interval) / F::from_duration(parameters.acceleration_time);
+        num_traits::Float::round(F::one() + (parameters.max_acceleration - F::one()) * speed)
+    }
+}
+
+impl<F: Float> ProcessBlock for RotaryKnobBlock<F> {
+    type Inputs = (F, bool);
+    type Output = (F, F);
+    type Parameters = Parameters<F>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (count, reset) = inputs;
+        let limit = |value| clamp(value, parameters.min, parameters.max);
+        let mut value = *self.value.get_or_insert(limit(parameters.initial));
+        if reset {
+            value = limit(parameters.initial);
+        }
+
+        let mut detents = F::zero();
+        if !num_traits::Float::is_nan(count) {
+            let detent_count = *self.detent_count.get_or_insert(count);
+            detents =
+                num_traits::Float::trunc((count - detent_count) / parameters.counts_per_detent);
+            if detents != F::zero() {
+                self.detent_count = Some(detent_count + detents * parameters.counts_per_detent);
+                let time = context.time();
+                let direction = detents > F::zero();
+                let acceleration = self.acceleration(parameters, time, direction);
+                self.last_detent = Some((time, direction));
+                value = limit(value + detents * acceleration * parameters.step);
+            }
+        }
+
+        self.value = Some(value);
+        self.buffer = (value, detents);
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+
+    #[test]
+    fn test_rotary_knob_default_buffer_no_panic() {
+        let block = RotaryKnobBlock::<f64>::default();
+        assert_eq!(block.buffer(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_rotary_knob_detents() {
+        let runtime = StubRuntime::default();
+        let params = Parameters::new(4.0, 0.5, -2.0, 2.0, 0.0, 0.0, 1.0);
+        let mut block = RotaryKnobBlock::<f64>::default();
+        let mut run = |count, reset| block.process(&params, &runtime.context(), (count, reset));
+
+        // The first count is the resting position
+        assert_eq!(run(100.0, false), (0.0, 0.0));
+        // Partial detents don't move the value, or jitter it around a detent
+        assert_eq!(run(103.0, false), (0.0, 0.0));
+        assert_eq!(run(104.0, false), (0.5, 1.0));
+        assert_eq!(run(103.0, false), (0.5, 0.0));
+        assert_eq!(run(105.0, false), (0.5, 0.0));
+        assert_eq!(run(113.0, false), (1.5, 2.0));
+        assert_eq!(run(104.0, false), (0.5, -2.0));
+
+        // Clamped to the limits
+        assert_eq!(run(140.0, false), (2.0, 9.0));
+        assert_eq!(run(f64::NAN, false), (2.0, 0.0));
+        assert_eq!(run(140.0, true), (0.0, 0.0));
+        assert_eq!(run(60.0, false), (-2.0, -20.0));
+    }
+
+    #[test]
+    fn test_rotary_knob_acceleration() {
+        let mut runtime = StubRuntime::default();
+        let params = Parameters::new(1.0, 1.0, 0.0, 1000.0, 0.0, 100.0, 5.0);
+        let mut block = RotaryKnobBlock::<f64>::default();
+
+        let mut run = |time_ms, count| {
+            runtime.set_time(Duration::from_millis(time_ms));
+            block.process(&params, &runtime.context(), (count, false)).0
+        };
+
+        run(0, 0.0);
+        assert_eq!(run(1000, 1.0), 1.0);
+        // Slow turning isn't accelerated
+        assert_eq!(run(1200, 2.0), 2.0);
+        // Half the acceleration time apart is 3x
+        assert_eq!(run(1250, 3.0), 5.0);
+        // Immediately after is 5x
+        assert_eq!(run(1250, 4.0), 10.0);
+        // Changing direction resets the acceleration
+        assert_eq!(run(1260, 3.0), 9.0);
+    }
+
+    #[test]
+    fn test_rotary_knob_invalid_parameters() {
+        assert!(Parameters::try_new(0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0).is_err());
+        assert!(Parameters::try_new(4.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0).is_err());
+        assert!(Parameters::try_new(4.0, 1.0, 0.0, 1.0, 0.0, -1.0, 1.0).is_err());
+        assert!(Parameters::try_new(4.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.5).is_err());
+    }
+}
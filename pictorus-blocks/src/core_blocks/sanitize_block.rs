@@ -0,0 +1,219 @@
+use crate::traits::Float;
+use crate::ParameterError;
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+/// The value substituted for non-finite elements
+#[derive(Clone, Copy, Debug, PartialEq, strum::EnumString)]
+pub enum SanitizeFallback {
+    /// Replace the element with its last finite output value
+    HoldLast,
+    /// Replace the element with the `constant` parameter
+    Constant,
+}
+
+/// Parameters for the sanitize block
+pub struct Parameters<F: Float> {
+    pub fallback: SanitizeFallback,
+    /// The value substituted by the `Constant` fallback. This is also the initial
+    /// value held by the `HoldLast` fallback before any finite input is seen.
+    pub constant: F,
+}
+
+impl<F: Float> Parameters<F> {
+    pub fn new(fallback: &str, constant: F) -> Self {
+        Self::try_new(fallback, constant).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(fallback: &str, constant: F) -> Result<Self, ParameterError> {
+        Ok(Self {
+            fallback: fallback
+                .parse()
+                .map_err(|_| ParameterError("Failed to parse sanitize fallback."))?,
+            constant,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved fallback, without parsing
+    pub const fn from_fallback(fallback: SanitizeFallback, constant: F) -> Self {
+        Self { fallback, constant }
+    }
+}
+
+/// Detects NaN and Inf values in its input and substitutes a fallback value for them.
+///
+/// The output type of the block is a tuple of (<input_type>, bool), where the bool
+/// is `true` if any element of the input was non-finite on this tick.
+///
+/// Supported fallbacks:
+/// - HoldLast
+/// - Constant
+pub struct SanitizeBlock<T: Apply> {
+    buffer: T,
+    fault: bool,
+    initialized: bool,
+}
+
+impl<T: Apply> Default for SanitizeBlock<T> {
+    fn default() -> Self {
+        Self {
+            buffer: T::default(),
+            fault: false,
+            initialized: false,
+        }
+    }
+}
+
+impl<T: Apply> ProcessBlock for SanitizeBlock<T> {
+    type Inputs = T;
+    type Output = (T, bool);
+    type Parameters = Parameters<T::Float>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        if !self.initialized {
+            self.buffer = T::from_element(parameters.constant);
+            self.initialized = true;
+        }
+        self.fault = T::sanitize(input, parameters, &mut self.buffer);
+        (self.buffer.as_by(), self.fault)
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        (self.buffer.as_by(), self.fault)
+    }
+}
+
+fn sanitize_value<F: Float>(value: F, previous: &mut F, parameters: &Parameters<F>) -> bool {
+    let fault = !num_traits::Float::is_finite(value);
+    *previous = match (fault, parameters.fallback) {
+        (false, _) => value,
+        (true, SanitizeFallback::HoldLast) => *previous,
+        (true, SanitizeFallback::Constant) => parameters.constant,
+    };
+    fault
+}
+
+pub trait Apply: Pass + Default + Copy {
+    type Float: Float;
+
+    fn from_element(value: Self::Float) -> Self;
+
+    /// Writes the sanitized input into `previous`, returning true if any element was non-finite
+    fn sanitize(
+        input: PassBy<Self>,
+        parameters: &Parameters<Self::Float>,
+        previous: &mut Self,
+    ) -> bool;
+}
+
+impl<F: Float> Apply for F {
+    type Float = F;
+
+    fn from_element(value: Self::Float) -> Self {
+        value
+    }
+
+    fn sanitize(
+        input: PassBy<Self>,
+        parameters: &Parameters<Self::Float>,
+        previous: &mut Self,
+    ) -> bool {
+        sanitize_value(input, previous, parameters)
+    }
+}
+
+impl<const NROWS: usize, const NCOLS: usize, F: Float> Apply for Matrix<NROWS, NCOLS, F> {
+    type Float = F;
+
+    fn from_element(value: Self::Float) -> Self {
+        Matrix {
+            data: [[value; NROWS]; NCOLS],
+        }
+    }
+
+    fn sanitize(
+        input: PassBy<Self>,
+        parameters: &Parameters<Self::Float>,
+        previous: &mut Self,
+    ) -> bool {
+        let mut fault = false;
+        for (input_col, previous_col) in input.data.iter().zip(previous.data.iter_mut()) {
+            for (value, previous) in input_col.iter().zip(previous_col.iter_mut()) {
+                fault |= sanitize_value(*value, previous, parameters);
+            }
+        }
+        fault
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+
+    #[test]
+    fn test_sanitize_default_buffer() {
+        let block = SanitizeBlock::<f64>::default();
+        assert_eq!(block.buffer(), (0.0, false));
+    }
+
+    #[test]
+    fn test_sanitize_scalar_hold_last() {
+        let ctxt = StubContext::default();
+        let params = Parameters::new("HoldLast", -1.0);
+        let mut block = SanitizeBlock::<f64>::default();
+
+        // Nothing to hold yet, so the initial value is the constant
+        assert_eq!(block.process(&params, &ctxt, f64::NAN), (-1.0, true));
+        assert_eq!(block.process(&params, &ctxt, 2.5), (2.5, false));
+        assert_eq!(block.process(&params, &ctxt, f64::INFINITY), (2.5, true));
+        assert_eq!(
+            block.process(&params, &ctxt, f64::NEG_INFINITY),
+            (2.5, true)
+        );
+        assert_eq!(block.buffer(), (2.5, true));
+        assert_eq!(block.process(&params, &ctxt, 3.0), (3.0, false));
+    }
+
+    #[test]
+    fn test_sanitize_scalar_constant() {
+        let ctxt = StubContext::default();
+        let params = Parameters::new("Constant", 7.0f32);
+        let mut block = SanitizeBlock::<f32>::default();
+
+        assert_eq!(block.process(&params, &ctxt, 1.0), (1.0, false));
+        assert_eq!(block.process(&params, &ctxt, f32::NAN), (7.0, true));
+        assert_eq!(block.buffer(), (7.0, true));
+    }
+
+    #[test]
+    fn test_sanitize_matrix() {
+        let ctxt = StubContext::default();
+        let params = Parameters::new("HoldLast", 0.0);
+        let mut block = SanitizeBlock::<Matrix<2, 2, f64>>::default();
+
+        let input = Matrix {
+            data: [[1.0, 2.0], [3.0, 4.0]],
+        };
+        let (output, fault) = block.process(&params, &ctxt, &input);
+        assert_eq!(output, &input);
+        assert!(!fault);
+
+        let input = Matrix {
+            data: [[f64::NAN, 5.0], [6.0, f64::INFINITY]],
+        };
+        let (output, fault) = block.process(&params, &ctxt, &input);
+        assert_eq!(output.data, [[1.0, 5.0], [6.0, 4.0]]);
+        assert!(fault);
+
+        let params = Parameters::new("Constant", -1.0);
+        let (output, fault) = block.process(&params, &ctxt, &input);
+        assert_eq!(output.data, [[-1.0, 5.0], [6.0, -1.0]]);
+        assert!(fault);
+    }
+}
@@ -0,0 +1,221 @@
+use core::time::Duration;
+
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+use crate::{
+    stale_tracker::{duration_from_ms_f64, StaleTracker},
+    traits::Float,
+};
+
+/// Parameters for the ServoOutputBlock
+pub struct Parameters<F: Float, const N: usize> {
+    /// PWM frame rate in Hz, used to convert pulse widths to duty cycles
+    pub frequency: F,
+    /// Pulse width (microseconds) for a command of -1 on each channel
+    pub min_us: [F; N],
+    /// Pulse width (microseconds) for a command of 0 on each channel
+    pub center_us: [F; N],
+    /// Pulse width (microseconds) for a command of 1 on each channel
+    pub max_us: [F; N],
+    /// Whether to invert the command direction of each channel
+    pub reverse: [bool; N],
+    /// Normalized command output on each channel when the input is stale
+    pub failsafe: [F; N],
+    /// Time without a valid input after which the failsafe commands are output
+    stale_age: Duration,
+}
+
+impl<F: Float, const N: usize> Parameters<F, N> {
+    pub fn new(
+        frequency: F,
+        min_us: [F; N],
+        center_us: [F; N],
+        max_us: [F; N],
+        reverse: [bool; N],
+        failsafe: [F; N],
+        stale_age_ms: f64,
+    ) -> Self {
+        Self {
+            frequency,
+            min_us,
+            center_us,
+            max_us,
+            reverse,
+            failsafe,
+            stale_age: duration_from_ms_f64(stale_age_ms),
+        }
+    }
+
+    /// Pulse width in microseconds for a normalized command on a channel
+    fn pulse_width(&self, channel: usize, command: F) -> F {
+        let one = <F as num_traits::One>::one();
+        // NaN commands are treated as center
+        let command = if num_traits::Float::is_nan(command) {
+            <F as num_traits::Zero>::zero()
+        } else {
+            num_traits::Float::clamp(command, -one, one)
+        };
+        let command = if self.reverse[channel] {
+            -command
+        } else {
+            command
+        };
+        let center = self.center_us[channel];
+        if command >= <F as num_traits::Zero>::zero() {
+            center + command * (self.max_us[channel] - center)
+        } else {
+            center + command * (center - self.min_us[channel])
+        }
+    }
+}
+
+/// Maps normalized servo commands in [-1, 1] to PWM pulse widths using per-channel
+/// min/center/max/reverse calibration.
+///
+/// Inputs are `(commands, valid)`. Commands are only accepted on ticks where `valid` is true,
+/// and if no valid commands have been received within the stale age every channel outputs its
+/// failsafe command instead. Commands outside [-1, 1] are clamped, so the pulse width never
+/// leaves the calibrated range.
+///
+/// The output is a tuple of (pulse widths in microseconds, duty cycles at the configured frame
+/// rate, failsafe active). The duty cycles can be fed directly to the PWM output blocks.
+pub struct ServoOutputBlock<F: Float, const N: usize> {
+    commands: [F; N],
+    stale_check: StaleTracker,
+    buffer: (Matrix<1, N, F>, Matrix<1, N, F>, bool),
+}
+
+impl<F: Float, const N: usize> Default for ServoOutputBlock<F, N> {
+    fn default() -> Self {
+        Self {
+            commands: [F::default(); N],
+            stale_check: StaleTracker::default(),
+            buffer: (Matrix::zeroed(), Matrix::zeroed(), false),
+        }
+    }
+}
+
+impl<F: Float, const N: usize> ProcessBlock for ServoOutputBlock<F, N> {
+    type Inputs = (Matrix<1, N, F>, bool);
+    type Output = (Matrix<1, N, F>, Matrix<1, N, F>, bool);
+    type Parameters = Parameters<F, N>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (commands, valid) = inputs;
+        if valid {
+            self.commands = core::array::from_fn(|channel| commands.data[channel][0]);
+            self.stale_check.mark_updated(context.time());
+        }
+        let failsafe = !self
+            .stale_check
+            .is_valid(context.time(), parameters.stale_age);
+        let commands = if failsafe {
+            &parameters.failsafe
+        } else {
+            &self.commands
+        };
+
+        let us_per_second = F::from(1e6).expect("1e6 fits in float");
+        let (pulse_widths, duty_cycles, failsafe_active) = &mut self.buffer;
+        for (channel, command) in commands.iter().enumerate() {
+            let pulse_width = parameters.pulse_width(channel, *command);
+            pulse_widths.data[channel][0] = pulse_width;
+            duty_cycles.data[channel][0] = pulse_width * parameters.frequency / us_per_second;
+        }
+        *failsafe_active = failsafe;
+
+        (self.buffer.0.as_by(), self.buffer.1.as_by(), self.buffer.2)
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        (self.buffer.0.as_by(), self.buffer.1.as_by(), self.buffer.2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+    use approx::assert_relative_eq;
+
+    fn params() -> Parameters<f64, 2> {
+        Parameters::new(
+            50.0,
+            [1000.0, 1100.0],
+            [1500.0, 1400.0],
+            [2000.0, 1900.0],
+            [false, true],
+            [0.0, -1.0],
+            250.0,
+        )
+    }
+
+    #[test]
+    fn test_servo_default_buffer_no_panic() {
+        let block = ServoOutputBlock::<f64, 2>::default();
+        assert_eq!(
+            block.buffer(),
+            (&Matrix::zeroed(), &Matrix::zeroed(), false)
+        );
+    }
+
+    #[test]
+    fn test_servo_calibration() {
+        let runtime = StubRuntime::default();
+        let params = params();
+        let mut block = ServoOutputBlock::<f64, 2>::default();
+
+        let mut check = |commands: [f64; 2], expected_us: [f64; 2]| {
+            let input = Matrix {
+                data: [[commands[0]], [commands[1]]],
+            };
+            let (pulse_widths, duty_cycles, failsafe) =
+                block.process(&params, &runtime.context(), (&input, true));
+            assert!(!failsafe);
+            for (channel, expected) in expected_us.iter().enumerate() {
+                assert_relative_eq!(pulse_widths.data[channel][0], *expected);
+                assert_relative_eq!(duty_cycles.data[channel][0], expected / 20000.0);
+            }
+        };
+
+        // The second channel is reversed and has an asymmetric range around its center
+        check([0.0, 0.0], [1500.0, 1400.0]);
+        check([1.0, 1.0], [2000.0, 1100.0]);
+        check([-0.5, -0.5], [1250.0, 1650.0]);
+        check([3.0, f64::NAN], [2000.0, 1400.0]);
+    }
+
+    #[test]
+    fn test_servo_failsafe() {
+        let mut runtime = StubRuntime::default();
+        let params = params();
+        let mut block = ServoOutputBlock::<f64, 2>::default();
+        let input = Matrix {
+            data: [[1.0], [1.0]],
+        };
+
+        // Failsafe until the first valid command
+        let (pulse_widths, _, failsafe) =
+            block.process(&params, &runtime.context(), (&input, false));
+        assert!(failsafe);
+        assert_eq!(pulse_widths.data, [[1500.0], [1900.0]]);
+
+        block.process(&params, &runtime.context(), (&input, true));
+        // Invalid inputs are ignored, and the last valid command is held until it goes stale
+        let other = Matrix::zeroed();
+        runtime.tick_n(2);
+        let (pulse_widths, _, failsafe) =
+            block.process(&params, &runtime.context(), (&other, false));
+        assert!(!failsafe);
+        assert_eq!(pulse_widths.data, [[2000.0], [1100.0]]);
+
+        runtime.tick();
+        let (_, _, failsafe) = block.process(&params, &runtime.context(), (&other, false));
+        assert!(failsafe);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::fast_math::FastMath;
 use crate::traits::Float;
 use pictorus_traits::{GeneratorBlock, PassBy};
 
@@ -40,7 +41,7 @@ where
     ) -> pictorus_traits::PassBy<'_, Self::Output> {
         let time = T::from_duration(context.time());
         let sin_val = parameters.amplitude
-            * num_traits::Float::sin(parameters.frequency * time + parameters.phase)
+            * FastMath::fast_sin(parameters.frequency * time + parameters.phase)
             + parameters.bias;
         self.buffer = sin_val;
         sin_val
@@ -74,7 +75,8 @@ impl<T: Float> Parameters<T> {
 mod tests {
     use super::*;
 
-    use crate::testing::StubContext;
+    use crate::testing::{fast_math_epsilon, StubContext};
+    use approx::assert_relative_eq;
     use core::time::Duration;
     use num_traits::Float;
 
@@ -96,11 +98,24 @@ mod tests {
 
         let mut context = StubContext::default();
 
-        assert_eq!(block.generate(&parameters, &context), Float::sin(0.5));
-        assert_eq!(block.buffer(), Float::sin(0.5));
+        let epsilon = fast_math_epsilon(0.0);
+        let output = block.generate(&parameters, &context);
+        assert_relative_eq!(
+            output,
+            Float::sin(0.5),
+            epsilon = epsilon,
+            max_relative = 0.0
+        );
+        assert_eq!(block.buffer(), output);
         context.time = Duration::from_secs(1);
 
-        assert_eq!(block.generate(&parameters, &context), Float::sin(1.5));
-        assert_eq!(block.buffer(), Float::sin(1.5));
+        let output = block.generate(&parameters, &context);
+        assert_relative_eq!(
+            output,
+            Float::sin(1.5),
+            epsilon = epsilon,
+            max_relative = 0.0
+        );
+        assert_eq!(block.buffer(), output);
     }
 }
@@ -95,15 +95,15 @@ where
             self.buffer = parameters.initial_condition;
         }
 
-        self.memory
-            .push_back(input)
-            .expect("SlidingWindowBlock VecDeque is full");
-
-        for (i, value) in self.memory.iter().enumerate() {
-            // Until the Deque is full adjust the index or the
-            // output will fill left to right instead of right to left
-            let i = i + (N - self.memory.len());
-            self.buffer.data.as_flattened_mut()[i] = *value;
+        // The oldest sample is popped once the window fills, so there's always room
+        let _ = self.memory.push_back(input);
+
+        // Until the Deque is full skip the leading slots or the
+        // output will fill left to right instead of right to left
+        let skip = N.saturating_sub(self.memory.len());
+        let slots = self.buffer.data.as_flattened_mut().iter_mut().skip(skip);
+        for (slot, value) in slots.zip(self.memory.iter()) {
+            *slot = *value;
         }
 
         if self.memory.len() == N {
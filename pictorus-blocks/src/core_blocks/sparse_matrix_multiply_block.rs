@@ -0,0 +1,230 @@
+use pictorus_traits::{Matrix, PassBy, ProcessBlock};
+
+use crate::traits::Float;
+use crate::ParameterError;
+
+/// A `NROWS` x `NCOLS` sparse matrix in compressed sparse row (CSR) form, borrowing its arrays so
+/// they can live in flash as `static`s.
+///
+/// Row `r` holds the nonzero elements `values[row_ptr[r]..row_ptr[r + 1]]`, which are in the
+/// columns given by the same range of `col_indices`.
+#[derive(Debug, Clone, Copy)]
+pub struct CsrMatrix<T: 'static, const NROWS: usize, const NCOLS: usize> {
+    values: &'static [T],
+    col_indices: &'static [u16],
+    row_ptr: &'static [u16],
+}
+
+impl<T, const NROWS: usize, const NCOLS: usize> CsrMatrix<T, NROWS, NCOLS> {
+    /// Panics if the arrays aren't a valid CSR matrix, which fails the build when the matrix is
+    /// constructed in a `static` or `const`.
+    pub const fn new(
+        values: &'static [T],
+        col_indices: &'static [u16],
+        row_ptr: &'static [u16],
+    ) -> Self {
+        match Self::try_new(values, col_indices, row_ptr) {
+            Ok(matrix) => matrix,
+            Err(err) => panic!("{}", err.0),
+        }
+    }
+
+    /// Fallible version of [`CsrMatrix::new`], for builds where panics are unacceptable
+    pub const fn try_new(
+        values: &'static [T],
+        col_indices: &'static [u16],
+        row_ptr: &'static [u16],
+    ) -> Result<Self, ParameterError> {
+        if row_ptr.len() != NROWS + 1 || row_ptr[0] != 0 {
+            return Err(ParameterError(
+                "CSR row pointers must start at 0 and have one entry per row plus one",
+            ));
+        }
+        if values.len() != col_indices.len() || row_ptr[NROWS] as usize != values.len() {
+            return Err(ParameterError(
+                "CSR values and column indices must have one entry per nonzero element",
+            ));
+        }
+        let mut row = 0;
+        while row < NROWS {
+            if row_ptr[row] > row_ptr[row + 1] {
+                return Err(ParameterError("CSR row pointers must be non-decreasing"));
+            }
+            row += 1;
+        }
+        let mut i = 0;
+        while i < col_indices.len() {
+            if col_indices[i] as usize >= NCOLS {
+                return Err(ParameterError("CSR column index out of range"));
+            }
+            i += 1;
+        }
+        Ok(Self {
+            values,
+            col_indices,
+            row_ptr,
+        })
+    }
+
+    /// Number of stored (nonzero) elements
+    pub const fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// The nonzero elements of `row` and their columns
+    fn row(&self, row: usize) -> impl Iterator<Item = (usize, &T)> {
+        // `try_new` checked the ranges, but they go through `get` so a lookup can't panic
+        let ptr = |row: usize| self.row_ptr.get(row).map_or(0, |ptr| *ptr as usize);
+        let range = ptr(row)..ptr(row + 1);
+        let col_indices = self.col_indices.get(range.clone()).unwrap_or_default();
+        let values = self.values.get(range).unwrap_or_default();
+        col_indices.iter().map(|col| *col as usize).zip(values)
+    }
+}
+
+/// Parameters for the SparseMatrixMultiplyBlock
+pub struct Parameters<T: 'static, const NROWS: usize, const NCOLS: usize> {
+    pub matrix: CsrMatrix<T, NROWS, NCOLS>,
+}
+
+impl<T, const NROWS: usize, const NCOLS: usize> Parameters<T, NROWS, NCOLS> {
+    pub const fn new(matrix: CsrMatrix<T, NROWS, NCOLS>) -> Self {
+        Self { matrix }
+    }
+}
+
+/// Multiplies the input by a constant sparse matrix: `output = matrix * input`.
+///
+/// Large gain or selection matrices are often mostly zeros. Storing only the nonzero elements
+/// (see [`CsrMatrix`]) saves flash, and skipping the zeros saves cycles: each output column costs
+/// one multiply-add per nonzero element rather than `NROWS * NCOLS`.
+///
+/// The input has `NCOLS` rows and any number of columns `K`; the output has `NROWS` rows and `K`
+/// columns.
+pub struct SparseMatrixMultiplyBlock<
+    T: Float,
+    const NROWS: usize,
+    const NCOLS: usize,
+    const K: usize = 1,
+> {
+    buffer: Matrix<NROWS, K, T>,
+}
+
+impl<T: Float, const NROWS: usize, const NCOLS: usize, const K: usize> Default
+    for SparseMatrixMultiplyBlock<T, NROWS, NCOLS, K>
+{
+    fn default() -> Self {
+        Self {
+            buffer: Matrix::zeroed(),
+        }
+    }
+}
+
+impl<T: Float, const NROWS: usize, const NCOLS: usize, const K: usize> ProcessBlock
+    for SparseMatrixMultiplyBlock<T, NROWS, NCOLS, K>
+{
+    type Inputs = Matrix<NCOLS, K, T>;
+    type Output = Matrix<NROWS, K, T>;
+    type Parameters = Parameters<T, NROWS, NCOLS>;
+
+    fn process(
+        &mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<Self::Inputs>,
+    ) -> PassBy<'_, Self::Output> {
+        for (output, input) in self.buffer.data.iter_mut().zip(&input.data) {
+            for (row, output) in output.iter_mut().enumerate() {
+                *output = parameters
+                    .matrix
+                    .row(row)
+                    .fold(T::zero(), |acc, (col, value)| {
+                        acc + input.get(col).map_or(T::zero(), |input| *value * *input)
+                    });
+            }
+        }
+        &self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        &self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+
+    // [[1, 0, 0, 2],
+    //  [0, 0, 0, 0],
+    //  [0, 3, 4, 0]]
+    static PARAMETERS: Parameters<f64, 3, 4> = Parameters::new(CsrMatrix::new(
+        &[1.0, 2.0, 3.0, 4.0],
+        &[0, 3, 1, 2],
+        &[0, 2, 2, 4],
+    ));
+
+    #[test]
+    fn test_sparse_multiply_default_buffer_no_panic() {
+        let block = SparseMatrixMultiplyBlock::<f64, 3, 4>::default();
+        assert_eq!(block.buffer(), &Matrix::zeroed());
+    }
+
+    #[test]
+    fn test_sparse_multiply_vector() {
+        let context = StubContext::default();
+        let mut block = SparseMatrixMultiplyBlock::<f64, 3, 4>::default();
+        let input = Matrix {
+            data: [[1.0, 2.0, 3.0, 4.0]],
+        };
+        let output = block.process(&PARAMETERS, &context, &input);
+        assert_eq!(output.data, [[9.0, 0.0, 18.0]]);
+        assert_eq!(block.buffer().data, [[9.0, 0.0, 18.0]]);
+        assert_eq!(PARAMETERS.matrix.nnz(), 4);
+    }
+
+    #[test]
+    fn test_sparse_multiply_matches_dense() {
+        let context = StubContext::default();
+        let mut block = SparseMatrixMultiplyBlock::<f32, 3, 4, 2>::default();
+        let parameters = Parameters::new(CsrMatrix::new(
+            &[1.0, 2.0, 3.0, 4.0],
+            &[0, 3, 1, 2],
+            &[0, 2, 2, 4],
+        ));
+        let dense = nalgebra::Matrix3x4::new(
+            1.0, 0.0, 0.0, 2.0, //
+            0.0, 0.0, 0.0, 0.0, //
+            0.0, 3.0, 4.0, 0.0,
+        );
+        let input = Matrix {
+            data: [[0.5, -1.0, 2.0, 0.0], [1.0, 1.0, 1.0, 1.0]],
+        };
+        let expected = dense * nalgebra::Matrix4x2::from_column_slice(input.data.as_flattened());
+
+        let output = block.process(&parameters, &context, &input);
+        assert_eq!(output.data.as_flattened(), expected.as_slice());
+    }
+
+    #[test]
+    fn test_csr_matrix_validation() {
+        assert!(CsrMatrix::<f64, 2, 2>::try_new(&[1.0], &[0], &[0, 1, 1]).is_ok());
+        // Wrong number of row pointers
+        assert!(CsrMatrix::<f64, 2, 2>::try_new(&[1.0], &[0], &[0, 1]).is_err());
+        // Row pointers don't start at 0
+        assert!(CsrMatrix::<f64, 2, 2>::try_new(&[1.0], &[0], &[1, 1, 1]).is_err());
+        // Decreasing row pointers
+        assert!(CsrMatrix::<f64, 2, 2>::try_new(&[1.0, 2.0], &[0, 1], &[0, 2, 1]).is_err());
+        // Fewer column indices than values
+        assert!(CsrMatrix::<f64, 2, 2>::try_new(&[1.0, 2.0], &[0], &[0, 1, 2]).is_err());
+        // Column out of range
+        assert!(CsrMatrix::<f64, 2, 2>::try_new(&[1.0], &[2], &[0, 1, 1]).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "CSR column index out of range")]
+    fn test_csr_matrix_new_panics() {
+        CsrMatrix::<f64, 1, 1>::new(&[1.0], &[1], &[0, 1]);
+    }
+}
@@ -0,0 +1,338 @@
+use crate::dsp::DspFloat;
+use crate::ParameterError;
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+/// The window applied to each segment before the FFT
+#[derive(Clone, Copy, Debug, PartialEq, strum::EnumString)]
+pub enum Window {
+    Rectangular,
+    Hann,
+    Hamming,
+    Blackman,
+}
+
+impl Window {
+    /// Periodic window coefficient `n` of `len`, which is the form used for spectral analysis
+    fn coefficient<T: DspFloat>(&self, n: usize, len: usize) -> T {
+        let cast = |value: f64| {
+            <T as num_traits::NumCast>::from(value).expect("Window value fits in float")
+        };
+        let x = core::f64::consts::TAU * n as f64 / len as f64;
+        let value = match self {
+            Window::Rectangular => 1.0,
+            Window::Hann => 0.5 - 0.5 * num_traits::Float::cos(x),
+            Window::Hamming => 0.54 - 0.46 * num_traits::Float::cos(x),
+            Window::Blackman => {
+                0.42 - 0.5 * num_traits::Float::cos(x) + 0.08 * num_traits::Float::cos(2.0 * x)
+            }
+        };
+        cast(value)
+    }
+}
+
+/// The quantity output for each frequency bin
+#[derive(Clone, Copy, Debug, PartialEq, strum::EnumString)]
+pub enum SpectrumOutput {
+    /// Single-sided amplitude, in the units of the input. A sinusoid centered on a bin
+    /// reads as its peak amplitude.
+    Magnitude,
+    /// The square of the `Magnitude` output
+    Power,
+}
+
+/// Parameters for the spectrum block
+pub struct Parameters<T: DspFloat, const N: usize> {
+    pub window: Window,
+    pub output: SpectrumOutput,
+    /// Number of new samples between successive segments. Values of zero or greater
+    /// than `N` are treated as `N` (no overlap).
+    pub hop: usize,
+    /// Number of segments averaged (Welch's method) for each output. Zero and one disable averaging.
+    pub averages: usize,
+    coefficients: [T; N],
+    coefficient_sum: T,
+}
+
+impl<T: DspFloat, const N: usize> Parameters<T, N> {
+    pub fn new(window: &str, output: &str, hop: usize, averages: usize) -> Self {
+        Self::try_new(window, output, hop, averages).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(
+        window: &str,
+        output: &str,
+        hop: usize,
+        averages: usize,
+    ) -> Result<Self, ParameterError> {
+        let window = window
+            .parse()
+            .map_err(|_| ParameterError("Failed to parse spectrum window."))?;
+        let output = output
+            .parse()
+            .map_err(|_| ParameterError("Failed to parse spectrum output."))?;
+        Ok(Self::from_window(window, output, hop, averages))
+    }
+
+    /// Builds the parameters from an already-resolved window and output, without parsing
+    pub fn from_window(
+        window: Window,
+        output: SpectrumOutput,
+        hop: usize,
+        averages: usize,
+    ) -> Self {
+        let coefficients: [T; N] = core::array::from_fn(|n| window.coefficient(n, N));
+        let coefficient_sum = coefficients
+            .iter()
+            .fold(<T as num_traits::Zero>::zero(), |acc, w| acc + *w);
+        Self {
+            window,
+            output,
+            hop,
+            averages,
+            coefficients,
+            coefficient_sum,
+        }
+    }
+
+    fn hop(&self) -> usize {
+        if self.hop == 0 || self.hop > N {
+            N
+        } else {
+            self.hop
+        }
+    }
+}
+
+/// Computes the windowed, single-sided spectrum of the last `N` input samples.
+///
+/// `BINS` must be `N / 2 + 1`; bin `k` corresponds to a frequency of `k / (N * timestep)`.
+/// Once `N` samples have been collected a new segment is transformed every `hop` samples.
+/// When averaging is enabled the power of `averages` consecutive segments is averaged (Welch's method)
+/// and the output only updates once all of them have been collected. The output holds its
+/// previous value between updates.
+///
+/// Supported windows:
+/// - Rectangular
+/// - Hann
+/// - Hamming
+/// - Blackman
+///
+/// Supported outputs:
+/// - Magnitude
+/// - Power
+pub struct SpectrumBlock<T: DspFloat, const N: usize, const BINS: usize> {
+    /// Ring buffer of the last `N` samples
+    samples: [T; N],
+    /// Index of the oldest sample, which is also where the next sample is written
+    sample_index: usize,
+    /// Samples received since the last segment was transformed, saturating at `N`
+    pending: usize,
+    filled: bool,
+    /// Sum of the power of each averaged segment so far
+    power_sum: [T; BINS],
+    segments: usize,
+    output: Matrix<1, BINS, T>,
+}
+
+impl<T: DspFloat, const N: usize, const BINS: usize> SpectrumBlock<T, N, BINS> {
+    const VALID_BINS: () = assert!(
+        BINS == N / 2 + 1,
+        "SpectrumBlock requires BINS == N / 2 + 1"
+    );
+
+    fn transform(&self, parameters: &Parameters<T, N>) -> [T; BINS] {
+        let mut frame = [T::default(); N];
+        for (n, value) in frame.iter_mut().enumerate() {
+            *value = self.samples[(self.sample_index + n) % N] * parameters.coefficients[n];
+        }
+        let mut re = [T::default(); N];
+        let mut im = [T::default(); N];
+        T::real_fft(&frame, &mut re, &mut im);
+
+        // Bins other than DC and Nyquist also hold the energy of their negative frequency mirror
+        let one = <T as num_traits::One>::one();
+        let two = one + one;
+        let mut power = [T::default(); BINS];
+        for (k, value) in power.iter_mut().enumerate() {
+            let fold = if k == 0 || 2 * k == N { one } else { two };
+            let amplitude =
+                fold * num_traits::Float::hypot(re[k], im[k]) / parameters.coefficient_sum;
+            *value = amplitude * amplitude;
+        }
+        power
+    }
+}
+
+impl<T: DspFloat, const N: usize, const BINS: usize> Default for SpectrumBlock<T, N, BINS> {
+    fn default() -> Self {
+        let () = Self::VALID_BINS;
+        Self {
+            samples: [T::default(); N],
+            sample_index: 0,
+            pending: 0,
+            filled: false,
+            power_sum: [T::default(); BINS],
+            segments: 0,
+            output: Matrix::zeroed(),
+        }
+    }
+}
+
+impl<T: DspFloat, const N: usize, const BINS: usize> ProcessBlock for SpectrumBlock<T, N, BINS> {
+    type Inputs = T;
+    type Output = Matrix<1, BINS, T>;
+    type Parameters = Parameters<T, N>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        if let Some(sample) = self.samples.get_mut(self.sample_index) {
+            *sample = input;
+        }
+        self.sample_index = (self.sample_index + 1) % N;
+        self.pending = (self.pending + 1).min(N);
+        self.filled |= self.sample_index == 0;
+
+        if !self.filled || self.pending < parameters.hop() {
+            return self.output.as_by();
+        }
+        self.pending = 0;
+
+        let power = self.transform(parameters);
+        self.power_sum
+            .iter_mut()
+            .zip(power)
+            .for_each(|(sum, value)| *sum += value);
+        self.segments += 1;
+        if self.segments < parameters.averages.max(1) {
+            return self.output.as_by();
+        }
+
+        let segments =
+            <T as num_traits::NumCast>::from(self.segments).expect("Segment count fits in float");
+        for (output, sum) in self.output.data.iter_mut().zip(self.power_sum.iter_mut()) {
+            let power = *sum / segments;
+            output[0] = match parameters.output {
+                SpectrumOutput::Magnitude => num_traits::Float::sqrt(power),
+                SpectrumOutput::Power => power,
+            };
+            *sum = T::default();
+        }
+        self.segments = 0;
+
+        self.output.as_by()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.output.as_by()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use approx::assert_relative_eq;
+
+    /// A sinusoid with `cycles` periods per `n` samples, plus an offset
+    fn tone(t: usize, n: usize, cycles: f64, amplitude: f64, offset: f64) -> f64 {
+        offset + amplitude * (core::f64::consts::TAU * cycles * t as f64 / n as f64).sin()
+    }
+
+    #[test]
+    fn test_spectrum_default_buffer() {
+        let block = SpectrumBlock::<f64, 8, 5>::default();
+        assert_eq!(block.buffer(), &Matrix::zeroed());
+    }
+
+    #[test]
+    fn test_window_coefficients() {
+        let hann = Parameters::<f64, 4>::new("Hann", "Magnitude", 0, 0);
+        assert_eq!(hann.window, Window::Hann);
+        for (actual, expected) in hann.coefficients.iter().zip([0.0, 0.5, 1.0, 0.5]) {
+            assert_relative_eq!(*actual, expected, epsilon = 1e-12);
+        }
+        assert_relative_eq!(hann.coefficient_sum, 2.0, epsilon = 1e-12);
+
+        let hamming = Parameters::<f64, 4>::new("Hamming", "Magnitude", 0, 0);
+        for (actual, expected) in hamming.coefficients.iter().zip([0.08, 0.54, 1.0, 0.54]) {
+            assert_relative_eq!(*actual, expected, epsilon = 1e-12);
+        }
+
+        let blackman = Parameters::<f64, 4>::new("Blackman", "Magnitude", 0, 0);
+        for (actual, expected) in blackman.coefficients.iter().zip([0.0, 0.34, 1.0, 0.34]) {
+            assert_relative_eq!(*actual, expected, epsilon = 1e-12);
+        }
+
+        let rectangular = Parameters::<f32, 4>::new("Rectangular", "Power", 0, 0);
+        assert_eq!(rectangular.coefficients, [1.0; 4]);
+        assert_eq!(rectangular.output, SpectrumOutput::Power);
+    }
+
+    #[test]
+    fn test_spectrum_recovers_amplitude() {
+        let ctxt = StubContext::default();
+        for window in ["Rectangular", "Hann", "Hamming", "Blackman"] {
+            let params = Parameters::<f64, 64>::new(window, "Magnitude", 0, 0);
+            let mut block = SpectrumBlock::<f64, 64, 33>::default();
+            for t in 0..63 {
+                block.process(&params, &ctxt, tone(t, 64, 8.0, 3.0, 1.5));
+            }
+            // Nothing is output until the first segment is full
+            assert_eq!(block.buffer(), &Matrix::zeroed());
+
+            let output = block.process(&params, &ctxt, tone(63, 64, 8.0, 3.0, 1.5));
+            assert_relative_eq!(output.data[0][0], 1.5, epsilon = 1e-9);
+            assert_relative_eq!(output.data[8][0], 3.0, epsilon = 1e-9);
+            // Far from the tone, all of the windows have negligible leakage
+            assert_relative_eq!(output.data[20][0], 0.0, epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_spectrum_power_and_nyquist() {
+        let ctxt = StubContext::default();
+        let params = Parameters::<f32, 8>::new("Rectangular", "Power", 0, 0);
+        let mut block = SpectrumBlock::<f32, 8, 5>::default();
+        let mut output = Matrix::zeroed();
+        for t in 0..8 {
+            // Alternating +/-2 sits entirely in the Nyquist bin
+            let value = if t % 2 == 0 { 2.0 } else { -2.0 };
+            output = *block.process(&params, &ctxt, value);
+        }
+        assert_relative_eq!(output.data[4][0], 4.0, epsilon = 1e-5);
+        assert_relative_eq!(output.data[0][0], 0.0, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn test_spectrum_hop_and_averaging() {
+        let ctxt = StubContext::default();
+        let params = Parameters::<f64, 16>::new("Hann", "Magnitude", 4, 3);
+        let mut block = SpectrumBlock::<f64, 16, 9>::default();
+
+        // The first segment completes at sample 16, then one every 4 samples after that.
+        // Averaging 3 segments means the first output arrives at sample 24. A ramp input
+        // changes the spectrum of every segment, so each update is visible.
+        let mut updates = heapless::Vec::<usize, 8>::new();
+        let mut previous = Matrix::zeroed();
+        for t in 0..40 {
+            let output = *block.process(&params, &ctxt, t as f64);
+            if output != previous {
+                updates.push(t + 1).unwrap();
+                previous = output;
+            }
+        }
+        assert_eq!(updates.as_slice(), &[24, 36]);
+
+        // A stationary tone has the same power in every segment as the average
+        let mut block = SpectrumBlock::<f64, 16, 9>::default();
+        for t in 0..24 {
+            block.process(&params, &ctxt, tone(t, 16, 2.0, 1.0, 0.0));
+        }
+        assert_relative_eq!(block.buffer().data[2][0], 1.0, epsilon = 1e-9);
+    }
+}
@@ -0,0 +1,346 @@
+use crate::traits::Float;
+use crate::ParameterError;
+use core::cmp::Ordering;
+use core::marker::PhantomData;
+use pictorus_traits::{Context, Matrix, Pass, PassBy, ProcessBlock};
+
+#[derive(strum::EnumString, Clone, Copy, Debug, PartialEq)]
+/// How waypoints are joined
+pub enum SplineMethod {
+    /// Natural cubic spline: continuous position, velocity and acceleration through every
+    /// waypoint, with zero acceleration at the ends. Passes through waypoints without stopping.
+    NaturalCubic,
+    /// Minimum jerk (quintic) segments that start and end each at rest, i.e. with zero velocity
+    /// and acceleration at every waypoint. Suited to point-to-point moves.
+    MinimumJerk,
+}
+
+/// Parameters for the SplineBlock
+pub struct Parameters<T: Float, const N: usize, const D: usize> {
+    pub method: SplineMethod,
+    /// Times (or other parameter values) of the waypoints, strictly increasing
+    pub knots: [T; N],
+    /// Waypoints, one row per knot and one column per dimension
+    pub waypoints: Matrix<N, D, T>,
+    /// Second derivatives of the natural cubic spline at each knot
+    second_derivatives: Matrix<N, D, T>,
+}
+
+impl<T: Float, const N: usize, const D: usize> Parameters<T, N, D> {
+    pub fn new(method: &str, knots: [T; N], waypoints: Matrix<N, D, T>) -> Self {
+        Self::try_new(method, knots, waypoints).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(
+        method: &str,
+        knots: [T; N],
+        waypoints: Matrix<N, D, T>,
+    ) -> Result<Self, ParameterError> {
+        let method = method
+            .parse()
+            .map_err(|_| ParameterError("Invalid spline method"))?;
+        Self::try_from_method(method, knots, waypoints)
+    }
+
+    /// Like [`Parameters::new`], but takes an already-resolved method
+    pub fn from_method(method: SplineMethod, knots: [T; N], waypoints: Matrix<N, D, T>) -> Self {
+        Self::try_from_method(method, knots, waypoints).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::from_method`], for builds where panics are unacceptable
+    pub fn try_from_method(
+        method: SplineMethod,
+        knots: [T; N],
+        waypoints: Matrix<N, D, T>,
+    ) -> Result<Self, ParameterError> {
+        if N < 2 {
+            return Err(ParameterError("A spline needs at least two waypoints"));
+        }
+        if knots
+            .windows(2)
+            .any(|pair| pair[1].partial_cmp(&pair[0]) != Some(Ordering::Greater))
+        {
+            return Err(ParameterError("Spline knots must be strictly increasing"));
+        }
+
+        let mut second_derivatives = Matrix::zeroed();
+        for (y, m) in waypoints
+            .data
+            .iter()
+            .zip(second_derivatives.data.iter_mut())
+        {
+            natural_second_derivatives(&knots, y, m);
+        }
+        Ok(Self {
+            method,
+            knots,
+            waypoints,
+            second_derivatives,
+        })
+    }
+}
+
+/// Solves the tridiagonal system for the second derivatives `m` of the natural cubic spline
+/// through `(knots, y)`, with the Thomas algorithm
+fn natural_second_derivatives<T: Float, const N: usize>(
+    knots: &[T; N],
+    y: &[T; N],
+    m: &mut [T; N],
+) {
+    let two = T::from(2.0).unwrap();
+    let six = T::from(6.0).unwrap();
+    // Forward elimination, leaving the upper diagonal coefficients in `c` and the right hand
+    // side in `m`. The first and last rows are m = 0.
+    let mut c = [T::zero(); N];
+    for i in 1..N - 1 {
+        let h0 = knots[i] - knots[i - 1];
+        let h1 = knots[i + 1] - knots[i];
+        let rhs = six * ((y[i + 1] - y[i]) / h1 - (y[i] - y[i - 1]) / h0);
+        let pivot = two * (h0 + h1) - h0 * c[i - 1];
+        c[i] = h1 / pivot;
+        m[i] = (rhs - h0 * m[i - 1]) / pivot;
+    }
+    for i in (1..N - 1).rev() {
+        let next = m[i + 1];
+        m[i] -= c[i] * next;
+    }
+}
+
+/// What the spline is evaluated at: the app time (for `()` inputs) or the input value
+pub trait Parameterization<T: Float>: Pass {
+    fn parameter(input: PassBy<Self>, context: &dyn Context) -> T;
+}
+
+impl<T: Float> Parameterization<T> for () {
+    fn parameter(_input: PassBy<Self>, context: &dyn Context) -> T {
+        T::from_duration(context.time())
+    }
+}
+
+impl<T: Float> Parameterization<T> for T {
+    fn parameter(input: PassBy<Self>, _context: &dyn Context) -> T {
+        input
+    }
+}
+
+/// Evaluates a spline through waypoints, for smooth reference trajectories in motion systems.
+///
+/// The spline is evaluated at the app time when the block has no input (`I = ()`), or at its
+/// scalar input otherwise, e.g. a path parameter or a time scaled by a speed override. The
+/// outputs are the position, velocity and acceleration (derivatives with respect to the
+/// parameter), each with one column per dimension `D`. Before the first knot and after the last
+/// the block holds the end waypoint with zero velocity and acceleration.
+///
+/// See [`SplineMethod`] for the available spline types.
+pub struct SplineBlock<T: Float, const N: usize, const D: usize = 1, I = ()>
+where
+    I: Parameterization<T>,
+{
+    buffer: (Matrix<1, D, T>, Matrix<1, D, T>, Matrix<1, D, T>),
+    _input: PhantomData<I>,
+}
+
+impl<T: Float, const N: usize, const D: usize, I: Parameterization<T>> Default
+    for SplineBlock<T, N, D, I>
+{
+    fn default() -> Self {
+        Self {
+            buffer: (Matrix::zeroed(), Matrix::zeroed(), Matrix::zeroed()),
+            _input: PhantomData,
+        }
+    }
+}
+
+impl<T: Float, const N: usize, const D: usize, I: Parameterization<T>> ProcessBlock
+    for SplineBlock<T, N, D, I>
+{
+    type Inputs = I;
+    type Output = (Matrix<1, D, T>, Matrix<1, D, T>, Matrix<1, D, T>);
+    type Parameters = Parameters<T, N, D>;
+
+    fn process(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+        inputs: PassBy<Self::Inputs>,
+    ) -> PassBy<'_, Self::Output> {
+        let t = I::parameter(inputs, context);
+        let knots = &parameters.knots;
+        let (position, velocity, acceleration) = &mut self.buffer;
+        *velocity = Matrix::zeroed();
+        *acceleration = Matrix::zeroed();
+
+        // NaN parameters hold the start too
+        let end = if t.is_nan() || t <= knots[0] {
+            Some(0)
+        } else if t >= knots[N - 1] {
+            Some(N - 1)
+        } else {
+            None
+        };
+        if let Some(end) = end {
+            for (output, waypoints) in position.data.iter_mut().zip(&parameters.waypoints.data) {
+                output[0] = waypoints[end];
+            }
+            return self.buffer.as_by();
+        }
+
+        // Segment [knots[i], knots[i + 1]] containing t
+        let i = knots[..N - 1].iter().rposition(|k| *k <= t).unwrap_or(0);
+        let h = knots[i + 1] - knots[i];
+        let (a, b) = (knots[i + 1] - t, t - knots[i]);
+        let dims = parameters
+            .waypoints
+            .data
+            .iter()
+            .zip(&parameters.second_derivatives.data)
+            .zip(position.data.iter_mut())
+            .zip(velocity.data.iter_mut())
+            .zip(acceleration.data.iter_mut());
+        for ((((y, m), p), v), acc) in dims {
+            let (y0, y1) = (y[i], y[i + 1]);
+            let (p, v, acc) = (&mut p[0], &mut v[0], &mut acc[0]);
+            match parameters.method {
+                SplineMethod::NaturalCubic => {
+                    let (m0, m1) = (m[i], m[i + 1]);
+                    let two = T::from(2.0).unwrap();
+                    let six = T::from(6.0).unwrap();
+                    let c0 = y0 / h - m0 * h / six;
+                    let c1 = y1 / h - m1 * h / six;
+                    *p = (m0 * a * a * a + m1 * b * b * b) / (six * h) + c0 * a + c1 * b;
+                    *v = (m1 * b * b - m0 * a * a) / (two * h) + c1 - c0;
+                    *acc = (m0 * a + m1 * b) / h;
+                }
+                SplineMethod::MinimumJerk => {
+                    let s = b / h;
+                    let (s2, s3) = (s * s, s * s * s);
+                    let c = |x: f64| T::from(x).unwrap();
+                    let delta = y1 - y0;
+                    *p = y0 + delta * s3 * (c(10.0) - c(15.0) * s + c(6.0) * s2);
+                    *v = delta / h * s2 * (c(30.0) - c(60.0) * s + c(30.0) * s2);
+                    *acc = delta / (h * h) * s * (c(60.0) - c(180.0) * s + c(120.0) * s2);
+                }
+            }
+        }
+        self.buffer.as_by()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer.as_by()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{StubContext, StubRuntime};
+    use approx::assert_relative_eq;
+    use core::time::Duration;
+
+    #[test]
+    fn test_spline_default_buffer_no_panic() {
+        let block = SplineBlock::<f64, 3, 2>::default();
+        assert_eq!(block.buffer().0, &Matrix::zeroed());
+    }
+
+    #[test]
+    fn test_natural_cubic_through_waypoints() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(
+            "NaturalCubic",
+            [0.0, 1.0, 3.0, 4.0],
+            Matrix {
+                data: [[0.0, 2.0, 1.0, 5.0]],
+            },
+        );
+        let mut block = SplineBlock::<f64, 4, 1, f64>::default();
+
+        for (t, y) in [(0.0, 0.0), (1.0, 2.0), (3.0, 1.0), (4.0, 5.0)] {
+            let (p, _, _) = block.process(&parameters, &context, t);
+            assert_relative_eq!(p.data[0][0], y, epsilon = 1e-12);
+        }
+
+        // Velocity and acceleration are continuous across knots, and acceleration is zero at
+        // the ends
+        let eval = |block: &mut SplineBlock<f64, 4, 1, f64>, t: f64| {
+            let (p, v, a) = block.process(&parameters, &context, t);
+            (p.data[0][0], v.data[0][0], a.data[0][0])
+        };
+        for knot in [1.0, 3.0] {
+            let before = eval(&mut block, knot - 1e-9);
+            let after = eval(&mut block, knot + 1e-9);
+            assert_relative_eq!(before.1, after.1, epsilon = 1e-6);
+            assert_relative_eq!(before.2, after.2, epsilon = 1e-6);
+        }
+        assert_relative_eq!(eval(&mut block, 1e-9).2, 0.0, epsilon = 1e-6);
+        assert_relative_eq!(eval(&mut block, 4.0 - 1e-9).2, 0.0, epsilon = 1e-6);
+
+        // Velocity matches the slope of the position
+        let (p0, v, _) = eval(&mut block, 2.0);
+        let (p1, _, _) = eval(&mut block, 2.0 + 1e-6);
+        assert_relative_eq!((p1 - p0) / 1e-6, v, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn test_natural_cubic_reproduces_lines() {
+        let context = StubContext::default();
+        let parameters = Parameters::new(
+            "NaturalCubic",
+            [0.0, 0.5, 2.0],
+            Matrix {
+                data: [[1.0, 2.0, 5.0], [0.0, -1.0, -4.0]],
+            },
+        );
+        let mut block = SplineBlock::<f32, 3, 2, f32>::default();
+        let (p, v, a) = block.process(&parameters, &context, 1.25);
+        assert_relative_eq!(p.data[0][0], 3.5, epsilon = 1e-5);
+        assert_relative_eq!(p.data[1][0], -2.5, epsilon = 1e-5);
+        assert_relative_eq!(v.data[0][0], 2.0, epsilon = 1e-5);
+        assert_relative_eq!(v.data[1][0], -2.0, epsilon = 1e-5);
+        assert_relative_eq!(a.data[0][0], 0.0, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn test_minimum_jerk_over_time() {
+        let mut runtime = StubRuntime::with_timestep(Duration::from_millis(250));
+        let parameters = Parameters::new(
+            "MinimumJerk",
+            [1.0, 2.0, 4.0],
+            Matrix {
+                data: [[0.0, 1.0, -1.0]],
+            },
+        );
+        let mut block = SplineBlock::<f64, 3>::default();
+
+        let mut outputs = [(0.0, 0.0, 0.0); 21];
+        for output in outputs.iter_mut() {
+            let (p, v, a) = block.process(&parameters, &runtime.context(), ());
+            *output = (p.data[0][0], v.data[0][0], a.data[0][0]);
+            runtime.tick();
+        }
+        // Holds the first waypoint until its knot, then comes to rest at each waypoint
+        assert_eq!(outputs[0], (0.0, 0.0, 0.0));
+        assert_eq!(outputs[4], (0.0, 0.0, 0.0));
+        assert_eq!(outputs[8], (1.0, 0.0, 0.0));
+        assert_eq!(outputs[16], (-1.0, 0.0, 0.0));
+        assert_eq!(outputs[20], (-1.0, 0.0, 0.0));
+        // Symmetric with peak velocity 1.875 * distance / duration halfway
+        assert_relative_eq!(outputs[6].0, 0.5, epsilon = 1e-12);
+        assert_relative_eq!(outputs[6].1, 1.875, epsilon = 1e-12);
+        assert_relative_eq!(outputs[6].2, 0.0, epsilon = 1e-12);
+        assert_relative_eq!(outputs[12].1, -1.875, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_spline_parameters() {
+        let waypoints = Matrix {
+            data: [[0.0, 1.0, 2.0]],
+        };
+        assert!(Parameters::try_new("NaturalCubic", [0.0, 1.0, 2.0], waypoints).is_ok());
+        assert!(Parameters::try_new("Bezier", [0.0, 1.0, 2.0], waypoints).is_err());
+        assert!(Parameters::try_new("MinimumJerk", [0.0, 1.0, 1.0], waypoints).is_err());
+        assert!(Parameters::try_new("MinimumJerk", [0.0, f64::NAN, 2.0], waypoints).is_err());
+        assert!(Parameters::<f64, 1, 1>::try_new("MinimumJerk", [0.0], Matrix::zeroed()).is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use crate::core_blocks::clamp_block::clamp;
+use crate::path_tracking::{distance, point, project, wrap_angle};
+use crate::traits::Float;
+use pictorus_traits::{Matrix, PassBy, ProcessBlock};
+
+/// Parameters for the StanleyBlock
+pub struct Parameters<F: Float> {
+    /// Gain on the cross-track error term
+    pub gain: F,
+    /// Softening constant added to the velocity in the cross-track term, which keeps the
+    /// steering bounded at low speeds
+    pub softening: F,
+    /// Distance between the front and rear axles
+    pub wheelbase: F,
+    /// Velocity command while following the path
+    pub velocity: F,
+    /// Maximum steering angle magnitude, in radians
+    pub max_steering: F,
+    /// Distance from the end of the path at which the vehicle stops
+    pub goal_tolerance: F,
+}
+
+impl<F: Float> Parameters<F> {
+    pub fn new(
+        gain: F,
+        softening: F,
+        wheelbase: F,
+        velocity: F,
+        max_steering: F,
+        goal_tolerance: F,
+    ) -> Self {
+        Self {
+            gain,
+            softening,
+            wheelbase,
+            velocity,
+            max_steering,
+            goal_tolerance,
+        }
+    }
+}
+
+/// Stanley path-tracking controller for car-like (bicycle model) ground vehicles.
+///
+/// The inputs are the vehicle pose `(x, y, heading)` as a (1, 3) matrix, measured at the rear axle,
+/// and the path as a (P, 2) matrix of `(x, y)` points. The front axle is projected onto the path,
+/// and the steering angle is the heading error to that path segment plus
+/// `atan(gain * cross_track / (softening + velocity))`, steering back towards the path. Headings
+/// and steering angles are in radians, counter-clockwise (left) positive.
+///
+/// The output is a tuple of (steering angle, velocity). The velocity is the configured cruise
+/// velocity, dropping to zero (with zero steering) once the vehicle is within the goal tolerance
+/// of the end of the path.
+pub struct StanleyBlock<F: Float, const P: usize> {
+    buffer: (F, F),
+}
+
+impl<F: Float, const P: usize> Default for StanleyBlock<F, P> {
+    fn default() -> Self {
+        const {
+            assert!(
+                P >= 2,
+                "StanleyBlock requires a path of at least two points"
+            );
+        }
+        Self {
+            buffer: (F::default(), F::default()),
+        }
+    }
+}
+
+impl<F: Float, const P: usize> ProcessBlock for StanleyBlock<F, P> {
+    type Inputs = (Matrix<1, 3, F>, Matrix<P, 2, F>);
+    type Output = (F, F);
+    type Parameters = Parameters<F>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (pose, path) = inputs;
+        let position = [pose.data[0][0], pose.data[1][0]];
+        let heading = pose.data[2][0];
+        let zero = <F as num_traits::Zero>::zero();
+
+        if distance(&position, &point(path, P - 1)) <= parameters.goal_tolerance {
+            self.buffer = (zero, zero);
+            return self.buffer;
+        }
+
+        let (sin, cos) = num_traits::Float::sin_cos(heading);
+        let front_axle = [
+            position[0] + parameters.wheelbase * cos,
+            position[1] + parameters.wheelbase * sin,
+        ];
+        let projection = project(path, &front_axle);
+        let heading_error = wrap_angle(projection.heading - heading);
+        // A positive cross-track error is left of the path, which needs a right (negative) correction
+        let correction = num_traits::Float::atan2(
+            parameters.gain * projection.cross_track,
+            parameters.softening + num_traits::Float::abs(parameters.velocity),
+        );
+        let steering = clamp(
+            wrap_angle(heading_error - correction),
+            -parameters.max_steering,
+            parameters.max_steering,
+        );
+
+        self.buffer = (steering, parameters.velocity);
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use approx::assert_relative_eq;
+
+    const PATH: Matrix<3, 2, f64> = Matrix {
+        data: [[0.0, 10.0, 20.0], [0.0, 0.0, 0.0]],
+    };
+
+    fn pose(x: f64, y: f64, heading: f64) -> Matrix<1, 3, f64> {
+        Matrix {
+            data: [[x], [y], [heading]],
+        }
+    }
+
+    #[test]
+    fn test_stanley_default_buffer() {
+        let block = StanleyBlock::<f64, 3>::default();
+        assert_eq!(block.buffer(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_stanley_steering() {
+        let ctxt = StubContext::default();
+        let params = Parameters::new(2.0, 1.0, 1.5, 3.0, 0.6, 0.5);
+        let mut block = StanleyBlock::<f64, 3>::default();
+
+        let (steering, velocity) = block.process(&params, &ctxt, (&pose(5.0, 0.0, 0.0), &PATH));
+        assert_relative_eq!(steering, 0.0);
+        assert_eq!(velocity, 3.0);
+
+        // Left of the path, steering right by atan(2 * 0.5 / (1 + 3))
+        let (steering, _) = block.process(&params, &ctxt, (&pose(5.0, 0.5, 0.0), &PATH));
+        assert_relative_eq!(steering, -(0.25f64).atan());
+
+        // On the path but yawed right, the front axle is right of the path too
+        let heading = -0.1f64;
+        let (steering, _) = block.process(&params, &ctxt, (&pose(5.0, 0.0, heading), &PATH));
+        let cross_track = 1.5 * heading.sin();
+        assert_relative_eq!(steering, 0.1 - (2.0 * cross_track / 4.0).atan());
+
+        // Steering is limited
+        let (steering, _) = block.process(&params, &ctxt, (&pose(5.0, -10.0, 0.0), &PATH));
+        assert_eq!(steering, 0.6);
+    }
+
+    #[test]
+    fn test_stanley_stops_at_goal() {
+        let ctxt = StubContext::default();
+        let params = Parameters::new(2.0, 1.0, 1.5, 3.0, 0.6, 0.5);
+        let mut block = StanleyBlock::<f64, 3>::default();
+        let (steering, velocity) = block.process(&params, &ctxt, (&pose(19.9, 0.0, 0.3), &PATH));
+        assert_eq!((steering, velocity), (0.0, 0.0));
+    }
+}
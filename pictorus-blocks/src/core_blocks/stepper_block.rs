@@ -0,0 +1,210 @@
+use crate::core_blocks::clamp_block::clamp;
+use crate::ParameterError;
+use core::time::Duration;
+
+use crate::traits::Float;
+use pictorus_traits::{PassBy, ProcessBlock};
+
+/// How the stepper block interprets its command input
+#[derive(Clone, Copy, Debug, PartialEq, strum::EnumString)]
+pub enum StepperMode {
+    /// The command is a target position, in steps
+    Position,
+    /// The command is a target velocity, in steps per second
+    Velocity,
+}
+
+/// Parameters for the StepperBlock
+#[doc(hidden)]
+pub struct Parameters<F: Float> {
+    pub mode: StepperMode,
+    /// Maximum step rate, in steps per second
+    pub max_velocity: F,
+    /// Maximum change in step rate, in steps per second squared. Zero or less disables the limit.
+    pub max_acceleration: F,
+}
+
+impl<F: Float> Parameters<F> {
+    pub fn new(mode: &str, max_velocity: F, max_acceleration: F) -> Self {
+        Self::try_new(mode, max_velocity, max_acceleration).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(
+        mode: &str,
+        max_velocity: F,
+        max_acceleration: F,
+    ) -> Result<Self, ParameterError> {
+        Ok(Self {
+            mode: mode
+                .parse()
+                .map_err(|_| ParameterError("Failed to parse StepperMode"))?,
+            max_velocity,
+            max_acceleration,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved mode, without parsing
+    pub const fn from_mode(mode: StepperMode, max_velocity: F, max_acceleration: F) -> Self {
+        Self {
+            mode,
+            max_velocity,
+            max_acceleration,
+        }
+    }
+}
+
+/// Generates an acceleration-limited step rate profile for a stepper motor driver from a
+/// position or velocity command.
+///
+/// The output is a tuple of (step_rate, position). The step rate is signed (negative steps
+/// backwards) and is the rate the hardware should step at until the next tick; the platform
+/// output block turns it into step/dir pulse trains. The position is the number of steps issued
+/// so far, which serves as position feedback for open loop stepper systems.
+///
+/// In `Position` mode the block follows a trapezoidal profile, decelerating so that it comes to
+/// rest at the commanded position. In `Velocity` mode the command is clamped to the maximum
+/// velocity and approached at the maximum acceleration.
+pub struct StepperBlock<F: Float> {
+    buffer: (F, F),
+}
+
+impl<F: Float> Default for StepperBlock<F> {
+    fn default() -> Self {
+        Self {
+            buffer: (F::default(), F::default()),
+        }
+    }
+}
+
+impl<F: Float> ProcessBlock for StepperBlock<F> {
+    type Inputs = F;
+    type Output = (F, F);
+    type Parameters = Parameters<F>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (velocity, position) = &mut self.buffer;
+        let dt = F::from_duration(context.timestep().unwrap_or(Duration::ZERO));
+        let zero = <F as num_traits::Zero>::zero();
+        let max_velocity = num_traits::Float::abs(parameters.max_velocity);
+        let accel = parameters.max_acceleration;
+
+        // Steps issued at the previously commanded rate since the last tick
+        *position += *velocity * dt;
+
+        let target = match parameters.mode {
+            StepperMode::Velocity => clamp(input, -max_velocity, max_velocity),
+            StepperMode::Position => {
+                let error = input - *position;
+                let half_step = F::from(0.5).expect("0.5 fits in float");
+                if num_traits::Float::abs(error) < half_step {
+                    zero
+                } else if accel > zero {
+                    // Fastest speed from which we can still stop at the target
+                    let two = F::from(2.0).expect("2 fits in float");
+                    let stopping =
+                        num_traits::Float::sqrt(two * accel * num_traits::Float::abs(error));
+                    num_traits::Float::signum(error)
+                        * num_traits::Float::min(max_velocity, stopping)
+                } else {
+                    num_traits::Float::signum(error) * max_velocity
+                }
+            }
+        };
+
+        *velocity = if accel > zero {
+            let max_change = accel * dt;
+            *velocity + clamp(target - *velocity, -max_change, max_change)
+        } else {
+            target
+        };
+
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubRuntime;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_stepper_default_buffer_no_panic() {
+        let block = StepperBlock::<f64>::default();
+        assert_eq!(block.buffer(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_stepper_velocity_mode() {
+        let mut runtime = StubRuntime::default();
+        let params = Parameters::new("Velocity", 1000.0, 2000.0);
+        let mut block = StepperBlock::<f64>::default();
+
+        // No timestep on the first tick, so no acceleration yet
+        assert_eq!(
+            block.process(&params, &runtime.context(), 5000.0),
+            (0.0, 0.0)
+        );
+
+        // 2000 steps/s^2 over 100ms ticks ramps 200 steps/s per tick up to the 1000 limit
+        let mut rates = [0.0; 7];
+        for rate in rates.iter_mut() {
+            runtime.tick();
+            *rate = block.process(&params, &runtime.context(), 5000.0).0;
+        }
+        assert_eq!(rates, [200.0, 400.0, 600.0, 800.0, 1000.0, 1000.0, 1000.0]);
+        // Position counts the steps issued over the previous ticks
+        assert_relative_eq!(block.buffer().1, 400.0, epsilon = 1e-9);
+
+        runtime.tick();
+        let (rate, _) = block.process(&params, &runtime.context(), -5000.0);
+        assert_eq!(rate, 800.0);
+    }
+
+    #[test]
+    fn test_stepper_position_mode() {
+        let mut runtime = StubRuntime::default();
+        runtime.context.fundamental_timestep = Duration::from_millis(1);
+        let params = Parameters::new("Position", 500.0, 1000.0);
+        let mut block = StepperBlock::<f64>::default();
+
+        let mut peak_rate: f64 = 0.0;
+        for _ in 0..5000 {
+            runtime.tick();
+            let (rate, _) = block.process(&params, &runtime.context(), -400.0);
+            peak_rate = peak_rate.max(rate.abs());
+        }
+        let (rate, position) = block.buffer();
+        assert_eq!(rate, 0.0);
+        assert!((position + 400.0).abs() < 0.5, "position {position}");
+        assert_relative_eq!(peak_rate, 500.0);
+    }
+
+    #[test]
+    fn test_stepper_unlimited_acceleration() {
+        let mut runtime = StubRuntime::default();
+        let params = Parameters::new("Position", 100.0, 0.0);
+        let mut block = StepperBlock::<f64>::default();
+
+        runtime.tick();
+        assert_eq!(
+            block.process(&params, &runtime.context(), 10.0),
+            (100.0, 0.0)
+        );
+        runtime.tick();
+        assert_eq!(
+            block.process(&params, &runtime.context(), 10.0),
+            (0.0, 10.0)
+        );
+    }
+}
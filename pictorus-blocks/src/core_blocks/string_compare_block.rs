@@ -0,0 +1,153 @@
+use crate::ParameterError;
+use pictorus_traits::{PassBy, ProcessBlock, StrSliceSignal};
+
+/// The string comparison to perform
+#[derive(Clone, Copy, Debug, PartialEq, strum::EnumString)]
+pub enum StringCompareMethod {
+    /// Check if the two strings are equal
+    Equal,
+    /// Check if the two strings are not equal
+    NotEqual,
+    /// Check if the first string starts with the second
+    StartsWith,
+    /// Check if the first string ends with the second
+    EndsWith,
+    /// Check if the first string contains the second
+    Contains,
+}
+
+/// Parameters for the StringCompareBlock
+pub struct Parameters {
+    pub method: StringCompareMethod,
+    /// Compare ASCII letters regardless of case. Non-ASCII characters always have to match
+    /// exactly.
+    pub ignore_case: bool,
+}
+
+impl Parameters {
+    pub fn new(method: &str, ignore_case: bool) -> Self {
+        Self::try_new(method, ignore_case).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(method: &str, ignore_case: bool) -> Result<Self, ParameterError> {
+        Ok(Self {
+            method: method
+                .parse()
+                .map_err(|_| ParameterError("Failed to parse string compare method."))?,
+            ignore_case,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved compare method, without parsing
+    pub const fn from_method(method: StringCompareMethod, ignore_case: bool) -> Self {
+        Self {
+            method,
+            ignore_case,
+        }
+    }
+}
+
+/// Compares two strings, outputting true if the comparison holds.
+///
+/// Useful for matching replies of text-based device protocols or state labels, e.g. checking
+/// whether a modem's reply starts with "+CREG".
+#[derive(Default)]
+pub struct StringCompareBlock {
+    buffer: bool,
+}
+
+impl ProcessBlock for StringCompareBlock {
+    type Inputs = (StrSliceSignal, StrSliceSignal);
+    type Output = bool;
+    type Parameters = Parameters;
+
+    fn process(
+        &mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<Self::Inputs>,
+    ) -> PassBy<'_, Self::Output> {
+        let (text, pattern) = (inputs.0.as_bytes(), inputs.1.as_bytes());
+        let matches = |a: &[u8], b: &[u8]| {
+            if parameters.ignore_case {
+                a.eq_ignore_ascii_case(b)
+            } else {
+                a == b
+            }
+        };
+        // Comparing bytes is safe for UTF-8: a match can't start or end partway through a
+        // character, since continuation bytes never match the first byte of a character
+        self.buffer = match parameters.method {
+            StringCompareMethod::Equal => matches(text, pattern),
+            StringCompareMethod::NotEqual => !matches(text, pattern),
+            StringCompareMethod::StartsWith => text
+                .get(..pattern.len())
+                .is_some_and(|start| matches(start, pattern)),
+            StringCompareMethod::EndsWith => text
+                .len()
+                .checked_sub(pattern.len())
+                .is_some_and(|start| matches(&text[start..], pattern)),
+            StringCompareMethod::Contains => {
+                pattern.is_empty()
+                    || text
+                        .windows(pattern.len())
+                        .any(|window| matches(window, pattern))
+            }
+        };
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+
+    fn compare(method: &str, ignore_case: bool, text: &str, pattern: &str) -> bool {
+        let parameters = Parameters::new(method, ignore_case);
+        let mut block = StringCompareBlock::default();
+        let output = block.process(&parameters, &StubContext::default(), (text, pattern));
+        assert_eq!(block.buffer(), output);
+        output
+    }
+
+    #[test]
+    fn test_string_compare_default_buffer_no_panic() {
+        let block = StringCompareBlock::default();
+        assert!(!block.buffer());
+    }
+
+    #[test]
+    fn test_string_compare_methods() {
+        assert!(compare("Equal", false, "IDLE", "IDLE"));
+        assert!(!compare("Equal", false, "IDLE", "idle"));
+        assert!(compare("NotEqual", false, "IDLE", "idle"));
+        assert!(compare("StartsWith", false, "+CREG: 0,1", "+CREG"));
+        assert!(!compare("StartsWith", false, "OK", "OK\r\n"));
+        assert!(compare("EndsWith", false, "AT+CSQ\r\nOK", "OK"));
+        assert!(!compare("EndsWith", false, "K", "OK"));
+        assert!(compare("Contains", false, "ERROR 42", "OR 4"));
+        assert!(!compare("Contains", false, "ERROR 42", "43"));
+        assert!(compare("Contains", false, "", ""));
+    }
+
+    #[test]
+    fn test_string_compare_ignore_case() {
+        assert!(compare("Equal", true, "Armed", "ARMED"));
+        assert!(compare("StartsWith", true, "ok\r\n", "OK"));
+        assert!(compare("Contains", true, "Battery LOW", "low"));
+        // Only ASCII letters are folded
+        assert!(!compare("Equal", true, "ÉTAT", "état"));
+    }
+
+    #[test]
+    fn test_string_compare_parameters() {
+        assert!(Parameters::try_new("Contains", false).is_ok());
+        assert!(Parameters::try_new("Like", false).is_err());
+    }
+}
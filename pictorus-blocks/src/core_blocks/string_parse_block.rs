@@ -0,0 +1,92 @@
+use pictorus_traits::{PassBy, ProcessBlock, StrSliceSignal};
+
+use crate::traits::Float;
+
+/// Parameters for the StringParseBlock
+#[derive(Default)]
+pub struct Parameters {}
+
+impl Parameters {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Parses a number from a string, such as a reading from a text-based sensor protocol.
+///
+/// Leading and trailing whitespace is ignored, and both integers and decimals (including
+/// exponents, like "1.5e3") are accepted. Outputs the number and whether parsing succeeded; when
+/// it fails the last successfully parsed number is held.
+pub struct StringParseBlock<F: Float> {
+    buffer: (F, bool),
+}
+
+impl<F: Float> Default for StringParseBlock<F> {
+    fn default() -> Self {
+        Self {
+            buffer: (F::zero(), false),
+        }
+    }
+}
+
+impl<F: Float> ProcessBlock for StringParseBlock<F> {
+    type Inputs = StrSliceSignal;
+    type Output = (F, bool);
+    type Parameters = Parameters;
+
+    fn process(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<Self::Inputs>,
+    ) -> PassBy<'_, Self::Output> {
+        match input.trim().parse::<f64>().ok().and_then(F::from) {
+            Some(value) => self.buffer = (value, true),
+            None => self.buffer.1 = false,
+        }
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+
+    #[test]
+    fn test_string_parse_default_buffer_no_panic() {
+        let block = StringParseBlock::<f64>::default();
+        assert_eq!(block.buffer(), (0.0, false));
+    }
+
+    #[test]
+    fn test_string_parse_block() {
+        let context = StubContext::default();
+        let parameters = Parameters::new();
+        let mut block = StringParseBlock::<f64>::default();
+
+        assert_eq!(block.process(&parameters, &context, "42"), (42.0, true));
+        assert_eq!(
+            block.process(&parameters, &context, " -3.25\r\n"),
+            (-3.25, true)
+        );
+        assert_eq!(
+            block.process(&parameters, &context, "1.5e3"),
+            (1500.0, true)
+        );
+        // Invalid input holds the last good value
+        assert_eq!(
+            block.process(&parameters, &context, "12 V"),
+            (1500.0, false)
+        );
+        assert_eq!(block.process(&parameters, &context, ""), (1500.0, false));
+        assert_eq!(block.buffer(), (1500.0, false));
+
+        let mut block = StringParseBlock::<f32>::default();
+        assert_eq!(block.process(&parameters, &context, "0.5"), (0.5, true));
+    }
+}
@@ -1,7 +1,10 @@
-use crate::matrix_ext::MatrixNalgebraExt;
-use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock, Scalar};
+use pictorus_traits::{Fixed, Matrix, Pass, PassBy, ProcessBlock, Scalar};
 
 /// Sums (adds or subtracts) all inputs together.
+///
+/// Inputs can also be bundles (tuples) of signals with matching shapes, which are summed element
+/// by element, e.g. `SumBlock<((Matrix<3, 1, f64>, f64), (Matrix<3, 1, f64>, f64))>` outputs
+/// `(Matrix<3, 1, f64>, f64)`.
 pub struct SumBlock<T: Summable> {
     store: T::Output,
 }
@@ -51,10 +54,11 @@ trait SumScalar:
 }
 impl SumScalar for f32 {}
 impl SumScalar for f64 {}
+impl<const FRAC: u32> SumScalar for Fixed<FRAC> {}
 
 /// This trait is used to determine the output type of a sum operation
-/// between two types, most importantly it can be used recursively. To get the output type for
-/// a tuple of inputs. For an input of all scalars the output is scalar. For all inputs being a
+/// between two types, most importantly it can be used recursively (see [`TuplePromotion`]) to get
+/// the output type for a tuple of inputs. For an input of all scalars the output is scalar. For all inputs being a
 /// single size of matrix, or a mix of scalars and a single size of matrix the output is a matrix
 /// of that size.
 pub trait TypePromotion<RHS> {
@@ -83,8 +87,39 @@ impl<const R: usize, const C: usize, S: SumScalar> TypePromotion<Matrix<R, C, S>
     type Output = Matrix<R, C, S>;
 }
 
+/// Bundles of signals sum element by element, outputting a bundle. This lets one block do the
+/// work of several when a model applies the same sum to many signals.
+macro_rules! impl_bundle_type_promotion {
+    ($( ( $( $A:ident $B:ident ),+ ) ),+ $(,)?) => {
+        $(
+            impl<$( $A, $B ),+> TypePromotion<( $( $B ),+ )> for ( $( $A ),+ )
+            where
+                $( $A: TypePromotion<$B> ),+
+            {
+                type Output = ( $( $A::Output ),+ );
+            }
+        )+
+    };
+}
+
+impl_bundle_type_promotion!(
+    (A1 B1, A2 B2),
+    (A1 B1, A2 B2, A3 B3),
+    (A1 B1, A2 B2, A3 B3, A4 B4),
+    (A1 B1, A2 B2, A3 B3, A4 B4, A5 B5),
+    (A1 B1, A2 B2, A3 B3, A4 B4, A5 B5, A6 B6),
+    (A1 B1, A2 B2, A3 B3, A4 B4, A5 B5, A6 B6, A7 B7),
+    (A1 B1, A2 B2, A3 B3, A4 B4, A5 B5, A6 B6, A7 B7, A8 B8),
+);
+
+/// The output type of summing `Self` with every element of the tuple `REST`. Defined recursively
+/// in terms of [`TypePromotion`], for sums of 3 or more inputs.
+pub trait TuplePromotion<REST> {
+    type Output: Pass + Default;
+}
+
 /// Recursive definition for 3 inputs
-impl<A, B, C> TypePromotion<(B, C)> for A
+impl<A, B, C> TuplePromotion<(B, C)> for A
 where
     B: TypePromotion<C>,
     A: TypePromotion<<B as TypePromotion<C>>::Output>,
@@ -93,45 +128,45 @@ where
 }
 
 /// Recursive definition for 4 inputs
-impl<A, B, C, D> TypePromotion<(B, C, D)> for A
+impl<A, B, C, D> TuplePromotion<(B, C, D)> for A
 where
-    B: TypePromotion<(C, D)>,
+    B: TuplePromotion<(C, D)>,
     A: TypePromotion<B::Output>,
 {
     type Output = <A as TypePromotion<B::Output>>::Output;
 }
 
 /// Recursive definition for 5 inputs
-impl<A, B, C, D, E> TypePromotion<(B, C, D, E)> for A
+impl<A, B, C, D, E> TuplePromotion<(B, C, D, E)> for A
 where
-    B: TypePromotion<(C, D, E)>,
+    B: TuplePromotion<(C, D, E)>,
     A: TypePromotion<B::Output>,
 {
     type Output = <A as TypePromotion<B::Output>>::Output;
 }
 
 /// Recursive definition for 6 inputs
-impl<A, B, C, D, E, F> TypePromotion<(B, C, D, E, F)> for A
+impl<A, B, C, D, E, F> TuplePromotion<(B, C, D, E, F)> for A
 where
-    B: TypePromotion<(C, D, E, F)>,
+    B: TuplePromotion<(C, D, E, F)>,
     A: TypePromotion<B::Output>,
 {
     type Output = <A as TypePromotion<B::Output>>::Output;
 }
 
 /// Recursive definition for 7 inputs
-impl<A, B, C, D, E, F, G> TypePromotion<(B, C, D, E, F, G)> for A
+impl<A, B, C, D, E, F, G> TuplePromotion<(B, C, D, E, F, G)> for A
 where
-    B: TypePromotion<(C, D, E, F, G)>,
+    B: TuplePromotion<(C, D, E, F, G)>,
     A: TypePromotion<B::Output>,
 {
     type Output = <A as TypePromotion<B::Output>>::Output;
 }
 
 /// Recursive definition for 8 inputs
-impl<A, B, C, D, E, F, G, H> TypePromotion<(B, C, D, E, F, G, H)> for A
+impl<A, B, C, D, E, F, G, H> TuplePromotion<(B, C, D, E, F, G, H)> for A
 where
-    B: TypePromotion<(C, D, E, F, G, H)>,
+    B: TuplePromotion<(C, D, E, F, G, H)>,
     A: TypePromotion<B::Output>,
 {
     type Output = <A as TypePromotion<B::Output>>::Output;
@@ -176,14 +211,14 @@ impl<const R: usize, const C: usize, S: SumScalar> SumInto<Matrix<R, C, S>> for
         dest: &'a mut Option<Matrix<R, C, S>>,
     ) -> PassBy<'a, Matrix<R, C, S>> {
         let dest = dest.get_or_insert(Matrix::<R, C, S>::zeroed());
-        let orig_dest = dest.as_view().clone_owned();
+        let pairs = dest
+            .data
+            .as_flattened_mut()
+            .iter_mut()
+            .zip(input.data.as_flattened());
         match sum_type {
-            SumType::Addition => {
-                orig_dest.add_to(&input.as_view(), &mut dest.as_view_mut());
-            }
-            SumType::Subtraction => {
-                orig_dest.sub_to(&input.as_view(), &mut dest.as_view_mut());
-            }
+            SumType::Addition => pairs.for_each(|(dest, input)| *dest += *input),
+            SumType::Subtraction => pairs.for_each(|(dest, input)| *dest -= *input),
         }
         dest
     }
@@ -197,20 +232,54 @@ impl<const R: usize, const C: usize, S: SumScalar> SumInto<Matrix<R, C, S>> for
         dest: &'a mut Option<Matrix<R, C, S>>,
     ) -> PassBy<'a, Matrix<R, C, S>> {
         let dest = dest.get_or_insert(Matrix::<R, C, S>::zeroed());
-        let mut orig_dest = dest.as_view().clone_owned();
+        let elements = dest.data.as_flattened_mut().iter_mut();
         match sum_type {
-            SumType::Addition => {
-                orig_dest = orig_dest.add_scalar(input);
-            }
-            SumType::Subtraction => {
-                orig_dest = orig_dest.add_scalar(-input);
-            }
+            SumType::Addition => elements.for_each(|dest| *dest += input),
+            SumType::Subtraction => elements.for_each(|dest| *dest -= input),
         }
-        dest.as_view_mut().copy_from(&orig_dest);
         dest
     }
 }
 
+/// Bundle summing into a bundle, element by element
+macro_rules! impl_bundle_sum_into {
+    ($( ( $( $T:ident $D:ident $i:tt ),+ ) ),+ $(,)?) => {
+        $(
+            impl<$( $T, $D ),+> SumInto<( $( $D ),+ )> for ( $( $T ),+ )
+            where
+                $( $T: SumInto<$D>, $D: Pass ),+
+            {
+                fn sum_into<'a>(
+                    input: PassBy<Self>,
+                    sum_type: SumType,
+                    dest: &'a mut Option<( $( $D ),+ )>,
+                ) -> PassBy<'a, ( $( $D ),+ )> {
+                    // Each element keeps its own optional accumulator, so split the bundle apart
+                    let mut elements = match dest.take() {
+                        Some(dest) => ( $( Some(dest.$i) ),+ ),
+                        None => Default::default(),
+                    };
+                    $( $T::sum_into(input.$i, sum_type, &mut elements.$i); )+
+                    let dest = dest.insert((
+                        $( elements.$i.expect("sum_into must initialize the buffer") ),+
+                    ));
+                    dest.as_by()
+                }
+            }
+        )+
+    };
+}
+
+impl_bundle_sum_into!(
+    (A1 D1 0, A2 D2 1),
+    (A1 D1 0, A2 D2 1, A3 D3 2),
+    (A1 D1 0, A2 D2 1, A3 D3 2, A4 D4 3),
+    (A1 D1 0, A2 D2 1, A3 D3 2, A4 D4 3, A5 D5 4),
+    (A1 D1 0, A2 D2 1, A3 D3 2, A4 D4 3, A5 D5 4, A6 D6 5),
+    (A1 D1 0, A2 D2 1, A3 D3 2, A4 D4 3, A5 D5 4, A6 D6 5, A7 D7 6),
+    (A1 D1 0, A2 D2 1, A3 D3 2, A4 D4 3, A5 D5 4, A6 D6 5, A7 D7 6, A8 D8 7),
+);
+
 /// This trait makes use of the two above , `SumInto` and `TypePromotion` to sum a tuple of inputs (or a single input)
 pub trait Summable: Pass {
     type Output: Pass + Default;
@@ -275,7 +344,7 @@ where
 
 impl<A, B, C> Summable for (A, B, C)
 where
-    A: TypePromotion<(B, C)>,
+    A: TuplePromotion<(B, C)>,
     A: SumInto<A::Output>,
     B: SumInto<A::Output>,
     C: SumInto<A::Output>,
@@ -297,7 +366,7 @@ where
 
 impl<A, B, C, D> Summable for (A, B, C, D)
 where
-    A: TypePromotion<(B, C, D)>,
+    A: TuplePromotion<(B, C, D)>,
     A: SumInto<A::Output>,
     B: SumInto<A::Output>,
     C: SumInto<A::Output>,
@@ -321,7 +390,7 @@ where
 
 impl<A, B, C, D, E> Summable for (A, B, C, D, E)
 where
-    A: TypePromotion<(B, C, D, E)>,
+    A: TuplePromotion<(B, C, D, E)>,
     A: SumInto<A::Output>,
     B: SumInto<A::Output>,
     C: SumInto<A::Output>,
@@ -347,7 +416,7 @@ where
 
 impl<A, B, C, D, E, F> Summable for (A, B, C, D, E, F)
 where
-    A: TypePromotion<(B, C, D, E, F)>,
+    A: TuplePromotion<(B, C, D, E, F)>,
     A: SumInto<A::Output>,
     B: SumInto<A::Output>,
     C: SumInto<A::Output>,
@@ -375,7 +444,7 @@ where
 
 impl<A, B, C, D, E, F, G> Summable for (A, B, C, D, E, F, G)
 where
-    A: TypePromotion<(B, C, D, E, F, G)>,
+    A: TuplePromotion<(B, C, D, E, F, G)>,
     A: SumInto<A::Output>,
     B: SumInto<A::Output>,
     C: SumInto<A::Output>,
@@ -405,7 +474,7 @@ where
 
 impl<A, B, C, D, E, F, G, H> Summable for (A, B, C, D, E, F, G, H)
 where
-    A: TypePromotion<(B, C, D, E, F, G, H)>,
+    A: TuplePromotion<(B, C, D, E, F, G, H)>,
     A: SumInto<A::Output>,
     B: SumInto<A::Output>,
     C: SumInto<A::Output>,
@@ -467,6 +536,7 @@ mod tests {
     use super::*;
     use crate::testing::StubContext;
     use approx::assert_relative_eq;
+    use pictorus_traits::Q15;
 
     #[test]
     fn test_sum_default_buffer_no_panic() {
@@ -798,4 +868,51 @@ mod tests {
             [[11.0, 13.0], [15.0, 17.0]].as_flattened()
         );
     }
+
+    #[test]
+    fn test_fixed_point() {
+        let stub_context = StubContext::default();
+        let q = Q15::from_f64;
+
+        let mut block = SumBlock::<(Q15, Q15, Q15)>::default();
+        let parameters = Parameters {
+            operations: [SumType::Addition, SumType::Subtraction, SumType::Addition],
+        };
+        let result = block.process(&parameters, &stub_context, (q(1.5), q(0.25), q(-3.0)));
+        assert_eq!(result, q(-1.75));
+
+        let mut block = SumBlock::<(Matrix<1, 2, Q15>, Q15)>::default();
+        let parameters = Parameters {
+            operations: [SumType::Addition, SumType::Subtraction],
+        };
+        let input = Matrix {
+            data: [[q(1.0)], [q(2.0)]],
+        };
+        let result = block.process(&parameters, &stub_context, (&input, q(0.5)));
+        assert_eq!(result.data, [[q(0.5)], [q(1.5)]]);
+    }
+    #[test]
+    fn test_bundles() {
+        let stub_context = StubContext::default();
+        let mut block = SumBlock::<(
+            (Matrix<2, 1, f64>, f64),
+            (Matrix<2, 1, f64>, f64),
+            (f64, f64),
+        )>::default();
+        let (buffer_matrix, buffer_scalar) = block.buffer();
+        assert_eq!(buffer_matrix.data, [[0.0, 0.0]]);
+        assert_eq!(buffer_scalar, 0.0);
+
+        let parameters = Parameters::new([1.0, -1.0, 1.0]);
+        let a = Matrix { data: [[1.0, 2.0]] };
+        let b = Matrix { data: [[0.5, 0.5]] };
+        let (matrix, scalar) = block.process(
+            &parameters,
+            &stub_context,
+            ((&a, 1.0), (&b, 2.0), (10.0, 4.0)),
+        );
+        assert_eq!(matrix.data, [[10.5, 11.5]]);
+        assert_eq!(scalar, 3.0);
+        assert_eq!(block.buffer().0.data, [[10.5, 11.5]]);
+    }
 }
@@ -0,0 +1,125 @@
+use crate::traits::Float;
+use pictorus_traits::{PassBy, ProcessBlock};
+
+/// Parameters for the SvpwmBlock
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Parameters;
+
+impl Parameters {
+    pub fn new() -> Self {
+        Parameters {}
+    }
+}
+
+/// Space-vector PWM modulator, converting a stationary frame voltage command into three
+/// phase duty cycles for a center-aligned PWM peripheral.
+///
+/// Inputs are `(v_alpha, v_beta, v_bus)`, and the output is the `(a, b, c)` duty cycles in [0, 1].
+/// This uses min-max zero sequence injection, which produces the same switching pattern as
+/// sector-based SVPWM and allows a voltage vector magnitude of up to `v_bus / sqrt(3)` before
+/// overmodulation. Commands beyond that are clipped. With a non-positive bus voltage every phase
+/// outputs 0.5 (the zero vector).
+pub struct SvpwmBlock<F: Float> {
+    buffer: (F, F, F),
+}
+
+impl<F: Float> Default for SvpwmBlock<F> {
+    fn default() -> Self {
+        let half = F::from(0.5).expect("0.5 fits in float");
+        Self {
+            buffer: (half, half, half),
+        }
+    }
+}
+
+impl<F: Float> ProcessBlock for SvpwmBlock<F> {
+    type Inputs = (F, F, F);
+    type Output = (F, F, F);
+    type Parameters = Parameters;
+
+    fn process<'b>(
+        &'b mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (alpha, beta, v_bus) = inputs;
+        let zero = <F as num_traits::Zero>::zero();
+        let one = <F as num_traits::One>::one();
+        let half = F::from(0.5).expect("0.5 fits in float");
+        if v_bus <= zero {
+            self.buffer = (half, half, half);
+            return self.buffer;
+        }
+
+        // Inverse Clarke to phase voltages
+        let sqrt3_2 = num_traits::Float::sqrt(F::from(3.0).expect("3 fits in float")) * half;
+        let a = alpha;
+        let b = -alpha * half + beta * sqrt3_2;
+        let c = -alpha * half - beta * sqrt3_2;
+
+        // Shift the phases so their extremes are centered, which maximizes the usable bus voltage
+        let max = num_traits::Float::max(a, num_traits::Float::max(b, c));
+        let min = num_traits::Float::min(a, num_traits::Float::min(b, c));
+        let offset = (max + min) * half;
+        let duty = |v: F| num_traits::Float::clamp(half + (v - offset) / v_bus, zero, one);
+
+        self.buffer = (duty(a), duty(b), duty(c));
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_svpwm_default_buffer_no_panic() {
+        let block = SvpwmBlock::<f64>::default();
+        assert_eq!(block.buffer(), (0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn test_svpwm_block() {
+        let context = StubContext::default();
+        let params = Parameters::new();
+        let mut block = SvpwmBlock::<f64>::default();
+
+        assert_eq!(
+            block.process(&params, &context, (0.0, 0.0, 24.0)),
+            (0.5, 0.5, 0.5)
+        );
+
+        // Along alpha: phase voltages are (v, -v/2, -v/2), shifted by v/4
+        let (a, b, c) = block.process(&params, &context, (4.0, 0.0, 24.0));
+        assert_relative_eq!(a, 0.5 + 3.0 / 24.0, epsilon = 1e-12);
+        assert_relative_eq!(b, 0.5 - 3.0 / 24.0, epsilon = 1e-12);
+        assert_relative_eq!(c, b, epsilon = 1e-12);
+
+        // The line-to-line voltages are preserved for any angle up to the linear limit
+        let magnitude = 24.0 / 3.0f64.sqrt();
+        for theta in [0.1, 1.0, 2.5, 4.0, 5.9] {
+            let (alpha, beta) = (magnitude * f64::cos(theta), magnitude * f64::sin(theta));
+            let (a, b, c) = block.process(&params, &context, (alpha, beta, 24.0));
+            for duty in [a, b, c] {
+                assert!((-1e-12..=1.0 + 1e-12).contains(&duty));
+            }
+            let expected_ab = alpha * 1.5 - beta * 3.0f64.sqrt() / 2.0;
+            assert_relative_eq!((a - b) * 24.0, expected_ab, epsilon = 1e-9);
+        }
+
+        // Overmodulation clips, and no bus voltage outputs the zero vector
+        let (a, b, _) = block.process(&params, &context, (100.0, 0.0, 24.0));
+        assert_eq!((a, b), (1.0, 0.0));
+        assert_eq!(
+            block.process(&params, &context, (1.0, 1.0, 0.0)),
+            (0.5, 0.5, 0.5)
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use crate::traits::Float;
+use crate::ParameterError;
+use pictorus_traits::{PassBy, ProcessBlock};
+
+/// Parameters for the TareScaleBlock
+pub struct Parameters<F: Float> {
+    /// Calibration slope, in output units per input count, e.g. grams per HX711 count found by
+    /// weighing a known mass
+    pub scale: F,
+    /// Input of an unloaded scale until the first tare, e.g. from a previous calibration
+    pub offset: F,
+    /// Number of ticks averaged for a tare
+    pub tare_samples: usize,
+}
+
+impl<F: Float> Parameters<F> {
+    pub fn new(scale: F, offset: F, tare_samples: f64) -> Self {
+        Self::try_new(scale, offset, tare_samples).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(scale: F, offset: F, tare_samples: f64) -> Result<Self, ParameterError> {
+        if tare_samples < 1.0 || f64::from(tare_samples as u32) != tare_samples {
+            return Err(ParameterError("Tare samples must be a positive integer"));
+        }
+        Ok(Self {
+            scale,
+            offset,
+            tare_samples: tare_samples as usize,
+        })
+    }
+}
+
+/// Converts raw load cell readings (e.g. HX711 counts) to calibrated weight or force, with a
+/// tare that zeroes the scale on command.
+///
+/// The inputs are (reading, tare). A rising edge on tare averages the reading over the next tare
+/// samples ticks, starting with the current one, and the average becomes the zero offset once
+/// complete. NaN readings are skipped. The output is a tuple of (weight, offset), where weight is
+/// `(reading - offset) * scale`, so the offset can be stored and restored as a parameter.
+pub struct TareScaleBlock<F: Float> {
+    buffer: (F, F),
+    /// Offset in use, None until the parameter is read on the first tick
+    offset: Option<F>,
+    /// Sum and count of the readings of a tare in progress
+    tare: Option<(F, usize)>,
+    previous_tare: bool,
+}
+
+impl<F: Float> Default for TareScaleBlock<F> {
+    fn default() -> Self {
+        Self {
+            buffer: (F::zero(), F::zero()),
+            offset: None,
+            tare: None,
+            previous_tare: false,
+        }
+    }
+}
+
+impl<F: Float> ProcessBlock for TareScaleBlock<F> {
+    type Inputs = (F, bool);
+    type Output = (F, F);
+    type Parameters = Parameters<F>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (reading, tare) = inputs;
+        let offset = self.offset.get_or_insert(parameters.offset);
+
+        if tare && !self.previous_tare {
+            self.tare = Some((F::zero(), 0));
+        }
+        self.previous_tare = tare;
+
+        if let Some((sum, count)) = &mut self.tare {
+            if !num_traits::Float::is_nan(reading) {
+                *sum += reading;
+                *count += 1;
+            }
+            if *count >= parameters.tare_samples {
+                *offset = *sum / F::from(*count).expect("Couldn't convert sample count to F");
+                self.tare = None;
+            }
+        }
+
+        self.buffer = ((reading - *offset) * parameters.scale, *offset);
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+
+    #[test]
+    fn test_tare_scale_default_buffer_no_panic() {
+        let block = TareScaleBlock::<f64>::default();
+        assert_eq!(block.buffer(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_tare_scale_calibration() {
+        let context = StubContext::default();
+        let params = Parameters::new(0.5, 1000.0, 1.0);
+        let mut block = TareScaleBlock::<f64>::default();
+
+        assert_eq!(
+            block.process(&params, &context, (1000.0, false)),
+            (0.0, 1000.0)
+        );
+        assert_eq!(
+            block.process(&params, &context, (1200.0, false)),
+            (100.0, 1000.0)
+        );
+        assert_eq!(
+            block.process(&params, &context, (800.0, false)),
+            (-100.0, 1000.0)
+        );
+    }
+
+    #[test]
+    fn test_tare_scale_tare() {
+        let context = StubContext::default();
+        let params = Parameters::new(2.0, 0.0, 3.0);
+        let mut block = TareScaleBlock::<f64>::default();
+
+        assert_eq!(block.process(&params, &context, (10.0, false)), (20.0, 0.0));
+
+        // Averaged over three readings, skipping NaN, and held while tare stays true
+        assert_eq!(block.process(&params, &context, (10.0, true)), (20.0, 0.0));
+        block.process(&params, &context, (f64::NAN, true));
+        assert_eq!(block.process(&params, &context, (12.0, true)), (24.0, 0.0));
+        assert_eq!(block.process(&params, &context, (14.0, true)), (4.0, 12.0));
+        assert_eq!(block.process(&params, &context, (13.0, true)), (2.0, 12.0));
+
+        // A new rising edge tares again
+        block.process(&params, &context, (13.0, false));
+        block.process(&params, &context, (13.0, true));
+        block.process(&params, &context, (13.0, false));
+        assert_eq!(block.process(&params, &context, (13.0, false)), (0.0, 13.0));
+    }
+
+    #[test]
+    fn test_tare_scale_invalid_parameters() {
+        assert!(Parameters::try_new(1.0, 0.0, 0.0).is_err());
+        assert!(Parameters::try_new(1.0, 0.0, 2.5).is_err());
+        assert!(Parameters::try_new(1.0, 0.0, f64::NAN).is_err());
+    }
+}
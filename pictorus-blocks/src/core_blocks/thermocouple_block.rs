@@ -0,0 +1,429 @@
+use crate::traits::Float;
+use crate::ParameterError;
+use pictorus_traits::{PassBy, ProcessBlock};
+
+/// NIST ITS-90 polynomial over a temperature (°C) or EMF (mV) range
+struct Segment {
+    /// Upper end of the range; the lower end is the previous segment's
+    upper: f64,
+    coefficients: &'static [f64],
+}
+
+/// NIST ITS-90 reference functions of a thermocouple type
+struct Tables {
+    /// Temperature range (°C) of the inverse functions
+    range: (f64, f64),
+    /// EMF (mV) as a function of temperature (°C)
+    emf: &'static [Segment],
+    /// Temperature (°C) as a function of EMF (mV)
+    temperature: &'static [Segment],
+}
+
+const TYPE_K: Tables = Tables {
+    range: (-200.0, 1372.0),
+    emf: &[
+        Segment {
+            upper: 0.0,
+            coefficients: &[
+                0.0,
+                0.394501280250E-01,
+                0.236223735980E-04,
+                -0.328589067840E-06,
+                -0.499048287770E-08,
+                -0.675090591730E-10,
+                -0.574103274280E-12,
+                -0.310888728940E-14,
+                -0.104516093650E-16,
+                -0.198892668780E-19,
+                -0.163226974860E-22,
+            ],
+        },
+        Segment {
+            upper: f64::INFINITY,
+            coefficients: &[
+                -0.176004136860E-01,
+                0.389212049750E-01,
+                0.185587700320E-04,
+                -0.994575928740E-07,
+                0.318409457190E-09,
+                -0.560728448890E-12,
+                0.560750590590E-15,
+                -0.320207200030E-18,
+                0.971511471520E-22,
+                -0.121047212750E-25,
+            ],
+        },
+    ],
+    temperature: &[
+        Segment {
+            upper: 0.0,
+            coefficients: &[
+                0.0,
+                2.5173462E+01,
+                -1.1662878E+00,
+                -1.0833638E+00,
+                -8.9773540E-01,
+                -3.7342377E-01,
+                -8.6632643E-02,
+                -1.0450598E-02,
+                -5.1920577E-04,
+            ],
+        },
+        Segment {
+            upper: 20.644,
+            coefficients: &[
+                0.0,
+                2.508355E+01,
+                7.860106E-02,
+                -2.503131E-01,
+                8.315270E-02,
+                -1.228034E-02,
+                9.804036E-04,
+                -4.413030E-05,
+                1.057734E-06,
+                -1.052755E-08,
+            ],
+        },
+        Segment {
+            upper: f64::INFINITY,
+            coefficients: &[
+                -1.318058E+02,
+                4.830222E+01,
+                -1.646031E+00,
+                5.464731E-02,
+                -9.650715E-04,
+                8.802193E-06,
+                -3.110810E-08,
+            ],
+        },
+    ],
+};
+
+/// Exponential term of the type K EMF function above 0°C
+const TYPE_K_EXPONENTIAL: (f64, f64, f64) = (0.118597600000E+00, -0.118343200000E-03, 126.9686);
+
+const TYPE_J: Tables = Tables {
+    range: (-210.0, 1200.0),
+    emf: &[
+        Segment {
+            upper: 760.0,
+            coefficients: &[
+                0.0,
+                0.503811878150E-01,
+                0.304758369300E-04,
+                -0.856810657200E-07,
+                0.132281952950E-09,
+                -0.170529583370E-12,
+                0.209480906970E-15,
+                -0.125383953360E-18,
+                0.156317256970E-22,
+            ],
+        },
+        Segment {
+            upper: f64::INFINITY,
+            coefficients: &[
+                0.296456256810E+03,
+                -0.149761277860E+01,
+                0.317871039240E-02,
+                -0.318476867010E-05,
+                0.157208190040E-08,
+                -0.306913690560E-12,
+            ],
+        },
+    ],
+    temperature: &[
+        Segment {
+            upper: 0.0,
+            coefficients: &[
+                0.0,
+                1.9528268E+01,
+                -1.2286185E+00,
+                -1.0752178E+00,
+                -5.9086933E-01,
+                -1.7256713E-01,
+                -2.8131513E-02,
+                -2.3963370E-03,
+                -8.3823321E-05,
+            ],
+        },
+        Segment {
+            upper: 42.919,
+            coefficients: &[
+                0.0,
+                1.978425E+01,
+                -2.001204E-01,
+                1.036969E-02,
+                -2.549687E-04,
+                3.585153E-06,
+                -5.344285E-08,
+                5.099890E-10,
+            ],
+        },
+        Segment {
+            upper: f64::INFINITY,
+            coefficients: &[
+                -3.11358187E+03,
+                3.00543684E+02,
+                -9.94773230E+00,
+                1.70276630E-01,
+                -1.43033468E-03,
+                4.73886084E-06,
+            ],
+        },
+    ],
+};
+
+const TYPE_T: Tables = Tables {
+    range: (-200.0, 400.0),
+    emf: &[
+        Segment {
+            upper: 0.0,
+            coefficients: &[
+                0.0,
+                0.387481063640E-01,
+                0.441944343470E-04,
+                0.118443231050E-06,
+                0.200329735540E-07,
+                0.901380195590E-09,
+                0.226511565930E-10,
+                0.360711542050E-12,
+                0.384939398830E-14,
+                0.282135219250E-16,
+                0.142515947790E-18,
+                0.487686622860E-21,
+                0.107955392700E-23,
+                0.139450270620E-26,
+                0.797951539270E-30,
+            ],
+        },
+        Segment {
+            upper: f64::INFINITY,
+            coefficients: &[
+                0.0,
+                0.387481063640E-01,
+                0.332922278800E-04,
+                0.206182434040E-06,
+                -0.218822568460E-08,
+                0.109968809280E-10,
+                -0.308157587720E-13,
+                0.454791352900E-16,
+                -0.275129016730E-19,
+            ],
+        },
+    ],
+    temperature: &[
+        Segment {
+            upper: 0.0,
+            coefficients: &[
+                0.0,
+                2.5949192E+01,
+                -2.1316967E-01,
+                7.9018692E-01,
+                4.2527777E-01,
+                1.3304473E-01,
+                2.0241446E-02,
+                1.2668171E-03,
+            ],
+        },
+        Segment {
+            upper: f64::INFINITY,
+            coefficients: &[
+                0.0,
+                2.592800E+01,
+                -7.602961E-01,
+                4.637791E-02,
+                -2.165394E-03,
+                6.048144E-05,
+                -7.293422E-07,
+            ],
+        },
+    ],
+};
+
+fn evaluate(segments: &[Segment], x: f64) -> f64 {
+    // The last segment is unbounded, so this always finds one
+    segments
+        .iter()
+        .find(|segment| x <= segment.upper)
+        .or(segments.last())
+        .map_or(0.0, |segment| {
+            segment
+                .coefficients
+                .iter()
+                .rev()
+                .fold(0.0, |acc, coefficient| acc * x + coefficient)
+        })
+}
+
+/// Thermocouple types supported by the ThermocoupleBlock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThermocoupleType {
+    K,
+    J,
+    T,
+}
+
+impl ThermocoupleType {
+    fn tables(self) -> &'static Tables {
+        match self {
+            Self::K => &TYPE_K,
+            Self::J => &TYPE_J,
+            Self::T => &TYPE_T,
+        }
+    }
+
+    /// Thermocouple EMF (mV) at a temperature (°C), relative to a 0°C junction
+    fn emf(self, temperature: f64) -> f64 {
+        let emf = evaluate(self.tables().emf, temperature);
+        if self == Self::K && temperature > 0.0 {
+            let (a0, a1, a2) = TYPE_K_EXPONENTIAL;
+            emf + a0 * num_traits::Float::exp(a1 * (temperature - a2) * (temperature - a2))
+        } else {
+            emf
+        }
+    }
+}
+
+/// Parameters for the ThermocoupleBlock
+pub struct Parameters {
+    pub thermocouple_type: ThermocoupleType,
+}
+
+impl Parameters {
+    pub fn new(thermocouple_type: &str) -> Self {
+        Self::try_new(thermocouple_type).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(thermocouple_type: &str) -> Result<Self, ParameterError> {
+        let thermocouple_type = match thermocouple_type {
+            "K" => ThermocoupleType::K,
+            "J" => ThermocoupleType::J,
+            "T" => ThermocoupleType::T,
+            _ => return Err(ParameterError("Thermocouple type must be one of K, J or T")),
+        };
+        Ok(Self { thermocouple_type })
+    }
+}
+
+/// Converts a thermocouple voltage to temperature using the NIST ITS-90 reference functions.
+///
+/// The inputs are (thermocouple voltage in volts, cold junction temperature in °C). The cold
+/// junction is where the thermocouple wires meet the copper of the measuring circuit, and its
+/// temperature usually comes from a sensor next to the terminals. Its EMF is added to the
+/// measured voltage before converting, so the result is the hot junction temperature.
+///
+/// The output is a tuple of (temperature in °C, in range). Outside the range of the reference
+/// functions the temperature is extrapolated, and in range is false.
+pub struct ThermocoupleBlock<F: Float> {
+    buffer: (F, bool),
+}
+
+impl<F: Float> Default for ThermocoupleBlock<F> {
+    fn default() -> Self {
+        Self {
+            buffer: (F::zero(), false),
+        }
+    }
+}
+
+impl<F: Float> ProcessBlock for ThermocoupleBlock<F> {
+    type Inputs = (F, F);
+    type Output = (F, bool);
+    type Parameters = Parameters;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (voltage, cold_junction) = inputs;
+        let thermocouple_type = parameters.thermocouple_type;
+        let voltage = voltage.to_f64().unwrap_or(f64::NAN);
+        let cold_junction = cold_junction.to_f64().unwrap_or(f64::NAN);
+
+        let emf = voltage * 1000.0 + thermocouple_type.emf(cold_junction);
+        let temperature = evaluate(thermocouple_type.tables().temperature, emf);
+        let (lower, upper) = thermocouple_type.tables().range;
+        self.buffer = (
+            F::from(temperature).unwrap_or(F::nan()),
+            (lower..=upper).contains(&temperature),
+        );
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use approx::assert_abs_diff_eq;
+
+    fn process(thermocouple_type: &str, millivolts: f64, cold_junction: f64) -> (f64, bool) {
+        let params = Parameters::new(thermocouple_type);
+        let mut block = ThermocoupleBlock::<f64>::default();
+        block.process(
+            &params,
+            &StubContext::default(),
+            (millivolts / 1000.0, cold_junction),
+        )
+    }
+
+    #[test]
+    fn test_thermocouple_default_buffer_no_panic() {
+        let block = ThermocoupleBlock::<f64>::default();
+        assert_eq!(block.buffer(), (0.0, false));
+    }
+
+    #[test]
+    fn test_thermocouple_reference_tables() {
+        // Points from the NIST ITS-90 tables, with a 0°C cold junction. The inverse functions
+        // are accurate to within 0.1°C.
+        let points = [
+            ("K", -5.891, -200.0),
+            ("K", 4.096, 100.0),
+            ("K", 20.644, 500.0),
+            ("K", 41.276, 1000.0),
+            ("J", -7.890, -200.0),
+            ("J", 5.269, 100.0),
+            ("J", 27.393, 500.0),
+            ("J", 57.953, 1000.0),
+            ("T", -5.603, -200.0),
+            ("T", 4.279, 100.0),
+            ("T", 20.872, 400.0),
+        ];
+        for (thermocouple_type, millivolts, expected) in points {
+            let (temperature, in_range) = process(thermocouple_type, millivolts, 0.0);
+            assert_abs_diff_eq!(temperature, expected, epsilon = 0.1);
+            assert!(in_range);
+        }
+    }
+
+    #[test]
+    fn test_thermocouple_cold_junction() {
+        // At a 25°C cold junction a type K at 100°C measures 4.096 - 1.000 mV
+        let (temperature, _) = process("K", 3.096, 25.0);
+        assert_abs_diff_eq!(temperature, 100.0, epsilon = 0.1);
+
+        // Hot and cold junctions at the same temperature measure 0V
+        for thermocouple_type in ["K", "J", "T"] {
+            let (temperature, _) = process(thermocouple_type, 0.0, 30.0);
+            assert_abs_diff_eq!(temperature, 30.0, epsilon = 0.05);
+        }
+    }
+
+    #[test]
+    fn test_thermocouple_range() {
+        let (_, in_range) = process("T", 25.0, 0.0);
+        assert!(!in_range);
+        let (temperature, in_range) = process("K", f64::NAN, 0.0);
+        assert!(temperature.is_nan());
+        assert!(!in_range);
+        assert!(Parameters::try_new("X").is_err());
+    }
+}
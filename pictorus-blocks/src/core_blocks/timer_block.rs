@@ -1,3 +1,4 @@
+use crate::ParameterError;
 use pictorus_traits::{PassBy, ProcessBlock};
 
 use crate::traits::{Float, Scalar};
@@ -21,8 +22,28 @@ pub struct Parameters<O: Float> {
 
 impl<O: Float> Parameters<O> {
     pub fn new(method: &str, interruptable: bool, countdown_time_s: O) -> Parameters<O> {
+        Self::try_new(method, interruptable, countdown_time_s).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(
+        method: &str,
+        interruptable: bool,
+        countdown_time_s: O,
+    ) -> Result<Self, ParameterError> {
+        Ok(Parameters {
+            method: method
+                .parse()
+                .map_err(|_| ParameterError("Failed to parse Timer Method"))?,
+            interruptable,
+            countdown_time_s,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved timer method, without parsing
+    pub const fn from_method(method: Method, interruptable: bool, countdown_time_s: O) -> Self {
         Parameters {
-            method: method.parse().expect("Failed to parse Timer Method"),
+            method,
             interruptable,
             countdown_time_s,
         }
@@ -136,6 +157,17 @@ mod tests {
         assert_eq!(block.buffer(), 0.0);
     }
 
+    #[test]
+    fn test_timer_typed_parameters() {
+        static PARAMETERS: Parameters<f64> = Parameters::from_method(Method::CountDown, false, 5.0);
+        let mut runtime = StubRuntime::default();
+        let mut block = TimerBlock::<f64>::default();
+
+        block.process(&PARAMETERS, &runtime.context(), 0.0);
+        runtime.set_time(time::Duration::from_secs_f64(1.0));
+        assert_eq!(block.process(&PARAMETERS, &runtime.context(), 1.0), 5.0);
+    }
+
     #[test]
     fn test_countdown_timer_non_interruptable() {
         let mut runtime = StubRuntime::default();
@@ -1,3 +1,4 @@
+use crate::dsp::DspFloat;
 use crate::traits::{Float, MatrixOps};
 use heapless::Deque;
 use num_traits::Zero;
@@ -93,25 +94,20 @@ macro_rules! impl_transfer_function {
                 _context: &dyn pictorus_traits::Context,
                 input: PassBy<Self::Inputs>,
             ) -> PassBy<'_, Self::Output> {
+                // None of the pushes can fail, as each deque is popped below its capacity every tick
                 if self.input.is_empty() {
                     for _ in 0..(NUM_SIZE - 1) {
-                        self.input.push_front(<$type>::zero()).expect(
-                            "Failed to push to samples when initializing TransferFunctionBlock",
-                        );
+                        let _ = self.input.push_front(<$type>::zero());
                     }
                 }
 
                 if self.output.is_empty() {
                     for _ in 0..DEN_SIZE {
-                        self.output.push_front(<$type>::zero()).expect(
-                            "Failed to push to samples when initializing TransferFunctionBlock",
-                        );
+                        let _ = self.output.push_front(<$type>::zero());
                     }
                 }
 
-                self.input
-                    .push_front(input)
-                    .expect("Failed to push to samples in TransferFunctionBlock");
+                let _ = self.input.push_front(input);
 
                 // as_mut_slices() seems to mess up the operation of the queue, clone it
                 // on the stack and work with the clone
@@ -122,26 +118,24 @@ macro_rules! impl_transfer_function {
                 let (output_front, _) = output_clone.as_mut_slices();
 
                 // input_front at this point is x[n], x[n-1], x[n-2], ...
-                let mut x_z = <$type>::zero();
-                for (i, n) in parameters.numerators.iter().enumerate() {
-                    x_z += *n * input_front[i];
-                }
+                let x_z = <$type>::dot(
+                    &parameters.numerators,
+                    input_front.get(..NUM_SIZE).unwrap_or_default(),
+                );
 
                 // output_front at this point is y[n-1], y[n-2], y[n-3], ...
                 // Skip the 0th element of the denominator BUT grab the
                 // y[n-1] element when it is time to calculate y[n]
-                let mut y_z = <$type>::zero();
-                for (i, d) in parameters.denominators.iter().enumerate().skip(1) {
-                    y_z -= *d * output_front[i - 1];
-                }
+                let y_z = -<$type>::dot(
+                    parameters.denominators.get(1..).unwrap_or_default(),
+                    output_front.get(..DEN_SIZE - 1).unwrap_or_default(),
+                );
 
                 // y[n]
                 self.buffer = x_z + y_z;
 
                 self.output.pop_back();
-                self.output
-                    .push_front(self.buffer)
-                    .expect("Failed to push to output sample in TransferFunctionBlock");
+                let _ = self.output.push_front(self.buffer);
 
                 self.input.pop_back();
 
@@ -173,25 +167,20 @@ macro_rules! impl_transfer_function {
                 _context: &dyn pictorus_traits::Context,
                 input: PassBy<Self::Inputs>,
             ) -> PassBy<'_, Self::Output> {
+                // None of the pushes can fail, as each deque is popped below its capacity every tick
                 if self.input.is_empty() {
                     for _ in 0..(NUM_SIZE - 1) {
-                        self.input.push_front(Matrix::zeroed()).expect(
-                            "Failed to push to samples when initializing TransferFunctionBlock",
-                        );
+                        let _ = self.input.push_front(Matrix::zeroed());
                     }
                 }
 
                 if self.output.is_empty() {
                     for _ in 0..DEN_SIZE {
-                        self.output.push_front(Matrix::zeroed()).expect(
-                            "Failed to push to samples when initializing TransferFunctionBlock",
-                        );
+                        let _ = self.output.push_front(Matrix::zeroed());
                     }
                 }
 
-                self.input
-                    .push_front(*input)
-                    .expect("Failed to push to samples in TransferFunctionBlock");
+                let _ = self.input.push_front(*input);
 
                 // as_mut_slices() seems to mess up the operation of the queue, clone it
                 // on the stack and work with the clone
@@ -202,9 +191,9 @@ macro_rules! impl_transfer_function {
                 let (output_front, _) = output_clone.as_mut_slices();
 
                 let mut x_z = Matrix::zeroed();
-                for (i, matrix) in input_front.iter().enumerate() {
+                for (matrix, numerator) in input_front.iter().zip(&parameters.numerators) {
                     matrix.for_each(|f, c, r| {
-                        x_z.data[c][r] += parameters.numerators[i] * f;
+                        x_z.data[c][r] += *numerator * f;
                     });
                 }
 
@@ -212,8 +201,13 @@ macro_rules! impl_transfer_function {
                 // output_front at this point is y[n-1], y[n-2], y[n-3], ...
                 // Skip the 0th element of the denominator BUT grab the
                 // y[n-1] element when it is time to calculate y[n]
-                for (i, d) in parameters.denominators.iter().enumerate().skip(1) {
-                    output_front[i - 1].for_each(|f, c, r| {
+                for (d, output) in parameters
+                    .denominators
+                    .iter()
+                    .skip(1)
+                    .zip(output_front.iter())
+                {
+                    output.for_each(|f, c, r| {
                         y_z.data[c][r] -= *d * f;
                     });
                 }
@@ -222,9 +216,7 @@ macro_rules! impl_transfer_function {
                 self.buffer = x_z.map_collect(|f, c, r| f + y_z.data[c][r]);
 
                 self.output.pop_back();
-                self.output
-                    .push_front(self.buffer)
-                    .expect("Failed to push to output sample in TransferFunctionBlock");
+                let _ = self.output.push_front(self.buffer);
 
                 self.input.pop_back();
 
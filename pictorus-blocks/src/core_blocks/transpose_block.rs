@@ -1,4 +1,6 @@
 use crate::matrix_ext::MatrixNalgebraExt;
+#[cfg(feature = "alloc")]
+use pictorus_traits::DMatrix;
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
 
 use crate::traits::Scalar;
@@ -76,6 +78,23 @@ impl<const NROWS: usize, const NCOLS: usize, S: Scalar> Apply for Matrix<NROWS,
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<S: Scalar> Apply for DMatrix<S> {
+    type Output = DMatrix<S>;
+
+    fn apply<'s>(store: &'s mut Self::Output, input: PassBy<Self>) -> PassBy<'s, Self::Output> {
+        let (nrows, ncols) = (input.nrows(), input.ncols());
+        store.reshape_zeroed(ncols, nrows);
+        let output = store.as_mut_slice();
+        for (col, column) in input.as_slice().chunks_exact(nrows.max(1)).enumerate() {
+            for (row, value) in column.iter().enumerate() {
+                output[row * ncols + col] = *value;
+            }
+        }
+        store
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::StubContext;
@@ -122,4 +141,21 @@ mod tests {
         assert_eq!(output.data, expected.data);
         assert_eq!(transpose_block.buffer().data, expected.data);
     }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_transpose_dmatrix_input() {
+        let ctxt = StubContext::default();
+        let params = Parameters::default();
+        let mut transpose_block = TransposeBlock::<DMatrix<f64>>::default();
+
+        let input = DMatrix::from_column_slice(3, 2, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+        let output = transpose_block.process(&params, &ctxt, &input);
+        assert_eq!((output.nrows(), output.ncols()), (2, 3));
+        assert_eq!(output.as_slice(), &[1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+
+        let empty = DMatrix::zeroed(0, 4);
+        let output = transpose_block.process(&params, &ctxt, &empty);
+        assert_eq!((output.nrows(), output.ncols()), (4, 0));
+    }
 }
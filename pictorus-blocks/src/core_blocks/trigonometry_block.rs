@@ -1,4 +1,6 @@
+use crate::fast_math::FastMath;
 use crate::traits::MatrixOps;
+use crate::ParameterError;
 use num_traits::Float;
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
 
@@ -24,11 +26,27 @@ pub struct Parameters {
 
 impl Parameters {
     pub fn new(function: &str) -> Self {
-        Self {
+        Self::try_new(function).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(function: &str) -> Result<Self, ParameterError> {
+        Ok(Self {
             function: function
                 .parse()
-                .expect("Failed to parse TrigonometryFunction"),
-        }
+                .map_err(|_| ParameterError("Failed to parse TrigonometryFunction"))?,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved function, without parsing
+    pub const fn from_function(function: TrigonometryFunction) -> Self {
+        Self { function }
+    }
+}
+
+impl From<TrigonometryFunction> for Parameters {
+    fn from(function: TrigonometryFunction) -> Self {
+        Self::from_function(function)
     }
 }
 
@@ -61,12 +79,12 @@ macro_rules! impl_trig_block {
                 inputs: PassBy<'_, Self::Inputs>,
             ) -> PassBy<'_, Self::Output> {
                 let output = match parameters.function {
-                    TrigonometryFunction::Sine => Float::sin(inputs),
-                    TrigonometryFunction::Cosine => Float::cos(inputs),
-                    TrigonometryFunction::Tangent => Float::tan(inputs),
+                    TrigonometryFunction::Sine => FastMath::fast_sin(inputs),
+                    TrigonometryFunction::Cosine => FastMath::fast_cos(inputs),
+                    TrigonometryFunction::Tangent => FastMath::fast_tan(inputs),
                     TrigonometryFunction::ArcSine => Float::asin(inputs),
                     TrigonometryFunction::ArcCosine => Float::acos(inputs),
-                    TrigonometryFunction::ArcTangent => Float::atan(inputs),
+                    TrigonometryFunction::ArcTangent => FastMath::fast_atan(inputs),
                     TrigonometryFunction::SineHyperbolic => Float::sinh(inputs),
                     TrigonometryFunction::CosineHyperbolic => Float::cosh(inputs),
                     TrigonometryFunction::TangentHyperbolic => Float::tanh(inputs),
@@ -98,12 +116,12 @@ macro_rules! impl_trig_block {
             ) -> PassBy<'_, Self::Output> {
                 inputs.for_each(|input, c, r| {
                     let output = match parameters.function {
-                        TrigonometryFunction::Sine => Float::sin(input),
-                        TrigonometryFunction::Cosine => Float::cos(input),
-                        TrigonometryFunction::Tangent => Float::tan(input),
+                        TrigonometryFunction::Sine => FastMath::fast_sin(input),
+                        TrigonometryFunction::Cosine => FastMath::fast_cos(input),
+                        TrigonometryFunction::Tangent => FastMath::fast_tan(input),
                         TrigonometryFunction::ArcSine => Float::asin(input),
                         TrigonometryFunction::ArcCosine => Float::acos(input),
-                        TrigonometryFunction::ArcTangent => Float::atan(input),
+                        TrigonometryFunction::ArcTangent => FastMath::fast_atan(input),
                         TrigonometryFunction::SineHyperbolic => Float::sinh(input),
                         TrigonometryFunction::CosineHyperbolic => Float::cosh(input),
                         TrigonometryFunction::TangentHyperbolic => Float::tanh(input),
@@ -130,7 +148,7 @@ impl_trig_block!(f32);
 mod tests {
     extern crate std;
     use super::*;
-    use crate::testing::StubContext;
+    use crate::testing::{fast_math_epsilon, StubContext};
     use approx::assert_relative_eq;
     use core::f64::consts::PI;
     use rstest::rstest;
@@ -176,8 +194,18 @@ mod tests {
         let p = Parameters::new(function);
 
         let output = block.process(&p, &c, input);
-        assert_relative_eq!(output, expected, max_relative = 0.00001);
-        assert_relative_eq!(block.buffer(), expected, max_relative = 0.00001);
+        assert_relative_eq!(
+            output,
+            expected,
+            epsilon = fast_math_epsilon(f64::EPSILON),
+            max_relative = 0.00001
+        );
+        assert_relative_eq!(
+            block.buffer(),
+            expected,
+            epsilon = fast_math_epsilon(f64::EPSILON),
+            max_relative = 0.00001
+        );
         assert_eq!(block.buffer(), output);
     }
 
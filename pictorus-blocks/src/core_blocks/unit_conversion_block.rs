@@ -0,0 +1,162 @@
+use core::marker::PhantomData;
+
+use pictorus_traits::units::{conversion_factor, Degree, Knot, MetersPerSecond, Radian, Unit};
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock, UnitTagged};
+
+use crate::traits::Float;
+
+/// Parameters for the UnitConversionBlock. The conversion is fixed by the block's units.
+#[derive(Default)]
+pub struct Parameters {}
+
+impl Parameters {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Converts a scalar, vector or matrix from one unit of measure to another.
+///
+/// Both units are part of the block's type, and must measure the same dimension: a block
+/// converting degrees to knots doesn't compile, and neither does connecting its input to a signal
+/// in any unit other than `From`.
+pub struct UnitConversionBlock<T, From: Unit, To: Unit<Dimension = From::Dimension>> {
+    buffer: UnitTagged<T, To>,
+    from: PhantomData<fn() -> From>,
+}
+
+/// Converts degrees to radians
+pub type DegToRadBlock<T> = UnitConversionBlock<T, Degree, Radian>;
+/// Converts radians to degrees
+pub type RadToDegBlock<T> = UnitConversionBlock<T, Radian, Degree>;
+/// Converts meters per second to knots
+pub type MpsToKnotBlock<T> = UnitConversionBlock<T, MetersPerSecond, Knot>;
+/// Converts knots to meters per second
+pub type KnotToMpsBlock<T> = UnitConversionBlock<T, Knot, MetersPerSecond>;
+
+impl<T, From, To> Default for UnitConversionBlock<T, From, To>
+where
+    T: Default,
+    From: Unit,
+    To: Unit<Dimension = From::Dimension>,
+{
+    fn default() -> Self {
+        Self {
+            buffer: UnitTagged::default(),
+            from: PhantomData,
+        }
+    }
+}
+
+impl<F, From, To> ProcessBlock for UnitConversionBlock<F, From, To>
+where
+    F: Float,
+    From: Unit,
+    To: Unit<Dimension = From::Dimension>,
+{
+    type Inputs = UnitTagged<F, From>;
+    type Output = UnitTagged<F, To>;
+    type Parameters = Parameters;
+
+    fn process(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<Self::Inputs>,
+    ) -> PassBy<'_, Self::Output> {
+        let factor = F::from(conversion_factor::<From, To>()).unwrap_or_else(F::nan);
+        self.buffer = UnitTagged::new(input.value * factor);
+        self.buffer
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer
+    }
+}
+
+impl<const ROWS: usize, const COLS: usize, F, From, To> ProcessBlock
+    for UnitConversionBlock<Matrix<ROWS, COLS, F>, From, To>
+where
+    F: Float,
+    From: Unit,
+    To: Unit<Dimension = From::Dimension>,
+{
+    type Inputs = UnitTagged<Matrix<ROWS, COLS, F>, From>;
+    type Output = UnitTagged<Matrix<ROWS, COLS, F>, To>;
+    type Parameters = Parameters;
+
+    fn process(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<Self::Inputs>,
+    ) -> PassBy<'_, Self::Output> {
+        let factor = F::from(conversion_factor::<From, To>()).unwrap_or_else(F::nan);
+        self.buffer.value.data = input.value.data;
+        self.buffer
+            .value
+            .data
+            .as_flattened_mut()
+            .iter_mut()
+            .for_each(|x| *x *= factor);
+        self.buffer.as_by()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer.as_by()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_unit_conversion_default_buffer_no_panic() {
+        let block = DegToRadBlock::<f64>::default();
+        assert_eq!(block.buffer().value, 0.0);
+
+        let block = MpsToKnotBlock::<Matrix<2, 2, f32>>::default();
+        assert_eq!(block.buffer().value.data, [[0.0; 2]; 2]);
+    }
+
+    #[test]
+    fn test_unit_conversion_scalar() {
+        let context = StubContext::default();
+        let parameters = Parameters::new();
+
+        let mut block = DegToRadBlock::<f64>::default();
+        let output = block.process(&parameters, &context, UnitTagged::new(90.0));
+        assert_relative_eq!(output.value, core::f64::consts::FRAC_PI_2);
+        assert_eq!(output.symbol(), "rad");
+
+        let mut block = RadToDegBlock::<f32>::default();
+        let output = block.process(
+            &parameters,
+            &context,
+            UnitTagged::new(core::f32::consts::PI),
+        );
+        assert_relative_eq!(output.value, 180.0);
+
+        let mut block = KnotToMpsBlock::<f64>::default();
+        let output = block.process(&parameters, &context, UnitTagged::new(3600.0));
+        assert_relative_eq!(output.value, 1852.0);
+        assert_relative_eq!(block.buffer().value, 1852.0);
+    }
+
+    #[test]
+    fn test_unit_conversion_matrix() {
+        let context = StubContext::default();
+        let parameters = Parameters::new();
+        let mut block = MpsToKnotBlock::<Matrix<1, 2, f64>>::default();
+        let input = UnitTagged::new(Matrix {
+            data: [[1852.0 / 3600.0], [-1.0]],
+        });
+        let output = block.process(&parameters, &context, input.as_by());
+        assert_relative_eq!(output.value.data[0][0], 1.0);
+        assert_relative_eq!(output.value.data[1][0], -1.9438444924406, epsilon = 1e-12);
+        assert_eq!(output.symbol(), "kt");
+    }
+}
@@ -1,22 +1,39 @@
+use crate::ParameterError;
 use pictorus_traits::{
     tuple_array_interop::TupleEquivalent, Matrix, Pass, PassBy, ProcessBlock, Scalar,
 };
 
 /// An array of indices used to extract individual values from the input matrix. Invalid string values
-/// will cause a panic when parsed.
+/// will cause a panic when parsed with `new`, or an error with `try_new`.
 pub struct Parameters<const N: usize> {
     indices: [usize; N],
 }
 
 impl<const N: usize> Parameters<N> {
     pub fn new<S: AsRef<str>>(index_values: &[S]) -> Self {
+        Self::try_new(index_values).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new<S: AsRef<str>>(index_values: &[S]) -> Result<Self, ParameterError> {
         let mut indices = [0; N];
         for (i, index) in index_values.iter().enumerate() {
-            indices[i] = index.as_ref().split(":").last().unwrap().parse().expect(
-                "Failed to parse index in VectorIndexBlock Parameters, check indices for validity",
-            );
+            // The part after the last `:`, found bytewise as `core`'s string searchers can
+            // reach a panic
+            let index = index.as_ref();
+            let index = match index.bytes().rposition(|byte| byte == b':') {
+                Some(colon) => index.get(colon + 1..).unwrap_or_default(),
+                None => index,
+            };
+            *indices.get_mut(i).ok_or(ParameterError(
+                "Too many indices in VectorIndexBlock Parameters",
+            ))? = index.parse().map_err(|_| {
+                ParameterError(
+                    "Failed to parse index in VectorIndexBlock Parameters, check indices for validity",
+                )
+            })?;
         }
-        Parameters { indices }
+        Ok(Parameters { indices })
     }
 }
 
@@ -1,4 +1,6 @@
 use crate::matrix_ext::MatrixNalgebraExt;
+#[cfg(feature = "alloc")]
+use pictorus_traits::DMatrix;
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
 
 pub struct Parameters {}
@@ -112,6 +114,21 @@ macro_rules! impl_vector_norm {
                 n
             }
         }
+
+        #[cfg(feature = "alloc")]
+        impl Apply for DMatrix<$type> {
+            type Output = $type;
+
+            fn apply<'s>(
+                store: &'s mut Self::Output,
+                input: PassBy<Self>,
+            ) -> PassBy<'s, Self::Output> {
+                let n = nalgebra::DVectorView::from_slice(input.as_slice(), input.as_slice().len())
+                    .norm();
+                *store = n;
+                n
+            }
+        }
     };
 }
 
@@ -173,4 +190,18 @@ mod tests {
     test_vector_norm!(u32);
     test_vector_norm!(f32);
     test_vector_norm!(f64);
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_vector_norm_dmatrix() {
+        let mut block = VectorNormBlock::<DMatrix<f64>>::default();
+        let p = Parameters::new();
+        let c = StubContext::default();
+
+        let input = DMatrix::from_column_slice(1, 2, &[3.0, 4.0]).unwrap();
+        assert_eq!(block.process(&p, &c, &input), 5.0);
+        let input = DMatrix::from_column_slice(2, 2, &[3.0, 3.0, 3.0, 3.0]).unwrap();
+        assert_eq!(block.process(&p, &c, &input), 6.0);
+        assert_eq!(block.process(&p, &c, &DMatrix::default()), 0.0);
+    }
 }
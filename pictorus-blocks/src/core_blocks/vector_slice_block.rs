@@ -1,3 +1,4 @@
+use crate::ParameterError;
 use crate::{traits::MatrixOps, Scalar};
 use num_traits::ToPrimitive;
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
@@ -12,16 +13,21 @@ pub struct Parameters {
 
 impl Parameters {
     pub fn new(rows: f64, cols: f64) -> Self {
-        let r_usize = rows
-            .to_usize()
-            .expect("Failed to convert rows to usize in VectorSliceBlock Parameters");
-        let c_usize = cols
-            .to_usize()
-            .expect("Failed to convert cols to usize in VectorSliceBlock Parameters");
-        Self {
+        Self::try_new(rows, cols).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(rows: f64, cols: f64) -> Result<Self, ParameterError> {
+        let r_usize = rows.to_usize().ok_or(ParameterError(
+            "Failed to convert rows to usize in VectorSliceBlock Parameters",
+        ))?;
+        let c_usize = cols.to_usize().ok_or(ParameterError(
+            "Failed to convert cols to usize in VectorSliceBlock Parameters",
+        ))?;
+        Ok(Self {
             rows: r_usize,
             cols: c_usize,
-        }
+        })
     }
 }
 
@@ -1,3 +1,4 @@
+use crate::ParameterError;
 use core::cmp::Ordering;
 
 use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
@@ -16,11 +17,27 @@ pub struct Parameters {
 
 impl Parameters {
     pub fn new(direction: &str) -> Self {
-        Self {
+        Self::try_new(direction).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(direction: &str) -> Result<Self, ParameterError> {
+        Ok(Self {
             direction: direction
                 .parse()
-                .expect("Failed to parse VectorSortDirection"),
-        }
+                .map_err(|_| ParameterError("Failed to parse VectorSortDirection"))?,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved sort direction, without parsing
+    pub const fn from_direction(direction: VectorSortDirection) -> Self {
+        Self { direction }
+    }
+}
+
+impl From<VectorSortDirection> for Parameters {
+    fn from(direction: VectorSortDirection) -> Self {
+        Self::from_direction(direction)
     }
 }
 
@@ -0,0 +1,295 @@
+use core::cmp::Ordering;
+
+use pictorus_traits::{Context, Matrix, Pass, PassBy, ProcessBlock};
+
+use crate::{traits::Float, ParameterError};
+
+/// How the VoterBlock combines the valid channels
+#[derive(strum::EnumString, Debug, Clone, Copy, PartialEq)]
+pub enum VoterMethod {
+    /// Element-wise median of the valid channels, which ignores a single outlier out of three
+    Median,
+    /// Element-wise mean of the valid channels
+    Average,
+}
+
+/// Parameters for the VoterBlock
+pub struct Parameters {
+    pub method: VoterMethod,
+    /// Largest difference between a channel and the voted value before it miscompares
+    pub tolerance: f64,
+}
+
+impl Parameters {
+    pub fn new(method: &str, tolerance: f64) -> Self {
+        Self::try_new(method, tolerance).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(method: &str, tolerance: f64) -> Result<Self, ParameterError> {
+        let method = method
+            .parse()
+            .map_err(|_| ParameterError("Invalid method, must be Median or Average"))?;
+        Self::try_from_method(method, tolerance)
+    }
+
+    /// Like [`Parameters::new`], but takes an already-resolved method
+    pub fn from_method(method: VoterMethod, tolerance: f64) -> Self {
+        Self::try_from_method(method, tolerance).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::from_method`], for builds where panics are unacceptable
+    pub const fn try_from_method(
+        method: VoterMethod,
+        tolerance: f64,
+    ) -> Result<Self, ParameterError> {
+        if tolerance.is_nan() || tolerance < 0.0 {
+            return Err(ParameterError(
+                "Voter tolerance must be a non-negative number",
+            ));
+        }
+        Ok(Self { method, tolerance })
+    }
+}
+
+/// Votes between N redundant sensors or computations, as in fault tolerant designs.
+///
+/// The block takes the channels as the columns of an `M`x`N` matrix, so N scalar channels are an
+/// N element vector, and whether each channel is valid. It outputs a tuple of (voted value,
+/// miscompare, excluded channel):
+/// - The voted value combines the valid channels element-wise with the chosen method.
+/// - Miscompare is true when any valid channel differs from the voted value by more than the
+///   tolerance in any element.
+/// - With at least three valid channels, the channel that differs the most is excluded on a
+///   miscompare and the vote is taken again without it. Its index is output as the excluded
+///   channel, which is -1 when none was. With only two valid channels there's no telling which
+///   one is wrong, so the vote stands.
+///
+/// Invalid channels, and channels with non-finite values, never count towards the vote. While
+/// no channel is valid, the last voted value is held.
+pub struct VoterBlock<const N: usize, const M: usize = 1, F: Float = f64> {
+    buffer: (Matrix<M, 1, F>, bool, F),
+}
+
+impl<const N: usize, const M: usize, F: Float> Default for VoterBlock<N, M, F> {
+    fn default() -> Self {
+        Self {
+            buffer: (Matrix::zeroed(), false, -F::one()),
+        }
+    }
+}
+
+impl<const N: usize, const M: usize, F: Float> VoterBlock<N, M, F> {
+    /// Votes element-wise between the channels in `included`, returning None if there are none
+    fn vote(
+        method: VoterMethod,
+        channels: &Matrix<M, N, F>,
+        included: &[bool; N],
+    ) -> Option<Matrix<M, 1, F>> {
+        let mut voted = Matrix::zeroed();
+        let mut values = [F::zero(); N];
+        for element in 0..M {
+            let mut count = 0;
+            let included_values = channels
+                .data
+                .iter()
+                .zip(included)
+                .filter(|(_, included)| **included)
+                .map(|(channel, _)| channel[element]);
+            for (slot, value) in values.iter_mut().zip(included_values) {
+                *slot = value;
+                count += 1;
+            }
+            let values = match values.get_mut(..count) {
+                Some(values) if count > 0 => values,
+                _ => return None,
+            };
+            voted.data[0][element] = match method {
+                VoterMethod::Average => {
+                    values.iter().fold(F::zero(), |sum, value| sum + *value)
+                        / F::from(count).unwrap_or_else(F::one)
+                }
+                VoterMethod::Median => {
+                    // Included values are finite, so they're totally ordered
+                    values.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+                    let middle = values.get((count - 1) / 2).zip(values.get(count / 2));
+                    middle.map_or(F::nan(), |(lower, upper)| {
+                        (*lower + *upper) / (F::one() + F::one())
+                    })
+                }
+            };
+        }
+        Some(voted)
+    }
+
+    /// Largest difference between a channel and the voted value, over every element
+    fn deviation(channels: &Matrix<M, N, F>, channel: usize, voted: &Matrix<M, 1, F>) -> F {
+        channels.data[channel]
+            .iter()
+            .zip(&voted.data[0])
+            .map(|(value, voted)| num_traits::Float::abs(*value - *voted))
+            .fold(F::zero(), num_traits::Float::max)
+    }
+}
+
+impl<const N: usize, const M: usize, F: Float> ProcessBlock for VoterBlock<N, M, F> {
+    type Parameters = Parameters;
+    type Inputs = (Matrix<M, N, F>, Matrix<1, N, bool>);
+    type Output = (Matrix<M, 1, F>, bool, F);
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let (channels, valid) = inputs;
+        let mut included = [false; N];
+        for (channel, included) in included.iter_mut().enumerate() {
+            *included = valid.data[channel][0]
+                && channels.data[channel]
+                    .iter()
+                    .all(|value| num_traits::Float::is_finite(*value));
+        }
+        self.buffer.1 = false;
+        self.buffer.2 = -F::one();
+
+        let Some(voted) = Self::vote(parameters.method, channels, &included) else {
+            return self.buffer();
+        };
+        self.buffer.0 = voted;
+
+        let worst = (0..N)
+            .filter(|channel| included[*channel])
+            .map(|channel| (channel, Self::deviation(channels, channel, &voted)))
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal));
+        if let Some((channel, deviation)) = worst {
+            if deviation > F::from(parameters.tolerance).unwrap_or_else(F::infinity) {
+                self.buffer.1 = true;
+                if included.iter().filter(|included| **included).count() >= 3 {
+                    if let Some(included) = included.get_mut(channel) {
+                        *included = false;
+                    }
+                    self.buffer.2 = F::from(channel).unwrap_or_else(F::zero);
+                    if let Some(voted) = Self::vote(parameters.method, channels, &included) {
+                        self.buffer.0 = voted;
+                    }
+                }
+            }
+        }
+
+        self.buffer()
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.buffer.as_by()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+
+    fn valid<const N: usize>(valid: [bool; N]) -> Matrix<1, N, bool> {
+        Matrix {
+            data: valid.map(|valid| [valid]),
+        }
+    }
+
+    fn scalars<const N: usize>(values: [f64; N]) -> Matrix<1, N, f64> {
+        Matrix {
+            data: values.map(|value| [value]),
+        }
+    }
+
+    #[test]
+    fn test_voter_default_buffer_no_panic() {
+        let block = VoterBlock::<3>::default();
+        assert_eq!(block.buffer(), (&Matrix::zeroed(), false, -1.0));
+    }
+
+    #[test]
+    fn test_voter_parameters_validation() {
+        assert!(Parameters::try_new("Median", 0.5).is_ok());
+        assert!(Parameters::try_new("Mode", 0.5).is_err());
+        assert!(Parameters::try_new("Average", -1.0).is_err());
+        assert!(Parameters::try_new("Average", f64::NAN).is_err());
+
+        const PARAMETERS: Result<Parameters, ParameterError> =
+            Parameters::try_from_method(VoterMethod::Average, 0.5);
+        assert!(PARAMETERS.is_ok_and(|p| p.method == VoterMethod::Average));
+        assert!(Parameters::try_from_method(VoterMethod::Median, -1.0).is_err());
+    }
+
+    #[test]
+    fn test_voter_median_excludes_outlier() {
+        let context = StubContext::default();
+        let parameters = Parameters::new("Median", 0.5);
+        let mut block = VoterBlock::<3>::default();
+
+        let (voted, miscompare, excluded) = block.process(
+            &parameters,
+            &context,
+            (&scalars([1.0, 1.2, 0.9]), &valid([true; 3])),
+        );
+        assert_eq!((voted.data[0][0], miscompare, excluded), (1.0, false, -1.0));
+
+        let (voted, miscompare, excluded) = block.process(
+            &parameters,
+            &context,
+            (&scalars([1.0, 9.0, 1.2]), &valid([true; 3])),
+        );
+        assert_eq!((voted.data[0][0], miscompare, excluded), (1.1, true, 1.0));
+
+        // Invalid channels are ignored, and two disagreeing channels can't exclude either
+        let (voted, miscompare, excluded) = block.process(
+            &parameters,
+            &context,
+            (&scalars([1.0, 9.0, 3.0]), &valid([true, false, true])),
+        );
+        assert_eq!((voted.data[0][0], miscompare, excluded), (2.0, true, -1.0));
+
+        // Non-finite values are never trusted
+        let (voted, miscompare, excluded) = block.process(
+            &parameters,
+            &context,
+            (&scalars([1.0, f64::NAN, 1.2]), &valid([true; 3])),
+        );
+        assert_eq!((voted.data[0][0], miscompare, excluded), (1.1, false, -1.0));
+
+        // The last vote is held without any valid channel
+        let (voted, miscompare, excluded) = block.process(
+            &parameters,
+            &context,
+            (&scalars([5.0, 5.0, 5.0]), &valid([false; 3])),
+        );
+        assert_eq!((voted.data[0][0], miscompare, excluded), (1.1, false, -1.0));
+    }
+
+    #[test]
+    fn test_voter_average_of_vectors() {
+        let context = StubContext::default();
+        let parameters = Parameters::new("Average", 1.0);
+        let mut block = VoterBlock::<4, 2, f32>::default();
+        // Each column is a channel
+        let channels = Matrix {
+            data: [[1.0, 10.0], [2.0, 11.0], [3.0, 12.0], [2.0, 20.0]],
+        };
+
+        let (voted, miscompare, excluded) =
+            block.process(&parameters, &context, (&channels, &valid([true; 4])));
+        assert_eq!(voted.data, [[2.0, 11.0]]);
+        assert!(miscompare);
+        assert_eq!(excluded, 3.0);
+
+        let (voted, miscompare, excluded) = block.process(
+            &parameters,
+            &context,
+            (&channels, &valid([true, true, true, false])),
+        );
+        assert_eq!(voted.data, [[2.0, 11.0]]);
+        assert!(!miscompare);
+        assert_eq!(excluded, -1.0);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::traits::Float;
+use pictorus_traits::{Matrix, Pass, PassBy, ProcessBlock};
+
+/// Parameters for the WaypointFollowerBlock
+pub struct Parameters<F: Float, const N: usize> {
+    /// Waypoints to visit in order, one `(x, y, z)` row per waypoint
+    pub waypoints: Matrix<N, 3, F>,
+    /// Distance from a waypoint at which it counts as reached
+    pub acceptance_radius: F,
+}
+
+impl<F: Float, const N: usize> Parameters<F, N> {
+    pub fn new(waypoints: Matrix<N, 3, F>, acceptance_radius: F) -> Self {
+        Self {
+            waypoints,
+            acceptance_radius,
+        }
+    }
+
+    /// The waypoint at `index`, or the last one if it's past the end
+    fn waypoint(&self, index: usize) -> [F; 3] {
+        core::array::from_fn(|axis| {
+            let column = &self.waypoints.data[axis];
+            column
+                .get(index)
+                .or(column.last())
+                .copied()
+                .unwrap_or_else(F::zero)
+        })
+    }
+}
+
+fn distance<F: Float>(a: &[F; 3], b: &[F; 3]) -> F {
+    let sum = a
+        .iter()
+        .zip(b)
+        .fold(<F as num_traits::Zero>::zero(), |acc, (a, b)| {
+            acc + (*a - *b) * (*a - *b)
+        });
+    num_traits::Float::sqrt(sum)
+}
+
+/// Distance from `point` to the segment from `start` to `end`
+fn segment_distance<F: Float>(point: &[F; 3], start: &[F; 3], end: &[F; 3]) -> F {
+    let zero = <F as num_traits::Zero>::zero();
+    let mut along = zero;
+    let mut length_squared = zero;
+    for axis in 0..3 {
+        let segment = end[axis] - start[axis];
+        along += (point[axis] - start[axis]) * segment;
+        length_squared += segment * segment;
+    }
+    let fraction = if length_squared > zero {
+        num_traits::Float::clamp(along / length_squared, zero, <F as num_traits::One>::one())
+    } else {
+        zero
+    };
+    let closest = core::array::from_fn(|axis| start[axis] + fraction * (end[axis] - start[axis]));
+    distance(point, &closest)
+}
+
+/// Steps through a list of waypoints as the current position reaches each of them.
+///
+/// The input is the current `(x, y, z)` position as a (1, 3) matrix. Once the position is within
+/// the acceptance radius of the active waypoint, the next waypoint becomes active. The output is a
+/// tuple of (active target, cross-track error, arrived):
+/// - The active target is the waypoint currently being flown/driven to, as a (1, 3) matrix
+/// - The cross-track error is the distance from the path segment between the previous waypoint and
+///   the active one. It is zero while heading to the first waypoint, since there is no path yet.
+/// - Arrived becomes true once the final waypoint has been reached, after which the final
+///   waypoint stays the active target.
+pub struct WaypointFollowerBlock<F: Float, const N: usize> {
+    index: usize,
+    buffer: (Matrix<1, 3, F>, F, bool),
+}
+
+impl<F: Float, const N: usize> Default for WaypointFollowerBlock<F, N> {
+    fn default() -> Self {
+        const {
+            assert!(
+                N > 0,
+                "WaypointFollowerBlock requires at least one waypoint"
+            );
+        }
+        Self {
+            index: 0,
+            buffer: (Matrix::zeroed(), F::default(), false),
+        }
+    }
+}
+
+impl<F: Float, const N: usize> ProcessBlock for WaypointFollowerBlock<F, N> {
+    type Inputs = Matrix<1, 3, F>;
+    type Output = (Matrix<1, 3, F>, F, bool);
+    type Parameters = Parameters<F, N>;
+
+    fn process<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        input: PassBy<'_, Self::Inputs>,
+    ) -> PassBy<'b, Self::Output> {
+        let position: [F; 3] = core::array::from_fn(|axis| input.data[axis][0]);
+        let (target, cross_track, arrived) = &mut self.buffer;
+
+        // Skip over every waypoint already within the acceptance radius
+        while !*arrived
+            && distance(&position, &parameters.waypoint(self.index)) <= parameters.acceptance_radius
+        {
+            if self.index + 1 < N {
+                self.index += 1;
+            } else {
+                *arrived = true;
+            }
+        }
+
+        let active = parameters.waypoint(self.index);
+        *cross_track = if self.index == 0 {
+            F::default()
+        } else {
+            segment_distance(&position, &parameters.waypoint(self.index - 1), &active)
+        };
+        for (axis, value) in target.data.iter_mut().enumerate() {
+            value[0] = active[axis];
+        }
+
+        (self.buffer.0.as_by(), self.buffer.1, self.buffer.2)
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        (self.buffer.0.as_by(), self.buffer.1, self.buffer.2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::StubContext;
+    use approx::assert_relative_eq;
+
+    fn position(x: f64, y: f64, z: f64) -> Matrix<1, 3, f64> {
+        Matrix {
+            data: [[x], [y], [z]],
+        }
+    }
+
+    #[test]
+    fn test_waypoint_follower_default_buffer() {
+        let block = WaypointFollowerBlock::<f64, 2>::default();
+        assert_eq!(block.buffer(), (&Matrix::zeroed(), 0.0, false));
+    }
+
+    #[test]
+    fn test_waypoint_follower() {
+        let ctxt = StubContext::default();
+        // Waypoints (0, 0, 0), (10, 0, 0), (10, 10, 5)
+        let waypoints = Matrix {
+            data: [[0.0, 10.0, 10.0], [0.0, 0.0, 10.0], [0.0, 0.0, 5.0]],
+        };
+        let params = Parameters::new(waypoints, 1.0);
+        let mut block = WaypointFollowerBlock::<f64, 3>::default();
+
+        // Heading to the first waypoint
+        let (target, cross_track, arrived) =
+            block.process(&params, &ctxt, &position(-5.0, 2.0, 0.0));
+        assert_eq!(target, &position(0.0, 0.0, 0.0));
+        assert_eq!(cross_track, 0.0);
+        assert!(!arrived);
+
+        // Reaching it activates the second, measuring cross-track error from the first leg
+        let (target, _, _) = block.process(&params, &ctxt, &position(0.5, 0.0, 0.0));
+        assert_eq!(target, &position(10.0, 0.0, 0.0));
+        let (_, cross_track, _) = block.process(&params, &ctxt, &position(4.0, 3.0, -4.0));
+        assert_relative_eq!(cross_track, 5.0);
+
+        // Past the end of the segment the error is the distance to the waypoint
+        let (target, cross_track, _) = block.process(&params, &ctxt, &position(13.0, -4.0, 0.0));
+        assert_eq!(target, &position(10.0, 0.0, 0.0));
+        assert_relative_eq!(cross_track, 5.0);
+
+        let (target, _, arrived) = block.process(&params, &ctxt, &position(10.0, 0.5, 0.0));
+        assert_eq!(target, &position(10.0, 10.0, 5.0));
+        assert!(!arrived);
+
+        let (target, _, arrived) = block.process(&params, &ctxt, &position(10.0, 10.0, 4.5));
+        assert_eq!(target, &position(10.0, 10.0, 5.0));
+        assert!(arrived);
+
+        // Arrival latches
+        let (_, _, arrived) = block.process(&params, &ctxt, &position(0.0, 0.0, 0.0));
+        assert!(arrived);
+    }
+
+    #[test]
+    fn test_waypoint_follower_skips_reached_waypoints() {
+        let ctxt = StubContext::default();
+        let waypoints = Matrix {
+            data: [[0.0, 0.5, 20.0], [0.0; 3], [0.0; 3]],
+        };
+        let params = Parameters::new(waypoints, 1.0);
+        let mut block = WaypointFollowerBlock::<f64, 3>::default();
+        let (target, cross_track, _) = block.process(&params, &ctxt, &position(0.0, 2.0, 0.0));
+        assert_eq!(target, &position(0.0, 0.0, 0.0));
+        assert_eq!(cross_track, 0.0);
+
+        let (target, cross_track, _) = block.process(&params, &ctxt, &position(0.0, 0.0, 0.0));
+        // Both the first and second waypoints were reached, and the position is behind the start
+        // of the active leg
+        assert_eq!(target, &position(20.0, 0.0, 0.0));
+        assert_eq!(cross_track, 0.5);
+    }
+}
@@ -0,0 +1,305 @@
+//! DSP kernels shared by the filter and spectral blocks.
+//!
+//! With the `cmsis-dsp` feature enabled on bare-metal Arm targets, `f32` kernels are routed through
+//! the CMSIS-DSP library, which uses the Cortex-M DSP/FPU instructions where available: dot
+//! products (`arm_dot_prod_f32`, which computes each FIR output, e.g. the numerator of the transfer
+//! function block), IIR filter sections (`arm_biquad_cascade_df2T_f32`) and real FFTs
+//! (`arm_rfft_fast_f32`).
+//! Everything else (other targets, other float types, or the feature disabled) uses the
+//! portable implementations below.
+use crate::traits::Float;
+
+pub trait DspFloat: Float {
+    /// Sum of the element-wise products of `a` and `b`, which must be the same length
+    fn dot(a: &[Self], b: &[Self]) -> Self;
+
+    /// Runs `input` through one biquad section in transposed direct form II, writing the filtered
+    /// samples to `output` (the same length) and updating `state`. The coefficients are
+    /// `[b0, b1, b2, a1, a2]`, with the feedback terms added as in CMSIS-DSP:
+    /// `y[n] = b0*x[n] + b1*x[n-1] + b2*x[n-2] + a1*y[n-1] + a2*y[n-2]`
+    fn biquad(coeffs: &[Self; 5], state: &mut [Self; 2], input: &[Self], output: &mut [Self]) {
+        biquad_portable(coeffs, state, input, output);
+    }
+
+    /// Forward DFT of the complex signal `in_re + j*in_im`. All slices must be the same length.
+    fn fft(in_re: &[Self], in_im: &[Self], out_re: &mut [Self], out_im: &mut [Self]) {
+        dft_portable(in_re, Some(in_im), out_re, out_im);
+    }
+
+    /// Forward DFT of a real signal. All slices must be the same length, and all `N`
+    /// bins are written (the upper half being the complex conjugate of the lower half).
+    fn real_fft(input: &[Self], out_re: &mut [Self], out_im: &mut [Self]) {
+        dft_portable(input, None, out_re, out_im);
+    }
+}
+
+fn dot_portable<F: Float>(a: &[F], b: &[F]) -> F {
+    debug_assert_eq!(a.len(), b.len());
+    a.iter()
+        .zip(b)
+        .fold(<F as num_traits::Zero>::zero(), |acc, (a, b)| acc + *a * *b)
+}
+
+fn biquad_portable<F: Float>(coeffs: &[F; 5], state: &mut [F; 2], input: &[F], output: &mut [F]) {
+    debug_assert_eq!(input.len(), output.len());
+    let [b0, b1, b2, a1, a2] = *coeffs;
+    for (x, y) in input.iter().zip(output) {
+        *y = b0 * *x + state[0];
+        state[0] = b1 * *x + a1 * *y + state[1];
+        state[1] = b2 * *x + a2 * *y;
+    }
+}
+
+/// One sample of the first order section `y[n] = b0*x[n] + b1*x[n-1] + a1*y[n-1]`, for filters
+/// whose coefficients change every tick (e.g. with the timestep), so the section's state is
+/// rebuilt from the previous input and output
+pub fn first_order<F: DspFloat>(
+    [b0, b1, a1]: [F; 3],
+    input: F,
+    prev_input: F,
+    prev_output: F,
+) -> F {
+    let zero = <F as num_traits::Zero>::zero();
+    let mut state = [b1 * prev_input + a1 * prev_output, zero];
+    let mut output = [zero];
+    F::biquad(&[b0, b1, zero, a1, zero], &mut state, &[input], &mut output);
+    output[0]
+}
+
+/// Out-of-place DFT. Uses an iterative radix-2 FFT when the length is a power of two,
+/// and falls back to a direct `O(N^2)` DFT otherwise.
+fn dft_portable<F: Float>(in_re: &[F], in_im: Option<&[F]>, out_re: &mut [F], out_im: &mut [F]) {
+    let n = in_re.len();
+    debug_assert!(out_re.len() == n && out_im.len() == n);
+    debug_assert!(in_im.is_none_or(|im| im.len() == n));
+    let zero = <F as num_traits::Zero>::zero();
+    // Indexing goes through `get` so the transform can't reach a panic in `panic-free` builds
+    let im_at = |i: usize| in_im.and_then(|im| im.get(i)).copied().unwrap_or(zero);
+    let angle = |k: usize, len: usize| {
+        -F::TAU * F::from(k).unwrap_or(zero) / F::from(len).unwrap_or_else(F::one)
+    };
+
+    if n <= 1 {
+        out_re.iter_mut().zip(in_re).for_each(|(v, x)| *v = *x);
+        out_im
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, v)| *v = im_at(i));
+        return;
+    }
+
+    if !n.is_power_of_two() {
+        for (k, (out_re, out_im)) in out_re.iter_mut().zip(out_im.iter_mut()).enumerate() {
+            let (mut re, mut im) = (zero, zero);
+            for (t, x_re) in in_re.iter().enumerate() {
+                let (sin, cos) = num_traits::Float::sin_cos(angle((k * t) % n, n));
+                re += *x_re * cos - im_at(t) * sin;
+                im += *x_re * sin + im_at(t) * cos;
+            }
+            *out_re = re;
+            *out_im = im;
+        }
+        return;
+    }
+
+    // Bit-reversed copy into the output, then butterflies in place
+    let bits = n.trailing_zeros();
+    for (i, x_re) in in_re.iter().enumerate() {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if let (Some(re), Some(im)) = (out_re.get_mut(j), out_im.get_mut(j)) {
+            *re = *x_re;
+            *im = im_at(i);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let half = len / 2;
+        for k in 0..half {
+            // Each twiddle is computed directly rather than by repeated multiplication to avoid
+            // accumulating rounding error, and is shared by every group in this stage
+            let (sin, cos) = num_traits::Float::sin_cos(angle(k, len));
+            for (re, im) in out_re
+                .chunks_exact_mut(len)
+                .zip(out_im.chunks_exact_mut(len))
+            {
+                let (Some((a_re, b_re)), Some((a_im, b_im))) =
+                    (re.split_at_mut_checked(half), im.split_at_mut_checked(half))
+                else {
+                    continue;
+                };
+                let (Some(a_re), Some(b_re), Some(a_im), Some(b_im)) = (
+                    a_re.get_mut(k),
+                    b_re.get_mut(k),
+                    a_im.get_mut(k),
+                    b_im.get_mut(k),
+                ) else {
+                    continue;
+                };
+                let t_re = *b_re * cos - *b_im * sin;
+                let t_im = *b_re * sin + *b_im * cos;
+                *b_re = *a_re - t_re;
+                *b_im = *a_im - t_im;
+                *a_re += t_re;
+                *a_im += t_im;
+            }
+        }
+        len *= 2;
+    }
+}
+
+impl DspFloat for f64 {
+    fn dot(a: &[Self], b: &[Self]) -> Self {
+        dot_portable(a, b)
+    }
+}
+
+#[cfg(not(all(feature = "cmsis-dsp", target_arch = "arm", target_os = "none")))]
+impl DspFloat for f32 {
+    fn dot(a: &[Self], b: &[Self]) -> Self {
+        dot_portable(a, b)
+    }
+}
+
+#[cfg(all(feature = "cmsis-dsp", target_arch = "arm", target_os = "none"))]
+impl DspFloat for f32 {
+    fn dot(a: &[Self], b: &[Self]) -> Self {
+        debug_assert_eq!(a.len(), b.len());
+        let len = a.len().min(b.len());
+        let mut result = 0.0;
+        // SAFETY: Both pointers are valid for `len` reads and `result` is a valid write location
+        unsafe {
+            cmsis_dsp_sys::arm_dot_prod_f32(a.as_ptr(), b.as_ptr(), len as u32, &mut result);
+        }
+        result
+    }
+
+    fn biquad(coeffs: &[Self; 5], state: &mut [Self; 2], input: &[Self], output: &mut [Self]) {
+        debug_assert_eq!(input.len(), output.len());
+        let len = input.len().min(output.len());
+        let mut instance =
+            core::mem::MaybeUninit::<cmsis_dsp_sys::arm_biquad_cascade_df2T_instance_f32>::uninit();
+        // SAFETY: The instance is initialized before use, a single stage reads 5 coefficients and
+        // updates 2 state values, and both buffers hold `len` elements
+        unsafe {
+            cmsis_dsp_sys::arm_biquad_cascade_df2T_init_f32(
+                instance.as_mut_ptr(),
+                1,
+                coeffs.as_ptr(),
+                state.as_mut_ptr(),
+            );
+            cmsis_dsp_sys::arm_biquad_cascade_df2T_f32(
+                instance.as_ptr(),
+                input.as_ptr(),
+                output.as_mut_ptr(),
+                len as u32,
+            );
+        }
+    }
+
+    fn real_fft(input: &[Self], out_re: &mut [Self], out_im: &mut [Self]) {
+        let n = input.len();
+        // CMSIS only provides tables for these lengths
+        if !(n.is_power_of_two() && (32..=4096).contains(&n)) {
+            return dft_portable(input, None, out_re, out_im);
+        }
+
+        // arm_rfft_fast_f32 modifies its input, so use `out_re` as the input scratch buffer and
+        // `out_im` for the packed output: [X0.re, X(N/2).re, X1.re, X1.im, X2.re, X2.im, ...]
+        out_re.copy_from_slice(input);
+        let mut instance =
+            core::mem::MaybeUninit::<cmsis_dsp_sys::arm_rfft_fast_instance_f32>::uninit();
+        // SAFETY: The instance is initialized before use, and both buffers hold `n` elements
+        unsafe {
+            cmsis_dsp_sys::arm_rfft_fast_init_f32(instance.as_mut_ptr(), n as u16);
+            cmsis_dsp_sys::arm_rfft_fast_f32(
+                instance.as_mut_ptr(),
+                out_re.as_mut_ptr(),
+                out_im.as_mut_ptr(),
+                0,
+            );
+        }
+
+        // Unpack in place. Reading index 2k+1 is always ahead of writing index k
+        let (dc, nyquist) = (out_im[0], out_im[1]);
+        for k in 1..n / 2 {
+            out_re[k] = out_im[2 * k];
+            out_im[k] = out_im[2 * k + 1];
+        }
+        out_re[0] = dc;
+        out_im[0] = 0.0;
+        out_re[n / 2] = nyquist;
+        out_im[n / 2] = 0.0;
+        for k in 1..n / 2 {
+            out_re[n - k] = out_re[k];
+            out_im[n - k] = -out_im[k];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_dot() {
+        assert_eq!(f64::dot(&[1.0, 2.0, 3.0], &[4.0, -5.0, 0.5]), -4.5);
+        assert_eq!(f32::dot(&[1.0, 2.0, 3.0], &[4.0, -5.0, 0.5]), -4.5);
+        assert_eq!(f64::dot(&[], &[]), 0.0);
+    }
+
+    #[test]
+    fn test_biquad() {
+        // y[n] = 0.5*x[n] + 0.25*x[n-1] + 0.5*y[n-1] - 0.25*y[n-2], split across two calls
+        let coeffs = [0.5, 0.25, 0.0, 0.5, -0.25];
+        let mut state = [0.0; 2];
+        let mut output = [0.0; 4];
+        f32::biquad(&coeffs, &mut state, &[1.0, 0.0], &mut output[..2]);
+        f32::biquad(&coeffs, &mut state, &[0.0, 2.0], &mut output[2..]);
+        assert_eq!(output, [0.5, 0.5, 0.125, 0.9375]);
+
+        // A first order section picks up from the previous sample
+        assert_eq!(first_order([0.5, -0.5, 0.5], 3.0f64, 1.0, 2.0), 2.0);
+    }
+
+    // Length 4 takes the radix-2 path and length 3 the direct DFT
+    #[test]
+    fn test_fft_power_of_two_and_direct() {
+        let input = [1.0, 2.0, 0.0, -1.0];
+        let (mut re, mut im) = ([0.0; 4], [0.0; 4]);
+        f64::real_fft(&input, &mut re, &mut im);
+        // X[k] = sum x[t] e^{-j2πkt/4}
+        let expected_re = [2.0, 1.0, 0.0, 1.0];
+        let expected_im = [0.0, -3.0, 0.0, 3.0];
+        for k in 0..4 {
+            assert_relative_eq!(re[k], expected_re[k], epsilon = 1e-12);
+            assert_relative_eq!(im[k], expected_im[k], epsilon = 1e-12);
+        }
+
+        let input = [1.0, 2.0, 3.0];
+        let (mut re, mut im) = ([0.0; 3], [0.0; 3]);
+        f64::real_fft(&input, &mut re, &mut im);
+        let s = 3.0f64.sqrt() / 2.0;
+        assert_relative_eq!(re[0], 6.0, epsilon = 1e-12);
+        assert_relative_eq!(re[1], -1.5, epsilon = 1e-12);
+        assert_relative_eq!(im[1], s, epsilon = 1e-12);
+        assert_relative_eq!(re[2], -1.5, epsilon = 1e-12);
+        assert_relative_eq!(im[2], -s, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_fft_complex_input() {
+        // A complex exponential at bin 3 of 8 puts all of its energy in that bin
+        let n = 8;
+        let in_re: [f64; 8] = core::array::from_fn(|t| (f64::TAU * 3.0 * t as f64 / 8.0).cos());
+        let in_im: [f64; 8] = core::array::from_fn(|t| (f64::TAU * 3.0 * t as f64 / 8.0).sin());
+        let (mut re, mut im) = ([0.0; 8], [0.0; 8]);
+        f64::fft(&in_re, &in_im, &mut re, &mut im);
+        for k in 0..n {
+            let expected = if k == 3 { n as f64 } else { 0.0 };
+            assert_relative_eq!(re[k], expected, epsilon = 1e-9);
+            assert_relative_eq!(im[k], 0.0, epsilon = 1e-9);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Packet format shared by the EncryptBlock and DecryptBlock.
+//!
+//! Each packet is the 12 byte nonce, the ChaCha20-Poly1305 ciphertext and the 16 byte
+//! authentication tag. The nonce is a 4 byte prefix identifying the sender followed by an
+//! 8 byte message counter, both big-endian, so receivers can reject replayed packets.
+use crate::ParameterError;
+
+pub(crate) const KEY_BYTES: usize = 32;
+pub(crate) const NONCE_BYTES: usize = 12;
+pub(crate) const TAG_BYTES: usize = 16;
+/// Bytes added to each payload by encryption
+pub const ENCRYPTION_OVERHEAD_BYTES: usize = NONCE_BYTES + TAG_BYTES;
+
+/// Parses a 256-bit key given as 64 hex characters
+pub(crate) fn parse_key(key: &str) -> Result<[u8; KEY_BYTES], ParameterError> {
+    let key = key.as_bytes();
+    if key.len() != KEY_BYTES * 2 {
+        return Err(ParameterError(
+            "Encryption key must be 64 hex characters (256 bits)",
+        ));
+    }
+
+    let mut parsed = [0; KEY_BYTES];
+    for (byte, digits) in parsed.iter_mut().zip(key.chunks_exact(2)) {
+        let digits = core::str::from_utf8(digits)
+            .map_err(|_| ParameterError("Encryption key must be hex"))?;
+        *byte = u8::from_str_radix(digits, 16)
+            .map_err(|_| ParameterError("Encryption key must be hex"))?;
+    }
+    Ok(parsed)
+}
+
+pub(crate) fn nonce(prefix: u32, counter: u64) -> [u8; NONCE_BYTES] {
+    let mut nonce = [0; NONCE_BYTES];
+    nonce[..4].copy_from_slice(&prefix.to_be_bytes());
+    nonce[4..].copy_from_slice(&counter.to_be_bytes());
+    nonce
+}
+
+/// Splits a nonce back into its prefix and counter
+pub(crate) fn split_nonce(nonce: &[u8; NONCE_BYTES]) -> (u32, u64) {
+    let [p0, p1, p2, p3, counter @ ..] = *nonce;
+    (
+        u32::from_be_bytes([p0, p1, p2, p3]),
+        u64::from_be_bytes(counter),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key() {
+        let key =
+            parse_key("000102030405060708090a0b0c0d0e0f101112131415161718191A1B1C1D1E1F").unwrap();
+        assert_eq!(key, core::array::from_fn(|i| i as u8));
+
+        assert!(parse_key("0001").is_err());
+        assert!(parse_key(&"zz".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn test_nonce_round_trip() {
+        let nonce = nonce(0xA1B2C3D4, 42);
+        assert_eq!(nonce, [0xA1, 0xB2, 0xC3, 0xD4, 0, 0, 0, 0, 0, 0, 0, 42]);
+        assert_eq!(split_nonce(&nonce), (0xA1B2C3D4, 42));
+    }
+}
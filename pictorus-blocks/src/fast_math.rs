@@ -0,0 +1,280 @@
+//! Elementary functions used by the trig and exp heavy blocks.
+//!
+//! By default these call libm (through `num_traits`). With the `fast-math` feature they use
+//! polynomial approximations instead, which are several times faster on FPUs without hardware
+//! transcendentals such as the Cortex-M4F. Maximum errors of the approximations, including
+//! rounding in the evaluation:
+//!
+//! | Function | Error bound |
+//! |----------|-------------|
+//! | `sin`, `cos` | 5e-7 absolute for `f32` and 1e-7 absolute for `f64`, for \|x\| ≤ 2π. Range reduction is done in working precision, so the error grows in proportion to \|x\| beyond that. |
+//! | `tan` | as `sin / cos`, so the relative error grows near the poles |
+//! | `atan`, `atan2` | 5e-7 absolute for `f32`, 1e-7 absolute for `f64` |
+//! | `exp` | 5e-7 relative for `f32`, 5e-8 relative for `f64`. Results that would be subnormal flush to zero. |
+//!
+//! NaN inputs produce NaN outputs in both modes, but other special cases (signed zeros, infinite
+//! inputs to `sin` and `cos`) aren't guaranteed to match libm with `fast-math` enabled.
+
+/// Elementary functions that switch to approximations with the `fast-math` feature. Named with a
+/// `fast_` prefix so they aren't confused with the exact `num_traits::Float` methods.
+pub trait FastMath: num_traits::Float {
+    fn fast_sin_cos(self) -> (Self, Self);
+
+    fn fast_sin(self) -> Self {
+        self.fast_sin_cos().0
+    }
+
+    fn fast_cos(self) -> Self {
+        self.fast_sin_cos().1
+    }
+
+    fn fast_tan(self) -> Self {
+        let (sin, cos) = self.fast_sin_cos();
+        sin / cos
+    }
+
+    fn fast_atan(self) -> Self;
+
+    fn fast_atan2(self, x: Self) -> Self;
+
+    fn fast_exp(self) -> Self;
+}
+
+#[cfg(not(feature = "fast-math"))]
+macro_rules! impl_fast_math {
+    ($ty:ident, $($layout:tt)*) => {
+        impl FastMath for $ty {
+            fn fast_sin_cos(self) -> (Self, Self) {
+                num_traits::Float::sin_cos(self)
+            }
+
+            fn fast_sin(self) -> Self {
+                num_traits::Float::sin(self)
+            }
+
+            fn fast_cos(self) -> Self {
+                num_traits::Float::cos(self)
+            }
+
+            fn fast_tan(self) -> Self {
+                num_traits::Float::tan(self)
+            }
+
+            fn fast_atan(self) -> Self {
+                num_traits::Float::atan(self)
+            }
+
+            fn fast_atan2(self, x: Self) -> Self {
+                num_traits::Float::atan2(self, x)
+            }
+
+            fn fast_exp(self) -> Self {
+                num_traits::Float::exp(self)
+            }
+        }
+    };
+}
+
+/// `$bits`, `$mantissa_bits` and `$exp_bias` describe the IEEE 754 layout of `$ty`, and
+/// `$max_ln` and `$min_ln` are the natural logs of its largest and smallest positive normal values
+#[cfg(feature = "fast-math")]
+macro_rules! impl_fast_math {
+    (
+        $ty:ident,
+        $bits:ty,
+        $mantissa_bits:literal,
+        $exp_bias:literal,
+        $max_ln:literal,
+        $min_ln:literal
+    ) => {
+        #[allow(clippy::excessive_precision)]
+        impl FastMath for $ty {
+            fn fast_sin_cos(self) -> (Self, Self) {
+                use core::$ty::consts::{FRAC_PI_2, PI, TAU};
+
+                // Reduce to [-pi, pi], then fold into [-pi/2, pi/2] where the Taylor series
+                // converge quickly. Folding mirrors sin and negates cos.
+                let r = self - TAU * num_traits::Float::round(self / TAU);
+                let (r, cos_sign) = if r > FRAC_PI_2 {
+                    (PI - r, -1.0)
+                } else if r < -FRAC_PI_2 {
+                    (-PI - r, -1.0)
+                } else {
+                    (r, 1.0)
+                };
+                let r2 = r * r;
+                // Truncation error is below (pi/2)^13 / 13! and (pi/2)^14 / 14! respectively
+                let sin = r
+                    * (1.0
+                        + r2 * (-1.0 / 6.0
+                            + r2 * (1.0 / 120.0
+                                + r2 * (-1.0 / 5040.0
+                                    + r2 * (1.0 / 362880.0 + r2 * (-1.0 / 39916800.0))))));
+                let cos = 1.0
+                    + r2 * (-1.0 / 2.0
+                        + r2 * (1.0 / 24.0
+                            + r2 * (-1.0 / 720.0
+                                + r2 * (1.0 / 40320.0
+                                    + r2 * (-1.0 / 3628800.0 + r2 * (1.0 / 479001600.0))))));
+                (sin, cos_sign * cos)
+            }
+
+            fn fast_atan(self) -> Self {
+                // Abramowitz & Stegun 4.4.49 on [-1, 1], with atan(x) = ±pi/2 - atan(1/x)
+                // outside it. Truncation error is below 2e-8.
+                let invert = num_traits::Float::abs(self) > 1.0;
+                let x = if invert { 1.0 / self } else { self };
+                let x2 = x * x;
+                let atan = x
+                    * (1.0
+                        + x2 * (-0.3333314528
+                            + x2 * (0.1999355085
+                                + x2 * (-0.1420889944
+                                    + x2 * (0.1065626393
+                                        + x2 * (-0.0752896400
+                                            + x2 * (0.0429096138
+                                                + x2 * (-0.0161657367 + x2 * 0.0028662257))))))));
+                if invert {
+                    num_traits::Float::copysign(core::$ty::consts::FRAC_PI_2, self) - atan
+                } else {
+                    atan
+                }
+            }
+
+            fn fast_atan2(self, x: Self) -> Self {
+                use core::$ty::consts::{FRAC_PI_2, PI};
+
+                let y = self;
+                if x == 0.0 {
+                    return if y > 0.0 {
+                        FRAC_PI_2
+                    } else if y < 0.0 {
+                        -FRAC_PI_2
+                    } else {
+                        y
+                    };
+                }
+                let atan = (y / x).fast_atan();
+                if x > 0.0 {
+                    atan
+                } else if y >= 0.0 {
+                    atan + PI
+                } else {
+                    atan - PI
+                }
+            }
+
+            fn fast_exp(self) -> Self {
+                // ln(2) split so that k * LN_2_HI is exact (Cody & Waite)
+                const LN_2_HI: $ty = 0.693145751953125;
+                const LN_2_LO: $ty = 1.4286068203094173e-6;
+
+                if self > $max_ln {
+                    return <$ty>::INFINITY;
+                }
+                if self < $min_ln {
+                    return 0.0;
+                }
+
+                // exp(x) = 2^k * exp(r) with |r| <= ln(2) / 2
+                let k = num_traits::Float::round(self * core::$ty::consts::LOG2_E);
+                let r = (self - k * LN_2_HI) - k * LN_2_LO;
+                // Truncation error is below (ln(2) / 2)^8 / 8! relative
+                let exp_r = 1.0
+                    + r * (1.0
+                        + r * (1.0 / 2.0
+                            + r * (1.0 / 6.0
+                                + r * (1.0 / 24.0
+                                    + r * (1.0 / 120.0
+                                        + r * (1.0 / 720.0 + r * (1.0 / 5040.0)))))));
+
+                // Scale by 2^k in two steps, so neither factor over- or underflows near the
+                // ends of the range
+                let k = k as i32;
+                let pow2 = |n: i32| <$ty>::from_bits(((n + $exp_bias) as $bits) << $mantissa_bits);
+                exp_r * pow2(k / 2) * pow2(k - k / 2)
+            }
+        }
+    };
+}
+
+impl_fast_math!(f32, u32, 23, 127, 88.72283, -87.33654);
+impl_fast_math!(f64, u64, 52, 1023, 709.782712893384, -708.3964185322641);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Largest error of `approx` against `exact` over a sweep of `[min, max]`, relative to the
+    /// exact value if `relative`
+    fn max_error<T: FastMath + Into<f64>>(
+        min: f64,
+        max: f64,
+        relative: bool,
+        approx: impl Fn(T) -> T,
+        exact: impl Fn(f64) -> f64,
+    ) -> f64 {
+        const STEPS: usize = 20_000;
+        (0..=STEPS)
+            .map(|i| {
+                let x = T::from(min + (max - min) * i as f64 / STEPS as f64).unwrap();
+                let expected = exact(x.into());
+                let error = (approx(x).into() - expected).abs();
+                if relative {
+                    error / expected.abs()
+                } else {
+                    error
+                }
+            })
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn test_sin_cos_error_bounds() {
+        use core::f64::consts::TAU;
+        assert!(max_error(-TAU, TAU, false, f32::fast_sin, f64::sin) < 5e-7);
+        assert!(max_error(-TAU, TAU, false, f32::fast_cos, f64::cos) < 5e-7);
+        assert!(max_error(-TAU, TAU, false, f64::fast_sin, f64::sin) < 1e-7);
+        assert!(max_error(-TAU, TAU, false, f64::fast_cos, f64::cos) < 1e-7);
+        // Beyond 2pi the error grows only through range reduction
+        assert!(max_error(-1000.0, 1000.0, false, f64::fast_sin, f64::sin) < 1e-7);
+    }
+
+    #[test]
+    fn test_atan_error_bounds() {
+        assert!(max_error(-50.0, 50.0, false, f32::fast_atan, f64::atan) < 5e-7);
+        assert!(max_error(-50.0, 50.0, false, f64::fast_atan, f64::atan) < 1e-7);
+
+        for (y, x) in [
+            (1.0, 1.0),
+            (1.0, -1.0),
+            (-1.0, -1.0),
+            (-2.0, 0.5),
+            (0.0, -3.0),
+        ] {
+            let error = f64::fast_atan2(y, x) - f64::atan2(y, x);
+            assert!(error.abs() < 1e-7, "atan2({y}, {x})");
+        }
+        assert_eq!(f64::fast_atan2(2.0, 0.0), core::f64::consts::FRAC_PI_2);
+        assert_eq!(f64::fast_atan2(-2.0, 0.0), -core::f64::consts::FRAC_PI_2);
+        assert_eq!(f64::fast_atan2(0.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_exp_error_bounds() {
+        assert!(max_error(-80.0, 80.0, true, f32::fast_exp, f64::exp) < 5e-7);
+        assert!(max_error(-700.0, 700.0, true, f64::fast_exp, f64::exp) < 5e-8);
+        assert_eq!(f32::fast_exp(100.0), f32::INFINITY);
+        assert_eq!(f64::fast_exp(-800.0), 0.0);
+        assert_eq!(f64::fast_exp(0.0), 1.0);
+    }
+
+    #[test]
+    fn test_nan_propagates() {
+        assert!(f32::NAN.fast_sin().is_nan());
+        assert!(f64::NAN.fast_cos().is_nan());
+        assert!(f64::NAN.fast_atan().is_nan());
+        assert!(f64::fast_atan2(f64::NAN, 1.0).is_nan());
+        assert!(f32::NAN.fast_exp().is_nan());
+    }
+}
@@ -0,0 +1,229 @@
+//! WGS-84 geodesy shared by the geodetic conversion blocks.
+//!
+//! Everything is computed in `f64`, since single precision can't resolve positions on the
+//! scale of the Earth much better than a meter. Latitudes and longitudes are in degrees
+//! and altitudes are height above the ellipsoid in meters.
+use num_traits::Float;
+use pictorus_traits::Matrix;
+
+/// WGS-84 semi-major axis, in meters
+pub(crate) const SEMI_MAJOR_AXIS: f64 = 6_378_137.0;
+/// WGS-84 flattening
+pub(crate) const FLATTENING: f64 = 1.0 / 298.257_223_563;
+/// WGS-84 first eccentricity squared
+pub(crate) const ECCENTRICITY_SQUARED: f64 = FLATTENING * (2.0 - FLATTENING);
+
+/// Mean Earth radius (IUGG), in meters, used for great-circle calculations
+pub(crate) const MEAN_RADIUS: f64 = 6_371_008.8;
+
+/// Great-circle distance in meters and initial bearing in radians (clockwise from north, in
+/// `[0, 2 * pi)`) from `[lat, lon]` `from` to `to`, using the haversine formula
+pub(crate) fn haversine(from: [f64; 2], to: [f64; 2]) -> (f64, f64) {
+    let (lat1, lat2) = (from[0].to_radians(), to[0].to_radians());
+    let delta_lat = lat2 - lat1;
+    let delta_lon = (to[1] - from[1]).to_radians();
+    let (sin_lat1, cos_lat1) = Float::sin_cos(lat1);
+    let (sin_lat2, cos_lat2) = Float::sin_cos(lat2);
+
+    let half_lat = Float::sin(delta_lat / 2.0);
+    let half_lon = Float::sin(delta_lon / 2.0);
+    let a = half_lat * half_lat + cos_lat1 * cos_lat2 * half_lon * half_lon;
+    // Clamping guards against rounding pushing `a` just past 1 for antipodal points
+    let distance = 2.0 * MEAN_RADIUS * Float::asin(Float::sqrt(Float::clamp(a, 0.0, 1.0)));
+
+    let (sin_delta_lon, cos_delta_lon) = Float::sin_cos(delta_lon);
+    let bearing = Float::atan2(
+        sin_delta_lon * cos_lat2,
+        cos_lat1 * sin_lat2 - sin_lat1 * cos_lat2 * cos_delta_lon,
+    );
+    let bearing = if bearing < 0.0 {
+        bearing + core::f64::consts::TAU
+    } else {
+        bearing
+    };
+    (distance, bearing)
+}
+
+/// Converts a `[lat, lon, alt]` position to Earth-centered, Earth-fixed `[x, y, z]` meters
+pub(crate) fn lla_to_ecef(lla: [f64; 3]) -> [f64; 3] {
+    let [lat, lon, alt] = lla;
+    let (sin_lat, cos_lat) = Float::sin_cos(lat.to_radians());
+    let (sin_lon, cos_lon) = Float::sin_cos(lon.to_radians());
+    let prime_vertical =
+        SEMI_MAJOR_AXIS / Float::sqrt(1.0 - ECCENTRICITY_SQUARED * sin_lat * sin_lat);
+    [
+        (prime_vertical + alt) * cos_lat * cos_lon,
+        (prime_vertical + alt) * cos_lat * sin_lon,
+        (prime_vertical * (1.0 - ECCENTRICITY_SQUARED) + alt) * sin_lat,
+    ]
+}
+
+/// Converts an Earth-centered, Earth-fixed `[x, y, z]` position to `[lat, lon, alt]`
+pub(crate) fn ecef_to_lla(ecef: [f64; 3]) -> [f64; 3] {
+    let [x, y, z] = ecef;
+    let p = Float::hypot(x, y);
+    let lon = Float::atan2(y, x);
+    // Fixed point iteration on latitude converges to well under a millimeter within a few
+    // iterations anywhere near the surface of the Earth
+    let mut lat = Float::atan2(z, p * (1.0 - ECCENTRICITY_SQUARED));
+    let mut alt = 0.0;
+    for _ in 0..6 {
+        let (sin_lat, cos_lat) = Float::sin_cos(lat);
+        let prime_vertical =
+            SEMI_MAJOR_AXIS / Float::sqrt(1.0 - ECCENTRICITY_SQUARED * sin_lat * sin_lat);
+        // This form of the height stays well conditioned at the poles
+        alt = p * cos_lat + z * sin_lat
+            - SEMI_MAJOR_AXIS * Float::sqrt(1.0 - ECCENTRICITY_SQUARED * sin_lat * sin_lat);
+        lat = Float::atan2(
+            z,
+            p * (1.0 - ECCENTRICITY_SQUARED * prime_vertical / (prime_vertical + alt)),
+        );
+    }
+    [lat.to_degrees(), lon.to_degrees(), alt]
+}
+
+/// Reads a (1, 3) vector input into `f64`
+pub(crate) fn vector_to_f64<F: crate::traits::Float>(vector: &Matrix<1, 3, F>) -> [f64; 3] {
+    core::array::from_fn(|i| {
+        num_traits::ToPrimitive::to_f64(&vector.data[i][0]).unwrap_or(f64::NAN)
+    })
+}
+
+/// Writes an `f64` result into a (1, 3) vector output
+pub(crate) fn vector_from_f64<F: crate::traits::Float>(
+    values: [f64; 3],
+    vector: &mut Matrix<1, 3, F>,
+) {
+    for (output, value) in vector.data.iter_mut().zip(values) {
+        output[0] = <F as num_traits::NumCast>::from(value).expect("f64 converts to float");
+    }
+}
+
+/// A local north-east-down frame tangent to the ellipsoid at an origin
+pub(crate) struct NedFrame {
+    origin_ecef: [f64; 3],
+    sin_lat: f64,
+    cos_lat: f64,
+    sin_lon: f64,
+    cos_lon: f64,
+}
+
+impl NedFrame {
+    pub fn new(origin: [f64; 3]) -> Self {
+        let (sin_lat, cos_lat) = Float::sin_cos(origin[0].to_radians());
+        let (sin_lon, cos_lon) = Float::sin_cos(origin[1].to_radians());
+        Self {
+            origin_ecef: lla_to_ecef(origin),
+            sin_lat,
+            cos_lat,
+            sin_lon,
+            cos_lon,
+        }
+    }
+
+    pub fn ecef_to_ned(&self, ecef: [f64; 3]) -> [f64; 3] {
+        let dx = ecef[0] - self.origin_ecef[0];
+        let dy = ecef[1] - self.origin_ecef[1];
+        let dz = ecef[2] - self.origin_ecef[2];
+        let horizontal = self.cos_lon * dx + self.sin_lon * dy;
+        [
+            -self.sin_lat * horizontal + self.cos_lat * dz,
+            -self.sin_lon * dx + self.cos_lon * dy,
+            -self.cos_lat * horizontal - self.sin_lat * dz,
+        ]
+    }
+
+    pub fn ned_to_ecef(&self, ned: [f64; 3]) -> [f64; 3] {
+        let [north, east, down] = ned;
+        let horizontal = -self.sin_lat * north - self.cos_lat * down;
+        [
+            self.origin_ecef[0] + self.cos_lon * horizontal - self.sin_lon * east,
+            self.origin_ecef[1] + self.sin_lon * horizontal + self.cos_lon * east,
+            self.origin_ecef[2] + self.cos_lat * north - self.sin_lat * down,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_haversine() {
+        // A degree of longitude along the equator, heading east
+        let (distance, bearing) = haversine([0.0, 0.0], [0.0, 1.0]);
+        assert_relative_eq!(
+            distance,
+            MEAN_RADIUS * core::f64::consts::PI / 180.0,
+            epsilon = 1e-6
+        );
+        assert_relative_eq!(bearing, core::f64::consts::FRAC_PI_2, epsilon = 1e-12);
+
+        // Due south, and the same point
+        let (_, bearing) = haversine([10.0, 20.0], [5.0, 20.0]);
+        assert_relative_eq!(bearing, core::f64::consts::PI, epsilon = 1e-12);
+        assert_eq!(haversine([10.0, 20.0], [10.0, 20.0]).0, 0.0);
+
+        // Antipodal points are half the circumference apart
+        let (distance, _) = haversine([30.0, 40.0], [-30.0, -140.0]);
+        assert_relative_eq!(
+            distance,
+            MEAN_RADIUS * core::f64::consts::PI,
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn test_lla_to_ecef_reference_points() {
+        let ecef = lla_to_ecef([0.0, 0.0, 0.0]);
+        assert_relative_eq!(ecef[0], SEMI_MAJOR_AXIS, epsilon = 1e-6);
+        assert_relative_eq!(ecef[1], 0.0, epsilon = 1e-6);
+        assert_relative_eq!(ecef[2], 0.0, epsilon = 1e-6);
+
+        // North pole, at the semi-minor axis
+        let ecef = lla_to_ecef([90.0, 0.0, 100.0]);
+        assert_relative_eq!(ecef[0], 0.0, epsilon = 1e-6);
+        assert_relative_eq!(
+            ecef[2],
+            SEMI_MAJOR_AXIS * (1.0 - FLATTENING) + 100.0,
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn test_ecef_round_trip() {
+        for lla in [
+            [37.7749, -122.4194, 30.0],
+            [-33.8688, 151.2093, -20.0],
+            [89.9999, 45.0, 1000.0],
+            [0.0, 180.0, 35_786_000.0],
+        ] {
+            let result = ecef_to_lla(lla_to_ecef(lla));
+            assert_relative_eq!(result[0], lla[0], epsilon = 1e-9);
+            assert_relative_eq!(result[1], lla[1], epsilon = 1e-9);
+            assert_relative_eq!(result[2], lla[2], epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_ned_frame() {
+        let origin = [45.0, 10.0, 200.0];
+        let frame = NedFrame::new(origin);
+        let ned = frame.ecef_to_ned(lla_to_ecef(origin));
+        ned.iter()
+            .for_each(|v| assert_relative_eq!(*v, 0.0, epsilon = 1e-6));
+
+        // Straight up is negative down
+        let ned = frame.ecef_to_ned(lla_to_ecef([45.0, 10.0, 300.0]));
+        assert_relative_eq!(ned[0], 0.0, epsilon = 1e-6);
+        assert_relative_eq!(ned[1], 0.0, epsilon = 1e-6);
+        assert_relative_eq!(ned[2], -100.0, epsilon = 1e-6);
+
+        let ned = [120.0, -45.0, 3.0];
+        let result = frame.ecef_to_ned(frame.ned_to_ecef(ned));
+        for (actual, expected) in result.iter().zip(ned) {
+            assert_relative_eq!(*actual, expected, epsilon = 1e-6);
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Framing shared by the hardware-in-the-loop (HIL) bridge blocks.
+//!
+//! A HIL frame is two sync bytes, a kind byte (0 for sensor values sent by the simulator, 1 for
+//! actuator values sent back by the target), a little-endian u16 sequence number, a count byte,
+//! that many little-endian f32 values and a little-endian CRC-16/CCITT-FALSE over everything
+//! after the sync bytes. Targets answer each sensor frame with an actuator frame carrying the
+//! same sequence number, so the simulator can tell how far behind the target is.
+use alloc::vec::Vec;
+use log::debug;
+
+use crate::byte_data::BUFF_SIZE_BYTES;
+
+const SYNC: [u8; 2] = [0xA5, 0x5A];
+/// Sync bytes, kind, sequence and count
+const HEADER_LEN: usize = 6;
+const CRC_LEN: usize = 2;
+
+/// Kinds of HIL frame
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum HilFrameKind {
+    Sensors = 0,
+    Actuators = 1,
+}
+
+/// CRC-16/CCITT-FALSE
+fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0xFFFF, |mut crc, &byte| {
+        crc ^= u16::from(byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+/// Appends a complete frame carrying `values` to `out`. At most 255 values are sent.
+pub(crate) fn write_frame(kind: HilFrameKind, sequence: u16, values: &[f64], out: &mut Vec<u8>) {
+    let start = out.len();
+    let values = &values[..values.len().min(u8::MAX as usize)];
+    out.extend_from_slice(&SYNC);
+    out.push(kind as u8);
+    out.extend_from_slice(&sequence.to_le_bytes());
+    out.push(values.len() as u8);
+    for &value in values {
+        out.extend_from_slice(&(value as f32).to_le_bytes());
+    }
+    let crc = crc16(&out[start + SYNC.len()..]);
+    out.extend_from_slice(&crc.to_le_bytes());
+}
+
+/// A decoded HIL frame
+#[derive(Debug, PartialEq)]
+pub(crate) struct HilFrame {
+    pub kind: HilFrameKind,
+    pub sequence: u16,
+    pub values: Vec<f64>,
+}
+
+impl Default for HilFrame {
+    fn default() -> Self {
+        Self {
+            kind: HilFrameKind::Sensors,
+            sequence: 0,
+            values: Vec::new(),
+        }
+    }
+}
+
+/// Reassembles frames from bytes that may arrive split across several ticks
+#[derive(Default)]
+pub(crate) struct HilFrameReader {
+    buffer: Vec<u8>,
+}
+
+impl HilFrameReader {
+    pub fn push(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+        if self.buffer.len() >= BUFF_SIZE_BYTES * 4 {
+            debug!("Read too many bytes without a complete HIL frame. Clearing buffer");
+            self.buffer.clear();
+        }
+    }
+
+    /// Decodes the next complete, valid frame into `frame`. Returns false if no complete frame
+    /// is buffered yet. Malformed frames are skipped.
+    pub fn next_frame(&mut self, frame: &mut HilFrame) -> bool {
+        loop {
+            let Some(start) = self.buffer.windows(SYNC.len()).position(|w| w == SYNC) else {
+                // Keep a trailing first sync byte, which may start a frame
+                let keep = usize::from(self.buffer.last() == Some(&SYNC[0]));
+                self.buffer.drain(..self.buffer.len() - keep);
+                return false;
+            };
+            self.buffer.drain(..start);
+            if self.buffer.len() < HEADER_LEN {
+                return false;
+            }
+
+            let kind = match self.buffer[2] {
+                0 => HilFrameKind::Sensors,
+                1 => HilFrameKind::Actuators,
+                kind => {
+                    debug!("Invalid HIL frame kind {kind}, resynchronizing");
+                    self.buffer.drain(..1);
+                    continue;
+                }
+            };
+            let count = usize::from(self.buffer[5]);
+            let len = HEADER_LEN + count * 4 + CRC_LEN;
+            if self.buffer.len() < len {
+                return false;
+            }
+
+            let crc = u16::from_le_bytes([self.buffer[len - 2], self.buffer[len - 1]]);
+            if crc16(&self.buffer[SYNC.len()..len - CRC_LEN]) != crc {
+                debug!("HIL frame CRC mismatch, resynchronizing");
+                self.buffer.drain(..1);
+                continue;
+            }
+
+            frame.kind = kind;
+            frame.sequence = u16::from_le_bytes([self.buffer[3], self.buffer[4]]);
+            frame.values.clear();
+            frame.values.extend(
+                self.buffer[HEADER_LEN..len - CRC_LEN]
+                    .chunks_exact(4)
+                    .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()) as f64),
+            );
+            self.buffer.drain(..len);
+            return true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc16() {
+        // Check value of CRC-16/CCITT-FALSE
+        assert_eq!(crc16(b"123456789"), 0x29B1);
+    }
+
+    #[test]
+    fn test_write_frame() {
+        let mut out = Vec::new();
+        write_frame(HilFrameKind::Actuators, 0x0102, &[1.0], &mut out);
+        assert_eq!(
+            out[..10],
+            [0xA5, 0x5A, 0x01, 0x02, 0x01, 0x01, 0, 0, 0x80, 0x3F]
+        );
+        assert_eq!(out.len(), 12);
+    }
+
+    #[test]
+    fn test_frame_reader_round_trip() {
+        let mut encoded = Vec::new();
+        write_frame(HilFrameKind::Sensors, 7, &[1.5, -2.0], &mut encoded);
+        write_frame(HilFrameKind::Actuators, 8, &[], &mut encoded);
+
+        let mut reader = HilFrameReader::default();
+        let mut frame = HilFrame::default();
+        // Split mid-frame, with leading garbage
+        reader.push(&[0x00, 0xA5]);
+        reader.push(&encoded[..5]);
+        assert!(!reader.next_frame(&mut frame));
+        reader.push(&encoded[5..]);
+
+        assert!(reader.next_frame(&mut frame));
+        assert_eq!(
+            frame,
+            HilFrame {
+                kind: HilFrameKind::Sensors,
+                sequence: 7,
+                values: alloc::vec![1.5, -2.0],
+            }
+        );
+        assert!(reader.next_frame(&mut frame));
+        assert_eq!((frame.kind, frame.sequence), (HilFrameKind::Actuators, 8));
+        assert!(frame.values.is_empty());
+        assert!(!reader.next_frame(&mut frame));
+    }
+
+    #[test]
+    fn test_frame_reader_skips_bad_crc() {
+        let mut encoded = Vec::new();
+        write_frame(HilFrameKind::Sensors, 1, &[3.0], &mut encoded);
+        encoded[7] ^= 0xFF;
+        write_frame(HilFrameKind::Sensors, 2, &[4.0], &mut encoded);
+
+        let mut reader = HilFrameReader::default();
+        let mut frame = HilFrame::default();
+        reader.push(&encoded);
+        assert!(reader.next_frame(&mut frame));
+        assert_eq!((frame.sequence, frame.values[0]), (2, 4.0));
+    }
+}
@@ -44,11 +44,25 @@ pub use std_blocks::*;
 
 #[cfg(feature = "alloc")]
 pub mod byte_data;
+mod dsp;
+#[cfg(feature = "encryption")]
+mod encryption;
+#[cfg(feature = "encryption")]
+pub use encryption::ENCRYPTION_OVERHEAD_BYTES;
+pub mod fast_math;
+mod geodesy;
+#[cfg(feature = "alloc")]
+mod hil;
 mod matrix_ext;
 pub use matrix_ext::{MatrixExt, MatrixNalgebraExt};
+mod path_tracking;
+pub mod signal_bus;
+pub use signal_bus::Topic;
 mod stale_tracker;
 pub(crate) mod traits;
 pub use traits::Scalar;
+#[cfg(feature = "alloc")]
+mod xbee;
 
 #[cfg(any(test, doctest))]
 mod testing;
@@ -56,3 +70,16 @@ mod testing;
 #[derive(Debug)]
 /// Error raised when parsing an enum from a string fails.
 pub struct ParseEnumError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Error raised when block parameters are invalid, such as an unrecognized method name.
+///
+/// Parameters that can be invalid provide a `try_new` constructor that returns this error
+/// instead of panicking, for builds where panics are unacceptable (see the `panic-free` feature).
+pub struct ParameterError(pub &'static str);
+
+impl core::fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.0)
+    }
+}
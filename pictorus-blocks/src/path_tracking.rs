@@ -0,0 +1,143 @@
+//! Path geometry shared by the ground vehicle path-tracking blocks.
+//!
+//! Paths are (P, 2) matrices holding one `(x, y)` point per row, connected by straight segments.
+use crate::traits::Float;
+use pictorus_traits::Matrix;
+
+/// The closest point on a path to some position
+pub(crate) struct Projection<F: Float> {
+    /// Index of the segment holding the closest point, which runs from point `segment` to
+    /// point `segment + 1`
+    pub segment: usize,
+    /// The closest point on the path
+    pub point: [F; 2],
+    /// Distance from the path, positive when the position is to the left of the direction of travel
+    pub cross_track: F,
+    /// Heading of the segment holding the closest point, in radians
+    pub heading: F,
+}
+
+pub(crate) fn point<F: Float, const P: usize>(path: &Matrix<P, 2, F>, index: usize) -> [F; 2] {
+    [path.data[0][index], path.data[1][index]]
+}
+
+pub(crate) fn distance<F: Float>(a: &[F; 2], b: &[F; 2]) -> F {
+    num_traits::Float::hypot(b[0] - a[0], b[1] - a[1])
+}
+
+/// Wraps an angle to `[-pi, pi]`
+pub(crate) fn wrap_angle<F: Float>(angle: F) -> F {
+    let (sin, cos) = num_traits::Float::sin_cos(angle);
+    num_traits::Float::atan2(sin, cos)
+}
+
+/// Projects `position` onto the closest segment of `path`, which must have at least two points
+pub(crate) fn project<F: Float, const P: usize>(
+    path: &Matrix<P, 2, F>,
+    position: &[F; 2],
+) -> Projection<F> {
+    let zero = <F as num_traits::Zero>::zero();
+    let one = <F as num_traits::One>::one();
+    let mut best: Option<(F, Projection<F>)> = None;
+    for segment in 0..P.saturating_sub(1) {
+        let start = point(path, segment);
+        let end = point(path, segment + 1);
+        let (dx, dy) = (end[0] - start[0], end[1] - start[1]);
+        let length_squared = dx * dx + dy * dy;
+        let fraction = if length_squared > zero {
+            let along = (position[0] - start[0]) * dx + (position[1] - start[1]) * dy;
+            num_traits::Float::clamp(along / length_squared, zero, one)
+        } else {
+            zero
+        };
+        let closest = [start[0] + fraction * dx, start[1] + fraction * dy];
+        let distance = distance(position, &closest);
+        if best.as_ref().is_some_and(|(best, _)| *best <= distance) {
+            continue;
+        }
+        // The sign of the cross product of the segment and the offset gives the side of the path
+        let side = dx * (position[1] - start[1]) - dy * (position[0] - start[0]);
+        let cross_track = if side < zero { -distance } else { distance };
+        best = Some((
+            distance,
+            Projection {
+                segment,
+                point: closest,
+                cross_track,
+                heading: num_traits::Float::atan2(dy, dx),
+            },
+        ));
+    }
+    best.map(|(_, projection)| projection)
+        .expect("Path must have at least two points")
+}
+
+/// The point `lookahead` further along `path` from `projection`, or the end of the path if
+/// it is closer than that
+pub(crate) fn lookahead_point<F: Float, const P: usize>(
+    path: &Matrix<P, 2, F>,
+    projection: &Projection<F>,
+    lookahead: F,
+) -> [F; 2] {
+    let mut remaining = lookahead;
+    let mut from = projection.point;
+    for index in projection.segment + 1..P {
+        let to = point(path, index);
+        let length = distance(&from, &to);
+        if length >= remaining && length > <F as num_traits::Zero>::zero() {
+            let fraction = remaining / length;
+            return [
+                from[0] + fraction * (to[0] - from[0]),
+                from[1] + fraction * (to[1] - from[1]),
+            ];
+        }
+        remaining -= length;
+        from = to;
+    }
+    from
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    // An L shaped path: (0, 0) -> (10, 0) -> (10, 10)
+    const PATH: Matrix<3, 2, f64> = Matrix {
+        data: [[0.0, 10.0, 10.0], [0.0, 0.0, 10.0]],
+    };
+
+    #[test]
+    fn test_project() {
+        let projection = project(&PATH, &[4.0, 2.0]);
+        assert_eq!(projection.segment, 0);
+        assert_eq!(projection.point, [4.0, 0.0]);
+        assert_eq!(projection.cross_track, 2.0);
+        assert_eq!(projection.heading, 0.0);
+
+        // Right of the second segment
+        let projection = project(&PATH, &[13.0, 6.0]);
+        assert_eq!(projection.segment, 1);
+        assert_eq!(projection.point, [10.0, 6.0]);
+        assert_eq!(projection.cross_track, -3.0);
+        assert_relative_eq!(projection.heading, core::f64::consts::FRAC_PI_2);
+    }
+
+    #[test]
+    fn test_lookahead_point() {
+        let projection = project(&PATH, &[8.0, -1.0]);
+        assert_eq!(lookahead_point(&PATH, &projection, 1.0), [9.0, 0.0]);
+        assert_eq!(lookahead_point(&PATH, &projection, 5.0), [10.0, 3.0]);
+        assert_eq!(lookahead_point(&PATH, &projection, 50.0), [10.0, 10.0]);
+    }
+
+    #[test]
+    fn test_wrap_angle() {
+        assert_relative_eq!(
+            wrap_angle(3.0 * core::f64::consts::PI / 2.0),
+            -core::f64::consts::FRAC_PI_2,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(wrap_angle(-0.5f64), -0.5, epsilon = 1e-12);
+    }
+}
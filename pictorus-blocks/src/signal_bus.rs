@@ -0,0 +1,247 @@
+//! A fixed table of topics that lets separately generated models exchange signals in one app.
+//!
+//! Each topic is a `static` declared once for the app, e.g.
+//! `static ATTITUDE: Topic<f64> = Topic::new("attitude", 0.0);`,
+//! which the BusPublishBlock of one model writes and the BusSubscribeBlocks of other models read.
+//! Topics hold the latest sample only, and need neither `alloc` nor a lock: samples are copied
+//! through atomic words under a sequence counter, readers retry if they catch a publish mid-write,
+//! and a publish that overlaps another one is dropped.
+use core::cell::UnsafeCell;
+use core::mem::{size_of, MaybeUninit};
+use core::sync::atomic::{fence, AtomicU32, Ordering};
+use core::time::Duration;
+
+use pictorus_traits::Matrix;
+
+/// How many times a reader retries before giving up on a read that keeps overlapping a write
+const READ_ATTEMPTS: usize = 4;
+
+/// Marks `published_at` as never published
+const UNPUBLISHED: u32 = u32::MAX;
+
+/// Types that can be carried by a [`Topic`].
+///
+/// # Safety
+///
+/// Every byte of the type must be initialized, i.e. it has no padding, since samples are copied
+/// through `AtomicU32` words.
+pub unsafe trait Plain: Copy + 'static {}
+
+macro_rules! impl_plain {
+    ($($t:ty),*) => {
+        $(
+            // Safety: primitives have no padding
+            unsafe impl Plain for $t {}
+        )*
+    };
+}
+
+impl_plain!(bool, u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+// Safety: arrays of types without padding have none between elements
+unsafe impl<const N: usize, T: Plain> Plain for [T; N] {}
+
+// Safety: a matrix is a single array
+unsafe impl<const NROWS: usize, const NCOLS: usize, T: Plain + pictorus_traits::Scalar> Plain
+    for Matrix<NROWS, NCOLS, T>
+{
+}
+
+/// A sample laid out as a whole number of `u32` words
+#[repr(C, align(4))]
+struct Words<T>(T);
+
+/// A signal shared between models, see the module docs
+pub struct Topic<T: Plain> {
+    name: &'static str,
+    /// Odd while a sample is being written, incremented twice per sample
+    sequence: AtomicU32,
+    /// Only ever accessed as `AtomicU32` words once the topic is shared. Zeroed before the
+    /// initial value is written, so the padding after the sample is initialized too.
+    value: UnsafeCell<MaybeUninit<Words<T>>>,
+    /// Nanoseconds of app time, as low and high words, or `UNPUBLISHED` in both
+    published_at: [AtomicU32; 2],
+}
+
+// Safety: samples are only copied in or out through atomic words, under the sequence protocol
+// which detects torn reads and admits one publisher at a time
+unsafe impl<T: Plain> Sync for Topic<T> {}
+
+impl<T: Plain> Topic<T> {
+    const WORDS: usize = size_of::<Words<T>>() / size_of::<u32>();
+
+    /// Creates a topic holding `initial` until the first sample is published
+    pub const fn new(name: &'static str, initial: T) -> Self {
+        Self {
+            name,
+            sequence: AtomicU32::new(0),
+            value: UnsafeCell::new(Self::words(initial)),
+            published_at: [AtomicU32::new(UNPUBLISHED), AtomicU32::new(UNPUBLISHED)],
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Replaces the topic's sample with `value`, published at app time `time`.
+    ///
+    /// A topic should have a single publisher: if another publish is in progress, `value` is
+    /// dropped and this returns `false`.
+    pub fn publish(&self, value: T, time: Duration) -> bool {
+        let sequence = self.sequence.load(Ordering::Relaxed);
+        if sequence % 2 == 1
+            || self
+                .sequence
+                .compare_exchange(
+                    sequence,
+                    sequence.wrapping_add(1),
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                )
+                .is_err()
+        {
+            return false;
+        }
+        fence(Ordering::Release);
+
+        let staged = Self::words(value);
+        for index in 0..Self::WORDS {
+            // Safety: `index` is within both samples, and all of `staged` is initialized
+            unsafe {
+                let word = staged.as_ptr().cast::<u32>().add(index).read();
+                self.word(index).store(word, Ordering::Relaxed);
+            }
+        }
+        let nanos = u64::try_from(time.as_nanos()).unwrap_or(u64::MAX - 1);
+        let [low, high] = &self.published_at;
+        low.store(nanos as u32, Ordering::Relaxed);
+        high.store((nanos >> 32) as u32, Ordering::Relaxed);
+
+        self.sequence
+            .store(sequence.wrapping_add(2), Ordering::Release);
+        true
+    }
+
+    /// The latest sample and the app time it was published at, which is `None` for the initial
+    /// value. Returns `None` if the publisher kept overwriting the sample while it was being read.
+    pub fn read(&self) -> Option<(T, Option<Duration>)> {
+        for _ in 0..READ_ATTEMPTS {
+            let before = self.sequence.load(Ordering::Acquire);
+            if before % 2 == 1 {
+                core::hint::spin_loop();
+                continue;
+            }
+            let mut staged = MaybeUninit::<Words<T>>::uninit();
+            for index in 0..Self::WORDS {
+                // Safety: `index` is within both samples
+                unsafe {
+                    let word = self.word(index).load(Ordering::Relaxed);
+                    staged.as_mut_ptr().cast::<u32>().add(index).write(word);
+                }
+            }
+            let [low, high] = &self.published_at;
+            let nanos = (u64::from(high.load(Ordering::Relaxed)) << 32)
+                | u64::from(low.load(Ordering::Relaxed));
+            fence(Ordering::Acquire);
+            if self.sequence.load(Ordering::Relaxed) == before {
+                // Safety: the sequence didn't move, so every word came from the same sample
+                let value = unsafe { staged.assume_init().0 };
+                let published_at = (nanos != u64::MAX).then(|| Duration::from_nanos(nanos));
+                return Some((value, published_at));
+            }
+        }
+        None
+    }
+
+    /// `value` followed by zeroed padding
+    const fn words(value: T) -> MaybeUninit<Words<T>> {
+        let mut words = MaybeUninit::<Words<T>>::zeroed();
+        // Safety: `Words` is `repr(C)`, so the sample is at its start
+        unsafe { words.as_mut_ptr().cast::<T>().write(value) };
+        words
+    }
+
+    /// The `index`th word of the topic's sample
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `WORDS`.
+    unsafe fn word(&self, index: usize) -> &AtomicU32 {
+        // Safety: `Words` is aligned for `u32`, and the caller keeps `index` in bounds
+        unsafe { AtomicU32::from_ptr(self.value.get().cast::<u32>().add(index)) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pictorus_traits::Matrix;
+
+    #[test]
+    fn test_topic_publish_read() {
+        static TOPIC: Topic<f64> = Topic::new("speed", 0.0);
+        assert_eq!(TOPIC.name(), "speed");
+        assert_eq!(TOPIC.read(), Some((0.0, None)));
+
+        TOPIC.publish(1.5, Duration::from_millis(10));
+        assert_eq!(TOPIC.read(), Some((1.5, Some(Duration::from_millis(10)))));
+        TOPIC.publish(2.5, Duration::from_millis(20));
+        assert_eq!(TOPIC.read(), Some((2.5, Some(Duration::from_millis(20)))));
+    }
+
+    #[test]
+    fn test_topic_read_during_publish() {
+        let topic = Topic::new("mid_write", 0u8);
+        topic.publish(1, Duration::ZERO);
+        // A reader preempting the publisher mid-write gives up rather than spinning forever
+        topic.sequence.fetch_add(1, Ordering::Relaxed);
+        assert_eq!(topic.read(), None);
+    }
+
+    #[test]
+    fn test_topic_overlapping_publish() {
+        let topic = Topic::new("two_writers", 0u8);
+        // A second publisher arriving mid-write is turned away instead of tearing the sample
+        topic.sequence.fetch_add(1, Ordering::Relaxed);
+        assert!(!topic.publish(2, Duration::from_millis(5)));
+        topic.sequence.fetch_add(1, Ordering::Relaxed);
+        assert_eq!(topic.read(), Some((0, None)));
+
+        assert!(topic.publish(3, Duration::from_millis(10)));
+        assert_eq!(topic.read(), Some((3, Some(Duration::from_millis(10)))));
+    }
+
+    #[test]
+    fn test_topic_concurrent_matrix() {
+        static TOPIC: Topic<Matrix<4, 4, f64>> = Topic::new(
+            "pose",
+            Matrix {
+                data: [[0.0; 4]; 4],
+            },
+        );
+        let publisher = std::thread::spawn(|| {
+            for i in 1..=10_000 {
+                let value = Matrix {
+                    data: [[i as f64; 4]; 4],
+                };
+                TOPIC.publish(value, Duration::from_micros(i));
+            }
+        });
+        let mut last = 0.0;
+        while !publisher.is_finished() {
+            if let Some((value, time)) = TOPIC.read() {
+                // Every element comes from the same sample, and samples only move forward
+                let first = value.data[0][0];
+                assert!(value.data.as_flattened().iter().all(|x| *x == first));
+                if first > 0.0 {
+                    assert_eq!(time, Some(Duration::from_micros(first as u64)));
+                }
+                assert!(first >= last);
+                last = first;
+            }
+        }
+        publisher.join().unwrap();
+        assert_eq!(TOPIC.read().unwrap().0.data[3][3], 10_000.0);
+    }
+}
@@ -12,17 +12,36 @@ impl StaleTracker {
 
     pub fn is_valid(&self, app_time: Duration, stale_duration: Duration) -> bool {
         self.last_updated
-            .and_then(|inst| app_time.checked_sub(inst))
+            .and_then(|inst| elapsed(app_time, inst))
             .map(|elapsed| elapsed <= stale_duration)
             .unwrap_or(false)
     }
 }
 
+/// Time from `since` until `now`, or `None` if `now` is earlier.
+///
+/// Unlike `Duration::checked_sub`, which builds its result through a constructor that can
+/// panic, nothing here can panic, so it's safe to use from `panic-free` blocks.
+pub fn elapsed(now: Duration, since: Duration) -> Option<Duration> {
+    let nanos = now.as_nanos().checked_sub(since.as_nanos())?;
+    Some(Duration::from_nanos(
+        u64::try_from(nanos).unwrap_or(u64::MAX),
+    ))
+}
+
 /// Convert a millisecond duration supplied as an `f64` into a `Duration`.
 ///
-/// This will panic on negative, NaN, or infinite inputs.
+/// This will panic on negative, NaN, or infinite inputs, unless the `panic-free` feature is
+/// enabled, in which case they saturate to zero or `Duration::MAX`.
 pub fn duration_from_ms_f64(ms: f64) -> Duration {
-    Duration::from_secs_f64(ms / 1000.0)
+    #[cfg(not(feature = "panic-free"))]
+    return Duration::from_secs_f64(ms / 1000.0);
+    #[cfg(feature = "panic-free")]
+    return Duration::try_from_secs_f64(ms / 1000.0).unwrap_or(if ms > 0.0 {
+        Duration::MAX
+    } else {
+        Duration::ZERO
+    });
 }
 
 #[cfg(test)]
@@ -56,26 +75,38 @@ mod test {
     }
 
     #[test]
+    #[cfg(not(feature = "panic-free"))]
     #[should_panic]
     fn test_duration_from_ms_f64_negative_input_panics() {
         duration_from_ms_f64(-1.0);
     }
 
     #[test]
+    #[cfg(not(feature = "panic-free"))]
     #[should_panic]
     fn test_duration_from_ms_f64_nan_input_panics() {
         duration_from_ms_f64(f64::NAN);
     }
 
     #[test]
+    #[cfg(not(feature = "panic-free"))]
     #[should_panic]
     fn test_duration_from_ms_f64_infinite_input_panics() {
         duration_from_ms_f64(f64::INFINITY);
     }
 
     #[test]
+    #[cfg(not(feature = "panic-free"))]
     #[should_panic]
     fn test_duration_from_ms_f64_neg_infinite_input_panics() {
         duration_from_ms_f64(f64::NEG_INFINITY);
     }
+
+    #[test]
+    #[cfg(feature = "panic-free")]
+    fn test_duration_from_ms_f64_invalid_input_saturates() {
+        assert_eq!(duration_from_ms_f64(-1.0), Duration::ZERO);
+        assert_eq!(duration_from_ms_f64(f64::NAN), Duration::ZERO);
+        assert_eq!(duration_from_ms_f64(f64::INFINITY), Duration::MAX);
+    }
 }
@@ -1,20 +1,12 @@
-mod fft_block;
-pub use fft_block::FftBlock as FFTBlock;
+mod schedule_block;
+#[doc(hidden)]
+pub use schedule_block::Parameters as ScheduleBlockParams;
+pub use schedule_block::ScheduleBlock;
 
 mod system_time_block;
-pub use system_time_block::SystemTimeBlock;
+pub use system_time_block::{SystemTimeBlock, SystemTimeEnum};
 
 #[cfg(target_arch = "x86_64")]
 mod fmu_block;
 #[cfg(target_arch = "x86_64")]
 pub use fmu_block::FmuBlock;
-
-mod udp_receive_block;
-#[doc(hidden)]
-pub use udp_receive_block::Parameters as UdpReceiveBlockParams;
-pub use udp_receive_block::UdpReceiveBlock;
-
-mod udp_transmit_block;
-#[doc(hidden)]
-pub use udp_transmit_block::Parameters as UdpTransmitBlockParams;
-pub use udp_transmit_block::UdpTransmitBlock;
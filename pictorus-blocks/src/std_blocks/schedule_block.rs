@@ -0,0 +1,157 @@
+use chrono::{DateTime, Local};
+use pictorus_traits::{GeneratorBlock, PassBy};
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// This block can be used in `std` environments to trigger periodic tasks, such as daily calibrations
+/// or data uploads, at fixed wall-clock times.
+///
+/// The output is true for a single tick each time a scheduled time is reached, and false otherwise.
+/// Two kinds of schedule are supported, and can be combined:
+/// - Times of day, in seconds after local midnight, which trigger once a day each
+/// - An interval in seconds, which triggers whenever the local time of day is a multiple of it
+///   (e.g. 3600 triggers at the top of every hour). Zero or less disables the interval.
+///
+/// Like the [`SystemTimeBlock`](crate::SystemTimeBlock), the wall-clock time is the system time at
+/// startup plus the elapsed app time, so schedules also work in faster than real-time simulations.
+/// Times are evaluated in local time, and a scheduled time that passes between two ticks triggers
+/// on the later tick. Nothing triggers on the first tick.
+pub struct ScheduleBlock<const N: usize> {
+    output: bool,
+    start_time: DateTime<Local>,
+    previous_events: Option<i64>,
+}
+
+impl<const N: usize> Default for ScheduleBlock<N> {
+    fn default() -> Self {
+        Self {
+            output: false,
+            start_time: Local::now(),
+            previous_events: None,
+        }
+    }
+}
+
+/// Seconds since the Unix epoch as they'd read on a local wall clock
+fn local_seconds(time: DateTime<Local>) -> f64 {
+    let naive = time.naive_local().and_utc();
+    naive.timestamp() as f64 + f64::from(naive.timestamp_subsec_nanos()) * 1e-9
+}
+
+/// The number of scheduled times up to and including `seconds` since the local epoch.
+/// Only differences between counts are meaningful.
+fn event_count<const N: usize>(seconds: f64, parameters: &Parameters<N>) -> i64 {
+    let day = (seconds / SECONDS_PER_DAY).floor();
+    let time_of_day = seconds - day * SECONDS_PER_DAY;
+    let daily = parameters
+        .times_of_day
+        .iter()
+        .filter(|time| **time <= time_of_day)
+        .count() as i64;
+    let mut count = day as i64 * N as i64 + daily;
+    if parameters.interval > 0.0 {
+        count += (seconds / parameters.interval).floor() as i64;
+    }
+    count
+}
+
+impl<const N: usize> GeneratorBlock for ScheduleBlock<N> {
+    type Output = bool;
+    type Parameters = Parameters<N>;
+
+    fn generate(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn pictorus_traits::Context,
+    ) -> pictorus_traits::PassBy<'_, Self::Output> {
+        let time_now = self.start_time + context.time();
+        let events = event_count(local_seconds(time_now), parameters);
+        self.output = self
+            .previous_events
+            .is_some_and(|previous| events > previous);
+        self.previous_events = Some(events);
+        self.output
+    }
+
+    fn buffer(&self) -> PassBy<'_, Self::Output> {
+        self.output
+    }
+}
+
+/// Parameters for the ScheduleBlock
+pub struct Parameters<const N: usize> {
+    /// Times of day to trigger at, in seconds after local midnight
+    pub times_of_day: [f64; N],
+    /// Interval to trigger at, in seconds. Zero or less disables the interval.
+    pub interval: f64,
+}
+
+impl<const N: usize> Parameters<N> {
+    pub fn new(times_of_day: [f64; N], interval: f64) -> Parameters<N> {
+        Parameters {
+            times_of_day,
+            interval,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::testing::StubContext;
+    use chrono::TimeZone;
+
+    fn context(seconds: f64) -> StubContext {
+        StubContext::new(
+            Duration::from_secs_f64(seconds),
+            None,
+            Duration::from_secs(1),
+        )
+    }
+
+    #[test]
+    fn test_schedule_default_buffer_no_panic() {
+        let block = ScheduleBlock::<0>::default();
+        assert!(!block.buffer());
+    }
+
+    #[test]
+    fn test_event_count() {
+        // Triggers at 06:00 and 18:00
+        let params = Parameters::new([6.0 * 3600.0, 18.0 * 3600.0], 0.0);
+        let day = 20_000.0 * SECONDS_PER_DAY;
+        assert_eq!(event_count(day + 3600.0, &params), 40_000);
+        assert_eq!(event_count(day + 6.0 * 3600.0, &params), 40_001);
+        assert_eq!(event_count(day + 20.0 * 3600.0, &params), 40_002);
+        assert_eq!(event_count(day + SECONDS_PER_DAY, &params), 40_002);
+
+        let params = Parameters::new([], 900.0);
+        assert_eq!(event_count(899.0, &params), 0);
+        assert_eq!(event_count(900.0, &params), 1);
+        assert_eq!(event_count(2000.0, &params), 2);
+    }
+
+    #[test]
+    fn test_schedule_block() {
+        let mut block = ScheduleBlock::<1> {
+            start_time: Local.with_ymd_and_hms(2024, 3, 1, 1, 59, 0).unwrap(),
+            ..Default::default()
+        };
+        // Daily at 02:00, and every 30 minutes
+        let params = Parameters::new([7200.0], 1800.0);
+
+        assert!(!block.generate(&params, &context(0.0)));
+        assert!(!block.generate(&params, &context(30.0)));
+        // 02:00 is both a daily time and an interval, which still pulses once
+        assert!(block.generate(&params, &context(60.0)));
+        assert!(block.buffer());
+        assert!(!block.generate(&params, &context(61.0)));
+
+        // 02:30 passes between ticks
+        assert!(!block.generate(&params, &context(1799.0)));
+        assert!(block.generate(&params, &context(1900.0)));
+        assert!(!block.generate(&params, &context(1901.0)));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::ParameterError;
 use chrono::{DateTime, Datelike, Local, Timelike};
 use pictorus_traits::{GeneratorBlock, PassBy};
 
@@ -72,9 +73,21 @@ pub struct Parameters {
 
 impl Parameters {
     pub fn new(method: &str) -> Parameters {
-        Parameters {
-            method: method.parse().unwrap(),
-        }
+        Self::try_new(method).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Parameters::new`], for builds where panics are unacceptable
+    pub fn try_new(method: &str) -> Result<Self, ParameterError> {
+        Ok(Parameters {
+            method: method
+                .parse()
+                .map_err(|_| ParameterError("Failed to parse method"))?,
+        })
+    }
+
+    /// Builds the parameters from an already-resolved output type, without parsing
+    pub const fn from_method(method: SystemTimeEnum) -> Self {
+        Parameters { method }
     }
 }
 
@@ -1,18 +1,21 @@
 //! A collection of traits that are used in the corelib-blocks library
+use crate::fast_math::FastMath;
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 use core::time::Duration;
 use nalgebra::{ComplexField, RealField, SimdPartialOrd};
-use pictorus_traits::{Matrix, Pass, PassBy};
+use pictorus_traits::{EnumSignal, Fixed, Matrix, Pass, PassBy, SignalEnum};
 #[cfg(feature = "alloc")]
 pub mod serialize;
 #[cfg(feature = "alloc")]
 pub use serialize::Serialize;
 
-// ByteSliceSignal is only imported in this file to use with `alloc`
+// ByteSliceSignal, DMatrix and StrSliceSignal are only imported in this file to use with `alloc`
 // gated implementations.
 #[cfg(feature = "alloc")]
-use pictorus_traits::ByteSliceSignal;
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use pictorus_traits::{ByteSliceSignal, DMatrix, StrSliceSignal};
 
 /// A re-export of the pictorus_traits::Scalar trait to allow for easier blanket implementations
 pub trait Scalar:
@@ -142,7 +145,7 @@ impl Scalar for f64 {
     }
 }
 
-pub trait Float: Scalar + num_traits::Float + ComplexField
+pub trait Float: Scalar + num_traits::Float + FastMath + ComplexField
 where
     Self: RealField<RealField = Self>,
 {
@@ -205,6 +208,18 @@ impl<T: Scalar, const NCOLS: usize, const NRWOS: usize> DefaultStorage for Matri
     }
 }
 
+impl<E: SignalEnum> DefaultStorage for EnumSignal<E> {
+    type Storage = EnumSignal<E>;
+
+    fn default_storage() -> Self::Storage {
+        EnumSignal::default()
+    }
+
+    fn from_storage(storage: &Self::Storage) -> PassBy<'_, Self> {
+        storage.as_by()
+    }
+}
+
 #[cfg(feature = "alloc")]
 impl DefaultStorage for ByteSliceSignal {
     type Storage = Vec<u8>;
@@ -218,6 +233,32 @@ impl DefaultStorage for ByteSliceSignal {
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<T: Scalar> DefaultStorage for DMatrix<T> {
+    type Storage = DMatrix<T>;
+
+    fn default_storage() -> Self::Storage {
+        DMatrix::default()
+    }
+
+    fn from_storage(storage: &Self::Storage) -> PassBy<'_, Self> {
+        storage
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl DefaultStorage for StrSliceSignal {
+    type Storage = String;
+
+    fn default_storage() -> Self::Storage {
+        String::new()
+    }
+
+    fn from_storage(storage: &Self::Storage) -> PassBy<'_, Self> {
+        storage.as_str()
+    }
+}
+
 /// A trait that allows for copying a PassBy of a type into an instance of that type
 /// or in the case of scalar into a matrix of that scalar (every element is set to the scalar)
 pub trait CopyInto<T>: Pass {
@@ -255,6 +296,12 @@ where
     }
 }
 
+impl<E: SignalEnum> CopyInto<EnumSignal<E>> for EnumSignal<E> {
+    fn copy_into(source: PassBy<Self>, dest: &mut EnumSignal<E>) {
+        dest.0 = source;
+    }
+}
+
 #[cfg(feature = "alloc")]
 impl CopyInto<Vec<u8>> for ByteSliceSignal {
     fn copy_into(source: PassBy<Self>, dest: &mut Vec<u8>) {
@@ -263,6 +310,21 @@ impl CopyInto<Vec<u8>> for ByteSliceSignal {
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<T: Scalar> CopyInto<DMatrix<T>> for DMatrix<T> {
+    fn copy_into(source: PassBy<Self>, dest: &mut DMatrix<T>) {
+        dest.copy_from(source);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl CopyInto<String> for StrSliceSignal {
+    fn copy_into(source: PassBy<Self>, dest: &mut String) {
+        dest.clear();
+        dest.push_str(source);
+    }
+}
+
 /// A recursive trait that allows one to refer to the output type relative to an input tuple
 /// that may contain all scalars, all matrices (of the same size), or a mix of scalars and
 /// all matrices (of the same size). The output will be a scalar for all scalar inputs, a matrix
@@ -300,6 +362,30 @@ impl<const NROWS: usize, const NCOLS: usize, S: Scalar> SizePromotion<Matrix<NRO
     type Output = Matrix<NROWS, NCOLS, S>;
 }
 
+/// Fixed-point scalars follow the same rules. They implement `pictorus_traits::Scalar`, but not
+/// [`crate::traits::Scalar`], since they can't implement nalgebra's SIMD traits
+impl<const FRAC: u32> SizePromotion<Fixed<FRAC>> for Fixed<FRAC> {
+    type Output = Fixed<FRAC>;
+}
+
+impl<const FRAC: u32, const NROWS: usize, const NCOLS: usize>
+    SizePromotion<Matrix<NROWS, NCOLS, Fixed<FRAC>>> for Fixed<FRAC>
+{
+    type Output = Matrix<NROWS, NCOLS, Fixed<FRAC>>;
+}
+
+impl<const FRAC: u32, const NROWS: usize, const NCOLS: usize> SizePromotion<Fixed<FRAC>>
+    for Matrix<NROWS, NCOLS, Fixed<FRAC>>
+{
+    type Output = Matrix<NROWS, NCOLS, Fixed<FRAC>>;
+}
+
+impl<const FRAC: u32, const NROWS: usize, const NCOLS: usize>
+    SizePromotion<Matrix<NROWS, NCOLS, Fixed<FRAC>>> for Matrix<NROWS, NCOLS, Fixed<FRAC>>
+{
+    type Output = Matrix<NROWS, NCOLS, Fixed<FRAC>>;
+}
+
 /// Recursive Definition for 3 inputs
 impl<A, B, C> SizePromotion<(B, C)> for A
 where
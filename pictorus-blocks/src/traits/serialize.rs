@@ -4,7 +4,7 @@ use miniserde::{
     json::{self, Array, Number, Value},
     ser::Fragment,
 };
-use pictorus_traits::{ByteSliceSignal, Matrix, Pass, PassBy};
+use pictorus_traits::{ByteSliceSignal, Matrix, Pass, PassBy, StrSliceSignal};
 
 /// A trait for serializing into a JSON value.
 /// This trait is used to convert various data types into a JSON representation.
@@ -115,6 +115,14 @@ impl Serialize for ByteSliceSignal {
     }
 }
 
+impl Serialize for StrSliceSignal {
+    type FormatOptions = ();
+
+    fn as_json_value(input: PassBy<Self>, _: ()) -> json::Value {
+        Value::String(String::from(input))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -145,6 +153,15 @@ mod tests {
         assert_eq!(bytes.as_slice(), b"\"hello\"");
     }
 
+    #[test]
+    fn test_serialize_str_slice_signal() {
+        let json_val = StrSliceSignal::as_json_value("hello", ());
+        assert_eq!(json::to_string(&json_val), "\"hello\"");
+
+        let bytes = StrSliceSignal::to_bytes_default("hello");
+        assert_eq!(bytes.as_slice(), b"\"hello\"");
+    }
+
     #[test]
     fn test_serialize_matrix() {
         let matrix = Matrix {
@@ -0,0 +1,179 @@
+//! Framing shared by the XBee API frame blocks.
+//!
+//! An API frame is a start delimiter, a big-endian length, the frame data (API identifier
+//! followed by its fields) and a checksum. In API mode 2 ("escaped"), bytes after the start
+//! delimiter that collide with control characters are sent as an escape byte followed by the
+//! byte XOR 0x20.
+use alloc::vec::Vec;
+use log::debug;
+
+use crate::byte_data::BUFF_SIZE_BYTES;
+
+pub(crate) const START_DELIMITER: u8 = 0x7E;
+const ESCAPE: u8 = 0x7D;
+const XON: u8 = 0x11;
+const XOFF: u8 = 0x13;
+const ESCAPE_MASK: u8 = 0x20;
+
+pub(crate) const API_AT_COMMAND: u8 = 0x08;
+pub(crate) const API_TRANSMIT_REQUEST: u8 = 0x10;
+pub(crate) const API_AT_COMMAND_RESPONSE: u8 = 0x88;
+pub(crate) const API_TRANSMIT_STATUS: u8 = 0x8B;
+pub(crate) const API_RECEIVE_PACKET: u8 = 0x90;
+
+/// Checksum over the frame data: 0xFF minus the low byte of the sum of all bytes
+pub(crate) fn checksum(frame_data: &[u8]) -> u8 {
+    0xFF - frame_data.iter().fold(0u8, |sum, &b| sum.wrapping_add(b))
+}
+
+fn needs_escape(byte: u8) -> bool {
+    matches!(byte, START_DELIMITER | ESCAPE | XON | XOFF)
+}
+
+/// Appends a complete frame carrying `frame_data` to `out`
+pub(crate) fn write_frame(frame_data: &[u8], escaped: bool, out: &mut Vec<u8>) {
+    out.push(START_DELIMITER);
+    let length = (frame_data.len() as u16).to_be_bytes();
+    let checksum = [checksum(frame_data)];
+    for &byte in length.iter().chain(frame_data).chain(&checksum) {
+        if escaped && needs_escape(byte) {
+            out.push(ESCAPE);
+            out.push(byte ^ ESCAPE_MASK);
+        } else {
+            out.push(byte);
+        }
+    }
+}
+
+/// Reassembles frames from bytes that may arrive split across several ticks
+#[derive(Default)]
+pub(crate) struct FrameReader {
+    buffer: Vec<u8>,
+    escape_next: bool,
+}
+
+impl FrameReader {
+    /// Appends received bytes, removing escaping if enabled
+    pub fn push(&mut self, data: &[u8], escaped: bool) {
+        for &byte in data {
+            if escaped {
+                if byte == START_DELIMITER {
+                    // Never escaped, so always starts a new frame
+                    self.escape_next = false;
+                } else if byte == ESCAPE {
+                    self.escape_next = true;
+                    continue;
+                } else if self.escape_next {
+                    self.escape_next = false;
+                    self.buffer.push(byte ^ ESCAPE_MASK);
+                    continue;
+                }
+            }
+            self.buffer.push(byte);
+        }
+
+        if self.buffer.len() >= BUFF_SIZE_BYTES * 4 {
+            debug!("Read too many bytes without a complete XBee frame. Clearing buffer");
+            self.buffer.clear();
+        }
+    }
+
+    /// Copies the data of the next complete, valid frame into `frame_data`. Returns false if
+    /// no complete frame is buffered yet. Malformed frames are skipped.
+    pub fn next_frame(&mut self, frame_data: &mut Vec<u8>) -> bool {
+        loop {
+            let Some(start) = self.buffer.iter().position(|&b| b == START_DELIMITER) else {
+                self.buffer.clear();
+                return false;
+            };
+            self.buffer.drain(..start);
+            if self.buffer.len() < 3 {
+                return false;
+            }
+
+            let length = usize::from(u16::from_be_bytes([self.buffer[1], self.buffer[2]]));
+            if length == 0 || length > BUFF_SIZE_BYTES {
+                debug!("Invalid XBee frame length {length}, resynchronizing");
+                self.buffer.drain(..1);
+                continue;
+            }
+            if self.buffer.len() < length + 4 {
+                return false;
+            }
+
+            let data = &self.buffer[3..3 + length];
+            if checksum(data) != self.buffer[3 + length] {
+                debug!("XBee frame checksum mismatch, resynchronizing");
+                self.buffer.drain(..1);
+                continue;
+            }
+
+            frame_data.clear();
+            frame_data.extend_from_slice(data);
+            self.buffer.drain(..length + 4);
+            return true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_frame() {
+        // AT command "NJ" from the XBee reference manual
+        let mut out = Vec::new();
+        write_frame(&[0x08, 0x52, b'N', b'J'], false, &mut out);
+        assert_eq!(out, [0x7E, 0x00, 0x04, 0x08, 0x52, 0x4E, 0x4A, 0x0D]);
+    }
+
+    #[test]
+    fn test_write_frame_escaped() {
+        let mut out = Vec::new();
+        write_frame(&[0x10, 0x7E, 0x11], true, &mut out);
+        let checksum = checksum(&[0x10, 0x7E, 0x11]);
+        assert_eq!(
+            out,
+            [0x7E, 0x00, 0x03, 0x10, 0x7D, 0x5E, 0x7D, 0x31, checksum]
+        );
+    }
+
+    #[test]
+    fn test_frame_reader_round_trip() {
+        for escaped in [false, true] {
+            let mut encoded = Vec::new();
+            write_frame(&[0x90, 0x7D, 0x13, 0x7E], escaped, &mut encoded);
+            write_frame(&[0x8B, 0x01], escaped, &mut encoded);
+
+            let mut reader = FrameReader::default();
+            let mut frame = Vec::new();
+            // Split mid-frame, with leading garbage
+            reader.push(&[0x00, 0x42], escaped);
+            reader.push(&encoded[..5], escaped);
+            assert!(!reader.next_frame(&mut frame));
+            reader.push(&encoded[5..], escaped);
+
+            assert!(reader.next_frame(&mut frame));
+            assert_eq!(frame, [0x90, 0x7D, 0x13, 0x7E]);
+            assert!(reader.next_frame(&mut frame));
+            assert_eq!(frame, [0x8B, 0x01]);
+            assert!(!reader.next_frame(&mut frame));
+        }
+    }
+
+    #[test]
+    fn test_frame_reader_skips_bad_checksum() {
+        let mut encoded = Vec::new();
+        write_frame(&[0x8B, 0x01], false, &mut encoded);
+        let last = encoded.len() - 1;
+        encoded[last] ^= 0xFF;
+        write_frame(&[0x8B, 0x02], false, &mut encoded);
+
+        let mut reader = FrameReader::default();
+        let mut frame = Vec::new();
+        reader.push(&encoded, false);
+        assert!(reader.next_frame(&mut frame));
+        assert_eq!(frame, [0x8B, 0x02]);
+    }
+}
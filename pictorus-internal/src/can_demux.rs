@@ -0,0 +1,350 @@
+//! Demultiplexing of received CAN frames to several model outputs.
+//!
+//! Rather than one receive block per message ID, each scanning the connection's frames, a
+//! [`CanDemux`] wraps a connection and sorts each tick's frames into slots by ID. A frame goes to
+//! every slot whose ID matches under the acceptance mask, so one slot can also take a range of
+//! IDs (e.g. a message from any source address). The output has a `(data, is_valid)` pair per
+//! slot, where a slot is valid if it has received a frame within the stale age.
+use core::time::Duration;
+
+use embedded_can::{Frame, Id, nb::Can};
+use heapless::Vec;
+use pictorus_traits::{ByteSliceSignal, Context, InputBlock, OutputBlock, Pass, PassBy};
+
+use crate::protocols::CanProtocol;
+
+/// Largest frame payload a slot holds, for CAN FD
+const MAX_FRAME_BYTES: usize = 64;
+
+/// Parameters for the CAN demux block
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanDemuxParams<const N: usize> {
+    /// Message ID of each slot
+    pub ids: [Id; N],
+    /// Bits of the ID that must match a slot's ID, all set for an exact match
+    pub mask: u32,
+    /// Time after which a slot without a new frame is marked invalid
+    pub stale_age: Duration,
+}
+
+impl<const N: usize> CanDemuxParams<N> {
+    pub fn new(ids: [Id; N], mask: u32, stale_age_ms: f64) -> Self {
+        Self {
+            ids,
+            mask,
+            stale_age: Duration::from_secs_f64(stale_age_ms.max(0.0) / 1000.0),
+        }
+    }
+
+    fn matches(&self, slot: usize, id: Id) -> bool {
+        match (self.ids[slot], id) {
+            (Id::Standard(slot_id), Id::Standard(id)) => {
+                (u32::from(slot_id.as_raw()) ^ u32::from(id.as_raw())) & self.mask == 0
+            }
+            (Id::Extended(slot_id), Id::Extended(id)) => {
+                (slot_id.as_raw() ^ id.as_raw()) & self.mask == 0
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Latest frame of a demux slot
+#[derive(Debug, Clone, Default)]
+pub struct DemuxSlot {
+    data: Vec<u8, MAX_FRAME_BYTES>,
+    updated: Option<Duration>,
+    is_valid: bool,
+}
+
+/// Maps the slots of a [`CanDemux`] to its output tuple, `(data, is_valid)` for each slot
+pub trait DemuxOutput {
+    type Output: Pass;
+
+    fn as_by(slots: &[DemuxSlot]) -> PassBy<'_, Self::Output>;
+}
+
+/// Number of slots of a [`CanDemux`], for selecting its [`DemuxOutput`]
+pub struct Slots<const N: usize>;
+
+macro_rules! demux_output {
+    ($n:literal; $($index:literal),+) => {
+        impl DemuxOutput for Slots<$n> {
+            type Output = ($(demux_output!(@bytes $index), bool),+);
+
+            fn as_by(slots: &[DemuxSlot]) -> PassBy<'_, Self::Output> {
+                ($(&slots[$index].data, slots[$index].is_valid),+)
+            }
+        }
+    };
+    (@bytes $index:literal) => {
+        ByteSliceSignal
+    };
+}
+
+demux_output!(1; 0);
+demux_output!(2; 0, 1);
+demux_output!(3; 0, 1, 2);
+demux_output!(4; 0, 1, 2, 3);
+demux_output!(5; 0, 1, 2, 3, 4);
+demux_output!(6; 0, 1, 2, 3, 4, 5);
+demux_output!(7; 0, 1, 2, 3, 4, 5, 6);
+demux_output!(8; 0, 1, 2, 3, 4, 5, 6, 7);
+
+/// Receives CAN frames for up to 8 message IDs from one connection, see the
+/// [module docs](self).
+///
+/// The demux owns the connection, and forwards transmits and flushes to it, so the same
+/// instance also serves the connection's transmit blocks.
+pub struct CanDemux<C: CanProtocol, const N: usize> {
+    connection: C,
+    slots: [DemuxSlot; N],
+}
+
+impl<C: CanProtocol, const N: usize> CanDemux<C, N> {
+    pub fn new(connection: C) -> Self {
+        Self {
+            connection,
+            slots: core::array::from_fn(|_| DemuxSlot::default()),
+        }
+    }
+
+    pub fn connection(&mut self) -> &mut C {
+        &mut self.connection
+    }
+}
+
+impl<C: CanProtocol, const N: usize> Can for CanDemux<C, N> {
+    type Frame = C::Frame;
+    type Error = C::Error;
+
+    fn transmit(&mut self, frame: &Self::Frame) -> nb::Result<Option<Self::Frame>, Self::Error> {
+        self.connection.transmit(frame)
+    }
+
+    fn receive(&mut self) -> nb::Result<Self::Frame, Self::Error> {
+        self.connection.receive()
+    }
+}
+
+impl<C: CanProtocol, const N: usize> CanProtocol for CanDemux<C, N> {
+    fn read_frames(&mut self) -> &[impl Frame] {
+        self.connection.read_frames()
+    }
+
+    fn flush(&mut self) {
+        self.connection.flush();
+    }
+}
+
+impl<C: CanProtocol, const N: usize> InputBlock for CanDemux<C, N>
+where
+    Slots<N>: DemuxOutput,
+{
+    type Output = <Slots<N> as DemuxOutput>::Output;
+    type Parameters = CanDemuxParams<N>;
+
+    fn input(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        let app_time = context.time();
+        for frame in self.connection.read_frames() {
+            for (index, slot) in self.slots.iter_mut().enumerate() {
+                if parameters.matches(index, frame.id()) {
+                    // No frame is longer than a CAN FD frame, so nothing is dropped
+                    let len = frame.data().len().min(MAX_FRAME_BYTES);
+                    slot.data.clear();
+                    slot.data.extend_from_slice(&frame.data()[..len]).ok();
+                    slot.updated = Some(app_time);
+                }
+            }
+        }
+        for slot in &mut self.slots {
+            slot.is_valid = slot
+                .updated
+                .is_some_and(|updated| app_time.saturating_sub(updated) <= parameters.stale_age);
+        }
+        <Slots<N> as DemuxOutput>::as_by(&self.slots)
+    }
+}
+
+impl<C: CanProtocol + OutputBlock, const N: usize> OutputBlock for CanDemux<C, N> {
+    type Inputs = C::Inputs;
+    type Parameters = C::Parameters;
+
+    fn output(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) {
+        self.connection.output(parameters, context, inputs);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use alloc::vec::Vec as AllocVec;
+    use embedded_can::{ExtendedId, StandardId};
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub(crate) struct StubFrame {
+        pub id: Id,
+        pub data: AllocVec<u8>,
+    }
+
+    impl Frame for StubFrame {
+        fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+            Some(Self {
+                id: id.into(),
+                data: data.into(),
+            })
+        }
+
+        fn new_remote(_id: impl Into<Id>, _dlc: usize) -> Option<Self> {
+            None
+        }
+
+        fn is_extended(&self) -> bool {
+            matches!(self.id, Id::Extended(_))
+        }
+
+        fn is_remote_frame(&self) -> bool {
+            false
+        }
+
+        fn id(&self) -> Id {
+            self.id
+        }
+
+        fn dlc(&self) -> usize {
+            self.data.len()
+        }
+
+        fn data(&self) -> &[u8] {
+            &self.data
+        }
+    }
+
+    /// Connection that reads the frames in `frames` and records transmitted frames in `sent`
+    #[derive(Default)]
+    pub(crate) struct StubCan {
+        pub frames: AllocVec<StubFrame>,
+        pub sent: AllocVec<StubFrame>,
+    }
+
+    impl Can for StubCan {
+        type Frame = StubFrame;
+        type Error = embedded_can::ErrorKind;
+
+        fn transmit(
+            &mut self,
+            frame: &Self::Frame,
+        ) -> nb::Result<Option<Self::Frame>, Self::Error> {
+            self.sent.push(frame.clone());
+            Ok(None)
+        }
+
+        fn receive(&mut self) -> nb::Result<Self::Frame, Self::Error> {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    impl CanProtocol for StubCan {
+        fn read_frames(&mut self) -> &[impl Frame] {
+            &self.frames
+        }
+
+        fn flush(&mut self) {
+            self.frames.clear();
+        }
+    }
+
+    pub(crate) struct StubContext(pub Duration);
+
+    impl Context for StubContext {
+        fn timestep(&self) -> Option<Duration> {
+            None
+        }
+
+        fn time(&self) -> Duration {
+            self.0
+        }
+
+        fn fundamental_timestep(&self) -> Duration {
+            Duration::from_millis(10)
+        }
+    }
+
+    fn standard(id: u16) -> Id {
+        Id::Standard(StandardId::new(id).unwrap())
+    }
+
+    pub(crate) fn extended(id: u32) -> Id {
+        Id::Extended(ExtendedId::new(id).unwrap())
+    }
+
+    fn receive(demux: &mut CanDemux<StubCan, 2>, frames: &[(Id, &[u8])]) {
+        demux.flush();
+        demux.connection().frames = frames
+            .iter()
+            .map(|(id, data)| StubFrame::new(*id, data).unwrap())
+            .collect();
+    }
+
+    #[test]
+    fn test_demux_exact_ids() {
+        let params = CanDemuxParams::new([standard(0x100), extended(0x100)], 0x1FFF_FFFF, 100.0);
+        let mut demux = CanDemux::<_, 2>::new(StubCan::default());
+
+        receive(
+            &mut demux,
+            &[
+                (standard(0x100), &[1]),
+                (standard(0x101), &[2]),
+                (extended(0x100), &[3, 4]),
+                (standard(0x100), &[5]),
+            ],
+        );
+        let output = demux.input(&params, &StubContext(Duration::ZERO));
+        // The latest frame of each ID, and standard and extended IDs are distinct
+        assert_eq!(output, (&[5u8][..], true, &[3u8, 4][..], true));
+
+        receive(&mut demux, &[(extended(0x100), &[6])]);
+        let output = demux.input(&params, &StubContext(Duration::from_millis(50)));
+        assert_eq!(output, (&[5u8][..], true, &[6u8][..], true));
+
+        // Data is held once stale
+        receive(&mut demux, &[]);
+        let output = demux.input(&params, &StubContext(Duration::from_millis(120)));
+        assert_eq!(output, (&[5u8][..], false, &[6u8][..], true));
+    }
+
+    #[test]
+    fn test_demux_mask() {
+        // Ignore the low byte, e.g. a J1939 source address
+        let params =
+            CanDemuxParams::new([extended(0x18FEF100), extended(0x18FEF200)], !0xFF, 100.0);
+        let mut demux = CanDemux::<_, 2>::new(StubCan::default());
+
+        let output = demux.input(&params, &StubContext(Duration::ZERO));
+        assert_eq!(output, (&[][..], false, &[][..], false));
+
+        receive(&mut demux, &[(extended(0x18FEF117), &[7])]);
+        let output = demux.input(&params, &StubContext(Duration::ZERO));
+        assert_eq!(output, (&[7u8][..], true, &[][..], false));
+    }
+
+    #[test]
+    fn test_demux_overlapping_slots() {
+        // A frame goes to every slot it matches
+        let params = CanDemuxParams::new([standard(0x200), standard(0x200)], 0x7FF, 100.0);
+        let mut demux = CanDemux::<_, 2>::new(StubCan::default());
+        receive(&mut demux, &[(standard(0x200), &[1, 2, 3])]);
+        let output = demux.input(&params, &StubContext(Duration::ZERO));
+        assert_eq!(output, (&[1u8, 2, 3][..], true, &[1u8, 2, 3][..], true));
+    }
+}
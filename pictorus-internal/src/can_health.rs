@@ -0,0 +1,177 @@
+//! CAN bus health statistics, shared by the platform CAN protocols.
+//!
+//! The platforms feed [`CanBusHealth`] with the controller's error counters and state, and with
+//! every frame seen on the bus, and it turns them into the outputs of the CAN health block:
+//! `(tx error count, rx error count, bus-off events, rx overflows, busload %, bus-off)`.
+use core::cell::Cell;
+use core::time::Duration;
+
+/// Period the busload is averaged over
+const BUSLOAD_WINDOW: Duration = Duration::from_millis(100);
+
+/// Parameters for the CAN health blocks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CanHealthParams {
+    /// Nominal bitrate of the bus in bits per second, for the busload estimate
+    pub bitrate: f64,
+}
+
+impl CanHealthParams {
+    pub fn new(bitrate: f64) -> Self {
+        Self { bitrate }
+    }
+}
+
+/// Number of bits a classic CAN frame occupies on the bus, including the interframe space but
+/// not stuff bits, so busload estimates are a lower bound
+pub fn frame_bits(extended: bool, data_len: usize) -> u32 {
+    let overhead = if extended { 67 } else { 47 };
+    overhead + 8 * data_len.min(8) as u32
+}
+
+/// Counts the bits of frames as a connection sends and receives them, so a separate health
+/// monitor can estimate the busload of a peripheral it doesn't own
+#[derive(Debug, Default)]
+pub struct CanTraffic {
+    bits: Cell<u64>,
+}
+
+impl CanTraffic {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_frame(&self, extended: bool, data_len: usize) {
+        self.bits
+            .set(self.bits.get() + u64::from(frame_bits(extended, data_len)));
+    }
+
+    /// Returns the bits counted since the previous call
+    pub fn take_bits(&self) -> u64 {
+        self.bits.take()
+    }
+}
+
+/// Accumulates the health statistics of a CAN bus, see the [module docs](self)
+#[derive(Debug, Default)]
+pub struct CanBusHealth {
+    tx_error_count: u8,
+    rx_error_count: u8,
+    bus_off: bool,
+    bus_off_events: u32,
+    rx_overflows: u32,
+    window_bits: u64,
+    window_start: Option<Duration>,
+    busload: f64,
+}
+
+impl CanBusHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the controller's transmit and receive error counters
+    pub fn set_error_counters(&mut self, tx: u8, rx: u8) {
+        self.tx_error_count = tx;
+        self.rx_error_count = rx;
+    }
+
+    /// Sets whether the controller is bus-off, counting each time it enters bus-off
+    pub fn set_bus_off(&mut self, bus_off: bool) {
+        if bus_off && !self.bus_off {
+            self.bus_off_events += 1;
+        }
+        self.bus_off = bus_off;
+    }
+
+    /// Counts a receive overflow, i.e. frames lost because they weren't read in time
+    pub fn record_rx_overflow(&mut self) {
+        self.rx_overflows += 1;
+    }
+
+    /// Counts the bits of a frame seen on the bus
+    pub fn record_frame(&mut self, extended: bool, data_len: usize) {
+        self.record_bits(u64::from(frame_bits(extended, data_len)));
+    }
+
+    pub fn record_bits(&mut self, bits: u64) {
+        self.window_bits += bits;
+    }
+
+    /// Updates the busload estimate, and returns the block outputs
+    pub fn update(&mut self, app_time: Duration, bitrate: f64) -> (f64, f64, f64, f64, f64, bool) {
+        let window_start = *self.window_start.get_or_insert(app_time);
+        let elapsed = app_time.saturating_sub(window_start);
+        if elapsed >= BUSLOAD_WINDOW {
+            self.busload = if bitrate > 0.0 {
+                100.0 * self.window_bits as f64 / (bitrate * elapsed.as_secs_f64())
+            } else {
+                f64::NAN
+            };
+            self.window_bits = 0;
+            self.window_start = Some(app_time);
+        }
+        (
+            f64::from(self.tx_error_count),
+            f64::from(self.rx_error_count),
+            f64::from(self.bus_off_events),
+            f64::from(self.rx_overflows),
+            self.busload,
+            self.bus_off,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_busload() {
+        let mut health = CanBusHealth::new();
+        let bitrate = 500_000.0;
+        assert_eq!(health.update(Duration::ZERO, bitrate).4, 0.0);
+
+        // 100 standard frames of 8 bytes (111 bits each) in 100ms is 11,100 bits of 50,000
+        for _ in 0..100 {
+            health.record_frame(false, 8);
+        }
+        assert_eq!(health.update(Duration::from_millis(50), bitrate).4, 0.0);
+        let busload = health.update(Duration::from_millis(100), bitrate).4;
+        assert!((busload - 22.2).abs() < 1e-9);
+
+        // The estimate is held until the next window ends
+        health.record_frame(true, 0);
+        assert_eq!(
+            health.update(Duration::from_millis(150), bitrate).4,
+            busload
+        );
+        let busload = health.update(Duration::from_millis(200), bitrate).4;
+        assert!((busload - 100.0 * 67.0 / 50_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut health = CanBusHealth::new();
+        health.set_error_counters(128, 5);
+        health.set_bus_off(true);
+        health.set_bus_off(true);
+        health.set_bus_off(false);
+        health.set_bus_off(true);
+        health.record_rx_overflow();
+
+        let (tx, rx, bus_off_events, overflows, _, bus_off) =
+            health.update(Duration::ZERO, 250_000.0);
+        assert_eq!((tx, rx, bus_off_events, overflows), (128.0, 5.0, 2.0, 1.0));
+        assert!(bus_off);
+    }
+
+    #[test]
+    fn test_traffic() {
+        let traffic = CanTraffic::new();
+        traffic.record_frame(false, 2);
+        traffic.record_frame(false, 20);
+        assert_eq!(traffic.take_bits(), 63 + 111);
+        assert_eq!(traffic.take_bits(), 0);
+    }
+}
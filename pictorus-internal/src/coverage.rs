@@ -0,0 +1,331 @@
+//! Coverage tracing for test campaigns.
+//!
+//! Generated code reports the active state of each state machine and the saturation flag of each
+//! saturating block (PID, mixer, control allocation...) once per tick. [`CoverageTracker`] logs
+//! every state transition and every saturation onset and release with its app time as it happens,
+//! and at the end of a run [`CoverageTracker::summary`] lists which states and transitions were
+//! exercised, so users can check that a test campaign reached every mode of their
+//! `StateManager` logic.
+//!
+//! Reporting is cheap but not free, so generated code only does it when the `coverage` feature
+//! is enabled.
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+
+use log::{info, warn};
+use serde::Serialize;
+
+#[derive(Debug, Default)]
+struct MachineCoverage {
+    /// All states of the machine, if declared, so unvisited ones can be reported
+    states: Vec<&'static str>,
+    current: Option<&'static str>,
+    /// Number of times each state was entered, including as the initial state
+    visits: BTreeMap<&'static str, u32>,
+    transitions: BTreeMap<(&'static str, &'static str), u32>,
+}
+
+#[derive(Debug, Default)]
+struct SaturationCoverage {
+    /// App time the current saturation started at, if saturated
+    since: Option<Duration>,
+    events: u32,
+    saturated_time: Duration,
+}
+
+/// Records state machine transitions and block saturation events, see the [module docs](self)
+#[derive(Debug, Default)]
+pub struct CoverageTracker {
+    machines: BTreeMap<&'static str, MachineCoverage>,
+    saturation: BTreeMap<&'static str, SaturationCoverage>,
+}
+
+impl CoverageTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares all states of `machine`, so states that are never visited show up in the summary
+    pub fn declare_states(&mut self, machine: &'static str, states: &[&'static str]) {
+        self.machines.entry(machine).or_default().states = states.to_vec();
+    }
+
+    /// Reports the active state of `machine` for the current tick. The first report is the
+    /// initial state; any later change is logged as a transition.
+    pub fn report_state(&mut self, machine: &'static str, state: &'static str, app_time: Duration) {
+        let coverage = self.machines.entry(machine).or_default();
+        if coverage.current == Some(state) {
+            return;
+        }
+        match coverage.current {
+            Some(from) => {
+                info!("[{app_time:?}] {machine}: {from} -> {state}");
+                *coverage.transitions.entry((from, state)).or_default() += 1;
+            }
+            None => info!("[{app_time:?}] {machine}: initial state {state}"),
+        }
+        *coverage.visits.entry(state).or_default() += 1;
+        coverage.current = Some(state);
+    }
+
+    /// Reports whether `block` is saturated this tick. The onset and release of each saturation
+    /// are logged.
+    pub fn report_saturation(&mut self, block: &'static str, saturated: bool, app_time: Duration) {
+        let coverage = self.saturation.entry(block).or_default();
+        match (coverage.since, saturated) {
+            (None, true) => {
+                info!("[{app_time:?}] {block}: saturated");
+                coverage.since = Some(app_time);
+                coverage.events += 1;
+            }
+            (Some(since), false) => {
+                let duration = app_time.saturating_sub(since);
+                info!("[{app_time:?}] {block}: released after {duration:?}");
+                coverage.saturated_time += duration;
+                coverage.since = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Summarizes coverage up to `app_time`, typically the end of the run. Saturations still in
+    /// progress count up to `app_time`.
+    pub fn summary(&self, app_time: Duration) -> CoverageSummary {
+        let machines = self
+            .machines
+            .iter()
+            .map(|(name, coverage)| MachineSummary {
+                name,
+                visits: coverage
+                    .visits
+                    .iter()
+                    .map(|(state, count)| (*state, *count))
+                    .collect(),
+                unvisited: coverage
+                    .states
+                    .iter()
+                    .copied()
+                    .filter(|state| !coverage.visits.contains_key(state))
+                    .collect(),
+                transitions: coverage
+                    .transitions
+                    .iter()
+                    .map(|((from, to), count)| TransitionSummary {
+                        from,
+                        to,
+                        count: *count,
+                    })
+                    .collect(),
+            })
+            .collect();
+        let saturation = self
+            .saturation
+            .iter()
+            .map(|(block, coverage)| {
+                let ongoing = coverage
+                    .since
+                    .map(|since| app_time.saturating_sub(since))
+                    .unwrap_or_default();
+                SaturationSummary {
+                    block,
+                    events: coverage.events,
+                    saturated_time: coverage.saturated_time + ongoing,
+                }
+            })
+            .collect();
+        CoverageSummary {
+            machines,
+            saturation,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TransitionSummary {
+    pub from: &'static str,
+    pub to: &'static str,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MachineSummary {
+    pub name: &'static str,
+    /// Visited states and the number of times each was entered
+    pub visits: Vec<(&'static str, u32)>,
+    /// Declared states that were never visited
+    pub unvisited: Vec<&'static str>,
+    pub transitions: Vec<TransitionSummary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SaturationSummary {
+    pub block: &'static str,
+    /// Number of times the block became saturated
+    pub events: u32,
+    pub saturated_time: Duration,
+}
+
+/// Post-run report of a [`CoverageTracker`]. Serializes to JSON for test tooling, and displays
+/// as a human readable report.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CoverageSummary {
+    pub machines: Vec<MachineSummary>,
+    pub saturation: Vec<SaturationSummary>,
+}
+
+impl CoverageSummary {
+    /// Whether every declared state of every machine was visited
+    pub fn all_states_visited(&self) -> bool {
+        self.machines.iter().all(|m| m.unvisited.is_empty())
+    }
+
+    /// Logs the report, with a warning for each machine that has unvisited states
+    pub fn log(&self) {
+        info!("Coverage summary:\n{self}");
+        for machine in &self.machines {
+            if !machine.unvisited.is_empty() {
+                warn!(
+                    "{}: states never visited: {}",
+                    machine.name,
+                    machine.unvisited.join(", ")
+                );
+            }
+        }
+    }
+}
+
+impl fmt::Display for CoverageSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for machine in &self.machines {
+            let declared = machine.visits.len() + machine.unvisited.len();
+            writeln!(
+                f,
+                "{}: {}/{declared} states visited",
+                machine.name,
+                machine.visits.len()
+            )?;
+            for (state, count) in &machine.visits {
+                writeln!(f, "  {state}: entered {count} times")?;
+            }
+            for state in &machine.unvisited {
+                writeln!(f, "  {state}: never visited")?;
+            }
+            for t in &machine.transitions {
+                writeln!(f, "  {} -> {}: {} times", t.from, t.to, t.count)?;
+            }
+        }
+        for s in &self.saturation {
+            writeln!(
+                f,
+                "{}: saturated {} times for {:?}",
+                s.block, s.events, s.saturated_time
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_state_coverage() {
+        let mut tracker = CoverageTracker::new();
+        tracker.declare_states("Flight", &["Idle", "Takeoff", "Hover", "Land"]);
+        for (time, state) in [
+            (0, "Idle"),
+            (10, "Idle"),
+            (20, "Takeoff"),
+            (30, "Hover"),
+            (40, "Takeoff"),
+            (50, "Hover"),
+        ] {
+            tracker.report_state("Flight", state, ms(time));
+        }
+
+        let summary = tracker.summary(ms(60));
+        assert!(!summary.all_states_visited());
+        let flight = &summary.machines[0];
+        assert_eq!(flight.name, "Flight");
+        assert_eq!(flight.visits, [("Hover", 2), ("Idle", 1), ("Takeoff", 2)]);
+        assert_eq!(flight.unvisited, ["Land"]);
+        assert_eq!(
+            flight.transitions,
+            [
+                TransitionSummary {
+                    from: "Hover",
+                    to: "Takeoff",
+                    count: 1
+                },
+                TransitionSummary {
+                    from: "Idle",
+                    to: "Takeoff",
+                    count: 1
+                },
+                TransitionSummary {
+                    from: "Takeoff",
+                    to: "Hover",
+                    count: 2
+                },
+            ]
+        );
+
+        tracker.report_state("Flight", "Land", ms(70));
+        assert!(tracker.summary(ms(70)).all_states_visited());
+    }
+
+    #[test]
+    fn test_saturation_coverage() {
+        let mut tracker = CoverageTracker::new();
+        for (time, saturated) in [(0, false), (10, true), (20, true), (30, false), (40, true)] {
+            tracker.report_saturation("Pid1", saturated, ms(time));
+        }
+        tracker.report_saturation("Mixer1", false, ms(40));
+
+        let summary = tracker.summary(ms(100));
+        assert_eq!(
+            summary.saturation,
+            [
+                SaturationSummary {
+                    block: "Mixer1",
+                    events: 0,
+                    saturated_time: Duration::ZERO,
+                },
+                // 20ms completed, plus 60ms still saturated at the end of the run
+                SaturationSummary {
+                    block: "Pid1",
+                    events: 2,
+                    saturated_time: ms(80),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_summary_report() {
+        let mut tracker = CoverageTracker::new();
+        tracker.declare_states("Mode", &["Off", "On"]);
+        tracker.report_state("Mode", "Off", ms(0));
+        tracker.report_saturation("Pid1", true, ms(0));
+
+        let report = tracker.summary(ms(5)).to_string();
+        assert_eq!(
+            report.lines().collect::<Vec<_>>(),
+            vec![
+                "Mode: 1/2 states visited",
+                "  Off: entered 1 times",
+                "  On: never visited",
+                "Pid1: saturated 1 times for 5ms",
+            ]
+        );
+    }
+}
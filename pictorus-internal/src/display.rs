@@ -0,0 +1,701 @@
+//! Drivers for small text displays: HD44780 character LCDs behind a PCF8574 I2C backpack, and
+//! SSD1306 OLEDs over I2C or SPI.
+//!
+//! Both are [`TextDisplay`] output blocks that render a vector of signals into lines of text
+//! with a format string, e.g. `"Bus {:.1}V\nTemp {:5.1}C"`. Each `{}` placeholder takes the next
+//! signal, with an optional width (right aligned) and precision: `{}`, `{:.2}`, `{:6}` or
+//! `{:6.2}`. Braces are escaped as `{{` and `}}`, placeholders without a signal show `-`, and
+//! lines are truncated to the width of the display. The drivers are generic over
+//! `embedded-hal`, so the same code runs on Linux and on microcontrollers.
+use core::fmt::Write;
+use core::time::Duration;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::i2c::I2c;
+use embedded_hal::spi::SpiBus;
+use heapless::String;
+use log::warn;
+use pictorus_traits::{Context, Matrix, OutputBlock, PassBy};
+
+use crate::error::{ErrorKind, PictorusError};
+
+const ERR_TYPE: &str = "DisplayProtocol";
+/// Longest format string
+pub const MAX_FORMAT_LEN: usize = 256;
+/// Most columns and rows of text any display has
+const MAX_COLS: usize = 40;
+const MAX_ROWS: usize = 8;
+
+/// Errors returned while writing to a display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayError {
+    I2c,
+    Spi,
+    Pin,
+}
+
+impl From<DisplayError> for PictorusError {
+    fn from(err: DisplayError) -> Self {
+        let msg = match err {
+            DisplayError::I2c => "I2C transfer failed",
+            DisplayError::Spi => "SPI transfer failed",
+            DisplayError::Pin => "Failed to access pin",
+        };
+        PictorusError::new(ErrorKind::Io, ERR_TYPE, msg)
+    }
+}
+
+/// Parameters for the display blocks. Their settings are fixed when they're created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DisplayParams {}
+
+impl DisplayParams {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// A display showing rows of ASCII text
+pub trait TextPanel {
+    /// Size of the display in (columns, rows) of text
+    fn size(&self) -> (usize, usize);
+
+    /// Shows `text` on a row, where `text` has exactly one byte per column
+    fn write_row(&mut self, row: usize, text: &[u8]) -> Result<(), DisplayError>;
+}
+
+/// Writes `value` formatted by a placeholder's spec (the text between `{` and `}`) to `out`
+fn format_value(out: &mut String<MAX_COLS>, spec: &str, value: Option<f64>) {
+    let spec = spec.strip_prefix(':').unwrap_or(spec);
+    let (width, precision) = spec.split_once('.').unwrap_or((spec, ""));
+    let width = width.parse().unwrap_or(0);
+    let precision = precision.parse().ok();
+    // Values wider than a row are truncated, so an overflowing write is fine to ignore
+    match (value, precision) {
+        (None, _) => write!(out, "{:>width$}", "-"),
+        (Some(value), Some(precision)) => write!(out, "{value:>width$.precision$}"),
+        (Some(value), None) => write!(out, "{value:>width$}"),
+    }
+    .ok();
+}
+
+/// Renders `format` with `values` into rows of `cols` bytes, padded with spaces
+fn render(format: &str, values: &[f64], cols: usize, rows: &mut [[u8; MAX_COLS]]) {
+    let mut values = values.iter().copied();
+    let mut lines = format.split('\n');
+    for row in rows.iter_mut() {
+        row.fill(b' ');
+        let Some(line) = lines.next() else {
+            continue;
+        };
+        let mut column = 0;
+        let mut push = |byte: u8| {
+            if column < cols {
+                row[column] = if byte.is_ascii_graphic() { byte } else { b' ' };
+                column += 1;
+            }
+        };
+        let mut rest = line.trim_end_matches('\r');
+        while let Some(first) = rest.chars().next() {
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                push(first as u8);
+                rest = &rest[2..];
+            } else if let Some((spec, after)) =
+                rest.strip_prefix('{').and_then(|r| r.split_once('}'))
+            {
+                let mut text = String::new();
+                format_value(&mut text, spec, values.next());
+                text.bytes().for_each(&mut push);
+                rest = after;
+            } else {
+                push(if first.is_ascii() { first as u8 } else { b'?' });
+                rest = &rest[first.len_utf8()..];
+            }
+        }
+    }
+}
+
+/// A [`TextPanel`] as an `OutputBlock` that renders its `N` input signals with a format string.
+///
+/// Only rows whose text changed are written, at most once per refresh interval, since writing
+/// a display over I2C takes milliseconds per row. If a write fails, the row is retried on the
+/// next refresh.
+pub struct TextDisplay<P: TextPanel, const N: usize> {
+    panel: P,
+    format: String<MAX_FORMAT_LEN>,
+    refresh_interval: Duration,
+    last_refresh: Option<Duration>,
+    /// Text on the display, or None for rows in an unknown state
+    shown: [Option<[u8; MAX_COLS]>; MAX_ROWS],
+}
+
+impl<P: TextPanel, const N: usize> TextDisplay<P, N> {
+    pub fn new(panel: P, format: &str, refresh_interval: Duration) -> Result<Self, PictorusError> {
+        let mut format_string = String::new();
+        format_string.push_str(format).map_err(|_| {
+            PictorusError::new(
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
+                "Display format is too long",
+            )
+        })?;
+        Ok(Self {
+            panel,
+            format: format_string,
+            refresh_interval,
+            last_refresh: None,
+            shown: [None; MAX_ROWS],
+        })
+    }
+
+    /// Renders `values` and writes the rows that changed
+    pub fn show(&mut self, values: &[f64]) -> Result<(), DisplayError> {
+        let (cols, rows) = self.panel.size();
+        let mut text = [[b' '; MAX_COLS]; MAX_ROWS];
+        render(&self.format, values, cols, &mut text[..rows]);
+        for (row, (text, shown)) in text.iter().zip(&mut self.shown).take(rows).enumerate() {
+            if shown.as_ref() != Some(text) {
+                *shown = None;
+                self.panel.write_row(row, &text[..cols])?;
+                *shown = Some(*text);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<P: TextPanel, const N: usize> OutputBlock for TextDisplay<P, N> {
+    type Inputs = Matrix<1, N, f64>;
+    type Parameters = DisplayParams;
+
+    fn output(
+        &mut self,
+        _parameters: &Self::Parameters,
+        context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) {
+        let time = context.time();
+        if self
+            .last_refresh
+            .is_some_and(|last| time.saturating_sub(last) < self.refresh_interval)
+        {
+            return;
+        }
+        self.last_refresh = Some(time);
+        let values = inputs.data.map(|value| value[0]);
+        if let Err(err) = self.show(&values) {
+            warn!("Failed to update display: {err:?}");
+        }
+    }
+}
+
+mod hd44780 {
+    // PCF8574 pins of the common backpacks, with the data nibble on P4 to P7
+    pub const RS: u8 = 0x01;
+    pub const EN: u8 = 0x04;
+    pub const BACKLIGHT: u8 = 0x08;
+
+    pub const CLEAR: u8 = 0x01;
+    pub const ENTRY_MODE_INCREMENT: u8 = 0x06;
+    pub const DISPLAY_ON: u8 = 0x0C;
+    /// 4-bit interface, two line mode (which four line displays also use), 5x8 font
+    pub const FUNCTION_SET: u8 = 0x28;
+    pub const SET_DDRAM_ADDRESS: u8 = 0x80;
+}
+
+/// HD44780 character LCD (16x2, 20x4, ...) behind a PCF8574 I2C backpack, usually at address
+/// 0x27 or 0x3F
+pub struct Hd44780<I: I2c, D: DelayNs> {
+    i2c: I,
+    delay: D,
+    address: u8,
+    cols: usize,
+    rows: usize,
+}
+
+impl<I: I2c, D: DelayNs> Hd44780<I, D> {
+    /// Initializes the LCD in 4-bit mode, with the backlight on
+    pub fn new(
+        i2c: I,
+        delay: D,
+        address: u8,
+        cols: usize,
+        rows: usize,
+    ) -> Result<Self, PictorusError> {
+        if !(1..=MAX_COLS).contains(&cols) || !(1..=4).contains(&rows) {
+            return Err(PictorusError::new(
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
+                "HD44780 displays have up to 40 columns and 4 rows",
+            ));
+        }
+        let mut lcd = Self {
+            i2c,
+            delay,
+            address,
+            cols,
+            rows,
+        };
+        lcd.init()?;
+        Ok(lcd)
+    }
+
+    fn write_nibble(&mut self, nibble: u8, flags: u8) -> Result<(), DisplayError> {
+        let byte = nibble << 4 | flags | hd44780::BACKLIGHT;
+        self.i2c
+            .write(self.address, &[byte | hd44780::EN, byte])
+            .map_err(|_| DisplayError::I2c)?;
+        self.delay.delay_us(50);
+        Ok(())
+    }
+
+    fn write_byte(&mut self, byte: u8, flags: u8) -> Result<(), DisplayError> {
+        self.write_nibble(byte >> 4, flags)?;
+        self.write_nibble(byte & 0x0F, flags)
+    }
+
+    fn init(&mut self) -> Result<(), DisplayError> {
+        self.delay.delay_ms(50);
+        // Resets to 8-bit mode from any state, then switches to 4-bit mode
+        for delay_us in [4_500, 150, 150] {
+            self.write_nibble(0x03, 0)?;
+            self.delay.delay_us(delay_us);
+        }
+        self.write_nibble(0x02, 0)?;
+        self.write_byte(hd44780::FUNCTION_SET, 0)?;
+        self.write_byte(hd44780::DISPLAY_ON, 0)?;
+        self.write_byte(hd44780::CLEAR, 0)?;
+        self.delay.delay_ms(2);
+        self.write_byte(hd44780::ENTRY_MODE_INCREMENT, 0)
+    }
+}
+
+impl<I: I2c, D: DelayNs> TextPanel for Hd44780<I, D> {
+    fn size(&self) -> (usize, usize) {
+        (self.cols, self.rows)
+    }
+
+    fn write_row(&mut self, row: usize, text: &[u8]) -> Result<(), DisplayError> {
+        // Rows 2 and 3 continue rows 0 and 1 in display memory
+        let start = [0x00, 0x40, self.cols, 0x40 + self.cols][row] as u8;
+        self.write_byte(hd44780::SET_DDRAM_ADDRESS | start, 0)?;
+        text.iter()
+            .try_for_each(|byte| self.write_byte(*byte, hd44780::RS))
+    }
+}
+
+/// Transfers commands and display data to an SSD1306
+pub trait Ssd1306Interface {
+    fn command(&mut self, bytes: &[u8]) -> Result<(), DisplayError>;
+    fn data(&mut self, bytes: &[u8]) -> Result<(), DisplayError>;
+}
+
+/// SSD1306 on an I2C bus, usually at address 0x3C
+pub struct I2cSsd1306<I: I2c> {
+    pub i2c: I,
+    pub address: u8,
+}
+
+impl<I: I2c> I2cSsd1306<I> {
+    /// Writes `bytes` after the control byte
+    fn write(&mut self, control: u8, bytes: &[u8]) -> Result<(), DisplayError> {
+        let mut buffer: heapless::Vec<u8, { ssd1306::WIDTH + 1 }> = heapless::Vec::new();
+        for chunk in bytes.chunks(ssd1306::WIDTH) {
+            buffer.clear();
+            buffer.push(control).ok();
+            buffer.extend_from_slice(chunk).ok();
+            self.i2c
+                .write(self.address, &buffer)
+                .map_err(|_| DisplayError::I2c)?;
+        }
+        Ok(())
+    }
+}
+
+impl<I: I2c> Ssd1306Interface for I2cSsd1306<I> {
+    fn command(&mut self, bytes: &[u8]) -> Result<(), DisplayError> {
+        self.write(0x00, bytes)
+    }
+
+    fn data(&mut self, bytes: &[u8]) -> Result<(), DisplayError> {
+        self.write(0x40, bytes)
+    }
+}
+
+/// SSD1306 on an SPI bus (mode 0), with the D/C pin low for commands and high for data
+pub struct SpiSsd1306<B: SpiBus, P: OutputPin> {
+    pub spi: B,
+    pub dc: P,
+}
+
+impl<B: SpiBus, P: OutputPin> SpiSsd1306<B, P> {
+    fn write(&mut self, data: bool, bytes: &[u8]) -> Result<(), DisplayError> {
+        self.dc
+            .set_state(data.into())
+            .map_err(|_| DisplayError::Pin)?;
+        self.spi
+            .write(bytes)
+            .and_then(|_| self.spi.flush())
+            .map_err(|_| DisplayError::Spi)
+    }
+}
+
+impl<B: SpiBus, P: OutputPin> Ssd1306Interface for SpiSsd1306<B, P> {
+    fn command(&mut self, bytes: &[u8]) -> Result<(), DisplayError> {
+        self.write(false, bytes)
+    }
+
+    fn data(&mut self, bytes: &[u8]) -> Result<(), DisplayError> {
+        self.write(true, bytes)
+    }
+}
+
+mod ssd1306 {
+    pub const WIDTH: usize = 128;
+    /// Pixel columns of a character, including a blank column between characters
+    pub const CHAR_WIDTH: usize = 6;
+    pub const COLS: usize = WIDTH / CHAR_WIDTH;
+
+    pub const DISPLAY_OFF: u8 = 0xAE;
+    pub const DISPLAY_ON: u8 = 0xAF;
+    pub const SET_COLUMN_ADDRESS: u8 = 0x21;
+    pub const SET_PAGE_ADDRESS: u8 = 0x22;
+
+    /// 5x7 font for the printable ASCII characters, one byte per pixel column with the top
+    /// pixel in the least significant bit
+    pub const FONT: [[u8; 5]; 95] = [
+        [0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+        [0x00, 0x00, 0x5F, 0x00, 0x00], // !
+        [0x00, 0x07, 0x00, 0x07, 0x00], // "
+        [0x14, 0x7F, 0x14, 0x7F, 0x14], // #
+        [0x24, 0x2A, 0x7F, 0x2A, 0x12], // $
+        [0x23, 0x13, 0x08, 0x64, 0x62], // %
+        [0x36, 0x49, 0x55, 0x22, 0x50], // &
+        [0x00, 0x05, 0x03, 0x00, 0x00], // '
+        [0x00, 0x1C, 0x22, 0x41, 0x00], // (
+        [0x00, 0x41, 0x22, 0x1C, 0x00], // )
+        [0x14, 0x08, 0x3E, 0x08, 0x14], // *
+        [0x08, 0x08, 0x3E, 0x08, 0x08], // +
+        [0x00, 0x50, 0x30, 0x00, 0x00], // ,
+        [0x08, 0x08, 0x08, 0x08, 0x08], // -
+        [0x00, 0x60, 0x60, 0x00, 0x00], // .
+        [0x20, 0x10, 0x08, 0x04, 0x02], // /
+        [0x3E, 0x51, 0x49, 0x45, 0x3E], // 0
+        [0x00, 0x42, 0x7F, 0x40, 0x00], // 1
+        [0x42, 0x61, 0x51, 0x49, 0x46], // 2
+        [0x21, 0x41, 0x45, 0x4B, 0x31], // 3
+        [0x18, 0x14, 0x12, 0x7F, 0x10], // 4
+        [0x27, 0x45, 0x45, 0x45, 0x39], // 5
+        [0x3C, 0x4A, 0x49, 0x49, 0x30], // 6
+        [0x01, 0x71, 0x09, 0x05, 0x03], // 7
+        [0x36, 0x49, 0x49, 0x49, 0x36], // 8
+        [0x06, 0x49, 0x49, 0x29, 0x1E], // 9
+        [0x00, 0x36, 0x36, 0x00, 0x00], // :
+        [0x00, 0x56, 0x36, 0x00, 0x00], // ;
+        [0x08, 0x14, 0x22, 0x41, 0x00], // <
+        [0x14, 0x14, 0x14, 0x14, 0x14], // =
+        [0x00, 0x41, 0x22, 0x14, 0x08], // >
+        [0x02, 0x01, 0x51, 0x09, 0x06], // ?
+        [0x32, 0x49, 0x79, 0x41, 0x3E], // @
+        [0x7E, 0x11, 0x11, 0x11, 0x7E], // A
+        [0x7F, 0x49, 0x49, 0x49, 0x36], // B
+        [0x3E, 0x41, 0x41, 0x41, 0x22], // C
+        [0x7F, 0x41, 0x41, 0x22, 0x1C], // D
+        [0x7F, 0x49, 0x49, 0x49, 0x41], // E
+        [0x7F, 0x09, 0x09, 0x09, 0x01], // F
+        [0x3E, 0x41, 0x49, 0x49, 0x7A], // G
+        [0x7F, 0x08, 0x08, 0x08, 0x7F], // H
+        [0x00, 0x41, 0x7F, 0x41, 0x00], // I
+        [0x20, 0x40, 0x41, 0x3F, 0x01], // J
+        [0x7F, 0x08, 0x14, 0x22, 0x41], // K
+        [0x7F, 0x40, 0x40, 0x40, 0x40], // L
+        [0x7F, 0x02, 0x0C, 0x02, 0x7F], // M
+        [0x7F, 0x04, 0x08, 0x10, 0x7F], // N
+        [0x3E, 0x41, 0x41, 0x41, 0x3E], // O
+        [0x7F, 0x09, 0x09, 0x09, 0x06], // P
+        [0x3E, 0x41, 0x51, 0x21, 0x5E], // Q
+        [0x7F, 0x09, 0x19, 0x29, 0x46], // R
+        [0x46, 0x49, 0x49, 0x49, 0x31], // S
+        [0x01, 0x01, 0x7F, 0x01, 0x01], // T
+        [0x3F, 0x40, 0x40, 0x40, 0x3F], // U
+        [0x1F, 0x20, 0x40, 0x20, 0x1F], // V
+        [0x3F, 0x40, 0x38, 0x40, 0x3F], // W
+        [0x63, 0x14, 0x08, 0x14, 0x63], // X
+        [0x07, 0x08, 0x70, 0x08, 0x07], // Y
+        [0x61, 0x51, 0x49, 0x45, 0x43], // Z
+        [0x00, 0x7F, 0x41, 0x41, 0x00], // [
+        [0x02, 0x04, 0x08, 0x10, 0x20], // \
+        [0x00, 0x41, 0x41, 0x7F, 0x00], // ]
+        [0x04, 0x02, 0x01, 0x02, 0x04], // ^
+        [0x40, 0x40, 0x40, 0x40, 0x40], // _
+        [0x00, 0x01, 0x02, 0x04, 0x00], // `
+        [0x20, 0x54, 0x54, 0x54, 0x78], // a
+        [0x7F, 0x48, 0x44, 0x44, 0x38], // b
+        [0x38, 0x44, 0x44, 0x44, 0x20], // c
+        [0x38, 0x44, 0x44, 0x48, 0x7F], // d
+        [0x38, 0x54, 0x54, 0x54, 0x18], // e
+        [0x08, 0x7E, 0x09, 0x01, 0x02], // f
+        [0x0C, 0x52, 0x52, 0x52, 0x3E], // g
+        [0x7F, 0x08, 0x04, 0x04, 0x78], // h
+        [0x00, 0x44, 0x7D, 0x40, 0x00], // i
+        [0x20, 0x40, 0x44, 0x3D, 0x00], // j
+        [0x7F, 0x10, 0x28, 0x44, 0x00], // k
+        [0x00, 0x41, 0x7F, 0x40, 0x00], // l
+        [0x7C, 0x04, 0x18, 0x04, 0x78], // m
+        [0x7C, 0x08, 0x04, 0x04, 0x78], // n
+        [0x38, 0x44, 0x44, 0x44, 0x38], // o
+        [0x7C, 0x14, 0x14, 0x14, 0x08], // p
+        [0x08, 0x14, 0x14, 0x18, 0x7C], // q
+        [0x7C, 0x08, 0x04, 0x04, 0x08], // r
+        [0x48, 0x54, 0x54, 0x54, 0x20], // s
+        [0x04, 0x3F, 0x44, 0x40, 0x20], // t
+        [0x3C, 0x40, 0x40, 0x20, 0x7C], // u
+        [0x1C, 0x20, 0x40, 0x20, 0x1C], // v
+        [0x3C, 0x40, 0x30, 0x40, 0x3C], // w
+        [0x44, 0x28, 0x10, 0x28, 0x44], // x
+        [0x0C, 0x50, 0x50, 0x50, 0x3C], // y
+        [0x44, 0x64, 0x54, 0x4C, 0x44], // z
+        [0x00, 0x08, 0x36, 0x41, 0x00], // {
+        [0x00, 0x00, 0x7F, 0x00, 0x00], // |
+        [0x00, 0x41, 0x36, 0x08, 0x00], // }
+        [0x08, 0x04, 0x08, 0x10, 0x08], // ~
+    ];
+}
+
+/// SSD1306 128x64 or 128x32 monochrome OLED, showing 21 columns of text on each 8 pixel row
+pub struct Ssd1306<S: Ssd1306Interface> {
+    interface: S,
+    rows: usize,
+}
+
+impl<S: Ssd1306Interface> Ssd1306<S> {
+    /// Initializes and clears the display. `height` is 64 or 32 pixels.
+    pub fn new(interface: S, height: usize) -> Result<Self, PictorusError> {
+        if height != 64 && height != 32 {
+            return Err(PictorusError::new(
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
+                "SSD1306 height must be 64 or 32 pixels",
+            ));
+        }
+        let mut display = Self {
+            interface,
+            rows: height / 8,
+        };
+        display.init(height as u8)?;
+        Ok(display)
+    }
+
+    fn init(&mut self, height: u8) -> Result<(), DisplayError> {
+        let com_pins = if height == 64 { 0x12 } else { 0x02 };
+        self.interface.command(&[
+            ssd1306::DISPLAY_OFF,
+            0xD5, // Clock divide ratio and oscillator frequency
+            0x80,
+            0xA8, // Multiplex ratio
+            height - 1,
+            0xD3, // Display offset
+            0x00,
+            0x40, // Start line 0
+            0x8D, // Charge pump on
+            0x14,
+            0x20, // Horizontal addressing
+            0x00,
+            0xA1, // Segments and COM scan remapped, for the usual module orientation
+            0xC8,
+            0xDA, // COM pins configuration
+            com_pins,
+            0x81, // Contrast
+            0xCF,
+            0xD9, // Precharge period
+            0xF1,
+            0xDB, // VCOMH deselect level
+            0x40,
+            0xA4, // Display follows RAM
+            0xA6, // Not inverted
+        ])?;
+        let blank = [0; ssd1306::WIDTH];
+        for page in 0..self.rows {
+            self.write_page(page, &blank)?;
+        }
+        self.interface.command(&[ssd1306::DISPLAY_ON])
+    }
+
+    fn write_page(
+        &mut self,
+        page: usize,
+        pixels: &[u8; ssd1306::WIDTH],
+    ) -> Result<(), DisplayError> {
+        self.interface.command(&[
+            ssd1306::SET_COLUMN_ADDRESS,
+            0,
+            ssd1306::WIDTH as u8 - 1,
+            ssd1306::SET_PAGE_ADDRESS,
+            page as u8,
+            page as u8,
+        ])?;
+        self.interface.data(pixels)
+    }
+}
+
+impl<S: Ssd1306Interface> TextPanel for Ssd1306<S> {
+    fn size(&self) -> (usize, usize) {
+        (ssd1306::COLS, self.rows)
+    }
+
+    fn write_row(&mut self, row: usize, text: &[u8]) -> Result<(), DisplayError> {
+        let mut pixels = [0; ssd1306::WIDTH];
+        for (byte, columns) in text.iter().zip(pixels.chunks_mut(ssd1306::CHAR_WIDTH)) {
+            let glyph = ssd1306::FONT
+                .get(usize::from(byte.wrapping_sub(b' ')))
+                .unwrap_or(&ssd1306::FONT[usize::from(b'?' - b' ')]);
+            columns[..glyph.len()].copy_from_slice(glyph);
+        }
+        self.write_page(row, &pixels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use core::convert::Infallible;
+    use embedded_hal::i2c::{ErrorType, Operation};
+
+    use crate::can_demux::tests::StubContext;
+
+    struct StubDelay;
+
+    impl DelayNs for StubDelay {
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    fn rendered<const N: usize>(format: &str, values: &[f64]) -> Vec<alloc::string::String> {
+        let mut rows = [[b' '; MAX_COLS]; N];
+        render(format, values, 16, &mut rows);
+        rows.iter()
+            .map(|row| alloc::string::String::from_utf8(row[..16].to_vec()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            rendered::<3>("V={:.2} I={:5.1}\n{{x}}: {}", &[12.345, -0.25, 7.0]),
+            ["V=12.35 I= -0.2 ", "{x}: 7          ", "                "]
+        );
+        // Missing values, long lines and non-ASCII text
+        assert_eq!(
+            rendered::<2>("{:3} {}\n0123456789abcdefXYZ", &[f64::NAN]),
+            ["NaN -           ", "0123456789abcdef"]
+        );
+        assert_eq!(rendered::<1>("T {:.1}°C", &[21.04]), ["T 21.0?C        "]);
+    }
+
+    /// Records the bytes of each I2C write
+    #[derive(Default)]
+    struct StubI2c {
+        writes: Vec<Vec<u8>>,
+    }
+
+    impl ErrorType for StubI2c {
+        type Error = Infallible;
+    }
+
+    impl I2c for StubI2c {
+        fn transaction(
+            &mut self,
+            _address: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), Self::Error> {
+            for operation in operations {
+                if let Operation::Write(bytes) = operation {
+                    self.writes.push(bytes.to_vec());
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Decodes the (RS, byte) pairs sent to an HD44780 from the backpack writes
+    fn decode(writes: &[Vec<u8>]) -> Vec<(bool, u8)> {
+        let nibbles: Vec<(bool, u8)> = writes
+            .iter()
+            .map(|write| (write[1] & hd44780::RS != 0, write[1] >> 4))
+            .collect();
+        nibbles
+            .chunks(2)
+            .map(|pair| (pair[0].0, pair[0].1 << 4 | pair[1].1))
+            .collect()
+    }
+
+    #[test]
+    fn test_hd44780() {
+        let lcd = Hd44780::new(StubI2c::default(), StubDelay, 0x27, 16, 2).unwrap();
+        let mut display =
+            TextDisplay::<_, 1>::new(lcd, "Speed\n{:.1} rpm", Duration::from_millis(100)).unwrap();
+        let params = DisplayParams::new();
+        // Skips the four reset nibbles
+        let init = decode(&display.panel.i2c.writes[4..]);
+        assert_eq!(
+            init,
+            [(false, 0x28), (false, 0x0C), (false, 0x01), (false, 0x06)]
+        );
+
+        display.panel.i2c.writes.clear();
+        display.output(
+            &params,
+            &StubContext(Duration::ZERO),
+            &Matrix { data: [[1.5]] },
+        );
+        let sent = decode(&display.panel.i2c.writes);
+        assert_eq!(sent[0], (false, 0x80));
+        assert_eq!(sent[17], (false, 0xC0));
+        let text: Vec<u8> = sent[18..].iter().map(|(_, byte)| *byte).collect();
+        assert_eq!(text, b"1.5 rpm         ");
+
+        // Throttled, then only the changed row is written
+        display.panel.i2c.writes.clear();
+        display.output(
+            &params,
+            &StubContext(Duration::from_millis(50)),
+            &Matrix { data: [[2.5]] },
+        );
+        assert!(display.panel.i2c.writes.is_empty());
+        display.output(
+            &params,
+            &StubContext(Duration::from_millis(100)),
+            &Matrix { data: [[2.5]] },
+        );
+        let sent = decode(&display.panel.i2c.writes);
+        assert_eq!(sent.len(), 17);
+        assert_eq!(sent[0], (false, 0xC0));
+    }
+
+    #[test]
+    fn test_ssd1306() {
+        let interface = I2cSsd1306 {
+            i2c: StubI2c::default(),
+            address: 0x3C,
+        };
+        let oled = Ssd1306::new(interface, 32).unwrap();
+        let mut display = TextDisplay::<_, 0>::new(oled, "\nA", Duration::ZERO).unwrap();
+        // Commands, then a command and a data write clearing each of the 4 pages
+        assert_eq!(display.panel.interface.i2c.writes.len(), 1 + 4 * 2 + 1);
+
+        display.panel.interface.i2c.writes.clear();
+        display.show(&[]).unwrap();
+        let writes = &display.panel.interface.i2c.writes;
+        // All four rows are written the first time
+        assert_eq!(writes.len(), 8);
+        assert_eq!(writes[2], [0x00, 0x21, 0, 127, 0x22, 1, 1]);
+        assert_eq!(writes[3][0], 0x40);
+        assert_eq!(writes[3][1..7], [0x7E, 0x11, 0x11, 0x11, 0x7E, 0x00]);
+        assert!(writes[3][7..].iter().all(|byte| *byte == 0));
+
+        display.panel.interface.i2c.writes.clear();
+        display.show(&[]).unwrap();
+        assert!(display.panel.interface.i2c.writes.is_empty());
+    }
+}
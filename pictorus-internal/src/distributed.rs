@@ -0,0 +1,503 @@
+//! Time synchronization and signal exchange between Pictorus apps on different devices, so their
+//! control loops run phase aligned and exchange signals deterministically.
+//!
+//! One app runs a [`SyncMaster`] and the others a [`SyncSlave`], exchanging a two-step protocol
+//! over UDP like PTP's: the master periodically sends a `Sync` message followed by a
+//! `FollowUp` carrying the time it sent the `Sync`, and the slave then measures the path delay
+//! with a `DelayReq` that the master answers with the time it received it. From the four
+//! timestamps the slave estimates the offset of its clock from the master's, assuming a
+//! symmetric path, and keeps the estimate of the exchange with the lowest delay among the
+//! recent ones, since queuing only ever adds delay.
+//!
+//! Times are microseconds on each app's own monotonic clock, passed in by the caller.
+//! Messages are timestamped when they're polled, not when they arrive, so both sides should
+//! poll often (e.g. while waiting for the next tick) for accurate estimates.
+//!
+//! Apps then align their ticks to the master's clock with [`time_to_next_tick`], and number
+//! them with [`tick_index`], so every node agrees on which tick is which. With a
+//! [`SignalExchange`], each node publishes its outputs for a tick, and reads its peers'
+//! outputs for the previous tick, so every node sees the same inputs however the frames are
+//! ordered on the network, as long as they arrive within a tick.
+use core::time::Duration;
+use heapless::Deque;
+use log::warn;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::vec::Vec;
+
+use crate::error::{ErrorKind, PictorusError};
+
+const ERR_TYPE: &str = "Distributed";
+/// First byte of every message, to ignore unrelated traffic on the port
+const MAGIC: u8 = b'P';
+const SYNC_MESSAGE_LEN: usize = 14;
+/// Largest datagram read
+const MAX_DATAGRAM: usize = 1500;
+/// Exchanges the offset estimate is chosen from
+const ESTIMATE_WINDOW: usize = 8;
+
+/// Messages of the synchronization protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMessage {
+    /// Sent by the master to start an exchange
+    Sync { seq: u32 },
+    /// Master time the `Sync` with the same sequence number was sent
+    FollowUp { seq: u32, master_time_us: u64 },
+    /// Sent by a slave to measure the path delay
+    DelayReq { seq: u32 },
+    /// Master time the `DelayReq` with the same sequence number was received
+    DelayResp { seq: u32, master_time_us: u64 },
+}
+
+impl SyncMessage {
+    fn encode(self) -> [u8; SYNC_MESSAGE_LEN] {
+        let (kind, seq, time) = match self {
+            SyncMessage::Sync { seq } => (1, seq, 0),
+            SyncMessage::FollowUp {
+                seq,
+                master_time_us,
+            } => (2, seq, master_time_us),
+            SyncMessage::DelayReq { seq } => (3, seq, 0),
+            SyncMessage::DelayResp {
+                seq,
+                master_time_us,
+            } => (4, seq, master_time_us),
+        };
+        let mut bytes = [0; SYNC_MESSAGE_LEN];
+        bytes[0] = MAGIC;
+        bytes[1] = kind;
+        bytes[2..6].copy_from_slice(&seq.to_le_bytes());
+        bytes[6..].copy_from_slice(&time.to_le_bytes());
+        bytes
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let bytes: &[u8; SYNC_MESSAGE_LEN] = bytes.try_into().ok()?;
+        if bytes[0] != MAGIC {
+            return None;
+        }
+        let seq = u32::from_le_bytes(bytes[2..6].try_into().ok()?);
+        let master_time_us = u64::from_le_bytes(bytes[6..].try_into().ok()?);
+        match bytes[1] {
+            1 => Some(SyncMessage::Sync { seq }),
+            2 => Some(SyncMessage::FollowUp {
+                seq,
+                master_time_us,
+            }),
+            3 => Some(SyncMessage::DelayReq { seq }),
+            4 => Some(SyncMessage::DelayResp {
+                seq,
+                master_time_us,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Estimates the offset of a slave clock from the master clock from complete exchanges
+#[derive(Debug, Clone, Default)]
+pub struct OffsetEstimator {
+    /// (offset, delay) of the recent exchanges
+    samples: Deque<(i64, i64), ESTIMATE_WINDOW>,
+    /// Slave time of the latest exchange
+    last_sample_us: Option<u64>,
+}
+
+impl OffsetEstimator {
+    /// Adds an exchange, where `t1` is the master time the `Sync` was sent, `t2` the slave time
+    /// it was received, `t3` the slave time the `DelayReq` was sent and `t4` the master time it
+    /// was received
+    pub fn add(&mut self, t1: u64, t2: u64, t3: u64, t4: u64) {
+        let master_to_slave = t2 as i64 - t1 as i64;
+        let slave_to_master = t4 as i64 - t3 as i64;
+        let offset = (master_to_slave - slave_to_master) / 2;
+        let delay = (master_to_slave + slave_to_master) / 2;
+        if self.samples.is_full() {
+            self.samples.pop_front();
+        }
+        // Can't fail, there's always room after dropping the oldest sample
+        self.samples.push_back((offset, delay)).ok();
+        self.last_sample_us = Some(t3);
+    }
+
+    /// Offset of the slave clock from the master clock, from the recent exchange with the
+    /// lowest delay
+    pub fn offset_us(&self) -> Option<i64> {
+        self.best().map(|(offset, _)| offset)
+    }
+
+    /// One way path delay of the exchange the offset is taken from
+    pub fn delay_us(&self) -> Option<i64> {
+        self.best().map(|(_, delay)| delay)
+    }
+
+    fn best(&self) -> Option<(i64, i64)> {
+        self.samples.iter().copied().min_by_key(|(_, delay)| *delay)
+    }
+}
+
+/// Microseconds until the next tick of a loop running every `period_us` on the master clock,
+/// or zero when a tick is due now
+pub fn time_to_next_tick(master_time_us: u64, period_us: u64) -> u64 {
+    (period_us - master_time_us % period_us) % period_us
+}
+
+/// Number of the tick running at `master_time_us`, counting from the start of the master
+/// clock
+pub fn tick_index(master_time_us: u64, period_us: u64) -> u64 {
+    master_time_us / period_us
+}
+
+fn resolve(address: &str) -> Result<SocketAddr, PictorusError> {
+    address
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .ok_or_else(|| {
+            PictorusError::new(
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
+                std::format!("Couldn't resolve address: {address}"),
+            )
+        })
+}
+
+fn bind(address: &str) -> Result<UdpSocket, PictorusError> {
+    let socket = UdpSocket::bind(address).map_err(|err| {
+        PictorusError::from_io(
+            ERR_TYPE,
+            std::format!("Couldn't bind UDP socket at {address}"),
+            &err,
+        )
+    })?;
+    socket.set_nonblocking(true).map_err(|err| {
+        PictorusError::from_io(ERR_TYPE, "Failed to set nonblocking on UDP socket", &err)
+    })?;
+    Ok(socket)
+}
+
+/// Receives the datagrams queued on a nonblocking socket
+fn receive<'a>(socket: &UdpSocket, buffer: &'a mut [u8]) -> Option<(&'a [u8], SocketAddr)> {
+    let (len, from) = socket.recv_from(buffer).ok()?;
+    Some((&buffer[..len], from))
+}
+
+/// Master of the synchronization protocol, whose clock the slaves follow
+pub struct SyncMaster {
+    socket: UdpSocket,
+    slaves: Vec<SocketAddr>,
+    sync_interval_us: u64,
+    next_sync_us: Option<u64>,
+    seq: u32,
+}
+
+impl SyncMaster {
+    /// Binds the master to `address` (e.g. "0.0.0.0:7400"), sending a `Sync` to each of the
+    /// `slaves` every `sync_interval`
+    pub fn new(
+        address: &str,
+        slaves: &[&str],
+        sync_interval: Duration,
+    ) -> Result<Self, PictorusError> {
+        Ok(Self {
+            socket: bind(address)?,
+            slaves: slaves
+                .iter()
+                .map(|slave| resolve(slave))
+                .collect::<Result<_, _>>()?,
+            sync_interval_us: sync_interval.as_micros() as u64,
+            next_sync_us: None,
+            seq: 0,
+        })
+    }
+
+    /// Answers the queued delay requests, and starts an exchange with each slave when the sync
+    /// interval has passed. `now_us` is the master clock.
+    pub fn poll(&mut self, now_us: u64) {
+        let mut buffer = [0; MAX_DATAGRAM];
+        while let Some((bytes, from)) = receive(&self.socket, &mut buffer) {
+            if let Some(SyncMessage::DelayReq { seq }) = SyncMessage::decode(bytes) {
+                let response = SyncMessage::DelayResp {
+                    seq,
+                    master_time_us: now_us,
+                };
+                self.socket.send_to(&response.encode(), from).ok();
+            }
+        }
+
+        if self.next_sync_us.is_some_and(|next| now_us < next) {
+            return;
+        }
+        self.next_sync_us = Some(now_us + self.sync_interval_us);
+        self.seq = self.seq.wrapping_add(1);
+        let sync = SyncMessage::Sync { seq: self.seq }.encode();
+        let follow_up = SyncMessage::FollowUp {
+            seq: self.seq,
+            master_time_us: now_us,
+        }
+        .encode();
+        for slave in &self.slaves {
+            if let Err(err) = self.socket.send_to(&sync, slave) {
+                warn!("Failed to send sync to {slave}: {err}");
+                continue;
+            }
+            self.socket.send_to(&follow_up, slave).ok();
+        }
+    }
+}
+
+/// Slave of the synchronization protocol, estimating the master clock
+pub struct SyncSlave {
+    socket: UdpSocket,
+    master: SocketAddr,
+    /// Estimates older than this don't count as synchronized
+    timeout_us: u64,
+    estimator: OffsetEstimator,
+    /// Sequence number and slave receive time of the last `Sync`
+    sync: Option<(u32, u64)>,
+    /// Sequence number, t1, t2 and t3 of the exchange waiting for its `DelayResp`
+    delay_req: Option<(u32, u64, u64, u64)>,
+}
+
+impl SyncSlave {
+    /// Binds the slave to `address` (e.g. "0.0.0.0:7400") to follow the master at `master`.
+    /// The estimate expires after `timeout` without a complete exchange, e.g. a few sync
+    /// intervals.
+    pub fn new(address: &str, master: &str, timeout: Duration) -> Result<Self, PictorusError> {
+        Ok(Self {
+            socket: bind(address)?,
+            master: resolve(master)?,
+            timeout_us: timeout.as_micros() as u64,
+            estimator: OffsetEstimator::default(),
+            sync: None,
+            delay_req: None,
+        })
+    }
+
+    /// Handles the queued messages from the master. `now_us` is the slave clock.
+    pub fn poll(&mut self, now_us: u64) {
+        let mut buffer = [0; MAX_DATAGRAM];
+        while let Some((bytes, from)) = receive(&self.socket, &mut buffer) {
+            if from != self.master {
+                continue;
+            }
+            match SyncMessage::decode(bytes) {
+                Some(SyncMessage::Sync { seq }) => self.sync = Some((seq, now_us)),
+                Some(SyncMessage::FollowUp {
+                    seq,
+                    master_time_us,
+                }) => {
+                    let Some((sync_seq, t2)) = self.sync.take() else {
+                        continue;
+                    };
+                    if sync_seq != seq {
+                        continue;
+                    }
+                    let request = SyncMessage::DelayReq { seq }.encode();
+                    if self.socket.send_to(&request, self.master).is_ok() {
+                        self.delay_req = Some((seq, master_time_us, t2, now_us));
+                    }
+                }
+                Some(SyncMessage::DelayResp {
+                    seq,
+                    master_time_us,
+                }) => {
+                    if let Some((_, t1, t2, t3)) = self.delay_req.filter(|req| req.0 == seq) {
+                        self.estimator.add(t1, t2, t3, master_time_us);
+                        self.delay_req = None;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// True while the offset estimate is recent enough to use
+    pub fn is_synchronized(&self, now_us: u64) -> bool {
+        self.estimator
+            .last_sample_us
+            .is_some_and(|last| now_us.saturating_sub(last) <= self.timeout_us)
+    }
+
+    /// Master clock at slave time `now_us`, once an exchange has completed
+    pub fn master_time_us(&self, now_us: u64) -> Option<u64> {
+        let offset = self.estimator.offset_us()?;
+        Some((now_us as i64 - offset).max(0) as u64)
+    }
+
+    pub fn estimator(&self) -> &OffsetEstimator {
+        &self.estimator
+    }
+}
+
+/// Exchanges the signals of each tick between nodes. Each node publishes `N` values per tick
+/// and keeps the latest frame from each of up to `NODES` nodes, numbered from zero.
+pub struct SignalExchange<const N: usize, const NODES: usize> {
+    socket: UdpSocket,
+    node: u8,
+    peers: Vec<SocketAddr>,
+    latest: [Option<(u64, [f64; N])>; NODES],
+}
+
+impl<const N: usize, const NODES: usize> SignalExchange<N, NODES> {
+    const FRAME_LEN: usize = 11 + 8 * N;
+
+    /// Binds the exchange for `node` to `address`, publishing to `peers`
+    pub fn new(address: &str, node: u8, peers: &[&str]) -> Result<Self, PictorusError> {
+        if usize::from(node) >= NODES || Self::FRAME_LEN > MAX_DATAGRAM || N > usize::from(u8::MAX)
+        {
+            return Err(PictorusError::new(
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
+                "Node number or signal count out of range",
+            ));
+        }
+        Ok(Self {
+            socket: bind(address)?,
+            node,
+            peers: peers
+                .iter()
+                .map(|peer| resolve(peer))
+                .collect::<Result<_, _>>()?,
+            latest: [None; NODES],
+        })
+    }
+
+    /// Sends this node's `values` for `tick` to its peers
+    pub fn publish(&mut self, tick: u64, values: &[f64; N]) {
+        let mut frame = Vec::with_capacity(Self::FRAME_LEN);
+        frame.extend_from_slice(&[MAGIC, 5, self.node]);
+        frame.extend_from_slice(&tick.to_le_bytes());
+        for value in values {
+            frame.extend_from_slice(&value.to_le_bytes());
+        }
+        for peer in &self.peers {
+            if let Err(err) = self.socket.send_to(&frame, peer) {
+                warn!("Failed to send signals to {peer}: {err}");
+            }
+        }
+        self.latest[usize::from(self.node)] = Some((tick, *values));
+    }
+
+    /// Stores the queued frames, keeping the newest tick from each node
+    pub fn poll(&mut self) {
+        let mut buffer = [0; MAX_DATAGRAM];
+        while let Some((bytes, _)) = receive(&self.socket, &mut buffer) {
+            if bytes.len() != Self::FRAME_LEN || bytes[..2] != [MAGIC, 5] {
+                continue;
+            }
+            let Some(latest) = self.latest.get_mut(usize::from(bytes[2])) else {
+                continue;
+            };
+            let tick = u64::from_le_bytes(bytes[3..11].try_into().unwrap());
+            if latest.is_some_and(|(latest_tick, _)| latest_tick >= tick) {
+                continue;
+            }
+            let mut values = [0.0; N];
+            for (value, bytes) in values.iter_mut().zip(bytes[11..].chunks_exact(8)) {
+                *value = f64::from_le_bytes(bytes.try_into().unwrap());
+            }
+            *latest = Some((tick, values));
+        }
+    }
+
+    /// Values `node` published for `tick`, or None if they haven't arrived (yet)
+    pub fn received(&self, node: u8, tick: u64) -> Option<[f64; N]> {
+        self.latest
+            .get(usize::from(node))
+            .copied()
+            .flatten()
+            .filter(|(latest_tick, _)| *latest_tick == tick)
+            .map(|(_, values)| values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn test_sync_message_encoding() {
+        let messages = [
+            SyncMessage::Sync { seq: 7 },
+            SyncMessage::FollowUp {
+                seq: 7,
+                master_time_us: 123_456_789,
+            },
+            SyncMessage::DelayReq { seq: u32::MAX },
+            SyncMessage::DelayResp {
+                seq: 1,
+                master_time_us: u64::MAX,
+            },
+        ];
+        for message in messages {
+            assert_eq!(SyncMessage::decode(&message.encode()), Some(message));
+        }
+        assert_eq!(SyncMessage::decode(b"not a sync msg"), None);
+    }
+
+    #[test]
+    fn test_offset_estimator() {
+        let mut estimator = OffsetEstimator::default();
+        assert_eq!(estimator.offset_us(), None);
+
+        // Slave clock 1000 µs ahead, with a 100 µs path each way
+        estimator.add(10_000, 11_100, 11_200, 10_300);
+        assert_eq!(estimator.offset_us(), Some(1_000));
+        assert_eq!(estimator.delay_us(), Some(100));
+
+        // A delayed Sync skews its exchange, so the lower delay one is kept
+        estimator.add(20_000, 21_900, 22_000, 21_100);
+        assert_eq!(estimator.offset_us(), Some(1_000));
+    }
+
+    #[test]
+    fn test_tick_alignment() {
+        assert_eq!(time_to_next_tick(10_250, 1_000), 750);
+        assert_eq!(time_to_next_tick(10_000, 1_000), 0);
+        assert_eq!(tick_index(10_999, 1_000), 10);
+    }
+
+    #[test]
+    fn test_sync_over_udp() {
+        let mut slave =
+            SyncSlave::new("127.0.0.1:0", "127.0.0.1:9", Duration::from_secs(1)).unwrap();
+        let slave_address = slave.socket.local_addr().unwrap().to_string();
+        let mut master =
+            SyncMaster::new("127.0.0.1:0", &[&slave_address], Duration::from_millis(10)).unwrap();
+        slave.master = master.socket.local_addr().unwrap();
+
+        // Slave clock 5000 µs ahead of the master's, with the messages taking no time
+        master.poll(1_000);
+        std::thread::sleep(Duration::from_millis(20));
+        slave.poll(6_000);
+        std::thread::sleep(Duration::from_millis(20));
+        master.poll(1_000);
+        std::thread::sleep(Duration::from_millis(20));
+        slave.poll(6_010);
+
+        assert!(slave.is_synchronized(6_010));
+        assert_eq!(slave.estimator().offset_us(), Some(5_000));
+        assert_eq!(slave.master_time_us(7_000), Some(2_000));
+        assert!(!slave.is_synchronized(2_000_000));
+    }
+
+    #[test]
+    fn test_signal_exchange() {
+        let mut node_1 = SignalExchange::<2, 2>::new("127.0.0.1:0", 1, &[]).unwrap();
+        let address = node_1.socket.local_addr().unwrap().to_string();
+        let mut node_0 = SignalExchange::<2, 2>::new("127.0.0.1:0", 0, &[&address]).unwrap();
+
+        node_0.publish(4, &[1.5, -2.0]);
+        node_0.publish(3, &[0.0, 0.0]);
+        std::thread::sleep(Duration::from_millis(20));
+        node_1.poll();
+        // Frames older than the newest are dropped
+        assert_eq!(node_1.received(0, 4), Some([1.5, -2.0]));
+        assert_eq!(node_1.received(0, 3), None);
+        assert_eq!(node_0.received(0, 4), None);
+        assert_eq!(node_0.received(0, 3), Some([0.0, 0.0]));
+
+        assert!(SignalExchange::<2, 2>::new("127.0.0.1:0", 2, &[]).is_err());
+    }
+}
@@ -0,0 +1,222 @@
+//! Heap and stack usage monitoring for embedded targets, shared by the platform memory stats
+//! blocks.
+//!
+//! Running out of memory on a microcontroller usually fails silently, or as a hard fault long
+//! after the fact. [`TrackingAllocator`] wraps the global allocator to count the bytes in use and
+//! their peak, and [`StackRegion`] paints the unused stack with a known pattern at startup, so
+//! the deepest the stack has grown can be found later by looking for where the pattern ends.
+//! The memory stats blocks output `(heap used, heap peak, stack peak, stack size)` in bytes.
+use core::alloc::{GlobalAlloc, Layout};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Word the unused stack is painted with
+const STACK_PAINT: u32 = 0xC0FF_EE55;
+/// Stack below the current frame that's left unpainted, for the frames of `paint` itself
+const PAINT_MARGIN: usize = 256;
+
+/// Parameters for the memory stats blocks
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MemoryStatsParams {}
+
+impl MemoryStatsParams {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Heap usage reported by an allocator, in bytes
+pub trait HeapUsage: Sync {
+    /// Bytes currently allocated
+    fn heap_used(&self) -> usize;
+    /// Most bytes allocated at once since startup
+    fn heap_peak(&self) -> usize;
+}
+
+/// Global allocator that counts the bytes allocated through the allocator it wraps.
+///
+/// Declared in place of the target's allocator:
+///
+/// ```ignore
+/// #[global_allocator]
+/// static HEAP: TrackingAllocator<Heap> = TrackingAllocator::new(Heap::empty());
+/// ```
+///
+/// The wrapped allocator is reachable through [`TrackingAllocator::inner`], e.g. to initialize
+/// it. Counting uses atomic read-modify-write operations, which ARMv6-M targets (Cortex-M0/M0+)
+/// don't have.
+pub struct TrackingAllocator<A> {
+    inner: A,
+    used: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl<A> TrackingAllocator<A> {
+    pub const fn new(inner: A) -> Self {
+        Self {
+            inner,
+            used: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    fn add(&self, size: usize) {
+        let used = self.used.fetch_add(size, Ordering::Relaxed) + size;
+        self.peak.fetch_max(used, Ordering::Relaxed);
+    }
+
+    fn remove(&self, size: usize) {
+        self.used.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+impl<A: Sync> HeapUsage for TrackingAllocator<A> {
+    fn heap_used(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    fn heap_peak(&self) -> usize {
+        self.peak.load(Ordering::Relaxed)
+    }
+}
+
+// SAFETY: Allocation is delegated to the wrapped allocator unchanged, only counting its results
+unsafe impl<A: GlobalAlloc> GlobalAlloc for TrackingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { self.inner.alloc(layout) };
+        if !ptr.is_null() {
+            self.add(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { self.inner.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            self.add(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { self.inner.dealloc(ptr, layout) };
+        self.remove(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { self.inner.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            self.remove(layout.size());
+            self.add(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// The memory reserved for a descending stack, from its lowest address (the deepest it may
+/// grow to) up to its highest (where it starts)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StackRegion {
+    bottom: usize,
+    top: usize,
+}
+
+impl StackRegion {
+    /// # Safety
+    ///
+    /// `bottom..top` must be memory only used by the stack (or nothing), valid for reads and
+    /// writes, since painting overwrites whatever is below the current stack frame.
+    pub unsafe fn new(bottom: *const u32, top: *const u32) -> Self {
+        let bottom = (bottom as usize).next_multiple_of(4);
+        Self {
+            bottom,
+            top: (top as usize & !3).max(bottom),
+        }
+    }
+
+    /// Size of the region in bytes
+    pub fn size(&self) -> usize {
+        self.top - self.bottom
+    }
+
+    /// Fills the unused part of the stack with the paint pattern. Should be called once, as
+    /// early as possible, since anything the stack grew into beforehand isn't painted.
+    pub fn paint(&self) {
+        let marker = 0u8;
+        let sp = core::ptr::addr_of!(marker) as usize;
+        // A region the stack isn't in (e.g. another thread's) is painted entirely
+        let end = if (self.bottom..self.top).contains(&sp) {
+            sp.saturating_sub(PAINT_MARGIN).max(self.bottom) & !3
+        } else {
+            self.top
+        };
+        for address in (self.bottom..end).step_by(4) {
+            // SAFETY: The region is only used by the stack, and this is below the current frame
+            unsafe { core::ptr::write_volatile(address as *mut u32, STACK_PAINT) };
+        }
+    }
+
+    /// Most bytes of the stack used since it was painted, found by looking for the first word
+    /// the paint pattern was overwritten in
+    pub fn peak_usage(&self) -> usize {
+        let untouched = (self.bottom..self.top)
+            .step_by(4)
+            // SAFETY: The region is valid for reads
+            .take_while(|&address| unsafe {
+                core::ptr::read_volatile(address as *const u32) == STACK_PAINT
+            })
+            .count();
+        self.size() - untouched * 4
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_tracking_allocator_counts_bytes() {
+        let allocator = TrackingAllocator::new(std::alloc::System);
+        let small = Layout::from_size_align(16, 8).unwrap();
+        let large = Layout::from_size_align(64, 8).unwrap();
+
+        unsafe {
+            let a = allocator.alloc(small);
+            let b = allocator.alloc_zeroed(large);
+            assert_eq!((allocator.heap_used(), allocator.heap_peak()), (80, 80));
+
+            allocator.dealloc(b, large);
+            assert_eq!((allocator.heap_used(), allocator.heap_peak()), (16, 80));
+
+            let a = allocator.realloc(a, small, 32);
+            assert_eq!((allocator.heap_used(), allocator.heap_peak()), (32, 80));
+            allocator.dealloc(a, Layout::from_size_align(32, 8).unwrap());
+        }
+        assert_eq!((allocator.heap_used(), allocator.heap_peak()), (0, 80));
+    }
+
+    #[test]
+    fn test_stack_region_peak_usage() {
+        let mut memory: Box<[u32]> = Vec::from([0; 64]).into_boxed_slice();
+        let range = memory.as_mut_ptr_range();
+        let region = unsafe { StackRegion::new(range.start, range.end) };
+        assert_eq!(region.size(), 256);
+        assert_eq!(region.peak_usage(), 256);
+
+        region.paint();
+        assert_eq!(region.peak_usage(), 0);
+
+        // The stack grows down from the top
+        unsafe { range.start.add(60).write(0) };
+        assert_eq!(region.peak_usage(), 16);
+        unsafe { range.start.add(10).write(0) };
+        assert_eq!(region.peak_usage(), 216);
+    }
+}
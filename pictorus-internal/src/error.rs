@@ -0,0 +1,259 @@
+//! The error type returned by Pictorus protocol drivers and runtime utilities.
+//!
+//! Every error carries an [`ErrorKind`] with a stable numeric code, so generated apps can
+//! react to specific failures (e.g. retry when a device isn't plugged in yet) without
+//! inspecting messages. Without `alloc`, messages are static strings; with `alloc`, messages
+//! can be formatted and errors can be chained to the error that caused them.
+use core::fmt;
+use serde::Serialize;
+use serde::ser::SerializeStruct;
+
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, boxed::Box};
+
+/// The message attached to an error
+#[cfg(feature = "alloc")]
+pub type Message = Cow<'static, str>;
+/// The message attached to an error
+#[cfg(not(feature = "alloc"))]
+pub type Message = &'static str;
+
+/// Broad categories of failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A panic or otherwise uncategorized failure
+    Unhandled,
+    /// A device, port, or file doesn't exist
+    NotFound,
+    /// Access to a device or file was denied
+    PermissionDenied,
+    /// A device is already in use
+    Busy,
+    /// A parameter or configuration value is invalid
+    InvalidConfig,
+    /// A device exists but couldn't be configured as requested
+    DeviceConfig,
+    /// Reading from or writing to a device failed
+    Io,
+    /// An operation didn't complete in time
+    Timeout,
+    /// Data received from a device or file is malformed
+    InvalidData,
+    /// The requested feature isn't supported by the device or target
+    Unsupported,
+    /// Any failure not covered above
+    Other,
+}
+
+impl ErrorKind {
+    /// A stable numeric code for the kind, suitable for reporting over telemetry
+    pub const fn code(self) -> u16 {
+        match self {
+            ErrorKind::Unhandled => 1,
+            ErrorKind::NotFound => 2,
+            ErrorKind::PermissionDenied => 3,
+            ErrorKind::Busy => 4,
+            ErrorKind::InvalidConfig => 5,
+            ErrorKind::DeviceConfig => 6,
+            ErrorKind::Io => 7,
+            ErrorKind::Timeout => 8,
+            ErrorKind::InvalidData => 9,
+            ErrorKind::Unsupported => 10,
+            ErrorKind::Other => 255,
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::Unhandled => "unhandled",
+            ErrorKind::NotFound => "not found",
+            ErrorKind::PermissionDenied => "permission denied",
+            ErrorKind::Busy => "busy",
+            ErrorKind::InvalidConfig => "invalid config",
+            ErrorKind::DeviceConfig => "device config",
+            ErrorKind::Io => "I/O",
+            ErrorKind::Timeout => "timeout",
+            ErrorKind::InvalidData => "invalid data",
+            ErrorKind::Unsupported => "unsupported",
+            ErrorKind::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::ErrorKind> for ErrorKind {
+    fn from(kind: std::io::ErrorKind) -> Self {
+        use std::io::ErrorKind as Io;
+        match kind {
+            Io::NotFound => ErrorKind::NotFound,
+            Io::PermissionDenied => ErrorKind::PermissionDenied,
+            Io::AddrInUse | Io::ResourceBusy => ErrorKind::Busy,
+            Io::InvalidInput => ErrorKind::InvalidConfig,
+            Io::InvalidData | Io::UnexpectedEof => ErrorKind::InvalidData,
+            Io::TimedOut | Io::WouldBlock => ErrorKind::Timeout,
+            Io::Unsupported => ErrorKind::Unsupported,
+            _ => ErrorKind::Io,
+        }
+    }
+}
+
+/// An error raised by a Pictorus subsystem
+#[derive(Debug, Clone, PartialEq)]
+pub struct PictorusError {
+    pub kind: ErrorKind,
+    /// The subsystem that raised the error, e.g. "CanProtocol"
+    pub err_type: &'static str,
+    pub message: Message,
+    #[cfg(feature = "alloc")]
+    source: Option<Box<PictorusError>>,
+}
+
+impl PictorusError {
+    pub fn new(kind: ErrorKind, err_type: &'static str, message: impl Into<Message>) -> Self {
+        PictorusError {
+            kind,
+            err_type,
+            message: message.into(),
+            #[cfg(feature = "alloc")]
+            source: None,
+        }
+    }
+
+    /// Creates an error whose kind is derived from the I/O error that caused it
+    #[cfg(feature = "std")]
+    pub fn from_io(
+        err_type: &'static str,
+        message: impl Into<Message>,
+        err: &std::io::Error,
+    ) -> Self {
+        Self::new(err.kind().into(), err_type, message)
+    }
+
+    /// Records `source` as the cause of this error
+    #[cfg(feature = "alloc")]
+    pub fn with_source(mut self, source: PictorusError) -> Self {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    /// The numeric code of the error's kind
+    pub const fn code(&self) -> u16 {
+        self.kind.code()
+    }
+
+    /// The error that caused this one, if any
+    #[cfg(feature = "alloc")]
+    pub fn source(&self) -> Option<&PictorusError> {
+        self.source.as_deref()
+    }
+
+    /// The error that first caused this chain of errors
+    #[cfg(feature = "alloc")]
+    pub fn root_cause(&self) -> &PictorusError {
+        let mut err = self;
+        while let Some(source) = err.source() {
+            err = source;
+        }
+        err
+    }
+}
+
+impl fmt::Display for PictorusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} error {} ({}): {}",
+            self.err_type,
+            self.code(),
+            self.kind,
+            self.message
+        )?;
+        #[cfg(feature = "alloc")]
+        if let Some(source) = self.source() {
+            write!(f, "\n  caused by: {source}")?;
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for PictorusError {
+    #[cfg(feature = "alloc")]
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn core::error::Error + 'static))
+    }
+}
+
+impl From<core::convert::Infallible> for PictorusError {
+    fn from(_: core::convert::Infallible) -> Self {
+        unreachable!();
+    }
+}
+
+// Serialized with the same `err_type` and `message` fields as the original string-based error,
+// so existing consumers of the error log keep working
+impl Serialize for PictorusError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("PictorusError", 5)?;
+        state.serialize_field("err_type", self.err_type)?;
+        state.serialize_field("message", &*self.message)?;
+        state.serialize_field("kind", &self.kind)?;
+        state.serialize_field("code", &self.code())?;
+        #[cfg(feature = "alloc")]
+        state.serialize_field("source", &self.source)?;
+        #[cfg(not(feature = "alloc"))]
+        state.serialize_field("source", &None::<()>)?;
+        state.end()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn test_error_codes_and_chaining() {
+        let cause = PictorusError::new(ErrorKind::NotFound, "SerialProtocol", "No such port");
+        let err = PictorusError::new(
+            ErrorKind::DeviceConfig,
+            "IoConfig",
+            std::format!("Failed to create {}", "gps_serial"),
+        )
+        .with_source(cause.clone());
+
+        assert_eq!(err.code(), 6);
+        assert_eq!(err.source(), Some(&cause));
+        assert_eq!(err.root_cause().kind, ErrorKind::NotFound);
+        assert_eq!(
+            err.to_string(),
+            "IoConfig error 6 (device config): Failed to create gps_serial\n  caused by: SerialProtocol error 2 (not found): No such port"
+        );
+        assert!(core::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn test_error_from_io() {
+        let io_err = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        let err = PictorusError::from_io("UdpProtocol", "Failed to bind", &io_err);
+        assert_eq!(err.kind, ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn test_error_serialization() {
+        let err = PictorusError::new(ErrorKind::Unhandled, "unhandled", "panicked");
+        assert_eq!(
+            serde_json::to_string(&err).unwrap(),
+            r#"{"err_type":"unhandled","message":"panicked","kind":"unhandled","code":1,"source":null}"#
+        );
+    }
+}
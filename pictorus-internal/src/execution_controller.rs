@@ -1,3 +1,12 @@
+use core::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+
+use pictorus_traits::Context;
+
+use crate::RuntimeContext;
+use crate::error::{ErrorKind, PictorusError};
+
+const ERR_TYPE: &str = "ExecutionController";
+
 /// This controller is used to determine when a component should execute based on a desired
 /// frequency. That is once every N times [ExecutionController::should_execute()] is called
 /// when the controller is constructed with a limit of N.
@@ -10,6 +19,10 @@
 /// is provided, the controller will start at that count and increment from there. This can
 /// be used to stagger the execution of multiple components that share the same limit.
 ///
+/// The controller can optionally log the first fault (e.g. a NaN/Inf detected by a
+/// `SanitizeBlock`) reported for each signal of the component. See
+/// [ExecutionController::with_fault_logging()] and [ExecutionController::report_fault()].
+///
 /// # Examples
 ///
 /// ```
@@ -28,17 +41,36 @@ pub struct ExecutionController {
     limit: usize,
     /// The current count of calls to [ExecutionController::should_execute()] between 0 and `limit`-1
     count: usize,
+    /// Whether [ExecutionController::report_fault()] should log the first fault of each signal
+    log_faults: bool,
+    /// Bitmask of the signal ids that have already had a fault logged
+    logged_faults: u64,
 }
 
+/// The maximum number of signals per controller that can have their first fault logged.
+/// Faults reported for signal ids at or above this limit are never logged.
+pub const MAX_FAULT_SIGNALS: usize = u64::BITS as usize;
+
 impl ExecutionController {
     /// Create a new `ExecutionController` with the specified limit and count
     pub fn new(limit: usize, count: usize) -> Self {
-        Self { limit, count }
+        Self {
+            limit,
+            count,
+            log_faults: false,
+            logged_faults: 0,
+        }
     }
 
     /// Create a new `ExecutionController` with the specified limit and a count defaulting to 0
     pub fn with_limit(limit: usize) -> Self {
-        Self { limit, count: 0 }
+        Self::new(limit, 0)
+    }
+
+    /// Enable logging of the first fault reported for each signal via [ExecutionController::report_fault()]
+    pub fn with_fault_logging(mut self) -> Self {
+        self.log_faults = true;
+        self
     }
 }
 
@@ -69,11 +101,461 @@ impl ExecutionController {
         }
         output
     }
+
+    /// Report the fault flag of a signal for the current tick. If fault logging is enabled,
+    /// this logs a warning the first time a fault is reported for `signal_id` and returns `true`.
+    /// Subsequent faults for the same signal are not logged.
+    ///
+    /// # Examples
+    /// ```
+    /// use pictorus_internal::ExecutionController;
+    /// let mut controller = ExecutionController::with_limit(1).with_fault_logging();
+    /// assert!(!controller.report_fault(0, "sanitize_1", false));
+    /// assert!(controller.report_fault(0, "sanitize_1", true)); // Logged
+    /// assert!(!controller.report_fault(0, "sanitize_1", true)); // Already logged
+    /// assert!(controller.report_fault(1, "sanitize_2", true)); // Different signal
+    /// ```
+    pub fn report_fault(&mut self, signal_id: usize, signal_name: &str, fault: bool) -> bool {
+        if !self.log_faults || !fault || signal_id >= MAX_FAULT_SIGNALS {
+            return false;
+        }
+        let mask = 1 << signal_id;
+        if self.logged_faults & mask != 0 {
+            return false;
+        }
+        self.logged_faults |= mask;
+        log::warn!("Non-finite value detected in signal {signal_name}");
+        true
+    }
+}
+
+/// A generated model that can share an app with other models, see [MultiModelController]
+pub trait Model<IO: ?Sized> {
+    /// Runs one tick of the model. `context` is the model's own, so its timestep reflects the
+    /// model's rate, and `io` is the I/O manager shared by all models.
+    fn run(&mut self, context: &dyn Context, io: &mut IO);
+}
+
+/// Set once the app should stop and put its outputs in their safe state
+static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Request the app to shut down, e.g. from a command received by a model. The models stop
+/// running and the [ShutdownSequence] runs on the next tick of the [MultiModelController].
+///
+/// # Examples
+///
+/// ```
+/// use pictorus_internal::execution_controller::{
+///     Model, MultiModelController, ShutdownSequence, request_shutdown,
+/// };
+/// use pictorus_traits::Context;
+///
+/// struct Motor;
+///
+/// impl Model<f64> for Motor {
+///     fn run(&mut self, _context: &dyn Context, throttle: &mut f64) {
+///         *throttle = 0.8;
+///     }
+/// }
+///
+/// let mut motor = Motor;
+/// let mut idle = |_: &dyn Context, throttle: &mut f64| *throttle = 0.0;
+/// let mut shutdown = ShutdownSequence::new();
+/// shutdown.add(&mut idle).unwrap();
+/// let mut controller =
+///     MultiModelController::<f64, 1, 1>::new(1_000).with_shutdown_sequence(shutdown);
+/// controller.register(&mut motor, 1_000, 0).unwrap();
+///
+/// let mut throttle = 0.0;
+/// assert!(controller.run(0, &mut throttle));
+/// assert_eq!(throttle, 0.8);
+///
+/// request_shutdown();
+/// assert!(!controller.run(1_000, &mut throttle));
+/// assert_eq!(throttle, 0.0);
+/// assert!(controller.is_shut_down());
+/// ```
+pub fn request_shutdown() {
+    SHUTDOWN_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+/// Whether the app has been requested to shut down, see [request_shutdown()]
+pub fn shutdown_requested() -> bool {
+    SHUTDOWN_REQUESTED.load(Ordering::Relaxed)
+}
+
+/// Request a shutdown on SIGINT (Ctrl-C) and when a thread panics, in addition to printing the
+/// panic as usual. Returns an error if a SIGINT handler is already installed.
+///
+/// A panic in the thread running the models unwinds past the controller, so run them with
+/// [run_until_shutdown()], which still runs the [ShutdownSequence] on the way out.
+#[cfg(feature = "std")]
+pub fn install_shutdown_handlers() -> Result<(), PictorusError> {
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(std::boxed::Box::new(move |info| {
+        request_shutdown();
+        previous_hook(info);
+    }));
+    ctrlc::set_handler(request_shutdown).map_err(|err| {
+        PictorusError::new(
+            ErrorKind::InvalidConfig,
+            ERR_TYPE,
+            alloc::format!("Failed to install SIGINT handler: {err}"),
+        )
+    })
+}
+
+/// Runs the models of `controller` every fundamental timestep until the app shuts down. Before
+/// each tick, `wait_until` is called with the tick's app time in microseconds, and should
+/// return once that time is reached.
+///
+/// A panic in a model is caught, so the [ShutdownSequence] still puts the outputs in their safe
+/// state, and is then resumed so the app exits as it would have.
+///
+/// # Examples
+///
+/// ```
+/// use pictorus_internal::execution_controller::{
+///     Model, MultiModelController, ShutdownSequence, run_until_shutdown,
+/// };
+/// use pictorus_traits::Context;
+///
+/// struct Faulty;
+///
+/// impl Model<f64> for Faulty {
+///     fn run(&mut self, context: &dyn Context, throttle: &mut f64) {
+///         assert!(context.time().as_millis() < 2, "Sensor fault");
+///         *throttle = 0.8;
+///     }
+/// }
+///
+/// let mut model = Faulty;
+/// let mut idle = |_: &dyn Context, throttle: &mut f64| *throttle = 0.0;
+/// let mut shutdown = ShutdownSequence::new();
+/// shutdown.add(&mut idle).unwrap();
+/// let mut controller =
+///     MultiModelController::<f64, 1, 1>::new(1_000).with_shutdown_sequence(shutdown);
+/// controller.register(&mut model, 1_000, 0).unwrap();
+///
+/// let mut throttle = 0.0;
+/// let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+///     run_until_shutdown(&mut controller, &mut throttle, |_| {})
+/// }));
+/// assert!(result.is_err());
+/// assert_eq!(throttle, 0.0);
+/// assert!(controller.is_shut_down());
+/// ```
+#[cfg(feature = "std")]
+pub fn run_until_shutdown<IO: ?Sized, const N: usize, const S: usize>(
+    controller: &mut MultiModelController<'_, IO, N, S>,
+    io: &mut IO,
+    mut wait_until: impl FnMut(u64),
+) {
+    let mut app_time_us = 0;
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        loop {
+            wait_until(app_time_us);
+            if !controller.run(app_time_us, io) {
+                break;
+            }
+            app_time_us += controller.fundamental_timestep_us;
+        }
+    }));
+    if let Err(panic) = result {
+        log::error!("Model panicked, shutting down");
+        controller.shut_down(app_time_us, io);
+        std::panic::resume_unwind(panic);
+    }
+}
+
+/// Puts the outputs in their safe state from a panic handler, see [set_panic_safe_state()]
+static PANIC_SAFE_STATE: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Set the function [apply_panic_safe_state()] runs, for `no_std` apps whose panics can't
+/// unwind to a [ShutdownSequence].
+///
+/// The I/O manager isn't reachable from a panic handler, so `safe_state` should drive the
+/// outputs to their safe state through the peripherals' registers directly (e.g. PWM compare
+/// values to idle, GPIO low).
+pub fn set_panic_safe_state(safe_state: fn()) {
+    PANIC_SAFE_STATE.store(safe_state as *mut (), Ordering::Relaxed);
+}
+
+/// Run the function set with [set_panic_safe_state()], at most once. Returns whether it ran.
+///
+/// Call it from the app's `#[panic_handler]`:
+///
+/// ```ignore
+/// #[panic_handler]
+/// fn panic(_info: &core::panic::PanicInfo) -> ! {
+///     pictorus_internal::execution_controller::apply_panic_safe_state();
+///     loop {}
+/// }
+/// ```
+pub fn apply_panic_safe_state() -> bool {
+    let safe_state = PANIC_SAFE_STATE.load(Ordering::Relaxed);
+    if safe_state.is_null() {
+        return false;
+    }
+    // Cleared first, so a panic in the safe state itself doesn't run it again. Only load and
+    // store are used since some targets (e.g. Cortex-M0) lack atomic swaps.
+    PANIC_SAFE_STATE.store(core::ptr::null_mut(), Ordering::Relaxed);
+    // SAFETY: the only non-null values stored are `fn()` pointers, in set_panic_safe_state
+    let safe_state = unsafe { core::mem::transmute::<*mut (), fn()>(safe_state) };
+    safe_state();
+    true
+}
+
+/// A step of a [ShutdownSequence] that puts an output in its safe state, usually by running an
+/// `OutputBlock` of the I/O manager with safe inputs. Implemented for closures taking the
+/// context and the I/O manager.
+pub trait SafeState<IO: ?Sized> {
+    fn apply_safe_state(&mut self, context: &dyn Context, io: &mut IO);
+}
+
+impl<IO: ?Sized, F: FnMut(&dyn Context, &mut IO)> SafeState<IO> for F {
+    fn apply_safe_state(&mut self, context: &dyn Context, io: &mut IO) {
+        self(context, io)
+    }
+}
+
+/// An ordered list of steps that put the app's outputs in a safe state when it shuts down (e.g.
+/// PWM to idle, GPIO low, CAN stop frames), so actuators are never left in their last
+/// commanded state.
+///
+/// The steps run in the order they were added, once: running the sequence again does nothing.
+///
+/// # Examples
+///
+/// ```
+/// use pictorus_internal::RuntimeContext;
+/// use pictorus_internal::execution_controller::ShutdownSequence;
+/// use pictorus_traits::Context;
+///
+/// let mut motor_idle = |_: &dyn Context, io: &mut Vec<&str>| io.push("motor idle");
+/// let mut brake_on = |_: &dyn Context, io: &mut Vec<&str>| io.push("brake on");
+/// let mut sequence = ShutdownSequence::<Vec<&str>, 2>::new();
+/// sequence.add(&mut motor_idle).unwrap();
+/// sequence.add(&mut brake_on).unwrap();
+///
+/// let mut io = Vec::new();
+/// assert!(sequence.run(&RuntimeContext::new(1_000), &mut io));
+/// assert!(!sequence.run(&RuntimeContext::new(1_000), &mut io));
+/// assert_eq!(io, ["motor idle", "brake on"]);
+/// ```
+pub struct ShutdownSequence<'a, IO: ?Sized, const N: usize> {
+    steps: heapless::Vec<&'a mut dyn SafeState<IO>, N>,
+    done: bool,
+}
+
+impl<IO: ?Sized, const N: usize> Default for ShutdownSequence<'_, IO, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, IO: ?Sized, const N: usize> ShutdownSequence<'a, IO, N> {
+    pub fn new() -> Self {
+        Self {
+            steps: heapless::Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Add a step, run after the steps added before it
+    pub fn add(&mut self, step: &'a mut dyn SafeState<IO>) -> Result<(), PictorusError> {
+        self.steps.push(step).map_err(|_| {
+            PictorusError::new(
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
+                "Too many shutdown steps added",
+            )
+        })
+    }
+
+    /// Run the steps, unless they already ran. Returns whether they ran.
+    pub fn run(&mut self, context: &dyn Context, io: &mut IO) -> bool {
+        if self.done {
+            return false;
+        }
+        self.done = true;
+        log::info!("Shutting down, putting outputs in their safe state");
+        for step in self.steps.iter_mut() {
+            step.apply_safe_state(context, io);
+        }
+        true
+    }
+
+    /// Whether the steps have run
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+struct ScheduledModel<'a, IO: ?Sized> {
+    model: &'a mut dyn Model<IO>,
+    controller: ExecutionController,
+    timestep_us: u64,
+    /// Seeded on the model's first run
+    context: Option<RuntimeContext>,
+}
+
+/// Runs several independently generated models in one app, e.g. perception and control
+/// diagrams, each at its own rate.
+///
+/// Models are registered with a timestep that must be a multiple of the controller's
+/// fundamental timestep, and each gets its own [ExecutionController] and [RuntimeContext] so
+/// their state and timing stay isolated. On every fundamental tick, the models due to run are
+/// run in the order they were registered, all with the same shared I/O manager. A model's
+/// first run can be offset by a number of fundamental ticks to spread slow models over
+/// different ticks.
+///
+/// Once a shutdown is requested (see [request_shutdown()]), the models stop running, and the
+/// [ShutdownSequence] set with [MultiModelController::with_shutdown_sequence()] runs instead.
+///
+/// # Examples
+///
+/// ```
+/// use pictorus_internal::execution_controller::{Model, MultiModelController};
+/// use pictorus_traits::Context;
+///
+/// struct Counter(u32);
+///
+/// impl Model<Vec<u32>> for Counter {
+///     fn run(&mut self, _context: &dyn Context, io: &mut Vec<u32>) {
+///         self.0 += 1;
+///         io.push(self.0);
+///     }
+/// }
+///
+/// let (mut fast, mut slow) = (Counter(0), Counter(100));
+/// let mut controller = MultiModelController::<Vec<u32>, 2>::new(1_000);
+/// controller.register(&mut fast, 1_000, 0).unwrap();
+/// controller.register(&mut slow, 2_000, 0).unwrap();
+///
+/// let mut io = Vec::new();
+/// for tick in 0..4 {
+///     controller.run(tick * 1_000, &mut io);
+/// }
+/// assert_eq!(io, [1, 101, 2, 3, 102, 4]);
+/// ```
+pub struct MultiModelController<'a, IO: ?Sized, const N: usize, const S: usize = 0> {
+    fundamental_timestep_us: u64,
+    models: heapless::Vec<ScheduledModel<'a, IO>, N>,
+    shutdown: ShutdownSequence<'a, IO, S>,
+    shutdown_requested: bool,
+}
+
+impl<'a, IO: ?Sized, const N: usize, const S: usize> MultiModelController<'a, IO, N, S> {
+    /// Create a controller that's run once every `fundamental_timestep_us`
+    pub fn new(fundamental_timestep_us: u64) -> Self {
+        Self {
+            fundamental_timestep_us,
+            models: heapless::Vec::new(),
+            shutdown: ShutdownSequence::new(),
+            shutdown_requested: false,
+        }
+    }
+
+    /// Run `shutdown` once a shutdown is requested, instead of the models
+    pub fn with_shutdown_sequence(mut self, shutdown: ShutdownSequence<'a, IO, S>) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Register a model to run every `timestep_us`, starting on fundamental tick `offset`
+    pub fn register(
+        &mut self,
+        model: &'a mut dyn Model<IO>,
+        timestep_us: u64,
+        offset: usize,
+    ) -> Result<(), PictorusError> {
+        let invalid = |msg| Err(PictorusError::new(ErrorKind::InvalidConfig, ERR_TYPE, msg));
+        if self.fundamental_timestep_us == 0
+            || timestep_us == 0
+            || !timestep_us.is_multiple_of(self.fundamental_timestep_us)
+        {
+            return invalid("Model timestep must be a multiple of the fundamental timestep");
+        }
+        let limit = (timestep_us / self.fundamental_timestep_us) as usize;
+        if offset >= limit {
+            return invalid("Model offset must be less than its timestep in ticks");
+        }
+
+        // Counting down from the limit delays the first run by `offset` ticks
+        let count = if offset == 0 { 0 } else { limit - offset };
+        let scheduled = ScheduledModel {
+            model,
+            controller: ExecutionController::new(limit, count),
+            timestep_us,
+            context: None,
+        };
+        if self.models.push(scheduled).is_err() {
+            return invalid("Too many models registered");
+        }
+        Ok(())
+    }
+
+    /// Run the models due on this fundamental tick, at `app_time_us`. Once a shutdown is
+    /// requested, runs the shutdown sequence instead and returns false, so the app can exit.
+    pub fn run(&mut self, app_time_us: u64, io: &mut IO) -> bool {
+        if self.shutdown_requested || shutdown_requested() {
+            self.shut_down(app_time_us, io);
+            return false;
+        }
+        for scheduled in self.models.iter_mut() {
+            if scheduled.controller.should_execute() {
+                // A model has no timestep on its first run, however far it's offset
+                let context = match &mut scheduled.context {
+                    Some(context) => {
+                        context.update_app_time(app_time_us);
+                        context
+                    }
+                    None => scheduled.context.insert(RuntimeContext::starting_at(
+                        scheduled.timestep_us,
+                        app_time_us,
+                    )),
+                };
+                scheduled.model.run(context, io);
+            }
+        }
+        true
+    }
+
+    /// Run the shutdown sequence now, at `app_time_us`, e.g. after a model panicked. Returns
+    /// whether it ran, since it only runs once.
+    pub fn shut_down(&mut self, app_time_us: u64, io: &mut IO) -> bool {
+        self.shutdown_requested = true;
+        let context = RuntimeContext::starting_at(self.fundamental_timestep_us, app_time_us);
+        self.shutdown.run(&context, io)
+    }
+
+    /// Request this controller to shut down on its next tick, like [request_shutdown()] does
+    /// for the whole app
+    pub fn request_shutdown(&mut self) {
+        self.shutdown_requested = true;
+    }
+
+    /// Whether the shutdown sequence has run
+    pub fn is_shut_down(&self) -> bool {
+        self.shutdown.is_done()
+    }
+
+    /// The number of registered models
+    pub fn len(&self) -> usize {
+        self.models.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.models.is_empty()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec::Vec;
+    use core::time::Duration;
 
     #[test]
     fn test_component_execution_controller() {
@@ -97,7 +579,9 @@ mod tests {
             ExecutionController::new(42, 9),
             ExecutionController {
                 limit: 42,
-                count: 9
+                count: 9,
+                log_faults: false,
+                logged_faults: 0,
             }
         )
     }
@@ -122,9 +606,162 @@ mod tests {
     fn test_pathological_zero_limit() {
         let mut controller = ExecutionController::new(0, 0);
         // Should always return true
-        assert_eq!(controller, ExecutionController { limit: 0, count: 0 });
+        assert_eq!(controller, ExecutionController::new(0, 0));
         assert!(controller.should_execute());
-        assert_eq!(controller, ExecutionController { limit: 0, count: 0 });
+        assert_eq!(controller, ExecutionController::new(0, 0));
         assert!(controller.should_execute());
     }
+
+    #[test]
+    fn test_report_fault() {
+        let mut controller = ExecutionController::with_limit(1);
+        // Fault logging is disabled by default
+        assert!(!controller.report_fault(0, "signal", true));
+
+        let mut controller = controller.with_fault_logging();
+        assert!(!controller.report_fault(3, "signal_3", false));
+        assert!(controller.report_fault(3, "signal_3", true));
+        assert!(!controller.report_fault(3, "signal_3", true));
+        assert!(controller.report_fault(63, "signal_63", true));
+        assert!(!controller.report_fault(MAX_FAULT_SIGNALS, "too_many", true));
+    }
+
+    struct RecordingModel {
+        id: u32,
+    }
+
+    impl Model<Vec<(u32, u64, Option<Duration>)>> for RecordingModel {
+        fn run(&mut self, context: &dyn Context, io: &mut Vec<(u32, u64, Option<Duration>)>) {
+            io.push((
+                self.id,
+                context.time().as_micros() as u64,
+                context.timestep(),
+            ));
+        }
+    }
+
+    #[test]
+    fn test_multi_model_controller() {
+        let mut fast = RecordingModel { id: 0 };
+        let mut slow = RecordingModel { id: 1 };
+        let mut controller = MultiModelController::<_, 2>::new(1_000);
+        assert!(controller.is_empty());
+        controller.register(&mut fast, 1_000, 0).unwrap();
+        controller.register(&mut slow, 3_000, 1).unwrap();
+        assert_eq!(controller.len(), 2);
+
+        let mut io = Vec::new();
+        for tick in 0..8 {
+            controller.run(tick * 1_000, &mut io);
+        }
+        let slow_runs: Vec<_> = io.iter().filter(|(id, ..)| *id == 1).collect();
+        assert_eq!(
+            slow_runs,
+            [
+                &(1, 1_000, None),
+                &(1, 4_000, Some(Duration::from_micros(3_000))),
+                &(1, 7_000, Some(Duration::from_micros(3_000))),
+            ]
+        );
+        // Each model's context is isolated, so the fast model sees its own timestep
+        assert_eq!(io.iter().filter(|(id, ..)| *id == 0).count(), 8);
+        assert_eq!(io[0], (0, 0, None));
+        assert_eq!(io[1], (0, 1_000, Some(Duration::from_micros(1_000))));
+        // Models due on the same tick run in registration order
+        assert_eq!(io[2], (1, 1_000, None));
+    }
+
+    #[test]
+    fn test_multi_model_controller_shutdown() {
+        let mut model = RecordingModel { id: 0 };
+        let mut first = |context: &dyn Context, io: &mut Vec<(u32, u64, Option<Duration>)>| {
+            io.push((10, context.time().as_micros() as u64, None))
+        };
+        let mut second =
+            |_: &dyn Context, io: &mut Vec<(u32, u64, Option<Duration>)>| io.push((11, 0, None));
+        let mut shutdown = ShutdownSequence::new();
+        shutdown.add(&mut first).unwrap();
+        shutdown.add(&mut second).unwrap();
+        let mut third = |_: &dyn Context, _: &mut Vec<(u32, u64, Option<Duration>)>| {};
+        assert!(shutdown.add(&mut third).is_err());
+
+        let mut controller =
+            MultiModelController::<_, 1, 2>::new(1_000).with_shutdown_sequence(shutdown);
+        controller.register(&mut model, 1_000, 0).unwrap();
+
+        let mut io = Vec::new();
+        assert!(controller.run(0, &mut io));
+        controller.request_shutdown();
+        assert!(!controller.is_shut_down());
+        assert!(!controller.run(1_000, &mut io));
+        assert!(!controller.run(2_000, &mut io));
+        assert!(controller.is_shut_down());
+        // The model stops, and the safe states are applied once, in order
+        assert_eq!(io, [(0, 0, None), (10, 1_000, None), (11, 0, None),]);
+    }
+
+    /// Panics on its third run
+    #[cfg(feature = "std")]
+    struct PanickingModel;
+
+    #[cfg(feature = "std")]
+    impl Model<Vec<(u32, u64, Option<Duration>)>> for PanickingModel {
+        fn run(&mut self, context: &dyn Context, io: &mut Vec<(u32, u64, Option<Duration>)>) {
+            let time = context.time().as_micros() as u64;
+            assert!(time < 2_000, "Model fault");
+            io.push((0, time, None));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_run_until_shutdown_applies_safe_state_on_panic() {
+        let mut model = PanickingModel;
+        let mut safe_state = |context: &dyn Context, io: &mut Vec<(u32, u64, Option<Duration>)>| {
+            io.push((10, context.time().as_micros() as u64, None))
+        };
+        let mut shutdown = ShutdownSequence::new();
+        shutdown.add(&mut safe_state).unwrap();
+        let mut controller =
+            MultiModelController::<_, 1, 1>::new(1_000).with_shutdown_sequence(shutdown);
+        controller.register(&mut model, 1_000, 0).unwrap();
+
+        let mut io = Vec::new();
+        let mut waits = Vec::new();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            run_until_shutdown(&mut controller, &mut io, |app_time_us| {
+                waits.push(app_time_us)
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(waits, [0, 1_000, 2_000]);
+        assert_eq!(io, [(0, 0, None), (0, 1_000, None), (10, 2_000, None)]);
+        assert!(controller.is_shut_down());
+        // The models never run again
+        assert!(!controller.run(3_000, &mut io));
+    }
+
+    static PANIC_SAFE_STATE_RUNS: AtomicBool = AtomicBool::new(false);
+
+    #[test]
+    fn test_apply_panic_safe_state() {
+        assert!(!apply_panic_safe_state());
+        set_panic_safe_state(|| PANIC_SAFE_STATE_RUNS.store(true, Ordering::Relaxed));
+        assert!(apply_panic_safe_state());
+        assert!(PANIC_SAFE_STATE_RUNS.load(Ordering::Relaxed));
+        // It only runs once
+        assert!(!apply_panic_safe_state());
+    }
+
+    #[test]
+    fn test_multi_model_controller_invalid_registration() {
+        let mut models = [0, 1, 2, 3, 4].map(|id| RecordingModel { id });
+        let [a, b, c, d, e] = &mut models;
+        let mut controller = MultiModelController::<_, 1>::new(1_000);
+        assert!(controller.register(a, 1_500, 0).is_err());
+        assert!(controller.register(b, 2_000, 2).is_err());
+        assert!(controller.register(c, 0, 0).is_err());
+        controller.register(d, 2_000, 1).unwrap();
+        assert!(controller.register(e, 1_000, 0).is_err());
+    }
 }
@@ -0,0 +1,301 @@
+//! A minimal HTTP server that exposes the status of a running app as JSON, so operators can
+//! poll a deployed app with `curl` or a dashboard.
+//!
+//! The server runs on its own thread and only ever reads a snapshot of the status, so a slow
+//! or stalled client never affects the app's execution. The following endpoints are served:
+//!
+//! - `GET /` - Everything below, as a single object
+//! - `GET /signals` - The most recently published signal values
+//! - `GET /parameters` - The app's parameters
+//! - `GET /stats` - Execution statistics
+use core::time::Duration;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::string::String;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use log::{info, warn};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::loggers::Logger;
+use crate::utils::PictorusError;
+
+const ERR_TYPE: &str = "HttpServer";
+/// How long the server thread waits between checks for new connections
+const ACCEPT_POLL: Duration = Duration::from_millis(20);
+/// Clients that don't send a complete request within this time are disconnected
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Execution statistics reported by the `/stats` endpoint
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct ExecutionStats {
+    /// App time of the most recent tick, in seconds
+    pub app_time_s: f64,
+    /// Number of ticks executed
+    pub ticks: u64,
+    /// Execution time of the most recent tick, in microseconds
+    pub last_tick_us: f64,
+    /// Longest tick execution time, in microseconds
+    pub max_tick_us: f64,
+    /// Mean tick execution time, in microseconds
+    pub mean_tick_us: f64,
+}
+
+#[derive(Default)]
+struct Status {
+    signals: Value,
+    parameters: Value,
+    stats: ExecutionStats,
+}
+
+/// Serves the app's signal values, parameters, and execution statistics over HTTP.
+///
+/// Signal values are published through the [`Logger`] trait like the other telemetry loggers,
+/// at the configured publish period.
+pub struct HttpServer {
+    status: Arc<Mutex<Status>>,
+    publish_period: Duration,
+    last_publish_time: Option<Duration>,
+    local_addr: SocketAddr,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl HttpServer {
+    /// Starts serving on `address` (e.g. "0.0.0.0:8080"), publishing signal values at most once
+    /// per `publish_period`
+    pub fn new(address: &str, publish_period: Duration) -> Result<Self, PictorusError> {
+        let listener = TcpListener::bind(address).map_err(|err| {
+            PictorusError::from_io(
+                ERR_TYPE,
+                std::format!("Couldn't bind HTTP server at address: {address} ({err})"),
+                &err,
+            )
+        })?;
+        let local_addr = listener.local_addr().map_err(|err| {
+            PictorusError::from_io(
+                ERR_TYPE,
+                std::format!("Couldn't read HTTP server address ({err})"),
+                &err,
+            )
+        })?;
+        listener.set_nonblocking(true).map_err(|err| {
+            PictorusError::from_io(
+                ERR_TYPE,
+                std::format!("Failed to set nonblocking on HTTP server ({err})"),
+                &err,
+            )
+        })?;
+        info!("Serving app status at http://{local_addr}");
+
+        let status = Arc::new(Mutex::new(Status::default()));
+        let running = Arc::new(AtomicBool::new(true));
+        let thread = {
+            let status = status.clone();
+            let running = running.clone();
+            std::thread::spawn(move || serve(listener, &status, &running))
+        };
+
+        Ok(HttpServer {
+            status,
+            publish_period,
+            last_publish_time: None,
+            local_addr,
+            running,
+            thread: Some(thread),
+        })
+    }
+
+    /// The address the server is listening on, which is useful when binding to port 0
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Sets the parameters reported by the `/parameters` endpoint
+    pub fn set_parameters(&mut self, parameters: &impl Serialize) {
+        let parameters = serde_json::to_value(parameters).unwrap_or(Value::Null);
+        if let Ok(mut status) = self.status.lock() {
+            status.parameters = parameters;
+        }
+    }
+
+    /// Records the execution time of a tick for the `/stats` endpoint
+    pub fn record_tick(&mut self, app_time: Duration, tick_duration: Duration) {
+        let Ok(mut status) = self.status.lock() else {
+            return;
+        };
+        let stats = &mut status.stats;
+        let tick_us = tick_duration.as_secs_f64() * 1e6;
+        stats.app_time_s = app_time.as_secs_f64();
+        stats.ticks += 1;
+        stats.last_tick_us = tick_us;
+        stats.max_tick_us = stats.max_tick_us.max(tick_us);
+        stats.mean_tick_us += (tick_us - stats.mean_tick_us) / stats.ticks as f64;
+    }
+}
+
+impl Logger for HttpServer {
+    fn should_log(&mut self, app_time: Duration) -> bool {
+        match self.last_publish_time {
+            None => true,
+            Some(last_publish) => (app_time - last_publish) >= self.publish_period,
+        }
+    }
+
+    fn log(&mut self, log_data: &impl Serialize, app_time: Duration) {
+        let signals = serde_json::to_value(log_data).unwrap_or(Value::Null);
+        if let Ok(mut status) = self.status.lock() {
+            status.signals = signals;
+        }
+        self.last_publish_time = Some(app_time);
+    }
+}
+
+impl Drop for HttpServer {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+fn serve(listener: TcpListener, status: &Mutex<Status>, running: &AtomicBool) {
+    while running.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if let Err(err) = handle_connection(stream, status) {
+                    warn!("Failed to handle HTTP request: {err}");
+                }
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(ACCEPT_POLL);
+            }
+            Err(err) => warn!("Failed to accept HTTP connection: {err}"),
+        }
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, status: &Mutex<Status>) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drain the headers, since closing with unread data resets the connection
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && header.trim_end() != "" {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+    let path = path.split('?').next().unwrap_or_default();
+    let body = if method != "GET" {
+        None
+    } else {
+        let status = status
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match path.trim_end_matches('/') {
+            "" => Some(json!({
+                "signals": status.signals,
+                "parameters": status.parameters,
+                "stats": status.stats,
+            })),
+            "/signals" => Some(status.signals.clone()),
+            "/parameters" => Some(status.parameters.clone()),
+            "/stats" => serde_json::to_value(status.stats).ok(),
+            _ => None,
+        }
+    };
+
+    let (status_line, body) = match body {
+        Some(body) => ("200 OK", serde_json::to_string(&body).unwrap_or_default()),
+        None if method != "GET" => (
+            "405 Method Not Allowed",
+            String::from("{\"error\":\"method not allowed\"}"),
+        ),
+        None => ("404 Not Found", String::from("{\"error\":\"not found\"}")),
+    };
+    let response = std::format!(
+        "HTTP/1.1 {status_line}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes())?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::string::ToString;
+
+    #[derive(Serialize)]
+    struct LogData {
+        app_time: f64,
+        current_state: String,
+        foo_block: f64,
+    }
+
+    fn get(server: &HttpServer, path: &str) -> String {
+        let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+        stream
+            .write_all(std::format!("GET {path} HTTP/1.1\r\nHost: test\r\n\r\n").as_bytes())
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    fn body(response: &str) -> Value {
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        serde_json::from_str(body).unwrap()
+    }
+
+    #[test]
+    fn test_http_server_endpoints() {
+        let mut server = HttpServer::new("127.0.0.1:0", Duration::from_millis(100)).unwrap();
+        assert!(server.should_log(Duration::ZERO));
+        server.log(
+            &LogData {
+                app_time: 1.5,
+                current_state: "main_state".to_string(),
+                foo_block: 2.0,
+            },
+            Duration::from_millis(1500),
+        );
+        assert!(!server.should_log(Duration::from_millis(1550)));
+        assert!(server.should_log(Duration::from_millis(1600)));
+
+        server.set_parameters(&json!({ "gain": 3.0 }));
+        server.record_tick(Duration::from_millis(1400), Duration::from_micros(100));
+        server.record_tick(Duration::from_millis(1500), Duration::from_micros(300));
+
+        let response = get(&server, "/signals");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert_eq!(
+            body(&response),
+            json!({ "app_time": 1.5, "current_state": "main_state", "foo_block": 2.0 })
+        );
+
+        assert_eq!(body(&get(&server, "/parameters")), json!({ "gain": 3.0 }));
+        assert_eq!(
+            body(&get(&server, "/stats?pretty")),
+            json!({
+                "app_time_s": 1.5,
+                "ticks": 2,
+                "last_tick_us": 300.0,
+                "max_tick_us": 300.0,
+                "mean_tick_us": 200.0,
+            })
+        );
+        assert_eq!(body(&get(&server, "/"))["parameters"]["gain"], json!(3.0));
+        assert!(get(&server, "/missing").starts_with("HTTP/1.1 404 Not Found"));
+    }
+}
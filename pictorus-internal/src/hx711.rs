@@ -0,0 +1,282 @@
+//! Driver for the HX711 24-bit load cell ADC.
+//!
+//! The HX711 has a two-wire interface: it pulls DOUT low once a conversion is ready, and the host
+//! then clocks the 24-bit result out on PD_SCK, MSB first, followed by 1 to 3 more pulses that
+//! select the channel and gain of the next conversion. The pulses can be bit-banged on GPIO pins,
+//! or generated by an SPI peripheral with PD_SCK wired to MOSI and DOUT to MISO. Both are generic
+//! over `embedded-hal`, so the same driver runs on Linux and on microcontrollers.
+use embedded_hal::digital::{InputPin, OutputPin};
+use embedded_hal::spi::SpiBus;
+use log::warn;
+use pictorus_traits::{Context, InputBlock, PassBy};
+
+use crate::error::{ErrorKind, PictorusError};
+
+const ERR_TYPE: &str = "Hx711Protocol";
+/// Bits of a conversion result
+const RESULT_BITS: u8 = 24;
+/// SPI bytes carrying the 24 result pulses, with each pulse taking two bits (high, then low)
+const SPI_RESULT_BYTES: usize = RESULT_BITS as usize * 2 / 8;
+/// SPI pattern of four clock pulses
+const SPI_PULSES: u8 = 0b1010_1010;
+
+/// Errors returned while reading the HX711
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hx711Error {
+    Spi,
+    Pin,
+}
+
+/// Channel and gain of the conversions, selected by the pulses after each result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hx711Gain {
+    /// Channel A at a gain of 128, for a ±20 mV full scale
+    A128,
+    /// Channel A at a gain of 64, for a ±40 mV full scale
+    A64,
+    /// Channel B at a fixed gain of 32, for a ±80 mV full scale
+    B32,
+}
+
+impl Hx711Gain {
+    /// Parses a gain of 128 or 64 (channel A) or 32 (channel B)
+    pub fn from_gain(gain: f64) -> Result<Self, PictorusError> {
+        match gain {
+            128.0 => Ok(Self::A128),
+            64.0 => Ok(Self::A64),
+            32.0 => Ok(Self::B32),
+            _ => Err(PictorusError::new(
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
+                "HX711 gain must be 128, 64 or 32",
+            )),
+        }
+    }
+
+    /// Pulses that follow the 24 result pulses
+    fn extra_pulses(self) -> u8 {
+        match self {
+            Self::A128 => 1,
+            Self::B32 => 2,
+            Self::A64 => 3,
+        }
+    }
+}
+
+/// Parameters for the HX711 block. The gain is fixed when the block is created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Hx711Params {}
+
+impl Hx711Params {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Transfers conversion results from an HX711
+pub trait Hx711Interface {
+    /// Returns true once a conversion is ready to be read, i.e. DOUT is low
+    fn is_ready(&mut self) -> Result<bool, Hx711Error>;
+
+    /// Clocks the 24 result bits out, followed by `extra_pulses` pulses
+    fn read_bits(&mut self, extra_pulses: u8) -> Result<u32, Hx711Error>;
+}
+
+/// Bit-bangs PD_SCK on an output pin and reads DOUT from an input pin. PD_SCK must not stay
+/// high for more than 60 µs, or the HX711 powers down, so the thread clocking it shouldn't be
+/// preempted for long.
+pub struct GpioHx711<C: OutputPin, D: InputPin> {
+    pub clock: C,
+    pub data: D,
+}
+
+impl<C: OutputPin, D: InputPin> Hx711Interface for GpioHx711<C, D> {
+    fn is_ready(&mut self) -> Result<bool, Hx711Error> {
+        self.data.is_low().map_err(|_| Hx711Error::Pin)
+    }
+
+    fn read_bits(&mut self, extra_pulses: u8) -> Result<u32, Hx711Error> {
+        let mut bits = 0;
+        for pulse in 0..RESULT_BITS + extra_pulses {
+            self.clock.set_high().map_err(|_| Hx711Error::Pin)?;
+            // Each bit is shifted out on the rising edge
+            let high = self.data.is_high().map_err(|_| Hx711Error::Pin)?;
+            self.clock.set_low().map_err(|_| Hx711Error::Pin)?;
+            if pulse < RESULT_BITS {
+                bits = bits << 1 | u32::from(high);
+            }
+        }
+        Ok(bits)
+    }
+}
+
+/// Generates PD_SCK on the MOSI line of an SPI peripheral, reading DOUT on MISO. Each clock
+/// pulse is two SPI bits, so the SPI clock should be twice the desired PD_SCK rate (at most
+/// 2 MHz), in mode 0. MOSI must idle low between transfers, and SCK isn't connected to the
+/// HX711.
+pub struct SpiHx711<B: SpiBus>(pub B);
+
+impl<B: SpiBus> Hx711Interface for SpiHx711<B> {
+    fn is_ready(&mut self) -> Result<bool, Hx711Error> {
+        // Sampling DOUT without pulsing PD_SCK
+        let mut byte = [0];
+        self.0
+            .transfer_in_place(&mut byte)
+            .and_then(|_| self.0.flush())
+            .map_err(|_| Hx711Error::Spi)?;
+        Ok(byte[0] == 0)
+    }
+
+    fn read_bits(&mut self, extra_pulses: u8) -> Result<u32, Hx711Error> {
+        let mut bytes = [SPI_PULSES; SPI_RESULT_BYTES + 1];
+        // The extra pulses fill the start of the last byte, and the rest of it holds PD_SCK low
+        bytes[SPI_RESULT_BYTES] = !(0xFF >> (extra_pulses * 2)) & SPI_PULSES;
+        self.0
+            .transfer_in_place(&mut bytes)
+            .and_then(|_| self.0.flush())
+            .map_err(|_| Hx711Error::Spi)?;
+        // Each result bit is the MISO level during the low half of its pulse
+        Ok(bytes[..SPI_RESULT_BYTES].iter().fold(0, |bits, byte| {
+            (0..4).fold(bits, |bits, pulse| {
+                bits << 1 | u32::from(byte >> (6 - pulse * 2) & 1)
+            })
+        }))
+    }
+}
+
+/// HX711 as an `InputBlock` that outputs (counts, new sample). Counts are the signed 24-bit
+/// conversion result, held until the next conversion is ready, and new sample is true on the
+/// ticks a conversion was read. The HX711 converts at 10 or 80 Hz, depending on its RATE pin.
+pub struct Hx711<I: Hx711Interface> {
+    interface: I,
+    gain: Hx711Gain,
+    buffer: (f64, bool),
+}
+
+impl<I: Hx711Interface> Hx711<I> {
+    pub fn new(interface: I, gain: f64) -> Result<Self, PictorusError> {
+        Ok(Self {
+            interface,
+            gain: Hx711Gain::from_gain(gain)?,
+            buffer: (0.0, false),
+        })
+    }
+
+    /// Reads a conversion if one is ready, returning its signed result
+    pub fn read(&mut self) -> Result<Option<i32>, Hx711Error> {
+        if !self.interface.is_ready()? {
+            return Ok(None);
+        }
+        let bits = self.interface.read_bits(self.gain.extra_pulses())?;
+        // Sign extends the 24-bit two's complement result
+        Ok(Some((bits << 8) as i32 >> 8))
+    }
+}
+
+impl<I: Hx711Interface> InputBlock for Hx711<I> {
+    type Output = (f64, bool);
+    type Parameters = Hx711Params;
+
+    fn input(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        self.buffer.1 = false;
+        match self.read() {
+            Ok(Some(counts)) => self.buffer = (f64::from(counts), true),
+            Ok(None) => {}
+            Err(err) => warn!("Failed to read HX711: {err:?}"),
+        }
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use core::convert::Infallible;
+    use embedded_hal::spi::ErrorType;
+
+    /// Simulated HX711 behind an SPI bus, recording the MOSI pulses and replaying a result
+    struct StubSpi {
+        ready: bool,
+        result: u32,
+        pulses: Vec<u8>,
+    }
+
+    impl ErrorType for StubSpi {
+        type Error = Infallible;
+    }
+
+    impl SpiBus for StubSpi {
+        fn read(&mut self, _words: &mut [u8]) -> Result<(), Self::Error> {
+            unimplemented!()
+        }
+
+        fn write(&mut self, _words: &[u8]) -> Result<(), Self::Error> {
+            unimplemented!()
+        }
+
+        fn transfer(&mut self, _read: &mut [u8], _write: &[u8]) -> Result<(), Self::Error> {
+            unimplemented!()
+        }
+
+        fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+            let mut pulse = 0;
+            for byte in words.iter_mut() {
+                let mosi = *byte;
+                *byte = 0;
+                for bit in (0..8).rev() {
+                    if mosi >> bit & 1 == 1 {
+                        pulse += 1;
+                    }
+                    let dout = match pulse {
+                        0 => !self.ready,
+                        1..=24 => self.result >> (24 - pulse) & 1 == 1,
+                        _ => true,
+                    };
+                    *byte |= u8::from(dout) << bit;
+                }
+            }
+            self.pulses.push(pulse);
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_hx711_spi() {
+        let spi = StubSpi {
+            ready: false,
+            result: 0xFF_FF38, // -200
+            pulses: Vec::new(),
+        };
+        let mut hx711 = Hx711::new(SpiHx711(spi), 64.0).unwrap();
+        let params = Hx711Params::new();
+        let context = crate::can_demux::tests::StubContext(core::time::Duration::ZERO);
+
+        // Not ready, so nothing is clocked
+        assert_eq!(hx711.input(&params, &context), (0.0, false));
+        assert_eq!(hx711.interface.0.pulses, [0]);
+
+        hx711.interface.0.ready = true;
+        assert_eq!(hx711.input(&params, &context), (-200.0, true));
+        assert_eq!(hx711.interface.0.pulses[1..], [0, 27]);
+
+        hx711.interface.0.result = 0x00_1234;
+        assert_eq!(hx711.read(), Ok(Some(0x1234)));
+    }
+
+    #[test]
+    fn test_hx711_gain() {
+        assert_eq!(Hx711Gain::from_gain(128.0).unwrap().extra_pulses(), 1);
+        assert_eq!(Hx711Gain::from_gain(32.0).unwrap().extra_pulses(), 2);
+        assert_eq!(Hx711Gain::from_gain(64.0).unwrap().extra_pulses(), 3);
+        assert!(Hx711Gain::from_gain(100.0).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+//! Virtual register map for serving model signals to an I2C host.
+//!
+//! [`RegisterMap`] implements the usual smart sensor protocol: a host write starts with a
+//! register address, followed by values for consecutive registers, and a host read returns
+//! consecutive registers from the current address (typically set by a write of just the address,
+//! then a repeated start). The first registers are written by the model each tick and are read
+//! only to the host, the rest are written by the host and read by the model, e.g. for
+//! configuration or commands.
+//!
+//! The map is independent of the I2C peripheral, so the platform crates only have to feed it the
+//! bus events.
+
+/// Value read from addresses beyond the end of the map, like an unpopulated bus
+const EMPTY_REGISTER: u8 = 0xFF;
+
+/// Parameters for the I2C slave blocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct I2cSlaveParams {
+    /// 7-bit address the device answers to
+    pub address: u8,
+    /// Number of registers, from address 0, that the model writes and the host can only read
+    pub model_registers: usize,
+}
+
+impl I2cSlaveParams {
+    pub fn new(address: f64, model_registers: f64) -> Self {
+        Self {
+            address: address as u8 & 0x7F,
+            model_registers: model_registers as usize,
+        }
+    }
+}
+
+/// Register file shared between the model and an I2C host, see the [module docs](self)
+#[derive(Debug, Clone)]
+pub struct RegisterMap<const N: usize> {
+    registers: [u8; N],
+    model_registers: usize,
+    pointer: usize,
+    /// Whether the next byte written by the host is a register address
+    expect_address: bool,
+}
+
+impl<const N: usize> RegisterMap<N> {
+    pub fn new(model_registers: usize) -> Self {
+        Self {
+            registers: [0; N],
+            model_registers: model_registers.min(N),
+            pointer: 0,
+            expect_address: false,
+        }
+    }
+
+    /// Handles the host addressing the device. A write starts with the register address; a read
+    /// continues from the current one.
+    pub fn start(&mut self, read: bool) {
+        self.expect_address = !read;
+    }
+
+    /// Handles a byte written by the host. Writes to model registers or beyond the map are
+    /// ignored, but still advance the address.
+    pub fn receive(&mut self, byte: u8) {
+        if self.expect_address {
+            self.pointer = usize::from(byte);
+            self.expect_address = false;
+            return;
+        }
+        if (self.model_registers..N).contains(&self.pointer) {
+            self.registers[self.pointer] = byte;
+        }
+        self.pointer = self.pointer.saturating_add(1);
+    }
+
+    /// Returns the next byte for the host to read
+    pub fn transmit(&mut self) -> u8 {
+        let byte = self
+            .registers
+            .get(self.pointer)
+            .copied()
+            .unwrap_or(EMPTY_REGISTER);
+        self.pointer = self.pointer.saturating_add(1);
+        byte
+    }
+
+    /// Updates the model registers from `data`. Extra bytes are ignored, and registers beyond
+    /// the end of `data` keep their value.
+    pub fn set_model_registers(&mut self, data: &[u8]) {
+        let len = data.len().min(self.model_registers);
+        self.registers[..len].copy_from_slice(&data[..len]);
+    }
+
+    /// Registers written by the host
+    pub fn host_registers(&self) -> &[u8] {
+        &self.registers[self.model_registers..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(map: &mut RegisterMap<8>, bytes: &[u8]) {
+        map.start(false);
+        bytes.iter().for_each(|byte| map.receive(*byte));
+    }
+
+    fn read(map: &mut RegisterMap<8>, count: usize) -> [u8; 4] {
+        map.start(true);
+        let mut bytes = [0; 4];
+        bytes[..count]
+            .iter_mut()
+            .for_each(|byte| *byte = map.transmit());
+        bytes
+    }
+
+    #[test]
+    fn test_register_read() {
+        let mut map = RegisterMap::<8>::new(4);
+        map.set_model_registers(&[1, 2, 3, 4, 5]);
+
+        // Set the address, then read with auto-increment
+        write(&mut map, &[1]);
+        assert_eq!(read(&mut map, 3), [2, 3, 4, 0]);
+        // Reads continue where the last one ended
+        assert_eq!(read(&mut map, 1), [0, 0, 0, 0]);
+
+        // Beyond the map
+        write(&mut map, &[7]);
+        assert_eq!(read(&mut map, 2), [0, 0xFF, 0, 0]);
+    }
+
+    #[test]
+    fn test_register_write() {
+        let mut map = RegisterMap::<8>::new(4);
+        map.set_model_registers(&[1, 2, 3, 4]);
+
+        // Model registers are read only to the host
+        write(&mut map, &[3, 10, 11, 12]);
+        assert_eq!(map.host_registers(), [11, 12, 0, 0]);
+        write(&mut map, &[0]);
+        assert_eq!(read(&mut map, 4), [1, 2, 3, 4]);
+
+        // Writes beyond the map are dropped
+        write(&mut map, &[7, 20, 21]);
+        assert_eq!(map.host_registers(), [11, 12, 0, 20]);
+    }
+
+    #[test]
+    fn test_register_params() {
+        let params = I2cSlaveParams::new(0x42 as f64, 4.0);
+        assert_eq!(params.address, 0x42);
+        assert_eq!(params.model_registers, 4);
+
+        // More model registers than the map holds leaves no host registers
+        let map = RegisterMap::<2>::new(4);
+        assert!(map.host_registers().is_empty());
+    }
+}
@@ -0,0 +1,280 @@
+//! Typed configuration for the I/O protocol instances an app uses.
+//!
+//! Each protocol instance is identified by the name of the block (or component) that owns it,
+//! and describes the hardware resources it should be constructed with. HAL crates look up
+//! their instance at startup, so a deployment can retarget ports and pins by editing the
+//! config file rather than regenerating code. An example in TOML:
+//!
+//! ```toml
+//! [protocols.gps_serial]
+//! type = "serial"
+//! port = "/dev/ttyUSB0"
+//! baud_rate = 115200
+//!
+//! [protocols.can0]
+//! type = "can"
+//! interface = "can0"
+//! bitrate = 500000
+//! ```
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ErrorKind, PictorusError};
+
+const ERR_TYPE: &str = "IoConfig";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerialConfig {
+    /// Device path or peripheral name, e.g. "/dev/ttyUSB0" or "USART2"
+    pub port: String,
+    pub baud_rate: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CanConfig {
+    /// Interface or peripheral name, e.g. "can0" or "FDCAN1"
+    pub interface: String,
+    pub bitrate: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct I2cConfig {
+    /// Bus device path or peripheral name, e.g. "/dev/i2c-1" or "I2C1"
+    pub bus: String,
+    /// Bus frequency, or the HAL's default if unset
+    #[serde(default)]
+    pub frequency_hz: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpiConfig {
+    /// Bus device path or peripheral name, e.g. "/dev/spidev0.0" or "SPI1"
+    pub bus: String,
+    pub frequency_hz: u32,
+    /// SPI mode (0-3)
+    #[serde(default)]
+    pub mode: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PinConfig {
+    /// Pin identifier understood by the HAL, e.g. "17" or "PA5"
+    pub pin: String,
+    /// Frequency for PWM pins, or the HAL's default if unset
+    #[serde(default)]
+    pub frequency_hz: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UdpConfig {
+    /// Local address to bind, e.g. "0.0.0.0:5000"
+    pub address: String,
+}
+
+/// The configuration of a single protocol instance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProtocolConfig {
+    Serial(SerialConfig),
+    Can(CanConfig),
+    I2c(I2cConfig),
+    Spi(SpiConfig),
+    Gpio(PinConfig),
+    Pwm(PinConfig),
+    Adc(PinConfig),
+    Udp(UdpConfig),
+}
+
+/// The protocol instances of an app, keyed by instance name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IoConfig {
+    #[serde(default)]
+    pub protocols: BTreeMap<String, ProtocolConfig>,
+}
+
+macro_rules! typed_getter {
+    ($(#[$meta:meta])* $name:ident, $variant:ident, $config:ty) => {
+        $(#[$meta])*
+        pub fn $name(&self, instance: &str) -> Result<Option<&$config>, PictorusError> {
+            match self.protocols.get(instance) {
+                None => Ok(None),
+                Some(ProtocolConfig::$variant(config)) => Ok(Some(config)),
+                Some(other) => Err(PictorusError::new(
+                    ErrorKind::InvalidConfig,
+                    ERR_TYPE,
+                    alloc::format!(
+                        "Protocol instance {instance} is configured as {other:?}, expected {}",
+                        stringify!($variant)
+                    ),
+                )),
+            }
+        }
+    };
+}
+
+impl IoConfig {
+    typed_getter!(
+        /// Returns the serial config for `instance`, if configured
+        serial,
+        Serial,
+        SerialConfig
+    );
+    typed_getter!(
+        /// Returns the CAN config for `instance`, if configured
+        can,
+        Can,
+        CanConfig
+    );
+    typed_getter!(
+        /// Returns the I2C config for `instance`, if configured
+        i2c,
+        I2c,
+        I2cConfig
+    );
+    typed_getter!(
+        /// Returns the SPI config for `instance`, if configured
+        spi,
+        Spi,
+        SpiConfig
+    );
+    typed_getter!(
+        /// Returns the GPIO config for `instance`, if configured
+        gpio,
+        Gpio,
+        PinConfig
+    );
+    typed_getter!(
+        /// Returns the PWM config for `instance`, if configured
+        pwm,
+        Pwm,
+        PinConfig
+    );
+    typed_getter!(
+        /// Returns the ADC config for `instance`, if configured
+        adc,
+        Adc,
+        PinConfig
+    );
+    typed_getter!(
+        /// Returns the UDP config for `instance`, if configured
+        udp,
+        Udp,
+        UdpConfig
+    );
+
+    #[cfg(feature = "std")]
+    pub fn from_json_str(source: &str) -> Result<Self, PictorusError> {
+        serde_json::from_str(source).map_err(|err| {
+            PictorusError::new(
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
+                alloc::format!("Failed to parse JSON I/O config: {err}"),
+            )
+        })
+    }
+
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(source: &str) -> Result<Self, PictorusError> {
+        toml::from_str(source).map_err(|err| {
+            PictorusError::new(
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
+                alloc::format!("Failed to parse TOML I/O config: {err}"),
+            )
+        })
+    }
+
+    /// Loads the config from a `.json` or (with the `toml` feature) `.toml` file
+    #[cfg(feature = "std")]
+    pub fn load(path: &std::path::Path) -> Result<Self, PictorusError> {
+        let source = std::fs::read_to_string(path).map_err(|err| {
+            PictorusError::from_io(
+                ERR_TYPE,
+                alloc::format!("Failed to read I/O config {}: {err}", path.display()),
+                &err,
+            )
+        })?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json_str(&source),
+            #[cfg(feature = "toml")]
+            Some("toml") => Self::from_toml_str(&source),
+            _ => Err(PictorusError::new(
+                ErrorKind::Unsupported,
+                ERR_TYPE,
+                alloc::format!("Unsupported I/O config format: {}", path.display()),
+            )),
+        }
+    }
+}
+
+/// Loads the I/O config from `io_config.toml` or `io_config.json` in the app's run path,
+/// falling back to an empty config (and the generated defaults) if neither is present.
+#[cfg(feature = "std")]
+pub fn get_io_config(vars: &crate::utils::PictorusVars) -> Result<IoConfig, PictorusError> {
+    let run_path = std::path::PathBuf::from(&vars.run_path);
+    for file_name in ["io_config.toml", "io_config.json"] {
+        let path = run_path.join(file_name);
+        if path.exists() {
+            log::info!("Loading I/O config: {}", path.display());
+            return IoConfig::load(&path);
+        }
+    }
+    log::info!("No I/O config found, using generated defaults.");
+    Ok(IoConfig::default())
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    const CONFIG_JSON: &str = r#"{
+        "protocols": {
+            "gps_serial": {"type": "serial", "port": "/dev/ttyUSB0", "baud_rate": 115200},
+            "can0": {"type": "can", "interface": "can0", "bitrate": 500000},
+            "imu_spi": {"type": "spi", "bus": "/dev/spidev0.0", "frequency_hz": 1000000},
+            "led": {"type": "gpio", "pin": "17"}
+        }
+    }"#;
+
+    #[test]
+    fn test_io_config_typed_lookup() {
+        let config = IoConfig::from_json_str(CONFIG_JSON).unwrap();
+
+        let serial = config.serial("gps_serial").unwrap().unwrap();
+        assert_eq!(serial.port, "/dev/ttyUSB0");
+        assert_eq!(serial.baud_rate, 115200);
+        assert_eq!(config.can("can0").unwrap().unwrap().bitrate, 500000);
+        assert_eq!(config.spi("imu_spi").unwrap().unwrap().mode, 0);
+        assert_eq!(config.gpio("led").unwrap().unwrap().frequency_hz, None);
+
+        // Missing instances fall back to the generated defaults
+        assert_eq!(config.serial("missing").unwrap(), None);
+        // Mismatched protocol types are an error
+        assert!(config.pwm("led").is_err());
+    }
+
+    #[test]
+    fn test_io_config_rejects_unknown_protocol() {
+        assert!(IoConfig::from_json_str(r#"{"protocols": {"x": {"type": "usb"}}}"#).is_err());
+        assert_eq!(IoConfig::from_json_str("{}").unwrap(), IoConfig::default());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_io_config_toml() {
+        let config = IoConfig::from_toml_str(
+            r#"
+            [protocols.gps_serial]
+            type = "serial"
+            port = "/dev/ttyAMA0"
+            baud_rate = 9600
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.serial("gps_serial").unwrap().unwrap().port,
+            "/dev/ttyAMA0"
+        );
+    }
+}
@@ -0,0 +1,695 @@
+//! SAE J1939 on top of a CAN connection, for heavy-vehicle and marine (NMEA 2000) networks.
+//!
+//! [`J1939`] wraps a connection and handles the network management and transport layers:
+//! - Frames are addressed by PGN, with the source and destination addresses taken from the
+//!   29-bit ID. Standard frames aren't J1939 and are ignored.
+//! - The node claims its address on the first tick, and answers requests for address claims.
+//!   If a node with a higher priority NAME claims the same address, this node gives up its
+//!   address and stops transmitting.
+//! - Multipacket messages up to 1785 bytes are reassembled from broadcast (TP.BAM) and
+//!   connection mode (TP.CM RTS/CTS) transfers. Connection mode transfers to this node are
+//!   acknowledged, and aborted if the sender stops.
+//!
+//! Each tick, the receive blocks output the latest complete message with their PGN, or an empty
+//! signal if there was none, like the CAN receive block. Transmits are limited to single frame
+//! messages of up to 8 bytes.
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use embedded_can::{ExtendedId, Frame, Id, nb::Can};
+use log::{debug, warn};
+use pictorus_traits::{ByteSliceSignal, Context, InputBlock, OutputBlock, PassBy};
+
+use crate::protocols::CanProtocol;
+
+/// Destination address of broadcasts, and the "any source" wildcard of the receive blocks
+pub const GLOBAL_ADDRESS: u8 = 0xFF;
+/// Source address of a node that couldn't claim an address
+pub const NULL_ADDRESS: u8 = 0xFE;
+
+const PGN_REQUEST: u32 = 0xEA00;
+const PGN_ADDRESS_CLAIMED: u32 = 0xEE00;
+const PGN_TP_CM: u32 = 0xEC00;
+const PGN_TP_DT: u32 = 0xEB00;
+
+const TP_CM_RTS: u8 = 16;
+const TP_CM_CTS: u8 = 17;
+const TP_CM_END_OF_MSG_ACK: u8 = 19;
+const TP_CM_BAM: u8 = 32;
+const TP_CM_ABORT: u8 = 255;
+const ABORT_RESOURCES: u8 = 2;
+const ABORT_TIMEOUT: u8 = 3;
+
+const PRIORITY_ADDRESS_CLAIM: u8 = 6;
+const PRIORITY_TP: u8 = 7;
+/// Largest message the transport protocol can carry, 255 packets of 7 bytes
+const MAX_TP_SIZE: usize = 1785;
+/// Longest gap between the packets of a broadcast transfer (T1)
+const BAM_TIMEOUT: Duration = Duration::from_millis(750);
+/// Longest gap between the packets of a connection mode transfer (T2)
+const CM_TIMEOUT: Duration = Duration::from_millis(1250);
+/// Time other nodes have to contest an address claim before the node may transmit
+const ADDRESS_CLAIM_DELAY: Duration = Duration::from_millis(250);
+
+/// Fields of a J1939 29-bit CAN ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct J1939Id {
+    pub priority: u8,
+    /// Parameter group number. For destination specific (PDU1) PGNs the low byte is 0.
+    pub pgn: u32,
+    pub source: u8,
+    /// Destination address, [`GLOBAL_ADDRESS`] for broadcast (PDU2) PGNs
+    pub destination: u8,
+}
+
+fn is_pdu1(pgn: u32) -> bool {
+    (pgn >> 8) & 0xFF < 240
+}
+
+impl J1939Id {
+    pub fn from_raw(raw: u32) -> Self {
+        let pgn = (raw >> 8) & 0x3FFFF;
+        let (pgn, destination) = if is_pdu1(pgn) {
+            (pgn & !0xFF, (raw >> 8) as u8)
+        } else {
+            (pgn, GLOBAL_ADDRESS)
+        };
+        Self {
+            priority: ((raw >> 26) & 0x7) as u8,
+            pgn,
+            source: raw as u8,
+            destination,
+        }
+    }
+
+    pub fn to_raw(&self) -> u32 {
+        let mut pgn = self.pgn & 0x3FFFF;
+        if is_pdu1(pgn) {
+            pgn = (pgn & !0xFF) | u32::from(self.destination);
+        }
+        (u32::from(self.priority & 0x7) << 26) | (pgn << 8) | u32::from(self.source)
+    }
+}
+
+/// Parameters for the J1939 receive block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct J1939ReceiveParams {
+    pub pgn: u32,
+    /// Only accept messages from this address, or from any with [`GLOBAL_ADDRESS`]
+    pub source: u8,
+}
+
+impl J1939ReceiveParams {
+    pub fn new(pgn: f64, source: f64) -> Self {
+        Self {
+            pgn: pgn as u32 & 0x3FFFF,
+            source: source as u8,
+        }
+    }
+}
+
+/// Parameters for the J1939 transmit block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct J1939TransmitParams {
+    pub pgn: u32,
+    pub priority: u8,
+    /// Destination address for destination specific (PDU1) PGNs, ignored otherwise
+    pub destination: u8,
+}
+
+impl J1939TransmitParams {
+    pub fn new(pgn: f64, priority: f64, destination: f64) -> Self {
+        Self {
+            pgn: pgn as u32 & 0x3FFFF,
+            priority: (priority as u8).min(7),
+            destination: destination as u8,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Message {
+    pgn: u32,
+    source: u8,
+    data: Vec<u8>,
+}
+
+/// Multipacket transfer being reassembled
+#[derive(Debug)]
+struct Session {
+    source: u8,
+    broadcast: bool,
+    pgn: u32,
+    size: usize,
+    packets: u8,
+    /// Most packets the sender allows per CTS, for connection mode
+    max_per_cts: u8,
+    next_sequence: u8,
+    /// Last sequence number of the current CTS window, for connection mode
+    window_end: u8,
+    data: Vec<u8>,
+    updated: Duration,
+}
+
+/// J1939 node on a CAN connection, see the [module docs](self)
+pub struct J1939<C: CanProtocol> {
+    connection: C,
+    name: u64,
+    address: u8,
+    claim_time: Option<Duration>,
+    sessions: Vec<Session>,
+    messages: Vec<Message>,
+    stale: bool,
+}
+
+impl<C: CanProtocol> J1939<C> {
+    /// Creates a node that claims `address` with the 64-bit `name`. Lower NAMEs win address
+    /// conflicts.
+    pub fn new(connection: C, name: u64, address: u8) -> Self {
+        Self {
+            connection,
+            name,
+            address,
+            claim_time: None,
+            sessions: Vec::new(),
+            messages: Vec::new(),
+            stale: true,
+        }
+    }
+
+    pub fn connection(&mut self) -> &mut C {
+        &mut self.connection
+    }
+
+    /// Address of the node, or [`NULL_ADDRESS`] if it lost its address claim
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    fn can_transmit(&self, app_time: Duration) -> bool {
+        self.address != NULL_ADDRESS
+            && self
+                .claim_time
+                .is_some_and(|claimed| app_time.saturating_sub(claimed) >= ADDRESS_CLAIM_DELAY)
+    }
+
+    fn send(&mut self, id: J1939Id, data: &[u8]) {
+        let frame = ExtendedId::new(id.to_raw()).and_then(|raw_id| C::Frame::new(raw_id, data));
+        let Some(frame) = frame else {
+            warn!("Failed to create J1939 frame for PGN {:#X}", id.pgn);
+            return;
+        };
+        if let Err(err) = self.connection.transmit(&frame) {
+            warn!("Failed to transmit J1939 frame: {err:?}");
+        }
+    }
+
+    fn send_address_claim(&mut self) {
+        let id = J1939Id {
+            priority: PRIORITY_ADDRESS_CLAIM,
+            pgn: PGN_ADDRESS_CLAIMED,
+            source: self.address,
+            destination: GLOBAL_ADDRESS,
+        };
+        self.send(id, &self.name.to_le_bytes());
+    }
+
+    fn send_tp_cm(&mut self, destination: u8, control: [u8; 5], pgn: u32) {
+        let id = J1939Id {
+            priority: PRIORITY_TP,
+            pgn: PGN_TP_CM,
+            source: self.address,
+            destination,
+        };
+        let [pgn_0, pgn_1, pgn_2, _] = pgn.to_le_bytes();
+        let [c_0, c_1, c_2, c_3, c_4] = control;
+        self.send(id, &[c_0, c_1, c_2, c_3, c_4, pgn_0, pgn_1, pgn_2]);
+    }
+
+    fn send_abort(&mut self, destination: u8, pgn: u32, reason: u8) {
+        self.send_tp_cm(destination, [TP_CM_ABORT, reason, 0xFF, 0xFF, 0xFF], pgn);
+    }
+
+    /// Sends a CTS for the next packets of the session at `index`
+    fn send_cts(&mut self, index: usize) {
+        let session = &mut self.sessions[index];
+        let remaining = session.packets - session.next_sequence + 1;
+        let count = remaining.min(session.max_per_cts);
+        session.window_end = session.next_sequence + count - 1;
+        let (source, next_sequence, pgn) = (session.source, session.next_sequence, session.pgn);
+        self.send_tp_cm(source, [TP_CM_CTS, count, next_sequence, 0xFF, 0xFF], pgn);
+    }
+
+    /// Reads and handles this tick's frames, if that hasn't been done since the last flush
+    fn poll(&mut self, app_time: Duration) {
+        if !self.stale {
+            return;
+        }
+        self.stale = false;
+        if self.claim_time.is_none() {
+            self.send_address_claim();
+            self.claim_time = Some(app_time);
+        }
+
+        // Copied out so frames can be sent in response
+        let frames: Vec<(J1939Id, heapless::Vec<u8, 8>)> = self
+            .connection
+            .read_frames()
+            .iter()
+            .filter_map(|frame| match frame.id() {
+                Id::Extended(id) if !frame.is_remote_frame() => Some((
+                    J1939Id::from_raw(id.as_raw()),
+                    heapless::Vec::from_slice(&frame.data()[..frame.data().len().min(8)]).ok()?,
+                )),
+                _ => None,
+            })
+            .collect();
+        for (id, data) in frames {
+            self.handle_frame(id, &data, app_time);
+        }
+        self.expire_sessions(app_time);
+    }
+
+    fn handle_frame(&mut self, id: J1939Id, data: &[u8], app_time: Duration) {
+        let for_node = id.destination == GLOBAL_ADDRESS
+            || (id.destination == self.address && self.address != NULL_ADDRESS);
+        if !for_node {
+            return;
+        }
+        match id.pgn {
+            PGN_ADDRESS_CLAIMED => self.handle_address_claim(id.source, data),
+            PGN_REQUEST if data.get(..3) == Some(&PGN_ADDRESS_CLAIMED.to_le_bytes()[..3]) => {
+                self.send_address_claim();
+            }
+            PGN_TP_CM => self.handle_tp_cm(id, data, app_time),
+            PGN_TP_DT => self.handle_tp_dt(id, data, app_time),
+            pgn => self.messages.push(Message {
+                pgn,
+                source: id.source,
+                data: data.into(),
+            }),
+        }
+    }
+
+    fn handle_address_claim(&mut self, source: u8, data: &[u8]) {
+        let Some(name) = data.get(..8).and_then(|name| name.try_into().ok()) else {
+            return;
+        };
+        let name = u64::from_le_bytes(name);
+        if source != self.address || self.address == NULL_ADDRESS || name == self.name {
+            return;
+        }
+        if name < self.name {
+            warn!("Lost J1939 address {source} to a node with a higher priority NAME");
+            self.address = NULL_ADDRESS;
+        }
+        // Either defends the address, or reports that the node couldn't claim one
+        self.send_address_claim();
+    }
+
+    fn handle_tp_cm(&mut self, id: J1939Id, data: &[u8], app_time: Duration) {
+        let Ok(data) = <[u8; 8]>::try_from(data) else {
+            return;
+        };
+        let size = usize::from(u16::from_le_bytes([data[1], data[2]]));
+        let packets = data[3];
+        let pgn = u32::from_le_bytes([data[5], data[6], data[7], 0]);
+        let broadcast = id.destination == GLOBAL_ADDRESS;
+        if data[0] == TP_CM_ABORT && !broadcast {
+            self.sessions
+                .retain(|session| session.source != id.source || session.broadcast);
+            return;
+        }
+        let opens = matches!((data[0], broadcast), (TP_CM_BAM, true) | (TP_CM_RTS, false));
+        if !opens {
+            return;
+        }
+        // A new transfer from the same sender replaces the old one
+        self.sessions
+            .retain(|session| session.source != id.source || session.broadcast != broadcast);
+        let valid = size > 8 && size <= MAX_TP_SIZE && usize::from(packets) == size.div_ceil(7);
+        if !valid {
+            debug!(
+                "Ignoring invalid J1939 transfer of {size} bytes from {}",
+                id.source
+            );
+            if !broadcast {
+                self.send_abort(id.source, pgn, ABORT_RESOURCES);
+            }
+            return;
+        }
+        self.sessions.push(Session {
+            source: id.source,
+            broadcast,
+            pgn,
+            size,
+            packets,
+            max_per_cts: data[4].max(1),
+            next_sequence: 1,
+            window_end: packets,
+            data: Vec::with_capacity(size),
+            updated: app_time,
+        });
+        if !broadcast {
+            self.send_cts(self.sessions.len() - 1);
+        }
+    }
+
+    fn handle_tp_dt(&mut self, id: J1939Id, data: &[u8], app_time: Duration) {
+        let broadcast = id.destination == GLOBAL_ADDRESS;
+        let Some(index) = self
+            .sessions
+            .iter()
+            .position(|session| session.source == id.source && session.broadcast == broadcast)
+        else {
+            return;
+        };
+        let Some((&sequence, payload)) = data.split_first() else {
+            return;
+        };
+        let session = &mut self.sessions[index];
+        if sequence != session.next_sequence || sequence > session.window_end {
+            let session = self.sessions.swap_remove(index);
+            debug!("J1939 transfer from {} lost a packet", session.source);
+            if !broadcast {
+                self.send_abort(session.source, session.pgn, ABORT_TIMEOUT);
+            }
+            return;
+        }
+        session.data.extend_from_slice(payload);
+        session.next_sequence += 1;
+        session.updated = app_time;
+
+        if session.data.len() >= session.size {
+            let mut session = self.sessions.swap_remove(index);
+            session.data.truncate(session.size);
+            if !broadcast {
+                let [size_0, size_1] = (session.size as u16).to_le_bytes();
+                self.send_tp_cm(
+                    session.source,
+                    [TP_CM_END_OF_MSG_ACK, size_0, size_1, session.packets, 0xFF],
+                    session.pgn,
+                );
+            }
+            self.messages.push(Message {
+                pgn: session.pgn,
+                source: session.source,
+                data: session.data,
+            });
+        } else if !broadcast && session.next_sequence > session.window_end {
+            self.send_cts(index);
+        }
+    }
+
+    fn expire_sessions(&mut self, app_time: Duration) {
+        let mut index = 0;
+        while index < self.sessions.len() {
+            let session = &self.sessions[index];
+            let timeout = if session.broadcast {
+                BAM_TIMEOUT
+            } else {
+                CM_TIMEOUT
+            };
+            if app_time.saturating_sub(session.updated) <= timeout {
+                index += 1;
+                continue;
+            }
+            let session = self.sessions.swap_remove(index);
+            debug!("J1939 transfer from {} timed out", session.source);
+            if !session.broadcast {
+                self.send_abort(session.source, session.pgn, ABORT_TIMEOUT);
+            }
+        }
+    }
+}
+
+impl<C: CanProtocol> Can for J1939<C> {
+    type Frame = C::Frame;
+    type Error = C::Error;
+
+    fn transmit(&mut self, frame: &Self::Frame) -> nb::Result<Option<Self::Frame>, Self::Error> {
+        self.connection.transmit(frame)
+    }
+
+    fn receive(&mut self) -> nb::Result<Self::Frame, Self::Error> {
+        self.connection.receive()
+    }
+}
+
+impl<C: CanProtocol> CanProtocol for J1939<C> {
+    fn read_frames(&mut self) -> &[impl Frame] {
+        self.connection.read_frames()
+    }
+
+    fn flush(&mut self) {
+        self.connection.flush();
+        self.messages.clear();
+        self.stale = true;
+    }
+}
+
+impl<C: CanProtocol> InputBlock for J1939<C> {
+    type Output = ByteSliceSignal;
+    type Parameters = J1939ReceiveParams;
+
+    fn input(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        self.poll(context.time());
+        self.messages
+            .iter()
+            .rfind(|message| {
+                message.pgn == parameters.pgn
+                    && (parameters.source == GLOBAL_ADDRESS || message.source == parameters.source)
+            })
+            .map_or(&[], |message| &message.data)
+    }
+}
+
+impl<C: CanProtocol> OutputBlock for J1939<C> {
+    type Inputs = ByteSliceSignal;
+    type Parameters = J1939TransmitParams;
+
+    fn output(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) {
+        self.poll(context.time());
+        if !self.can_transmit(context.time()) {
+            return;
+        }
+        if inputs.len() > 8 {
+            warn!(
+                "J1939 transmit of {} bytes for PGN {:#X} exceeds a single frame",
+                inputs.len(),
+                parameters.pgn
+            );
+            return;
+        }
+        let id = J1939Id {
+            priority: parameters.priority,
+            pgn: parameters.pgn,
+            source: self.address,
+            destination: parameters.destination,
+        };
+        self.send(id, inputs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::can_demux::tests::{StubCan, StubContext, StubFrame, extended};
+
+    const NAME: u64 = 0x8000_0000_0012_3456;
+    const ADDRESS: u8 = 0x80;
+
+    fn frame(id: J1939Id, data: &[u8]) -> StubFrame {
+        StubFrame::new(extended(id.to_raw()), data).unwrap()
+    }
+
+    fn id(pgn: u32, source: u8, destination: u8) -> J1939Id {
+        J1939Id {
+            priority: PRIORITY_TP,
+            pgn,
+            source,
+            destination,
+        }
+    }
+
+    impl J1939Id {
+        fn with_priority(self, priority: u8) -> Self {
+            Self { priority, ..self }
+        }
+    }
+
+    /// Runs a tick with `frames` received, and returns the frames sent
+    fn tick(node: &mut J1939<StubCan>, frames: &[StubFrame], time_ms: u64) -> Vec<StubFrame> {
+        node.flush();
+        node.connection().frames = frames.into();
+        let context = StubContext(Duration::from_millis(time_ms));
+        node.input(&J1939ReceiveParams::new(0.0, 255.0), &context);
+        core::mem::take(&mut node.connection().sent)
+    }
+
+    fn received(node: &mut J1939<StubCan>, pgn: u32, source: u8) -> Vec<u8> {
+        let params = J1939ReceiveParams::new(pgn as f64, source as f64);
+        node.input(&params, &StubContext(Duration::ZERO)).into()
+    }
+
+    #[test]
+    fn test_j1939_id() {
+        // EEC1 (PGN 61444, PDU2) from the engine at priority 3
+        let eec1 = J1939Id::from_raw(0x0CF0_0400);
+        assert_eq!(eec1, id(61444, 0x00, GLOBAL_ADDRESS).with_priority(3));
+        assert_eq!(eec1.to_raw(), 0x0CF0_0400);
+
+        // Request (PDU1) from 0xF9 to 0x00
+        let request = J1939Id::from_raw(0x18EA_00F9);
+        assert_eq!(request, id(PGN_REQUEST, 0xF9, 0x00).with_priority(6));
+        assert_eq!(request.to_raw(), 0x18EA_00F9);
+    }
+
+    #[test]
+    fn test_j1939_address_claim() {
+        let mut node = J1939::new(StubCan::default(), NAME, ADDRESS);
+        let claim = frame(
+            id(PGN_ADDRESS_CLAIMED, ADDRESS, GLOBAL_ADDRESS).with_priority(6),
+            &NAME.to_le_bytes(),
+        );
+        assert_eq!(tick(&mut node, &[], 0), core::slice::from_ref(&claim));
+
+        // Can't transmit until the claim has gone uncontested
+        let params = J1939TransmitParams::new(65262.0, 6.0, 255.0);
+        node.output(&params, &StubContext(Duration::from_millis(100)), &[1, 2]);
+        assert!(node.connection().sent.is_empty());
+        node.output(&params, &StubContext(Duration::from_millis(250)), &[1, 2]);
+        assert_eq!(node.connection().sent.len(), 1);
+        node.connection().sent.clear();
+
+        // Requests for address claims are answered
+        let request = frame(id(PGN_REQUEST, 0x10, GLOBAL_ADDRESS), &[0x00, 0xEE, 0x00]);
+        assert_eq!(
+            tick(&mut node, &[request], 300),
+            core::slice::from_ref(&claim)
+        );
+
+        // A lower priority NAME is refused, a higher priority one takes the address
+        let lower = frame(
+            id(PGN_ADDRESS_CLAIMED, ADDRESS, GLOBAL_ADDRESS),
+            &(NAME + 1).to_le_bytes(),
+        );
+        assert_eq!(tick(&mut node, &[lower], 400), [claim]);
+        let higher = frame(
+            id(PGN_ADDRESS_CLAIMED, ADDRESS, GLOBAL_ADDRESS),
+            &(NAME - 1).to_le_bytes(),
+        );
+        let cannot_claim = frame(
+            id(PGN_ADDRESS_CLAIMED, NULL_ADDRESS, GLOBAL_ADDRESS).with_priority(6),
+            &NAME.to_le_bytes(),
+        );
+        assert_eq!(tick(&mut node, &[higher], 500), [cannot_claim]);
+        assert_eq!(node.address(), NULL_ADDRESS);
+        node.output(&params, &StubContext(Duration::from_millis(600)), &[1, 2]);
+        assert!(node.connection().sent.is_empty());
+    }
+
+    #[test]
+    fn test_j1939_bam() {
+        let mut node = J1939::new(StubCan::default(), NAME, ADDRESS);
+        tick(&mut node, &[], 0);
+
+        // 10 bytes of PGN 65226 (DM1) in 2 packets
+        let cm = frame(
+            id(PGN_TP_CM, 0x00, GLOBAL_ADDRESS),
+            &[TP_CM_BAM, 10, 0, 2, 0xFF, 0xCA, 0xFE, 0x00],
+        );
+        let dt_1 = frame(
+            id(PGN_TP_DT, 0x00, GLOBAL_ADDRESS),
+            &[1, 0, 1, 2, 3, 4, 5, 6],
+        );
+        let dt_2 = frame(
+            id(PGN_TP_DT, 0x00, GLOBAL_ADDRESS),
+            &[2, 7, 8, 9, 0xFF, 0xFF, 0xFF, 0xFF],
+        );
+        assert!(tick(&mut node, &[cm.clone(), dt_1.clone()], 100).is_empty());
+        assert!(received(&mut node, 65226, 255).is_empty());
+        // Broadcasts aren't acknowledged
+        assert!(tick(&mut node, core::slice::from_ref(&dt_2), 150).is_empty());
+        assert_eq!(
+            received(&mut node, 65226, 0x00),
+            [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+        );
+        assert!(received(&mut node, 65226, 0x01).is_empty());
+        // Only output on the tick the message completes
+        tick(&mut node, &[], 200);
+        assert!(received(&mut node, 65226, 255).is_empty());
+
+        // Timed out transfers are dropped
+        tick(&mut node, &[cm, dt_1], 300);
+        tick(&mut node, &[], 1100);
+        tick(&mut node, &[dt_2], 1150);
+        assert!(received(&mut node, 65226, 255).is_empty());
+    }
+
+    #[test]
+    fn test_j1939_rts_cts() {
+        let mut node = J1939::new(StubCan::default(), NAME, ADDRESS);
+        tick(&mut node, &[], 0);
+
+        // 16 bytes of PGN 0xEF00 (proprietary A) in 3 packets, at most 2 per CTS
+        let rts = frame(
+            id(PGN_TP_CM, 0x20, ADDRESS),
+            &[TP_CM_RTS, 16, 0, 3, 2, 0x00, 0xEF, 0x00],
+        );
+        let response = |control: [u8; 5]| {
+            let mut data = control.to_vec();
+            data.extend_from_slice(&[0x00, 0xEF, 0x00]);
+            frame(id(PGN_TP_CM, ADDRESS, 0x20), &data)
+        };
+        // Each packet is filled with its sequence number
+        let dt = |sequence: u8| frame(id(PGN_TP_DT, 0x20, ADDRESS), &[sequence; 8]);
+
+        assert_eq!(
+            tick(&mut node, &[rts], 100),
+            [response([TP_CM_CTS, 2, 1, 0xFF, 0xFF])]
+        );
+        assert_eq!(
+            tick(&mut node, &[dt(1), dt(2)], 150),
+            [response([TP_CM_CTS, 1, 3, 0xFF, 0xFF])]
+        );
+        assert_eq!(
+            tick(&mut node, &[dt(3)], 200),
+            [response([TP_CM_END_OF_MSG_ACK, 16, 0, 3, 0xFF])]
+        );
+        let mut expected = [1; 16];
+        expected[7..14].fill(2);
+        expected[14..].fill(3);
+        assert_eq!(received(&mut node, 0xEF00, 0x20), expected);
+
+        // Transfers to other nodes are ignored
+        let other = frame(
+            id(PGN_TP_CM, 0x20, 0x81),
+            &[TP_CM_RTS, 16, 0, 3, 2, 0x00, 0xEF, 0x00],
+        );
+        assert!(tick(&mut node, &[other], 300).is_empty());
+
+        // A stalled transfer is aborted
+        let rts = frame(
+            id(PGN_TP_CM, 0x20, ADDRESS),
+            &[TP_CM_RTS, 16, 0, 3, 0xFF, 0x00, 0xEF, 0x00],
+        );
+        assert_eq!(
+            tick(&mut node, &[rts], 400),
+            [response([TP_CM_CTS, 3, 1, 0xFF, 0xFF])]
+        );
+        assert_eq!(
+            tick(&mut node, &[], 1700),
+            [response([TP_CM_ABORT, ABORT_TIMEOUT, 0xFF, 0xFF, 0xFF])]
+        );
+    }
+}
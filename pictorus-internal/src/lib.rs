@@ -11,14 +11,44 @@ extern crate alloc;
 extern crate std;
 
 pub mod execution_controller;
-pub use execution_controller::ExecutionController;
+pub use execution_controller::{ExecutionController, MultiModelController};
 
 pub mod runtime_context;
 pub use runtime_context::RuntimeContext;
 
+pub mod can_demux;
+pub mod can_health;
+#[cfg(feature = "coverage")]
+pub mod coverage;
+pub mod display;
+#[cfg(feature = "std")]
+pub mod distributed;
+#[cfg(feature = "embedded-metrics")]
+pub mod embedded_metrics;
 pub mod encoders;
+pub mod error;
+#[cfg(feature = "http_server")]
+pub mod http_server;
+pub mod hx711;
+pub mod i2c_slave;
+#[cfg(feature = "alloc")]
+pub mod io_config;
+#[cfg(feature = "alloc")]
+pub mod j1939;
 pub mod loggers;
 pub mod logging;
+pub mod lora;
+#[cfg(feature = "montecarlo")]
+pub mod montecarlo;
+pub mod param_store;
+pub mod persistent_counter;
 pub mod protocols;
+pub mod rand;
+pub mod rangefinder;
+pub mod selftest;
+pub mod shift_register;
+#[cfg(feature = "signatures")]
+pub mod signing;
 pub mod timing;
+pub mod tone;
 pub mod utils;
@@ -1,11 +1,12 @@
 use chrono::Utc;
+use core::fmt::Write as _;
 use core::time::Duration;
 use log::info;
 use std::boxed::Box;
 use std::io::{BufWriter, Write};
 use std::{fs::File, string::String};
 
-use super::Logger;
+use super::{Logger, SyncedTimestamp};
 
 /// CsvLogger logs data to a file in CSV format.
 ///
@@ -18,6 +19,8 @@ pub struct CsvLogger {
     pub writer: Box<dyn Write>,
     pub output_path: std::path::PathBuf,
     pub app_start_epoch: Duration,
+    /// Adds sync_time and sync_source columns holding the synchronized timestamp of each sample
+    synced_time: bool,
     /// Reusable buffer for formatting CSV samples to avoid repeated allocations.
     buffer: String,
 }
@@ -45,9 +48,18 @@ impl CsvLogger {
                     .try_into()
                     .expect("Could not cast app start epoch as u64"),
             ),
+            synced_time: false,
             buffer: String::with_capacity(1024),
         }
     }
+
+    /// Adds sync_time (seconds since the Unix epoch) and sync_source columns, holding the
+    /// synchronized timestamps passed to `log_timestamped`. They're empty for samples without
+    /// one, e.g. before the clock is synchronized.
+    pub fn with_synced_time(mut self) -> Self {
+        self.synced_time = true;
+        self
+    }
 }
 
 impl Logger for CsvLogger {
@@ -60,10 +72,25 @@ impl Logger for CsvLogger {
     }
 
     fn log(&mut self, log_data: &impl serde::Serialize, app_time: Duration) {
+        self.log_timestamped(log_data, app_time, None);
+    }
+
+    fn log_timestamped(
+        &mut self,
+        log_data: &impl serde::Serialize,
+        app_time: Duration,
+        timestamp: Option<SyncedTimestamp>,
+    ) {
         if self.should_log(app_time) {
             format_samples_csv(log_data, &mut self.buffer);
+            if self.synced_time {
+                push_synced_time_csv(timestamp, &mut self.buffer);
+            }
             if self.last_csv_log_time.is_none() {
-                let header = format_header_csv(log_data);
+                let mut header = format_header_csv(log_data);
+                if self.synced_time {
+                    header.push_str(",sync_time,sync_source");
+                }
                 writeln!(self.writer, "{header}").ok();
             }
             writeln!(self.writer, "{}", self.buffer).ok();
@@ -91,6 +118,24 @@ pub fn format_header_csv(data: &impl serde::Serialize) -> String {
     header
 }
 
+/// Appends the sync_time and sync_source columns of a sample to its CSV row
+fn push_synced_time_csv(timestamp: Option<SyncedTimestamp>, output: &mut String) {
+    match timestamp {
+        Some(timestamp) => {
+            let unix_time = timestamp.unix_time;
+            write!(
+                output,
+                ",{}.{:06},{}",
+                unix_time.as_secs(),
+                unix_time.subsec_micros(),
+                timestamp.source.as_str()
+            )
+            .ok();
+        }
+        None => output.push_str(",,"),
+    }
+}
+
 /// Formats the samples for CSV output based on the provided data.
 pub fn format_samples_csv(data: &impl serde::Serialize, output: &mut String) {
     output.clear();
@@ -225,4 +270,44 @@ mod tests {
         dl.log(&log_data, Duration::from_millis(123));
         assert_eq!(dl.last_csv_log_time, Some(Duration::from_millis(123)));
     }
+
+    /// Writer whose output can be read back while the logger owns it
+    #[derive(Clone, Default)]
+    struct SharedWriter(std::sync::Arc<std::sync::Mutex<std::vec::Vec<u8>>>);
+
+    impl Write for SharedWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_csv_logger_synced_time() {
+        #[derive(serde::Serialize)]
+        struct Sample {
+            scalar: f64,
+        }
+
+        let output = SharedWriter::default();
+        let mut dl =
+            CsvLogger::new(Duration::from_millis(10), "/dev/null".into()).with_synced_time();
+        dl.writer = Box::new(output.clone());
+
+        let timestamp = SyncedTimestamp {
+            unix_time: Duration::new(1_700_000_000, 1_500_000),
+            source: super::super::TimeSource::Ptp,
+        };
+        dl.log_timestamped(&Sample { scalar: 1.0 }, Duration::ZERO, Some(timestamp));
+        dl.log(&Sample { scalar: 2.0 }, Duration::from_millis(10));
+
+        let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(
+            output,
+            "scalar,sync_time,sync_source\n1.0,1700000000.001500,ptp\n2.0,,\n"
+        );
+    }
 }
@@ -0,0 +1,525 @@
+use chrono::Utc;
+use core::time::Duration;
+use log::{info, warn};
+use serde::Deserialize;
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket},
+    string::{String, ToString},
+    sync::mpsc::{Receiver, SyncSender, TrySendError, sync_channel},
+    thread::JoinHandle,
+};
+
+use super::{Logger, SyncedTimestamp};
+
+// Batches are flushed early once they grow past this size, to stay under typical UDP limits
+const MAX_BATCH_BYTES: usize = 8192;
+const HTTP_TIMEOUT: Duration = Duration::from_secs(1);
+/// Batches waiting for the sender thread beyond this are dropped
+const QUEUED_BATCHES: usize = 4;
+
+/// Where the InfluxLogger sends its batches.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InfluxTransport {
+    /// Send each batch as a datagram to an InfluxDB UDP listener, e.g. "127.0.0.1:8089"
+    Udp { address: String },
+    /// POST each batch to the InfluxDB HTTP API, e.g. address "127.0.0.1:8086" and path
+    /// "/api/v2/write?org=my-org&bucket=my-bucket&precision=ns"
+    Http {
+        address: String,
+        path: String,
+        #[serde(default)]
+        token: Option<String>,
+    },
+}
+
+/// Configuration for the InfluxLogger, typically deserialized from the app config.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct InfluxConfig {
+    pub transport: InfluxTransport,
+    /// Measurement name for all samples
+    pub measurement: String,
+    /// Tags attached to every sample, such as the app or device name
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    /// Number of samples sent in each batch
+    pub batch_size: usize,
+}
+
+/// Where the sender thread ships batches, with the address resolved when the logger is created
+enum Destination {
+    Udp {
+        socket: UdpSocket,
+        address: SocketAddr,
+    },
+    Http {
+        address: SocketAddr,
+        host: String,
+        path: String,
+        token: Option<String>,
+    },
+}
+
+/// Line protocol for some samples, and how many there are
+struct Batch {
+    lines: String,
+    count: usize,
+}
+
+/// InfluxLogger batches samples in InfluxDB line protocol and ships them over UDP or HTTP.
+///
+/// Numeric and boolean signals become fields, with matrices flattened into one field per
+/// element (e.g. `matrix_1_0`). The current state is logged as a string field. Samples are
+/// timestamped with the UTC time derived from the app start epoch, like the CsvLogger, unless
+/// they're logged with a synchronized timestamp, which is used instead so samples from several
+/// devices line up.
+///
+/// Batches are shipped from a background thread, so a slow or unreachable InfluxDB never stalls
+/// the app. If batches are produced faster than they can be sent, new batches are dropped once
+/// a few are queued.
+pub struct InfluxLogger {
+    config: InfluxConfig,
+    sender: Option<SyncSender<Batch>>,
+    thread: Option<JoinHandle<()>>,
+    log_period: Duration,
+    last_log_time: Option<Duration>,
+    app_start_epoch: Duration,
+    /// Line protocol prefix shared by every sample (measurement and tags)
+    series_key: String,
+    batch: String,
+    batch_count: usize,
+}
+
+impl InfluxLogger {
+    pub fn new(log_period: Duration, config: InfluxConfig) -> Self {
+        let destination = if log_period.is_zero() {
+            info!("Not logging to InfluxDB, logging rate set to zero.");
+            None
+        } else {
+            match connect(&config.transport) {
+                Ok(destination) => Some(destination),
+                Err(err) => {
+                    warn!("Not logging to InfluxDB, couldn't connect: {err}");
+                    None
+                }
+            }
+        };
+        let (sender, thread) = match destination {
+            Some(destination) => {
+                let (sender, receiver) = sync_channel(QUEUED_BATCHES);
+                let thread = std::thread::spawn(move || ship(&destination, receiver));
+                (Some(sender), Some(thread))
+            }
+            None => (None, None),
+        };
+
+        let mut series_key = escape_key(&config.measurement, true);
+        for (key, value) in &config.tags {
+            series_key.push(',');
+            series_key.push_str(&escape_key(key, false));
+            series_key.push('=');
+            series_key.push_str(&escape_key(value, false));
+        }
+
+        InfluxLogger {
+            config,
+            sender,
+            thread,
+            log_period,
+            last_log_time: None,
+            app_start_epoch: Duration::from_micros(
+                Utc::now()
+                    .timestamp_micros()
+                    .try_into()
+                    .expect("Could not cast app start epoch as u64"),
+            ),
+            series_key,
+            batch: String::with_capacity(MAX_BATCH_BYTES),
+            batch_count: 0,
+        }
+    }
+
+    /// Queues any batched samples to be sent immediately
+    pub fn flush(&mut self) {
+        if self.batch.is_empty() {
+            return;
+        }
+
+        let batch = Batch {
+            lines: core::mem::replace(&mut self.batch, String::with_capacity(MAX_BATCH_BYTES)),
+            count: core::mem::take(&mut self.batch_count),
+        };
+        if let Some(sender) = &self.sender {
+            match sender.try_send(batch) {
+                Ok(()) => {}
+                Err(TrySendError::Full(batch)) => warn!(
+                    "InfluxDB sender is falling behind, dropping {} samples",
+                    batch.count
+                ),
+                Err(TrySendError::Disconnected(batch)) => {
+                    warn!("InfluxDB sender stopped, dropping {} samples", batch.count)
+                }
+            }
+        }
+    }
+}
+
+impl Logger for InfluxLogger {
+    fn should_log(&mut self, app_time: Duration) -> bool {
+        self.log_period > Duration::ZERO
+            && match self.last_log_time {
+                None => true, // Log if there's no previous log time
+                Some(last_log) => (app_time - last_log) >= self.log_period,
+            }
+    }
+
+    fn log(&mut self, log_data: &impl serde::Serialize, app_time: Duration) {
+        self.log_timestamped(log_data, app_time, None);
+    }
+
+    fn log_timestamped(
+        &mut self,
+        log_data: &impl serde::Serialize,
+        app_time: Duration,
+        timestamp: Option<SyncedTimestamp>,
+    ) {
+        if self.sender.is_none() || !self.should_log(app_time) {
+            return;
+        }
+
+        let timestamp = match timestamp {
+            Some(timestamp) => timestamp.unix_time,
+            None => self.app_start_epoch + app_time,
+        };
+        format_line_protocol(&self.series_key, log_data, timestamp, &mut self.batch);
+        self.batch_count += 1;
+        self.last_log_time = Some(app_time);
+        if self.batch_count >= self.config.batch_size || self.batch.len() >= MAX_BATCH_BYTES {
+            self.flush();
+        }
+    }
+}
+
+impl Drop for InfluxLogger {
+    fn drop(&mut self) {
+        self.flush();
+        // Closing the channel stops the sender thread once it has sent the queued batches
+        self.sender = None;
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+/// Resolves the transport's address and opens its socket
+fn connect(transport: &InfluxTransport) -> std::io::Result<Destination> {
+    let resolve = |address: &str| {
+        address.to_socket_addrs()?.next().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                std::format!("Could not resolve address: {address}"),
+            )
+        })
+    };
+    match transport {
+        InfluxTransport::Udp { address } => {
+            info!("Logging to InfluxDB over UDP at: {address}");
+            Ok(Destination::Udp {
+                address: resolve(address)?,
+                socket: UdpSocket::bind("0.0.0.0:0")?,
+            })
+        }
+        InfluxTransport::Http {
+            address,
+            path,
+            token,
+        } => {
+            info!("Logging to InfluxDB over HTTP at: {address}");
+            Ok(Destination::Http {
+                address: resolve(address)?,
+                host: address.clone(),
+                path: path.clone(),
+                token: token.clone(),
+            })
+        }
+    }
+}
+
+/// Runs on the sender thread until the logger is dropped
+fn ship(destination: &Destination, batches: Receiver<Batch>) {
+    for batch in batches {
+        let result = match destination {
+            Destination::Udp { socket, address } => {
+                socket.send_to(batch.lines.as_bytes(), address).map(|_| ())
+            }
+            Destination::Http {
+                address,
+                host,
+                path,
+                token,
+            } => post(address, host, path, token.as_deref(), &batch.lines),
+        };
+        if let Err(err) = result {
+            warn!(
+                "Failed to send InfluxDB batch, dropping {} samples: {err}",
+                batch.count
+            );
+        }
+    }
+}
+
+/// Appends a sample in InfluxDB line protocol to `output`. The `series_key` is the escaped
+/// measurement name followed by any tags.
+pub fn format_line_protocol(
+    series_key: &str,
+    data: &impl serde::Serialize,
+    timestamp: Duration,
+    output: &mut String,
+) {
+    let json = serde_json::to_value(data).unwrap();
+    let start = output.len();
+    output.push_str(series_key);
+    output.push(' ');
+    let fields_start = output.len();
+    if let Some(json_map) = json.as_object() {
+        for (key, value) in json_map {
+            push_field(output, &escape_key(key, false), value);
+        }
+    }
+
+    if output.len() == fields_start {
+        // Line protocol requires at least one field, so drop samples without any
+        output.truncate(start);
+        return;
+    }
+
+    // Replace the trailing field separator
+    output.pop();
+    output.push(' ');
+    output.push_str(&timestamp.as_nanos().to_string());
+    output.push('\n');
+}
+
+fn push_field(output: &mut String, key: &str, value: &Value) {
+    match value {
+        Value::Bool(flag) => {
+            output.push_str(key);
+            output.push('=');
+            output.push_str(if *flag { "true" } else { "false" });
+            output.push(',');
+        }
+        Value::Number(number) => {
+            // Non-finite values can't be represented in line protocol
+            if let Some(number) = number.as_f64().filter(|n| n.is_finite()) {
+                output.push_str(key);
+                output.push('=');
+                output.push_str(&number.to_string());
+                output.push(',');
+            }
+        }
+        Value::String(text) => {
+            output.push_str(key);
+            output.push_str("=\"");
+            output.push_str(&text.replace('\\', "\\\\").replace('"', "\\\""));
+            output.push_str("\",");
+        }
+        Value::Array(values) => {
+            for (i, value) in values.iter().enumerate() {
+                push_field(output, &std::format!("{key}_{i}"), value);
+            }
+        }
+        Value::Null | Value::Object(_) => {}
+    }
+}
+
+fn escape_key(key: &str, measurement: bool) -> String {
+    let mut escaped = String::with_capacity(key.len());
+    for c in key.chars() {
+        if c == ',' || c == ' ' || (!measurement && c == '=') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn post(
+    address: &SocketAddr,
+    host: &str,
+    path: &str,
+    token: Option<&str>,
+    body: &str,
+) -> std::io::Result<()> {
+    let mut stream = TcpStream::connect_timeout(address, HTTP_TIMEOUT)?;
+    stream.set_read_timeout(Some(HTTP_TIMEOUT))?;
+    stream.set_write_timeout(Some(HTTP_TIMEOUT))?;
+
+    let authorization = token
+        .map(|token| std::format!("Authorization: Token {token}\r\n"))
+        .unwrap_or_default();
+    let request = std::format!(
+        "POST {path} HTTP/1.1\r\nHost: {host}\r\n{authorization}Content-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(request.as_bytes())?;
+
+    let mut status = [0; 12];
+    stream.read_exact(&mut status)?;
+    // The status line starts with "HTTP/1.1 2xx" on success
+    if status[9] != b'2' {
+        return Err(std::io::Error::other(std::format!(
+            "InfluxDB responded with: {}",
+            String::from_utf8_lossy(&status[9..])
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Serialize)]
+    struct TestLogData {
+        current_state: String,
+        scalar: f64,
+        vector: [[f64; 2]; 1],
+        flag: bool,
+        missing: Option<f64>,
+    }
+
+    fn log_data() -> TestLogData {
+        TestLogData {
+            current_state: "main_state".to_string(),
+            scalar: 1.5,
+            vector: [[2.0, -3.0]],
+            flag: true,
+            missing: None,
+        }
+    }
+
+    #[test]
+    fn test_line_protocol_formatting() {
+        let mut output = String::new();
+        format_line_protocol(
+            "pictorus,app=my\\ app",
+            &log_data(),
+            Duration::from_millis(1_500),
+            &mut output,
+        );
+        assert_eq!(
+            output,
+            "pictorus,app=my\\ app current_state=\"main_state\",scalar=1.5,vector_0_0=2,vector_0_1=-3,flag=true 1500000000\n"
+        );
+    }
+
+    #[test]
+    fn test_influx_logger_batches_over_udp() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        receiver
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        let config = InfluxConfig {
+            transport: InfluxTransport::Udp {
+                address: receiver.local_addr().unwrap().to_string(),
+            },
+            measurement: "pictorus".to_string(),
+            tags: BTreeMap::from([("app".to_string(), "test".to_string())]),
+            batch_size: 2,
+        };
+        let mut logger = InfluxLogger::new(Duration::from_millis(100), config);
+
+        logger.log(&log_data(), Duration::ZERO);
+        assert!(!logger.should_log(Duration::from_millis(50)));
+        logger.log(&log_data(), Duration::from_millis(100));
+
+        let mut buffer = [0; 1024];
+        let len = receiver.recv(&mut buffer).unwrap();
+        let batch = core::str::from_utf8(&buffer[..len]).unwrap();
+        assert_eq!(batch.lines().count(), 2);
+        assert!(
+            batch
+                .lines()
+                .all(|line| line.starts_with("pictorus,app=test "))
+        );
+
+        // Synchronized timestamps replace the app start epoch based ones
+        let timestamp = SyncedTimestamp {
+            unix_time: Duration::from_secs(1_700_000_000),
+            source: super::super::TimeSource::Ntp,
+        };
+        logger.log_timestamped(&log_data(), Duration::from_millis(200), Some(timestamp));
+        logger.flush();
+        let len = receiver.recv(&mut buffer).unwrap();
+        let batch = core::str::from_utf8(&buffer[..len]).unwrap();
+        assert!(batch.ends_with(" 1700000000000000000\n"));
+    }
+
+    #[test]
+    fn test_influx_logger_doesnt_block_on_stalled_server() {
+        // Accepts connections into its backlog but never responds
+        let server = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let config = InfluxConfig {
+            transport: InfluxTransport::Http {
+                address: server.local_addr().unwrap().to_string(),
+                path: "/write".to_string(),
+                token: None,
+            },
+            measurement: "pictorus".to_string(),
+            tags: BTreeMap::new(),
+            batch_size: 1,
+        };
+        let mut logger = InfluxLogger::new(Duration::from_millis(1), config);
+
+        let start = std::time::Instant::now();
+        for tick in 0..20 {
+            logger.log(&log_data(), Duration::from_millis(tick));
+        }
+        // Batches beyond the queue are dropped rather than waiting for the server
+        assert!(start.elapsed() < HTTP_TIMEOUT / 2);
+
+        // Refuse further connections, so dropping the logger doesn't wait on the queued batches
+        drop(server);
+    }
+
+    #[test]
+    fn test_influx_logger_unresolvable_address() {
+        let config = InfluxConfig {
+            transport: InfluxTransport::Udp {
+                address: "not an address".to_string(),
+            },
+            measurement: "pictorus".to_string(),
+            tags: BTreeMap::new(),
+            batch_size: 1,
+        };
+        let mut logger = InfluxLogger::new(Duration::from_millis(100), config);
+        assert!(logger.sender.is_none());
+        logger.log(&log_data(), Duration::ZERO);
+        assert!(logger.batch.is_empty());
+    }
+
+    #[test]
+    fn test_influx_config_deserialization() {
+        let config: InfluxConfig = serde_json::from_str(
+            r#"{
+                "transport": {"type": "http", "address": "localhost:8086", "path": "/write?db=test"},
+                "measurement": "pictorus",
+                "tags": {"device": "rover"},
+                "batch_size": 10
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.transport,
+            InfluxTransport::Http {
+                address: "localhost:8086".to_string(),
+                path: "/write?db=test".to_string(),
+                token: None,
+            }
+        );
+        assert_eq!(config.tags["device"], "rover");
+    }
+}
@@ -3,22 +3,120 @@ use serde::Serialize;
 #[cfg(feature = "std")]
 pub mod csv_logger;
 
+#[cfg(feature = "std")]
+pub mod influx_logger;
+
+#[cfg(feature = "std")]
+pub mod prometheus_logger;
+
 #[cfg(feature = "std")]
 pub mod std_logger;
 
+pub mod timestamped_logger;
+
+pub mod triggered_logger;
+
 #[cfg(feature = "std")]
 pub mod udp_logger;
 
 #[cfg(feature = "rtt")]
 pub mod rtt_logger;
 
+/// Clock a [`SyncedTimestamp`] was read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeSource {
+    /// Disciplined by the Precision Time Protocol, e.g. a PTP hardware clock or a system clock
+    /// synchronized to one
+    Ptp,
+    /// Disciplined by the Network Time Protocol
+    Ntp,
+    /// Disciplined by GNSS time and its pulse per second
+    Gnss,
+    /// Captured by a hardware timestamping unit
+    Hardware,
+}
+
+impl TimeSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TimeSource::Ptp => "ptp",
+            TimeSource::Ntp => "ntp",
+            TimeSource::Gnss => "gnss",
+            TimeSource::Hardware => "hardware",
+        }
+    }
+}
+
+/// Time of a sample on a clock synchronized between devices, so logs from several nodes can be
+/// lined up. App time only orders the samples of one app, since each app starts its own clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SyncedTimestamp {
+    /// Time since the Unix epoch
+    pub unix_time: Duration,
+    pub source: TimeSource,
+}
+
+/// Provides the synchronized timestamps for log records
+pub trait TimestampProvider {
+    /// Current synchronized time, or None while the clock isn't synchronized
+    fn timestamp(&mut self) -> Option<SyncedTimestamp>;
+}
+
+/// Timestamps records with the system clock, for hosts whose system clock is disciplined by an
+/// NTP daemon (e.g. chrony) or by PTP (e.g. ptp4l and phc2sys). Whether the clock is actually
+/// synchronized isn't checked, so the source is as configured.
+#[cfg(feature = "std")]
+pub struct SystemClockTimestamps {
+    pub source: TimeSource,
+}
+
+#[cfg(feature = "std")]
+impl TimestampProvider for SystemClockTimestamps {
+    fn timestamp(&mut self) -> Option<SyncedTimestamp> {
+        let unix_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?;
+        Some(SyncedTimestamp {
+            unix_time,
+            source: self.source,
+        })
+    }
+}
+
+/// Telemetry payload of a sample: the log data, followed by its synchronized timestamp when it
+/// has one. Postcard decoders that only read the log data ignore the trailing timestamp, so
+/// receivers that don't know about it can still read the telemetry.
+pub struct TelemetrySample<'a, D: Serialize> {
+    pub data: &'a D,
+    pub timestamp: Option<SyncedTimestamp>,
+}
+
+impl<D: Serialize> Serialize for TelemetrySample<'_, D> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.timestamp {
+            Some(timestamp) => (self.data, timestamp).serialize(serializer),
+            None => self.data.serialize(serializer),
+        }
+    }
+}
+
 /// The Logger trait is used to log data to a file or transmit via telemetry.
 ///
 /// Current implementations:
 ///
 /// CsvLogger can be used to format and log CSV data to a file.
 /// UdpLogger can be used to format and transmit telemetry data over UDP.
+/// InfluxLogger can be used to ship data to InfluxDB in line protocol.
+/// PrometheusLogger can be used to expose signals as Prometheus metrics.
 /// RttLogger can be used to transmit telemetry data over RTT.
+///
+/// TriggeredLogger wraps another logger to only log around events of interest.
+/// TimestampedLogger wraps another logger to timestamp every sample from a [`TimestampProvider`].
+///
+/// CsvLogger and InfluxLogger also record the synchronized timestamps passed to
+/// `log_timestamped`, for correlating logs across devices, and UdpLogger and RttLogger append
+/// them to the telemetry (see [`TelemetrySample`]).
 pub trait Logger {
     /// Trait method to determine if the logger should log data based on the app's current elapsed
     /// time.
@@ -28,4 +126,16 @@ pub trait Logger {
     /// result in data being logged. Use `should_log` to see if the logger should log data before
     /// calling this function.
     fn log(&mut self, log_data: &impl Serialize, app_time: Duration);
+
+    /// Logs data like [`Logger::log`], with the time of the sample on a synchronized clock when
+    /// one is available. Loggers that can't carry the timestamp (e.g. because their wire format
+    /// is fixed) log the data alone, which is the default.
+    fn log_timestamped(
+        &mut self,
+        log_data: &impl Serialize,
+        app_time: Duration,
+        _timestamp: Option<SyncedTimestamp>,
+    ) {
+        self.log(log_data, app_time);
+    }
 }
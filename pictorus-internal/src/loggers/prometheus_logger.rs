@@ -0,0 +1,316 @@
+use core::fmt::Write as _;
+use core::time::Duration;
+use log::{info, warn};
+use serde_json::Value;
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    string::{String, ToString},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::JoinHandle,
+    vec::Vec,
+};
+
+use crate::utils::PictorusError;
+
+use super::Logger;
+
+const ERR_TYPE: &str = "PrometheusLogger";
+// How long the scrape thread waits between checks for new connections
+const ACCEPT_POLL: Duration = Duration::from_millis(20);
+// Scrapers that don't send a complete request within this time are disconnected
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The PrometheusLogger exposes signal values and runtime stats as Prometheus metrics.
+///
+/// Metrics are served in the Prometheus text format on `/metrics` of the configured port.
+/// Numeric and boolean signals are exported as the `pictorus_signal` gauge labeled by signal
+/// name, with matrix elements additionally labeled by their index. The current state is
+/// exported as `pictorus_state`, and `pictorus_app_time_seconds` and
+/// `pictorus_log_updates_total` can be used to detect stalled apps.
+pub struct PrometheusLogger {
+    metrics: Arc<Mutex<String>>,
+    publish_period: Duration,
+    last_publish_time: Option<Duration>,
+    signals: Vec<String>,
+    updates: u64,
+    local_addr: SocketAddr,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PrometheusLogger {
+    /// Creates a logger serving metrics on `port` (0 picks a free port), updated at most once per
+    /// `publish_period`. Only the listed `signals` are exported, or all of them if it is empty.
+    pub fn new(
+        publish_period: Duration,
+        port: u16,
+        signals: &[&str],
+    ) -> Result<Self, PictorusError> {
+        let listener = TcpListener::bind(("0.0.0.0", port)).map_err(|err| {
+            PictorusError::from_io(
+                ERR_TYPE,
+                std::format!("Couldn't bind Prometheus exporter to port: {port} ({err})"),
+                &err,
+            )
+        })?;
+        let local_addr = listener.local_addr().map_err(|err| {
+            PictorusError::from_io(
+                ERR_TYPE,
+                std::format!("Couldn't read Prometheus exporter address ({err})"),
+                &err,
+            )
+        })?;
+        listener.set_nonblocking(true).map_err(|err| {
+            PictorusError::from_io(
+                ERR_TYPE,
+                std::format!("Failed to set nonblocking on Prometheus exporter ({err})"),
+                &err,
+            )
+        })?;
+        info!("Serving Prometheus metrics at http://{local_addr}/metrics");
+
+        let metrics = Arc::new(Mutex::new(String::new()));
+        let running = Arc::new(AtomicBool::new(true));
+        let thread = {
+            let metrics = metrics.clone();
+            let running = running.clone();
+            std::thread::spawn(move || serve(listener, &metrics, &running))
+        };
+
+        Ok(PrometheusLogger {
+            metrics,
+            publish_period,
+            last_publish_time: None,
+            signals: signals.iter().map(|s| s.to_string()).collect(),
+            updates: 0,
+            local_addr,
+            running,
+            thread: Some(thread),
+        })
+    }
+
+    /// The address metrics are served on, which is useful when binding to port 0
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    fn is_selected(&self, name: &str) -> bool {
+        self.signals.is_empty() || self.signals.iter().any(|s| s == name)
+    }
+
+    fn render(&self, log_data: &Value, app_time: Duration) -> String {
+        let mut out = String::new();
+        out.push_str("# HELP pictorus_signal Current value of an app signal\n");
+        out.push_str("# TYPE pictorus_signal gauge\n");
+        let mut state = None;
+        if let Value::Object(fields) = log_data {
+            for (name, value) in fields {
+                if name == "current_state" {
+                    state = value.as_str();
+                } else if name != "app_time" && self.is_selected(name) {
+                    write_signal(&mut out, name, None, value);
+                }
+            }
+        }
+
+        if let Some(state) = state {
+            out.push_str("# HELP pictorus_state Currently active state\n");
+            out.push_str("# TYPE pictorus_state gauge\n");
+            writeln!(out, "pictorus_state{{state=\"{}\"}} 1", escape_label(state)).ok();
+        }
+        out.push_str("# HELP pictorus_app_time_seconds App time of the latest update\n");
+        out.push_str("# TYPE pictorus_app_time_seconds gauge\n");
+        writeln!(out, "pictorus_app_time_seconds {}", app_time.as_secs_f64()).ok();
+        out.push_str("# HELP pictorus_log_updates_total Number of metric updates\n");
+        out.push_str("# TYPE pictorus_log_updates_total counter\n");
+        writeln!(out, "pictorus_log_updates_total {}", self.updates).ok();
+        out
+    }
+}
+
+impl Logger for PrometheusLogger {
+    fn should_log(&mut self, app_time: Duration) -> bool {
+        match self.last_publish_time {
+            None => true,
+            Some(last_publish) => (app_time - last_publish) >= self.publish_period,
+        }
+    }
+
+    fn log(&mut self, log_data: &impl serde::Serialize, app_time: Duration) {
+        let Ok(log_data) = serde_json::to_value(log_data) else {
+            warn!("Failed to serialize data for Prometheus metrics");
+            return;
+        };
+        self.updates += 1;
+        let rendered = self.render(&log_data, app_time);
+        if let Ok(mut metrics) = self.metrics.lock() {
+            *metrics = rendered;
+        }
+        self.last_publish_time = Some(app_time);
+    }
+}
+
+impl Drop for PrometheusLogger {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+fn write_signal(out: &mut String, name: &str, index: Option<&str>, value: &Value) {
+    match value {
+        Value::Number(number) => {
+            if let Some(number) = number.as_f64() {
+                write_sample(out, name, index, number);
+            }
+        }
+        Value::Bool(flag) => write_sample(out, name, index, if *flag { 1.0 } else { 0.0 }),
+        Value::Array(elements) => {
+            for (i, element) in elements.iter().enumerate() {
+                let index = match index {
+                    Some(outer) => std::format!("{outer}_{i}"),
+                    None => i.to_string(),
+                };
+                write_signal(out, name, Some(&index), element);
+            }
+        }
+        // Strings, nulls and nested objects have no meaningful gauge value
+        _ => {}
+    }
+}
+
+fn write_sample(out: &mut String, name: &str, index: Option<&str>, value: f64) {
+    let name = escape_label(name);
+    let value = format_value(value);
+    match index {
+        Some(index) => writeln!(
+            out,
+            "pictorus_signal{{name=\"{name}\",index=\"{index}\"}} {value}"
+        ),
+        None => writeln!(out, "pictorus_signal{{name=\"{name}\"}} {value}"),
+    }
+    .ok();
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".into()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.into()
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape_label(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn serve(listener: TcpListener, metrics: &Mutex<String>, running: &AtomicBool) {
+    while running.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if let Err(err) = handle_scrape(stream, metrics) {
+                    warn!("Failed to handle Prometheus scrape: {err}");
+                }
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(ACCEPT_POLL);
+            }
+            Err(err) => warn!("Failed to accept Prometheus connection: {err}"),
+        }
+    }
+}
+
+fn handle_scrape(mut stream: TcpStream, metrics: &Mutex<String>) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drain the headers, since closing with unread data resets the connection
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim_end().is_empty() {
+        header.clear();
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let path = path.split('?').next().unwrap_or_default();
+    let (status_line, body) = if path == "/metrics" {
+        let metrics = metrics
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        ("200 OK", metrics.clone())
+    } else {
+        (
+            "404 Not Found",
+            String::from("Metrics are served on /metrics\n"),
+        )
+    };
+    let response = std::format!(
+        "HTTP/1.1 {status_line}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes())?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+    use std::io::Read;
+
+    #[derive(Serialize)]
+    struct LogData {
+        app_time: f64,
+        current_state: String,
+        foo_block: f64,
+        bar_block: [[f64; 1]; 2],
+        baz_block: bool,
+    }
+
+    #[test]
+    fn test_prometheus_logger_serves_metrics() {
+        let log_data = LogData {
+            app_time: 1.5,
+            current_state: "main_state".to_string(),
+            foo_block: 2.5,
+            bar_block: [[1.0], [-3.0]],
+            baz_block: true,
+        };
+
+        let mut logger =
+            PrometheusLogger::new(Duration::from_millis(100), 0, &["foo_block", "bar_block"])
+                .unwrap();
+        assert!(logger.should_log(Duration::ZERO));
+        logger.log(&log_data, Duration::from_millis(1500));
+        assert!(!logger.should_log(Duration::from_millis(1550)));
+        assert!(logger.should_log(Duration::from_millis(1600)));
+
+        let mut stream = TcpStream::connect(logger.local_addr()).unwrap();
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: test\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("pictorus_signal{name=\"foo_block\"} 2.5\n"));
+        assert!(response.contains("pictorus_signal{name=\"bar_block\",index=\"0_0\"} 1\n"));
+        assert!(response.contains("pictorus_signal{name=\"bar_block\",index=\"1_0\"} -3\n"));
+        assert!(!response.contains("baz_block"));
+        assert!(response.contains("pictorus_state{state=\"main_state\"} 1\n"));
+        assert!(response.contains("pictorus_app_time_seconds 1.5\n"));
+        assert!(response.contains("pictorus_log_updates_total 1\n"));
+    }
+}
@@ -1,6 +1,6 @@
 use rtt_target::UpChannel;
 
-use super::Logger;
+use super::{Logger, SyncedTimestamp, TelemetrySample};
 use crate::encoders::postcard_encoder::PostcardEncoderCOBS;
 use core::time::Duration;
 
@@ -91,8 +91,21 @@ impl Logger for RttLogger {
     }
 
     fn log(&mut self, log_data: &impl serde::Serialize, app_time: Duration) {
+        self.log_timestamped(log_data, app_time, None);
+    }
+
+    fn log_timestamped(
+        &mut self,
+        log_data: &impl serde::Serialize,
+        app_time: Duration,
+        timestamp: Option<SyncedTimestamp>,
+    ) {
         if self.should_log(app_time) {
-            let encoded = self.encoder.encode::<RTT_DATA_BUFFER_SIZE>(log_data);
+            let sample = TelemetrySample {
+                data: log_data,
+                timestamp,
+            };
+            let encoded = self.encoder.encode::<RTT_DATA_BUFFER_SIZE>(&sample);
             self.data_channel.write(&encoded);
             self.last_broadcast_time = Some(app_time);
         }
@@ -2,7 +2,7 @@ use crate::loggers::Logger;
 use core::time::Duration;
 use std::path::PathBuf;
 
-use super::{csv_logger::CsvLogger, udp_logger::UdpLogger};
+use super::{SyncedTimestamp, csv_logger::CsvLogger, udp_logger::UdpLogger};
 
 /// StdLogger for Linux/Windows/MacOS systems that logs data via UDP telemetry using
 /// the device manager as well as a CSV file.
@@ -23,6 +23,20 @@ impl StdLogger {
             csv_logger: CsvLogger::new(csv_log_period, csv_output_path),
         }
     }
+
+    /// Adds the synchronized timestamps of samples to the CSV file. See
+    /// [`CsvLogger::with_synced_time`].
+    pub fn with_csv_synced_time(mut self) -> Self {
+        self.csv_logger = self.csv_logger.with_synced_time();
+        self
+    }
+
+    /// Encrypts UDP telemetry. See [`UdpLogger::with_encryption`].
+    #[cfg(feature = "encryption")]
+    pub fn with_udp_encryption(mut self, key: [u8; 32]) -> Self {
+        self.udp_logger = self.udp_logger.with_encryption(key);
+        self
+    }
 }
 
 impl Logger for StdLogger {
@@ -34,4 +48,16 @@ impl Logger for StdLogger {
         self.udp_logger.log(log_data, app_time);
         self.csv_logger.log(log_data, app_time);
     }
+
+    fn log_timestamped(
+        &mut self,
+        log_data: &impl serde::Serialize,
+        app_time: Duration,
+        timestamp: Option<SyncedTimestamp>,
+    ) {
+        self.udp_logger
+            .log_timestamped(log_data, app_time, timestamp);
+        self.csv_logger
+            .log_timestamped(log_data, app_time, timestamp);
+    }
 }
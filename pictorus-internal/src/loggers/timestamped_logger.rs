@@ -0,0 +1,121 @@
+use core::time::Duration;
+use serde::Serialize;
+
+use super::{Logger, SyncedTimestamp, TimestampProvider};
+
+/// TimestampedLogger reads the synchronized time from a [`TimestampProvider`] each time a sample
+/// is logged, and passes it on to another logger with `log_timestamped`. This lets the app log
+/// with plain `log` calls while its loggers record the synchronized time of every sample.
+pub struct TimestampedLogger<L: Logger, P: TimestampProvider> {
+    inner: L,
+    provider: P,
+}
+
+impl<L: Logger, P: TimestampProvider> TimestampedLogger<L, P> {
+    pub fn new(inner: L, provider: P) -> Self {
+        TimestampedLogger { inner, provider }
+    }
+
+    pub fn inner(&mut self) -> &mut L {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> L {
+        self.inner
+    }
+}
+
+impl<L: Logger, P: TimestampProvider> Logger for TimestampedLogger<L, P> {
+    fn should_log(&mut self, app_time: Duration) -> bool {
+        self.inner.should_log(app_time)
+    }
+
+    fn log(&mut self, log_data: &impl Serialize, app_time: Duration) {
+        let timestamp = self.provider.timestamp();
+        self.inner.log_timestamped(log_data, app_time, timestamp);
+    }
+
+    /// Samples that already have a timestamp keep it, and the others are timestamped from the
+    /// provider
+    fn log_timestamped(
+        &mut self,
+        log_data: &impl Serialize,
+        app_time: Duration,
+        timestamp: Option<SyncedTimestamp>,
+    ) {
+        let timestamp = timestamp.or_else(|| self.provider.timestamp());
+        self.inner.log_timestamped(log_data, app_time, timestamp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loggers::TimeSource;
+    use alloc::vec::Vec;
+
+    /// Records the timestamps of the samples it's given
+    #[derive(Default)]
+    struct TimestampRecorder {
+        timestamps: Vec<Option<SyncedTimestamp>>,
+    }
+
+    impl Logger for TimestampRecorder {
+        fn should_log(&mut self, _app_time: Duration) -> bool {
+            true
+        }
+
+        fn log(&mut self, log_data: &impl Serialize, app_time: Duration) {
+            self.log_timestamped(log_data, app_time, None);
+        }
+
+        fn log_timestamped(
+            &mut self,
+            _log_data: &impl Serialize,
+            _app_time: Duration,
+            timestamp: Option<SyncedTimestamp>,
+        ) {
+            self.timestamps.push(timestamp);
+        }
+    }
+
+    /// Synchronized after the first reading, then ticks by a second per reading
+    struct SteppingClock {
+        readings: u64,
+    }
+
+    impl TimestampProvider for SteppingClock {
+        fn timestamp(&mut self) -> Option<SyncedTimestamp> {
+            self.readings += 1;
+            (self.readings > 1).then(|| timestamp(self.readings))
+        }
+    }
+
+    fn timestamp(secs: u64) -> SyncedTimestamp {
+        SyncedTimestamp {
+            unix_time: Duration::from_secs(secs),
+            source: TimeSource::Ptp,
+        }
+    }
+
+    #[test]
+    fn test_timestamped_logger() {
+        let mut logger =
+            TimestampedLogger::new(TimestampRecorder::default(), SteppingClock { readings: 0 });
+
+        logger.log(&1.0, Duration::ZERO);
+        logger.log(&2.0, Duration::from_millis(10));
+        logger.log_timestamped(&3.0, Duration::from_millis(20), Some(timestamp(100)));
+        logger.log_timestamped(&4.0, Duration::from_millis(30), None);
+
+        assert_eq!(
+            logger.into_inner().timestamps,
+            [
+                None,
+                Some(timestamp(2)),
+                Some(timestamp(100)),
+                Some(timestamp(3))
+            ]
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use core::time::Duration;
+use heapless::Deque;
+use serde::Serialize;
+
+use super::{Logger, SyncedTimestamp};
+
+/// TriggeredLogger only passes samples on to another logger around events of interest, so
+/// high-rate logs aren't filled with data nobody looks at.
+///
+/// Recording is switched on and off with [`TriggeredLogger::set_recording`], typically from the
+/// output of a LogTriggerBlock, which keeps recording for a post trigger window after the event.
+/// While not recording, the last `N` samples within the pre trigger window are kept, and are
+/// logged ahead of the trigger sample once recording starts. Each held sample is a copy of the
+/// log data, so size `N` with the log data's size in mind.
+///
+/// With an idle period set, samples are also logged at that reduced rate while not recording, so
+/// the trigger boosts the log rate instead of enabling logging.
+///
+/// The wrapped logger's `should_log` still applies to every sample, so set its period to the rate
+/// wanted while recording.
+pub struct TriggeredLogger<L: Logger, D: Serialize + Clone, const N: usize> {
+    inner: L,
+    pre_trigger: Duration,
+    idle_period: Option<Duration>,
+    recording: bool,
+    history: Deque<(D, Duration, Option<SyncedTimestamp>), N>,
+    last_idle_log: Option<Duration>,
+    /// Time of the newest sample passed on, so held samples are never logged twice or out of
+    /// order
+    last_forwarded: Option<Duration>,
+}
+
+impl<L: Logger, D: Serialize + Clone, const N: usize> TriggeredLogger<L, D, N> {
+    pub fn new(inner: L, pre_trigger: Duration, idle_period: Option<Duration>) -> Self {
+        TriggeredLogger {
+            inner,
+            pre_trigger,
+            idle_period,
+            recording: false,
+            history: Deque::new(),
+            last_idle_log: None,
+            last_forwarded: None,
+        }
+    }
+
+    pub fn set_recording(&mut self, recording: bool) {
+        self.recording = recording;
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    pub fn inner(&mut self) -> &mut L {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> L {
+        self.inner
+    }
+
+    /// Logs the sample if recording or the idle period is due, otherwise holds it for the pre
+    /// trigger window
+    pub fn log(&mut self, log_data: &D, app_time: Duration) {
+        self.log_timestamped(log_data, app_time, None);
+    }
+
+    /// Logs the sample like [`TriggeredLogger::log`], keeping its synchronized timestamp with it
+    /// while it's held
+    pub fn log_timestamped(
+        &mut self,
+        log_data: &D,
+        app_time: Duration,
+        timestamp: Option<SyncedTimestamp>,
+    ) {
+        if self.recording {
+            while let Some((held_data, held_time, held_timestamp)) = self.history.pop_front() {
+                self.forward(&held_data, held_time, held_timestamp);
+            }
+            self.forward(log_data, app_time, timestamp);
+            return;
+        }
+
+        if let Some(idle_period) = self.idle_period {
+            let idle_due = self
+                .last_idle_log
+                .is_none_or(|last| app_time.saturating_sub(last) >= idle_period);
+            if idle_due {
+                self.last_idle_log = Some(app_time);
+                self.forward(log_data, app_time, timestamp);
+            }
+        }
+
+        while self
+            .history
+            .front()
+            .is_some_and(|(_, held_time, _)| app_time.saturating_sub(*held_time) > self.pre_trigger)
+        {
+            self.history.pop_front();
+        }
+        if self.history.is_full() {
+            self.history.pop_front();
+        }
+        // Can't fail, there's always room after dropping the oldest sample
+        self.history
+            .push_back((log_data.clone(), app_time, timestamp))
+            .ok();
+    }
+
+    fn forward(&mut self, log_data: &D, app_time: Duration, timestamp: Option<SyncedTimestamp>) {
+        if self.last_forwarded.is_some_and(|last| app_time <= last) {
+            return;
+        }
+        if self.inner.should_log(app_time) {
+            self.inner.log_timestamped(log_data, app_time, timestamp);
+            self.last_forwarded = Some(app_time);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    /// Records the times of the samples it's given
+    #[derive(Default)]
+    struct TimeLogger {
+        times: Vec<u64>,
+        timestamps: Vec<Option<SyncedTimestamp>>,
+    }
+
+    impl Logger for TimeLogger {
+        fn should_log(&mut self, _app_time: Duration) -> bool {
+            true
+        }
+
+        fn log(&mut self, _log_data: &impl Serialize, app_time: Duration) {
+            self.times.push(app_time.as_millis() as u64);
+        }
+
+        fn log_timestamped(
+            &mut self,
+            log_data: &impl Serialize,
+            app_time: Duration,
+            timestamp: Option<SyncedTimestamp>,
+        ) {
+            self.log(log_data, app_time);
+            self.timestamps.push(timestamp);
+        }
+    }
+
+    fn run(
+        logger: &mut TriggeredLogger<TimeLogger, f64, 4>,
+        ticks: core::ops::Range<u64>,
+        recording: bool,
+    ) {
+        logger.set_recording(recording);
+        for tick in ticks {
+            logger.log(&(tick as f64), Duration::from_millis(10 * tick));
+        }
+    }
+
+    #[test]
+    fn test_triggered_logger_pre_trigger_window() {
+        let mut logger = TriggeredLogger::<_, f64, 4>::new(
+            TimeLogger::default(),
+            Duration::from_millis(20),
+            None,
+        );
+        run(&mut logger, 0..10, false);
+        assert!(logger.inner().times.is_empty());
+
+        // The samples from the last 20 ms are logged ahead of the trigger sample
+        run(&mut logger, 10..13, true);
+        assert_eq!(logger.inner().times, [70, 80, 90, 100, 110, 120]);
+
+        run(&mut logger, 13..20, false);
+        run(&mut logger, 20..21, true);
+        assert_eq!(
+            logger.into_inner().times,
+            [70, 80, 90, 100, 110, 120, 170, 180, 190, 200]
+        );
+    }
+
+    #[test]
+    fn test_triggered_logger_history_capacity() {
+        let mut logger =
+            TriggeredLogger::<_, f64, 4>::new(TimeLogger::default(), Duration::from_secs(1), None);
+        run(&mut logger, 0..10, false);
+        run(&mut logger, 10..11, true);
+        assert_eq!(logger.inner().times, [60, 70, 80, 90, 100]);
+    }
+
+    #[test]
+    fn test_triggered_logger_idle_rate() {
+        let mut logger = TriggeredLogger::<_, f64, 4>::new(
+            TimeLogger::default(),
+            Duration::from_millis(30),
+            Some(Duration::from_millis(50)),
+        );
+        run(&mut logger, 0..8, false);
+        assert_eq!(logger.inner().times, [0, 50]);
+
+        // Held samples already logged at the idle rate aren't logged again
+        run(&mut logger, 8..10, true);
+        assert_eq!(logger.inner().times, [0, 50, 60, 70, 80, 90]);
+        assert!(logger.is_recording());
+    }
+
+    #[test]
+    fn test_triggered_logger_keeps_synced_timestamps() {
+        let mut logger =
+            TriggeredLogger::<_, f64, 4>::new(TimeLogger::default(), Duration::from_secs(1), None);
+        let timestamp = |ms: u64| SyncedTimestamp {
+            unix_time: Duration::from_millis(1_000 + ms),
+            source: super::super::TimeSource::Gnss,
+        };
+        logger.log_timestamped(&0.0, Duration::ZERO, Some(timestamp(0)));
+        logger.log(&1.0, Duration::from_millis(10));
+        logger.set_recording(true);
+        logger.log_timestamped(&2.0, Duration::from_millis(20), Some(timestamp(20)));
+        assert_eq!(logger.inner().times, [0, 10, 20]);
+        assert_eq!(
+            logger.inner().timestamps,
+            [Some(timestamp(0)), None, Some(timestamp(20))]
+        );
+    }
+}
@@ -5,12 +5,62 @@ use std::{
     string::{String, ToString},
 };
 
+#[cfg(feature = "encryption")]
+use chacha20poly1305::{AeadInPlace, ChaCha20Poly1305, Key, KeyInit, Nonce};
+#[cfg(feature = "encryption")]
+use std::vec::Vec;
+
 use crate::encoders::postcard_encoder::PostcardEncoderCOBS;
 
-use super::Logger;
+use super::{Logger, SyncedTimestamp, TelemetrySample};
 
 const UDP_ENCODER_BUFFER_SIZE: usize = 1024;
 
+/// Seals telemetry packets in the format read by `pictorus_blocks::DecryptBlock`: the nonce
+/// (4 byte prefix and 8 byte counter, big-endian), the ciphertext and the 16 byte tag.
+#[cfg(feature = "encryption")]
+struct TelemetryCipher {
+    cipher: ChaCha20Poly1305,
+    nonce_prefix: u32,
+    counter: u64,
+    packet: Vec<u8>,
+}
+
+#[cfg(feature = "encryption")]
+impl TelemetryCipher {
+    /// Draws a random nonce prefix and counter start, so the nonces of every launch (and of
+    /// every sender sharing the key) are distinct without any persistent state. The 32 bit
+    /// prefix and the 32 bit counter start give each launch 64 random bits.
+    fn new(key: [u8; 32]) -> Result<Self, getrandom::Error> {
+        let mut random = [0; 8];
+        getrandom::fill(&mut random)?;
+        let [p0, p1, p2, p3, c0, c1, c2, c3] = random;
+        Ok(Self {
+            cipher: ChaCha20Poly1305::new(Key::from_slice(&key)),
+            nonce_prefix: u32::from_be_bytes([p0, p1, p2, p3]),
+            counter: u64::from(u32::from_be_bytes([c0, c1, c2, c3])) << 32,
+            packet: Vec::new(),
+        })
+    }
+
+    fn seal(&mut self, payload: &[u8]) -> Option<&[u8]> {
+        self.counter = self.counter.checked_add(1)?;
+        let mut nonce = [0; 12];
+        nonce[..4].copy_from_slice(&self.nonce_prefix.to_be_bytes());
+        nonce[4..].copy_from_slice(&self.counter.to_be_bytes());
+
+        self.packet.clear();
+        self.packet.extend_from_slice(&nonce);
+        self.packet.extend_from_slice(payload);
+        let tag = self
+            .cipher
+            .encrypt_in_place_detached(Nonce::from_slice(&nonce), b"", &mut self.packet[12..])
+            .ok()?;
+        self.packet.extend_from_slice(tag.as_slice());
+        Some(&self.packet)
+    }
+}
+
 /// The UdpLogger is used to transmit data over the UDP protocol to the device manager.
 pub struct UdpLogger {
     pub file: Option<std::fs::File>,
@@ -20,6 +70,8 @@ pub struct UdpLogger {
     last_udp_publish_time: Option<Duration>,
     has_udp_connection: bool,
     encoder: PostcardEncoderCOBS,
+    #[cfg(feature = "encryption")]
+    cipher: Option<TelemetryCipher>,
 }
 
 // Wait this long to re-establish connection to telemetry manager before giving up
@@ -43,8 +95,28 @@ impl UdpLogger {
             last_udp_publish_time: None,
             has_udp_connection: true,
             encoder: PostcardEncoderCOBS {},
+            #[cfg(feature = "encryption")]
+            cipher: None,
         }
     }
+
+    /// Encrypts each telemetry packet with ChaCha20-Poly1305 so it can be decrypted by a
+    /// `DecryptBlock` holding the same key.
+    ///
+    /// Each launch draws a random nonce prefix, which the `DecryptBlock` treats as a new
+    /// session, so nonces aren't reused with the key across restarts. If the OS's random number
+    /// generator is unavailable, telemetry is disabled rather than sent unencrypted.
+    #[cfg(feature = "encryption")]
+    pub fn with_encryption(mut self, key: [u8; 32]) -> Self {
+        match TelemetryCipher::new(key) {
+            Ok(cipher) => self.cipher = Some(cipher),
+            Err(err) => {
+                warn!("Unable to seed telemetry encryption ({err}), disabling UDP telemetry");
+                self.socket = None;
+            }
+        }
+        self
+    }
 }
 
 impl Logger for UdpLogger {
@@ -57,15 +129,40 @@ impl Logger for UdpLogger {
     }
 
     fn log(&mut self, log_data: &impl serde::Serialize, app_time: Duration) {
+        self.log_timestamped(log_data, app_time, None);
+    }
+
+    fn log_timestamped(
+        &mut self,
+        log_data: &impl serde::Serialize,
+        app_time: Duration,
+        timestamp: Option<SyncedTimestamp>,
+    ) {
         if self.socket.is_some() && self.should_log(app_time) {
-            let encoded_data = self.encoder.encode::<UDP_ENCODER_BUFFER_SIZE>(log_data);
+            let sample = TelemetrySample {
+                data: log_data,
+                timestamp,
+            };
+            let encoded_data = self.encoder.encode::<UDP_ENCODER_BUFFER_SIZE>(&sample);
+            let payload: &[u8] = &encoded_data;
+            #[cfg(feature = "encryption")]
+            let payload = match &mut self.cipher {
+                Some(cipher) => match cipher.seal(payload) {
+                    Some(packet) => packet,
+                    None => {
+                        warn!("Failed to encrypt telemetry, skipping transmit...");
+                        return;
+                    }
+                },
+                None => payload,
+            };
 
             if let Some(socket) = &mut self.socket {
                 let time_since_last_udp_publish = match self.last_udp_publish_time {
                     Some(last_publish_time) => app_time - last_publish_time,
                     None => app_time,
                 };
-                match socket.send_to(&encoded_data, &self.publish_socket) {
+                match socket.send_to(payload, &self.publish_socket) {
                     Ok(_) => {
                         self.last_udp_publish_time = Some(app_time);
                         if !self.has_udp_connection {
@@ -122,4 +219,86 @@ mod tests {
         // Verify we can pass it samples to log without errors
         dl.log(&log_data, app_time);
     }
+
+    #[test]
+    fn test_udp_logger_appends_timestamp() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = receiver.local_addr().unwrap().to_string();
+        let mut dl = UdpLogger::new(Duration::from_millis(100), &address);
+        let log_data = LogData {
+            app_time: 1.0,
+            current_state: "test_state".to_string(),
+            foo_block: 0.0,
+            bar_block: 1.0,
+        };
+        let timestamp = SyncedTimestamp {
+            unix_time: Duration::from_millis(1_700_000_000_250),
+            source: crate::loggers::TimeSource::Ptp,
+        };
+
+        dl.log(&log_data, Duration::from_millis(100));
+        dl.log_timestamped(&log_data, Duration::from_millis(200), Some(timestamp));
+
+        let mut packet = [0; 256];
+        let len = receiver.recv(&mut packet).unwrap();
+        let expected = PostcardEncoderCOBS {}.encode::<UDP_ENCODER_BUFFER_SIZE>(&log_data);
+        assert_eq!(&packet[..len], expected.as_slice());
+        let len = receiver.recv(&mut packet).unwrap();
+        let expected =
+            PostcardEncoderCOBS {}.encode::<UDP_ENCODER_BUFFER_SIZE>(&(&log_data, timestamp));
+        assert_eq!(&packet[..len], expected.as_slice());
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_udp_logger_encryption() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = receiver.local_addr().unwrap().to_string();
+        let key = [7; 32];
+        let log_data = LogData {
+            app_time: 1.0,
+            current_state: "test_state".to_string(),
+            foo_block: 0.0,
+            bar_block: 1.0,
+        };
+        let expected = PostcardEncoderCOBS {}.encode::<UDP_ENCODER_BUFFER_SIZE>(&log_data);
+
+        // Receives a packet, checks it decrypts to the log data and returns its nonce
+        let receive = || {
+            let mut packet = [0; 256];
+            let len = receiver.recv(&mut packet).unwrap();
+            let (nonce, body) = packet[..len].split_at_mut(12);
+            let (ciphertext, tag) = body.split_at_mut(len - 12 - 16);
+            ChaCha20Poly1305::new(Key::from_slice(&key))
+                .decrypt_in_place_detached(
+                    Nonce::from_slice(nonce),
+                    b"",
+                    ciphertext,
+                    chacha20poly1305::Tag::from_slice(tag),
+                )
+                .unwrap();
+            assert_eq!(ciphertext, expected.as_slice());
+            let (prefix, counter) = nonce.split_at(4);
+            (
+                u32::from_be_bytes(prefix.try_into().unwrap()),
+                u64::from_be_bytes(counter.try_into().unwrap()),
+            )
+        };
+
+        let mut dl = UdpLogger::new(Duration::from_millis(100), &address).with_encryption(key);
+        dl.log(&log_data, Duration::from_millis(100));
+        dl.log(&log_data, Duration::from_millis(200));
+        let (prefix, counter) = receive();
+        assert_eq!(receive(), (prefix, counter + 1));
+
+        // A restarted sender draws new nonces rather than starting over from the same ones
+        let mut restarted =
+            UdpLogger::new(Duration::from_millis(100), &address).with_encryption(key);
+        restarted.log(&log_data, Duration::from_millis(100));
+        let (restarted_prefix, restarted_counter) = receive();
+        assert_ne!(
+            (restarted_prefix, restarted_counter >> 32),
+            (prefix, counter >> 32)
+        );
+    }
 }
@@ -0,0 +1,1070 @@
+//! SPI drivers for Semtech LoRa transceivers (SX127x and SX126x families).
+//!
+//! The chip drivers are generic over `embedded-hal` SPI buses and pins, so the same code runs on
+//! Linux (spidev) and on microcontrollers. [`LoRaConnection`] wraps a configured radio as an
+//! `InputBlock` that outputs received packets with their RSSI/SNR, and an `OutputBlock` that
+//! transmits its input. The radio stays in continuous receive mode except while transmitting.
+use embedded_hal::digital::{InputPin, OutputPin};
+use embedded_hal::spi::SpiBus;
+use heapless::Vec;
+use log::warn;
+use pictorus_traits::{ByteSliceSignal, Context, InputBlock, OutputBlock, PassBy};
+
+use crate::error::{ErrorKind, PictorusError};
+use crate::protocols::Flush;
+
+const ERR_TYPE: &str = "LoRaProtocol";
+/// Largest payload a LoRa packet can carry
+pub const MAX_PAYLOAD_BYTES: usize = 255;
+/// Crystal frequency shared by both chip families
+const XTAL_HZ: u64 = 32_000_000;
+
+/// Supported bandwidths in Hz, with their SX127x and SX126x register codes
+const BANDWIDTHS: [(u32, u8, u8); 10] = [
+    (7_800, 0x00, 0x00),
+    (10_400, 0x01, 0x08),
+    (15_600, 0x02, 0x01),
+    (20_800, 0x03, 0x09),
+    (31_250, 0x04, 0x02),
+    (41_700, 0x05, 0x0A),
+    (62_500, 0x06, 0x03),
+    (125_000, 0x07, 0x04),
+    (250_000, 0x08, 0x05),
+    (500_000, 0x09, 0x06),
+];
+
+/// Errors returned by the LoRa chip drivers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoRaError {
+    /// An SPI transfer failed
+    Spi,
+    /// Driving the chip select or reading the busy pin failed
+    Pin,
+    /// The SX126x busy pin never went low
+    Timeout,
+    /// The chip didn't report a known silicon version
+    UnknownDevice(u8),
+    /// The configuration can't be applied
+    InvalidConfig(&'static str),
+}
+
+impl From<LoRaError> for PictorusError {
+    fn from(err: LoRaError) -> Self {
+        match err {
+            LoRaError::Spi => PictorusError::new(ErrorKind::Io, ERR_TYPE, "SPI transfer failed"),
+            LoRaError::Pin => PictorusError::new(ErrorKind::Io, ERR_TYPE, "Failed to access pin"),
+            LoRaError::Timeout => {
+                PictorusError::new(ErrorKind::Timeout, ERR_TYPE, "Radio stayed busy")
+            }
+            LoRaError::UnknownDevice(_) => PictorusError::new(
+                ErrorKind::NotFound,
+                ERR_TYPE,
+                "No supported LoRa radio found",
+            ),
+            LoRaError::InvalidConfig(msg) => {
+                PictorusError::new(ErrorKind::InvalidConfig, ERR_TYPE, msg)
+            }
+        }
+    }
+}
+
+/// Radio settings applied when a connection is created
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoRaConfig {
+    pub frequency_hz: u32,
+    /// Spreading factor, 7 to 12
+    pub spreading_factor: u8,
+    /// One of the LoRa bandwidths between 7.8 kHz and 500 kHz
+    pub bandwidth_hz: u32,
+    /// Denominator of the 4/x coding rate, 5 to 8
+    pub coding_rate: u8,
+    pub tx_power_dbm: i8,
+    pub sync_word: u8,
+    pub preamble_len: u16,
+    pub crc: bool,
+}
+
+impl LoRaConfig {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        frequency_hz: f64,
+        spreading_factor: f64,
+        bandwidth_hz: f64,
+        coding_rate: f64,
+        tx_power_dbm: f64,
+        sync_word: f64,
+        preamble_len: f64,
+        crc: bool,
+    ) -> Self {
+        Self {
+            frequency_hz: frequency_hz as u32,
+            spreading_factor: spreading_factor as u8,
+            bandwidth_hz: bandwidth_hz as u32,
+            coding_rate: coding_rate as u8,
+            tx_power_dbm: tx_power_dbm as i8,
+            sync_word: sync_word as u8,
+            preamble_len: preamble_len as u16,
+            crc,
+        }
+    }
+
+    /// Checks that the settings are supported by both chip families
+    pub fn validate(&self) -> Result<(), LoRaError> {
+        if !(7..=12).contains(&self.spreading_factor) {
+            return Err(LoRaError::InvalidConfig(
+                "Spreading factor must be between 7 and 12",
+            ));
+        }
+        if !(5..=8).contains(&self.coding_rate) {
+            return Err(LoRaError::InvalidConfig(
+                "Coding rate must be between 5 and 8 (4/5 to 4/8)",
+            ));
+        }
+        self.bandwidth_codes()?;
+        Ok(())
+    }
+
+    /// Register codes for the bandwidth, allowing the nominal values to be rounded (e.g. 41.66 kHz)
+    fn bandwidth_codes(&self) -> Result<(u8, u8), LoRaError> {
+        BANDWIDTHS
+            .iter()
+            .find(|(hz, ..)| hz.abs_diff(self.bandwidth_hz) < 100)
+            .map(|&(_, sx127x, sx126x)| (sx127x, sx126x))
+            .ok_or(LoRaError::InvalidConfig("Unsupported LoRa bandwidth"))
+    }
+
+    /// Whether symbols are long enough (> 16 ms) to require low data rate optimization
+    fn low_data_rate_optimize(&self) -> bool {
+        (1u32 << self.spreading_factor) * 1000 / self.bandwidth_hz.max(1) > 16
+    }
+}
+
+/// Signal quality of a received packet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PacketStatus {
+    pub rssi_dbm: f64,
+    pub snr_db: f64,
+}
+
+/// Interrupts reported by [`LoRaRadio::poll`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoRaEvent {
+    None,
+    TxDone,
+    /// A packet was received and copied into the poll buffer
+    RxDone(PacketStatus),
+    /// A packet was received but failed its CRC check, so it was discarded
+    CrcError,
+}
+
+/// Operations shared by the supported LoRa chips
+pub trait LoRaRadio {
+    /// Puts the chip in LoRa mode and applies the config. Leaves the chip in standby.
+    fn configure(&mut self, config: &LoRaConfig) -> Result<(), LoRaError>;
+
+    /// Loads `payload` into the chip and starts transmitting it
+    fn transmit(&mut self, payload: &[u8]) -> Result<(), LoRaError>;
+
+    /// Enters continuous receive mode
+    fn start_receive(&mut self) -> Result<(), LoRaError>;
+
+    /// Reads and clears pending interrupts. Received packets are copied into `packet`.
+    fn poll(&mut self, packet: &mut Vec<u8, MAX_PAYLOAD_BYTES>) -> Result<LoRaEvent, LoRaError>;
+}
+
+/// Frequency register value for a chip with a PLL step of `XTAL_HZ / 2^shift`
+fn frequency_steps(frequency_hz: u32, shift: u32) -> u32 {
+    ((u64::from(frequency_hz) << shift) / XTAL_HZ) as u32
+}
+
+fn transaction<SPI: SpiBus, CS: OutputPin>(
+    spi: &mut SPI,
+    cs: &mut CS,
+    f: impl FnOnce(&mut SPI) -> Result<(), SPI::Error>,
+) -> Result<(), LoRaError> {
+    cs.set_low().map_err(|_| LoRaError::Pin)?;
+    let result = f(spi).and_then(|_| spi.flush());
+    cs.set_high().map_err(|_| LoRaError::Pin)?;
+    result.map_err(|_| LoRaError::Spi)
+}
+
+mod sx127x_reg {
+    pub const FIFO: u8 = 0x00;
+    pub const OP_MODE: u8 = 0x01;
+    pub const FRF_MSB: u8 = 0x06;
+    pub const PA_CONFIG: u8 = 0x09;
+    pub const FIFO_ADDR_PTR: u8 = 0x0D;
+    pub const FIFO_TX_BASE_ADDR: u8 = 0x0E;
+    pub const FIFO_RX_BASE_ADDR: u8 = 0x0F;
+    pub const FIFO_RX_CURRENT_ADDR: u8 = 0x10;
+    pub const IRQ_FLAGS: u8 = 0x12;
+    pub const RX_NB_BYTES: u8 = 0x13;
+    pub const PKT_SNR_VALUE: u8 = 0x19;
+    pub const PKT_RSSI_VALUE: u8 = 0x1A;
+    pub const MODEM_CONFIG_1: u8 = 0x1D;
+    pub const MODEM_CONFIG_2: u8 = 0x1E;
+    pub const PREAMBLE_MSB: u8 = 0x20;
+    pub const PAYLOAD_LENGTH: u8 = 0x22;
+    pub const MODEM_CONFIG_3: u8 = 0x26;
+    pub const SYNC_WORD: u8 = 0x39;
+    pub const VERSION: u8 = 0x42;
+    pub const PA_DAC: u8 = 0x4D;
+
+    pub const MODE_LONG_RANGE: u8 = 0x80;
+    pub const MODE_SLEEP: u8 = 0x00;
+    pub const MODE_STANDBY: u8 = 0x01;
+    pub const MODE_TX: u8 = 0x03;
+    pub const MODE_RX_CONTINUOUS: u8 = 0x05;
+
+    pub const IRQ_RX_DONE: u8 = 0x40;
+    pub const IRQ_PAYLOAD_CRC_ERROR: u8 = 0x20;
+    pub const IRQ_TX_DONE: u8 = 0x08;
+
+    pub const SILICON_VERSION: u8 = 0x12;
+}
+
+/// Driver for SX1276/77/78/79 (and RFM95/96/98) radios, using the PA_BOOST output
+pub struct Sx127x<SPI, CS> {
+    spi: SPI,
+    cs: CS,
+    frequency_hz: u32,
+}
+
+impl<SPI: SpiBus, CS: OutputPin> Sx127x<SPI, CS> {
+    pub fn new(spi: SPI, cs: CS) -> Self {
+        Self {
+            spi,
+            cs,
+            frequency_hz: 0,
+        }
+    }
+
+    fn write_registers(&mut self, address: u8, data: &[u8]) -> Result<(), LoRaError> {
+        transaction(&mut self.spi, &mut self.cs, |spi| {
+            spi.write(&[address | 0x80])?;
+            spi.write(data)
+        })
+    }
+
+    fn write_register(&mut self, address: u8, value: u8) -> Result<(), LoRaError> {
+        self.write_registers(address, &[value])
+    }
+
+    fn read_registers(&mut self, address: u8, data: &mut [u8]) -> Result<(), LoRaError> {
+        transaction(&mut self.spi, &mut self.cs, |spi| {
+            spi.write(&[address & 0x7F])?;
+            spi.read(data)
+        })
+    }
+
+    fn read_register(&mut self, address: u8) -> Result<u8, LoRaError> {
+        let mut value = [0];
+        self.read_registers(address, &mut value)?;
+        Ok(value[0])
+    }
+
+    fn set_mode(&mut self, mode: u8) -> Result<(), LoRaError> {
+        self.write_register(sx127x_reg::OP_MODE, sx127x_reg::MODE_LONG_RANGE | mode)
+    }
+}
+
+impl<SPI: SpiBus, CS: OutputPin> LoRaRadio for Sx127x<SPI, CS> {
+    fn configure(&mut self, config: &LoRaConfig) -> Result<(), LoRaError> {
+        use sx127x_reg::*;
+
+        config.validate()?;
+        let version = self.read_register(VERSION)?;
+        if version != SILICON_VERSION {
+            return Err(LoRaError::UnknownDevice(version));
+        }
+
+        // LoRa mode can only be selected while the chip is asleep
+        self.set_mode(MODE_SLEEP)?;
+        self.frequency_hz = config.frequency_hz;
+        let frf = frequency_steps(config.frequency_hz, 19).to_be_bytes();
+        self.write_registers(FRF_MSB, &frf[1..])?;
+        self.write_register(FIFO_TX_BASE_ADDR, 0)?;
+        self.write_register(FIFO_RX_BASE_ADDR, 0)?;
+
+        // PA_BOOST covers 2-17 dBm, and up to 20 dBm with the high power DAC enabled
+        let power = config.tx_power_dbm.clamp(2, 20);
+        if power > 17 {
+            self.write_register(PA_DAC, 0x87)?;
+            self.write_register(PA_CONFIG, 0x80 | (power - 5) as u8)?;
+        } else {
+            self.write_register(PA_DAC, 0x84)?;
+            self.write_register(PA_CONFIG, 0x80 | (power - 2) as u8)?;
+        }
+
+        let (bandwidth, _) = config.bandwidth_codes()?;
+        self.write_register(
+            MODEM_CONFIG_1,
+            (bandwidth << 4) | ((config.coding_rate - 4) << 1),
+        )?;
+        self.write_register(
+            MODEM_CONFIG_2,
+            (config.spreading_factor << 4) | (u8::from(config.crc) << 2),
+        )?;
+        // AGC on, plus low data rate optimization for long symbols
+        self.write_register(
+            MODEM_CONFIG_3,
+            (u8::from(config.low_data_rate_optimize()) << 3) | 0x04,
+        )?;
+        self.write_registers(PREAMBLE_MSB, &config.preamble_len.to_be_bytes())?;
+        self.write_register(SYNC_WORD, config.sync_word)?;
+
+        self.set_mode(MODE_STANDBY)
+    }
+
+    fn transmit(&mut self, payload: &[u8]) -> Result<(), LoRaError> {
+        use sx127x_reg::*;
+
+        let payload = &payload[..payload.len().min(MAX_PAYLOAD_BYTES)];
+        self.set_mode(MODE_STANDBY)?;
+        self.write_register(FIFO_ADDR_PTR, 0)?;
+        self.write_registers(FIFO, payload)?;
+        self.write_register(PAYLOAD_LENGTH, payload.len() as u8)?;
+        self.write_register(IRQ_FLAGS, 0xFF)?;
+        self.set_mode(MODE_TX)
+    }
+
+    fn start_receive(&mut self) -> Result<(), LoRaError> {
+        self.write_register(sx127x_reg::IRQ_FLAGS, 0xFF)?;
+        self.set_mode(sx127x_reg::MODE_RX_CONTINUOUS)
+    }
+
+    fn poll(&mut self, packet: &mut Vec<u8, MAX_PAYLOAD_BYTES>) -> Result<LoRaEvent, LoRaError> {
+        use sx127x_reg::*;
+
+        let flags = self.read_register(IRQ_FLAGS)?;
+        if flags == 0 {
+            return Ok(LoRaEvent::None);
+        }
+        self.write_register(IRQ_FLAGS, flags)?;
+
+        if flags & IRQ_RX_DONE != 0 {
+            if flags & IRQ_PAYLOAD_CRC_ERROR != 0 {
+                return Ok(LoRaEvent::CrcError);
+            }
+            let len = usize::from(self.read_register(RX_NB_BYTES)?);
+            let start = self.read_register(FIFO_RX_CURRENT_ADDR)?;
+            self.write_register(FIFO_ADDR_PTR, start)?;
+            packet.clear();
+            packet.resize(len, 0).ok();
+            self.read_registers(FIFO, packet)?;
+
+            let snr_db = f64::from(self.read_register(PKT_SNR_VALUE)? as i8) / 4.0;
+            let rssi = f64::from(self.read_register(PKT_RSSI_VALUE)?);
+            // The RSSI offset depends on which RF port (high or low frequency) is in use
+            let offset = if self.frequency_hz >= 779_000_000 {
+                -157.0
+            } else {
+                -164.0
+            };
+            let mut rssi_dbm = offset + rssi;
+            if snr_db < 0.0 {
+                rssi_dbm += snr_db;
+            }
+            return Ok(LoRaEvent::RxDone(PacketStatus { rssi_dbm, snr_db }));
+        }
+
+        if flags & IRQ_TX_DONE != 0 {
+            return Ok(LoRaEvent::TxDone);
+        }
+        Ok(LoRaEvent::None)
+    }
+}
+
+mod sx126x_cmd {
+    pub const WRITE_REGISTER: u8 = 0x0D;
+    pub const WRITE_BUFFER: u8 = 0x0E;
+    pub const READ_BUFFER: u8 = 0x1E;
+    pub const SET_DIO_IRQ_PARAMS: u8 = 0x08;
+    pub const GET_IRQ_STATUS: u8 = 0x12;
+    pub const CLEAR_IRQ_STATUS: u8 = 0x02;
+    pub const GET_RX_BUFFER_STATUS: u8 = 0x13;
+    pub const GET_PACKET_STATUS: u8 = 0x14;
+    pub const SET_STANDBY: u8 = 0x80;
+    pub const SET_RX: u8 = 0x82;
+    pub const SET_TX: u8 = 0x83;
+    pub const SET_RF_FREQUENCY: u8 = 0x86;
+    pub const SET_PACKET_TYPE: u8 = 0x8A;
+    pub const SET_MODULATION_PARAMS: u8 = 0x8B;
+    pub const SET_PACKET_PARAMS: u8 = 0x8C;
+    pub const SET_TX_PARAMS: u8 = 0x8E;
+    pub const SET_BUFFER_BASE_ADDRESS: u8 = 0x8F;
+    pub const SET_PA_CONFIG: u8 = 0x95;
+    pub const SET_DIO2_AS_RF_SWITCH: u8 = 0x9D;
+
+    pub const REG_SYNC_WORD: u16 = 0x0740;
+
+    pub const IRQ_TX_DONE: u16 = 0x0001;
+    pub const IRQ_RX_DONE: u16 = 0x0002;
+    pub const IRQ_CRC_ERROR: u16 = 0x0040;
+    pub const IRQ_TIMEOUT: u16 = 0x0200;
+}
+
+/// Number of times the busy pin is polled before a command gives up
+const SX126X_BUSY_POLLS: u32 = 100_000;
+
+/// Driver for SX1261/62/68 radios. The busy pin must be connected, and DIO2 is used to drive
+/// the RF switch as on most modules.
+pub struct Sx126x<SPI, CS, BUSY> {
+    spi: SPI,
+    cs: CS,
+    busy: BUSY,
+    preamble_len: u16,
+    crc: bool,
+}
+
+impl<SPI: SpiBus, CS: OutputPin, BUSY: InputPin> Sx126x<SPI, CS, BUSY> {
+    pub fn new(spi: SPI, cs: CS, busy: BUSY) -> Self {
+        Self {
+            spi,
+            cs,
+            busy,
+            preamble_len: 8,
+            crc: true,
+        }
+    }
+
+    fn wait_busy(&mut self) -> Result<(), LoRaError> {
+        for _ in 0..SX126X_BUSY_POLLS {
+            if self.busy.is_low().map_err(|_| LoRaError::Pin)? {
+                return Ok(());
+            }
+        }
+        Err(LoRaError::Timeout)
+    }
+
+    fn command(&mut self, opcode: u8, params: &[u8]) -> Result<(), LoRaError> {
+        self.wait_busy()?;
+        transaction(&mut self.spi, &mut self.cs, |spi| {
+            spi.write(&[opcode])?;
+            spi.write(params)
+        })
+    }
+
+    /// Issues a command that returns data. The status byte clocked out first is discarded.
+    fn read_command(
+        &mut self,
+        opcode: u8,
+        params: &[u8],
+        response: &mut [u8],
+    ) -> Result<(), LoRaError> {
+        self.wait_busy()?;
+        transaction(&mut self.spi, &mut self.cs, |spi| {
+            spi.write(&[opcode])?;
+            spi.write(params)?;
+            spi.read(&mut [0])?;
+            spi.read(response)
+        })
+    }
+
+    fn set_packet_params(&mut self, payload_len: u8) -> Result<(), LoRaError> {
+        let [preamble_msb, preamble_lsb] = self.preamble_len.to_be_bytes();
+        // Explicit header, standard IQ
+        self.command(
+            sx126x_cmd::SET_PACKET_PARAMS,
+            &[
+                preamble_msb,
+                preamble_lsb,
+                0x00,
+                payload_len,
+                u8::from(self.crc),
+                0x00,
+            ],
+        )
+    }
+}
+
+impl<SPI: SpiBus, CS: OutputPin, BUSY: InputPin> LoRaRadio for Sx126x<SPI, CS, BUSY> {
+    fn configure(&mut self, config: &LoRaConfig) -> Result<(), LoRaError> {
+        use sx126x_cmd::*;
+
+        config.validate()?;
+        self.preamble_len = config.preamble_len;
+        self.crc = config.crc;
+
+        // Standby on the RC oscillator, LoRa packet type
+        self.command(SET_STANDBY, &[0x00])?;
+        self.command(SET_PACKET_TYPE, &[0x01])?;
+        self.command(SET_DIO2_AS_RF_SWITCH, &[0x01])?;
+        self.command(
+            SET_RF_FREQUENCY,
+            &frequency_steps(config.frequency_hz, 25).to_be_bytes(),
+        )?;
+
+        // High power PA settings for up to +22 dBm with a 200 us ramp
+        self.command(SET_PA_CONFIG, &[0x04, 0x07, 0x00, 0x01])?;
+        let power = config.tx_power_dbm.clamp(-9, 22);
+        self.command(SET_TX_PARAMS, &[power as u8, 0x04])?;
+        self.command(SET_BUFFER_BASE_ADDRESS, &[0x00, 0x00])?;
+
+        let (_, bandwidth) = config.bandwidth_codes()?;
+        self.command(
+            SET_MODULATION_PARAMS,
+            &[
+                config.spreading_factor,
+                bandwidth,
+                config.coding_rate - 4,
+                u8::from(config.low_data_rate_optimize()),
+            ],
+        )?;
+        self.set_packet_params(MAX_PAYLOAD_BYTES as u8)?;
+
+        // Route the interrupts we care about to DIO1 so they latch in the IRQ status
+        let [mask_msb, mask_lsb] =
+            (IRQ_TX_DONE | IRQ_RX_DONE | IRQ_CRC_ERROR | IRQ_TIMEOUT).to_be_bytes();
+        self.command(
+            SET_DIO_IRQ_PARAMS,
+            &[mask_msb, mask_lsb, mask_msb, mask_lsb, 0, 0, 0, 0],
+        )?;
+
+        // The 8 bit sync word is spread across the two nibble-pairs of the SX126x register
+        let [reg_msb, reg_lsb] = REG_SYNC_WORD.to_be_bytes();
+        self.command(
+            WRITE_REGISTER,
+            &[
+                reg_msb,
+                reg_lsb,
+                (config.sync_word & 0xF0) | 0x04,
+                (config.sync_word << 4) | 0x04,
+            ],
+        )
+    }
+
+    fn transmit(&mut self, payload: &[u8]) -> Result<(), LoRaError> {
+        use sx126x_cmd::*;
+
+        let payload = &payload[..payload.len().min(MAX_PAYLOAD_BYTES)];
+        self.command(SET_STANDBY, &[0x00])?;
+        self.set_packet_params(payload.len() as u8)?;
+        self.wait_busy()?;
+        transaction(&mut self.spi, &mut self.cs, |spi| {
+            spi.write(&[WRITE_BUFFER, 0x00])?;
+            spi.write(payload)
+        })?;
+        self.command(CLEAR_IRQ_STATUS, &[0xFF, 0xFF])?;
+        // No timeout
+        self.command(SET_TX, &[0x00, 0x00, 0x00])
+    }
+
+    fn start_receive(&mut self) -> Result<(), LoRaError> {
+        use sx126x_cmd::*;
+
+        self.set_packet_params(MAX_PAYLOAD_BYTES as u8)?;
+        self.command(CLEAR_IRQ_STATUS, &[0xFF, 0xFF])?;
+        self.command(SET_RX, &[0xFF, 0xFF, 0xFF])
+    }
+
+    fn poll(&mut self, packet: &mut Vec<u8, MAX_PAYLOAD_BYTES>) -> Result<LoRaEvent, LoRaError> {
+        use sx126x_cmd::*;
+
+        let mut status = [0; 2];
+        self.read_command(GET_IRQ_STATUS, &[], &mut status)?;
+        let flags = u16::from_be_bytes(status);
+        if flags == 0 {
+            return Ok(LoRaEvent::None);
+        }
+        self.command(CLEAR_IRQ_STATUS, &status)?;
+
+        if flags & IRQ_RX_DONE != 0 {
+            if flags & IRQ_CRC_ERROR != 0 {
+                return Ok(LoRaEvent::CrcError);
+            }
+            let mut buffer_status = [0; 2];
+            self.read_command(GET_RX_BUFFER_STATUS, &[], &mut buffer_status)?;
+            let [len, start] = buffer_status;
+            packet.clear();
+            packet.resize(usize::from(len), 0).ok();
+            self.read_command(READ_BUFFER, &[start], packet)?;
+
+            let mut packet_status = [0; 3];
+            self.read_command(GET_PACKET_STATUS, &[], &mut packet_status)?;
+            return Ok(LoRaEvent::RxDone(PacketStatus {
+                rssi_dbm: -f64::from(packet_status[0]) / 2.0,
+                snr_db: f64::from(packet_status[1] as i8) / 4.0,
+            }));
+        }
+
+        if flags & IRQ_TX_DONE != 0 {
+            return Ok(LoRaEvent::TxDone);
+        }
+        Ok(LoRaEvent::None)
+    }
+}
+
+/// Parameters for the LoRa input and output blocks
+#[derive(Default)]
+pub struct LoRaParams;
+
+impl LoRaParams {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// A configured LoRa radio exposed as blocks.
+///
+/// The input block outputs the packet received this tick (empty if none), along with the RSSI
+/// (dBm) and SNR (dB) of the most recent packet. The output block transmits non-empty inputs.
+/// A transmission takes many ticks at low data rates; inputs arriving while the radio is still
+/// transmitting are dropped.
+pub struct LoRaConnection<R: LoRaRadio> {
+    radio: R,
+    packet: Vec<u8, MAX_PAYLOAD_BYTES>,
+    rssi_dbm: f64,
+    snr_db: f64,
+    is_cache_valid: bool,
+    transmitting: bool,
+}
+
+impl<R: LoRaRadio> LoRaConnection<R> {
+    pub fn new(mut radio: R, config: &LoRaConfig) -> Result<Self, PictorusError> {
+        radio.configure(config)?;
+        radio.start_receive()?;
+        Ok(Self {
+            radio,
+            packet: Vec::new(),
+            rssi_dbm: 0.0,
+            snr_db: 0.0,
+            is_cache_valid: false,
+            transmitting: false,
+        })
+    }
+
+    fn poll(&mut self) {
+        match self.radio.poll(&mut self.packet) {
+            Ok(LoRaEvent::RxDone(status)) => {
+                self.rssi_dbm = status.rssi_dbm;
+                self.snr_db = status.snr_db;
+            }
+            Ok(LoRaEvent::TxDone) => {
+                self.transmitting = false;
+                if let Err(err) = self.radio.start_receive() {
+                    warn!("Failed to resume LoRa receive: {err:?}");
+                }
+            }
+            Ok(LoRaEvent::CrcError) => warn!("Dropped LoRa packet with bad CRC"),
+            Ok(LoRaEvent::None) => {}
+            Err(err) => warn!("Failed to poll LoRa radio: {err:?}"),
+        }
+    }
+}
+
+impl<R: LoRaRadio> InputBlock for LoRaConnection<R> {
+    type Output = (ByteSliceSignal, f64, f64);
+    type Parameters = LoRaParams;
+
+    fn input(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        if !self.is_cache_valid {
+            self.is_cache_valid = true;
+            self.poll();
+        }
+
+        (&self.packet, self.rssi_dbm, self.snr_db)
+    }
+}
+
+impl<R: LoRaRadio> OutputBlock for LoRaConnection<R> {
+    type Inputs = ByteSliceSignal;
+    type Parameters = LoRaParams;
+
+    fn output(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) {
+        if inputs.is_empty() {
+            return;
+        }
+
+        // Nothing can be received mid-transmission, so polling here never drops a packet
+        if self.transmitting {
+            self.poll();
+        }
+        if self.transmitting {
+            warn!("LoRa radio is still transmitting, dropping payload");
+            return;
+        }
+
+        match self.radio.transmit(inputs) {
+            Ok(()) => self.transmitting = true,
+            Err(err) => warn!("Failed to start LoRa transmit: {err:?}"),
+        }
+    }
+}
+
+impl<R: LoRaRadio> Flush for LoRaConnection<R> {
+    fn flush(&mut self) {
+        self.packet.clear();
+        self.is_cache_valid = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec as StdVec;
+    use core::convert::Infallible;
+    use embedded_hal::digital::ErrorType as PinErrorType;
+    use embedded_hal::spi::ErrorType as SpiErrorType;
+
+    use crate::RuntimeContext;
+
+    struct NoopPin;
+
+    impl PinErrorType for NoopPin {
+        type Error = Infallible;
+    }
+
+    impl OutputPin for NoopPin {
+        fn set_low(&mut self) -> Result<(), Infallible> {
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Infallible> {
+            Ok(())
+        }
+    }
+
+    impl InputPin for NoopPin {
+        fn is_high(&mut self) -> Result<bool, Infallible> {
+            Ok(false)
+        }
+
+        fn is_low(&mut self) -> Result<bool, Infallible> {
+            Ok(true)
+        }
+    }
+
+    /// Register-level model of an SX127x. Transactions end on flush.
+    struct Sx127xMock {
+        registers: [u8; 128],
+        fifo: [u8; 256],
+        address: Option<u8>,
+    }
+
+    impl Sx127xMock {
+        fn new() -> Self {
+            let mut registers = [0; 128];
+            registers[sx127x_reg::VERSION as usize] = sx127x_reg::SILICON_VERSION;
+            Self {
+                registers,
+                fifo: [0; 256],
+                address: None,
+            }
+        }
+
+        fn next_address(&mut self) -> u8 {
+            let address = self.address.unwrap();
+            // The FIFO pointer advances, other registers auto-increment
+            if address & 0x7F == sx127x_reg::FIFO {
+                self.registers[sx127x_reg::FIFO_ADDR_PTR as usize] += 1;
+            } else {
+                self.address = Some(address + 1);
+            }
+            address
+        }
+
+        fn fifo_index(&self) -> usize {
+            usize::from(self.registers[sx127x_reg::FIFO_ADDR_PTR as usize])
+        }
+    }
+
+    impl SpiErrorType for Sx127xMock {
+        type Error = Infallible;
+    }
+
+    impl SpiBus for Sx127xMock {
+        fn read(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+            for word in words {
+                let index = self.fifo_index();
+                let address = self.next_address() & 0x7F;
+                *word = if address == sx127x_reg::FIFO {
+                    self.fifo[index]
+                } else {
+                    self.registers[usize::from(address)]
+                };
+            }
+            Ok(())
+        }
+
+        fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+            for &word in words {
+                if self.address.is_none() {
+                    self.address = Some(word);
+                    continue;
+                }
+                let index = self.fifo_index();
+                let address = self.next_address() & 0x7F;
+                if address == sx127x_reg::FIFO {
+                    self.fifo[index] = word;
+                } else if address == sx127x_reg::IRQ_FLAGS {
+                    // Flags are cleared by writing 1s
+                    self.registers[usize::from(address)] &= !word;
+                } else {
+                    self.registers[usize::from(address)] = word;
+                }
+            }
+            Ok(())
+        }
+
+        fn transfer(&mut self, _read: &mut [u8], _write: &[u8]) -> Result<(), Infallible> {
+            unimplemented!()
+        }
+
+        fn transfer_in_place(&mut self, _words: &mut [u8]) -> Result<(), Infallible> {
+            unimplemented!()
+        }
+
+        fn flush(&mut self) -> Result<(), Infallible> {
+            self.address = None;
+            Ok(())
+        }
+    }
+
+    /// Command-level model of an SX126x that records commands and replays canned responses
+    #[derive(Default)]
+    struct Sx126xMock {
+        commands: StdVec<StdVec<u8>>,
+        current: StdVec<u8>,
+        responses: StdVec<StdVec<u8>>,
+        status_read: bool,
+    }
+
+    impl SpiErrorType for Sx126xMock {
+        type Error = Infallible;
+    }
+
+    impl SpiBus for Sx126xMock {
+        fn read(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+            // The first byte read in a transaction is the status, reported as 0
+            if !self.status_read {
+                self.status_read = true;
+                words.fill(0);
+                return Ok(());
+            }
+            let response = self.responses.remove(0);
+            words.copy_from_slice(&response);
+            Ok(())
+        }
+
+        fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+            self.current.extend_from_slice(words);
+            Ok(())
+        }
+
+        fn transfer(&mut self, _read: &mut [u8], _write: &[u8]) -> Result<(), Infallible> {
+            unimplemented!()
+        }
+
+        fn transfer_in_place(&mut self, _words: &mut [u8]) -> Result<(), Infallible> {
+            unimplemented!()
+        }
+
+        fn flush(&mut self) -> Result<(), Infallible> {
+            let command = core::mem::take(&mut self.current);
+            self.commands.push(command);
+            self.status_read = false;
+            Ok(())
+        }
+    }
+
+    fn config() -> LoRaConfig {
+        LoRaConfig::new(915e6, 9.0, 125e3, 5.0, 14.0, 0x12 as f64, 8.0, true)
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert_eq!(config().validate(), Ok(()));
+        // Nominal bandwidths that aren't whole numbers of Hz are accepted
+        let mut rounded = config();
+        rounded.bandwidth_hz = 41_670;
+        assert_eq!(rounded.bandwidth_codes(), Ok((0x05, 0x0A)));
+
+        let mut bad = config();
+        bad.spreading_factor = 13;
+        assert!(bad.validate().is_err());
+        let mut bad = config();
+        bad.bandwidth_hz = 100_000;
+        assert!(bad.validate().is_err());
+        let mut bad = config();
+        bad.coding_rate = 4;
+        assert!(bad.validate().is_err());
+
+        assert!(!config().low_data_rate_optimize());
+        let mut slow = config();
+        slow.spreading_factor = 12;
+        assert!(slow.low_data_rate_optimize());
+    }
+
+    #[test]
+    fn test_sx127x_configure() {
+        let mut radio = Sx127x::new(Sx127xMock::new(), NoopPin);
+        radio.configure(&config()).unwrap();
+
+        let registers = &radio.spi.registers;
+        // 915 MHz / 61.035 Hz steps
+        assert_eq!(registers[0x06..0x09], [0xE4, 0xC0, 0x00]);
+        assert_eq!(registers[sx127x_reg::PA_CONFIG as usize], 0x80 | 12);
+        assert_eq!(registers[sx127x_reg::MODEM_CONFIG_1 as usize], 0x72);
+        assert_eq!(registers[sx127x_reg::MODEM_CONFIG_2 as usize], 0x94);
+        assert_eq!(registers[sx127x_reg::MODEM_CONFIG_3 as usize], 0x04);
+        assert_eq!(registers[sx127x_reg::PREAMBLE_MSB as usize..0x22], [0, 8]);
+        assert_eq!(registers[sx127x_reg::SYNC_WORD as usize], 0x12);
+        assert_eq!(registers[sx127x_reg::OP_MODE as usize], 0x81);
+    }
+
+    #[test]
+    fn test_sx127x_rejects_unknown_device() {
+        let mut mock = Sx127xMock::new();
+        mock.registers[sx127x_reg::VERSION as usize] = 0x00;
+        let mut radio = Sx127x::new(mock, NoopPin);
+        assert_eq!(
+            radio.configure(&config()),
+            Err(LoRaError::UnknownDevice(0x00))
+        );
+    }
+
+    #[test]
+    fn test_sx127x_transmit_and_receive() {
+        let mut radio = Sx127x::new(Sx127xMock::new(), NoopPin);
+        radio.configure(&config()).unwrap();
+
+        radio.transmit(b"hello").unwrap();
+        assert_eq!(&radio.spi.fifo[..5], b"hello");
+        assert_eq!(radio.spi.registers[sx127x_reg::PAYLOAD_LENGTH as usize], 5);
+        assert_eq!(radio.spi.registers[sx127x_reg::OP_MODE as usize], 0x83);
+
+        let mut packet = Vec::new();
+        radio.spi.registers[sx127x_reg::IRQ_FLAGS as usize] = sx127x_reg::IRQ_TX_DONE;
+        assert_eq!(radio.poll(&mut packet), Ok(LoRaEvent::TxDone));
+
+        // A packet landed at FIFO offset 16 with -3 dB SNR
+        radio.spi.fifo[16..19].copy_from_slice(b"abc");
+        let registers = &mut radio.spi.registers;
+        registers[sx127x_reg::IRQ_FLAGS as usize] = sx127x_reg::IRQ_RX_DONE;
+        registers[sx127x_reg::RX_NB_BYTES as usize] = 3;
+        registers[sx127x_reg::FIFO_RX_CURRENT_ADDR as usize] = 16;
+        registers[sx127x_reg::PKT_SNR_VALUE as usize] = (-12i8) as u8;
+        registers[sx127x_reg::PKT_RSSI_VALUE as usize] = 60;
+        assert_eq!(
+            radio.poll(&mut packet),
+            Ok(LoRaEvent::RxDone(PacketStatus {
+                rssi_dbm: -157.0 + 60.0 - 3.0,
+                snr_db: -3.0,
+            }))
+        );
+        assert_eq!(packet.as_slice(), b"abc");
+
+        radio.spi.registers[sx127x_reg::IRQ_FLAGS as usize] =
+            sx127x_reg::IRQ_RX_DONE | sx127x_reg::IRQ_PAYLOAD_CRC_ERROR;
+        assert_eq!(radio.poll(&mut packet), Ok(LoRaEvent::CrcError));
+    }
+
+    #[test]
+    fn test_sx126x_configure() {
+        let mut radio = Sx126x::new(Sx126xMock::default(), NoopPin, NoopPin);
+        radio.configure(&config()).unwrap();
+
+        let commands = &radio.spi.commands;
+        assert!(commands.contains(&vec![sx126x_cmd::SET_PACKET_TYPE, 0x01]));
+        // 915 MHz / 0.954 Hz steps
+        assert!(commands.contains(&vec![sx126x_cmd::SET_RF_FREQUENCY, 0x39, 0x30, 0x00, 0x00]));
+        assert!(commands.contains(&vec![sx126x_cmd::SET_TX_PARAMS, 14, 0x04]));
+        assert!(commands.contains(&vec![sx126x_cmd::SET_MODULATION_PARAMS, 9, 0x04, 1, 0]));
+        assert!(commands.contains(&vec![sx126x_cmd::SET_PACKET_PARAMS, 0, 8, 0, 255, 1, 0]));
+        assert!(commands.contains(&vec![sx126x_cmd::WRITE_REGISTER, 0x07, 0x40, 0x14, 0x24]));
+    }
+
+    #[test]
+    fn test_sx126x_receive() {
+        let mut radio = Sx126x::new(Sx126xMock::default(), NoopPin, NoopPin);
+        radio.spi.responses = vec![
+            vec![0x00, 0x02],
+            vec![4, 32],
+            b"ping".to_vec(),
+            vec![100, (-8i8) as u8, 90],
+        ];
+
+        let mut packet = Vec::new();
+        assert_eq!(
+            radio.poll(&mut packet),
+            Ok(LoRaEvent::RxDone(PacketStatus {
+                rssi_dbm: -50.0,
+                snr_db: -2.0,
+            }))
+        );
+        assert_eq!(packet.as_slice(), b"ping");
+        assert!(
+            radio
+                .spi
+                .commands
+                .contains(&vec![sx126x_cmd::CLEAR_IRQ_STATUS, 0x00, 0x02])
+        );
+        assert!(
+            radio
+                .spi
+                .commands
+                .contains(&vec![sx126x_cmd::READ_BUFFER, 32])
+        );
+    }
+
+    #[test]
+    fn test_lora_connection_blocks() {
+        let context = RuntimeContext::new(10_000);
+        let params = LoRaParams::new();
+        let mut connection =
+            LoRaConnection::new(Sx127x::new(Sx127xMock::new(), NoopPin), &config()).unwrap();
+        assert_eq!(
+            connection.radio.spi.registers[sx127x_reg::OP_MODE as usize],
+            0x85
+        );
+
+        let (packet, ..) = connection.input(&params, &context);
+        assert_eq!(packet, b"");
+        connection.flush();
+
+        connection.output(&params, &context, b"first");
+        assert!(connection.transmitting);
+        // Still transmitting, so the next payload is dropped
+        connection.output(&params, &context, b"second");
+        assert_eq!(&connection.radio.spi.fifo[..5], b"first");
+
+        // Once the transmission completes the radio goes back to receiving
+        connection.radio.spi.registers[sx127x_reg::IRQ_FLAGS as usize] = sx127x_reg::IRQ_TX_DONE;
+        connection.input(&params, &context);
+        assert!(!connection.transmitting);
+        assert_eq!(
+            connection.radio.spi.registers[sx127x_reg::OP_MODE as usize],
+            0x85
+        );
+        connection.flush();
+
+        connection.radio.spi.fifo[..2].copy_from_slice(b"hi");
+        let registers = &mut connection.radio.spi.registers;
+        registers[sx127x_reg::IRQ_FLAGS as usize] = sx127x_reg::IRQ_RX_DONE;
+        registers[sx127x_reg::RX_NB_BYTES as usize] = 2;
+        registers[sx127x_reg::FIFO_RX_CURRENT_ADDR as usize] = 0;
+        registers[sx127x_reg::PKT_SNR_VALUE as usize] = 40;
+        registers[sx127x_reg::PKT_RSSI_VALUE as usize] = 100;
+        let (packet, rssi, snr) = connection.input(&params, &context);
+        assert_eq!(packet, b"hi");
+        assert_eq!(rssi, -57.0);
+        assert_eq!(snr, 10.0);
+
+        // The packet is only output for one tick, while the signal quality is held
+        connection.flush();
+        let (packet, rssi, _) = connection.input(&params, &context);
+        assert_eq!(packet, b"");
+        assert_eq!(rssi, -57.0);
+    }
+}
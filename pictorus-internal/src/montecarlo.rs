@@ -0,0 +1,480 @@
+//! Monte Carlo campaigns: run a model many times with randomly perturbed parameters to see how
+//! much its outputs spread.
+//!
+//! A [`Campaign`] samples every varied parameter from its [`Distribution`] once per run, overlays
+//! the samples on the base [`DiagramParams`] and hands them to the model. These are the same
+//! parameters a generated app loads from diagram_params.json, so models pick them up through
+//! [`load_param`](crate::utils::load_param) without changes. The model returns named scalar
+//! outputs (final values, peak errors, settling times...), which are collected into one CSV row
+//! per run and summarized per output.
+//!
+//! Runs execute in parallel on the rayon thread pool. Parameters are sampled up front on one
+//! thread, and each run gets its own [`RunSeed`] for any randomness inside the model, so a
+//! campaign gives the same results for the same seed however many threads it runs on.
+use std::format;
+use std::io::{self, Write};
+use std::string::{String, ToString};
+use std::vec;
+use std::vec::Vec;
+
+use log::info;
+use rayon::prelude::*;
+
+use crate::error::{ErrorKind, PictorusError};
+use crate::rand::{Rng, RunSeed};
+use crate::utils::DiagramParams;
+
+/// Stream of the campaign seed that parameters are sampled from. Run seeds use streams `0..runs`.
+const SAMPLING_STREAM: u64 = u64::MAX;
+
+/// How a varied parameter is distributed across runs
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Distribution {
+    /// Uniform in `[min, max)`
+    Uniform {
+        min: f64,
+        max: f64,
+    },
+    Normal {
+        mean: f64,
+        std_dev: f64,
+    },
+}
+
+impl Distribution {
+    fn validate(&self) -> Result<(), &'static str> {
+        match *self {
+            Distribution::Uniform { min, max }
+                if !min.is_finite() || !max.is_finite() || min > max =>
+            {
+                Err("uniform distribution needs finite bounds with min <= max")
+            }
+            Distribution::Normal { mean, std_dev }
+                if !mean.is_finite() || !std_dev.is_finite() || std_dev < 0.0 =>
+            {
+                Err("normal distribution needs a finite mean and non-negative standard deviation")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn sample(&self, rng: &mut Rng) -> f64 {
+        match *self {
+            Distribution::Uniform { min, max } => min + (max - min) * rng.next_f64(),
+            Distribution::Normal { mean, std_dev } => {
+                // Box-Muller. 1 - u keeps the log argument in (0, 1].
+                let u1 = 1.0 - rng.next_f64();
+                let u2 = rng.next_f64();
+                let z = (-2.0 * u1.ln()).sqrt() * (core::f64::consts::TAU * u2).cos();
+                mean + std_dev * z
+            }
+        }
+    }
+}
+
+/// A parameter varied by the campaign, identified like in [`DiagramParams`]
+#[derive(Clone, Debug)]
+struct VariedParam {
+    block_name: String,
+    var_name: String,
+    distribution: Distribution,
+}
+
+impl VariedParam {
+    /// Column name in the results
+    fn column(&self) -> String {
+        format!("{}.{}", self.block_name, self.var_name)
+    }
+}
+
+/// What a model is given for one run
+#[derive(Clone, Debug)]
+pub struct RunConfig {
+    /// Index of the run in the campaign, from 0
+    pub index: usize,
+    /// Seed for any random number streams inside the model
+    pub seed: RunSeed,
+    /// The base parameters with this run's samples applied
+    pub params: DiagramParams,
+}
+
+/// Named scalar outputs of one run
+pub type RunOutputs = Vec<(String, f64)>;
+
+/// Configures and runs a Monte Carlo campaign
+#[derive(Clone, Debug)]
+pub struct Campaign {
+    runs: usize,
+    seed: RunSeed,
+    base_params: DiagramParams,
+    varied: Vec<VariedParam>,
+}
+
+impl Campaign {
+    pub fn new(runs: usize, seed: RunSeed) -> Self {
+        Self {
+            runs,
+            seed,
+            base_params: DiagramParams::new(),
+            varied: Vec::new(),
+        }
+    }
+
+    /// Parameters shared by every run, e.g. from [`get_diagram_params`](crate::utils::get_diagram_params)
+    pub fn with_base_params(mut self, params: DiagramParams) -> Self {
+        self.base_params = params;
+        self
+    }
+
+    /// Samples `var_name` of `block_name` from `distribution` in every run
+    pub fn vary(mut self, block_name: &str, var_name: &str, distribution: Distribution) -> Self {
+        self.varied.push(VariedParam {
+            block_name: block_name.to_string(),
+            var_name: var_name.to_string(),
+            distribution,
+        });
+        self
+    }
+
+    /// Runs `model` once per run in parallel. Runs whose model returns an error are kept in the
+    /// results (and reported in the CSV) rather than aborting the campaign.
+    pub fn run<F>(&self, model: F) -> Result<CampaignResults, PictorusError>
+    where
+        F: Fn(&RunConfig) -> Result<RunOutputs, PictorusError> + Sync,
+    {
+        for param in &self.varied {
+            param.distribution.validate().map_err(|err| {
+                PictorusError::new(
+                    ErrorKind::InvalidConfig,
+                    "MonteCarlo",
+                    format!("{}: {err}", param.column()),
+                )
+            })?;
+        }
+
+        info!(
+            "Starting Monte Carlo campaign: {} runs, {} varied parameters, seed {}",
+            self.runs,
+            self.varied.len(),
+            self.seed.value()
+        );
+        let mut rng = self.seed.rng(SAMPLING_STREAM);
+        let samples: Vec<Vec<f64>> = (0..self.runs)
+            .map(|_| {
+                self.varied
+                    .iter()
+                    .map(|param| param.distribution.sample(&mut rng))
+                    .collect()
+            })
+            .collect();
+
+        let runs = samples
+            .into_par_iter()
+            .enumerate()
+            .map(|(index, samples)| {
+                let mut params = self.base_params.clone();
+                for (param, value) in self.varied.iter().zip(&samples) {
+                    params
+                        .entry(param.block_name.clone())
+                        .or_default()
+                        .insert(param.var_name.clone(), value.to_string());
+                }
+                let config = RunConfig {
+                    index,
+                    seed: RunSeed::new(self.seed.stream_seed(index as u64)),
+                    params,
+                };
+                let outputs = model(&config);
+                RunResult {
+                    index,
+                    seed: config.seed,
+                    samples,
+                    outputs,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let failed = runs.iter().filter(|run| run.outputs.is_err()).count();
+        info!(
+            "Monte Carlo campaign finished, {failed} of {} runs failed",
+            self.runs
+        );
+        Ok(CampaignResults {
+            parameters: self.varied.iter().map(VariedParam::column).collect(),
+            runs,
+        })
+    }
+}
+
+/// The sampled parameters and outputs of one run
+#[derive(Clone, Debug)]
+pub struct RunResult {
+    pub index: usize,
+    pub seed: RunSeed,
+    /// Sampled values, in the order the parameters were added to the campaign
+    pub samples: Vec<f64>,
+    pub outputs: Result<RunOutputs, PictorusError>,
+}
+
+/// Statistics of one output over the runs that produced it
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutputSummary {
+    pub name: String,
+    pub count: usize,
+    pub mean: f64,
+    /// Sample standard deviation, 0 for fewer than two runs
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct CampaignResults {
+    /// Varied parameters as `block.var`, in the order of [`RunResult::samples`]
+    pub parameters: Vec<String>,
+    /// Every run, ordered by index
+    pub runs: Vec<RunResult>,
+}
+
+impl CampaignResults {
+    /// Names of all outputs, in the order they first appear
+    pub fn output_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for run in &self.runs {
+            for (name, _) in run.outputs.iter().flatten() {
+                if !names.contains(&name.as_str()) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+    }
+
+    /// Values of output `name` from the runs that produced it, e.g. to plot a histogram
+    pub fn output_values(&self, name: &str) -> Vec<f64> {
+        self.runs
+            .iter()
+            .filter_map(|run| run.outputs.as_ref().ok())
+            .filter_map(|outputs| outputs.iter().find(|(n, _)| n == name))
+            .map(|(_, value)| *value)
+            .collect()
+    }
+
+    pub fn summary(&self) -> Vec<OutputSummary> {
+        self.output_names()
+            .into_iter()
+            .map(|name| {
+                let values = self.output_values(name);
+                let count = values.len();
+                let mean = values.iter().sum::<f64>() / count as f64;
+                let std_dev = if count > 1 {
+                    let sum_sq = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>();
+                    (sum_sq / (count - 1) as f64).sqrt()
+                } else {
+                    0.0
+                };
+                OutputSummary {
+                    name: name.to_string(),
+                    count,
+                    mean,
+                    std_dev,
+                    min: values.iter().copied().fold(f64::INFINITY, f64::min),
+                    max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                }
+            })
+            .collect()
+    }
+
+    /// Writes one row per run: index, seed, sampled parameters, outputs and the error of failed
+    /// runs. Outputs a run didn't produce are left empty.
+    pub fn write_runs_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        let outputs = self.output_names();
+        let mut header = vec!["run".to_string(), "seed".to_string()];
+        header.extend(self.parameters.iter().cloned());
+        header.extend(outputs.iter().map(|name| name.to_string()));
+        header.push("error".to_string());
+        write_row(&mut writer, &header)?;
+
+        for run in &self.runs {
+            let mut row = vec![run.index.to_string(), run.seed.value().to_string()];
+            row.extend(run.samples.iter().map(f64::to_string));
+            match &run.outputs {
+                Ok(values) => {
+                    row.extend(outputs.iter().map(|name| {
+                        values
+                            .iter()
+                            .find(|(n, _)| n == name)
+                            .map(|(_, value)| value.to_string())
+                            .unwrap_or_default()
+                    }));
+                    row.push(String::new());
+                }
+                Err(err) => {
+                    row.extend(outputs.iter().map(|_| String::new()));
+                    row.push(err.to_string());
+                }
+            }
+            write_row(&mut writer, &row)?;
+        }
+        writer.flush()
+    }
+
+    /// Writes one row of [`OutputSummary`] statistics per output
+    pub fn write_summary_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        write_row(
+            &mut writer,
+            &["output", "count", "mean", "std_dev", "min", "max"],
+        )?;
+        for summary in self.summary() {
+            write_row(
+                &mut writer,
+                &[
+                    summary.name,
+                    summary.count.to_string(),
+                    summary.mean.to_string(),
+                    summary.std_dev.to_string(),
+                    summary.min.to_string(),
+                    summary.max.to_string(),
+                ],
+            )?;
+        }
+        writer.flush()
+    }
+}
+
+/// Writes a CSV row, quoting fields that contain separators or quotes
+fn write_row(writer: &mut impl Write, fields: &[impl AsRef<str>]) -> io::Result<()> {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            writer.write_all(b",")?;
+        }
+        let field = field.as_ref();
+        if field.contains([',', '"', '\n', '\r']) {
+            write!(writer, "\"{}\"", field.replace('"', "\"\""))?;
+        } else {
+            writer.write_all(field.as_bytes())?;
+        }
+    }
+    writer.write_all(b"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{LoadableParams, load_param};
+
+    /// Stand-in for a generated model: its output is the sum of two parameters
+    fn model(config: &RunConfig) -> Result<RunOutputs, PictorusError> {
+        let gain = load_param("Gain1", "gain", 1.0f64, &config.params);
+        let offset = load_param("Constant1", "value", 0.0f64, &config.params);
+        if gain < 0.0 {
+            return Err(PictorusError::new(
+                ErrorKind::InvalidData,
+                "Model",
+                "negative gain",
+            ));
+        }
+        Ok(vec![
+            ("final".to_string(), gain + offset),
+            ("noise".to_string(), config.seed.rng(0).next_f64()),
+        ])
+    }
+
+    #[test]
+    fn test_campaign_is_reproducible() {
+        let mut base = DiagramParams::new();
+        base.entry("Constant1".to_string())
+            .or_default()
+            .insert("value".to_string(), "10".to_string());
+        let campaign = Campaign::new(64, RunSeed::new(1))
+            .with_base_params(base)
+            .vary(
+                "Gain1",
+                "gain",
+                Distribution::Uniform { min: 1.0, max: 2.0 },
+            );
+
+        let a = campaign.run(model).unwrap();
+        let b = campaign.run(model).unwrap();
+        assert_eq!(a.parameters, ["Gain1.gain"]);
+        assert_eq!(a.runs.len(), 64);
+        for (run_a, run_b) in a.runs.iter().zip(&b.runs) {
+            assert_eq!(run_a.samples, run_b.samples);
+            assert_eq!(run_a.outputs, run_b.outputs);
+        }
+        for (i, run) in a.runs.iter().enumerate() {
+            assert_eq!(run.index, i);
+            let gain = run.samples[0];
+            assert!((1.0..2.0).contains(&gain));
+            // Samples pass through diagram params unchanged, and the base params still apply
+            assert_eq!(f64::parse(&gain.to_string(), None), Some(gain));
+            assert_eq!(run.outputs.as_ref().unwrap()[0].1, gain + 10.0);
+        }
+        assert_ne!(a.runs[0].seed, a.runs[1].seed);
+    }
+
+    #[test]
+    fn test_normal_distribution_statistics() {
+        let campaign = Campaign::new(4000, RunSeed::new(2)).vary(
+            "Gain1",
+            "gain",
+            Distribution::Normal {
+                mean: 5.0,
+                std_dev: 0.5,
+            },
+        );
+        let results = campaign.run(model).unwrap();
+        let summary = &results.summary()[0];
+        assert_eq!(summary.name, "final");
+        assert_eq!(summary.count, 4000);
+        assert!((summary.mean - 5.0).abs() < 0.05, "{summary:?}");
+        assert!((summary.std_dev - 0.5).abs() < 0.05, "{summary:?}");
+        assert!(summary.min < 4.0 && summary.max > 6.0);
+        assert_eq!(results.output_values("noise").len(), 4000);
+    }
+
+    #[test]
+    fn test_failed_runs_and_csv() {
+        let campaign = Campaign::new(20, RunSeed::new(3)).vary(
+            "Gain1",
+            "gain",
+            Distribution::Uniform {
+                min: -1.0,
+                max: 1.0,
+            },
+        );
+        let results = campaign.run(model).unwrap();
+        let failed = results.runs.iter().filter(|r| r.outputs.is_err()).count();
+        assert!(failed > 0 && failed < 20);
+        assert_eq!(results.summary()[0].count, 20 - failed);
+
+        let mut csv = Vec::new();
+        results.write_runs_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("run,seed,Gain1.gain,final,noise,error"));
+        assert_eq!(lines.clone().count(), 20);
+        // Failed runs have no outputs, only an error
+        assert!(lines.any(|line| line.ends_with(",,,Model error 9 (invalid data): negative gain")));
+
+        let mut summary = Vec::new();
+        results.write_summary_csv(&mut summary).unwrap();
+        let summary = String::from_utf8(summary).unwrap();
+        assert!(summary.starts_with("output,count,mean,std_dev,min,max\nfinal,"));
+        assert_eq!(summary.lines().count(), 3);
+    }
+
+    #[test]
+    fn test_invalid_distribution() {
+        let campaign = Campaign::new(1, RunSeed::new(0)).vary(
+            "Gain1",
+            "gain",
+            Distribution::Normal {
+                mean: 0.0,
+                std_dev: -1.0,
+            },
+        );
+        let err = campaign.run(model).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidConfig);
+    }
+}
@@ -0,0 +1,712 @@
+//! Small key-value storage for values that must survive reboots, such as boot counts and
+//! operating hours.
+//!
+//! [`ParamStore`] is implemented by [`FlashParamStore`], which keeps records in any
+//! `embedded-storage` NOR flash (e.g. a few spare sectors of a microcontroller's internal flash),
+//! and by [`FileParamStore`] on targets with a filesystem.
+//!
+//! Flash can only be erased a limited number of times, so the flash store never rewrites a record
+//! in place. It splits its region into two banks and appends each write to the active bank as a
+//! new record. When the active bank is full, the latest value of every key is copied to the other
+//! bank, which then becomes active. Erasing only happens on that switch, and a bank header written
+//! last makes the switch atomic, so losing power mid-write loses at most the value being written.
+//! Writes should still be rate limited by the caller, as [`crate::persistent_counter`] does.
+use core::cell::RefCell;
+use embedded_storage::nor_flash::NorFlash;
+
+use crate::error::{ErrorKind, PictorusError};
+
+const ERR_TYPE: &str = "ParamStore";
+/// Longest key a record can have
+pub const MAX_KEY_BYTES: usize = 32;
+/// Longest value a record can have
+pub const MAX_VALUE_BYTES: usize = 64;
+
+/// Persistent key-value storage
+pub trait ParamStore {
+    /// Copies the latest value stored under `key` into `buf`, returning its length, or `None`
+    /// if nothing has been stored under `key`
+    fn read(&mut self, key: &str, buf: &mut [u8]) -> Result<Option<usize>, PictorusError>;
+
+    /// Stores `value` under `key`, replacing any previous value
+    fn write(&mut self, key: &str, value: &[u8]) -> Result<(), PictorusError>;
+}
+
+impl<S: ParamStore + ?Sized> ParamStore for &mut S {
+    fn read(&mut self, key: &str, buf: &mut [u8]) -> Result<Option<usize>, PictorusError> {
+        (**self).read(key, buf)
+    }
+
+    fn write(&mut self, key: &str, value: &[u8]) -> Result<(), PictorusError> {
+        (**self).write(key, value)
+    }
+}
+
+/// Lets several blocks share one store, e.g. through a `&'static RefCell`
+impl<S: ParamStore> ParamStore for &RefCell<S> {
+    fn read(&mut self, key: &str, buf: &mut [u8]) -> Result<Option<usize>, PictorusError> {
+        self.borrow_mut().read(key, buf)
+    }
+
+    fn write(&mut self, key: &str, value: &[u8]) -> Result<(), PictorusError> {
+        self.borrow_mut().write(key, value)
+    }
+}
+
+const BANK_MAGIC: [u8; 4] = *b"PcPS";
+const BANK_HEADER_BYTES: usize = 8;
+const RECORD_MAGIC: u8 = 0xA5;
+/// Magic, key length, value length and checksum
+const RECORD_HEADER_BYTES: usize = 4;
+/// Largest write size supported, which covers the 256-bit flash words of STM32H7 parts
+const MAX_WRITE_SIZE: usize = 32;
+const RECORD_BUFFER_BYTES: usize = align_up(
+    RECORD_HEADER_BYTES + MAX_KEY_BYTES + MAX_VALUE_BYTES,
+    MAX_WRITE_SIZE,
+);
+const ERASED: u8 = 0xFF;
+
+const fn align_up(len: usize, align: usize) -> usize {
+    len.div_ceil(align) * align
+}
+
+/// CRC-8 (polynomial 0x07) over a record's lengths, key and value
+fn checksum(lengths: &[u8], body: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for byte in lengths.iter().chain(body) {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn flash_error<E: core::fmt::Debug>(err: E) -> PictorusError {
+    log::warn!("Flash operation failed: {err:?}");
+    PictorusError::new(ErrorKind::Io, ERR_TYPE, "Flash operation failed")
+}
+
+/// What was found at a position in a bank
+enum Slot {
+    /// Erased flash, or bytes that aren't a record, so nothing more can be read from the bank
+    End,
+    /// A record that failed its checksum, likely because power was lost while writing it
+    Corrupt { len: u32 },
+    Valid {
+        len: u32,
+        key_len: usize,
+        value_len: usize,
+    },
+}
+
+/// A [`ParamStore`] that appends records to a region of NOR flash, see the module docs.
+///
+/// The region starts at `offset` and spans two banks of `bank_size` bytes, both multiples of the
+/// flash's erase size. A bank must hold at least one copy of every stored value, plus room to
+/// append new ones: the larger the bank, the less often it's erased.
+pub struct FlashParamStore<F: NorFlash> {
+    flash: F,
+    offset: u32,
+    bank_size: u32,
+    active: u32,
+    sequence: u32,
+    write_pos: u32,
+}
+
+impl<F: NorFlash> FlashParamStore<F> {
+    /// Opens the store, erasing and formatting the region if it doesn't contain a store yet
+    pub fn new(flash: F, offset: u32, bank_size: u32) -> Result<Self, PictorusError> {
+        let erase_size = F::ERASE_SIZE as u32;
+        let invalid =
+            |msg: &'static str| Err(PictorusError::new(ErrorKind::InvalidConfig, ERR_TYPE, msg));
+        if F::READ_SIZE != 1 || F::WRITE_SIZE > MAX_WRITE_SIZE {
+            return invalid("Flash read or write size isn't supported");
+        }
+        if !offset.is_multiple_of(erase_size)
+            || bank_size == 0
+            || !bank_size.is_multiple_of(erase_size)
+        {
+            return invalid("Flash region must be aligned to erase sectors");
+        }
+        if (bank_size as usize) < Self::header_len() + RECORD_BUFFER_BYTES {
+            return invalid("Flash bank is too small");
+        }
+        if offset as usize + 2 * bank_size as usize > flash.capacity() {
+            return invalid("Flash region is outside the flash");
+        }
+
+        let mut store = Self {
+            flash,
+            offset,
+            bank_size,
+            active: 0,
+            sequence: 0,
+            write_pos: 0,
+        };
+        let sequences = [store.read_sequence(0)?, store.read_sequence(1)?];
+        match sequences {
+            [Some(first), Some(second)] if second > first => {
+                store.active = 1;
+                store.sequence = second;
+            }
+            [Some(sequence), _] => store.sequence = sequence,
+            [None, Some(sequence)] => {
+                store.active = 1;
+                store.sequence = sequence;
+            }
+            [None, None] => {
+                log::info!("Formatting flash parameter store");
+                store.erase_bank(0)?;
+                store.write_sequence(0, 0)?;
+            }
+        }
+        store.write_pos = store.find_end(store.active)?;
+        Ok(store)
+    }
+
+    /// Consumes the store, returning the underlying flash
+    pub fn into_inner(self) -> F {
+        self.flash
+    }
+
+    const fn header_len() -> usize {
+        align_up(BANK_HEADER_BYTES, F::WRITE_SIZE)
+    }
+
+    fn record_len(key_len: usize, value_len: usize) -> u32 {
+        align_up(RECORD_HEADER_BYTES + key_len + value_len, F::WRITE_SIZE) as u32
+    }
+
+    fn bank_offset(&self, bank: u32) -> u32 {
+        self.offset + bank * self.bank_size
+    }
+
+    fn read_sequence(&mut self, bank: u32) -> Result<Option<u32>, PictorusError> {
+        let mut header = [0; BANK_HEADER_BYTES];
+        self.flash
+            .read(self.bank_offset(bank), &mut header)
+            .map_err(flash_error)?;
+        if header[..4] != BANK_MAGIC {
+            return Ok(None);
+        }
+        Ok(Some(u32::from_le_bytes(header[4..].try_into().unwrap())))
+    }
+
+    fn write_sequence(&mut self, bank: u32, sequence: u32) -> Result<(), PictorusError> {
+        let mut header = [ERASED; MAX_WRITE_SIZE];
+        header[..4].copy_from_slice(&BANK_MAGIC);
+        header[4..BANK_HEADER_BYTES].copy_from_slice(&sequence.to_le_bytes());
+        self.flash
+            .write(self.bank_offset(bank), &header[..Self::header_len()])
+            .map_err(flash_error)?;
+        self.active = bank;
+        self.sequence = sequence;
+        Ok(())
+    }
+
+    fn erase_bank(&mut self, bank: u32) -> Result<(), PictorusError> {
+        let from = self.bank_offset(bank);
+        self.flash
+            .erase(from, from + self.bank_size)
+            .map_err(flash_error)
+    }
+
+    /// Reads the record at `pos` in `bank` into `record`
+    fn read_slot(
+        &mut self,
+        bank: u32,
+        pos: u32,
+        record: &mut [u8; RECORD_BUFFER_BYTES],
+    ) -> Result<Slot, PictorusError> {
+        if pos as usize + RECORD_HEADER_BYTES > self.bank_size as usize {
+            return Ok(Slot::End);
+        }
+        let offset = self.bank_offset(bank) + pos;
+        let header = &mut record[..RECORD_HEADER_BYTES];
+        self.flash.read(offset, header).map_err(flash_error)?;
+        let [magic, key_len, value_len, crc] = [header[0], header[1], header[2], header[3]];
+        let (key_len, value_len) = (key_len as usize, value_len as usize);
+        let len = Self::record_len(key_len, value_len);
+        if magic != RECORD_MAGIC
+            || key_len > MAX_KEY_BYTES
+            || value_len > MAX_VALUE_BYTES
+            || pos + len > self.bank_size
+        {
+            return Ok(Slot::End);
+        }
+
+        let body = &mut record[RECORD_HEADER_BYTES..][..key_len + value_len];
+        self.flash
+            .read(offset + RECORD_HEADER_BYTES as u32, body)
+            .map_err(flash_error)?;
+        if checksum(&[key_len as u8, value_len as u8], body) != crc {
+            return Ok(Slot::Corrupt { len });
+        }
+        Ok(Slot::Valid {
+            len,
+            key_len,
+            value_len,
+        })
+    }
+
+    /// Finds where the next record can be appended to `bank`
+    fn find_end(&mut self, bank: u32) -> Result<u32, PictorusError> {
+        let mut record = [0; RECORD_BUFFER_BYTES];
+        let mut pos = Self::header_len() as u32;
+        loop {
+            match self.read_slot(bank, pos, &mut record)? {
+                Slot::End => break,
+                Slot::Corrupt { len } | Slot::Valid { len, .. } => pos += len,
+            }
+        }
+        if pos < self.bank_size {
+            // Anything but erased flash here is a record torn before its header was complete,
+            // which can't be written over, so the next write moves to the other bank
+            let mut byte = [0];
+            self.flash
+                .read(self.bank_offset(bank) + pos, &mut byte)
+                .map_err(flash_error)?;
+            if byte[0] != ERASED {
+                log::warn!("Found a torn record in the flash parameter store");
+                return Ok(self.bank_size);
+            }
+        }
+        Ok(pos)
+    }
+
+    /// Finds the latest valid record for `key` in `bank` at or after `start`, leaving it in
+    /// `found` and returning its value length
+    fn find_latest(
+        &mut self,
+        bank: u32,
+        start: u32,
+        key: &[u8],
+        found: &mut [u8; RECORD_BUFFER_BYTES],
+    ) -> Result<Option<usize>, PictorusError> {
+        let mut record = [0; RECORD_BUFFER_BYTES];
+        let mut latest = None;
+        let mut pos = start;
+        loop {
+            match self.read_slot(bank, pos, &mut record)? {
+                Slot::End => return Ok(latest),
+                Slot::Corrupt { len } => pos += len,
+                Slot::Valid {
+                    len,
+                    key_len,
+                    value_len,
+                } => {
+                    if &record[RECORD_HEADER_BYTES..][..key_len] == key {
+                        found.copy_from_slice(&record);
+                        latest = Some(value_len);
+                    }
+                    pos += len;
+                }
+            }
+        }
+    }
+
+    fn append(
+        &mut self,
+        bank: u32,
+        pos: u32,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<u32, PictorusError> {
+        let mut record = [ERASED; RECORD_BUFFER_BYTES];
+        record[..RECORD_HEADER_BYTES].copy_from_slice(&[
+            RECORD_MAGIC,
+            key.len() as u8,
+            value.len() as u8,
+            0,
+        ]);
+        let body = &mut record[RECORD_HEADER_BYTES..][..key.len() + value.len()];
+        body[..key.len()].copy_from_slice(key);
+        body[key.len()..].copy_from_slice(value);
+        record[3] = checksum(
+            &record[1..3],
+            &record[RECORD_HEADER_BYTES..][..key.len() + value.len()],
+        );
+
+        let len = Self::record_len(key.len(), value.len());
+        self.flash
+            .write(self.bank_offset(bank) + pos, &record[..len as usize])
+            .map_err(flash_error)?;
+        Ok(pos + len)
+    }
+
+    /// Copies the latest value of every key but `key` to the inactive bank, appends `value`
+    /// under `key` there and switches to it
+    fn compact(&mut self, key: &[u8], value: &[u8]) -> Result<(), PictorusError> {
+        let source = self.active;
+        let target = 1 - source;
+        self.erase_bank(target)?;
+
+        let mut record = [0; RECORD_BUFFER_BYTES];
+        let mut latest = [0; RECORD_BUFFER_BYTES];
+        let mut read_pos = Self::header_len() as u32;
+        let mut write_pos = Self::header_len() as u32;
+        loop {
+            let (len, key_len) = match self.read_slot(source, read_pos, &mut record)? {
+                Slot::End => break,
+                Slot::Corrupt { len } => {
+                    read_pos += len;
+                    continue;
+                }
+                Slot::Valid { len, key_len, .. } => (len, key_len),
+            };
+            read_pos += len;
+            let record_key = &record[RECORD_HEADER_BYTES..][..key_len];
+            // Only the last record for each key is copied
+            if record_key == key
+                || self
+                    .find_latest(source, read_pos, record_key, &mut latest)?
+                    .is_some()
+            {
+                continue;
+            }
+            let value_len = record[2] as usize;
+            let (record_key, record_value) =
+                record[RECORD_HEADER_BYTES..][..key_len + value_len].split_at(key_len);
+            if write_pos + Self::record_len(key_len, value_len) > self.bank_size {
+                return Err(PictorusError::new(
+                    ErrorKind::Other,
+                    ERR_TYPE,
+                    "Flash parameter store is full",
+                ));
+            }
+            write_pos = self.append(target, write_pos, record_key, record_value)?;
+        }
+
+        if write_pos + Self::record_len(key.len(), value.len()) > self.bank_size {
+            return Err(PictorusError::new(
+                ErrorKind::Other,
+                ERR_TYPE,
+                "Flash parameter store is full",
+            ));
+        }
+        write_pos = self.append(target, write_pos, key, value)?;
+        self.write_sequence(target, self.sequence.wrapping_add(1))?;
+        self.write_pos = write_pos;
+        Ok(())
+    }
+}
+
+impl<F: NorFlash> ParamStore for FlashParamStore<F> {
+    fn read(&mut self, key: &str, buf: &mut [u8]) -> Result<Option<usize>, PictorusError> {
+        let mut record = [0; RECORD_BUFFER_BYTES];
+        let start = Self::header_len() as u32;
+        let Some(value_len) = self.find_latest(self.active, start, key.as_bytes(), &mut record)?
+        else {
+            return Ok(None);
+        };
+        let Some(buf) = buf.get_mut(..value_len) else {
+            return Err(PictorusError::new(
+                ErrorKind::InvalidData,
+                ERR_TYPE,
+                "Stored value is larger than the buffer",
+            ));
+        };
+        buf.copy_from_slice(&record[RECORD_HEADER_BYTES + key.len()..][..value_len]);
+        Ok(Some(value_len))
+    }
+
+    fn write(&mut self, key: &str, value: &[u8]) -> Result<(), PictorusError> {
+        let key = key.as_bytes();
+        if key.is_empty() || key.len() > MAX_KEY_BYTES || value.len() > MAX_VALUE_BYTES {
+            return Err(PictorusError::new(
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
+                "Key or value is too long for the flash parameter store",
+            ));
+        }
+
+        // Rewriting an unchanged value would only wear the flash
+        let mut record = [0; RECORD_BUFFER_BYTES];
+        let start = Self::header_len() as u32;
+        let unchanged = self
+            .find_latest(self.active, start, key, &mut record)?
+            .is_some_and(|value_len| {
+                &record[RECORD_HEADER_BYTES + key.len()..][..value_len] == value
+            });
+        if unchanged {
+            return Ok(());
+        }
+
+        if self.write_pos + Self::record_len(key.len(), value.len()) > self.bank_size {
+            return self.compact(key, value);
+        }
+        self.write_pos = self.append(self.active, self.write_pos, key, value)?;
+        Ok(())
+    }
+}
+
+/// A [`ParamStore`] that keeps each value in its own file in a directory
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct FileParamStore {
+    dir: std::path::PathBuf,
+}
+
+#[cfg(feature = "std")]
+impl FileParamStore {
+    /// Opens the store in `dir`, creating the directory if it doesn't exist
+    pub fn new(dir: impl Into<std::path::PathBuf>) -> Result<Self, PictorusError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|err| {
+            PictorusError::from_io(
+                ERR_TYPE,
+                alloc::format!("Failed to create {}: {err}", dir.display()),
+                &err,
+            )
+        })?;
+        Ok(Self { dir })
+    }
+}
+
+#[cfg(feature = "std")]
+impl ParamStore for FileParamStore {
+    fn read(&mut self, key: &str, buf: &mut [u8]) -> Result<Option<usize>, PictorusError> {
+        let path = self.dir.join(key);
+        let value = match std::fs::read(&path) {
+            Ok(value) => value,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(PictorusError::from_io(
+                    ERR_TYPE,
+                    alloc::format!("Failed to read {}: {err}", path.display()),
+                    &err,
+                ));
+            }
+        };
+        let Some(buf) = buf.get_mut(..value.len()) else {
+            return Err(PictorusError::new(
+                ErrorKind::InvalidData,
+                ERR_TYPE,
+                alloc::format!("{} is larger than the buffer", path.display()),
+            ));
+        };
+        buf.copy_from_slice(&value);
+        Ok(Some(value.len()))
+    }
+
+    fn write(&mut self, key: &str, value: &[u8]) -> Result<(), PictorusError> {
+        // Write to a temporary file first so a crash can't leave a partially written value
+        let path = self.dir.join(key);
+        let tmp_path = self.dir.join(alloc::format!(".{key}.tmp"));
+        std::fs::write(&tmp_path, value)
+            .and_then(|_| std::fs::rename(&tmp_path, &path))
+            .map_err(|err| {
+                PictorusError::from_io(
+                    ERR_TYPE,
+                    alloc::format!("Failed to write {}: {err}", path.display()),
+                    &err,
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use embedded_storage::nor_flash::{ErrorType, NorFlashErrorKind, ReadNorFlash};
+
+    /// RAM-backed flash that only allows clearing bits, like real NOR flash
+    pub(crate) struct MockFlash {
+        pub(crate) data: Vec<u8>,
+        pub(crate) erases: usize,
+    }
+
+    impl MockFlash {
+        pub(crate) fn new(sectors: usize) -> Self {
+            Self {
+                data: vec![ERASED; sectors * Self::ERASE_SIZE],
+                erases: 0,
+            }
+        }
+    }
+
+    impl ErrorType for MockFlash {
+        type Error = NorFlashErrorKind;
+    }
+
+    impl ReadNorFlash for MockFlash {
+        const READ_SIZE: usize = 1;
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            bytes.copy_from_slice(&self.data[offset..offset + bytes.len()]);
+            Ok(())
+        }
+
+        fn capacity(&self) -> usize {
+            self.data.len()
+        }
+    }
+
+    impl NorFlash for MockFlash {
+        const WRITE_SIZE: usize = 8;
+        const ERASE_SIZE: usize = 256;
+
+        fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+            self.data[from as usize..to as usize].fill(ERASED);
+            self.erases += 1;
+            Ok(())
+        }
+
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            if !offset.is_multiple_of(Self::WRITE_SIZE)
+                || !bytes.len().is_multiple_of(Self::WRITE_SIZE)
+            {
+                return Err(NorFlashErrorKind::NotAligned);
+            }
+            let target = &mut self.data[offset..offset + bytes.len()];
+            assert!(
+                target.iter().all(|byte| *byte == ERASED),
+                "write to unerased flash"
+            );
+            target.copy_from_slice(bytes);
+            Ok(())
+        }
+    }
+
+    fn read_u32(store: &mut impl ParamStore, key: &str) -> Option<u32> {
+        let mut buf = [0; 4];
+        store
+            .read(key, &mut buf)
+            .unwrap()
+            .map(|_| u32::from_le_bytes(buf))
+    }
+
+    #[test]
+    fn test_flash_store_round_trip() {
+        let mut store = FlashParamStore::new(MockFlash::new(2), 0, 256).unwrap();
+        assert_eq!(read_u32(&mut store, "boots"), None);
+
+        store.write("boots", &1u32.to_le_bytes()).unwrap();
+        store.write("faults", &7u32.to_le_bytes()).unwrap();
+        store.write("boots", &2u32.to_le_bytes()).unwrap();
+        assert_eq!(read_u32(&mut store, "boots"), Some(2));
+        assert_eq!(read_u32(&mut store, "faults"), Some(7));
+
+        // Values are restored when the store is reopened
+        let mut store = FlashParamStore::new(store.into_inner(), 0, 256).unwrap();
+        assert_eq!(read_u32(&mut store, "boots"), Some(2));
+        assert_eq!(read_u32(&mut store, "faults"), Some(7));
+
+        let mut small = [0; 2];
+        assert!(store.read("boots", &mut small).is_err());
+    }
+
+    #[test]
+    fn test_flash_store_switches_banks() {
+        let mut store = FlashParamStore::new(MockFlash::new(4), 256, 256).unwrap();
+        store.write("faults", &3u32.to_le_bytes()).unwrap();
+        for count in 0..100u32 {
+            store.write("boots", &count.to_le_bytes()).unwrap();
+        }
+        assert_eq!(read_u32(&mut store, "boots"), Some(99));
+        assert_eq!(read_u32(&mut store, "faults"), Some(3));
+
+        let flash = store.into_inner();
+        // 15 records fit in a bank and each switch carries 2 of them over, so after formatting
+        // the region, the 101 writes switch banks 7 times
+        assert_eq!(flash.erases, 8);
+        // The region before the store is never touched
+        assert!(flash.data[..256].iter().all(|byte| *byte == ERASED));
+
+        let mut store = FlashParamStore::new(flash, 256, 256).unwrap();
+        assert_eq!(read_u32(&mut store, "boots"), Some(99));
+        assert_eq!(read_u32(&mut store, "faults"), Some(3));
+    }
+
+    #[test]
+    fn test_flash_store_skips_unchanged_values() {
+        let mut store = FlashParamStore::new(MockFlash::new(2), 0, 256).unwrap();
+        store.write("boots", &1u32.to_le_bytes()).unwrap();
+        let write_pos = store.write_pos;
+        store.write("boots", &1u32.to_le_bytes()).unwrap();
+        assert_eq!(store.write_pos, write_pos);
+    }
+
+    #[test]
+    fn test_flash_store_recovers_from_torn_writes() {
+        let mut store = FlashParamStore::new(MockFlash::new(2), 0, 256).unwrap();
+        store.write("boots", &1u32.to_le_bytes()).unwrap();
+        store.write("boots", &2u32.to_le_bytes()).unwrap();
+        let mut flash = store.into_inner();
+
+        // Power was lost while the value of the second record was being written
+        flash.data[8 + 16 + 10] = ERASED;
+        let mut store = FlashParamStore::new(flash, 0, 256).unwrap();
+        assert_eq!(read_u32(&mut store, "boots"), Some(1));
+        store.write("boots", &3u32.to_le_bytes()).unwrap();
+        assert_eq!(read_u32(&mut store, "boots"), Some(3));
+
+        // Power was lost after only the magic of the next record was written
+        let mut flash = store.into_inner();
+        flash.data[8 + 3 * 16] = RECORD_MAGIC;
+        let mut store = FlashParamStore::new(flash, 0, 256).unwrap();
+        assert_eq!(read_u32(&mut store, "boots"), Some(3));
+        store.write("boots", &4u32.to_le_bytes()).unwrap();
+        assert_eq!(store.active, 1);
+        let mut store = FlashParamStore::new(store.into_inner(), 0, 256).unwrap();
+        assert_eq!(read_u32(&mut store, "boots"), Some(4));
+    }
+
+    #[test]
+    fn test_flash_store_invalid_config() {
+        assert!(FlashParamStore::new(MockFlash::new(2), 0, 100).is_err());
+        assert!(FlashParamStore::new(MockFlash::new(2), 128, 256).is_err());
+        assert!(FlashParamStore::new(MockFlash::new(2), 256, 256).is_err());
+
+        let mut store = FlashParamStore::new(MockFlash::new(2), 0, 256).unwrap();
+        assert!(store.write("", b"").is_err());
+        assert!(store.write("key", &[0; MAX_VALUE_BYTES + 1]).is_err());
+    }
+
+    #[test]
+    fn test_flash_store_full() {
+        let mut store = FlashParamStore::new(MockFlash::new(2), 0, 256).unwrap();
+        let value = [0; MAX_VALUE_BYTES];
+        store.write("a", &value).unwrap();
+        store.write("b", &value).unwrap();
+        store.write("c", &value).unwrap();
+        let err = store.write("d", &value).unwrap_err();
+        assert_eq!(err.message, "Flash parameter store is full");
+        // The values already stored are kept
+        assert_eq!(
+            store.read("a", &mut [0; MAX_VALUE_BYTES]).unwrap(),
+            Some(64)
+        );
+    }
+
+    #[test]
+    fn test_shared_store() {
+        let store = RefCell::new(FlashParamStore::new(MockFlash::new(2), 0, 256).unwrap());
+        let (mut first, mut second) = (&store, &store);
+        first.write("boots", &5u32.to_le_bytes()).unwrap();
+        assert_eq!(read_u32(&mut second, "boots"), Some(5));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_file_store() {
+        let dir = std::env::temp_dir().join(alloc::format!("param_store_{}", std::process::id()));
+        let mut store = FileParamStore::new(&dir).unwrap();
+        assert_eq!(read_u32(&mut store, "boots"), None);
+        store.write("boots", &3u32.to_le_bytes()).unwrap();
+        store.write("boots", &4u32.to_le_bytes()).unwrap();
+
+        let mut store = FileParamStore::new(&dir).unwrap();
+        assert_eq!(read_u32(&mut store, "boots"), Some(4));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
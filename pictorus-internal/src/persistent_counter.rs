@@ -0,0 +1,336 @@
+//! Counters that survive reboots, such as a boot count, operating hours or the number of times a
+//! fault has occurred.
+//!
+//! [`PersistentCounterBlock`] keeps its count in a [`ParamStore`]. Counts change far more often
+//! than flash can be written, so the block only commits a changed count once per commit interval.
+//! A reset or power loss loses at most the changes since the last commit; call
+//! [`PersistentCounterBlock::commit`] on a graceful shutdown to keep them.
+use core::time::Duration;
+use log::warn;
+use pictorus_traits::{Context, InputBlock, OutputBlock, PassBy};
+
+use crate::error::{ErrorKind, PictorusError};
+use crate::param_store::ParamStore;
+
+const ERR_TYPE: &str = "PersistentCounter";
+
+/// What a [`PersistentCounterBlock`] counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CounterMode {
+    /// Counts the times the block was created, i.e. app starts
+    Boots,
+    /// Accumulates the seconds during which the input is truthy
+    OperatingTime,
+    /// Counts rising edges of the input, e.g. fault occurrences
+    Events,
+}
+
+impl core::str::FromStr for CounterMode {
+    type Err = PictorusError;
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode {
+            "Boots" => Ok(CounterMode::Boots),
+            "OperatingTime" => Ok(CounterMode::OperatingTime),
+            "Events" => Ok(CounterMode::Events),
+            _ => Err(PictorusError::new(
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
+                "Failed to parse CounterMode",
+            )),
+        }
+    }
+}
+
+/// Parameters for the PersistentCounterBlock. Its settings are fixed when it's created.
+pub struct PersistentCounterParams;
+
+/// A count persisted in a [`ParamStore`] under a key.
+///
+/// As an `OutputBlock` the block takes a scalar input: while counting operating time it adds the
+/// time of every tick on which the input is truthy, and while counting events it counts the ticks
+/// on which the input becomes truthy. The input is ignored when counting boots, which happens
+/// once, when the block is created. As an `InputBlock` it outputs the count, in seconds for
+/// operating time.
+///
+/// A changed count is committed to the store at most once per `commit_interval`. Use an interval
+/// that keeps flash writes within the flash's endurance over the product's lifetime: a counter
+/// that changes every tick writes once per interval, and every write takes a record in the store.
+pub struct PersistentCounterBlock<S: ParamStore> {
+    store: S,
+    key: &'static str,
+    mode: CounterMode,
+    commit_interval: Duration,
+    /// Boots or events, or microseconds of operating time
+    count: u64,
+    committed: u64,
+    last_commit: Duration,
+    was_active: bool,
+    output: f64,
+}
+
+impl<S: ParamStore> PersistentCounterBlock<S> {
+    /// Restores the count stored under `key`, which must be unique among the store's users,
+    /// starting from 0 if there isn't one. Counting boots commits the incremented count
+    /// immediately.
+    pub fn new(
+        store: S,
+        key: &'static str,
+        mode: CounterMode,
+        commit_interval: Duration,
+    ) -> Result<Self, PictorusError> {
+        let mut block = Self {
+            store,
+            key,
+            mode,
+            commit_interval,
+            count: 0,
+            committed: 0,
+            last_commit: Duration::ZERO,
+            was_active: false,
+            output: 0.0,
+        };
+
+        let mut value = [0; 8];
+        match block.store.read(key, &mut value)? {
+            Some(8) => block.count = u64::from_le_bytes(value),
+            Some(_) => {
+                return Err(PictorusError::new(
+                    ErrorKind::InvalidData,
+                    ERR_TYPE,
+                    "Stored count has the wrong size",
+                ));
+            }
+            None => {}
+        }
+        block.committed = block.count;
+        if mode == CounterMode::Boots {
+            block.count = block.count.saturating_add(1);
+            block.commit()?;
+        }
+        block.update_output();
+        Ok(block)
+    }
+
+    /// The count in its stored units: boots, events or microseconds of operating time
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Writes the count to the store now if it changed since the last commit
+    pub fn commit(&mut self) -> Result<(), PictorusError> {
+        if self.count == self.committed {
+            return Ok(());
+        }
+        self.store.write(self.key, &self.count.to_le_bytes())?;
+        self.committed = self.count;
+        Ok(())
+    }
+
+    fn update_output(&mut self) {
+        self.output = match self.mode {
+            CounterMode::OperatingTime => self.count as f64 / 1e6,
+            CounterMode::Boots | CounterMode::Events => self.count as f64,
+        };
+    }
+}
+
+impl<S: ParamStore> InputBlock for PersistentCounterBlock<S> {
+    type Output = f64;
+    type Parameters = PersistentCounterParams;
+
+    fn input(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        self.output
+    }
+}
+
+impl<S: ParamStore> OutputBlock for PersistentCounterBlock<S> {
+    type Inputs = f64;
+    type Parameters = PersistentCounterParams;
+
+    fn output(
+        &mut self,
+        _parameters: &Self::Parameters,
+        context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) {
+        let active = inputs != 0.0;
+        match self.mode {
+            CounterMode::Boots => {}
+            CounterMode::OperatingTime if active => {
+                let elapsed = context.timestep().unwrap_or(Duration::ZERO);
+                self.count = self.count.saturating_add(elapsed.as_micros() as u64);
+            }
+            CounterMode::OperatingTime => {}
+            CounterMode::Events if active && !self.was_active => {
+                self.count = self.count.saturating_add(1);
+            }
+            CounterMode::Events => {}
+        }
+        self.was_active = active;
+        self.update_output();
+
+        let now = context.time();
+        if now.saturating_sub(self.last_commit) >= self.commit_interval
+            && self.count != self.committed
+        {
+            // Failed commits are retried after another interval
+            self.last_commit = now;
+            if let Err(err) = self.commit() {
+                warn!("Failed to commit persistent counter {}: {err:?}", self.key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::param_store::FlashParamStore;
+    use crate::param_store::tests::MockFlash;
+    use core::cell::RefCell;
+
+    /// Context where each tick advances time by 100 ms
+    struct TickContext {
+        time: Duration,
+    }
+
+    impl TickContext {
+        fn tick(&mut self) -> &Self {
+            self.time += Duration::from_millis(100);
+            self
+        }
+    }
+
+    impl Context for TickContext {
+        fn timestep(&self) -> Option<Duration> {
+            Some(Duration::from_millis(100))
+        }
+
+        fn time(&self) -> Duration {
+            self.time
+        }
+
+        fn fundamental_timestep(&self) -> Duration {
+            Duration::from_millis(100)
+        }
+    }
+
+    fn flash_store() -> RefCell<FlashParamStore<MockFlash>> {
+        RefCell::new(FlashParamStore::new(MockFlash::new(2), 0, 256).unwrap())
+    }
+
+    fn stored(store: &RefCell<FlashParamStore<MockFlash>>, key: &str) -> Option<u64> {
+        let mut value = [0; 8];
+        store
+            .borrow_mut()
+            .read(key, &mut value)
+            .unwrap()
+            .map(|_| u64::from_le_bytes(value))
+    }
+
+    #[test]
+    fn test_boot_count() {
+        let store = flash_store();
+        let interval = Duration::from_secs(60);
+        let mut context = TickContext {
+            time: Duration::ZERO,
+        };
+        for boot in 1..=3 {
+            let mut block =
+                PersistentCounterBlock::new(&store, "boots", CounterMode::Boots, interval).unwrap();
+            block.output(&PersistentCounterParams, context.tick(), 1.0);
+            assert_eq!(block.input(&PersistentCounterParams, &context), boot as f64);
+            assert_eq!(stored(&store, "boots"), Some(boot));
+        }
+    }
+
+    #[test]
+    fn test_operating_time() {
+        let store = flash_store();
+        let interval = Duration::from_secs(1);
+        let mut context = TickContext {
+            time: Duration::ZERO,
+        };
+        let mut block =
+            PersistentCounterBlock::new(&store, "hours", CounterMode::OperatingTime, interval)
+                .unwrap();
+        for _ in 0..5 {
+            block.output(&PersistentCounterParams, context.tick(), 1.0);
+        }
+        block.output(&PersistentCounterParams, context.tick(), 0.0);
+        assert_eq!(block.input(&PersistentCounterParams, &context), 0.5);
+        // Not committed until the interval has passed
+        assert_eq!(stored(&store, "hours"), None);
+
+        for _ in 0..4 {
+            block.output(&PersistentCounterParams, context.tick(), 1.0);
+        }
+        assert_eq!(stored(&store, "hours"), Some(900_000));
+
+        // Restarting resumes from the last commit
+        for _ in 0..3 {
+            block.output(&PersistentCounterParams, context.tick(), 1.0);
+        }
+        let block =
+            PersistentCounterBlock::new(&store, "hours", CounterMode::OperatingTime, interval)
+                .unwrap();
+        assert_eq!(block.count(), 900_000);
+    }
+
+    #[test]
+    fn test_event_count() {
+        let store = flash_store();
+        let mut context = TickContext {
+            time: Duration::ZERO,
+        };
+        let mut block = PersistentCounterBlock::new(
+            &store,
+            "overcurrent",
+            CounterMode::Events,
+            Duration::from_secs(10),
+        )
+        .unwrap();
+        for input in [0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0] {
+            block.output(&PersistentCounterParams, context.tick(), input);
+        }
+        assert_eq!(block.input(&PersistentCounterParams, &context), 3.0);
+        assert_eq!(stored(&store, "overcurrent"), None);
+
+        block.commit().unwrap();
+        assert_eq!(stored(&store, "overcurrent"), Some(3));
+    }
+
+    #[test]
+    fn test_counters_share_store() {
+        let store = flash_store();
+        let interval = Duration::ZERO;
+        let mut context = TickContext {
+            time: Duration::ZERO,
+        };
+        let mut boots =
+            PersistentCounterBlock::new(&store, "boots", CounterMode::Boots, interval).unwrap();
+        let mut faults =
+            PersistentCounterBlock::new(&store, "faults", CounterMode::Events, interval).unwrap();
+        for _ in 0..50 {
+            boots.output(&PersistentCounterParams, context.tick(), 0.0);
+            faults.output(&PersistentCounterParams, context.tick(), 1.0);
+            faults.output(&PersistentCounterParams, context.tick(), 0.0);
+        }
+        assert_eq!(stored(&store, "boots"), Some(1));
+        assert_eq!(stored(&store, "faults"), Some(50));
+    }
+
+    #[test]
+    fn test_counter_mode_from_str() {
+        assert_eq!(
+            "Events".parse::<CounterMode>().unwrap(),
+            CounterMode::Events
+        );
+        assert!("events".parse::<CounterMode>().is_err());
+    }
+}
@@ -0,0 +1,201 @@
+//! Deterministic pseudo-random numbers.
+//!
+//! Every stochastic part of an app (noise blocks, PRBS generators, fault injection) should draw
+//! from its own [`Rng`] stream derived from a single [`RunSeed`], so a whole run can be reproduced
+//! from the one seed recorded in the log. [`Rng`] uses the same algorithm (xoshiro128++) on every
+//! target, so a seed also reproduces a hardware run in simulation. This isn't true of `rand`'s
+//! `SmallRng`, whose algorithm depends on the target's pointer width.
+//!
+//! [`Rng`] implements the `rand_core` traits, so it can be used with `rand` distributions and by
+//! blocks that are generic over their random number generator.
+use rand_core::{RngCore, SeedableRng, impls};
+use serde::Serialize;
+
+/// Increment of the SplitMix64 generator (the golden ratio in 64-bit fixed point)
+const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Advances a SplitMix64 generator, returning its next output. Used to expand 64-bit seeds into
+/// well-mixed generator states, as recommended by the xoshiro authors.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(GOLDEN_GAMMA);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Seedable, `no_std` pseudo-random number generator (xoshiro128++). Fast on 32-bit cores and
+/// statistically strong, but not cryptographically secure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rng {
+    state: [u32; 4],
+}
+
+impl Rng {
+    /// Uniformly distributed in `[0, 1)`
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Returns true with the given probability, e.g. to decide whether to inject a fault this tick
+    pub fn chance(&mut self, probability: f64) -> bool {
+        self.next_f64() < probability
+    }
+}
+
+impl RngCore for Rng {
+    fn next_u32(&mut self) -> u32 {
+        let [s0, s1, s2, s3] = &mut self.state;
+        let result = s0.wrapping_add(*s3).rotate_left(7).wrapping_add(*s0);
+        let t = *s1 << 9;
+        *s2 ^= *s0;
+        *s3 ^= *s1;
+        *s1 ^= *s2;
+        *s0 ^= *s3;
+        *s2 ^= t;
+        *s3 = s3.rotate_left(11);
+        result
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_u32(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Rng {
+    type Seed = [u8; 16];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        // xoshiro never leaves the all-zero state, so substitute a valid one
+        if seed == [0; 16] {
+            return Self::seed_from_u64(0);
+        }
+        let mut state = [0; 4];
+        for (word, bytes) in state.iter_mut().zip(seed.chunks_exact(4)) {
+            *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        Self { state }
+    }
+
+    fn seed_from_u64(mut seed: u64) -> Self {
+        let mut bytes = [0; 16];
+        for chunk in bytes.chunks_exact_mut(8) {
+            chunk.copy_from_slice(&splitmix64(&mut seed).to_le_bytes());
+        }
+        Self::from_seed(bytes)
+    }
+}
+
+/// The seed that all random number streams in a run are derived from. Record it (it's logged by
+/// [`RunSeed::from_env`]) to be able to reproduce the run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct RunSeed(u64);
+
+impl RunSeed {
+    pub const fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub const fn value(self) -> u64 {
+        self.0
+    }
+
+    /// Seed for the independent stream `stream`, e.g. a block's index in the model. Distinct
+    /// streams get distinct, uncorrelated seeds.
+    pub fn stream_seed(self, stream: u64) -> u64 {
+        let mut state = self.0.wrapping_add(stream.wrapping_mul(GOLDEN_GAMMA));
+        splitmix64(&mut state)
+    }
+
+    /// Generator for the stream `stream`
+    pub fn rng(self, stream: u64) -> Rng {
+        Rng::seed_from_u64(self.stream_seed(stream))
+    }
+}
+
+#[cfg(feature = "std")]
+impl RunSeed {
+    /// Environment variable that fixes the seed of a run
+    pub const ENV_VAR: &'static str = "APP_RANDOM_SEED";
+
+    /// Reads the seed from [`RunSeed::ENV_VAR`], or picks one from the system clock if it isn't
+    /// set. The seed is logged either way, so any run can be reproduced.
+    pub fn from_env() -> Self {
+        let var = std::env::var(Self::ENV_VAR).ok();
+        let seed = match var.as_deref().map(|var| var.trim().parse::<u64>()) {
+            Some(Ok(seed)) => seed,
+            parsed => {
+                if parsed.is_some() {
+                    log::warn!("Could not parse {}={var:?} as a u64", Self::ENV_VAR);
+                }
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|since_epoch| since_epoch.as_nanos() as u64)
+                    .unwrap_or_default()
+            }
+        };
+        log::info!(
+            "Random seed: {seed} (set {}={seed} to reproduce this run)",
+            Self::ENV_VAR
+        );
+        Self(seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng_reference_output() {
+        // State [1, 2, 3, 4], checked against the reference xoshiro128++ implementation
+        let mut rng = Rng::from_seed([1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0]);
+        let output: [u32; 6] = core::array::from_fn(|_| rng.next_u32());
+        assert_eq!(
+            output,
+            [641, 1573767, 3222811527, 3517856514, 836907274, 4247214768]
+        );
+
+        // All zero seeds are replaced by a usable state
+        let mut rng = Rng::from_seed([0; 16]);
+        assert_ne!(rng.next_u64(), rng.next_u64());
+    }
+
+    #[test]
+    fn test_run_seed_streams() {
+        let seed = RunSeed::new(42);
+        assert_eq!(seed.value(), 42);
+
+        // Streams are reproducible but independent
+        let mut a = seed.rng(0);
+        let mut b = seed.rng(1);
+        assert_eq!(a.clone().next_u64(), seed.rng(0).next_u64());
+        assert_ne!(a.next_u64(), b.next_u64());
+        assert_ne!(seed.stream_seed(0), RunSeed::new(43).stream_seed(0));
+    }
+
+    #[test]
+    fn test_rng_floats() {
+        let mut rng = RunSeed::new(7).rng(3);
+        let mean = (0..10_000).map(|_| rng.next_f64()).sum::<f64>() / 10_000.0;
+        assert!((mean - 0.5).abs() < 0.02, "mean {mean}");
+        assert!(
+            (0..1000)
+                .map(|_| rng.next_f64())
+                .all(|x| (0.0..1.0).contains(&x))
+        );
+
+        let hits = (0..10_000).filter(|_| rng.chance(0.1)).count();
+        assert!((800..1200).contains(&hits), "hits {hits}");
+        assert!(!rng.chance(0.0));
+    }
+}
@@ -0,0 +1,891 @@
+//! Drivers for distance sensors: the HC-SR04 ultrasonic rangefinder, and the ST VL53L0X and
+//! VL53L1X time-of-flight sensors.
+//!
+//! The drivers are generic over `embedded-hal` pins, I2C buses and delays, and an
+//! `embedded-time` clock, so the same code runs on Linux and on microcontrollers. Each one is an
+//! `InputBlock` that outputs (distance in meters, valid), holding the last measurement until a
+//! new one is available. Valid is false while the target is out of range or the measurement
+//! failed.
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{InputPin, OutputPin};
+use embedded_hal::i2c::I2c;
+use embedded_time::Clock;
+use log::warn;
+use pictorus_traits::{Context, InputBlock, PassBy};
+
+use crate::error::{ErrorKind, PictorusError};
+use crate::timing::embedded_duration_to_us;
+
+const ERR_TYPE: &str = "RangefinderProtocol";
+/// Speed of sound in dry air at 20°C, in m/s
+const SPEED_OF_SOUND: f64 = 343.0;
+/// Longest wait for the HC-SR04 to start its echo pulse after the trigger
+const ECHO_START_TIMEOUT_US: u64 = 5_000;
+/// Longest wait for a time-of-flight sensor to boot or finish a calibration
+const TOF_TIMEOUT_MS: u32 = 500;
+/// Default I2C address of both time-of-flight sensors
+pub const TOF_DEFAULT_ADDRESS: u8 = 0x29;
+
+/// Errors returned by the rangefinder drivers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangefinderError {
+    /// An I2C transfer failed
+    I2c,
+    /// Driving or reading a pin failed
+    Pin,
+    /// Reading the clock failed
+    Clock,
+    /// The sensor didn't respond in time
+    Timeout,
+    /// The sensor didn't report the expected model ID
+    UnknownDevice(u16),
+}
+
+impl From<RangefinderError> for PictorusError {
+    fn from(err: RangefinderError) -> Self {
+        match err {
+            RangefinderError::I2c => {
+                PictorusError::new(ErrorKind::Io, ERR_TYPE, "I2C transfer failed")
+            }
+            RangefinderError::Pin => {
+                PictorusError::new(ErrorKind::Io, ERR_TYPE, "Failed to access pin")
+            }
+            RangefinderError::Clock => {
+                PictorusError::new(ErrorKind::Other, ERR_TYPE, "Failed to read clock")
+            }
+            RangefinderError::Timeout => {
+                PictorusError::new(ErrorKind::Timeout, ERR_TYPE, "Sensor didn't respond")
+            }
+            RangefinderError::UnknownDevice(_) => PictorusError::new(
+                ErrorKind::NotFound,
+                ERR_TYPE,
+                "No supported time-of-flight sensor found",
+            ),
+        }
+    }
+}
+
+/// Parameters for the rangefinder blocks. Their settings are fixed when they're created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RangefinderParams {}
+
+impl RangefinderParams {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// HC-SR04 ultrasonic rangefinder, timing the echo pulse after a 10 µs trigger pulse.
+///
+/// Each tick blocks until the echo ends, for up to 6 ms per meter of `max_range`, so keep the
+/// range to what the application needs. The measurement assumes the speed of sound at 20°C,
+/// which is about 0.17% fast per degree colder.
+pub struct HcSr04<T: OutputPin, E: InputPin, C: Clock<T = u64>, D: DelayNs> {
+    trigger: T,
+    echo: E,
+    clock: C,
+    delay: D,
+    /// Longest echo pulse within range
+    timeout_us: u64,
+    buffer: (f64, bool),
+}
+
+impl<T: OutputPin, E: InputPin, C: Clock<T = u64>, D: DelayNs> HcSr04<T, E, C, D> {
+    pub fn new(mut trigger: T, echo: E, clock: C, delay: D, max_range_m: f64) -> Self {
+        trigger.set_low().ok();
+        Self {
+            trigger,
+            echo,
+            clock,
+            delay,
+            timeout_us: (max_range_m * 2.0 / SPEED_OF_SOUND * 1e6) as u64,
+            buffer: (0.0, false),
+        }
+    }
+
+    fn now_us(&self) -> Result<u64, RangefinderError> {
+        let now = self.clock.try_now().map_err(|_| RangefinderError::Clock)?;
+        Ok(embedded_duration_to_us(now.duration_since_epoch()))
+    }
+
+    /// Waits for the echo pin to reach `high`, returning the time it did, or None after
+    /// `timeout_us` from `since`
+    fn wait_for_echo(
+        &mut self,
+        high: bool,
+        since: u64,
+        timeout_us: u64,
+    ) -> Result<Option<u64>, RangefinderError> {
+        loop {
+            let level = self.echo.is_high().map_err(|_| RangefinderError::Pin)?;
+            let now = self.now_us()?;
+            if level == high {
+                return Ok(Some(now));
+            }
+            if now.saturating_sub(since) > timeout_us {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Triggers a measurement, returning the distance in meters, or None without an echo
+    /// within range
+    pub fn measure(&mut self) -> Result<Option<f64>, RangefinderError> {
+        // An echo still going from the last measurement would be mistaken for this one's
+        if self.echo.is_high().map_err(|_| RangefinderError::Pin)? {
+            return Ok(None);
+        }
+        self.trigger.set_high().map_err(|_| RangefinderError::Pin)?;
+        self.delay.delay_us(10);
+        self.trigger.set_low().map_err(|_| RangefinderError::Pin)?;
+
+        let triggered = self.now_us()?;
+        let Some(start) = self.wait_for_echo(true, triggered, ECHO_START_TIMEOUT_US)? else {
+            return Ok(None);
+        };
+        let Some(end) = self.wait_for_echo(false, start, self.timeout_us)? else {
+            return Ok(None);
+        };
+        let round_trip = (end - start) as f64 * 1e-6;
+        Ok(Some(round_trip * SPEED_OF_SOUND / 2.0))
+    }
+}
+
+impl<T: OutputPin, E: InputPin, C: Clock<T = u64>, D: DelayNs> InputBlock for HcSr04<T, E, C, D> {
+    type Output = (f64, bool);
+    type Parameters = RangefinderParams;
+
+    fn input(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        match self.measure() {
+            Ok(Some(distance)) => self.buffer = (distance, true),
+            Ok(None) => self.buffer.1 = false,
+            Err(err) => {
+                warn!("Failed to measure HC-SR04 range: {err:?}");
+                self.buffer.1 = false;
+            }
+        }
+        self.buffer
+    }
+}
+
+/// Register accesses shared by the time-of-flight sensors, which only differ in their register
+/// address width
+struct TofBus<I: I2c, D: DelayNs> {
+    i2c: I,
+    delay: D,
+    address: u8,
+}
+
+impl<I: I2c, D: DelayNs> TofBus<I, D> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), RangefinderError> {
+        self.i2c
+            .write(self.address, bytes)
+            .map_err(|_| RangefinderError::I2c)
+    }
+
+    fn read(&mut self, register: &[u8], bytes: &mut [u8]) -> Result<(), RangefinderError> {
+        self.i2c
+            .write_read(self.address, register, bytes)
+            .map_err(|_| RangefinderError::I2c)
+    }
+
+    /// Polls until `done` returns true, a millisecond apart
+    fn wait(
+        &mut self,
+        mut done: impl FnMut(&mut Self) -> Result<bool, RangefinderError>,
+    ) -> Result<(), RangefinderError> {
+        for _ in 0..TOF_TIMEOUT_MS {
+            if done(self)? {
+                return Ok(());
+            }
+            self.delay.delay_ms(1);
+        }
+        Err(RangefinderError::Timeout)
+    }
+
+    fn write_u8(&mut self, register: u8, value: u8) -> Result<(), RangefinderError> {
+        self.write(&[register, value])
+    }
+
+    fn read_u8(&mut self, register: u8) -> Result<u8, RangefinderError> {
+        let mut value = [0];
+        self.read(&[register], &mut value)?;
+        Ok(value[0])
+    }
+
+    fn read_u16(&mut self, register: u8) -> Result<u16, RangefinderError> {
+        let mut value = [0; 2];
+        self.read(&[register], &mut value)?;
+        Ok(u16::from_be_bytes(value))
+    }
+
+    fn write_u8_wide(&mut self, register: u16, value: u8) -> Result<(), RangefinderError> {
+        let [high, low] = register.to_be_bytes();
+        self.write(&[high, low, value])
+    }
+
+    fn read_u8_wide(&mut self, register: u16) -> Result<u8, RangefinderError> {
+        let mut value = [0];
+        self.read(&register.to_be_bytes(), &mut value)?;
+        Ok(value[0])
+    }
+
+    fn read_u16_wide(&mut self, register: u16) -> Result<u16, RangefinderError> {
+        let mut value = [0; 2];
+        self.read(&register.to_be_bytes(), &mut value)?;
+        Ok(u16::from_be_bytes(value))
+    }
+}
+
+mod vl53l0x {
+    pub const SYSRANGE_START: u8 = 0x00;
+    pub const SYSTEM_SEQUENCE_CONFIG: u8 = 0x01;
+    pub const SYSTEM_INTERRUPT_CONFIG_GPIO: u8 = 0x0A;
+    pub const SYSTEM_INTERRUPT_CLEAR: u8 = 0x0B;
+    pub const RESULT_INTERRUPT_STATUS: u8 = 0x13;
+    pub const RESULT_RANGE_STATUS: u8 = 0x14;
+    /// Range in mm, within the range status block
+    pub const RESULT_RANGE_MM: u8 = RESULT_RANGE_STATUS + 10;
+    pub const FINAL_RANGE_CONFIG_MIN_COUNT_RATE_RTN_LIMIT: u8 = 0x44;
+    pub const MSRC_CONFIG_CONTROL: u8 = 0x60;
+    pub const GPIO_HV_MUX_ACTIVE_HIGH: u8 = 0x84;
+    pub const IDENTIFICATION_MODEL_ID: u8 = 0xC0;
+    pub const GLOBAL_CONFIG_SPAD_ENABLES_REF_0: u8 = 0xB0;
+    pub const GLOBAL_CONFIG_REF_EN_START_SELECT: u8 = 0xB6;
+    pub const DYNAMIC_SPAD_NUM_REQUESTED_REF_SPAD: u8 = 0x4E;
+    pub const DYNAMIC_SPAD_REF_EN_START_OFFSET: u8 = 0x4F;
+    pub const STOP_VARIABLE: u8 = 0x91;
+
+    pub const MODEL_ID: u8 = 0xEE;
+    /// Range reported when there is no target
+    pub const OUT_OF_RANGE_MM: u16 = 8190;
+    /// Device range status of a valid measurement
+    pub const STATUS_RANGE_VALID: u8 = 11;
+
+    /// Undocumented settings from ST's API, written in order during initialization
+    pub const TUNING: [(u8, u8); 80] = [
+        (0xFF, 0x01),
+        (0x00, 0x00),
+        (0xFF, 0x00),
+        (0x09, 0x00),
+        (0x10, 0x00),
+        (0x11, 0x00),
+        (0x24, 0x01),
+        (0x25, 0xFF),
+        (0x75, 0x00),
+        (0xFF, 0x01),
+        (0x4E, 0x2C),
+        (0x48, 0x00),
+        (0x30, 0x20),
+        (0xFF, 0x00),
+        (0x30, 0x09),
+        (0x54, 0x00),
+        (0x31, 0x04),
+        (0x32, 0x03),
+        (0x40, 0x83),
+        (0x46, 0x25),
+        (0x60, 0x00),
+        (0x27, 0x00),
+        (0x50, 0x06),
+        (0x51, 0x00),
+        (0x52, 0x96),
+        (0x56, 0x08),
+        (0x57, 0x30),
+        (0x61, 0x00),
+        (0x62, 0x00),
+        (0x64, 0x00),
+        (0x65, 0x00),
+        (0x66, 0xA0),
+        (0xFF, 0x01),
+        (0x22, 0x32),
+        (0x47, 0x14),
+        (0x49, 0xFF),
+        (0x4A, 0x00),
+        (0xFF, 0x00),
+        (0x7A, 0x0A),
+        (0x7B, 0x00),
+        (0x78, 0x21),
+        (0xFF, 0x01),
+        (0x23, 0x34),
+        (0x42, 0x00),
+        (0x44, 0xFF),
+        (0x45, 0x26),
+        (0x46, 0x05),
+        (0x40, 0x40),
+        (0x0E, 0x06),
+        (0x20, 0x1A),
+        (0x43, 0x40),
+        (0xFF, 0x00),
+        (0x34, 0x03),
+        (0x35, 0x44),
+        (0xFF, 0x01),
+        (0x31, 0x04),
+        (0x4B, 0x09),
+        (0x4C, 0x05),
+        (0x4D, 0x04),
+        (0xFF, 0x00),
+        (0x44, 0x00),
+        (0x45, 0x20),
+        (0x47, 0x08),
+        (0x48, 0x28),
+        (0x67, 0x00),
+        (0x70, 0x04),
+        (0x71, 0x01),
+        (0x72, 0xFE),
+        (0x76, 0x00),
+        (0x77, 0x00),
+        (0xFF, 0x01),
+        (0x0D, 0x01),
+        (0xFF, 0x00),
+        (0x80, 0x01),
+        (0x01, 0xF8),
+        (0xFF, 0x01),
+        (0x8E, 0x01),
+        (0x00, 0x01),
+        (0xFF, 0x00),
+        (0x80, 0x00),
+    ];
+}
+
+/// ST VL53L0X time-of-flight sensor, ranging continuously up to about 2 m with its default
+/// 33 ms timing budget
+pub struct Vl53l0x<I: I2c, D: DelayNs> {
+    bus: TofBus<I, D>,
+    buffer: (f64, bool),
+}
+
+impl<I: I2c, D: DelayNs> Vl53l0x<I, D> {
+    /// Initializes the sensor at `address` and starts continuous ranging
+    pub fn new(i2c: I, delay: D, address: u8) -> Result<Self, PictorusError> {
+        let mut sensor = Self {
+            bus: TofBus {
+                i2c,
+                delay,
+                address,
+            },
+            buffer: (0.0, false),
+        };
+        sensor.init()?;
+        Ok(sensor)
+    }
+
+    /// Writes the (register, value) pairs in order
+    fn write_all(&mut self, writes: &[(u8, u8)]) -> Result<(), RangefinderError> {
+        writes
+            .iter()
+            .try_for_each(|(register, value)| self.bus.write_u8(*register, *value))
+    }
+
+    /// Reads the value ST's API calls the stop variable, needed to start ranging
+    fn read_stop_variable(&mut self) -> Result<u8, RangefinderError> {
+        self.write_all(&[(0x88, 0x00), (0x80, 0x01), (0xFF, 0x01), (0x00, 0x00)])?;
+        let stop_variable = self.bus.read_u8(vl53l0x::STOP_VARIABLE)?;
+        self.write_all(&[(0x00, 0x01), (0xFF, 0x00), (0x80, 0x00)])?;
+        Ok(stop_variable)
+    }
+
+    /// Reads the factory reference SPAD count and type from the sensor's NVM
+    fn read_spad_info(&mut self) -> Result<(u8, bool), RangefinderError> {
+        self.write_all(&[(0x80, 0x01), (0xFF, 0x01), (0x00, 0x00), (0xFF, 0x06)])?;
+        let value = self.bus.read_u8(0x83)?;
+        self.bus.write_u8(0x83, value | 0x04)?;
+        self.write_all(&[
+            (0xFF, 0x07),
+            (0x81, 0x01),
+            (0x80, 0x01),
+            (0x94, 0x6B),
+            (0x83, 0x00),
+        ])?;
+        self.bus.wait(|bus| Ok(bus.read_u8(0x83)? != 0))?;
+        self.bus.write_u8(0x83, 0x01)?;
+        let info = self.bus.read_u8(0x92)?;
+        self.write_all(&[(0x81, 0x00), (0xFF, 0x06)])?;
+        let value = self.bus.read_u8(0x83)?;
+        self.bus.write_u8(0x83, value & !0x04)?;
+        self.write_all(&[(0xFF, 0x01), (0x00, 0x01), (0xFF, 0x00), (0x80, 0x00)])?;
+        Ok((info & 0x7F, info & 0x80 != 0))
+    }
+
+    /// Enables the first `count` good reference SPADs, starting past the first 12 (the
+    /// non-aperture ones) for aperture SPADs
+    fn set_reference_spads(&mut self, count: u8, aperture: bool) -> Result<(), RangefinderError> {
+        let mut map = [0; 6];
+        self.bus
+            .read(&[vl53l0x::GLOBAL_CONFIG_SPAD_ENABLES_REF_0], &mut map)?;
+        self.write_all(&[
+            (0xFF, 0x01),
+            (vl53l0x::DYNAMIC_SPAD_REF_EN_START_OFFSET, 0x00),
+            (vl53l0x::DYNAMIC_SPAD_NUM_REQUESTED_REF_SPAD, 0x2C),
+            (0xFF, 0x00),
+            (vl53l0x::GLOBAL_CONFIG_REF_EN_START_SELECT, 0xB4),
+        ])?;
+
+        let first = if aperture { 12 } else { 0 };
+        let mut enabled = 0;
+        for spad in 0..48 {
+            let bit = 1 << (spad % 8);
+            if spad < first || enabled == count {
+                map[spad / 8] &= !bit;
+            } else if map[spad / 8] & bit != 0 {
+                enabled += 1;
+            }
+        }
+        let mut bytes = [0; 7];
+        bytes[0] = vl53l0x::GLOBAL_CONFIG_SPAD_ENABLES_REF_0;
+        bytes[1..].copy_from_slice(&map);
+        self.bus.write(&bytes)
+    }
+
+    /// Runs one of the reference calibrations, `vhv_init` selecting VHV (0x40) or phase (0x00)
+    fn calibrate(&mut self, sequence: u8, vhv_init: u8) -> Result<(), RangefinderError> {
+        self.bus
+            .write_u8(vl53l0x::SYSTEM_SEQUENCE_CONFIG, sequence)?;
+        self.bus
+            .write_u8(vl53l0x::SYSRANGE_START, 0x01 | vhv_init)?;
+        self.bus
+            .wait(|bus| Ok(bus.read_u8(vl53l0x::RESULT_INTERRUPT_STATUS)? & 0x07 != 0))?;
+        self.bus.write_u8(vl53l0x::SYSTEM_INTERRUPT_CLEAR, 0x01)?;
+        self.bus.write_u8(vl53l0x::SYSRANGE_START, 0x00)
+    }
+
+    fn init(&mut self) -> Result<(), RangefinderError> {
+        let model = self.bus.read_u8(vl53l0x::IDENTIFICATION_MODEL_ID)?;
+        if model != vl53l0x::MODEL_ID {
+            return Err(RangefinderError::UnknownDevice(model.into()));
+        }
+        let stop_variable = self.read_stop_variable()?;
+
+        // Disables the signal rate checks of the MSRC and pre-range steps, and sets the final
+        // range signal rate limit to 0.25 MCPS (Q9.7)
+        let value = self.bus.read_u8(vl53l0x::MSRC_CONFIG_CONTROL)?;
+        self.bus
+            .write_u8(vl53l0x::MSRC_CONFIG_CONTROL, value | 0x12)?;
+        self.bus.write(&[
+            vl53l0x::FINAL_RANGE_CONFIG_MIN_COUNT_RATE_RTN_LIMIT,
+            0x00,
+            0x20,
+        ])?;
+        self.bus.write_u8(vl53l0x::SYSTEM_SEQUENCE_CONFIG, 0xFF)?;
+
+        let (count, aperture) = self.read_spad_info()?;
+        self.set_reference_spads(count, aperture)?;
+        self.write_all(&vl53l0x::TUNING)?;
+
+        // New sample ready interrupt, active low
+        self.bus
+            .write_u8(vl53l0x::SYSTEM_INTERRUPT_CONFIG_GPIO, 0x04)?;
+        let value = self.bus.read_u8(vl53l0x::GPIO_HV_MUX_ACTIVE_HIGH)?;
+        self.bus
+            .write_u8(vl53l0x::GPIO_HV_MUX_ACTIVE_HIGH, value & !0x10)?;
+        self.bus.write_u8(vl53l0x::SYSTEM_INTERRUPT_CLEAR, 0x01)?;
+
+        self.calibrate(0x01, 0x40)?;
+        self.calibrate(0x02, 0x00)?;
+        // Final range, DSS, pre-range and MSRC steps, without TCC
+        self.bus.write_u8(vl53l0x::SYSTEM_SEQUENCE_CONFIG, 0xE8)?;
+
+        // Back-to-back continuous ranging
+        self.write_all(&[
+            (0x80, 0x01),
+            (0xFF, 0x01),
+            (0x00, 0x00),
+            (vl53l0x::STOP_VARIABLE, stop_variable),
+            (0x00, 0x01),
+            (0xFF, 0x00),
+            (0x80, 0x00),
+            (vl53l0x::SYSRANGE_START, 0x02),
+        ])
+    }
+
+    /// Reads a measurement if one is ready, returning (distance in meters, valid)
+    pub fn read(&mut self) -> Result<Option<(f64, bool)>, RangefinderError> {
+        if self.bus.read_u8(vl53l0x::RESULT_INTERRUPT_STATUS)? & 0x07 == 0 {
+            return Ok(None);
+        }
+        let status = self.bus.read_u8(vl53l0x::RESULT_RANGE_STATUS)? >> 3 & 0x0F;
+        let range_mm = self.bus.read_u16(vl53l0x::RESULT_RANGE_MM)?;
+        self.bus.write_u8(vl53l0x::SYSTEM_INTERRUPT_CLEAR, 0x01)?;
+        let valid = status == vl53l0x::STATUS_RANGE_VALID && range_mm < vl53l0x::OUT_OF_RANGE_MM;
+        Ok(Some((f64::from(range_mm) / 1000.0, valid)))
+    }
+}
+
+impl<I: I2c, D: DelayNs> InputBlock for Vl53l0x<I, D> {
+    type Output = (f64, bool);
+    type Parameters = RangefinderParams;
+
+    fn input(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        match self.read() {
+            Ok(Some((distance, true))) => self.buffer = (distance, true),
+            Ok(Some((_, false))) => self.buffer.1 = false,
+            Ok(None) => {}
+            Err(err) => {
+                warn!("Failed to read VL53L0X range: {err:?}");
+                self.buffer.1 = false;
+            }
+        }
+        self.buffer
+    }
+}
+
+mod vl53l1x {
+    pub const VHV_CONFIG_TIMEOUT_MACROP_LOOP_BOUND: u16 = 0x0008;
+    pub const VHV_CONFIG_INIT: u16 = 0x000B;
+    /// First register of the default configuration
+    pub const DEFAULT_CONFIG_START: u16 = 0x002D;
+    pub const GPIO_HV_MUX_CTRL: u16 = 0x0030;
+    pub const GPIO_TIO_HV_STATUS: u16 = 0x0031;
+    pub const SYSTEM_INTERRUPT_CLEAR: u16 = 0x0086;
+    pub const SYSTEM_MODE_START: u16 = 0x0087;
+    pub const RESULT_RANGE_STATUS: u16 = 0x0089;
+    pub const RESULT_RANGE_MM: u16 = 0x0096;
+    pub const FIRMWARE_SYSTEM_STATUS: u16 = 0x00E5;
+    pub const IDENTIFICATION_MODEL_ID: u16 = 0x010F;
+
+    pub const MODEL_ID: u16 = 0xEACC;
+
+    /// Default configuration from ST's ultra lite driver, for registers 0x2D to 0x87: long
+    /// distance mode with a 100 ms timing budget
+    pub const DEFAULT_CONFIG: [u8; 91] = [
+        0x00, 0x00, 0x00, 0x01, 0x02, 0x00, 0x02, 0x08, 0x00, 0x08, 0x10, 0x01, 0x01, 0x00, 0x00,
+        0x00, 0x00, 0xFF, 0x00, 0x0F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20, 0x0B, 0x00, 0x00, 0x02,
+        0x0A, 0x21, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0xC8, 0x00, 0x00, 0x38, 0xFF, 0x01,
+        0x00, 0x08, 0x00, 0x00, 0x01, 0xCC, 0x0F, 0x01, 0xF1, 0x0D, 0x01, 0x68, 0x00, 0x80, 0x08,
+        0xB8, 0x00, 0x00, 0x00, 0x00, 0x0F, 0x89, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+        0x0F, 0x0D, 0x0E, 0x0E, 0x00, 0x00, 0x02, 0xC7, 0xFF, 0x9B, 0x00, 0x00, 0x00, 0x01, 0x00,
+        0x00,
+    ];
+
+    /// Maps the raw range status to ST's range status, where 0 is a valid measurement
+    pub const RANGE_STATUS: [u8; 24] = [
+        255, 255, 255, 5, 2, 4, 1, 7, 3, 0, 255, 255, 9, 13, 255, 255, 255, 255, 10, 6, 255, 255,
+        11, 12,
+    ];
+}
+
+/// ST VL53L1X time-of-flight sensor, ranging continuously up to about 4 m in long distance mode
+/// with a 100 ms timing budget
+pub struct Vl53l1x<I: I2c, D: DelayNs> {
+    bus: TofBus<I, D>,
+    /// GPIO level signalling a measurement is ready
+    ready_level: u8,
+    buffer: (f64, bool),
+}
+
+impl<I: I2c, D: DelayNs> Vl53l1x<I, D> {
+    /// Initializes the sensor at `address` and starts continuous ranging
+    pub fn new(i2c: I, delay: D, address: u8) -> Result<Self, PictorusError> {
+        let mut sensor = Self {
+            bus: TofBus {
+                i2c,
+                delay,
+                address,
+            },
+            ready_level: 1,
+            buffer: (0.0, false),
+        };
+        sensor.init()?;
+        Ok(sensor)
+    }
+
+    fn is_ready(&mut self) -> Result<bool, RangefinderError> {
+        let status = self.bus.read_u8_wide(vl53l1x::GPIO_TIO_HV_STATUS)?;
+        Ok(status & 0x01 == self.ready_level)
+    }
+
+    fn init(&mut self) -> Result<(), RangefinderError> {
+        let model = self.bus.read_u16_wide(vl53l1x::IDENTIFICATION_MODEL_ID)?;
+        if model != vl53l1x::MODEL_ID {
+            return Err(RangefinderError::UnknownDevice(model));
+        }
+        self.bus
+            .wait(|bus| Ok(bus.read_u8_wide(vl53l1x::FIRMWARE_SYSTEM_STATUS)? & 0x01 != 0))?;
+
+        let mut bytes = [0; 2 + vl53l1x::DEFAULT_CONFIG.len()];
+        bytes[..2].copy_from_slice(&vl53l1x::DEFAULT_CONFIG_START.to_be_bytes());
+        bytes[2..].copy_from_slice(&vl53l1x::DEFAULT_CONFIG);
+        self.bus.write(&bytes)?;
+        let mux = self.bus.read_u8_wide(vl53l1x::GPIO_HV_MUX_CTRL)?;
+        self.ready_level = u8::from(mux & 0x10 == 0);
+
+        // A first measurement completes the VHV calibration
+        self.bus.write_u8_wide(vl53l1x::SYSTEM_MODE_START, 0x40)?;
+        self.bus.wait(|bus| {
+            let status = bus.read_u8_wide(vl53l1x::GPIO_TIO_HV_STATUS)?;
+            let mux = bus.read_u8_wide(vl53l1x::GPIO_HV_MUX_CTRL)?;
+            Ok(status & 0x01 == u8::from(mux & 0x10 == 0))
+        })?;
+        self.bus
+            .write_u8_wide(vl53l1x::SYSTEM_INTERRUPT_CLEAR, 0x01)?;
+        self.bus.write_u8_wide(vl53l1x::SYSTEM_MODE_START, 0x00)?;
+        // Two bounds for the VHV search, and starting it from the calibrated value
+        self.bus
+            .write_u8_wide(vl53l1x::VHV_CONFIG_TIMEOUT_MACROP_LOOP_BOUND, 0x09)?;
+        self.bus.write_u8_wide(vl53l1x::VHV_CONFIG_INIT, 0x00)?;
+
+        self.bus.write_u8_wide(vl53l1x::SYSTEM_MODE_START, 0x40)
+    }
+
+    /// Reads a measurement if one is ready, returning (distance in meters, valid)
+    pub fn read(&mut self) -> Result<Option<(f64, bool)>, RangefinderError> {
+        if !self.is_ready()? {
+            return Ok(None);
+        }
+        let status = self.bus.read_u8_wide(vl53l1x::RESULT_RANGE_STATUS)? & 0x1F;
+        let range_mm = self.bus.read_u16_wide(vl53l1x::RESULT_RANGE_MM)?;
+        self.bus
+            .write_u8_wide(vl53l1x::SYSTEM_INTERRUPT_CLEAR, 0x01)?;
+        let valid = vl53l1x::RANGE_STATUS.get(usize::from(status)) == Some(&0);
+        Ok(Some((f64::from(range_mm) / 1000.0, valid)))
+    }
+}
+
+impl<I: I2c, D: DelayNs> InputBlock for Vl53l1x<I, D> {
+    type Output = (f64, bool);
+    type Parameters = RangefinderParams;
+
+    fn input(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        match self.read() {
+            Ok(Some((distance, true))) => self.buffer = (distance, true),
+            Ok(Some((_, false))) => self.buffer.1 = false,
+            Ok(None) => {}
+            Err(err) => {
+                warn!("Failed to read VL53L1X range: {err:?}");
+                self.buffer.1 = false;
+            }
+        }
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::BTreeMap;
+    use core::cell::Cell;
+    use core::convert::Infallible;
+    use embedded_hal::i2c::{ErrorType as I2cErrorType, Operation};
+    use embedded_time::Instant;
+    use embedded_time::rate::Fraction;
+
+    struct StubDelay;
+
+    impl DelayNs for StubDelay {
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    /// Microsecond clock that advances every time it's read
+    struct StubClock<'a>(&'a Cell<u64>);
+
+    impl Clock for StubClock<'_> {
+        type T = u64;
+        const SCALING_FACTOR: Fraction = Fraction::new(1, 1_000_000);
+
+        fn try_now(&self) -> Result<Instant<Self>, embedded_time::clock::Error> {
+            self.0.set(self.0.get() + 10);
+            Ok(Instant::new(self.0.get()))
+        }
+    }
+
+    /// Echo pin that is high between two times of the clock
+    struct StubEcho<'a> {
+        time: &'a Cell<u64>,
+        pulse: (u64, u64),
+    }
+
+    impl embedded_hal::digital::ErrorType for StubEcho<'_> {
+        type Error = Infallible;
+    }
+
+    impl InputPin for StubEcho<'_> {
+        fn is_high(&mut self) -> Result<bool, Self::Error> {
+            Ok((self.pulse.0..self.pulse.1).contains(&self.time.get()))
+        }
+
+        fn is_low(&mut self) -> Result<bool, Self::Error> {
+            self.is_high().map(|high| !high)
+        }
+    }
+
+    struct StubTrigger;
+
+    impl embedded_hal::digital::ErrorType for StubTrigger {
+        type Error = Infallible;
+    }
+
+    impl OutputPin for StubTrigger {
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    /// Register map of a sensor, with register addresses one or two bytes wide. Writes store
+    /// bytes at consecutive addresses, and reads return them, or the `fixed` value of a
+    /// register if it has one.
+    struct StubSensor {
+        wide: bool,
+        registers: BTreeMap<u16, u8>,
+        fixed: BTreeMap<u16, u8>,
+    }
+
+    impl StubSensor {
+        fn new(wide: bool, fixed: &[(u16, u8)]) -> Self {
+            Self {
+                wide,
+                registers: BTreeMap::new(),
+                fixed: fixed.iter().copied().collect(),
+            }
+        }
+
+        fn split<'a>(&self, bytes: &'a [u8]) -> (u16, &'a [u8]) {
+            if self.wide {
+                (u16::from_be_bytes([bytes[0], bytes[1]]), &bytes[2..])
+            } else {
+                (bytes[0].into(), &bytes[1..])
+            }
+        }
+    }
+
+    impl I2cErrorType for StubSensor {
+        type Error = Infallible;
+    }
+
+    impl I2c for StubSensor {
+        fn transaction(
+            &mut self,
+            address: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), Self::Error> {
+            assert_eq!(address, TOF_DEFAULT_ADDRESS);
+            let mut register = 0;
+            for operation in operations {
+                match operation {
+                    Operation::Write(bytes) => {
+                        let (start, data) = self.split(bytes);
+                        register = start;
+                        for (offset, byte) in data.iter().enumerate() {
+                            self.registers.insert(start + offset as u16, *byte);
+                        }
+                    }
+                    Operation::Read(bytes) => {
+                        for (offset, byte) in bytes.iter_mut().enumerate() {
+                            let address = register + offset as u16;
+                            *byte = self
+                                .fixed
+                                .get(&address)
+                                .or(self.registers.get(&address))
+                                .copied()
+                                .unwrap_or(0);
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_hc_sr04() {
+        let time = Cell::new(0);
+        // A 5.83 ms echo, for a target 1 m away
+        let echo = StubEcho {
+            time: &time,
+            pulse: (500, 6_331),
+        };
+        let mut sensor = HcSr04::new(StubTrigger, echo, StubClock(&time), StubDelay, 2.0);
+        let context = crate::can_demux::tests::StubContext(core::time::Duration::ZERO);
+
+        let (distance, valid) = sensor.input(&RangefinderParams::new(), &context);
+        assert!((distance - 1.0).abs() < 0.005);
+        assert!(valid);
+
+        // Without an echo the last distance is held
+        sensor.echo.pulse = (0, 0);
+        time.set(0);
+        assert_eq!(
+            sensor.input(&RangefinderParams::new(), &context),
+            (distance, false)
+        );
+
+        // An echo longer than the maximum range is out of range
+        sensor.echo.pulse = (100, 20_000);
+        time.set(0);
+        assert_eq!(sensor.measure(), Ok(None));
+    }
+
+    #[test]
+    fn test_vl53l0x() {
+        let i2c = StubSensor::new(
+            false,
+            &[
+                (vl53l0x::IDENTIFICATION_MODEL_ID.into(), vl53l0x::MODEL_ID),
+                (0x83, 0x10),
+                (0x92, 0x85),
+                (vl53l0x::RESULT_INTERRUPT_STATUS.into(), 0x04),
+                (vl53l0x::RESULT_RANGE_STATUS.into(), 11 << 3),
+                (vl53l0x::RESULT_RANGE_MM.into(), 0x01),
+                (vl53l0x::RESULT_RANGE_MM as u16 + 1, 0x2C),
+            ],
+        );
+        let mut sensor = Vl53l0x::new(i2c, StubDelay, TOF_DEFAULT_ADDRESS).unwrap();
+        let registers = &sensor.bus.i2c.registers;
+        assert_eq!(registers[&vl53l0x::SYSRANGE_START.into()], 0x02);
+        assert_eq!(registers[&vl53l0x::SYSTEM_SEQUENCE_CONFIG.into()], 0xE8);
+        assert_eq!(sensor.read(), Ok(Some((0.3, true))));
+
+        sensor
+            .bus
+            .i2c
+            .fixed
+            .insert(vl53l0x::RESULT_RANGE_STATUS.into(), 4 << 3);
+        assert_eq!(sensor.read(), Ok(Some((0.3, false))));
+
+        let i2c = StubSensor::new(false, &[]);
+        assert!(Vl53l0x::new(i2c, StubDelay, TOF_DEFAULT_ADDRESS).is_err());
+    }
+
+    #[test]
+    fn test_vl53l1x() {
+        let i2c = StubSensor::new(
+            true,
+            &[
+                (vl53l1x::IDENTIFICATION_MODEL_ID, 0xEA),
+                (vl53l1x::IDENTIFICATION_MODEL_ID + 1, 0xCC),
+                (vl53l1x::FIRMWARE_SYSTEM_STATUS, 0x01),
+                (vl53l1x::GPIO_TIO_HV_STATUS, 0x01),
+                (vl53l1x::RESULT_RANGE_STATUS, 0x09),
+                (vl53l1x::RESULT_RANGE_MM, 0x04),
+                (vl53l1x::RESULT_RANGE_MM + 1, 0xD2),
+            ],
+        );
+        let mut sensor = Vl53l1x::new(i2c, StubDelay, TOF_DEFAULT_ADDRESS).unwrap();
+        let registers = &sensor.bus.i2c.registers;
+        assert_eq!(registers[&vl53l1x::SYSTEM_MODE_START], 0x40);
+        // The default configuration sets the interrupt active high
+        assert_eq!(sensor.ready_level, 1);
+        assert_eq!(sensor.read(), Ok(Some((1.234, true))));
+
+        sensor
+            .bus
+            .i2c
+            .fixed
+            .insert(vl53l1x::GPIO_TIO_HV_STATUS, 0x00);
+        assert_eq!(sensor.read(), Ok(None));
+    }
+}
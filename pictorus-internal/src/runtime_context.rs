@@ -25,6 +25,15 @@ impl RuntimeContext {
         }
     }
 
+    /// Create a context whose first tick is at `app_time_us`, so it has no timestep yet
+    pub fn starting_at(fundamental_timestep_us: u64, app_time_us: u64) -> Self {
+        RuntimeContext {
+            app_time_us,
+            fundamental_timestep_us,
+            last_app_time_us: None,
+        }
+    }
+
     pub fn update_app_time(&mut self, app_time_us: u64) {
         self.last_app_time_us = Some(self.app_time_us);
         self.app_time_us = app_time_us;
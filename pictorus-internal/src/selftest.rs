@@ -0,0 +1,316 @@
+//! Power-on self tests, run once before the main loop.
+//!
+//! Protocol drivers provide [`SelfTest`] checks for the hardware they talk to (e.g. a sensor's
+//! WHO_AM_I register, a bus loopback, or wiggling an actuator and watching its feedback), which
+//! are registered with a [`SelfTestRunner`]. Running them logs each result and produces a
+//! [`SelfTestReport`], whose block outputs `(passed, failed, failed mask, critical failure)` so
+//! the model can react to them. Bit `i` of the failed mask is set if the `i`th check registered
+//! failed. When a critical check fails, the app should hold its outputs in their safe state
+//! (see [`ShutdownSequence`](crate::execution_controller::ShutdownSequence)) instead of running
+//! the models.
+use embedded_hal::i2c::I2c;
+use pictorus_traits::{Context, InputBlock, PassBy};
+
+use crate::error::{ErrorKind, PictorusError};
+
+const ERR_TYPE: &str = "SelfTest";
+
+/// Most checks a runner can hold, one per bit of the failed mask
+pub const MAX_SELF_TESTS: usize = u32::BITS as usize;
+
+/// A check of a device or bus, run once at startup
+pub trait SelfTest {
+    /// Name the result is logged under
+    fn name(&self) -> &str;
+
+    /// Whether the app can't run safely if this check fails
+    fn critical(&self) -> bool {
+        true
+    }
+
+    fn run(&mut self) -> Result<(), PictorusError>;
+}
+
+/// A check run by a closure, for checks specific to an app or a driver, such as a bus
+/// loopback or an actuator wiggle
+pub struct SelfTestFn<F> {
+    name: &'static str,
+    critical: bool,
+    check: F,
+}
+
+impl<F: FnMut() -> Result<(), PictorusError>> SelfTestFn<F> {
+    pub fn new(name: &'static str, critical: bool, check: F) -> Self {
+        Self {
+            name,
+            critical,
+            check,
+        }
+    }
+}
+
+impl<F: FnMut() -> Result<(), PictorusError>> SelfTest for SelfTestFn<F> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn critical(&self) -> bool {
+        self.critical
+    }
+
+    fn run(&mut self) -> Result<(), PictorusError> {
+        (self.check)()
+    }
+}
+
+/// Checks that an I2C device answers with the expected ID from its WHO_AM_I register, which
+/// catches missing, miswired and wrong parts
+pub struct WhoAmICheck<'a, I: I2c> {
+    name: &'static str,
+    bus: &'a mut I,
+    address: u8,
+    register: u8,
+    expected: u8,
+    critical: bool,
+}
+
+impl<'a, I: I2c> WhoAmICheck<'a, I> {
+    pub fn new(
+        name: &'static str,
+        bus: &'a mut I,
+        address: u8,
+        register: u8,
+        expected: u8,
+    ) -> Self {
+        Self {
+            name,
+            bus,
+            address,
+            register,
+            expected,
+            critical: true,
+        }
+    }
+
+    /// Let the app run even if the device doesn't answer as expected
+    pub fn non_critical(mut self) -> Self {
+        self.critical = false;
+        self
+    }
+}
+
+impl<I: I2c> SelfTest for WhoAmICheck<'_, I> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn critical(&self) -> bool {
+        self.critical
+    }
+
+    fn run(&mut self) -> Result<(), PictorusError> {
+        let mut id = [0];
+        self.bus
+            .write_read(self.address, &[self.register], &mut id)
+            .map_err(|_| {
+                PictorusError::new(ErrorKind::Io, ERR_TYPE, "Failed to read WHO_AM_I register")
+            })?;
+        if id[0] != self.expected {
+            return Err(PictorusError::new(
+                ErrorKind::NotFound,
+                ERR_TYPE,
+                "Unexpected WHO_AM_I value",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Runs the registered [`SelfTest`]s in order, see the [module docs](self)
+pub struct SelfTestRunner<'a, const N: usize> {
+    checks: heapless::Vec<&'a mut dyn SelfTest, N>,
+}
+
+impl<const N: usize> Default for SelfTestRunner<'_, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, const N: usize> SelfTestRunner<'a, N> {
+    pub fn new() -> Self {
+        Self {
+            checks: heapless::Vec::new(),
+        }
+    }
+
+    pub fn register(&mut self, check: &'a mut dyn SelfTest) -> Result<(), PictorusError> {
+        if self.checks.len() >= MAX_SELF_TESTS || self.checks.push(check).is_err() {
+            return Err(PictorusError::new(
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
+                "Too many self tests registered",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Runs every check, logging its result
+    pub fn run(&mut self) -> SelfTestReport {
+        let mut report = SelfTestReport::default();
+        for (index, check) in self.checks.iter_mut().enumerate() {
+            match check.run() {
+                Ok(()) => {
+                    log::info!("Self test {} passed", check.name());
+                    report.passed += 1;
+                }
+                Err(err) if check.critical() => {
+                    log::error!("Critical self test {} failed: {err}", check.name());
+                    report.record_failure(index, true);
+                }
+                Err(err) => {
+                    log::warn!("Self test {} failed: {err}", check.name());
+                    report.record_failure(index, false);
+                }
+            }
+        }
+        if report.critical_failure {
+            log::error!(
+                "{} of {} self tests failed, holding outputs in their safe state",
+                report.failed,
+                report.passed + report.failed
+            );
+        }
+        report
+    }
+}
+
+/// Parameters for the self test block
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SelfTestParams {}
+
+impl SelfTestParams {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Results of the self tests, and the block that outputs them as `(passed, failed, failed
+/// mask, critical failure)`
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SelfTestReport {
+    pub passed: u32,
+    pub failed: u32,
+    /// Bit `i` is set if the `i`th check failed
+    pub failed_mask: u32,
+    /// Whether any critical check failed
+    pub critical_failure: bool,
+}
+
+impl SelfTestReport {
+    fn record_failure(&mut self, index: usize, critical: bool) {
+        self.failed += 1;
+        self.failed_mask |= 1 << index;
+        self.critical_failure |= critical;
+    }
+
+    /// Whether the app should hold its outputs in their safe state instead of running
+    pub fn should_hold(&self) -> bool {
+        self.critical_failure
+    }
+}
+
+impl InputBlock for SelfTestReport {
+    type Output = (f64, f64, f64, bool);
+    type Parameters = SelfTestParams;
+
+    fn input(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        (
+            f64::from(self.passed),
+            f64::from(self.failed),
+            f64::from(self.failed_mask),
+            self.critical_failure,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal::i2c::{ErrorType, Operation};
+
+    struct FakeSensor {
+        id: u8,
+    }
+
+    impl ErrorType for FakeSensor {
+        type Error = core::convert::Infallible;
+    }
+
+    impl I2c for FakeSensor {
+        fn transaction(
+            &mut self,
+            _address: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), Self::Error> {
+            for operation in operations {
+                if let Operation::Read(buffer) = operation {
+                    buffer.fill(self.id);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn failing() -> Result<(), PictorusError> {
+        Err(PictorusError::new(ErrorKind::Io, ERR_TYPE, "No loopback"))
+    }
+
+    #[test]
+    fn test_self_test_runner() {
+        let mut imu = FakeSensor { id: 0x68 };
+        let mut wrong_part = FakeSensor { id: 0x12 };
+        let mut who_am_i = WhoAmICheck::new("imu", &mut imu, 0x68, 0x75, 0x68);
+        let mut wrong_who_am_i =
+            WhoAmICheck::new("baro", &mut wrong_part, 0x76, 0xD0, 0x58).non_critical();
+        let mut loopback = SelfTestFn::new("can loopback", false, failing);
+
+        let mut runner = SelfTestRunner::<3>::new();
+        runner.register(&mut who_am_i).unwrap();
+        runner.register(&mut wrong_who_am_i).unwrap();
+        runner.register(&mut loopback).unwrap();
+        let mut report = runner.run();
+        assert_eq!(
+            report,
+            SelfTestReport {
+                passed: 1,
+                failed: 2,
+                failed_mask: 0b110,
+                critical_failure: false,
+            }
+        );
+        assert!(!report.should_hold());
+        let context = crate::RuntimeContext::new(1_000);
+        assert_eq!(
+            report.input(&SelfTestParams::new(), &context),
+            (1.0, 2.0, 6.0, false)
+        );
+    }
+
+    #[test]
+    fn test_self_test_critical_failure() {
+        let mut wiggle = SelfTestFn::new("servo wiggle", true, failing);
+        let mut extra = SelfTestFn::new("extra", true, || Ok(()));
+        let mut runner = SelfTestRunner::<1>::new();
+        runner.register(&mut wiggle).unwrap();
+        assert!(runner.register(&mut extra).is_err());
+
+        let report = runner.run();
+        assert_eq!((report.failed, report.failed_mask), (1, 1));
+        assert!(report.should_hold());
+    }
+}
@@ -0,0 +1,332 @@
+//! Drivers for chains of 74HC595 (serial in, parallel out) and 74HC165 (parallel in, serial out)
+//! shift registers, for controlling dozens of digital lines from a few pins.
+//!
+//! The registers can be clocked by an SPI peripheral (mode 0, MSB first) or by bit-banging two
+//! GPIO pins, and both are generic over `embedded-hal`, so the same drivers run on Linux and on
+//! microcontrollers. Line `i` of a chain is bit `i % 8` (Q0..Q7 or D0..D7) of register `i / 8`,
+//! where register 0 is the one wired to the host.
+use embedded_hal::digital::{InputPin, OutputPin};
+use embedded_hal::spi::SpiBus;
+use heapless::Vec;
+use log::warn;
+use pictorus_traits::{Context, InputBlock, Matrix, OutputBlock, Pass, PassBy};
+
+/// Most registers a chain can have
+const MAX_REGISTERS: usize = 32;
+
+/// Errors returned while shifting data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShiftRegisterError {
+    Spi,
+    Pin,
+}
+
+/// Parameters for the shift register blocks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShiftRegisterParams {}
+
+impl ShiftRegisterParams {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Shifts bytes out to a chain, MSB first
+pub trait ShiftOut {
+    fn shift_out(&mut self, bytes: &[u8]) -> Result<(), ShiftRegisterError>;
+}
+
+/// Shifts bytes in from a chain, MSB first
+pub trait ShiftIn {
+    fn shift_in(&mut self, bytes: &mut [u8]) -> Result<(), ShiftRegisterError>;
+}
+
+/// Clocks a chain with an SPI peripheral
+pub struct SpiShift<B: SpiBus>(pub B);
+
+impl<B: SpiBus> ShiftOut for SpiShift<B> {
+    fn shift_out(&mut self, bytes: &[u8]) -> Result<(), ShiftRegisterError> {
+        self.0
+            .write(bytes)
+            .and_then(|_| self.0.flush())
+            .map_err(|_| ShiftRegisterError::Spi)
+    }
+}
+
+impl<B: SpiBus> ShiftIn for SpiShift<B> {
+    fn shift_in(&mut self, bytes: &mut [u8]) -> Result<(), ShiftRegisterError> {
+        self.0
+            .read(bytes)
+            .and_then(|_| self.0.flush())
+            .map_err(|_| ShiftRegisterError::Spi)
+    }
+}
+
+/// Clocks a chain by toggling GPIO pins. The data pin is an output for 74HC595 chains and an
+/// input for 74HC165 chains.
+pub struct GpioShift<C: OutputPin, D> {
+    pub clock: C,
+    pub data: D,
+}
+
+impl<C: OutputPin, D> GpioShift<C, D> {
+    fn pulse_clock(&mut self) -> Result<(), ShiftRegisterError> {
+        self.clock.set_high().map_err(|_| ShiftRegisterError::Pin)?;
+        self.clock.set_low().map_err(|_| ShiftRegisterError::Pin)
+    }
+}
+
+impl<C: OutputPin, D: OutputPin> ShiftOut for GpioShift<C, D> {
+    fn shift_out(&mut self, bytes: &[u8]) -> Result<(), ShiftRegisterError> {
+        for byte in bytes {
+            for bit in (0..8).rev() {
+                self.data
+                    .set_state((byte >> bit & 1 == 1).into())
+                    .map_err(|_| ShiftRegisterError::Pin)?;
+                self.pulse_clock()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<C: OutputPin, D: InputPin> ShiftIn for GpioShift<C, D> {
+    fn shift_in(&mut self, bytes: &mut [u8]) -> Result<(), ShiftRegisterError> {
+        for byte in bytes {
+            *byte = 0;
+            for _ in 0..8 {
+                // Each bit is already on the data pin before its clock edge
+                let high = self.data.is_high().map_err(|_| ShiftRegisterError::Pin)?;
+                *byte = *byte << 1 | u8::from(high);
+                self.pulse_clock()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn register_count(lines: usize) -> usize {
+    lines.div_ceil(8)
+}
+
+/// Chain of 74HC595 output registers, as an `OutputBlock` that sets its `N` lines from a
+/// boolean column vector. `latch` is the storage register clock (RCLK), so the outputs all
+/// change together once the chain has been shifted.
+pub struct Hc595<S: ShiftOut, L: OutputPin, const N: usize> {
+    shift: S,
+    latch: L,
+}
+
+impl<S: ShiftOut, L: OutputPin, const N: usize> Hc595<S, L, N> {
+    pub fn new(shift: S, mut latch: L) -> Self {
+        const {
+            assert!(
+                N > 0 && N <= MAX_REGISTERS * 8,
+                "Unsupported shift register chain"
+            );
+        }
+        latch.set_low().ok();
+        Self { shift, latch }
+    }
+
+    /// Shifts `lines` out to the chain and latches them
+    pub fn write(&mut self, lines: &[bool; N]) -> Result<(), ShiftRegisterError> {
+        let mut bytes: Vec<u8, MAX_REGISTERS> = Vec::new();
+        bytes.resize(register_count(N), 0).ok();
+        for (index, _) in lines.iter().enumerate().filter(|(_, line)| **line) {
+            bytes[index / 8] |= 1 << (index % 8);
+        }
+        // The first byte shifted ends up in the register furthest from the host
+        bytes.reverse();
+        self.shift.shift_out(&bytes)?;
+        self.latch.set_high().map_err(|_| ShiftRegisterError::Pin)?;
+        self.latch.set_low().map_err(|_| ShiftRegisterError::Pin)
+    }
+}
+
+impl<S: ShiftOut, L: OutputPin, const N: usize> OutputBlock for Hc595<S, L, N> {
+    type Inputs = Matrix<N, 1, bool>;
+    type Parameters = ShiftRegisterParams;
+
+    fn output(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) {
+        if let Err(err) = self.write(&inputs.data[0]) {
+            warn!("Failed to write shift register outputs: {err:?}");
+        }
+    }
+}
+
+/// Chain of 74HC165 input registers, as an `InputBlock` that outputs its `N` lines as a boolean
+/// column vector. `load` is the active low shift/load pin (SH/LD). If a read fails, the
+/// previous lines are held.
+pub struct Hc165<S: ShiftIn, L: OutputPin, const N: usize> {
+    shift: S,
+    load: L,
+    buffer: Matrix<N, 1, bool>,
+}
+
+impl<S: ShiftIn, L: OutputPin, const N: usize> Hc165<S, L, N> {
+    pub fn new(shift: S, mut load: L) -> Self {
+        const {
+            assert!(
+                N > 0 && N <= MAX_REGISTERS * 8,
+                "Unsupported shift register chain"
+            );
+        }
+        load.set_high().ok();
+        Self {
+            shift,
+            load,
+            buffer: Matrix::zeroed(),
+        }
+    }
+
+    /// Latches the parallel inputs and shifts them in
+    pub fn read(&mut self) -> Result<[bool; N], ShiftRegisterError> {
+        self.load.set_low().map_err(|_| ShiftRegisterError::Pin)?;
+        self.load.set_high().map_err(|_| ShiftRegisterError::Pin)?;
+        let mut bytes: Vec<u8, MAX_REGISTERS> = Vec::new();
+        bytes.resize(register_count(N), 0).ok();
+        self.shift.shift_in(&mut bytes)?;
+        Ok(core::array::from_fn(|index| {
+            bytes[index / 8] >> (index % 8) & 1 == 1
+        }))
+    }
+}
+
+impl<S: ShiftIn, L: OutputPin, const N: usize> InputBlock for Hc165<S, L, N> {
+    type Output = Matrix<N, 1, bool>;
+    type Parameters = ShiftRegisterParams;
+
+    fn input(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        match self.read() {
+            Ok(lines) => self.buffer.data[0] = lines,
+            Err(err) => warn!("Failed to read shift register inputs: {err:?}"),
+        }
+        self.buffer.as_by()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec as AllocVec;
+    use core::cell::RefCell;
+    use core::convert::Infallible;
+    use embedded_hal::digital::ErrorType;
+
+    /// Simulated chain of registers, recording bits clocked in and replaying bits to clock out
+    #[derive(Default)]
+    struct Chain {
+        clocked_in: AllocVec<bool>,
+        to_clock_out: AllocVec<bool>,
+        data: bool,
+        latches: usize,
+    }
+
+    enum Pin<'a> {
+        Clock(&'a RefCell<Chain>),
+        Data(&'a RefCell<Chain>),
+        Latch(&'a RefCell<Chain>),
+    }
+
+    impl ErrorType for Pin<'_> {
+        type Error = Infallible;
+    }
+
+    impl OutputPin for Pin<'_> {
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            if let Pin::Data(chain) = self {
+                chain.borrow_mut().data = false;
+            }
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            match self {
+                Pin::Clock(chain) => {
+                    let mut chain = chain.borrow_mut();
+                    let data = chain.data;
+                    chain.clocked_in.push(data);
+                    if !chain.to_clock_out.is_empty() {
+                        chain.to_clock_out.remove(0);
+                    }
+                }
+                Pin::Data(chain) => chain.borrow_mut().data = true,
+                Pin::Latch(chain) => chain.borrow_mut().latches += 1,
+            }
+            Ok(())
+        }
+    }
+
+    impl InputPin for Pin<'_> {
+        fn is_high(&mut self) -> Result<bool, Self::Error> {
+            let Pin::Data(chain) = self else {
+                return Ok(false);
+            };
+            Ok(chain
+                .borrow()
+                .to_clock_out
+                .first()
+                .copied()
+                .unwrap_or(false))
+        }
+
+        fn is_low(&mut self) -> Result<bool, Self::Error> {
+            self.is_high().map(|high| !high)
+        }
+    }
+
+    fn bits(bytes: &[u8]) -> AllocVec<bool> {
+        bytes
+            .iter()
+            .flat_map(|byte| (0..8).rev().map(move |bit| byte >> bit & 1 == 1))
+            .collect()
+    }
+
+    #[test]
+    fn test_hc595_gpio() {
+        let chain = RefCell::new(Chain::default());
+        let shift = GpioShift {
+            clock: Pin::Clock(&chain),
+            data: Pin::Data(&chain),
+        };
+        let mut registers = Hc595::<_, _, 12>::new(shift, Pin::Latch(&chain));
+
+        let mut lines = [false; 12];
+        lines[0] = true;
+        lines[7] = true;
+        lines[9] = true;
+        registers.write(&lines).unwrap();
+
+        // The second register's byte is shifted first, then the first one's
+        let chain = chain.borrow();
+        assert_eq!(chain.clocked_in, bits(&[0b0000_0010, 0b1000_0001]));
+        assert_eq!(chain.latches, 1);
+    }
+
+    #[test]
+    fn test_hc165_gpio() {
+        let chain = RefCell::new(Chain {
+            to_clock_out: bits(&[0b0100_0001, 0b0000_1000]),
+            ..Default::default()
+        });
+        let shift = GpioShift {
+            clock: Pin::Clock(&chain),
+            data: Pin::Data(&chain),
+        };
+        let mut registers = Hc165::<_, _, 16>::new(shift, Pin::Latch(&chain));
+
+        let lines = registers.read().unwrap();
+        let high: AllocVec<usize> = (0..16).filter(|index| lines[*index]).collect();
+        assert_eq!(high, [0, 6, 11]);
+    }
+}
@@ -0,0 +1,257 @@
+//! Ed25519 signature verification for data that must be authenticated before it's applied, such
+//! as parameter bundles or command uplinks.
+//!
+//! Blobs are signed offline with a private key that never leaves the ground station or build
+//! server, so targets only need the 32-byte public key. A signed blob is the payload followed by
+//! the 64-byte signature of the payload. Signatures are checked with strict verification, which
+//! rejects weak keys and malleable signatures.
+//!
+//! A valid signature only proves who produced the payload, not when: to stop an old uplink from
+//! being replayed, include a sequence number or timestamp in the signed payload and check it
+//! after verification.
+use ed25519_dalek::{Signature, VerifyingKey};
+
+use crate::error::{ErrorKind, PictorusError};
+
+const ERR_TYPE: &str = "SignedBlob";
+/// Length of an ed25519 public key
+pub const PUBLIC_KEY_BYTES: usize = 32;
+/// Length of an ed25519 signature, appended to the payload of a signed blob
+pub const SIGNATURE_BYTES: usize = 64;
+
+/// Reasons a signed blob is rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureError {
+    /// The public key isn't a valid ed25519 key
+    InvalidKey,
+    /// The blob is too short to contain a signature
+    Truncated,
+    /// The signature doesn't match the payload and public key
+    Mismatch,
+}
+
+impl From<SignatureError> for PictorusError {
+    fn from(err: SignatureError) -> Self {
+        match err {
+            SignatureError::InvalidKey => PictorusError::new(
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
+                "Invalid ed25519 public key",
+            ),
+            SignatureError::Truncated => PictorusError::new(
+                ErrorKind::InvalidData,
+                ERR_TYPE,
+                "Signed blob is shorter than a signature",
+            ),
+            SignatureError::Mismatch => PictorusError::new(
+                ErrorKind::InvalidData,
+                ERR_TYPE,
+                "Signature verification failed",
+            ),
+        }
+    }
+}
+
+/// Verifies blobs signed by the holder of a single private key
+#[derive(Debug, Clone)]
+pub struct BlobVerifier {
+    key: VerifyingKey,
+}
+
+impl BlobVerifier {
+    pub fn new(public_key: &[u8; PUBLIC_KEY_BYTES]) -> Result<Self, SignatureError> {
+        let key = VerifyingKey::from_bytes(public_key).map_err(|_| SignatureError::InvalidKey)?;
+        if key.is_weak() {
+            return Err(SignatureError::InvalidKey);
+        }
+        Ok(Self { key })
+    }
+
+    /// Creates a verifier from the public key as 64 hex characters
+    pub fn from_hex(public_key: &str) -> Result<Self, SignatureError> {
+        let public_key = public_key.trim();
+        if public_key.len() != 2 * PUBLIC_KEY_BYTES || !public_key.is_ascii() {
+            return Err(SignatureError::InvalidKey);
+        }
+        let mut key = [0; PUBLIC_KEY_BYTES];
+        for (byte, digits) in key.iter_mut().zip(public_key.as_bytes().chunks(2)) {
+            // Both digits are ASCII, so this is always valid UTF-8
+            let digits = core::str::from_utf8(digits).unwrap();
+            *byte = u8::from_str_radix(digits, 16).map_err(|_| SignatureError::InvalidKey)?;
+        }
+        Self::new(&key)
+    }
+
+    /// Checks that `signature` is a valid signature of `payload`
+    pub fn verify_detached(&self, payload: &[u8], signature: &[u8]) -> Result<(), SignatureError> {
+        let signature = Signature::from_slice(signature).map_err(|_| SignatureError::Truncated)?;
+        self.key
+            .verify_strict(payload, &signature)
+            .map_err(|_| SignatureError::Mismatch)
+    }
+
+    /// Checks the signature at the end of `blob`, returning the payload it signs
+    pub fn verify<'a>(&self, blob: &'a [u8]) -> Result<&'a [u8], SignatureError> {
+        let Some(payload_len) = blob.len().checked_sub(SIGNATURE_BYTES) else {
+            return Err(SignatureError::Truncated);
+        };
+        let (payload, signature) = blob.split_at(payload_len);
+        self.verify_detached(payload, signature)?;
+        Ok(payload)
+    }
+
+    /// Reads a file along with its detached signature, stored next to it with a `.sig`
+    /// extension appended (e.g. `diagram_params.json.sig`), and returns the file's contents if
+    /// the signature is valid. This keeps signed files, like parameter bundles, readable by
+    /// tools that don't know about signatures.
+    #[cfg(feature = "std")]
+    pub fn read_file(&self, path: &std::path::Path) -> Result<alloc::vec::Vec<u8>, PictorusError> {
+        let mut signature_path = std::ffi::OsString::from(path);
+        signature_path.push(".sig");
+        let signature_path = std::path::PathBuf::from(signature_path);
+
+        let read = |path: &std::path::Path| {
+            std::fs::read(path).map_err(|err| {
+                PictorusError::from_io(
+                    ERR_TYPE,
+                    alloc::format!("Failed to read {}: {err}", path.display()),
+                    &err,
+                )
+            })
+        };
+        let payload = read(path)?;
+        let signature = read(&signature_path)?;
+        self.verify_detached(&payload, &signature).map_err(|err| {
+            PictorusError::new(
+                ErrorKind::InvalidData,
+                ERR_TYPE,
+                alloc::format!("Rejected {}: signature verification failed", path.display()),
+            )
+            .with_source(err.into())
+        })?;
+        Ok(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    // Test 2 from RFC 8032 section 7.1
+    const PUBLIC_KEY: &str = "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c";
+    const SIGNED_BLOB: [u8; 65] = [
+        0x72, 0x92, 0xa0, 0x09, 0xa9, 0xf0, 0xd4, 0xca, 0xb8, 0x72, 0x0e, 0x82, 0x0b, 0x5f, 0x64,
+        0x25, 0x40, 0xa2, 0xb2, 0x7b, 0x54, 0x16, 0x50, 0x3f, 0x8f, 0xb3, 0x76, 0x22, 0x23, 0xeb,
+        0xdb, 0x69, 0xda, 0x08, 0x5a, 0xc1, 0xe4, 0x3e, 0x15, 0x99, 0x6e, 0x45, 0x8f, 0x36, 0x13,
+        0xd0, 0xf1, 0x1d, 0x8c, 0x38, 0x7b, 0x2e, 0xae, 0xb4, 0x30, 0x2a, 0xee, 0xb0, 0x0d, 0x29,
+        0x16, 0x12, 0xbb, 0x0c, 0x00,
+    ];
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7; 32])
+    }
+
+    fn sign(payload: &[u8]) -> Vec<u8> {
+        let mut blob = payload.to_vec();
+        blob.extend_from_slice(&signing_key().sign(payload).to_bytes());
+        blob
+    }
+
+    #[test]
+    fn test_verify_rfc8032_vector() {
+        let verifier = BlobVerifier::from_hex(PUBLIC_KEY).unwrap();
+        assert_eq!(verifier.verify(&SIGNED_BLOB), Ok([0x72].as_slice()));
+
+        let mut tampered = SIGNED_BLOB;
+        tampered[0] ^= 0x01;
+        assert_eq!(verifier.verify(&tampered), Err(SignatureError::Mismatch));
+    }
+
+    #[test]
+    fn test_verify_round_trip() {
+        let verifier = BlobVerifier::new(signing_key().verifying_key().as_bytes()).unwrap();
+        let payload = br#"{"gain_1": {"gain": 2.5}}"#;
+        let blob = sign(payload);
+        assert_eq!(verifier.verify(&blob), Ok(payload.as_slice()));
+        assert_eq!(
+            verifier.verify_detached(payload, &blob[payload.len()..]),
+            Ok(())
+        );
+
+        // An empty payload can be signed too
+        assert_eq!(verifier.verify(&sign(b"")), Ok(b"".as_slice()));
+    }
+
+    #[test]
+    fn test_verify_rejects_bad_blobs() {
+        let verifier = BlobVerifier::new(signing_key().verifying_key().as_bytes()).unwrap();
+        let mut blob = sign(b"arm");
+
+        // Signed by someone else
+        let other = BlobVerifier::from_hex(PUBLIC_KEY).unwrap();
+        assert_eq!(other.verify(&blob), Err(SignatureError::Mismatch));
+
+        assert_eq!(
+            verifier.verify(&blob[..SIGNATURE_BYTES - 1]),
+            Err(SignatureError::Truncated)
+        );
+        // Dropping a payload byte shifts the signature
+        assert_eq!(verifier.verify(&blob[1..]), Err(SignatureError::Mismatch));
+
+        let last = blob.len() - 1;
+        blob[last] ^= 0x80;
+        assert_eq!(verifier.verify(&blob), Err(SignatureError::Mismatch));
+    }
+
+    #[test]
+    fn test_invalid_public_keys() {
+        assert_eq!(
+            BlobVerifier::from_hex("not a key").unwrap_err(),
+            SignatureError::InvalidKey
+        );
+        assert_eq!(
+            BlobVerifier::from_hex(&PUBLIC_KEY[2..]).unwrap_err(),
+            SignatureError::InvalidKey
+        );
+        // The identity point is a weak key that would accept forged signatures
+        let mut identity = [0; PUBLIC_KEY_BYTES];
+        identity[0] = 1;
+        assert_eq!(
+            BlobVerifier::new(&identity).unwrap_err(),
+            SignatureError::InvalidKey
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_read_file() {
+        let verifier = BlobVerifier::new(signing_key().verifying_key().as_bytes()).unwrap();
+        let dir = std::env::temp_dir().join(alloc::format!("signed_blob_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("diagram_params.json");
+        let payload = br#"{"gain_1": {"gain": 2.5}}"#;
+        std::fs::write(&path, payload).unwrap();
+
+        let err = verifier.read_file(&path).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NotFound);
+
+        std::fs::write(
+            dir.join("diagram_params.json.sig"),
+            &sign(payload)[payload.len()..],
+        )
+        .unwrap();
+        assert_eq!(verifier.read_file(&path).unwrap(), payload);
+
+        std::fs::write(&path, br#"{"gain_1": {"gain": 25.0}}"#).unwrap();
+        let err = verifier.read_file(&path).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidData);
+        assert_eq!(
+            err.source().unwrap().message,
+            "Signature verification failed"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
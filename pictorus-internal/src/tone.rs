@@ -0,0 +1,374 @@
+//! Tone sequences for buzzers and piezo speakers driven by a PWM channel, e.g. arming chimes and
+//! fault alarms.
+//!
+//! Sequences are written as comma separated `pitch:milliseconds` notes, where the pitch is a
+//! frequency in Hz (`2000`), a note name with an optional sharp or flat (`C5`, `F#4`, `Bb3`), or
+//! `R` for a rest. A sequence ending in `*` repeats for as long as it's triggered. Several
+//! sequences are separated by semicolons, e.g. `"C5:100,E5:100,G5:200; 3000:150,R:150,*"`.
+use core::time::Duration;
+
+use embedded_hal_02::Pwm;
+use heapless::Vec;
+use log::warn;
+use pictorus_traits::{Context, OutputBlock, PassBy};
+
+use crate::error::{ErrorKind, PictorusError};
+
+const ERR_TYPE: &str = "ToneProtocol";
+/// Most sequences a block can play
+pub const MAX_SEQUENCES: usize = 8;
+/// Most notes in a sequence
+pub const MAX_NOTES: usize = 32;
+
+/// Parameters for the ToneBlock. Its sequences are fixed when it's created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ToneParams {}
+
+impl ToneParams {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// A note of a sequence, with a frequency of zero for rests
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Note {
+    pub frequency: f64,
+    pub duration: Duration,
+}
+
+/// Notes played one after the other
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToneSequence {
+    pub notes: Vec<Note, MAX_NOTES>,
+    /// Repeats while the block is triggered, instead of playing once
+    pub repeat: bool,
+    length: Duration,
+}
+
+fn invalid(msg: &'static str) -> PictorusError {
+    PictorusError::new(ErrorKind::InvalidConfig, ERR_TYPE, msg)
+}
+
+/// Parses a frequency in Hz, a note name such as `A4` or `C#5`, or `R` for a rest
+fn parse_pitch(pitch: &str) -> Result<f64, PictorusError> {
+    if pitch.eq_ignore_ascii_case("r") {
+        return Ok(0.0);
+    }
+    if let Ok(frequency) = pitch.parse::<f64>() {
+        return match frequency.is_finite() && frequency >= 0.0 {
+            true => Ok(frequency),
+            false => Err(invalid("Tone frequency must be a non-negative number")),
+        };
+    }
+    let mut chars = pitch.chars();
+    let semitone: i32 = match chars.next() {
+        Some('C') => 0,
+        Some('D') => 2,
+        Some('E') => 4,
+        Some('F') => 5,
+        Some('G') => 7,
+        Some('A') => 9,
+        Some('B') => 11,
+        _ => return Err(invalid("Unknown note name in tone sequence")),
+    };
+    let rest = chars.as_str();
+    let (accidental, octave) = match rest.as_bytes().first() {
+        Some(b'#') => (1, &rest[1..]),
+        Some(b'b') => (-1, &rest[1..]),
+        _ => (0, rest),
+    };
+    let octave: i32 = octave
+        .parse()
+        .map_err(|_| invalid("Note names need an octave, e.g. A4"))?;
+    // MIDI note number, where A4 (440 Hz) is 69
+    let midi = (octave + 1) * 12 + semitone + accidental;
+    Ok(440.0 * num_traits::Float::powf(2.0, f64::from(midi - 69) / 12.0))
+}
+
+impl ToneSequence {
+    /// Parses a sequence of comma separated `pitch:milliseconds` notes
+    pub fn parse(text: &str) -> Result<Self, PictorusError> {
+        let mut sequence = Self {
+            notes: Vec::new(),
+            repeat: false,
+            length: Duration::ZERO,
+        };
+        let mut notes = text.split(',').map(str::trim).peekable();
+        while let Some(note) = notes.next() {
+            if note == "*" && notes.peek().is_none() {
+                sequence.repeat = true;
+                break;
+            }
+            let (pitch, duration_ms) = note
+                .split_once(':')
+                .ok_or_else(|| invalid("Tone sequence notes must be written as pitch:ms"))?;
+            let duration_ms: f64 = duration_ms
+                .trim()
+                .parse()
+                .map_err(|_| invalid("Note duration must be a number of milliseconds"))?;
+            if !duration_ms.is_finite() || duration_ms <= 0.0 {
+                return Err(invalid("Note duration must be positive"));
+            }
+            let note = Note {
+                frequency: parse_pitch(pitch.trim())?,
+                duration: Duration::from_secs_f64(duration_ms / 1000.0),
+            };
+            sequence
+                .notes
+                .push(note)
+                .map_err(|_| invalid("Tone sequence has too many notes"))?;
+            sequence.length += note.duration;
+        }
+        if sequence.notes.is_empty() {
+            return Err(invalid("Tone sequence has no notes"));
+        }
+        Ok(sequence)
+    }
+
+    /// Frequency playing `elapsed` after the sequence started, or None once it's finished
+    fn frequency_at(&self, elapsed: Duration) -> Option<f64> {
+        let mut elapsed = elapsed;
+        if self.repeat {
+            let position = elapsed.as_nanos() % self.length.as_nanos();
+            elapsed = Duration::from_nanos(position as u64);
+        }
+        let mut end = Duration::ZERO;
+        self.notes.iter().find_map(|note| {
+            end += note.duration;
+            (elapsed < end).then_some(note.frequency)
+        })
+    }
+}
+
+/// Plays tone sequences on a PWM channel, as an `OutputBlock` with inputs of (sequence, play).
+///
+/// A rising edge on play starts the sequence numbered by sequence (from zero), interrupting any
+/// sequence in progress. Sequences that repeat stop when play goes false, and the others play
+/// to the end. The sequencer advances with the model time, so notes are quantized to the tick
+/// rate and the block never blocks. Tones are played at a fixed duty cycle, which sets the
+/// volume of most piezo buzzers, and silence is a duty cycle of zero.
+pub struct ToneBlock<P: Pwm<Time = f64, Duty = f64>> {
+    pwm: P,
+    channel: P::Channel,
+    sequences: Vec<ToneSequence, MAX_SEQUENCES>,
+    duty: f64,
+    /// Sequence playing and the time it started
+    playing: Option<(usize, Duration)>,
+    previous_play: bool,
+    /// Frequency on the PWM channel, or None while silent
+    sounding: Option<f64>,
+}
+
+impl<P: Pwm<Time = f64, Duty = f64>> ToneBlock<P>
+where
+    P::Channel: Copy,
+{
+    /// Creates the block from semicolon separated `sequences`, playing tones at `duty` (0 to 1)
+    pub fn new(
+        mut pwm: P,
+        channel: P::Channel,
+        sequences: &str,
+        duty: f64,
+    ) -> Result<Self, PictorusError> {
+        let mut parsed = Vec::new();
+        for sequence in sequences.split(';').filter(|s| !s.trim().is_empty()) {
+            parsed
+                .push(ToneSequence::parse(sequence)?)
+                .map_err(|_| invalid("Too many tone sequences"))?;
+        }
+        if !(0.0..=1.0).contains(&duty) {
+            return Err(invalid("Tone duty cycle must be between 0 and 1"));
+        }
+        pwm.set_duty(channel, 0.0);
+        pwm.enable(channel);
+        Ok(Self {
+            pwm,
+            channel,
+            sequences: parsed,
+            duty,
+            playing: None,
+            previous_play: false,
+            sounding: None,
+        })
+    }
+
+    /// True while a sequence is playing
+    pub fn is_playing(&self) -> bool {
+        self.playing.is_some()
+    }
+
+    fn sound(&mut self, frequency: Option<f64>) {
+        if frequency == self.sounding {
+            return;
+        }
+        match frequency {
+            Some(frequency) => {
+                // Some PWM drivers hold the duty as a pulse width, which must not exceed the
+                // period, so it's cleared while the period changes
+                self.pwm.set_duty(self.channel, 0.0);
+                self.pwm.set_period(1.0 / frequency);
+                self.pwm.set_duty(self.channel, self.duty);
+            }
+            None => self.pwm.set_duty(self.channel, 0.0),
+        }
+        self.sounding = frequency;
+    }
+}
+
+impl<P: Pwm<Time = f64, Duty = f64>> OutputBlock for ToneBlock<P>
+where
+    P::Channel: Copy,
+{
+    type Inputs = (f64, bool);
+    type Parameters = ToneParams;
+
+    fn output(
+        &mut self,
+        _parameters: &Self::Parameters,
+        context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) {
+        let (sequence, play) = inputs;
+        let time = context.time();
+        if play && !self.previous_play {
+            let index = sequence as usize;
+            if sequence >= 0.0 && sequence == index as f64 && index < self.sequences.len() {
+                self.playing = Some((index, time));
+            } else {
+                warn!("No tone sequence {sequence}");
+            }
+        }
+        self.previous_play = play;
+
+        let frequency = self.playing.and_then(|(index, start)| {
+            let sequence = &self.sequences[index];
+            if sequence.repeat && !play {
+                return None;
+            }
+            sequence.frequency_at(time.saturating_sub(start))
+        });
+        if frequency.is_none() {
+            self.playing = None;
+        }
+        self.sound(frequency.filter(|frequency| *frequency > 0.0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::can_demux::tests::StubContext;
+
+    /// Records the period and duty of a PWM channel
+    #[derive(Default)]
+    struct StubPwm {
+        period: f64,
+        duty: f64,
+        enabled: bool,
+        period_writes: usize,
+    }
+
+    impl Pwm for StubPwm {
+        type Channel = ();
+        type Time = f64;
+        type Duty = f64;
+
+        fn disable(&mut self, _channel: ()) {
+            self.enabled = false;
+        }
+
+        fn enable(&mut self, _channel: ()) {
+            self.enabled = true;
+        }
+
+        fn get_period(&self) -> f64 {
+            self.period
+        }
+
+        fn get_duty(&self, _channel: ()) -> f64 {
+            self.duty
+        }
+
+        fn get_max_duty(&self) -> f64 {
+            1.0
+        }
+
+        fn set_duty(&mut self, _channel: (), duty: f64) {
+            self.duty = duty;
+        }
+
+        fn set_period<P: Into<f64>>(&mut self, period: P) {
+            self.period = period.into();
+            self.period_writes += 1;
+        }
+    }
+
+    #[test]
+    fn test_parse_tone_sequence() {
+        let sequence = ToneSequence::parse("A4:100, C#5:50,Bb3:25,R:10,2000:1000,*").unwrap();
+        let frequencies: Vec<f64, MAX_NOTES> =
+            sequence.notes.iter().map(|note| note.frequency).collect();
+        assert!((frequencies[0] - 440.0).abs() < 1e-9);
+        assert!((frequencies[1] - 554.365).abs() < 1e-3);
+        assert!((frequencies[2] - 233.082).abs() < 1e-3);
+        assert_eq!(frequencies[3..], [0.0, 2000.0]);
+        assert_eq!(sequence.notes[1].duration, Duration::from_millis(50));
+        assert!(sequence.repeat);
+        assert_eq!(sequence.length, Duration::from_millis(1185));
+
+        assert!(ToneSequence::parse("").is_err());
+        assert!(ToneSequence::parse("A4").is_err());
+        assert!(ToneSequence::parse("H4:100").is_err());
+        assert!(ToneSequence::parse("A4:0").is_err());
+        assert!(ToneSequence::parse("*,A4:100").is_err());
+    }
+
+    #[test]
+    fn test_tone_block() {
+        let mut block = ToneBlock::new(
+            StubPwm::default(),
+            (),
+            "1000:100,R:50,2000:100; 500:10,*",
+            0.5,
+        )
+        .unwrap();
+        let params = ToneParams::new();
+        assert!(block.pwm.enabled);
+        let run = |block: &mut ToneBlock<StubPwm>, ms, sequence, play| {
+            block.output(
+                &params,
+                &StubContext(Duration::from_millis(ms)),
+                (sequence, play),
+            );
+            (1.0 / block.pwm.period, block.pwm.duty)
+        };
+
+        // Plays once on a rising edge, even after play goes false
+        assert_eq!(run(&mut block, 0, 0.0, false).1, 0.0);
+        assert_eq!(run(&mut block, 10, 0.0, true), (1000.0, 0.5));
+        assert_eq!(run(&mut block, 60, 0.0, false), (1000.0, 0.5));
+        assert_eq!(run(&mut block, 120, 0.0, false).1, 0.0);
+        assert_eq!(run(&mut block, 170, 0.0, false), (2000.0, 0.5));
+        assert_eq!(run(&mut block, 200, 0.0, false), (2000.0, 0.5));
+        assert_eq!(block.pwm.period_writes, 2);
+        assert_eq!(run(&mut block, 260, 0.0, false).1, 0.0);
+        assert!(!block.is_playing());
+
+        // Repeats while played, and a new sequence interrupts
+        assert_eq!(run(&mut block, 300, 1.0, true), (500.0, 0.5));
+        assert_eq!(run(&mut block, 1305, 1.0, true), (500.0, 0.5));
+        assert_eq!(run(&mut block, 1310, 1.0, false).1, 0.0);
+        assert!(!block.is_playing());
+
+        // Unknown sequences are ignored
+        run(&mut block, 1400, 2.0, true);
+        assert!(!block.is_playing());
+    }
+
+    #[test]
+    fn test_tone_block_invalid_config() {
+        assert!(ToneBlock::new(StubPwm::default(), (), "1000:100", 1.5).is_err());
+        assert!(ToneBlock::new(StubPwm::default(), (), "1000:100;x", 0.5).is_err());
+    }
+}
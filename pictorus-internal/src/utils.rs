@@ -4,9 +4,6 @@ use num_traits::{AsPrimitive, Float};
 
 use log::debug;
 
-#[cfg(feature = "alloc")]
-use serde::{Deserialize, Serialize};
-
 #[cfg(feature = "alloc")]
 pub struct PictorusVars {
     pub run_path: alloc::string::String,
@@ -28,26 +25,7 @@ pub fn string_to_scalar(val: &str) -> Result<f64, ()> {
     val.trim().parse().or(Err(()))
 }
 
-#[cfg(feature = "alloc")]
-#[derive(Serialize, Deserialize, Debug)]
-pub struct PictorusError {
-    pub err_type: alloc::string::String,
-    pub message: alloc::string::String,
-}
-
-#[cfg(feature = "alloc")]
-impl PictorusError {
-    pub fn new(err_type: alloc::string::String, message: alloc::string::String) -> Self {
-        PictorusError { err_type, message }
-    }
-}
-
-#[cfg(feature = "alloc")]
-impl From<core::convert::Infallible> for PictorusError {
-    fn from(_: core::convert::Infallible) -> Self {
-        unreachable!();
-    }
-}
+pub use crate::error::{ErrorKind, PictorusError};
 
 pub fn positive_duration(f: f64) -> Duration {
     Duration::from_secs_f64(f64::max(0.0, f))
@@ -284,12 +262,52 @@ mod std_utils {
                 String::from("{}")
             }
         };
-        serde_json::from_str(input_params_json.as_str()).unwrap_or_else(|_| {
+        parse_diagram_params(&input_params_json).unwrap_or_else(|| {
             warn!("Error parsing params file, using empty params map.");
             HashMap::<String, HashMap<String, String>>::new()
         })
     }
 
+    pub fn parse_diagram_params(source: &str) -> Option<DiagramParams> {
+        serde_json::from_str(source).ok()
+    }
+
+    /// Returns the entries of `new` that were added or changed relative to `old`, grouped by
+    /// block, so only the affected blocks need to reload their parameters.
+    pub fn changed_diagram_params(old: &DiagramParams, new: &DiagramParams) -> DiagramParams {
+        let mut changed = DiagramParams::new();
+        for (block_name, params) in new {
+            let old_params = old.get(block_name);
+            for (var_name, value) in params {
+                if old_params.and_then(|p| p.get(var_name)) != Some(value) {
+                    changed
+                        .entry(block_name.clone())
+                        .or_default()
+                        .insert(var_name.clone(), value.clone());
+                }
+            }
+        }
+        changed
+    }
+
+    /// Returns the entries of `old` that are missing from `new`, grouped by block, so the
+    /// affected blocks can fall back to their default parameters.
+    pub fn removed_diagram_params(old: &DiagramParams, new: &DiagramParams) -> DiagramParams {
+        let mut removed = DiagramParams::new();
+        for (block_name, params) in old {
+            let new_params = new.get(block_name);
+            for (var_name, value) in params {
+                if new_params.is_none_or(|p| !p.contains_key(var_name)) {
+                    removed
+                        .entry(block_name.clone())
+                        .or_default()
+                        .insert(var_name.clone(), value.clone());
+                }
+            }
+        }
+        removed
+    }
+
     pub fn get_pictorus_vars() -> PictorusVars {
         // Load special environment variables that control app execution, or use safe defaults if not present.
         PictorusVars {
@@ -319,10 +337,7 @@ mod std_utils {
 
     pub fn custom_panic_handler(panic_info: &PanicHookInfo, run_path: &str) {
         warn!("Unhandled panic, dumping stack trace to error log...");
-        let err = PictorusError {
-            err_type: "unhandled".to_string(),
-            message: panic_info.to_string(),
-        };
+        let err = PictorusError::new(ErrorKind::Unhandled, "unhandled", panic_info.to_string());
         dump_error(&err, run_path);
     }
 
@@ -360,6 +375,152 @@ mod std_utils {
 #[cfg(feature = "std")]
 pub use std_utils::*;
 
+// Watching the params file relies on inotify, so is only available on Linux
+#[cfg(all(feature = "hot_reload", target_os = "linux"))]
+mod params_watcher {
+    use super::*;
+    use inotify::{Inotify, WatchMask};
+    use log::{info, warn};
+    use std::format;
+    use std::path::PathBuf;
+    use std::prelude::rust_2021::*;
+
+    const PARAMS_FILE_NAME: &str = "diagram_params.json";
+
+    /// Changes to the params file, grouped by block
+    #[derive(Debug, Default, Clone, PartialEq)]
+    pub struct ParamsUpdate {
+        /// Params that were added or changed, with their new values
+        pub changed: DiagramParams,
+        /// Params that were removed, with their previous values
+        pub removed: DiagramParams,
+    }
+
+    impl ParamsUpdate {
+        /// Names of the blocks whose params changed or were removed
+        pub fn blocks(&self) -> impl Iterator<Item = &String> {
+            self.changed.keys().chain(
+                self.removed
+                    .keys()
+                    .filter(|block| !self.changed.contains_key(*block)),
+            )
+        }
+    }
+
+    /// Watches diagram_params.json for changes so parameters can be tuned while the app runs.
+    ///
+    /// The watcher never blocks. Generated code calls [`DiagramParamsWatcher::poll`] between
+    /// ticks with a hook that reloads the parameters of the blocks in the update, so a tick
+    /// always sees either the old or the new set of parameters. A file that fails to parse
+    /// (e.g. while only partly written) is ignored until the next change.
+    pub struct DiagramParamsWatcher {
+        inotify: Inotify,
+        path: PathBuf,
+        current: DiagramParams,
+        buffer: [u8; 1024],
+    }
+
+    impl DiagramParamsWatcher {
+        /// Starts watching the params file in the app's run path. `current` should be the
+        /// params the app was started with.
+        pub fn new(vars: &PictorusVars, current: DiagramParams) -> Result<Self, PictorusError> {
+            let run_path = PathBuf::from(&vars.run_path);
+            let watch_path = if vars.run_path.is_empty() {
+                PathBuf::from(".")
+            } else {
+                run_path.clone()
+            };
+            let inotify = Inotify::init().map_err(|err| {
+                PictorusError::from_io(
+                    "DiagramParamsWatcher",
+                    format!("Failed to initialize inotify: {err}"),
+                    &err,
+                )
+            })?;
+            // Watch the directory rather than the file, since editors and deployment tools
+            // commonly replace the file instead of writing it in place
+            inotify
+                .watches()
+                .add(&watch_path, WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO)
+                .map_err(|err| {
+                    PictorusError::from_io(
+                        "DiagramParamsWatcher",
+                        format!("Failed to watch {}: {err}", watch_path.display()),
+                        &err,
+                    )
+                })?;
+            info!("Watching {PARAMS_FILE_NAME} for changes");
+
+            Ok(DiagramParamsWatcher {
+                inotify,
+                path: run_path.join(PARAMS_FILE_NAME),
+                current,
+                buffer: [0; 1024],
+            })
+        }
+
+        /// Passes any changes to the params file since the last poll to `apply`, which should
+        /// reload the params of every block in the update (falling back to the defaults of
+        /// removed params) and return whether it succeeded. Returns whether an update was
+        /// applied.
+        ///
+        /// Only applied updates become the current params, so the changes in a rejected
+        /// update are offered again with the next change to the file.
+        pub fn poll(&mut self, apply: impl FnOnce(&ParamsUpdate) -> bool) -> bool {
+            let mut modified = false;
+            loop {
+                match self.inotify.read_events(&mut self.buffer) {
+                    Ok(events) => {
+                        modified |= events
+                            .into_iter()
+                            .any(|event| event.name.is_some_and(|name| name == PARAMS_FILE_NAME));
+                    }
+                    Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => break,
+                    Err(err) => {
+                        warn!("Failed to read params file events: {err}");
+                        break;
+                    }
+                }
+            }
+            if !modified {
+                return false;
+            }
+
+            let Ok(source) = std::fs::read_to_string(&self.path) else {
+                return false;
+            };
+            let Some(new_params) = parse_diagram_params(&source) else {
+                warn!("Error parsing updated params file, keeping current params.");
+                return false;
+            };
+            let update = ParamsUpdate {
+                changed: changed_diagram_params(&self.current, &new_params),
+                removed: removed_diagram_params(&self.current, &new_params),
+            };
+            if update.changed.is_empty() && update.removed.is_empty() {
+                return false;
+            }
+            info!(
+                "Reloading params for blocks: {:?}",
+                update.blocks().collect::<Vec<_>>()
+            );
+            if !apply(&update) {
+                warn!("Failed to apply updated params, keeping current params.");
+                return false;
+            }
+            self.current = new_params;
+            true
+        }
+
+        /// The most recently loaded params
+        pub fn current(&self) -> &DiagramParams {
+            &self.current
+        }
+    }
+}
+#[cfg(all(feature = "hot_reload", target_os = "linux"))]
+pub use params_watcher::*;
+
 #[cfg(all(test, feature = "std"))]
 #[allow(clippy::approx_constant)]
 mod tests {
@@ -371,6 +532,96 @@ mod tests {
     use std::collections::HashMap;
     use temp_env::with_vars;
 
+    #[test]
+    fn test_changed_diagram_params() {
+        let old: DiagramParams = parse_diagram_params(
+            r#"{"gain_block": {"gain": "1.0"}, "sine_block": {"amplitude": "2.0", "frequency": "3.0"}}"#,
+        )
+        .unwrap();
+        let new: DiagramParams = parse_diagram_params(
+            r#"{"gain_block": {"gain": "1.0"}, "sine_block": {"amplitude": "2.5", "frequency": "3.0"}, "new_block": {"x": "1"}}"#,
+        )
+        .unwrap();
+
+        let changed = changed_diagram_params(&old, &new);
+        assert_eq!(changed.len(), 2);
+        assert_eq!(changed["sine_block"].len(), 1);
+        assert_eq!(changed["sine_block"]["amplitude"], "2.5");
+        assert_eq!(changed["new_block"]["x"], "1");
+        assert!(changed_diagram_params(&new, &new).is_empty());
+        assert!(parse_diagram_params("{\"partial\": ").is_none());
+    }
+
+    #[test]
+    fn test_removed_diagram_params() {
+        let old: DiagramParams = parse_diagram_params(
+            r#"{"gain_block": {"gain": "1.0"}, "sine_block": {"amplitude": "2.0", "frequency": "3.0"}}"#,
+        )
+        .unwrap();
+        let new: DiagramParams =
+            parse_diagram_params(r#"{"sine_block": {"amplitude": "2.5"}}"#).unwrap();
+
+        let removed = removed_diagram_params(&old, &new);
+        assert_eq!(removed.len(), 2);
+        assert_eq!(removed["gain_block"]["gain"], "1.0");
+        assert_eq!(removed["sine_block"].len(), 1);
+        assert_eq!(removed["sine_block"]["frequency"], "3.0");
+        assert!(removed_diagram_params(&new, &old).is_empty());
+    }
+
+    #[cfg(all(feature = "hot_reload", target_os = "linux"))]
+    #[test]
+    fn test_diagram_params_watcher() {
+        let run_path = std::env::temp_dir().join(std::format!(
+            "pictorus_params_watcher_{}_{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&run_path).unwrap();
+        let params_path = run_path.join("diagram_params.json");
+        let initial = r#"{"gain_block": {"gain": "1.0"}, "sine_block": {"amplitude": "2.0"}}"#;
+        std::fs::write(&params_path, initial).unwrap();
+
+        let vars = PictorusVars {
+            run_path: run_path.to_str().unwrap().to_string(),
+            data_log_rate_hz: 0.0,
+            transmit_enabled: false,
+            publish_socket: String::new(),
+        };
+        let mut watcher =
+            DiagramParamsWatcher::new(&vars, parse_diagram_params(initial).unwrap()).unwrap();
+        let mut update = None;
+        assert!(!watcher.poll(|_| unreachable!("The file hasn't changed")));
+
+        std::fs::write(&params_path, r#"{"gain_block": {"gain": "2.0"}}"#).unwrap();
+        // A rejected update isn't committed
+        assert!(!watcher.poll(|_| false));
+        assert_eq!(watcher.current()["gain_block"]["gain"], "1.0");
+
+        // Replacing the file, as deployment tools do, is picked up too
+        let staged_path = run_path.join("diagram_params.json.tmp");
+        std::fs::write(&staged_path, r#"{"gain_block": {"gain": "3.0"}}"#).unwrap();
+        std::fs::rename(&staged_path, &params_path).unwrap();
+        assert!(watcher.poll(|new| {
+            update = Some(new.clone());
+            true
+        }));
+        let update = update.unwrap();
+        assert_eq!(update.changed["gain_block"]["gain"], "3.0");
+        assert_eq!(update.removed["sine_block"]["amplitude"], "2.0");
+        assert_eq!(update.blocks().count(), 2);
+        assert_eq!(watcher.current()["gain_block"]["gain"], "3.0");
+
+        // Partly written files are ignored
+        std::fs::write(&params_path, r#"{"gain_block": "#).unwrap();
+        assert!(!watcher.poll(|_| unreachable!("The file doesn't parse")));
+
+        std::fs::remove_dir_all(&run_path).unwrap();
+    }
+
     #[test]
     fn test_load_param_f64() {
         let mut diagram_params = DiagramParams::new();
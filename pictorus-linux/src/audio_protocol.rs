@@ -0,0 +1,172 @@
+use alsa::pcm::{Access, Format, HwParams, PCM};
+use alsa::{Direction, ValueOr};
+use log::warn;
+use pictorus_internal::utils::PictorusError;
+use pictorus_traits::{Context, InputBlock, Matrix, OutputBlock, Pass, PassBy};
+
+const ERR_TYPE: &str = "AudioProtocol";
+/// Full scale of a signed 16 bit sample
+const FULL_SCALE: f64 = 32768.0;
+
+/// Parameters for the audio input and output blocks
+#[derive(Default)]
+pub struct AudioParams;
+
+impl AudioParams {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// ALSA reports failures as errno values, so classify them like the equivalent I/O error
+fn alsa_error(message: String, err: &alsa::Error) -> PictorusError {
+    PictorusError::from_io(
+        ERR_TYPE,
+        message,
+        &std::io::Error::from_raw_os_error(err.errno()),
+    )
+}
+
+fn open_pcm(device: &str, direction: Direction, sample_rate: f64) -> Result<PCM, PictorusError> {
+    let error = |action: &str, err: alsa::Error| {
+        alsa_error(
+            format!("Failed to {action} for audio device {device} ({err})"),
+            &err,
+        )
+    };
+    let pcm = PCM::new(device, direction, true).map_err(|err| error("open device", err))?;
+    {
+        let hw_params = HwParams::any(&pcm).map_err(|err| error("read parameters", err))?;
+        hw_params
+            .set_channels(1)
+            .map_err(|err| error("set mono", err))?;
+        hw_params
+            .set_rate(sample_rate as u32, ValueOr::Nearest)
+            .map_err(|err| error("set sample rate", err))?;
+        hw_params
+            .set_format(Format::s16())
+            .map_err(|err| error("set sample format", err))?;
+        hw_params
+            .set_access(Access::RWInterleaved)
+            .map_err(|err| error("set access mode", err))?;
+        pcm.hw_params(&hw_params)
+            .map_err(|err| error("apply parameters", err))?;
+    }
+    pcm.prepare().map_err(|err| error("prepare", err))?;
+    Ok(pcm)
+}
+
+/// Captures mono audio from an ALSA device in frames of `N` samples.
+///
+/// The output is the most recent complete frame, with samples scaled to `[-1, 1)`, and is held
+/// until the next frame is available. Reads never block. If the model runs slower than frames
+/// arrive, the older frames are dropped so the output always reflects the latest audio.
+pub struct AudioInput<const N: usize> {
+    pcm: PCM,
+    samples: [i16; N],
+    buffer: Matrix<1, N, f64>,
+}
+
+/// Opens an ALSA capture device (e.g. "default" or "hw:1,0") at the given sample rate in Hz
+pub fn create_audio_input<const N: usize>(
+    device: &str,
+    sample_rate: f64,
+) -> Result<AudioInput<N>, PictorusError> {
+    let pcm = open_pcm(device, Direction::Capture, sample_rate)?;
+    pcm.start().map_err(|err| {
+        alsa_error(
+            format!("Failed to start capture on audio device {device} ({err})"),
+            &err,
+        )
+    })?;
+    Ok(AudioInput {
+        pcm,
+        samples: [0; N],
+        buffer: Matrix::zeroed(),
+    })
+}
+
+impl<const N: usize> AudioInput<N> {
+    /// Reads the latest complete frame into the output buffer, if there is one
+    fn read_frames(&mut self) -> Result<(), alsa::Error> {
+        let mut updated = false;
+        while self.pcm.avail_update()? >= N as alsa::pcm::Frames {
+            let read = self.pcm.io_i16()?.readi(&mut self.samples)?;
+            updated |= read == N;
+        }
+        if updated {
+            for (output, sample) in self.buffer.data.iter_mut().zip(self.samples) {
+                output[0] = f64::from(sample) / FULL_SCALE;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> InputBlock for AudioInput<N> {
+    type Output = Matrix<1, N, f64>;
+    type Parameters = AudioParams;
+
+    fn input(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        if let Err(err) = self.read_frames() {
+            // Overruns leave the device stopped until it is recovered
+            if let Err(err) = self.pcm.try_recover(err, true) {
+                warn!("Failed to recover audio capture: {err}");
+            } else {
+                self.pcm.start().ok();
+            }
+        }
+        self.buffer.as_by()
+    }
+}
+
+/// Plays mono audio on an ALSA device in frames of `N` samples.
+///
+/// Each tick queues the input frame, with samples in `[-1, 1]` (values outside are clipped).
+/// Writes never block, so the model rate times `N` should match the sample rate. Frames that
+/// don't fit in the device buffer are dropped, and underruns are recovered automatically.
+pub struct AudioOutput<const N: usize> {
+    pcm: PCM,
+    samples: [i16; N],
+}
+
+/// Opens an ALSA playback device (e.g. "default" or "hw:1,0") at the given sample rate in Hz
+pub fn create_audio_output<const N: usize>(
+    device: &str,
+    sample_rate: f64,
+) -> Result<AudioOutput<N>, PictorusError> {
+    Ok(AudioOutput {
+        pcm: open_pcm(device, Direction::Playback, sample_rate)?,
+        samples: [0; N],
+    })
+}
+
+impl<const N: usize> OutputBlock for AudioOutput<N> {
+    type Inputs = Matrix<1, N, f64>;
+    type Parameters = AudioParams;
+
+    fn output(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) {
+        for (sample, input) in self.samples.iter_mut().zip(inputs.data.iter()) {
+            // Float to int casts saturate, and NaN becomes silence
+            *sample = (input[0] * FULL_SCALE) as i16;
+        }
+        let result = self.pcm.io_i16().and_then(|io| io.writei(&self.samples));
+        if let Err(err) = result {
+            if err.errno() == libc::EAGAIN {
+                return;
+            }
+            if let Err(err) = self.pcm.try_recover(err, true) {
+                warn!("Failed to recover audio playback: {err}");
+            }
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use log::warn;
+use pictorus_internal::utils::{ErrorKind, PictorusError};
+use pictorus_traits::{Context, InputBlock, Matrix, Pass, PassBy};
+use v4l::buffer::Type;
+use v4l::io::traits::CaptureStream;
+use v4l::prelude::*;
+use v4l::video::Capture;
+use v4l::video::capture::Parameters as CaptureParameters;
+use v4l::{Format, FourCC};
+
+const ERR_TYPE: &str = "CameraProtocol";
+/// Number of driver buffers to queue, so a late read doesn't drop frames mid-capture
+const BUFFER_COUNT: u32 = 4;
+
+/// Parameters for the CameraInput block
+#[derive(Default)]
+pub struct CameraInputParams;
+
+impl CameraInputParams {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Delivers the latest frame from a V4L2 camera as a downscaled `H` x `W` grayscale image.
+///
+/// Frames are captured on a dedicated thread, since waiting for the camera would stall the model.
+/// Each frame is box-filtered down to `H` rows by `W` columns (element `(row, col)` of the output
+/// matrix), and each tick outputs the most recent frame. The output is all zeros until the first
+/// frame arrives.
+pub struct CameraInput<const H: usize, const W: usize> {
+    /// Latest frame in row-major order
+    latest: Arc<Mutex<Vec<u8>>>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    buffer: Matrix<H, W, u8>,
+}
+
+/// Opens `/dev/video{device}`, capturing `width` x `height` frames at `fps` frames per second.
+///
+/// The camera must support the YUYV or GREY pixel formats. Cameras may round the size and frame
+/// rate to the nearest ones they support.
+pub fn create_camera_input<const H: usize, const W: usize>(
+    device: f64,
+    width: f64,
+    height: f64,
+    fps: f64,
+) -> Result<CameraInput<H, W>, PictorusError> {
+    let device_index = device as usize;
+    let error = |action: &str, err: std::io::Error| {
+        PictorusError::from_io(
+            ERR_TYPE,
+            format!("Failed to {action} for camera /dev/video{device_index} ({err})"),
+            &err,
+        )
+    };
+
+    let camera = Device::new(device_index).map_err(|err| error("open device", err))?;
+    let requested = Format::new(width as u32, height as u32, FourCC::new(b"YUYV"));
+    let format = camera
+        .set_format(&requested)
+        .map_err(|err| error("set format", err))?;
+    let bytes_per_pixel = match &format.fourcc.repr {
+        b"YUYV" => 2,
+        b"GREY" => 1,
+        _ => {
+            return Err(PictorusError::new(
+                ErrorKind::Unsupported,
+                ERR_TYPE,
+                format!(
+                    "Camera /dev/video{device_index} doesn't support a grayscale compatible format, got {}",
+                    format.fourcc
+                ),
+            ));
+        }
+    };
+    if fps > 0.0 {
+        camera
+            .set_params(&CaptureParameters::with_fps(fps.round() as u32))
+            .map_err(|err| error("set frame rate", err))?;
+    }
+    let stream = MmapStream::with_buffers(&camera, Type::VideoCapture, BUFFER_COUNT)
+        .map_err(|err| error("start streaming", err))?;
+
+    let latest = Arc::new(Mutex::new(Vec::new()));
+    let running = Arc::new(AtomicBool::new(true));
+    let thread = {
+        let latest = latest.clone();
+        let running = running.clone();
+        std::thread::spawn(move || {
+            // The device must outlive the stream
+            let _camera = camera;
+            capture_loop::<H, W>(stream, &format, bytes_per_pixel, &latest, &running)
+        })
+    };
+
+    Ok(CameraInput {
+        latest,
+        running,
+        thread: Some(thread),
+        buffer: Matrix::zeroed(),
+    })
+}
+
+fn capture_loop<const H: usize, const W: usize>(
+    mut stream: MmapStream,
+    format: &Format,
+    bytes_per_pixel: usize,
+    latest: &Mutex<Vec<u8>>,
+    running: &AtomicBool,
+) {
+    let mut frame = vec![0; H * W];
+    while running.load(Ordering::Relaxed) {
+        match stream.next() {
+            Ok((data, _)) => {
+                downscale::<H, W>(data, format, bytes_per_pixel, &mut frame);
+                if let Ok(mut latest) = latest.lock() {
+                    latest.clone_from(&frame);
+                }
+            }
+            Err(err) => {
+                warn!("Failed to capture camera frame: {err}");
+                return;
+            }
+        }
+    }
+}
+
+/// Box-filters the luma channel of a frame down to `H` x `W`, written in row-major order
+fn downscale<const H: usize, const W: usize>(
+    data: &[u8],
+    format: &Format,
+    bytes_per_pixel: usize,
+    output: &mut [u8],
+) {
+    let (width, height) = (format.width as usize, format.height as usize);
+    let stride = (format.stride as usize).max(width * bytes_per_pixel);
+    for row in 0..H {
+        let (top, bottom) = (
+            row * height / H,
+            ((row + 1) * height / H).max(row * height / H + 1),
+        );
+        for col in 0..W {
+            let (left, right) = (
+                col * width / W,
+                ((col + 1) * width / W).max(col * width / W + 1),
+            );
+            let mut sum = 0u32;
+            let mut count = 0u32;
+            for y in top..bottom.min(height) {
+                for x in left..right.min(width) {
+                    // For YUYV the luma is the first byte of every pixel
+                    if let Some(luma) = data.get(y * stride + x * bytes_per_pixel) {
+                        sum += u32::from(*luma);
+                        count += 1;
+                    }
+                }
+            }
+            output[row * W + col] = if count > 0 { (sum / count) as u8 } else { 0 };
+        }
+    }
+}
+
+impl<const H: usize, const W: usize> Drop for CameraInput<H, W> {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+impl<const H: usize, const W: usize> InputBlock for CameraInput<H, W> {
+    type Output = Matrix<H, W, u8>;
+    type Parameters = CameraInputParams;
+
+    fn input(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        if let Ok(latest) = self.latest.lock() {
+            if latest.len() == H * W {
+                for (index, pixel) in latest.iter().enumerate() {
+                    self.buffer.data[index % W][index / W] = *pixel;
+                }
+            }
+        }
+        self.buffer.as_by()
+    }
+}
@@ -3,13 +3,18 @@ use log::debug;
 use pictorus_blocks::CanReceiveBlockParams;
 use pictorus_blocks::CanTransmitBlockParams;
 use pictorus_traits::{ByteSliceSignal, Context, InputBlock, OutputBlock, PassBy};
-use socketcan::{CanFrame, CanSocket, Socket};
+use socketcan::{CanErrorFrame, CanFrame, CanSocket, Socket, SocketOptions};
 
+use pictorus_internal::can_health::CanBusHealth;
 use pictorus_internal::protocols::CanProtocol;
-use pictorus_internal::utils::PictorusError;
+use pictorus_internal::utils::{ErrorKind, PictorusError};
 
 const ERR_TYPE: &str = "CanProtocol";
 
+pub use pictorus_internal::can_demux::{CanDemux, CanDemuxParams};
+pub use pictorus_internal::can_health::CanHealthParams;
+pub use pictorus_internal::j1939::{J1939, J1939ReceiveParams, J1939TransmitParams};
+
 pub struct CanConnection {
     socket: CanSocket,
     frames: Vec<CanFrame>,
@@ -20,21 +25,24 @@ impl CanConnection {
     pub fn new(iface: &[u8]) -> Result<Self, PictorusError> {
         let iface_str = std::str::from_utf8(iface).map_err(|err| {
             PictorusError::new(
-                ERR_TYPE.into(),
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
                 format!("Couldn't bind to CAN interface because interface bytes are not valid UTF-8 ({err})")
             )
         })?;
         let socket = CanSocket::open(iface_str).map_err(|err| {
-            PictorusError::new(
-                ERR_TYPE.into(),
+            PictorusError::from_io(
+                ERR_TYPE,
                 format!("Failed to open CAN socket on interface: {iface_str} ({err})",),
+                &err,
             )
         })?;
 
         socket.set_nonblocking(true).map_err(|err| {
-            PictorusError::new(
-                ERR_TYPE.into(),
+            PictorusError::from_io(
+                ERR_TYPE,
                 format!("Failed to set CAN socket to non-blocking mode: {iface_str} ({err})",),
+                &err,
             )
         })?;
 
@@ -125,3 +133,86 @@ impl InputBlock for CanConnection {
         frame.data()
     }
 }
+
+// Error frame classes and details, from linux/can/error.h
+const CAN_ERR_MASK: u32 = 0x1FFF_FFFF;
+const CAN_ERR_CRTL: u32 = 0x0000_0004;
+const CAN_ERR_BUSOFF: u32 = 0x0000_0040;
+const CAN_ERR_RESTARTED: u32 = 0x0000_0100;
+const CAN_ERR_CNT: u32 = 0x0000_0200;
+const CAN_ERR_CRTL_RX_OVERFLOW: u8 = 0x01;
+
+/// Reports the health of a CAN interface: `(tx error count, rx error count, bus-off events,
+/// rx overflows, busload %, bus-off)`.
+///
+/// The monitor opens its own socket with error frames enabled, so it sees all traffic on the
+/// interface, including frames sent by other sockets on this host, without interfering with
+/// the CAN connection. Error counters are updated from the kernel's error frames, which carry
+/// them on recent kernels. Busload is estimated from the frames seen over 100ms windows,
+/// ignoring stuff bits.
+pub struct CanHealthMonitor {
+    socket: CanSocket,
+    health: CanBusHealth,
+}
+
+impl CanHealthMonitor {
+    pub fn new(iface: &[u8]) -> Result<Self, PictorusError> {
+        let connection = CanConnection::new(iface)?;
+        connection
+            .socket
+            .set_error_filter_accept_all()
+            .map_err(|err| {
+                PictorusError::from_io(
+                    ERR_TYPE,
+                    format!("Failed to enable CAN error frames ({err})"),
+                    &err,
+                )
+            })?;
+        Ok(Self {
+            socket: connection.socket,
+            health: CanBusHealth::new(),
+        })
+    }
+
+    fn handle_error_frame(&mut self, frame: &CanErrorFrame) {
+        let class = socketcan::Frame::id_word(frame) & CAN_ERR_MASK;
+        let data = EmbeddedFrame::data(frame);
+        if class & CAN_ERR_CNT != 0 && data.len() >= 8 {
+            self.health.set_error_counters(data[6], data[7]);
+        }
+        if class & CAN_ERR_BUSOFF != 0 {
+            self.health.set_bus_off(true);
+        } else if class & CAN_ERR_RESTARTED != 0 {
+            self.health.set_bus_off(false);
+        }
+        if class & CAN_ERR_CRTL != 0
+            && data
+                .get(1)
+                .is_some_and(|crtl| crtl & CAN_ERR_CRTL_RX_OVERFLOW != 0)
+        {
+            self.health.record_rx_overflow();
+        }
+    }
+}
+
+impl InputBlock for CanHealthMonitor {
+    type Output = (f64, f64, f64, f64, f64, bool);
+    type Parameters = CanHealthParams;
+
+    fn input(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        while let Ok(frame) = self.socket.read_frame() {
+            match frame {
+                CanFrame::Error(frame) => self.handle_error_frame(&frame),
+                frame => self.health.record_frame(
+                    EmbeddedFrame::is_extended(&frame),
+                    EmbeddedFrame::dlc(&frame),
+                ),
+            }
+        }
+        self.health.update(context.time(), parameters.bitrate)
+    }
+}
@@ -0,0 +1,72 @@
+use core::time::Duration;
+
+use linux_embedded_hal::{I2cdev, SpidevBus};
+use pictorus_internal::display::{Hd44780, I2cSsd1306, SpiSsd1306, Ssd1306, TextDisplay};
+use pictorus_internal::utils::PictorusError;
+
+use crate::spi_protocol::open_spidev_bus;
+use crate::{CdevPin, StdDelayProtocol, create_gpio_output_pin, create_i2c_protocol};
+
+pub use pictorus_internal::display::DisplayParams;
+
+pub type Hd44780Output<const N: usize> = TextDisplay<Hd44780<I2cdev, StdDelayProtocol>, N>;
+pub type Ssd1306I2cOutput<const N: usize> = TextDisplay<Ssd1306<I2cSsd1306<I2cdev>>, N>;
+pub type Ssd1306SpiOutput<const N: usize> = TextDisplay<Ssd1306<SpiSsd1306<SpidevBus, CdevPin>>, N>;
+
+fn refresh_interval(refresh_interval_ms: f64) -> Duration {
+    Duration::from_micros((refresh_interval_ms * 1000.0) as u64)
+}
+
+/// Shows the inputs on an HD44780 character LCD with a PCF8574 backpack at `address` on the I2C
+/// bus, formatted by `format`. The display is redrawn at most every `refresh_interval_ms`.
+pub fn create_hd44780_output<const N: usize>(
+    address: f64,
+    cols: f64,
+    rows: f64,
+    format: &str,
+    refresh_interval_ms: f64,
+) -> Result<Hd44780Output<N>, PictorusError> {
+    let lcd = Hd44780::new(
+        create_i2c_protocol()?,
+        StdDelayProtocol::new(),
+        address as u8,
+        cols as usize,
+        rows as usize,
+    )?;
+    TextDisplay::new(lcd, format, refresh_interval(refresh_interval_ms))
+}
+
+/// Shows the inputs on an SSD1306 OLED at `address` on the I2C bus, formatted by `format`.
+/// `height` is 64 or 32 pixels, and the display is redrawn at most every `refresh_interval_ms`.
+pub fn create_ssd1306_i2c_output<const N: usize>(
+    address: f64,
+    height: f64,
+    format: &str,
+    refresh_interval_ms: f64,
+) -> Result<Ssd1306I2cOutput<N>, PictorusError> {
+    let interface = I2cSsd1306 {
+        i2c: create_i2c_protocol()?,
+        address: address as u8,
+    };
+    let oled = Ssd1306::new(interface, height as usize)?;
+    TextDisplay::new(oled, format, refresh_interval(refresh_interval_ms))
+}
+
+/// Shows the inputs on an SSD1306 OLED on a spidev port, with its D/C line on `dc_pin`,
+/// formatted by `format`. `height` is 64 or 32 pixels, and the display is redrawn at most every
+/// `refresh_interval_ms`.
+pub fn create_ssd1306_spi_output<const N: usize>(
+    port: &str,
+    frequency: u32,
+    dc_pin: f64,
+    height: f64,
+    format: &str,
+    refresh_interval_ms: f64,
+) -> Result<Ssd1306SpiOutput<N>, PictorusError> {
+    let interface = SpiSsd1306 {
+        spi: open_spidev_bus(port, frequency)?,
+        dc: create_gpio_output_pin(dc_pin)?,
+    };
+    let oled = Ssd1306::new(interface, height as usize)?;
+    TextDisplay::new(oled, format, refresh_interval(refresh_interval_ms))
+}
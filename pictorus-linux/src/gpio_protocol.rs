@@ -1,11 +1,11 @@
 pub use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
 use linux_embedded_hal::gpio_cdev::{Chip, LineRequestFlags};
 use pictorus_blocks::{GpioInputBlockParams, GpioOutputBlockParams};
-use pictorus_internal::utils::PictorusError;
+use pictorus_internal::utils::{ErrorKind, PictorusError};
 use pictorus_traits::{Context, InputBlock, OutputBlock, PassBy};
 
 // TODO: This should be configurable by block param
-const GPIO_CHIP: &str = "/dev/gpiochip0";
+pub(crate) const GPIO_CHIP: &str = "/dev/gpiochip0";
 const ERR_TYPE: &str = "GpioProtocol";
 
 pub struct CdevPin(linux_embedded_hal::CdevPin);
@@ -39,12 +39,15 @@ impl OutputPin for CdevPin {
     }
 }
 
-fn create_error(message: String) -> PictorusError {
-    PictorusError::new(ERR_TYPE.into(), message)
+fn create_error(kind: ErrorKind, message: String) -> PictorusError {
+    PictorusError::new(kind, ERR_TYPE, message)
 }
 
 fn create_pin_error(pin: u32) -> PictorusError {
-    create_error(format!("Failed to bind to GPIO pin: {pin}"))
+    create_error(
+        ErrorKind::DeviceConfig,
+        format!("Failed to bind to GPIO pin: {pin}"),
+    )
 }
 
 fn create_cdev_pin(
@@ -54,9 +57,10 @@ fn create_cdev_pin(
 ) -> Result<CdevPin, PictorusError> {
     let pin_line = pin_line as u32;
     let mut chip = Chip::new(chip).map_err(|_| {
-        create_error(format!(
-            "Failed to bind to GPIO bus {chip} for pin: {pin_line}",
-        ))
+        create_error(
+            ErrorKind::NotFound,
+            format!("Failed to bind to GPIO bus {chip} for pin: {pin_line}",),
+        )
     })?;
     let handle = chip
         .get_line(pin_line)
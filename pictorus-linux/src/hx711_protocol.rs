@@ -0,0 +1,36 @@
+use linux_embedded_hal::SpidevBus;
+use pictorus_internal::hx711::{GpioHx711, Hx711, SpiHx711};
+use pictorus_internal::utils::PictorusError;
+
+use crate::spi_protocol::open_spidev_bus;
+use crate::{CdevPin, create_gpio_input_pin, create_gpio_output_pin};
+
+pub use pictorus_internal::hx711::Hx711Params;
+
+pub type Hx711GpioInput = Hx711<GpioHx711<CdevPin, CdevPin>>;
+pub type Hx711SpiInput = Hx711<SpiHx711<SpidevBus>>;
+
+/// Reads an HX711 by bit-banging PD_SCK on `clock_pin` and reading DOUT on `data_pin`. `gain`
+/// is 128 or 64 for channel A, or 32 for channel B.
+pub fn create_hx711_gpio_input(
+    clock_pin: f64,
+    data_pin: f64,
+    gain: f64,
+) -> Result<Hx711GpioInput, PictorusError> {
+    let interface = GpioHx711 {
+        clock: create_gpio_output_pin(clock_pin)?,
+        data: create_gpio_input_pin(data_pin)?,
+    };
+    Hx711::new(interface, gain)
+}
+
+/// Reads an HX711 from a spidev port, with PD_SCK on MOSI and DOUT on MISO. `frequency` is the
+/// SPI clock, twice the PD_SCK rate, and `gain` is 128 or 64 for channel A, or 32 for channel B.
+pub fn create_hx711_spi_input(
+    port: &str,
+    frequency: u32,
+    gain: f64,
+) -> Result<Hx711SpiInput, PictorusError> {
+    let bus = open_spidev_bus(port, frequency)?;
+    Hx711::new(SpiHx711(bus), gain)
+}
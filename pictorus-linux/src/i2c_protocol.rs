@@ -5,7 +5,7 @@ use pictorus_blocks::{I2cInputBlockParams, I2cOutputBlockParams};
 use pictorus_traits::{ByteSliceSignal, InputBlock, OutputBlock};
 
 use pictorus_internal::protocols::I2c;
-use pictorus_internal::utils::PictorusError;
+use pictorus_internal::utils::{ErrorKind, PictorusError};
 
 const ERR_TYPE: &str = "I2cProtocol";
 // TODO: This should be configurable by block param
@@ -13,18 +13,25 @@ const I2C_PATH: &str = "/dev/i2c-1";
 
 pub fn create_i2c_protocol() -> Result<I2cdev, PictorusError> {
     let i2c = I2cdev::new(I2C_PATH).map_err(|err| {
-        let msg = match err {
-            LinuxI2CError::Errno(e) => {
-                format!("Unknown error! Failed to bind to I2C device: {I2C_PATH} ({e})",)
-            }
+        let (kind, msg) = match err {
+            LinuxI2CError::Errno(e) => (
+                ErrorKind::Other,
+                format!("Unknown error! Failed to bind to I2C device: {I2C_PATH} ({e})",),
+            ),
             LinuxI2CError::Io(e) => match e.kind() {
-                std::io::ErrorKind::NotFound => format!(
-                    "Failed to bind to I2C device: {I2C_PATH} - not found. Is the I2C bus enabled?",
+                std::io::ErrorKind::NotFound => (
+                    ErrorKind::NotFound,
+                    format!(
+                        "Failed to bind to I2C device: {I2C_PATH} - not found. Is the I2C bus enabled?",
+                    ),
+                ),
+                kind => (
+                    kind.into(),
+                    format!("Unknown error! Failed to bind to I2C device: {I2C_PATH} ({e})",),
                 ),
-                _ => format!("Unknown error! Failed to bind to I2C device: {I2C_PATH} ({e})",),
             },
         };
-        PictorusError::new(ERR_TYPE.into(), msg)
+        PictorusError::new(kind, ERR_TYPE, msg)
     })?;
 
     Ok(i2c)
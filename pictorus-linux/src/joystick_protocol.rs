@@ -0,0 +1,166 @@
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Read};
+use std::os::unix::fs::OpenOptionsExt;
+use std::time::Duration;
+
+use pictorus_traits::{Context, InputBlock, Matrix, Pass, PassBy};
+
+// Event types of the Linux joystick API (linux/joystick.h)
+const JS_EVENT_BUTTON: u8 = 0x01;
+const JS_EVENT_AXIS: u8 = 0x02;
+/// Flags the events describing the initial state, sent when the device is opened
+const JS_EVENT_INIT: u8 = 0x80;
+/// Size of a `struct js_event`: u32 timestamp, i16 value, u8 type, u8 number
+const JS_EVENT_SIZE: usize = 8;
+const AXIS_MAX: f64 = 32767.0;
+
+/// Parameters for the JoystickBlock
+pub struct JoystickBlockParams {
+    /// Path of the joystick device, e.g. /dev/input/js0
+    pub device_path: String,
+    /// Fraction of each axis' travel around center that reads as zero
+    pub deadzone: f64,
+    /// How often a missing joystick is looked for
+    pub reconnect_period: Duration,
+}
+
+impl JoystickBlockParams {
+    pub fn new(device: f64, deadzone: f64, reconnect_period_ms: f64) -> Self {
+        Self {
+            device_path: format!("/dev/input/js{}", device as u32),
+            deadzone: deadzone.clamp(0.0, 0.99),
+            reconnect_period: Duration::from_secs_f64(reconnect_period_ms.max(0.0) / 1000.0),
+        }
+    }
+}
+
+/// Reads a joystick or gamepad through the Linux joystick API, for teleoperating models from a
+/// desktop.
+///
+/// The output is a tuple of (axes, buttons, connected). Axes are scaled to -1 to 1 (the axis
+/// numbering follows the driver, e.g. left stick X and Y first on most gamepads), with the
+/// deadzone around center reading as zero and the remaining travel rescaled to stay continuous.
+/// Buttons are true while pressed. Axes and buttons beyond the sizes of the outputs are ignored.
+///
+/// The device is read without blocking, taking every event queued since the previous tick.
+/// While the joystick is unplugged or missing, connected is false and the axes and buttons
+/// read as zero and released, so a model can fail safe; the device is reopened every reconnect
+/// period, and its current state is read back when it returns.
+pub struct JoystickBlock<const AXES: usize, const BUTTONS: usize> {
+    device: Option<File>,
+    /// Time of the next attempt to open the device, or None to try on the next tick
+    next_connect: Option<Duration>,
+    raw_axes: [i16; AXES],
+    buffer: (Matrix<1, AXES, f64>, Matrix<1, BUTTONS, bool>, bool),
+}
+
+impl<const AXES: usize, const BUTTONS: usize> JoystickBlock<AXES, BUTTONS> {
+    pub fn new() -> Self {
+        Self {
+            device: None,
+            next_connect: None,
+            raw_axes: [0; AXES],
+            buffer: (Matrix::zeroed(), Matrix::zeroed(), false),
+        }
+    }
+
+    fn connect(&mut self, parameters: &JoystickBlockParams, time: Duration) {
+        if self.next_connect.is_some_and(|next| time < next) {
+            return;
+        }
+        match OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(&parameters.device_path)
+        {
+            Ok(device) => {
+                log::info!("Connected to joystick {}", parameters.device_path);
+                self.device = Some(device);
+                self.next_connect = None;
+            }
+            Err(err) => {
+                log::debug!("Failed to open joystick {}: {err}", parameters.device_path);
+                self.next_connect = Some(time + parameters.reconnect_period);
+            }
+        }
+    }
+
+    /// Applies the queued events, returning false if the device was lost
+    fn read_events(&mut self) -> bool {
+        let Some(device) = &mut self.device else {
+            return false;
+        };
+        let mut events = [0; JS_EVENT_SIZE * 64];
+        loop {
+            let read = match device.read(&mut events) {
+                Ok(0) => return false,
+                Ok(read) => read,
+                Err(err) if err.kind() == ErrorKind::WouldBlock => return true,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    log::warn!("Lost joystick: {err}");
+                    return false;
+                }
+            };
+            for event in events[..read].chunks_exact(JS_EVENT_SIZE) {
+                let value = i16::from_ne_bytes([event[4], event[5]]);
+                let number = usize::from(event[7]);
+                match event[6] & !JS_EVENT_INIT {
+                    JS_EVENT_AXIS if number < AXES => self.raw_axes[number] = value,
+                    JS_EVENT_BUTTON if number < BUTTONS => {
+                        self.buffer.1.data[number][0] = value != 0
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn disconnect(&mut self, parameters: &JoystickBlockParams, time: Duration) {
+        self.device = None;
+        self.next_connect = Some(time + parameters.reconnect_period);
+        self.raw_axes = [0; AXES];
+        self.buffer = (Matrix::zeroed(), Matrix::zeroed(), false);
+    }
+}
+
+impl<const AXES: usize, const BUTTONS: usize> Default for JoystickBlock<AXES, BUTTONS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Scales a raw axis value to -1 to 1, with values inside the deadzone reading as zero
+fn scale_axis(raw: i16, deadzone: f64) -> f64 {
+    let value = (f64::from(raw) / AXIS_MAX).clamp(-1.0, 1.0);
+    if value.abs() <= deadzone {
+        return 0.0;
+    }
+    value.signum() * (value.abs() - deadzone) / (1.0 - deadzone)
+}
+
+impl<const AXES: usize, const BUTTONS: usize> InputBlock for JoystickBlock<AXES, BUTTONS> {
+    type Output = (Matrix<1, AXES, f64>, Matrix<1, BUTTONS, bool>, bool);
+    type Parameters = JoystickBlockParams;
+
+    fn input(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        let time = context.time();
+        if self.device.is_none() {
+            self.connect(parameters, time);
+        }
+        if self.device.is_some() && !self.read_events() {
+            self.disconnect(parameters, time);
+        }
+
+        let connected = self.device.is_some();
+        for (axis, raw) in self.buffer.0.data.iter_mut().zip(self.raw_axes) {
+            axis[0] = scale_axis(raw, parameters.deadzone);
+        }
+        self.buffer.2 = connected;
+        self.buffer.as_by()
+    }
+}
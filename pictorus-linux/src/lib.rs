@@ -2,19 +2,60 @@
 //! on Linux-based platforms (i.e. Raspberry Pi). These are typically defined as `InputBlock`
 //! or `OutputBlock` interfaces as defined in the `pictorus-traits` crate.
 
-pub use pictorus_std::{clock_protocol::*, delay_protocol::*, serial_protocol::*, udp_protocol::*};
+pub use pictorus_std::{
+    app_runner::*, clock_protocol::*, delay_protocol::*, serial_protocol::*, udp_protocol::*,
+};
+
+mod audio_protocol;
+pub use audio_protocol::*;
+
+mod camera_protocol;
+pub use camera_protocol::*;
+
+mod display_protocol;
+pub use display_protocol::*;
 
 mod gpio_protocol;
 pub use gpio_protocol::*;
 
+mod hx711_protocol;
+pub use hx711_protocol::*;
+
 mod i2c_protocol;
 pub use i2c_protocol::*;
 
+mod joystick_protocol;
+pub use joystick_protocol::*;
+
 mod pwm_protocol;
 pub use pwm_protocol::*;
 
+mod pwm_capture_protocol;
+pub use pwm_capture_protocol::*;
+
 mod can_protocol;
 pub use can_protocol::*;
 
+mod lora_protocol;
+pub use lora_protocol::*;
+
+mod process_protocol;
+pub use process_protocol::*;
+
+mod rangefinder_protocol;
+pub use rangefinder_protocol::*;
+
+mod shift_register_protocol;
+pub use shift_register_protocol::*;
+
 mod spi_protocol;
 pub use spi_protocol::*;
+
+mod stepper_protocol;
+pub use stepper_protocol::*;
+
+mod system_stats_protocol;
+pub use system_stats_protocol::*;
+
+mod tone_protocol;
+pub use tone_protocol::*;
@@ -0,0 +1,35 @@
+use linux_embedded_hal::SpidevBus;
+use pictorus_internal::lora::{LoRaConfig, LoRaConnection, Sx126x, Sx127x};
+use pictorus_internal::utils::PictorusError;
+
+use super::CdevPin;
+use crate::spi_protocol::open_spidev_bus;
+
+pub use pictorus_internal::lora::LoRaParams;
+
+pub type Sx127xConnection = LoRaConnection<Sx127x<SpidevBus, CdevPin>>;
+pub type Sx126xConnection = LoRaConnection<Sx126x<SpidevBus, CdevPin, CdevPin>>;
+
+/// Opens an SX127x (RFM95/96/98) radio on the given spidev port and applies `config`
+pub fn create_sx127x_connection(
+    port: &str,
+    frequency: u32,
+    cs: CdevPin,
+    config: &LoRaConfig,
+) -> Result<Sx127xConnection, PictorusError> {
+    let bus = open_spidev_bus(port, frequency)?;
+    LoRaConnection::new(Sx127x::new(bus, cs), config)
+}
+
+/// Opens an SX126x radio on the given spidev port and applies `config`. `busy` must be
+/// configured as an input.
+pub fn create_sx126x_connection(
+    port: &str,
+    frequency: u32,
+    cs: CdevPin,
+    busy: CdevPin,
+    config: &LoRaConfig,
+) -> Result<Sx126xConnection, PictorusError> {
+    let bus = open_spidev_bus(port, frequency)?;
+    LoRaConnection::new(Sx126x::new(bus, cs, busy), config)
+}
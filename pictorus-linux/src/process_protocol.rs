@@ -0,0 +1,134 @@
+use std::process::{Command, Output};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use log::warn;
+use pictorus_traits::{ByteSliceSignal, Context, InputBlock, OutputBlock, PassBy};
+
+/// Parameters for the process exec blocks
+pub struct ProcessExecParams {
+    /// Command line to run, interpreted by `sh -c`
+    pub command: String,
+    /// Minimum time between the starts of successive runs
+    pub min_interval: Duration,
+}
+
+impl ProcessExecParams {
+    pub fn new(command: &str, min_interval_ms: f64) -> Self {
+        Self {
+            command: command.into(),
+            min_interval: Duration::from_secs_f64(min_interval_ms.max(0.0) / 1000.0),
+        }
+    }
+}
+
+/// Runs a shell command when triggered, for integrating with existing system tooling.
+///
+/// As an `OutputBlock`, a rising edge on the trigger input starts the command in the background,
+/// so a slow command never stalls the model. Triggers are ignored while the previous run is still
+/// in progress, or if less than the minimum interval has passed since it started.
+///
+/// As an `InputBlock`, the output is a tuple of (stdout, exit code) from the most recently
+/// completed run. The exit code is NaN until a run completes, or if the command couldn't be
+/// started or was killed by a signal.
+pub struct ProcessExec {
+    running: Option<JoinHandle<std::io::Result<Output>>>,
+    last_start: Option<Duration>,
+    previous_trigger: bool,
+    stdout: Vec<u8>,
+    exit_code: f64,
+}
+
+impl ProcessExec {
+    pub fn new() -> Self {
+        Self {
+            running: None,
+            last_start: None,
+            previous_trigger: false,
+            stdout: Vec::new(),
+            exit_code: f64::NAN,
+        }
+    }
+
+    /// Collects the result of the running command if it has finished
+    fn poll(&mut self) {
+        if !self
+            .running
+            .as_ref()
+            .is_some_and(|handle| handle.is_finished())
+        {
+            return;
+        }
+        let Some(handle) = self.running.take() else {
+            return;
+        };
+        match handle.join() {
+            Ok(Ok(output)) => {
+                self.stdout = output.stdout;
+                self.exit_code = output.status.code().map_or(f64::NAN, f64::from);
+            }
+            Ok(Err(err)) => {
+                warn!("Failed to run command: {err}");
+                self.stdout.clear();
+                self.exit_code = f64::NAN;
+            }
+            Err(_) => {
+                self.stdout.clear();
+                self.exit_code = f64::NAN;
+            }
+        }
+    }
+}
+
+impl Default for ProcessExec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OutputBlock for ProcessExec {
+    type Inputs = bool;
+    type Parameters = ProcessExecParams;
+
+    fn output(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) {
+        let rising_edge = inputs && !self.previous_trigger;
+        self.previous_trigger = inputs;
+        self.poll();
+        if !rising_edge || self.running.is_some() {
+            return;
+        }
+
+        let now = context.time();
+        if self
+            .last_start
+            .is_some_and(|last_start| now < last_start + parameters.min_interval)
+        {
+            return;
+        }
+        self.last_start = Some(now);
+
+        let command = parameters.command.clone();
+        self.running = Some(std::thread::spawn(move || {
+            Command::new("sh").arg("-c").arg(command).output()
+        }));
+    }
+}
+
+impl InputBlock for ProcessExec {
+    type Output = (ByteSliceSignal, f64); // (Stdout, Exit code)
+    type Parameters = ProcessExecParams;
+
+    fn input(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        self.poll();
+        (&self.stdout, self.exit_code)
+    }
+}
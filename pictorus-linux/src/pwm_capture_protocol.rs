@@ -0,0 +1,225 @@
+use std::os::fd::AsRawFd;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use linux_embedded_hal::gpio_cdev::{
+    Chip, EventRequestFlags, EventType, LineEventHandle, LineRequestFlags,
+};
+use log::warn;
+use pictorus_internal::utils::{ErrorKind, PictorusError};
+use pictorus_traits::{Context, InputBlock, Matrix, Pass, PassBy};
+
+use crate::gpio_protocol::GPIO_CHIP;
+
+const ERR_TYPE: &str = "PwmCaptureProtocol";
+/// Gap between PPM pulses that marks the start of a new frame. Channel pulses are at most ~2.1ms.
+const PPM_SYNC_GAP_NS: u64 = 2_700_000;
+/// How long the capture threads wait for an edge before checking whether to stop
+const POLL_TIMEOUT_MS: i32 = 100;
+
+/// Parameters for the PWM capture block
+pub struct PwmCaptureParams {
+    /// How long a channel keeps its last value without a new pulse, e.g. after the receiver
+    /// loses power
+    pub timeout: Duration,
+}
+
+impl PwmCaptureParams {
+    pub fn new(timeout_ms: f64) -> Self {
+        Self {
+            timeout: Duration::from_secs_f64(timeout_ms.max(0.0) / 1000.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Channel {
+    /// Latest pulse width in seconds
+    width: f64,
+    /// When the latest pulse was measured
+    updated: Option<Instant>,
+}
+
+type Channels<const N: usize> = Arc<Mutex<[Channel; N]>>;
+
+fn store<const N: usize>(channels: &Channels<N>, index: usize, width_ns: u64) {
+    if let Ok(mut channels) = channels.lock() {
+        channels[index] = Channel {
+            width: width_ns as f64 * 1e-9,
+            updated: Some(Instant::now()),
+        };
+    }
+}
+
+/// Measures PWM or PPM pulse widths on GPIO inputs, e.g. from an RC receiver.
+///
+/// Edges are captured by helper threads from gpiod line events, which the kernel timestamps as
+/// they happen, so the measurements don't depend on the model rate or on thread scheduling.
+/// The output is the latest pulse width of each channel in seconds (typically 1ms to 2ms for
+/// RC receivers). A channel that hasn't had a pulse within the timeout outputs NaN, so a lost
+/// receiver can be detected and handled as a failsafe.
+pub struct PwmCapture<const N: usize> {
+    channels: Channels<N>,
+    stop: Arc<AtomicBool>,
+    threads: Vec<JoinHandle<()>>,
+    buffer: Matrix<1, N, f64>,
+}
+
+fn create_error(kind: ErrorKind, message: String) -> PictorusError {
+    PictorusError::new(kind, ERR_TYPE, message)
+}
+
+fn request_events(pin: f64) -> Result<LineEventHandle, PictorusError> {
+    let pin = pin as u32;
+    let mut chip = Chip::new(GPIO_CHIP).map_err(|_| {
+        create_error(
+            ErrorKind::NotFound,
+            format!("Failed to bind to GPIO bus {GPIO_CHIP} for pin: {pin}"),
+        )
+    })?;
+    chip.get_line(pin)
+        .and_then(|line| {
+            line.events(
+                LineRequestFlags::INPUT,
+                EventRequestFlags::BOTH_EDGES,
+                "pictorus",
+            )
+        })
+        .map_err(|_| {
+            create_error(
+                ErrorKind::DeviceConfig,
+                format!("Failed to request edge events on GPIO pin: {pin}"),
+            )
+        })
+}
+
+/// Handles the edges of a line until `stop` is set, with their kernel timestamp in nanoseconds
+fn run_capture(
+    mut events: LineEventHandle,
+    stop: &AtomicBool,
+    mut on_edge: impl FnMut(EventType, u64),
+) {
+    let mut poll_fd = libc::pollfd {
+        fd: events.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    while !stop.load(Ordering::Relaxed) {
+        // Wait with a timeout rather than blocking in get_event, so the thread can be stopped
+        // SAFETY: poll_fd is a valid pollfd and the count matches
+        let ready = unsafe { libc::poll(&mut poll_fd, 1, POLL_TIMEOUT_MS) };
+        if ready <= 0 {
+            continue;
+        }
+        match events.get_event() {
+            Ok(event) => on_edge(event.event_type(), event.timestamp()),
+            Err(err) => {
+                warn!("Stopping PWM capture after failing to read edge event: {err}");
+                return;
+            }
+        }
+    }
+}
+
+impl<const N: usize> PwmCapture<N> {
+    fn new() -> Self {
+        Self {
+            channels: Arc::new(Mutex::new(
+                [Channel {
+                    width: f64::NAN,
+                    updated: None,
+                }; N],
+            )),
+            stop: Arc::new(AtomicBool::new(false)),
+            threads: Vec::new(),
+            buffer: Matrix {
+                data: [[f64::NAN]; N],
+            },
+        }
+    }
+}
+
+/// Captures one PWM channel per pin, measuring the time from each rising edge to the next
+/// falling edge
+pub fn create_pwm_capture<const N: usize>(pins: [f64; N]) -> Result<PwmCapture<N>, PictorusError> {
+    let mut capture = PwmCapture::new();
+    for (index, pin) in pins.into_iter().enumerate() {
+        let events = request_events(pin)?;
+        let channels = capture.channels.clone();
+        let stop = capture.stop.clone();
+        capture.threads.push(thread::spawn(move || {
+            let mut rising = None;
+            run_capture(events, &stop, |event, timestamp| match event {
+                EventType::RisingEdge => rising = Some(timestamp),
+                EventType::FallingEdge => {
+                    if let Some(start) = rising.take() {
+                        store(&channels, index, timestamp.saturating_sub(start));
+                    }
+                }
+            });
+        }));
+    }
+    Ok(capture)
+}
+
+/// Captures `N` channels of a PPM stream on a single pin. Each channel is the time between
+/// successive rising edges, and a gap longer than any channel pulse starts a new frame.
+pub fn create_ppm_capture<const N: usize>(pin: f64) -> Result<PwmCapture<N>, PictorusError> {
+    let mut capture = PwmCapture::new();
+    let events = request_events(pin)?;
+    let channels = capture.channels.clone();
+    let stop = capture.stop.clone();
+    capture.threads.push(thread::spawn(move || {
+        let mut last_rising: Option<u64> = None;
+        // Channels aren't recorded until the first sync gap, since the position in the frame
+        // is unknown before then
+        let mut index = N;
+        run_capture(events, &stop, |event, timestamp| {
+            if !matches!(event, EventType::RisingEdge) {
+                return;
+            }
+            if let Some(last) = last_rising.replace(timestamp) {
+                let interval = timestamp.saturating_sub(last);
+                if interval >= PPM_SYNC_GAP_NS {
+                    index = 0;
+                } else if index < N {
+                    store(&channels, index, interval);
+                    index += 1;
+                }
+            }
+        });
+    }));
+    Ok(capture)
+}
+
+impl<const N: usize> Drop for PwmCapture<N> {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        for thread in self.threads.drain(..) {
+            thread.join().ok();
+        }
+    }
+}
+
+impl<const N: usize> InputBlock for PwmCapture<N> {
+    type Output = Matrix<1, N, f64>;
+    type Parameters = PwmCaptureParams;
+
+    fn input(
+        &mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        if let Ok(channels) = self.channels.lock() {
+            for (output, channel) in self.buffer.data.iter_mut().zip(channels.iter()) {
+                let fresh = channel
+                    .updated
+                    .is_some_and(|updated| updated.elapsed() <= parameters.timeout);
+                output[0] = if fresh { channel.width } else { f64::NAN };
+            }
+        }
+        self.buffer.as_by()
+    }
+}
@@ -0,0 +1,49 @@
+use linux_embedded_hal::I2cdev;
+use pictorus_internal::rangefinder::{HcSr04, Vl53l0x, Vl53l1x};
+use pictorus_internal::utils::PictorusError;
+
+use crate::{
+    CdevPin, StandardClock, StdDelayProtocol, create_gpio_input_pin, create_gpio_output_pin,
+    create_i2c_protocol,
+};
+
+pub use pictorus_internal::rangefinder::RangefinderParams;
+
+pub type HcSr04Input = HcSr04<CdevPin, CdevPin, StandardClock, StdDelayProtocol>;
+pub type Vl53l0xInput = Vl53l0x<I2cdev, StdDelayProtocol>;
+pub type Vl53l1xInput = Vl53l1x<I2cdev, StdDelayProtocol>;
+
+/// Measures range with an HC-SR04, pulsing TRIG on `trigger_pin` and timing ECHO on `echo_pin`.
+/// The ECHO output is 5V, so it needs a level shifter or divider on a Raspberry Pi.
+/// `max_range` is in meters, and bounds how long each tick waits for the echo.
+pub fn create_hc_sr04_input(
+    trigger_pin: f64,
+    echo_pin: f64,
+    max_range: f64,
+) -> Result<HcSr04Input, PictorusError> {
+    Ok(HcSr04::new(
+        create_gpio_output_pin(trigger_pin)?,
+        create_gpio_input_pin(echo_pin)?,
+        StandardClock::default(),
+        StdDelayProtocol::new(),
+        max_range,
+    ))
+}
+
+/// Initializes a VL53L0X time-of-flight sensor at `address` on the I2C bus and starts ranging
+pub fn create_vl53l0x_input(address: f64) -> Result<Vl53l0xInput, PictorusError> {
+    Vl53l0x::new(
+        create_i2c_protocol()?,
+        StdDelayProtocol::new(),
+        address as u8,
+    )
+}
+
+/// Initializes a VL53L1X time-of-flight sensor at `address` on the I2C bus and starts ranging
+pub fn create_vl53l1x_input(address: f64) -> Result<Vl53l1xInput, PictorusError> {
+    Vl53l1x::new(
+        create_i2c_protocol()?,
+        StdDelayProtocol::new(),
+        address as u8,
+    )
+}
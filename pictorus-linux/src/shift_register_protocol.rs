@@ -0,0 +1,65 @@
+use linux_embedded_hal::SpidevBus;
+use pictorus_internal::shift_register::{GpioShift, Hc165, Hc595, SpiShift};
+use pictorus_internal::utils::PictorusError;
+
+use crate::spi_protocol::open_spidev_bus;
+use crate::{CdevPin, create_gpio_input_pin, create_gpio_output_pin};
+
+pub use pictorus_internal::shift_register::ShiftRegisterParams;
+
+pub type Hc595SpiOutput<const N: usize> = Hc595<SpiShift<SpidevBus>, CdevPin, N>;
+pub type Hc595GpioOutput<const N: usize> = Hc595<GpioShift<CdevPin, CdevPin>, CdevPin, N>;
+pub type Hc165SpiInput<const N: usize> = Hc165<SpiShift<SpidevBus>, CdevPin, N>;
+pub type Hc165GpioInput<const N: usize> = Hc165<GpioShift<CdevPin, CdevPin>, CdevPin, N>;
+
+/// Drives a chain of 74HC595 registers from a spidev port (SER on MOSI, SRCLK on SCLK), with
+/// RCLK on `latch_pin`
+pub fn create_hc595_spi_output<const N: usize>(
+    port: &str,
+    frequency: u32,
+    latch_pin: f64,
+) -> Result<Hc595SpiOutput<N>, PictorusError> {
+    let bus = open_spidev_bus(port, frequency)?;
+    Ok(Hc595::new(
+        SpiShift(bus),
+        create_gpio_output_pin(latch_pin)?,
+    ))
+}
+
+/// Drives a chain of 74HC595 registers by bit-banging SRCLK and SER, with RCLK on `latch_pin`
+pub fn create_hc595_gpio_output<const N: usize>(
+    clock_pin: f64,
+    data_pin: f64,
+    latch_pin: f64,
+) -> Result<Hc595GpioOutput<N>, PictorusError> {
+    let shift = GpioShift {
+        clock: create_gpio_output_pin(clock_pin)?,
+        data: create_gpio_output_pin(data_pin)?,
+    };
+    Ok(Hc595::new(shift, create_gpio_output_pin(latch_pin)?))
+}
+
+/// Reads a chain of 74HC165 registers from a spidev port (QH on MISO, CLK on SCLK), with SH/LD
+/// on `load_pin`
+pub fn create_hc165_spi_input<const N: usize>(
+    port: &str,
+    frequency: u32,
+    load_pin: f64,
+) -> Result<Hc165SpiInput<N>, PictorusError> {
+    let bus = open_spidev_bus(port, frequency)?;
+    Ok(Hc165::new(SpiShift(bus), create_gpio_output_pin(load_pin)?))
+}
+
+/// Reads a chain of 74HC165 registers by bit-banging CLK and reading QH, with SH/LD on
+/// `load_pin`
+pub fn create_hc165_gpio_input<const N: usize>(
+    clock_pin: f64,
+    data_pin: f64,
+    load_pin: f64,
+) -> Result<Hc165GpioInput<N>, PictorusError> {
+    let shift = GpioShift {
+        clock: create_gpio_output_pin(clock_pin)?,
+        data: create_gpio_input_pin(data_pin)?,
+    };
+    Ok(Hc165::new(shift, create_gpio_output_pin(load_pin)?))
+}
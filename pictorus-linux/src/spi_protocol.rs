@@ -1,13 +1,37 @@
 use std::io::{Read, Write};
 
-use linux_embedded_hal::spidev::{Spidev, SpidevOptions};
+use linux_embedded_hal::SpidevBus;
+use linux_embedded_hal::spidev::{SpiModeFlags, Spidev, SpidevOptions};
 use pictorus_blocks::{SpiReceiveBlockParams, SpiTransmitBlockParams};
 use pictorus_traits::ByteSliceSignal;
 use pictorus_traits::{Context, InputBlock, OutputBlock, PassBy};
 
 use super::CdevPin;
 use pictorus_internal::protocols::{Flush, OutputPin};
-use pictorus_internal::utils::PictorusError;
+use pictorus_internal::utils::{ErrorKind, PictorusError};
+
+/// Opens a spidev port as an `embedded-hal` bus in mode 0, for chip drivers that manage their
+/// own chip select
+pub(crate) fn open_spidev_bus(port: &str, frequency: u32) -> Result<SpidevBus, PictorusError> {
+    let mut spi = Spidev::open(port).map_err(|err| {
+        PictorusError::from_io("SpiConnection", "Failed to open SPI device", &err)
+    })?;
+
+    let mut options = SpidevOptions::new();
+    options
+        .mode(SpiModeFlags::SPI_MODE_0)
+        .bits_per_word(8)
+        .max_speed_hz(frequency);
+    spi.configure(&options).map_err(|_err| {
+        PictorusError::new(
+            ErrorKind::DeviceConfig,
+            "SpiConnection",
+            "Failed to configure SPI device",
+        )
+    })?;
+
+    Ok(SpidevBus(spi))
+}
 
 pub struct SpiConnection {
     device: Spidev,
@@ -25,8 +49,8 @@ impl SpiConnection {
         mode: &'static str,
         cs: CdevPin,
     ) -> Result<Self, PictorusError> {
-        let mut spi = Spidev::open(port).map_err(|_err| {
-            PictorusError::new("SpiConnection".into(), "Failed to open SPI device".into())
+        let mut spi = Spidev::open(port).map_err(|err| {
+            PictorusError::from_io("SpiConnection", "Failed to open SPI device", &err)
         })?;
 
         let mut options = SpidevOptions::new();
@@ -44,8 +68,9 @@ impl SpiConnection {
 
         spi.configure(&options).map_err(|_err| {
             PictorusError::new(
-                "SpiConnection".into(),
-                "Failed to configure SPI device".into(),
+                ErrorKind::DeviceConfig,
+                "SpiConnection",
+                "Failed to configure SPI device",
             )
         })?;
 
@@ -79,8 +104,9 @@ impl InputBlock for SpiConnection {
                 .read_exact(self.cache.as_mut_slice())
                 .map_err(|_err| {
                     PictorusError::new(
-                        "SpiConnection".into(),
-                        "Failed to read from SPI device in ::read".into(),
+                        ErrorKind::Io,
+                        "SpiConnection",
+                        "Failed to read from SPI device in ::read",
                     )
                 });
 
@@ -91,8 +117,9 @@ impl InputBlock for SpiConnection {
 
             let result = self.cs.set_high().map_err(|_err| {
                 PictorusError::new(
-                    "SpiConnection".into(),
-                    "Failed to set CS pin in ::write".into(),
+                    ErrorKind::Io,
+                    "SpiConnection",
+                    "Failed to set CS pin in ::write",
                 )
             });
 
@@ -121,8 +148,9 @@ impl OutputBlock for SpiConnection {
             .set_low()
             .map_err(|_err| {
                 PictorusError::new(
-                    "SpiConnection".into(),
-                    "Failed to set CS pin in ::write".into(),
+                    ErrorKind::Io,
+                    "SpiConnection",
+                    "Failed to set CS pin in ::write",
                 )
             })
             .ok();
@@ -132,8 +160,9 @@ impl OutputBlock for SpiConnection {
             .write(inputs)
             .map_err(|_err| {
                 PictorusError::new(
-                    "SpiConnection".into(),
-                    "Failed to write to SPI device in ::write_u8".into(),
+                    ErrorKind::Io,
+                    "SpiConnection",
+                    "Failed to write to SPI device in ::write_u8",
                 )
             })
             .ok();
@@ -147,8 +176,9 @@ impl Flush for SpiConnection {
             .set_high()
             .map_err(|_err| {
                 PictorusError::new(
-                    "SpiConnection".into(),
-                    "Failed to set CS pin in ::write".into(),
+                    ErrorKind::Io,
+                    "SpiConnection",
+                    "Failed to set CS pin in ::write",
                 )
             })
             .ok();
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use embedded_hal::digital::OutputPin;
+use pictorus_blocks::StepperBlockParams;
+use pictorus_internal::utils::PictorusError;
+use pictorus_traits::{Context, OutputBlock, PassBy};
+
+use crate::{CdevPin, create_gpio_output_pin};
+
+/// How long the pulse thread waits before checking for a new rate while stopped
+const IDLE_POLL: Duration = Duration::from_millis(1);
+
+/// Generates step/dir pulse trains on a pair of GPIO lines.
+///
+/// Pulses are generated on a dedicated thread, since step rates are usually much higher than the
+/// model rate. Each model tick only updates the requested rate. Timing relies on thread sleeps,
+/// so this is suited to step rates up to a few kHz.
+pub struct StepperOutput {
+    /// Requested step rate in steps per second, stored as f64 bits
+    rate: Arc<AtomicU64>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+pub fn create_stepper_output(step_pin: f64, dir_pin: f64) -> Result<StepperOutput, PictorusError> {
+    let step = create_gpio_output_pin(step_pin)?;
+    let dir = create_gpio_output_pin(dir_pin)?;
+    Ok(StepperOutput::new(step, dir))
+}
+
+impl StepperOutput {
+    pub fn new(step: CdevPin, dir: CdevPin) -> Self {
+        let rate = Arc::new(AtomicU64::new(0f64.to_bits()));
+        let running = Arc::new(AtomicBool::new(true));
+        let thread = {
+            let rate = rate.clone();
+            let running = running.clone();
+            std::thread::spawn(move || pulse_loop(step, dir, &rate, &running))
+        };
+        StepperOutput {
+            rate,
+            running,
+            thread: Some(thread),
+        }
+    }
+}
+
+fn pulse_loop(mut step: CdevPin, mut dir: CdevPin, rate: &AtomicU64, running: &AtomicBool) {
+    while running.load(Ordering::Relaxed) {
+        let rate = f64::from_bits(rate.load(Ordering::Relaxed));
+        if !rate.is_finite() || rate.abs() < 1.0 {
+            std::thread::sleep(IDLE_POLL);
+            continue;
+        }
+
+        if rate > 0.0 {
+            dir.set_high().ok();
+        } else {
+            dir.set_low().ok();
+        }
+        let half_period = Duration::from_secs_f64(0.5 / rate.abs());
+        step.set_high().ok();
+        std::thread::sleep(half_period);
+        step.set_low().ok();
+        std::thread::sleep(half_period);
+    }
+}
+
+impl Drop for StepperOutput {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+impl OutputBlock for StepperOutput {
+    type Inputs = (f64, f64); // (Step rate, Position)
+    type Parameters = StepperBlockParams<f64>;
+
+    fn output(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) {
+        let (rate, _position) = inputs;
+        self.rate.store(rate.to_bits(), Ordering::Relaxed);
+    }
+}
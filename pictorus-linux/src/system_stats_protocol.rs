@@ -0,0 +1,162 @@
+use std::ffi::CString;
+use std::time::Duration;
+
+use pictorus_traits::{Context, InputBlock, PassBy};
+
+/// Parameters for the SystemStatsBlock
+pub struct SystemStatsBlockParams {
+    /// Path on the filesystem to report free space for
+    pub disk_path: CString,
+    /// Path of the sysfs file holding the CPU temperature in millidegrees Celsius
+    pub temperature_path: String,
+    /// How often the statistics are refreshed. Reading them is too slow to do every tick at
+    /// typical model rates.
+    pub update_period: Duration,
+}
+
+impl SystemStatsBlockParams {
+    pub fn new(disk_path: &str, thermal_zone: f64, update_period_ms: f64) -> Self {
+        Self {
+            disk_path: CString::new(disk_path).expect("Disk path must not contain nul bytes"),
+            temperature_path: format!(
+                "/sys/class/thermal/thermal_zone{}/temp",
+                thermal_zone as u32
+            ),
+            update_period: Duration::from_secs_f64(update_period_ms.max(0.0) / 1000.0),
+        }
+    }
+}
+
+/// Reports the health of the host system.
+///
+/// The output is a tuple of (CPU load, memory usage, CPU temperature, disk free space):
+/// - CPU load is the percentage of non-idle CPU time across all cores since the previous update
+/// - Memory usage is the percentage of memory that is not available to new processes
+/// - CPU temperature is in degrees Celsius, read from the configured thermal zone
+/// - Disk free space is the number of bytes available to unprivileged users on the filesystem
+///   holding the configured path
+///
+/// Any statistic that can't be read (e.g. a board without a thermal zone) outputs NaN.
+/// Statistics are refreshed at the configured update period and held in between.
+pub struct SystemStatsBlock {
+    previous_cpu: Option<CpuTimes>,
+    next_update: Option<Duration>,
+    buffer: (f64, f64, f64, f64),
+}
+
+impl SystemStatsBlock {
+    pub fn new() -> Self {
+        Self {
+            previous_cpu: None,
+            next_update: None,
+            buffer: (f64::NAN, f64::NAN, f64::NAN, f64::NAN),
+        }
+    }
+
+    fn cpu_load(&mut self) -> f64 {
+        let current = std::fs::read_to_string("/proc/stat")
+            .ok()
+            .and_then(|stat| parse_cpu_times(&stat));
+        let load = match (self.previous_cpu, current) {
+            (Some(previous), Some(current)) => {
+                let total = current.total.saturating_sub(previous.total);
+                let idle = current.idle.saturating_sub(previous.idle);
+                if total > 0 {
+                    100.0 * (total - idle.min(total)) as f64 / total as f64
+                } else {
+                    self.buffer.0
+                }
+            }
+            _ => f64::NAN,
+        };
+        self.previous_cpu = current;
+        load
+    }
+}
+
+impl Default for SystemStatsBlock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Aggregate CPU time counters, in clock ticks
+#[derive(Clone, Copy)]
+struct CpuTimes {
+    total: u64,
+    idle: u64,
+}
+
+/// Parses the aggregate `cpu` line of `/proc/stat`
+fn parse_cpu_times(stat: &str) -> Option<CpuTimes> {
+    let line = stat.lines().find(|line| line.starts_with("cpu "))?;
+    let fields: Vec<u64> = line
+        .split_whitespace()
+        .skip(1)
+        .map(|field| field.parse().ok())
+        .collect::<Option<_>>()?;
+    // user nice system idle iowait irq softirq steal, the guest times are already included in user
+    let total = fields.iter().take(8).sum();
+    let idle = fields.get(3)? + fields.get(4).unwrap_or(&0);
+    Some(CpuTimes { total, idle })
+}
+
+/// Percentage of memory in use, from the contents of `/proc/meminfo`
+fn parse_memory_usage(meminfo: &str) -> Option<f64> {
+    let field = |name: &str| -> Option<f64> {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(name))?
+            .trim_start_matches(':')
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok()
+    };
+    let total = field("MemTotal")?;
+    let available = field("MemAvailable")?;
+    (total > 0.0).then(|| 100.0 * (total - available) / total)
+}
+
+fn read_temperature(path: &str) -> Option<f64> {
+    let millidegrees: f64 = std::fs::read_to_string(path).ok()?.trim().parse().ok()?;
+    Some(millidegrees / 1000.0)
+}
+
+fn read_disk_free(path: &CString) -> Option<f64> {
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `path` is a valid nul-terminated string and `stats` is a valid write location
+    let result = unsafe { libc::statvfs(path.as_ptr(), &mut stats) };
+    (result == 0).then_some(stats.f_bavail as f64 * stats.f_frsize as f64)
+}
+
+impl InputBlock for SystemStatsBlock {
+    type Output = (f64, f64, f64, f64); // (CPU load %, Memory usage %, CPU temperature C, Disk free bytes)
+    type Parameters = SystemStatsBlockParams;
+
+    fn input(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        let now = context.time();
+        if self
+            .next_update
+            .is_some_and(|next_update| now < next_update)
+        {
+            return self.buffer;
+        }
+        self.next_update = Some(now + parameters.update_period);
+
+        let memory = std::fs::read_to_string("/proc/meminfo")
+            .ok()
+            .and_then(|meminfo| parse_memory_usage(&meminfo));
+        self.buffer = (
+            self.cpu_load(),
+            memory.unwrap_or(f64::NAN),
+            read_temperature(&parameters.temperature_path).unwrap_or(f64::NAN),
+            read_disk_free(&parameters.disk_path).unwrap_or(f64::NAN),
+        );
+        self.buffer
+    }
+}
@@ -0,0 +1,18 @@
+use pictorus_internal::tone::ToneBlock;
+use pictorus_internal::utils::PictorusError;
+
+use crate::{PwmConnection, create_pwm_protocol};
+
+pub use pictorus_internal::tone::ToneParams;
+
+pub type ToneOutput = ToneBlock<PwmConnection>;
+
+/// Plays the semicolon separated tone `sequences` on the PWM output of `pin_number`, at a duty
+/// cycle of `duty` (0 to 1)
+pub fn create_tone_output(
+    pin_number: f64,
+    sequences: &str,
+    duty: f64,
+) -> Result<ToneOutput, PictorusError> {
+    ToneBlock::new(create_pwm_protocol(pin_number)?, (), sequences, duty)
+}
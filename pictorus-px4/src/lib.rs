@@ -42,6 +42,7 @@
 //!
 //! - **[`UorbInputBlock<T>`](uorb_binding::UorbInputBlock)**: Reads uORB messages into Pictorus computation graphs
 //! - **[`UorbOutputBlock<T>`](uorb_binding::UorbOutputBlock)**: Writes Pictorus results back to uORB system
+//! - **[`VehicleCommandBlock`](vehicle_command::VehicleCommandBlock)**: Arms, disarms and switches flight modes, tracking command acks
 //!
 //! ## Usage Example
 //!
@@ -112,6 +113,12 @@ pub mod uorb_binding;
 /// - [`ToPassType`](message_impls::ToPassType)/[`FromPassType`](message_impls::FromPassType): Pictorus integration
 pub mod message_impls;
 
+/// Block for arming, disarming and switching flight modes through `vehicle_command`
+///
+/// See [`VehicleCommandBlock`](vehicle_command::VehicleCommandBlock), which sends the commands
+/// and tracks their acknowledgment.
+pub mod vehicle_command;
+
 /// Critical section implementation for single-threaded PX4 module context
 pub struct CriticalSection;
 critical_section::set_impl!(CriticalSection);
@@ -218,6 +218,43 @@ pub trait FromPassType: UorbMessage {
     fn from_pass_type(timestamp: u64, pass: PassBy<Self::PassType>) -> Self;
 }
 
+/// Trait for reading and writing the array fields of PX4 messages element by element
+///
+/// [`ToPassType`] and [`FromPassType`] only map a fixed subset of each message's fields.
+/// This trait exposes whole array fields by name (e.g. all 12 `control` values of
+/// `actuator_motors`), so [`UorbArrayInputBlock`](crate::uorb_binding::UorbArrayInputBlock)
+/// and [`UorbArrayOutputBlock`](crate::uorb_binding::UorbArrayOutputBlock) can map any
+/// selection of their elements to and from a `Matrix`.
+///
+/// Elements are converted to and from `f64` with `as` casts, so integer elements saturate.
+pub trait ArrayFields: UorbMessage {
+    /// Names of the array fields
+    const ARRAY_FIELDS: &'static [&'static str];
+
+    /// Get the length of an array field
+    ///
+    /// # Returns
+    /// Number of elements in the field, or `None` if the message has no such array field
+    fn array_len(field: &str) -> Option<usize>;
+
+    /// Get one element of an array field
+    ///
+    /// # Returns
+    /// The element as `f64`, or `None` if the field or index doesn't exist
+    fn array_element(&self, field: &str, index: usize) -> Option<f64>;
+
+    /// Set one element of an array field, ignoring fields or indices that don't exist
+    fn set_array_element(&mut self, field: &str, index: usize, value: f64);
+
+    /// Create a message with every field zeroed and the given timestamp
+    fn zeroed(timestamp: u64) -> Self;
+}
+
+/// Get the length of the array a field accessor points to
+fn field_len<M, T, const N: usize>(_field: impl Fn(&M) -> &[T; N]) -> usize {
+    N
+}
+
 // when given a list of messages, this macro will implement the UorbMessage trait for each message type
 macro_rules! impl_uorb_message {
     ($($message:ty),+) => {
@@ -244,6 +281,47 @@ macro_rules! impl_uorb_message {
     };
 }
 
+// Macro to implement ArrayFields for messages, given the names of their array fields
+macro_rules! impl_array_fields {
+    ($($message:ty { $($field:ident),+ $(,)? });+ $(;)?) => {
+        $(
+            impl ArrayFields for $message {
+                const ARRAY_FIELDS: &'static [&'static str] = &[$(stringify!($field)),+];
+
+                fn array_len(field: &str) -> Option<usize> {
+                    match field {
+                        $(stringify!($field) => Some(field_len(|message: &Self| &message.$field)),)+
+                        _ => None,
+                    }
+                }
+
+                fn array_element(&self, field: &str, index: usize) -> Option<f64> {
+                    match field {
+                        $(stringify!($field) => self.$field.get(index).map(|value| *value as f64),)+
+                        _ => None,
+                    }
+                }
+
+                fn set_array_element(&mut self, field: &str, index: usize, value: f64) {
+                    let element = match field {
+                        $(stringify!($field) => self.$field.get_mut(index).map(|element| *element = value as _),)+
+                        _ => None,
+                    };
+                    debug_assert!(element.is_some(), "No element {index} in array field {field}");
+                }
+
+                fn zeroed(timestamp: u64) -> Self {
+                    // SAFETY: PX4 messages are #[repr(C)] structs of integers, floats, bools and
+                    // arrays of those, for which all zero bytes are valid values.
+                    let mut message: Self = unsafe { core::mem::zeroed() };
+                    message.timestamp = timestamp;
+                    message
+                }
+            }
+        )+
+    };
+}
+
 // Macro to define topic ZSTs
 macro_rules! define_topics {
     ($(
@@ -285,7 +363,7 @@ use px4_msgs_sys::{
         __orb_dataman_response, __orb_debug_array, __orb_debug_key_value, __orb_debug_value,
         __orb_debug_vect, __orb_differential_pressure, __orb_distance_sensor,
         __orb_distance_sensor_mode_change_request, __orb_ekf2_timestamps, __orb_esc_report,
-        __orb_esc_serial_passthru, __orb_estimator_aid_src_airspeed,
+        __orb_esc_serial_passthru, __orb_esc_status, __orb_estimator_aid_src_airspeed,
         __orb_estimator_aid_src_aux_global_position, __orb_estimator_aid_src_aux_vel,
         __orb_estimator_aid_src_baro_hgt, __orb_estimator_aid_src_drag,
         __orb_estimator_aid_src_ev_hgt, __orb_estimator_aid_src_ev_pos,
@@ -381,7 +459,7 @@ use px4_msgs_sys::{
         control_allocator_status_s, cpuload_s, dataman_request_s, dataman_response_s,
         debug_array_s, debug_key_value_s, debug_value_s, debug_vect_s, differential_pressure_s,
         distance_sensor_mode_change_request_s, distance_sensor_s, ekf2_timestamps_s, esc_report_s,
-        estimator_aid_source1d_s, estimator_aid_source2d_s, estimator_aid_source3d_s,
+        esc_status_s, estimator_aid_source1d_s, estimator_aid_source2d_s, estimator_aid_source3d_s,
         estimator_bias3d_s, estimator_bias_s, estimator_event_flags_s, estimator_gps_status_s,
         estimator_innovations_s, estimator_selector_status_s, estimator_sensor_bias_s,
         estimator_states_s, estimator_status_flags_s, estimator_status_s, event_s,
@@ -430,7 +508,7 @@ use px4_msgs_sys::{
         vehicle_odometry_s, vehicle_optical_flow_s, vehicle_optical_flow_vel_s,
         vehicle_rates_setpoint_s, vehicle_roi_s, vehicle_status_s, vehicle_thrust_setpoint_s,
         vehicle_torque_setpoint_s, velocity_limits_s, vtol_vehicle_status_s, wheel_encoders_s,
-        wind_s, yaw_estimator_status_s,
+        wind_s, yaw_estimator_status_s, BATTERY_STATUS_WARNING_NONE,
     },
     orb::orb_metadata,
 };
@@ -472,6 +550,7 @@ impl_uorb_message!(
     distance_sensor_s,
     ekf2_timestamps_s,
     esc_report_s,
+    esc_status_s,
     estimator_aid_source1d_s,
     estimator_aid_source2d_s,
     estimator_aid_source3d_s,
@@ -938,6 +1017,9 @@ define_topics! {
     /// Esc Report topic
     EscReport => esc_report_s, __orb_esc_report;
 
+    /// Esc Status topic
+    EscStatus => esc_status_s, __orb_esc_status;
+
     /// Pwm Input topic
     PwmInput => pwm_input_s, __orb_pwm_input;
 
@@ -1515,6 +1597,24 @@ define_topics! {
     GimbalDeviceInformation => gimbal_device_information_s, __orb_gimbal_device_information;
 }
 
+impl_array_fields! {
+    actuator_motors_s { control };
+    actuator_outputs_s { output };
+    actuator_servos_s { control };
+    actuator_servos_trim_s { trim };
+    adc_report_s { raw_data, channel_id };
+    battery_status_s { voltage_cell_v };
+    debug_array_s { data };
+    estimator_states_s { states, covariances };
+    input_rc_s { values };
+    obstacle_distance_s { distances };
+    px4io_status_s { pwm, pwm_disarmed, pwm_failsafe, pwm_rate_hz, raw_inputs };
+    rc_channels_s { channels };
+    sensor_accel_fifo_s { x, y, z };
+    sensor_gyro_fifo_s { x, y, z };
+    yaw_estimator_status_s { yaw, innov_vn, innov_ve, weight };
+}
+
 // --------------------------------------------------------------------------------
 // Manual message impls below here
 // --------------------------------------------------------------------------------
@@ -1570,6 +1670,106 @@ impl FromPassType for manual_control_setpoint_s {
     }
 }
 
+/// Number of ESCs reported by esc_status
+const ESC_STATUS_MAX_ESCS: usize = 8;
+/// Number of cells reported by battery_status
+const BATTERY_STATUS_MAX_CELLS: usize = 14;
+
+/// Manual Implementation for Esc Status; the per-ESC reports are nested structs, so they're
+/// mapped to one matrix per quantity, indexed by ESC.
+///
+/// Outputs (rpm, voltage, current, temperature, healthy, esc_count). An ESC is healthy when
+/// it's one of the `esc_count` ESCs reported, is online and reports no failures; the values
+/// of ESCs that aren't reported are zero.
+impl ToPassType for esc_status_s {
+    type PassType = (
+        Matrix<ESC_STATUS_MAX_ESCS, 1, f64>,
+        Matrix<ESC_STATUS_MAX_ESCS, 1, f64>,
+        Matrix<ESC_STATUS_MAX_ESCS, 1, f64>,
+        Matrix<ESC_STATUS_MAX_ESCS, 1, f64>,
+        Matrix<ESC_STATUS_MAX_ESCS, 1, bool>,
+        f64,
+    );
+    fn to_pass_type(&self) -> (u64, Self::PassType) {
+        let esc_count = usize::from(self.esc_count).min(ESC_STATUS_MAX_ESCS);
+        let reported = |i: usize| i < esc_count;
+        let per_esc = |value: fn(&esc_report_s) -> f64| Matrix {
+            data: [core::array::from_fn(|i| {
+                if reported(i) {
+                    value(&self.esc[i])
+                } else {
+                    0.0
+                }
+            })],
+        };
+        (
+            self.timestamp,
+            (
+                per_esc(|esc| esc.esc_rpm as f64),
+                per_esc(|esc| esc.esc_voltage as f64),
+                per_esc(|esc| esc.esc_current as f64),
+                per_esc(|esc| esc.esc_temperature as f64),
+                Matrix {
+                    data: [core::array::from_fn(|i| {
+                        reported(i)
+                            && self.esc_online_flags & (1 << i) != 0
+                            && self.esc[i].failures == 0
+                    })],
+                },
+                esc_count as f64,
+            ),
+        )
+    }
+}
+
+/// Manual Implementation for Battery Status; Battery Status has far more than 8 fields, so
+/// only those useful for monitoring battery health are mapped.
+///
+/// Outputs (voltage_v, current_a, remaining, temperature, cell_voltages, cell_valid, connected,
+/// warning, faults, healthy). A cell's voltage is valid when it's one of the `cell_count` cells
+/// and has been measured. The battery is healthy when it's connected, has no warning and
+/// reports no faults.
+impl ToPassType for battery_status_s {
+    type PassType = (
+        f64,
+        f64,
+        f64,
+        f64,
+        Matrix<BATTERY_STATUS_MAX_CELLS, 1, f64>,
+        Matrix<BATTERY_STATUS_MAX_CELLS, 1, bool>,
+        bool,
+        f64,
+        f64,
+        bool,
+    );
+    fn to_pass_type(&self) -> (u64, Self::PassType) {
+        let cell_count = usize::from(self.cell_count);
+        let healthy =
+            self.connected && self.warning == BATTERY_STATUS_WARNING_NONE as u8 && self.faults == 0;
+        (
+            self.timestamp,
+            (
+                self.voltage_v as f64,
+                self.current_a as f64,
+                self.remaining as f64,
+                self.temperature as f64,
+                Matrix {
+                    data: [core::array::from_fn(|i| self.voltage_cell_v[i] as f64)],
+                },
+                Matrix {
+                    data: [core::array::from_fn(|i| {
+                        i < cell_count && self.voltage_cell_v[i] > 0.0
+                    })],
+                },
+                self.connected,
+                self.warning as f64,
+                self.faults as f64,
+                healthy,
+            ),
+        )
+    }
+}
+
 // --------------------------------------------------------------------------------
 // Auto-generated message impls below here
 // --------------------------------------------------------------------------------
@@ -3,14 +3,14 @@
 //! The idea is to provide a way for pictorus static libs to to communicate larger amounts of data than
 //! what we currently pass through FFI variables. This Rust code requires C/C++ code on the calling side
 //! which is using the exact same memory layout that the rust code expects.
-use crate::message_impls::{Topic, UorbMessage};
+use crate::message_impls::{ArrayFields, Topic, UorbMessage};
 use once_cell::sync::Lazy;
-use pictorus_traits::{InputBlock, OutputBlock, Pass, PassBy};
+use pictorus_traits::{InputBlock, Matrix, OutputBlock, Pass, PassBy};
 use px4_msgs_sys::orb::orb_id_t;
 use spin::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 extern crate alloc;
-use alloc::{boxed::Box, vec, vec::Vec};
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
 
 use crate::message_impls::{FromPassType, ToPassType};
 
@@ -444,6 +444,207 @@ where
         self.data.as_by()
     }
 }
+/// Parameters for [`UorbArrayInputBlock`] and [`UorbArrayOutputBlock`]
+///
+/// Selects the array field of the topic's message the block maps, and which of its elements
+/// make up the block's `N` signals. Invalid selections are configuration errors, so they're
+/// caught when the parameters are created.
+pub struct UorbArrayBlockParameters<T: Topic, const N: usize>
+where
+    T::Message: ArrayFields,
+{
+    /// Name of the array field
+    field: String,
+    /// Index in the array field of each signal
+    elements: [usize; N],
+    /// Value of the elements an output block doesn't set
+    fill: f64,
+    _marker: core::marker::PhantomData<T>,
+}
+
+impl<T: Topic, const N: usize> UorbArrayBlockParameters<T, N>
+where
+    T::Message: ArrayFields,
+{
+    /// Create parameters selecting `elements` of the array field `field`
+    ///
+    /// # Arguments
+    /// * `field` - Name of the array field, e.g. "control" for `actuator_motors`
+    /// * `elements` - Index of each of the `N` signals in the field, or empty for the first `N`
+    /// * `fill` - Value output blocks write to the elements that aren't selected
+    ///
+    /// # Panics
+    /// If the message has no such array field, or the selected elements don't exist
+    pub fn new(field: &str, elements: &[f64], fill: f64) -> Self {
+        let Some(len) = T::Message::array_len(field) else {
+            panic!(
+                "Topic {} has no array field {field}, only {:?}",
+                T::name(),
+                T::Message::ARRAY_FIELDS
+            );
+        };
+        let elements: [usize; N] = if elements.is_empty() {
+            core::array::from_fn(|i| i)
+        } else {
+            assert_eq!(
+                elements.len(),
+                N,
+                "Expected {N} elements of {}.{field} to be selected",
+                T::name()
+            );
+            core::array::from_fn(|i| elements[i] as usize)
+        };
+        assert!(
+            elements.iter().all(|&index| index < len),
+            "Selected elements {elements:?} of {}.{field} are out of range, it has {len}",
+            T::name()
+        );
+        Self {
+            field: String::from(field),
+            elements,
+            fill,
+            _marker: core::marker::PhantomData,
+        }
+    }
+}
+
+/// Pictorus input block for reading an array field of a PX4 uORB topic
+///
+/// Like [`UorbInputBlock`], but outputs the selected elements of one array field of the
+/// topic's message as a `Matrix`, rather than the fields mapped by [`ToPassType`]. This gives
+/// models access to arrays that aren't mapped, or only partially, like the outputs of
+/// `actuator_outputs` or the channels of `input_rc`.
+///
+/// # Usage
+///
+/// ```rust
+/// use pictorus_px4::uorb_binding::{UorbArrayBlockParameters, UorbArrayInputBlock};
+/// use pictorus_px4::message_impls::InputRc;
+///
+/// // Read the first 8 RC channels
+/// let parameters = UorbArrayBlockParameters::<InputRc, 8>::new("values", &[], 0.0);
+/// let mut input_block = UorbArrayInputBlock::<InputRc, 8>::default();
+/// ```
+pub struct UorbArrayInputBlock<T: Topic, const N: usize>
+where
+    T::Message: ArrayFields,
+{
+    /// Selected elements of the last message received
+    data: Matrix<N, 1, f64>,
+    _marker: core::marker::PhantomData<T>,
+}
+
+impl<T: Topic, const N: usize> Default for UorbArrayInputBlock<T, N>
+where
+    T::Message: ArrayFields,
+{
+    fn default() -> Self {
+        Self {
+            data: Matrix::zeroed(),
+            _marker: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<T: Topic, const N: usize> InputBlock for UorbArrayInputBlock<T, N>
+where
+    T::Message: ArrayFields,
+{
+    type Output = Matrix<N, 1, f64>;
+    type Parameters = UorbArrayBlockParameters<T, N>;
+
+    fn input(
+        &mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+    ) -> PassBy<'_, Self::Output> {
+        let protocol = UorbBinding::get();
+
+        let (data_opt, result) = protocol.get_message::<T>();
+        debug_assert!(
+            result.is_success(),
+            "Failed to get message for topic: {:?}",
+            result
+        );
+        if let Some(data) = data_opt {
+            for (value, &index) in self.data.data[0].iter_mut().zip(&parameters.elements) {
+                *value = data
+                    .array_element(&parameters.field, index)
+                    .unwrap_or_default();
+            }
+        }
+        self.data.as_by()
+    }
+}
+
+/// Pictorus output block for writing an array field of a PX4 uORB topic
+///
+/// Like [`UorbOutputBlock`], but publishes a message built from the selected elements of one
+/// array field, rather than from the fields mapped by [`FromPassType`]. The elements that
+/// aren't selected are set to the fill value (e.g. NaN for the unused motors of
+/// `actuator_motors`), and the message's other fields are zero apart from its timestamp.
+///
+/// # Usage
+///
+/// ```rust
+/// use pictorus_px4::uorb_binding::{UorbArrayBlockParameters, UorbArrayOutputBlock};
+/// use pictorus_px4::message_impls::ActuatorMotors;
+///
+/// // Drive motors 1 to 4, leaving the rest disarmed
+/// let parameters = UorbArrayBlockParameters::<ActuatorMotors, 4>::new("control", &[], f64::NAN);
+/// let mut output_block = UorbArrayOutputBlock::<ActuatorMotors, 4>::default();
+/// ```
+pub struct UorbArrayOutputBlock<T: Topic, const N: usize>
+where
+    T::Message: ArrayFields,
+{
+    /// Zero-sized marker for compile-time topic identification
+    _marker: core::marker::PhantomData<T>,
+}
+
+impl<T: Topic, const N: usize> Default for UorbArrayOutputBlock<T, N>
+where
+    T::Message: ArrayFields,
+{
+    fn default() -> Self {
+        Self {
+            _marker: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<T: Topic, const N: usize> OutputBlock for UorbArrayOutputBlock<T, N>
+where
+    T::Message: ArrayFields,
+{
+    type Inputs = Matrix<N, 1, f64>;
+    type Parameters = UorbArrayBlockParameters<T, N>;
+
+    fn output(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) {
+        let field = parameters.field.as_str();
+        let mut message = T::Message::zeroed(context.time().as_micros() as u64);
+        for index in 0..T::Message::array_len(field).unwrap_or_default() {
+            message.set_array_element(field, index, parameters.fill);
+        }
+        for (&value, &index) in inputs.data[0].iter().zip(&parameters.elements) {
+            message.set_array_element(field, index, value);
+        }
+
+        let mut protocol = UorbBinding::get_mut();
+        let result = protocol.set_message::<T>(message);
+        debug_assert!(
+            result.is_success(),
+            "Failed to set message for topic: {:?}",
+            result
+        );
+    }
+}
+
 // C-compatible FFI functions
 
 /// Get the count of input messages registered with the FFI protocol
@@ -0,0 +1,247 @@
+//! Block for managing the vehicle's arming state and flight mode from a Pictorus model.
+//!
+//! The [`VehicleCommandBlock`] turns changes in the model's requested arming state and mode
+//! into `vehicle_command` messages, and tracks the `vehicle_command_ack` PX4 answers them with.
+use alloc::collections::VecDeque;
+use core::time::Duration;
+
+use pictorus_traits::{InputBlock, OutputBlock, Pass, PassBy};
+use px4_msgs_sys::message_defs::{
+    vehicle_command_s, VEHICLE_COMMAND_ACK_VEHICLE_CMD_RESULT_IN_PROGRESS,
+    VEHICLE_COMMAND_ARMING_ACTION_ARM, VEHICLE_COMMAND_ARMING_ACTION_DISARM,
+    VEHICLE_COMMAND_VEHICLE_CMD_COMPONENT_ARM_DISARM, VEHICLE_COMMAND_VEHICLE_CMD_DO_SET_MODE,
+};
+
+use crate::message_impls::{VehicleCommand, VehicleCommandAck};
+use crate::uorb_binding::UorbBinding;
+
+/// `MAV_MODE_FLAG_CUSTOM_MODE_ENABLED`, set in the base mode so PX4 reads the custom mode
+const CUSTOM_MODE_ENABLED: f32 = 1.0;
+/// Result reported before any command has been answered
+const NO_RESULT: f64 = -1.0;
+
+/// Parameters for the [`VehicleCommandBlock`]
+pub struct VehicleCommandBlockParameters {
+    /// System ID of the vehicle, as in `MAV_SYS_ID`
+    pub target_system: u8,
+    /// Component the commands are for, usually the autopilot (1)
+    pub target_component: u8,
+    /// How long to wait for a command to be acknowledged before resending it
+    pub ack_timeout: Duration,
+    /// Number of times a command is resent before giving up on it
+    pub max_retries: u8,
+}
+
+impl VehicleCommandBlockParameters {
+    pub fn new(
+        target_system: f64,
+        target_component: f64,
+        ack_timeout_ms: f64,
+        max_retries: f64,
+    ) -> Self {
+        Self {
+            target_system: target_system as u8,
+            target_component: target_component as u8,
+            ack_timeout: Duration::from_secs_f64(ack_timeout_ms.max(0.0) / 1000.0),
+            max_retries: max_retries as u8,
+        }
+    }
+}
+
+/// A command waiting to be acknowledged
+struct PendingCommand {
+    message: vehicle_command_s,
+    sent_at: Duration,
+    retries: u8,
+}
+
+/// Pictorus block for arming, disarming and switching the flight mode of the vehicle
+///
+/// As an output block it takes the requested arming state and PX4 custom main and sub mode
+/// (e.g. 6 and 0 for offboard), and publishes a `vehicle_command` whenever one of them
+/// changes: `VEHICLE_CMD_COMPONENT_ARM_DISARM` for the arming state, and
+/// `VEHICLE_CMD_DO_SET_MODE` for the mode. A main mode of 0 never commands a mode change.
+/// Requests start out disarmed with no mode, so a model that requests neither sends nothing.
+///
+/// Commands are sent one at a time, each waiting for its `vehicle_command_ack`. A command that
+/// isn't acknowledged within the ack timeout is resent, with its confirmation count raised, up
+/// to `max_retries` times; acks reporting the command in progress restart the timeout. When
+/// both requests change before the previous command is answered, the latest of each is sent in
+/// turn.
+///
+/// As an input block it outputs the acknowledgment status as a tuple of (command, result,
+/// pending, timed_out): the ID of the last command sent, the `VEHICLE_CMD_RESULT` PX4 answered
+/// it with (0 when accepted, -1 until answered), whether it's waiting for an answer and whether
+/// it went unanswered after all retries.
+///
+/// The PX4 module must advertise `vehicle_command` and subscribe to `vehicle_command_ack`.
+pub struct VehicleCommandBlock {
+    /// Last requested arming state
+    armed: bool,
+    /// Last requested custom main and sub mode
+    mode: (f64, f64),
+    queue: VecDeque<vehicle_command_s>,
+    pending: Option<PendingCommand>,
+    /// Timestamp of the last ack handled, since the same ack is read until the next arrives
+    last_ack: u64,
+    /// (command, result, pending, timed_out)
+    status: (f64, f64, bool, bool),
+}
+
+impl Default for VehicleCommandBlock {
+    fn default() -> Self {
+        Self {
+            armed: false,
+            mode: (0.0, 0.0),
+            queue: VecDeque::new(),
+            pending: None,
+            last_ack: 0,
+            status: (0.0, NO_RESULT, false, false),
+        }
+    }
+}
+
+impl VehicleCommandBlock {
+    fn command(
+        parameters: &VehicleCommandBlockParameters,
+        command: u32,
+        params: [f32; 3],
+    ) -> vehicle_command_s {
+        vehicle_command_s {
+            timestamp: 0,
+            param5: 0.0,
+            param6: 0.0,
+            param1: params[0],
+            param2: params[1],
+            param3: params[2],
+            param4: 0.0,
+            param7: 0.0,
+            command,
+            source_component: u16::from(parameters.target_component),
+            target_system: parameters.target_system,
+            target_component: parameters.target_component,
+            source_system: parameters.target_system,
+            confirmation: 0,
+            from_external: false,
+            _padding0: [0; 1],
+        }
+    }
+
+    /// Queues a command, replacing a queued command of the same kind
+    fn enqueue(&mut self, message: vehicle_command_s) {
+        self.queue
+            .retain(|queued| queued.command != message.command);
+        self.queue.push_back(message);
+    }
+
+    fn send(&mut self, mut message: vehicle_command_s, now: Duration, retries: u8) {
+        message.timestamp = now.as_micros() as u64;
+        message.confirmation = retries;
+        let result = UorbBinding::get_mut().set_message::<VehicleCommand>(message);
+        debug_assert!(
+            result.is_success(),
+            "Failed to set message for topic: {:?}",
+            result
+        );
+        self.pending = Some(PendingCommand {
+            message,
+            sent_at: now,
+            retries,
+        });
+        self.status = (message.command as f64, NO_RESULT, true, false);
+    }
+
+    /// Handles a new ack and the ack timeout of the pending command
+    fn update(&mut self, parameters: &VehicleCommandBlockParameters, now: Duration) {
+        let ack = {
+            let protocol = UorbBinding::get();
+            let (ack, _) = protocol.get_message::<VehicleCommandAck>();
+            ack.copied()
+        };
+        if let (Some(ack), Some(pending)) = (ack, &mut self.pending) {
+            if ack.timestamp != self.last_ack && ack.command == pending.message.command {
+                if u32::from(ack.result) == VEHICLE_COMMAND_ACK_VEHICLE_CMD_RESULT_IN_PROGRESS {
+                    pending.sent_at = now;
+                } else {
+                    self.status = (ack.command as f64, ack.result as f64, false, false);
+                    self.pending = None;
+                }
+            }
+        }
+        if let Some(ack) = ack {
+            self.last_ack = ack.timestamp;
+        }
+
+        if let Some(pending) = &self.pending {
+            if now.saturating_sub(pending.sent_at) >= parameters.ack_timeout {
+                let (message, retries) = (pending.message, pending.retries);
+                if retries < parameters.max_retries {
+                    self.send(message, now, retries + 1);
+                } else {
+                    self.status = (message.command as f64, NO_RESULT, false, true);
+                    self.pending = None;
+                }
+            }
+        }
+    }
+}
+
+impl OutputBlock for VehicleCommandBlock {
+    type Inputs = (bool, f64, f64);
+    type Parameters = VehicleCommandBlockParameters;
+
+    fn output(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn pictorus_traits::Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) {
+        let (armed, main_mode, sub_mode) = inputs;
+        let now = context.time();
+
+        if armed != self.armed {
+            self.armed = armed;
+            let action = if armed {
+                VEHICLE_COMMAND_ARMING_ACTION_ARM
+            } else {
+                VEHICLE_COMMAND_ARMING_ACTION_DISARM
+            };
+            self.enqueue(Self::command(
+                parameters,
+                VEHICLE_COMMAND_VEHICLE_CMD_COMPONENT_ARM_DISARM,
+                [action as f32, 0.0, 0.0],
+            ));
+        }
+        if (main_mode, sub_mode) != self.mode {
+            self.mode = (main_mode, sub_mode);
+            if main_mode > 0.0 {
+                self.enqueue(Self::command(
+                    parameters,
+                    VEHICLE_COMMAND_VEHICLE_CMD_DO_SET_MODE,
+                    [CUSTOM_MODE_ENABLED, main_mode as f32, sub_mode as f32],
+                ));
+            }
+        }
+
+        self.update(parameters, now);
+        if self.pending.is_none() {
+            if let Some(message) = self.queue.pop_front() {
+                self.send(message, now, 0);
+            }
+        }
+    }
+}
+
+impl InputBlock for VehicleCommandBlock {
+    type Output = (f64, f64, bool, bool);
+    type Parameters = VehicleCommandBlockParameters;
+
+    fn input(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn pictorus_traits::Context,
+    ) -> PassBy<'_, Self::Output> {
+        self.update(parameters, context.time());
+        self.status.as_by()
+    }
+}
@@ -0,0 +1,9 @@
+//! Real serial and UDP connections for hardware-in-the-loop (HIL) runs.
+//!
+//! The simulator's own connections don't send or receive anything. With the `hil` feature,
+//! these stand in for them, so a `HilHostBlock` in the simulated plant can stream sensor values
+//! to a target (e.g. an STM32 board) running the generated model with a `HilTargetBlock`, and
+//! read its actuator values back.
+pub use pictorus_std::{
+    SerialConnection as HilSerialConnection, UdpConnection as HilUdpConnection,
+};
@@ -33,3 +33,6 @@ pub use can_protocol::*;
 
 mod spi_protocol;
 pub use spi_protocol::*;
+
+#[cfg(feature = "hil")]
+pub mod hil;
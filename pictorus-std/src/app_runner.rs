@@ -0,0 +1,28 @@
+use std::time::{Duration, Instant};
+
+use pictorus_internal::execution_controller::{
+    MultiModelController, install_shutdown_handlers, run_until_shutdown,
+};
+
+/// Runs the models of `controller` in real time until the app shuts down, which happens on
+/// SIGINT (Ctrl-C), when a shutdown is requested, or when a thread panics. The controller's
+/// shutdown sequence puts the outputs in their safe state in every case, and a panic in a model
+/// is then resumed.
+///
+/// Ticks are scheduled from the start of the app, so a late tick doesn't delay the ones after
+/// it.
+pub fn run_app<IO: ?Sized, const N: usize, const S: usize>(
+    controller: &mut MultiModelController<'_, IO, N, S>,
+    io: &mut IO,
+) {
+    if let Err(err) = install_shutdown_handlers() {
+        log::warn!("{err}, Ctrl-C won't put the outputs in their safe state");
+    }
+    let start = Instant::now();
+    run_until_shutdown(controller, io, |app_time_us| {
+        let tick = start + Duration::from_micros(app_time_us);
+        if let Some(wait) = tick.checked_duration_since(Instant::now()) {
+            std::thread::sleep(wait);
+        }
+    });
+}
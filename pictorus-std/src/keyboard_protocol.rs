@@ -0,0 +1,274 @@
+use std::io::{IsTerminal, Read};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{Receiver, channel};
+use std::time::Duration;
+
+use pictorus_internal::utils::{ErrorKind, PictorusError};
+use pictorus_traits::{Context, InputBlock, Matrix, Pass, PassBy};
+
+const ERR_TYPE: &str = "KeyboardProtocol";
+
+/// Parameters for the keyboard block. Its key bindings are fixed when it's created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyboardParams {}
+
+impl KeyboardParams {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// A key as read from a terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// A character key, with letters in lower case
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    Escape,
+}
+
+impl Key {
+    /// Parses a key name: a single character, or one of space, enter, tab, esc, up, down, left
+    /// or right
+    pub fn from_name(name: &str) -> Result<Self, PictorusError> {
+        let key = match name.to_ascii_lowercase().as_str() {
+            "space" => Key::Char(' '),
+            "enter" => Key::Char('\n'),
+            "tab" => Key::Char('\t'),
+            "esc" => Key::Escape,
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "left" => Key::Left,
+            "right" => Key::Right,
+            _ => {
+                let mut chars = name.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Key::Char(c.to_ascii_lowercase()),
+                    _ => {
+                        return Err(PictorusError::new(
+                            ErrorKind::InvalidConfig,
+                            ERR_TYPE,
+                            format!("Unknown key name: {name}"),
+                        ));
+                    }
+                }
+            }
+        };
+        Ok(key)
+    }
+
+    /// Splits the bytes read from a terminal into keys, decoding the escape sequences of arrow
+    /// keys
+    fn parse_input(input: &str) -> Vec<Key> {
+        let mut keys = Vec::new();
+        let mut chars = input.chars();
+        while let Some(c) = chars.next() {
+            let key = match c {
+                '\x1b' if chars.as_str().starts_with('[') => {
+                    chars.next();
+                    match chars.next() {
+                        Some('A') => Key::Up,
+                        Some('B') => Key::Down,
+                        Some('C') => Key::Right,
+                        Some('D') => Key::Left,
+                        _ => continue,
+                    }
+                }
+                '\x1b' => Key::Escape,
+                '\r' => Key::Char('\n'),
+                c => Key::Char(c.to_ascii_lowercase()),
+            };
+            keys.push(key);
+        }
+        keys
+    }
+}
+
+/// Splits a comma separated list, ignoring empty entries
+fn split_list(list: &str) -> impl Iterator<Item = &str> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+}
+
+/// An axis adjusted by a pair of keys
+struct KeyAxis {
+    increase: Key,
+    decrease: Key,
+    step: f64,
+}
+
+impl KeyAxis {
+    /// Parses `increase/decrease:step`, e.g. `w/s:0.1`
+    fn parse(binding: &str) -> Result<Self, PictorusError> {
+        let invalid = || {
+            PictorusError::new(
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
+                format!("Key axes must be written as increase/decrease:step, not {binding}"),
+            )
+        };
+        let (keys, step) = binding.rsplit_once(':').ok_or_else(invalid)?;
+        let (increase, decrease) = keys.split_once('/').ok_or_else(invalid)?;
+        Ok(Self {
+            increase: Key::from_name(increase.trim())?,
+            decrease: Key::from_name(decrease.trim())?,
+            step: step.trim().parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Reads key presses from the terminal without blocking, for driving models by hand on a
+/// workstation.
+///
+/// Keys are bound when the block is created, as a comma separated list of button keys (e.g.
+/// `"space, q, enter"`) and a comma separated list of axes, each adjusted by a pair of keys and
+/// a step (e.g. `"w/s:0.1, up/down:1"`). Keys are a single character (letters match either
+/// case) or one of space, enter, tab, esc, up, down, left and right.
+///
+/// The output is a tuple of (buttons, axes). Terminals only report key presses, not releases,
+/// so a button is true for the hold time after its key was last pressed; holding a key down
+/// keeps it true once the terminal starts auto-repeating it, which takes longer than the repeat
+/// interval. Each axis starts at zero, and every press of its keys adds or subtracts the step.
+///
+/// Keys are read on a background thread, with the terminal switched to unbuffered input
+/// without echo through `stty` while the block exists. Where that isn't possible (e.g. stdin
+/// isn't a terminal), keys are read once enter is pressed.
+pub struct KeyboardInput<const BUTTONS: usize, const AXES: usize> {
+    keys: Receiver<String>,
+    buttons: Vec<Key>,
+    axes: Vec<KeyAxis>,
+    hold_time: Duration,
+    /// Time each button was last pressed
+    last_pressed: [Option<Duration>; BUTTONS],
+    raw_mode: bool,
+    buffer: (Matrix<1, BUTTONS, bool>, Matrix<1, AXES, f64>),
+}
+
+/// Switches the terminal on stdin between unbuffered input without echo, and line input
+fn set_raw_mode(raw: bool) -> bool {
+    let args: &[&str] = if raw {
+        &["-icanon", "-echo", "min", "1"]
+    } else {
+        &["icanon", "echo"]
+    };
+    Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+impl<const BUTTONS: usize, const AXES: usize> KeyboardInput<BUTTONS, AXES> {
+    pub fn new(buttons: &str, axes: &str, hold_time_ms: f64) -> Result<Self, PictorusError> {
+        let buttons = split_list(buttons)
+            .map(Key::from_name)
+            .collect::<Result<Vec<_>, _>>()?;
+        let axes = split_list(axes)
+            .map(KeyAxis::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        if buttons.len() != BUTTONS || axes.len() != AXES {
+            return Err(PictorusError::new(
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
+                format!(
+                    "Expected {BUTTONS} buttons and {AXES} axes, but {} and {} are bound",
+                    buttons.len(),
+                    axes.len()
+                ),
+            ));
+        }
+
+        let raw_mode = std::io::stdin().is_terminal() && set_raw_mode(true);
+        if !raw_mode {
+            log::warn!("Couldn't switch the terminal to unbuffered input, keys are read on enter");
+        }
+        let (sender, keys) = channel();
+        std::thread::Builder::new()
+            .name("keyboard".into())
+            .spawn(move || {
+                let mut stdin = std::io::stdin();
+                let mut bytes = [0; 64];
+                // Stops once stdin closes or the block is dropped
+                while let Ok(read @ 1..) = stdin.read(&mut bytes) {
+                    let text = String::from_utf8_lossy(&bytes[..read]).into_owned();
+                    if sender.send(text).is_err() {
+                        break;
+                    }
+                }
+            })
+            .map_err(|err| {
+                PictorusError::from_io(ERR_TYPE, "Failed to start keyboard thread", &err)
+            })?;
+
+        Ok(Self {
+            keys,
+            buttons,
+            axes,
+            hold_time: Duration::from_secs_f64(hold_time_ms.max(0.0) / 1000.0),
+            last_pressed: [None; BUTTONS],
+            raw_mode,
+            buffer: (Matrix::zeroed(), Matrix::zeroed()),
+        })
+    }
+
+    fn press(&mut self, key: Key, time: Duration) {
+        for (button, last_pressed) in self.buttons.iter().zip(&mut self.last_pressed) {
+            if *button == key {
+                *last_pressed = Some(time);
+            }
+        }
+        for (axis, value) in self.axes.iter().zip(&mut self.buffer.1.data) {
+            if axis.increase == key {
+                value[0] += axis.step;
+            } else if axis.decrease == key {
+                value[0] -= axis.step;
+            }
+        }
+    }
+}
+
+impl<const BUTTONS: usize, const AXES: usize> Drop for KeyboardInput<BUTTONS, AXES> {
+    fn drop(&mut self) {
+        if self.raw_mode {
+            set_raw_mode(false);
+        }
+    }
+}
+
+impl<const BUTTONS: usize, const AXES: usize> InputBlock for KeyboardInput<BUTTONS, AXES> {
+    type Output = (Matrix<1, BUTTONS, bool>, Matrix<1, AXES, f64>);
+    type Parameters = KeyboardParams;
+
+    fn input(
+        &mut self,
+        _parameters: &Self::Parameters,
+        context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        let time = context.time();
+        while let Ok(text) = self.keys.try_recv() {
+            for key in Key::parse_input(&text) {
+                self.press(key, time);
+            }
+        }
+
+        for (pressed, last_pressed) in self.buffer.0.data.iter_mut().zip(self.last_pressed) {
+            pressed[0] =
+                last_pressed.is_some_and(|last| time.saturating_sub(last) < self.hold_time);
+        }
+        self.buffer.as_by()
+    }
+}
+
+/// Reads the `buttons` and `axes` key bindings from the terminal, holding buttons for
+/// `hold_time_ms` after each press
+pub fn create_keyboard_input<const BUTTONS: usize, const AXES: usize>(
+    buttons: &str,
+    axes: &str,
+    hold_time_ms: f64,
+) -> Result<KeyboardInput<BUTTONS, AXES>, PictorusError> {
+    KeyboardInput::new(buttons, axes, hold_time_ms)
+}
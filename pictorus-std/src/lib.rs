@@ -1,12 +1,18 @@
 //! This crate contains std library based implementations of Pictorus I/O drivers.
 //! These are typically defined as `InputBlock` or `OutputBlock` interfaces as defined
 //! in the `pictorus-traits` crate.
+pub mod app_runner;
+pub use app_runner::*;
+
 pub mod clock_protocol;
 pub use clock_protocol::*;
 
 pub mod delay_protocol;
 pub use delay_protocol::*;
 
+pub mod keyboard_protocol;
+pub use keyboard_protocol::*;
+
 pub mod serial_protocol;
 pub use serial_protocol::*;
 
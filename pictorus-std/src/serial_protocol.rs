@@ -7,7 +7,7 @@ use pictorus_traits::{ByteSliceSignal, InputBlock};
 use serialport::{self, SerialPort};
 
 use pictorus_internal::protocols::BUFF_SIZE_BYTES;
-use pictorus_internal::utils::PictorusError;
+use pictorus_internal::utils::{ErrorKind, PictorusError};
 
 const ERR_TYPE: &str = "SerialProtocol";
 
@@ -22,16 +22,25 @@ pub fn create_serial_port(
 
     let baud_rate = baud_rate as u32;
     let port = serialport::new(port, baud_rate).open().map_err(|err| {
-                let message = match err.kind() {
-                    serialport::ErrorKind::NoDevice => {
-                        format!("Failed to bind to serial port: {port} - This could indicate that the device is in use by another process or was disconnected while performing I/O.")
-                    }
-                    serialport::ErrorKind::Io(_) => {
-                        format!("Failed to bind to serial port: {port} - Does it exist?")
-                    }
-                    _ => format!("Unknown error! Unable to connect to serial port: {port} ({err})"),
+                let (kind, message) = match err.kind() {
+                    serialport::ErrorKind::NoDevice => (
+                        ErrorKind::Busy,
+                        format!("Failed to bind to serial port: {port} - This could indicate that the device is in use by another process or was disconnected while performing I/O."),
+                    ),
+                    serialport::ErrorKind::Io(kind) => (
+                        kind.into(),
+                        format!("Failed to bind to serial port: {port} - Does it exist?"),
+                    ),
+                    serialport::ErrorKind::InvalidInput => (
+                        ErrorKind::InvalidConfig,
+                        format!("Invalid settings for serial port: {port} ({err})"),
+                    ),
+                    _ => (
+                        ErrorKind::Other,
+                        format!("Unknown error! Unable to connect to serial port: {port} ({err})"),
+                    ),
                 };
-                PictorusError::new(ERR_TYPE.into(), message)
+                PictorusError::new(kind, ERR_TYPE, message)
             })?;
     Ok(Some(port))
 }
@@ -47,7 +56,8 @@ impl SerialConnection {
     pub fn new(port: &[u8], baud: f64, transmit_enabled: bool) -> Result<Self, PictorusError> {
         let port_str = str::from_utf8(port).map_err(|err| {
             PictorusError::new(
-                ERR_TYPE.into(),
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
                 format!(
                     "Couldn't bind to serial port because port bytes are not valid UTF-8 ({err})"
                 ),
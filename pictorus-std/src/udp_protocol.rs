@@ -3,7 +3,7 @@ use std::net::UdpSocket;
 
 use log::debug;
 use pictorus_blocks::{UdpReceiveBlockParams, UdpTransmitBlockParams};
-use pictorus_traits::{ByteSliceSignal, InputBlock, OutputBlock};
+use pictorus_traits::{BufferProvider, ByteSliceSignal, InputBlock, OutputBlock};
 
 use pictorus_internal::protocols::{BUFF_SIZE_BYTES, UdpProtocol};
 use pictorus_internal::utils::PictorusError;
@@ -19,7 +19,8 @@ fn create_udp_socket(
     }
     let address_str = std::str::from_utf8(address).map_err(|err| {
         PictorusError::new(
-            ERR_TYPE.into(),
+            pictorus_internal::utils::ErrorKind::InvalidConfig,
+            ERR_TYPE,
             format!("Couldn't bind UDP receiver because address bytes are not valid UTF-8 ({err})"),
         )
     })?;
@@ -30,16 +31,14 @@ fn create_udp_socket(
             ErrorKind::AddrInUse => format!("Couldn't bind UDP receiver at already bound address: {address_str} - Is another process currently bound here?"),
             _ => format!("Unknown error! Couldn't bind UDP receiver at address: {address_str} ({err})"),
         };
-        PictorusError::new(
-            ERR_TYPE.into(),
-            message,
-        )
+        PictorusError::from_io(ERR_TYPE, message, &err)
     })?;
 
-    socket.set_nonblocking(true).map_err(|_| {
-        PictorusError::new(
-            ERR_TYPE.into(),
+    socket.set_nonblocking(true).map_err(|err| {
+        PictorusError::from_io(
+            ERR_TYPE,
             format!("Failed to set nonblocking on UDP port at address: {address_str}",),
+            &err,
         )
     })?;
     Ok(Some(socket))
@@ -47,30 +46,33 @@ fn create_udp_socket(
 
 pub struct UdpConnection {
     socket: Option<UdpSocket>,
-    cache: Option<Vec<u8>>,
+    buffer: Vec<u8>,
+    is_cache_valid: bool,
 }
 
 impl UdpConnection {
     pub fn new(address: &[u8], transmit_enabled: bool) -> Result<Self, PictorusError> {
         Ok(UdpConnection {
-            cache: None,
+            buffer: Vec::new(),
+            is_cache_valid: false,
             socket: create_udp_socket(address, transmit_enabled)?,
         })
     }
 
-    fn read_into_vec(&mut self) -> Result<Vec<u8>, Error> {
+    fn read_into_buffer(&mut self) -> Result<(), Error> {
         if let Some(socket) = &mut self.socket {
-            // Set the cache regardless of the result so we don't read again until flush is called
-            let mut num_bytes_read = 0;
-            let mut output = vec![0; BUFF_SIZE_BYTES];
-            // Only use the most recent value on the socket
-            while let Ok((n, _)) = socket.recv_from(&mut output) {
-                num_bytes_read = n;
-            }
+            // Receive straight into the reused buffer instead of allocating a new one each read
+            let num_bytes_read = self.buffer.write_with(BUFF_SIZE_BYTES, |output| {
+                let mut num_bytes_read = 0;
+                // Only use the most recent value on the socket
+                while let Ok((n, _)) = socket.recv_from(output) {
+                    num_bytes_read = n;
+                }
+                num_bytes_read
+            });
 
             debug!("Received {num_bytes_read} bytes");
-            output.resize(num_bytes_read, 0);
-            Ok(output)
+            Ok(())
         } else {
             Err(Error::new(ErrorKind::NotConnected, "I/O disabled"))
         }
@@ -79,18 +81,16 @@ impl UdpConnection {
 
 impl UdpProtocol for UdpConnection {
     fn read(&mut self) -> Result<&[u8], Error> {
-        let cache = match self.cache {
-            Some(ref mut cache) => cache,
-            None => {
-                let read_bytes = self.read_into_vec()?;
-                self.cache.insert(read_bytes)
-            }
-        };
+        if !self.is_cache_valid {
+            self.read_into_buffer()?;
+            // Keep the results, good or bad, so we don't read again until flush is called
+            self.is_cache_valid = true;
+        }
 
-        if cache.is_empty() {
+        if self.buffer.is_empty() {
             Err(Error::new(ErrorKind::WouldBlock, "No data received"))
         } else {
-            Ok(cache.as_slice())
+            Ok(self.buffer.as_slice())
         }
     }
 
@@ -103,7 +103,7 @@ impl UdpProtocol for UdpConnection {
     }
 
     fn flush(&mut self) {
-        self.cache = None;
+        self.is_cache_valid = false;
     }
 }
 
@@ -1,6 +1,8 @@
 use alloc::vec::Vec;
 
 use embassy_futures::poll_once;
+use embassy_stm32::pac;
+use pictorus_internal::can_health::{CanBusHealth, CanTraffic};
 use pictorus_internal::protocols::CanProtocol;
 // The `fdcan` feature is mutually exclusive with the `can` feature
 // due to the way embassy-stm32 implements can drivers. Boards that support
@@ -15,13 +17,24 @@ use pictorus_blocks::CanReceiveBlockParams;
 use pictorus_blocks::CanTransmitBlockParams;
 use pictorus_traits::{ByteSliceSignal, Context, InputBlock, OutputBlock, PassBy};
 
+pub use pictorus_internal::can_demux::{CanDemux, CanDemuxParams};
+pub use pictorus_internal::can_health::CanHealthParams;
+pub use pictorus_internal::j1939::{J1939, J1939ReceiveParams, J1939TransmitParams};
+
 pub struct CanConnection<'a> {
     can: Can<'a>,
     frames: Vec<Frame>,
     stale: bool,
+    traffic: Option<&'a CanTraffic>,
 }
 
 impl<'a> CanConnection<'a> {
+    /// Enables the peripheral at `bitrate`, accepting every frame.
+    ///
+    /// # Panics
+    ///
+    /// embassy-stm32 panics if the peripheral clock can't produce `bitrate`, and offers no
+    /// fallible way to set it.
     #[cfg(not(feature = "fdcan"))]
     pub fn new(mut can: Can<'a>, bitrate: u32) -> Self {
         can.modify_filters()
@@ -34,9 +47,16 @@ impl<'a> CanConnection<'a> {
             can,
             frames: Vec::new(),
             stale: true,
+            traffic: None,
         }
     }
 
+    /// Enables the peripheral at `bitrate`, accepting every frame.
+    ///
+    /// # Panics
+    ///
+    /// embassy-stm32 panics if the peripheral clock can't produce `bitrate`, and offers no
+    /// fallible way to set it.
     #[cfg(feature = "fdcan")]
     pub fn new(mut can: CanConfigurator<'a>, bitrate: u32) -> Self {
         use embassy_stm32::can::OperatingMode;
@@ -52,6 +72,20 @@ impl<'a> CanConnection<'a> {
             can,
             frames: Vec::new(),
             stale: true,
+            traffic: None,
+        }
+    }
+
+    /// Counts the frames sent and received in `traffic`, for a [`CanHealthMonitor`]'s busload
+    /// estimate
+    pub fn with_traffic(mut self, traffic: &'a CanTraffic) -> Self {
+        self.traffic = Some(traffic);
+        self
+    }
+
+    fn record_traffic(&self, frame: &Frame) {
+        if let Some(traffic) = self.traffic {
+            traffic.record_frame(frame.is_extended(), frame.dlc());
         }
     }
 }
@@ -105,6 +139,7 @@ impl CanProtocol for CanConnection<'_> {
         }
 
         while let Ok(frame) = self.receive() {
+            self.record_traffic(&frame);
             self.frames.push(frame);
         }
 
@@ -134,9 +169,9 @@ impl OutputBlock for CanConnection<'_> {
             return;
         };
 
-        let res = self.transmit(&frame);
-        if let Err(e) = res {
-            log::warn!("Failed to transmit frame: {e:?}");
+        match self.transmit(&frame) {
+            Ok(_) => self.record_traffic(&frame),
+            Err(e) => log::warn!("Failed to transmit frame: {e:?}"),
         }
     }
 }
@@ -163,3 +198,71 @@ impl InputBlock for CanConnection<'_> {
         frame.data()
     }
 }
+
+/// Registers of the CAN peripheral a [`CanHealthMonitor`] reads
+#[cfg(not(feature = "fdcan"))]
+pub type CanRegisters = pac::can::Can;
+#[cfg(feature = "fdcan")]
+pub type CanRegisters = pac::can::Fdcan;
+
+/// Reports the health of a CAN peripheral: `(tx error count, rx error count, bus-off events,
+/// rx overflows, busload %, bus-off)`.
+///
+/// Error counters and bus-off state are read from the peripheral's registers each tick. Receive
+/// FIFO overruns are only detected on bxCAN peripherals; FDCAN reports 0. The busload is
+/// estimated from the frames the [`CanConnection`] sharing `traffic` sends and receives, over
+/// 100ms windows and ignoring stuff bits, so frames dropped by the acceptance filters aren't
+/// counted.
+pub struct CanHealthMonitor<'a> {
+    regs: CanRegisters,
+    traffic: &'a CanTraffic,
+    health: CanBusHealth,
+}
+
+impl<'a> CanHealthMonitor<'a> {
+    /// Monitors the peripheral at `regs` (e.g. `embassy_stm32::pac::CAN1`), which should be the
+    /// one used by the connection created `with_traffic(traffic)`
+    pub fn new(regs: CanRegisters, traffic: &'a CanTraffic) -> Self {
+        Self {
+            regs,
+            traffic,
+            health: CanBusHealth::new(),
+        }
+    }
+
+    #[cfg(not(feature = "fdcan"))]
+    fn read_registers(&mut self) {
+        let esr = self.regs.esr().read();
+        self.health.set_error_counters(esr.tec(), esr.rec());
+        self.health.set_bus_off(esr.boff());
+        for fifo in 0..2 {
+            if self.regs.rfr(fifo).read().fovr() {
+                // The flag is cleared by writing 1
+                self.regs.rfr(fifo).write(|w| w.set_fovr(true));
+                self.health.record_rx_overflow();
+            }
+        }
+    }
+
+    #[cfg(feature = "fdcan")]
+    fn read_registers(&mut self) {
+        let ecr = self.regs.ecr().read();
+        self.health.set_error_counters(ecr.tec(), ecr.rec());
+        self.health.set_bus_off(self.regs.psr().read().bo());
+    }
+}
+
+impl InputBlock for CanHealthMonitor<'_> {
+    type Output = (f64, f64, f64, f64, f64, bool);
+    type Parameters = CanHealthParams;
+
+    fn input(
+        &mut self,
+        parameters: &Self::Parameters,
+        context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        self.read_registers();
+        self.health.record_bits(self.traffic.take_bits());
+        self.health.update(context.time(), parameters.bitrate)
+    }
+}
@@ -0,0 +1,71 @@
+use core::time::Duration;
+
+#[cfg(feature = "spi")]
+use embassy_stm32::{gpio::Output, spi::Spi};
+use embassy_stm32::{i2c::I2c, mode::Blocking};
+use embassy_time::Delay;
+#[cfg(feature = "spi")]
+use pictorus_internal::display::SpiSsd1306;
+use pictorus_internal::display::{Hd44780, I2cSsd1306, Ssd1306, TextDisplay};
+use pictorus_internal::utils::PictorusError;
+
+pub use pictorus_internal::display::DisplayParams;
+
+pub type Hd44780Output<'a, const N: usize> = TextDisplay<Hd44780<I2c<'a, Blocking>, Delay>, N>;
+pub type Ssd1306I2cOutput<'a, const N: usize> =
+    TextDisplay<Ssd1306<I2cSsd1306<I2c<'a, Blocking>>>, N>;
+#[cfg(feature = "spi")]
+pub type Ssd1306SpiOutput<'a, const N: usize> =
+    TextDisplay<Ssd1306<SpiSsd1306<Spi<'a, Blocking>, Output<'a>>>, N>;
+
+fn refresh_interval(refresh_interval_ms: f64) -> Duration {
+    Duration::from_micros((refresh_interval_ms * 1000.0) as u64)
+}
+
+/// Shows the inputs on an HD44780 character LCD with a PCF8574 backpack at `address` on a
+/// blocking I2C peripheral, formatted by `format`. The display is redrawn at most every
+/// `refresh_interval_ms`.
+pub fn create_hd44780_output<'a, const N: usize>(
+    i2c: I2c<'a, Blocking>,
+    address: f64,
+    cols: f64,
+    rows: f64,
+    format: &str,
+    refresh_interval_ms: f64,
+) -> Result<Hd44780Output<'a, N>, PictorusError> {
+    let lcd = Hd44780::new(i2c, Delay, address as u8, cols as usize, rows as usize)?;
+    TextDisplay::new(lcd, format, refresh_interval(refresh_interval_ms))
+}
+
+/// Shows the inputs on an SSD1306 OLED at `address` on a blocking I2C peripheral, formatted by
+/// `format`. `height` is 64 or 32 pixels, and the display is redrawn at most every
+/// `refresh_interval_ms`.
+pub fn create_ssd1306_i2c_output<'a, const N: usize>(
+    i2c: I2c<'a, Blocking>,
+    address: f64,
+    height: f64,
+    format: &str,
+    refresh_interval_ms: f64,
+) -> Result<Ssd1306I2cOutput<'a, N>, PictorusError> {
+    let interface = I2cSsd1306 {
+        i2c,
+        address: address as u8,
+    };
+    let oled = Ssd1306::new(interface, height as usize)?;
+    TextDisplay::new(oled, format, refresh_interval(refresh_interval_ms))
+}
+
+/// Shows the inputs on an SSD1306 OLED on a blocking SPI peripheral in mode 0, with its D/C
+/// line on `dc`, formatted by `format`. `height` is 64 or 32 pixels, and the display is redrawn
+/// at most every `refresh_interval_ms`.
+#[cfg(feature = "spi")]
+pub fn create_ssd1306_spi_output<'a, const N: usize>(
+    spi: Spi<'a, Blocking>,
+    dc: Output<'a>,
+    height: f64,
+    format: &str,
+    refresh_interval_ms: f64,
+) -> Result<Ssd1306SpiOutput<'a, N>, PictorusError> {
+    let oled = Ssd1306::new(SpiSsd1306 { spi, dc }, height as usize)?;
+    TextDisplay::new(oled, format, refresh_interval(refresh_interval_ms))
+}
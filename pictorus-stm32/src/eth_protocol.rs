@@ -0,0 +1,368 @@
+use alloc::vec::Vec;
+use core::time::Duration;
+use embassy_futures::{
+    block_on,
+    select::{Either, select},
+    yield_now,
+};
+use embassy_net::driver::Driver;
+use embassy_net::udp::{PacketMetadata, UdpSocket};
+use embassy_net::{
+    Config, IpEndpoint, Ipv4Address, Ipv4Cidr, Runner, Stack, StackResources, StaticConfigV4,
+};
+use heapless::Vec as HVec;
+use log::{info, warn};
+use pictorus_blocks::{UdpReceiveBlockParams, UdpTransmitBlockParams};
+use pictorus_internal::encoders::postcard_encoder::PostcardEncoderCOBS;
+use pictorus_internal::loggers::{Logger, SyncedTimestamp, TelemetrySample};
+use pictorus_internal::protocols::BUFF_SIZE_BYTES;
+use pictorus_internal::utils::{ErrorKind, PictorusError};
+use pictorus_traits::{ByteSliceSignal, Context, InputBlock, OutputBlock, PassBy};
+
+const ERR_TYPE: &str = "EthProtocol";
+const UDP_ENCODER_BUFFER_SIZE: usize = 1024;
+/// Packets each UDP socket can queue in each direction
+const PACKET_QUEUE: usize = 4;
+
+/// IP configuration of the Ethernet interface
+#[derive(Debug, Clone, PartialEq)]
+pub enum EthParams {
+    /// Requests an address from a DHCP server
+    Dhcp,
+    /// Uses a fixed address, e.g. for a direct link to a ground station
+    Static {
+        address: Ipv4Address,
+        prefix_len: u8,
+        gateway: Option<Ipv4Address>,
+    },
+}
+
+impl EthParams {
+    /// Parses an address with prefix length (e.g. "192.168.1.50/24"), or an empty string or
+    /// "dhcp" for DHCP. An empty gateway means none.
+    pub fn new(address: &str, gateway: &str) -> Result<Self, PictorusError> {
+        let invalid = |message: &str| {
+            PictorusError::new(
+                ErrorKind::InvalidConfig,
+                ERR_TYPE,
+                alloc::format!("{message}: {address}"),
+            )
+        };
+        if address.is_empty() || address.eq_ignore_ascii_case("dhcp") {
+            return Ok(Self::Dhcp);
+        }
+        let (ip, prefix_len) = address
+            .split_once('/')
+            .ok_or_else(|| invalid("IP address is missing a prefix length"))?;
+        let gateway = match gateway {
+            "" => None,
+            gateway => Some(
+                gateway
+                    .parse()
+                    .map_err(|_| invalid("Invalid gateway for IP address"))?,
+            ),
+        };
+        Ok(Self::Static {
+            address: ip.parse().map_err(|_| invalid("Invalid IP address"))?,
+            prefix_len: prefix_len
+                .parse()
+                .ok()
+                .filter(|len| *len <= 32)
+                .ok_or_else(|| invalid("Invalid prefix length"))?,
+            gateway,
+        })
+    }
+
+    fn config(&self) -> Config {
+        match self {
+            Self::Dhcp => Config::dhcpv4(Default::default()),
+            Self::Static {
+                address,
+                prefix_len,
+                gateway,
+            } => Config::ipv4_static(StaticConfigV4 {
+                address: Ipv4Cidr::new(*address, *prefix_len),
+                gateway: *gateway,
+                dns_servers: HVec::new(),
+            }),
+        }
+    }
+}
+
+/// Network stack on an Ethernet (or other embassy-net) driver, e.g. the STM32H7/F7 `Ethernet`
+/// peripheral with its PHY.
+///
+/// Apps don't run an async executor, so the stack only makes progress when [`EthStack::poll`]
+/// is called, which should happen once per tick before the UDP blocks and telemetry logger run.
+/// Each poll handles all the packets that have arrived since the previous one.
+pub struct EthStack<'a, D: Driver> {
+    stack: Stack<'a>,
+    runner: Runner<'a, D>,
+    has_address: bool,
+}
+
+/// Starts a network stack on `driver`. `seed` should be random (e.g. from the RNG peripheral),
+/// and `resources` must have room for a socket per UDP connection and logger, plus one for DHCP.
+pub fn create_eth_stack<'a, D: Driver, const SOCKETS: usize>(
+    driver: D,
+    params: &EthParams,
+    resources: &'a mut StackResources<SOCKETS>,
+    seed: u64,
+) -> EthStack<'a, D> {
+    let (stack, runner) = embassy_net::new(driver, params.config(), resources, seed);
+    EthStack {
+        stack,
+        runner,
+        has_address: false,
+    }
+}
+
+impl<'a, D: Driver> EthStack<'a, D> {
+    /// Processes pending network traffic without blocking
+    pub fn poll(&mut self) {
+        // The runner never completes, so this polls it until the yield lets the tick continue
+        block_on(select(self.runner.run(), yield_now()));
+
+        let has_address = self.stack.config_v4().is_some();
+        if has_address != self.has_address {
+            self.has_address = has_address;
+            match self.stack.config_v4() {
+                Some(config) => info!("Ethernet up with address {}", config.address),
+                None => warn!("Ethernet lost its IP address"),
+            }
+        }
+    }
+
+    /// Handle to the stack, for creating sockets
+    pub fn stack(&self) -> Stack<'a> {
+        self.stack
+    }
+}
+
+/// Buffers for a UDP socket. They must outlive the socket, so they are usually static.
+pub struct UdpBuffers {
+    rx_meta: [PacketMetadata; PACKET_QUEUE],
+    rx: [u8; BUFF_SIZE_BYTES],
+    tx_meta: [PacketMetadata; PACKET_QUEUE],
+    tx: [u8; BUFF_SIZE_BYTES],
+}
+
+impl UdpBuffers {
+    pub const fn new() -> Self {
+        Self {
+            rx_meta: [PacketMetadata::EMPTY; PACKET_QUEUE],
+            rx: [0; BUFF_SIZE_BYTES],
+            tx_meta: [PacketMetadata::EMPTY; PACKET_QUEUE],
+            tx: [0; BUFF_SIZE_BYTES],
+        }
+    }
+}
+
+impl Default for UdpBuffers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn create_socket<'a>(
+    stack: Stack<'a>,
+    buffers: &'a mut UdpBuffers,
+    port: u16,
+) -> Result<UdpSocket<'a>, PictorusError> {
+    let mut socket = UdpSocket::new(
+        stack,
+        &mut buffers.rx_meta,
+        &mut buffers.rx,
+        &mut buffers.tx_meta,
+        &mut buffers.tx,
+    );
+    socket.bind(port).map_err(|err| {
+        PictorusError::new(
+            ErrorKind::DeviceConfig,
+            ERR_TYPE,
+            alloc::format!("Couldn't bind UDP socket to port {port} ({err:?})"),
+        )
+    })?;
+    Ok(socket)
+}
+
+/// UDP socket on an [`EthStack`], as the hardware side of the UDP receive and transmit blocks.
+///
+/// Like the std `UdpConnection`, the input is the most recent datagram received since the
+/// previous tick, and the output sends the input bytes to the block's destination address.
+pub struct EthUdpConnection<'a> {
+    socket: UdpSocket<'a>,
+    buffer: Vec<u8>,
+    is_cache_valid: bool,
+}
+
+impl<'a> EthUdpConnection<'a> {
+    /// Binds a socket to `port` on the stack. Port 0 picks an ephemeral port, for transmit only
+    /// connections.
+    pub fn new(
+        stack: Stack<'a>,
+        buffers: &'a mut UdpBuffers,
+        port: u16,
+    ) -> Result<Self, PictorusError> {
+        Ok(Self {
+            socket: create_socket(stack, buffers, port)?,
+            buffer: Vec::with_capacity(BUFF_SIZE_BYTES),
+            is_cache_valid: false,
+        })
+    }
+
+    fn read_into_buffer(&mut self) {
+        self.buffer.resize(BUFF_SIZE_BYTES, 0);
+        let mut len = 0;
+        // Only use the most recent datagram on the socket
+        loop {
+            match block_on(select(self.socket.recv_from(&mut self.buffer), yield_now())) {
+                Either::First(Ok((read, _))) => len = read,
+                // A truncated datagram has overwritten the buffer, so it can't be used
+                Either::First(Err(_)) => len = 0,
+                Either::Second(_) => break,
+            }
+        }
+        self.buffer.truncate(len);
+    }
+}
+
+impl InputBlock for EthUdpConnection<'_> {
+    type Output = ByteSliceSignal;
+    type Parameters = UdpReceiveBlockParams;
+
+    fn input(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        if !self.is_cache_valid {
+            self.read_into_buffer();
+            // Don't read again until flush is called
+            self.is_cache_valid = true;
+        }
+        &self.buffer
+    }
+}
+
+impl OutputBlock for EthUdpConnection<'_> {
+    type Inputs = ByteSliceSignal;
+    type Parameters = UdpTransmitBlockParams;
+
+    fn output(
+        &mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) {
+        let Ok(destination) = parameters.destination().parse::<IpEndpoint>() else {
+            warn!("Invalid UDP destination: {}", parameters.destination());
+            return;
+        };
+        // Sends only wait for buffer space, so give up rather than stall the tick
+        if let Either::Second(_) = block_on(select(
+            self.socket.send_to(inputs, destination),
+            yield_now(),
+        )) {
+            warn!("UDP transmit buffer full, dropping packet");
+        }
+    }
+}
+
+impl pictorus_internal::protocols::Flush for EthUdpConnection<'_> {
+    fn flush(&mut self) {
+        self.is_cache_valid = false;
+    }
+}
+
+/// Streams telemetry to the device manager over UDP, in the same COBS-encoded postcard format as
+/// the std `UdpLogger`, so Ethernet boards don't need a host PC serial link.
+pub struct EthUdpLogger<'a> {
+    socket: UdpSocket<'a>,
+    destination: Option<IpEndpoint>,
+    publish_period: Duration,
+    last_publish_time: Option<Duration>,
+    has_connection: bool,
+    encoder: PostcardEncoderCOBS,
+}
+
+impl<'a> EthUdpLogger<'a> {
+    /// Publishes to `publish_socket` (e.g. "192.168.1.10:8080") every `publish_period`. An
+    /// empty address or zero period disables publishing.
+    pub fn new(
+        stack: Stack<'a>,
+        buffers: &'a mut UdpBuffers,
+        publish_period: Duration,
+        publish_socket: &str,
+    ) -> Result<Self, PictorusError> {
+        let destination = match publish_socket {
+            "" => None,
+            address => Some(address.parse().map_err(|_| {
+                PictorusError::new(
+                    ErrorKind::InvalidConfig,
+                    ERR_TYPE,
+                    alloc::format!("Invalid telemetry address: {address}"),
+                )
+            })?),
+        };
+        Ok(Self {
+            socket: create_socket(stack, buffers, 0)?,
+            destination,
+            publish_period,
+            last_publish_time: None,
+            has_connection: true,
+            encoder: PostcardEncoderCOBS {},
+        })
+    }
+}
+
+impl Logger for EthUdpLogger<'_> {
+    fn should_log(&mut self, app_time: Duration) -> bool {
+        self.destination.is_some()
+            && self.publish_period > Duration::ZERO
+            && self
+                .last_publish_time
+                .is_none_or(|last| app_time.saturating_sub(last) >= self.publish_period)
+    }
+
+    fn log(&mut self, log_data: &impl serde::Serialize, app_time: Duration) {
+        self.log_timestamped(log_data, app_time, None);
+    }
+
+    fn log_timestamped(
+        &mut self,
+        log_data: &impl serde::Serialize,
+        app_time: Duration,
+        timestamp: Option<SyncedTimestamp>,
+    ) {
+        let Some(destination) = self.destination else {
+            return;
+        };
+        if !self.should_log(app_time) {
+            return;
+        }
+        let sample = TelemetrySample {
+            data: log_data,
+            timestamp,
+        };
+        let encoded = self.encoder.encode::<UDP_ENCODER_BUFFER_SIZE>(&sample);
+        let sent = matches!(
+            block_on(select(
+                self.socket.send_to(&encoded, destination),
+                yield_now()
+            )),
+            Either::First(Ok(()))
+        );
+        if sent {
+            self.last_publish_time = Some(app_time);
+            if !self.has_connection {
+                info!("Regained UDP connection.");
+                self.has_connection = true;
+            }
+        } else if self.has_connection {
+            // Unlike the std logger this never aborts, since the link may come back
+            warn!("Lost UDP connection! Skipping telemetry transmit...");
+            self.has_connection = false;
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use embassy_stm32::flash::{Blocking, Flash};
+use pictorus_internal::param_store::FlashParamStore;
+use pictorus_internal::utils::PictorusError;
+
+pub type Stm32ParamStore<'a> = FlashParamStore<Flash<'a, Blocking>>;
+
+/// Opens a parameter store in two banks of `bank_size` bytes of internal flash, starting
+/// `offset` bytes after the start of flash. The region must be aligned to flash sectors and
+/// must not overlap the app, so reserve it in the linker script's memory layout.
+pub fn create_param_store(
+    flash: Flash<'_, Blocking>,
+    offset: u32,
+    bank_size: u32,
+) -> Result<Stm32ParamStore<'_>, PictorusError> {
+    FlashParamStore::new(flash, offset, bank_size)
+}
@@ -0,0 +1,34 @@
+use embassy_stm32::gpio::{Input, Output};
+#[cfg(feature = "spi")]
+use embassy_stm32::{mode::Blocking, spi::Spi};
+#[cfg(feature = "spi")]
+use pictorus_internal::hx711::SpiHx711;
+use pictorus_internal::hx711::{GpioHx711, Hx711};
+use pictorus_internal::utils::PictorusError;
+
+pub use pictorus_internal::hx711::Hx711Params;
+
+pub type Hx711GpioInput<'a> = Hx711<GpioHx711<Output<'a>, Input<'a>>>;
+#[cfg(feature = "spi")]
+pub type Hx711SpiInput<'a> = Hx711<SpiHx711<Spi<'a, Blocking>>>;
+
+/// Reads an HX711 by bit-banging PD_SCK on `clock` and reading DOUT on `data`. `gain` is 128 or
+/// 64 for channel A, or 32 for channel B.
+pub fn create_hx711_gpio_input<'a>(
+    clock: Output<'a>,
+    data: Input<'a>,
+    gain: f64,
+) -> Result<Hx711GpioInput<'a>, PictorusError> {
+    Hx711::new(GpioHx711 { clock, data }, gain)
+}
+
+/// Reads an HX711 from a blocking SPI peripheral, with PD_SCK on MOSI and DOUT on MISO. The SPI
+/// peripheral must be configured for mode 0, MSB first, at twice the PD_SCK rate. `gain` is 128
+/// or 64 for channel A, or 32 for channel B.
+#[cfg(feature = "spi")]
+pub fn create_hx711_spi_input<'a>(
+    spi: Spi<'a, Blocking>,
+    gain: f64,
+) -> Result<Hx711SpiInput<'a>, PictorusError> {
+    Hx711::new(SpiHx711(spi), gain)
+}
@@ -1,10 +1,16 @@
 use alloc::vec::Vec;
 use embassy_stm32::i2c::I2c;
 use embassy_stm32::mode::Blocking;
+use embassy_stm32::pac::{self, i2c::vals as i2c_vals};
+use embassy_time::{Duration, Instant};
 use embedded_hal::i2c::I2c as I2cTrait;
+use log::warn;
 use pictorus_blocks::{I2cInputBlockParams, I2cOutputBlockParams};
+use pictorus_internal::i2c_slave::RegisterMap;
 use pictorus_traits::{ByteSliceSignal, InputBlock, OutputBlock};
 
+pub use pictorus_internal::i2c_slave::I2cSlaveParams;
+
 pub struct I2cWrapper<'a> {
     i2c: I2c<'a, Blocking>,
     buffer: Vec<u8>,
@@ -61,3 +67,109 @@ impl OutputBlock for I2cWrapper<'_> {
         self.i2c.write(parameters.address, &tx_buffer).ok();
     }
 }
+
+/// Longest a host transaction may take before the slave gives up on it
+const SLAVE_TRANSACTION_TIMEOUT: Duration = Duration::from_millis(5);
+
+/// I2C peripheral in slave mode, exposing a [`RegisterMap`] of `N` registers to a host computer
+/// so the app behaves like a smart sensor.
+///
+/// As an `OutputBlock`, the input bytes update the model registers the host reads. As an
+/// `InputBlock`, the output is the registers the host has written. The peripheral is polled
+/// from both blocks: while the model is between ticks, clock stretching holds the host until the
+/// next poll services its transaction, so hosts must tolerate stretching of up to a tick.
+/// Serving a whole transaction at once keeps the register values consistent within each read.
+///
+/// The register setup applies to the I2C v2 peripherals (e.g. STM32F0, F3, F7, L4, G4, H7).
+pub struct I2cSlaveWrapper<'a, const N: usize> {
+    // Held so the peripheral and pins stay configured
+    _i2c: I2c<'a, Blocking>,
+    regs: pac::i2c::I2c,
+    map: RegisterMap<N>,
+}
+
+impl<'a, const N: usize> I2cSlaveWrapper<'a, N> {
+    /// Switches `i2c` to slave mode at the configured address. `i2c` must be created for the
+    /// same peripheral as `regs` (e.g. `embassy_stm32::pac::I2C1`), with the bus frequency of the
+    /// host.
+    pub fn new(i2c: I2c<'a, Blocking>, regs: pac::i2c::I2c, params: &I2cSlaveParams) -> Self {
+        regs.cr1().modify(|w| w.set_pe(false));
+        regs.oar1().write(|w| {
+            w.set_oa1(u16::from(params.address) << 1);
+            w.set_oa1mode(i2c_vals::Addmode::BIT7);
+            w.set_oa1en(true);
+        });
+        regs.cr1().modify(|w| {
+            w.set_nostretch(false);
+            w.set_pe(true);
+        });
+        Self {
+            _i2c: i2c,
+            regs,
+            map: RegisterMap::new(params.model_registers),
+        }
+    }
+
+    /// Serves the pending host transaction, if any, through to its stop condition
+    fn poll(&mut self) {
+        if !self.regs.isr().read().addr() {
+            return;
+        }
+        let deadline = Instant::now() + SLAVE_TRANSACTION_TIMEOUT;
+        while Instant::now() < deadline {
+            let isr = self.regs.isr().read();
+            if isr.addr() {
+                // Also handles the repeated start between setting the address and reading
+                let read = isr.dir() == i2c_vals::Dir::READ;
+                if read {
+                    // Discard any byte preloaded for a read the host ended early
+                    self.regs.isr().modify(|w| w.set_txe(true));
+                }
+                self.map.start(read);
+                self.regs.icr().write(|w| w.set_addrcf(true));
+            } else if isr.rxne() {
+                self.map.receive(self.regs.rxdr().read().rxdata());
+            } else if isr.txis() {
+                self.regs
+                    .txdr()
+                    .write(|w| w.set_txdata(self.map.transmit()));
+            } else if isr.stopf() {
+                self.regs.icr().write(|w| {
+                    w.set_stopcf(true);
+                    w.set_nackcf(true);
+                });
+                return;
+            }
+        }
+        warn!("I2C slave transaction timed out");
+    }
+}
+
+impl<const N: usize> InputBlock for I2cSlaveWrapper<'_, N> {
+    type Output = ByteSliceSignal;
+    type Parameters = I2cSlaveParams;
+
+    fn input(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+    ) -> pictorus_traits::PassBy<'_, Self::Output> {
+        self.poll();
+        self.map.host_registers()
+    }
+}
+
+impl<const N: usize> OutputBlock for I2cSlaveWrapper<'_, N> {
+    type Inputs = ByteSliceSignal;
+    type Parameters = I2cSlaveParams;
+
+    fn output(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn pictorus_traits::Context,
+        inputs: pictorus_traits::PassBy<'_, Self::Inputs>,
+    ) {
+        self.poll();
+        self.map.set_model_registers(inputs);
+    }
+}
@@ -22,6 +22,12 @@ pub use can_protocol::*;
 mod pwm_protocol;
 pub use pwm_protocol::*;
 
+mod stepper_protocol;
+pub use stepper_protocol::*;
+
+mod tone_protocol;
+pub use tone_protocol::*;
+
 #[cfg(feature = "alloc")]
 mod i2c_protocol;
 #[cfg(feature = "alloc")]
@@ -37,10 +43,40 @@ mod spi_protocol;
 #[cfg(feature = "spi")]
 pub use spi_protocol::*;
 
+#[cfg(feature = "spi")]
+mod lora_protocol;
+#[cfg(feature = "spi")]
+pub use lora_protocol::*;
+
+#[cfg(feature = "eth")]
+mod eth_protocol;
+#[cfg(feature = "eth")]
+pub use eth_protocol::*;
+
 #[cfg(feature = "adc")]
 mod adc_protocol;
 #[cfg(feature = "adc")]
 pub use adc_protocol::*;
 
+mod flash_protocol;
+pub use flash_protocol::*;
+
+mod display_protocol;
+pub use display_protocol::*;
+
 mod gpio_protocol;
 pub use gpio_protocol::*;
+
+mod hx711_protocol;
+pub use hx711_protocol::*;
+
+#[cfg(feature = "embedded-metrics")]
+mod memory_stats_protocol;
+#[cfg(feature = "embedded-metrics")]
+pub use memory_stats_protocol::*;
+
+mod rangefinder_protocol;
+pub use rangefinder_protocol::*;
+
+mod shift_register_protocol;
+pub use shift_register_protocol::*;
@@ -0,0 +1,31 @@
+use embassy_stm32::gpio::{Input, Output};
+use embassy_stm32::mode::Blocking;
+use embassy_stm32::spi::Spi;
+use pictorus_internal::lora::{LoRaConfig, LoRaConnection, Sx126x, Sx127x};
+use pictorus_internal::utils::PictorusError;
+
+pub use pictorus_internal::lora::LoRaParams;
+
+pub type Sx127xConnection<'a> = LoRaConnection<Sx127x<Spi<'a, Blocking>, Output<'a>>>;
+pub type Sx126xConnection<'a> = LoRaConnection<Sx126x<Spi<'a, Blocking>, Output<'a>, Input<'a>>>;
+
+/// Applies `config` to an SX127x (RFM95/96/98) radio on a blocking SPI peripheral.
+/// The SPI peripheral must be configured for mode 0.
+pub fn create_sx127x_connection<'a>(
+    spi: Spi<'a, Blocking>,
+    cs: Output<'a>,
+    config: &LoRaConfig,
+) -> Result<Sx127xConnection<'a>, PictorusError> {
+    LoRaConnection::new(Sx127x::new(spi, cs), config)
+}
+
+/// Applies `config` to an SX126x radio on a blocking SPI peripheral.
+/// The SPI peripheral must be configured for mode 0.
+pub fn create_sx126x_connection<'a>(
+    spi: Spi<'a, Blocking>,
+    cs: Output<'a>,
+    busy: Input<'a>,
+    config: &LoRaConfig,
+) -> Result<Sx126xConnection<'a>, PictorusError> {
+    LoRaConnection::new(Sx126x::new(spi, cs, busy), config)
+}
@@ -0,0 +1,69 @@
+use pictorus_internal::embedded_metrics::{HeapUsage, StackRegion};
+pub use pictorus_internal::embedded_metrics::{MemoryStatsParams, TrackingAllocator};
+use pictorus_traits::{Context, InputBlock, PassBy};
+
+unsafe extern "C" {
+    /// Start of the stack, at the end of RAM, from the cortex-m-rt linker script
+    static _stack_start: u32;
+    /// End of the statically allocated RAM, which the stack grows down towards
+    static __sheap: u32;
+}
+
+/// The RAM between the end of the static data and the start of the stack, which is what the
+/// stack can grow into with the default cortex-m-rt memory layout
+pub fn default_stack_region() -> StackRegion {
+    // SAFETY: With the default layout nothing but the stack uses this RAM. Heaps are usually
+    // static arrays, placed before `__sheap`.
+    unsafe {
+        StackRegion::new(
+            core::ptr::addr_of!(__sheap),
+            core::ptr::addr_of!(_stack_start),
+        )
+    }
+}
+
+/// Reports the memory use of the app in bytes: `(heap used, heap peak, stack peak, stack
+/// size)`.
+///
+/// Heap usage is read from a [`TrackingAllocator`] declared as the global allocator, and is 0
+/// without one. The stack is painted when the block is created, so it should be created early
+/// in `main`, and the stack peak is the deepest the stack has grown since then.
+pub struct MemoryStatsBlock {
+    heap: Option<&'static dyn HeapUsage>,
+    stack: StackRegion,
+    buffer: (f64, f64, f64, f64),
+}
+
+impl MemoryStatsBlock {
+    pub fn new(heap: Option<&'static dyn HeapUsage>, stack: StackRegion) -> Self {
+        stack.paint();
+        Self {
+            heap,
+            stack,
+            buffer: (0.0, 0.0, 0.0, stack.size() as f64),
+        }
+    }
+}
+
+impl InputBlock for MemoryStatsBlock {
+    type Output = (f64, f64, f64, f64);
+    type Parameters = MemoryStatsParams;
+
+    fn input(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+    ) -> PassBy<'_, Self::Output> {
+        if let Some(heap) = self.heap {
+            self.buffer.0 = heap.heap_used() as f64;
+            self.buffer.1 = heap.heap_peak() as f64;
+        }
+        self.buffer.2 = self.stack.peak_usage() as f64;
+        self.buffer
+    }
+}
+
+/// Monitors the heap tracked by `heap`, if any, and the stack in its default region
+pub fn create_memory_stats_block(heap: Option<&'static dyn HeapUsage>) -> MemoryStatsBlock {
+    MemoryStatsBlock::new(heap, default_stack_region())
+}
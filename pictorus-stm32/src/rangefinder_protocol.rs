@@ -0,0 +1,43 @@
+use embassy_stm32::gpio::{Input, Output};
+use embassy_stm32::i2c::I2c;
+use embassy_stm32::mode::Blocking;
+use embassy_time::Delay;
+use pictorus_internal::rangefinder::{HcSr04, Vl53l0x, Vl53l1x};
+use pictorus_internal::utils::PictorusError;
+
+use crate::Stm32Clock;
+
+pub use pictorus_internal::rangefinder::RangefinderParams;
+
+pub type HcSr04Input<'a> = HcSr04<Output<'a>, Input<'a>, Stm32Clock, Delay>;
+pub type Vl53l0xInput<'a> = Vl53l0x<I2c<'a, Blocking>, Delay>;
+pub type Vl53l1xInput<'a> = Vl53l1x<I2c<'a, Blocking>, Delay>;
+
+/// Measures range with an HC-SR04, pulsing TRIG on `trigger` and timing ECHO on `echo`. The
+/// ECHO output is 5V, so `echo` must be a 5V tolerant pin. `max_range` is in meters, and bounds
+/// how long each tick waits for the echo.
+pub fn create_hc_sr04_input<'a>(
+    trigger: Output<'a>,
+    echo: Input<'a>,
+    max_range: f64,
+) -> HcSr04Input<'a> {
+    HcSr04::new(trigger, echo, Stm32Clock::default(), Delay, max_range)
+}
+
+/// Initializes a VL53L0X time-of-flight sensor at `address` on a blocking I2C peripheral and
+/// starts ranging
+pub fn create_vl53l0x_input<'a>(
+    i2c: I2c<'a, Blocking>,
+    address: f64,
+) -> Result<Vl53l0xInput<'a>, PictorusError> {
+    Vl53l0x::new(i2c, Delay, address as u8)
+}
+
+/// Initializes a VL53L1X time-of-flight sensor at `address` on a blocking I2C peripheral and
+/// starts ranging
+pub fn create_vl53l1x_input<'a>(
+    i2c: I2c<'a, Blocking>,
+    address: f64,
+) -> Result<Vl53l1xInput<'a>, PictorusError> {
+    Vl53l1x::new(i2c, Delay, address as u8)
+}
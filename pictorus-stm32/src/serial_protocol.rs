@@ -40,15 +40,21 @@ pub struct SerialWrapper<'a> {
 impl<'a> SerialWrapper<'a> {
     #[cfg(not(feature = "interrupt-uart"))]
     pub fn new(uart: Uart<'a, Async>, rx_buf: &'a mut [u8]) -> Self {
+        Self::try_new(uart, rx_buf).unwrap()
+    }
+
+    /// Fallible version of [`SerialWrapper::new`], which fails if the receive DMA can't start
+    #[cfg(not(feature = "interrupt-uart"))]
+    pub fn try_new(uart: Uart<'a, Async>, rx_buf: &'a mut [u8]) -> Result<Self, Error> {
         let (tx, rx) = uart.split();
         let mut rx = rx.into_ring_buffered(rx_buf);
-        rx.start().unwrap();
-        Self {
+        rx.start()?;
+        Ok(Self {
             tx,
             rx,
             cache_stale: true,
             cache: Vec::with_capacity(BUFF_SIZE_BYTES),
-        }
+        })
     }
 
     #[cfg(feature = "interrupt-uart")]
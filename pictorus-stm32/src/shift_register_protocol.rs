@@ -0,0 +1,55 @@
+use embassy_stm32::gpio::{Input, Output};
+#[cfg(feature = "spi")]
+use embassy_stm32::{mode::Blocking, spi::Spi};
+#[cfg(feature = "spi")]
+use pictorus_internal::shift_register::SpiShift;
+use pictorus_internal::shift_register::{GpioShift, Hc165, Hc595};
+
+pub use pictorus_internal::shift_register::ShiftRegisterParams;
+
+#[cfg(feature = "spi")]
+pub type Hc595SpiOutput<'a, const N: usize> = Hc595<SpiShift<Spi<'a, Blocking>>, Output<'a>, N>;
+pub type Hc595GpioOutput<'a, const N: usize> =
+    Hc595<GpioShift<Output<'a>, Output<'a>>, Output<'a>, N>;
+#[cfg(feature = "spi")]
+pub type Hc165SpiInput<'a, const N: usize> = Hc165<SpiShift<Spi<'a, Blocking>>, Output<'a>, N>;
+pub type Hc165GpioInput<'a, const N: usize> =
+    Hc165<GpioShift<Output<'a>, Input<'a>>, Output<'a>, N>;
+
+/// Drives a chain of 74HC595 registers from a blocking SPI peripheral (SER on MOSI, SRCLK on
+/// SCK), with RCLK on `latch`. The SPI peripheral must be configured for mode 0, MSB first.
+#[cfg(feature = "spi")]
+pub fn create_hc595_spi_output<'a, const N: usize>(
+    spi: Spi<'a, Blocking>,
+    latch: Output<'a>,
+) -> Hc595SpiOutput<'a, N> {
+    Hc595::new(SpiShift(spi), latch)
+}
+
+/// Drives a chain of 74HC595 registers by bit-banging SRCLK and SER, with RCLK on `latch`
+pub fn create_hc595_gpio_output<'a, const N: usize>(
+    clock: Output<'a>,
+    data: Output<'a>,
+    latch: Output<'a>,
+) -> Hc595GpioOutput<'a, N> {
+    Hc595::new(GpioShift { clock, data }, latch)
+}
+
+/// Reads a chain of 74HC165 registers from a blocking SPI peripheral (QH on MISO, CLK on SCK),
+/// with SH/LD on `load`. The SPI peripheral must be configured for mode 0, MSB first.
+#[cfg(feature = "spi")]
+pub fn create_hc165_spi_input<'a, const N: usize>(
+    spi: Spi<'a, Blocking>,
+    load: Output<'a>,
+) -> Hc165SpiInput<'a, N> {
+    Hc165::new(SpiShift(spi), load)
+}
+
+/// Reads a chain of 74HC165 registers by bit-banging CLK and reading QH, with SH/LD on `load`
+pub fn create_hc165_gpio_input<'a, const N: usize>(
+    clock: Output<'a>,
+    data: Input<'a>,
+    load: Output<'a>,
+) -> Hc165GpioInput<'a, N> {
+    Hc165::new(GpioShift { clock, data }, load)
+}
@@ -1,6 +1,9 @@
+use embassy_stm32::dma::{ReadableRingBuffer, TransferOptions, WritableRingBuffer};
 use embassy_stm32::gpio::Output;
 use embassy_stm32::mode::Blocking;
-use embassy_stm32::spi::Spi;
+use embassy_stm32::pac::{self, spi::vals};
+use embassy_stm32::spi::{Instance, RxDma, Spi, TxDma};
+use embassy_stm32::{Peripheral, into_ref};
 use heapless::Vec;
 use log::warn;
 use pictorus_blocks::{SpiReceiveBlockParams, SpiTransmitBlockParams};
@@ -99,3 +102,167 @@ impl Flush for SpiWrapper<'_> {
         self.cache.clear();
     }
 }
+
+/// SPI peripheral in slave (peripheral) mode, serving data to an external master such as a
+/// companion SoC.
+///
+/// Both directions run through circular DMA ring buffers, so the master can clock transactions
+/// at any time without the model being involved:
+/// - As an `InputBlock`, the output is the bytes received since the previous tick, up to
+///   `read_bytes` (or the whole backlog if `read_bytes` is 0). Older bytes beyond that are
+///   dropped so the output reflects the latest data.
+/// - As an `OutputBlock`, the input bytes are queued for the master to read. Bytes that don't
+///   fit in the TX ring are dropped. The ring is circular, so if the master reads more than was
+///   queued it gets the previously sent bytes again; refresh the data every tick.
+///
+/// Slave select is handled in software as always active, so the bus must be dedicated to this
+/// device. The register setup applies to the SPI v1-v3 peripherals (e.g. STM32F4, F7, L4, G4).
+pub struct SpiSlaveWrapper<'a> {
+    rx: ReadableRingBuffer<'a, u8>,
+    tx: WritableRingBuffer<'a, u8>,
+    cache: Vec<u8, BUFF_SIZE_BYTES>,
+    cache_stale: bool,
+    // Held so the peripheral and pins stay configured. Declared last so the DMA transfers are
+    // stopped before the driver disables the peripheral.
+    _spi: Spi<'a, Blocking>,
+}
+
+impl<'a> SpiSlaveWrapper<'a> {
+    /// Switches `spi` to slave mode and starts the DMA ring buffers. `spi` must be created with
+    /// the bus mode of the master; its clock frequency is ignored, since the master drives the
+    /// clock.
+    ///
+    /// # Safety
+    ///
+    /// `spi`, `regs` (e.g. `embassy_stm32::pac::SPI1`) and the DMA channels must all be for the
+    /// same peripheral `T`, since the DMA channels transfer to and from the data register of
+    /// `regs`. embassy-stm32 doesn't expose an `Instance`'s registers, so this can't be checked.
+    pub unsafe fn new<T: Instance>(
+        spi: Spi<'a, Blocking>,
+        regs: pac::spi::Spi,
+        rx_dma: impl Peripheral<P = impl RxDma<T>> + 'a,
+        tx_dma: impl Peripheral<P = impl TxDma<T>> + 'a,
+        rx_buffer: &'a mut [u8],
+        tx_buffer: &'a mut [u8],
+    ) -> Self {
+        into_ref!(rx_dma, tx_dma);
+        let rx_request = rx_dma.request();
+        let tx_request = tx_dma.request();
+
+        regs.cr1().modify(|w| w.set_spe(false));
+        regs.cr1().modify(|w| {
+            w.set_mstr(vals::Mstr::SLAVE);
+            w.set_ssm(true);
+            w.set_ssi(false);
+        });
+        regs.cr2().modify(|w| {
+            w.set_rxdmaen(true);
+            w.set_txdmaen(true);
+        });
+        // SAFETY: The caller guarantees the DMA channels are for this SPI peripheral, whose data
+        // register address is valid for the lifetime of the program
+        let (mut rx, mut tx) = unsafe {
+            (
+                ReadableRingBuffer::new(
+                    rx_dma,
+                    rx_request,
+                    regs.dr().as_ptr() as *mut u8,
+                    rx_buffer,
+                    TransferOptions::default(),
+                ),
+                WritableRingBuffer::new(
+                    tx_dma,
+                    tx_request,
+                    regs.dr().as_ptr() as *mut u8,
+                    tx_buffer,
+                    TransferOptions::default(),
+                ),
+            )
+        };
+        rx.start();
+        tx.start();
+        regs.cr1().modify(|w| w.set_spe(true));
+
+        Self {
+            rx,
+            tx,
+            cache: Vec::new(),
+            cache_stale: true,
+            // The peripheral keeps its pin and bus mode configuration, only the role changes
+            _spi: spi,
+        }
+    }
+}
+
+impl InputBlock for SpiSlaveWrapper<'_> {
+    type Output = ByteSliceSignal;
+    type Parameters = SpiReceiveBlockParams;
+
+    fn input<'b>(
+        &'b mut self,
+        parameters: &Self::Parameters,
+        _context: &dyn Context,
+    ) -> PassBy<'b, Self::Output> {
+        if self.cache_stale {
+            self.cache_stale = false;
+            let limit = match parameters.read_bytes {
+                0 => BUFF_SIZE_BYTES,
+                read_bytes => read_bytes.min(BUFF_SIZE_BYTES),
+            };
+
+            let mut chunk = [0u8; 64];
+            loop {
+                let read = match self.rx.read(&mut chunk) {
+                    Ok((0, _)) => break,
+                    Ok((read, _)) => read,
+                    Err(_) => {
+                        warn!("SPI slave receive overrun, dropping buffered data");
+                        self.rx.clear();
+                        break;
+                    }
+                };
+                // Keep the newest `limit` bytes
+                let overflow = (self.cache.len() + read).saturating_sub(limit);
+                if overflow > 0 {
+                    let dropped = overflow.min(self.cache.len());
+                    self.cache.rotate_left(dropped);
+                    self.cache.truncate(self.cache.len() - dropped);
+                }
+                let start = read.saturating_sub(limit);
+                self.cache.extend_from_slice(&chunk[start..read]).ok();
+            }
+        }
+
+        &self.cache
+    }
+}
+
+impl OutputBlock for SpiSlaveWrapper<'_> {
+    type Inputs = ByteSliceSignal;
+    type Parameters = SpiTransmitBlockParams;
+
+    fn output(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) {
+        match self.tx.write(inputs) {
+            Ok((written, _)) if written < inputs.len() => {
+                warn!("SPI slave transmit buffer full, dropping data");
+            }
+            Ok(_) => {}
+            Err(_) => {
+                warn!("SPI slave transmit underrun, resetting buffer");
+                self.tx.clear();
+            }
+        }
+    }
+}
+
+impl Flush for SpiSlaveWrapper<'_> {
+    fn flush(&mut self) {
+        self.cache_stale = true;
+        self.cache.clear();
+    }
+}
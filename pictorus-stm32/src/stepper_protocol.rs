@@ -0,0 +1,72 @@
+use embassy_stm32::gpio::Output;
+use embassy_stm32::time::hz;
+use embassy_stm32::timer::simple_pwm::SimplePwm;
+use embassy_stm32::timer::{self, Channel};
+use pictorus_blocks::StepperBlockParams;
+use pictorus_traits::{Context, OutputBlock, PassBy};
+
+/// Generates step/dir pulse trains using a hardware timer for the step output and a GPIO
+/// for the direction output.
+///
+/// The timer runs a 50% duty cycle PWM at the requested step rate, so pulse timing is
+/// independent of the model rate. The timer frequency resolution is 1Hz, and the channel is
+/// disabled for rates below 1 step per second.
+pub struct StepperWrapper<'d, T: timer::GeneralInstance4Channel> {
+    simple_pwm: SimplePwm<'d, T>,
+    channel: Channel,
+    dir: Output<'d>,
+    frequency: u32,
+}
+
+impl<'d, T: timer::GeneralInstance4Channel> StepperWrapper<'d, T> {
+    pub fn new(simple_pwm: SimplePwm<'d, T>, channel: Channel, dir: Output<'d>) -> Self {
+        let mut wrapper = StepperWrapper {
+            simple_pwm,
+            channel,
+            dir,
+            frequency: 0,
+        };
+        wrapper.simple_pwm.disable(channel);
+        wrapper
+    }
+
+    fn set_rate(&mut self, rate: f64) {
+        if rate > 0.0 {
+            self.dir.set_high();
+        } else if rate < 0.0 {
+            self.dir.set_low();
+        }
+
+        // Saturating float to int cast, NaN maps to 0
+        let frequency = rate.abs() as u32;
+        if frequency == self.frequency {
+            return;
+        }
+        self.frequency = frequency;
+
+        if frequency == 0 {
+            self.simple_pwm.disable(self.channel);
+            return;
+        }
+        self.simple_pwm.set_frequency(hz(frequency));
+        // The max duty changes with the frequency, so the duty cycle must be set again
+        let half_duty = self.simple_pwm.get_max_duty() / 2;
+        self.simple_pwm.set_duty(self.channel, half_duty);
+        self.simple_pwm.enable(self.channel);
+    }
+}
+
+impl<T: timer::GeneralInstance4Channel> OutputBlock for StepperWrapper<'_, T> {
+    type Inputs = (f64, f64); // (Step rate, Position)
+    type Parameters = StepperBlockParams<f64>;
+
+    fn output(
+        &mut self,
+        _parameters: &Self::Parameters,
+        _context: &dyn Context,
+        inputs: PassBy<'_, Self::Inputs>,
+    ) {
+        let (rate, _position) = inputs;
+        self.set_rate(rate);
+    }
+}
@@ -0,0 +1,21 @@
+use embassy_stm32::timer::{Channel, GeneralInstance4Channel};
+use pictorus_internal::tone::ToneBlock;
+use pictorus_internal::utils::PictorusError;
+
+use crate::PwmWrapper;
+
+pub use pictorus_internal::tone::ToneParams;
+
+pub type ToneOutput<'d, T> = ToneBlock<PwmWrapper<'d, T>>;
+
+/// Plays the semicolon separated tone `sequences` on `channel` of a PWM timer, at a duty cycle
+/// of `duty` (0 to 1). The timer's frequency follows the tones, so its other channels shouldn't
+/// be used for anything else.
+pub fn create_tone_output<'d, T: GeneralInstance4Channel>(
+    pwm: PwmWrapper<'d, T>,
+    channel: Channel,
+    sequences: &str,
+    duty: f64,
+) -> Result<ToneOutput<'d, T>, PictorusError> {
+    ToneBlock::new(pwm, channel, sequences, duty)
+}
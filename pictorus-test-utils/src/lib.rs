@@ -0,0 +1,266 @@
+//! This crate provides structs used for unit testing blocks that implement Pictorus traits.
+//!
+//! Currently the [`StubContext`] and [`StubRuntime`] structs are provided. The [`StubContext`] struct
+//! implements the [`pictorus_traits::Context`] trait and can be used in unit tests to be able to make
+//! calls against block functionality. The [`StubRuntime`] struct wraps the [`StubContext`] struct
+//! and offers a convenient way to simulate the passage of time in a unit test.
+//!
+//! The [`assert_matrix_eq!`] and [`assert_matrix_approx_eq!`] macros compare two [`Matrix`] values
+//! element-wise and report the (row, column) of the first mismatch on failure.
+//!
+//! This crate should be considered unstable and only used as a development dependency.
+#![no_std]
+
+use core::time::Duration;
+use pictorus_traits::{Context, Matrix, Scalar};
+
+/// An implementation of the [`pictorus_traits::Context`] trait that can be used in unit tests.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StubContext {
+    pub time: Duration,
+    pub timestep: Option<Duration>,
+    pub fundamental_timestep: Duration,
+}
+
+impl StubContext {
+    pub fn new(time: Duration, timestep: Option<Duration>, fundamental_timestep: Duration) -> Self {
+        Self {
+            time,
+            timestep,
+            fundamental_timestep,
+        }
+    }
+}
+
+impl Default for StubContext {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(0), None, Duration::from_millis(100))
+    }
+}
+
+impl Context for StubContext {
+    fn time(&self) -> Duration {
+        self.time
+    }
+
+    fn timestep(&self) -> Option<Duration> {
+        self.timestep
+    }
+
+    fn fundamental_timestep(&self) -> Duration {
+        self.fundamental_timestep
+    }
+}
+
+/// A struct that wraps a [`StubContext`] and provides a convenient way to simulate the passage of time in a unit test.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StubRuntime {
+    pub context: StubContext,
+}
+
+impl StubRuntime {
+    pub fn new(context: StubContext) -> Self {
+        Self { context }
+    }
+
+    /// Create a runtime starting at time zero that ticks at the given fundamental timestep
+    pub fn with_timestep(fundamental_timestep: Duration) -> Self {
+        Self::new(StubContext::new(Duration::ZERO, None, fundamental_timestep))
+    }
+
+    /// Advance time by a single fundamental timestep
+    pub fn tick(&mut self) {
+        self.context.time += self.context.fundamental_timestep;
+        self.context.timestep = Some(self.context.fundamental_timestep);
+    }
+
+    /// Advance time by `n` fundamental timesteps
+    pub fn tick_n(&mut self, n: usize) {
+        for _ in 0..n {
+            self.tick();
+        }
+    }
+
+    /// Advance time by an arbitrary duration. This is useful for simulating a late or
+    /// skipped tick, since the reported timestep will not match the fundamental timestep.
+    pub fn tick_by(&mut self, timestep: Duration) {
+        self.context.time += timestep;
+        self.context.timestep = Some(timestep);
+    }
+
+    pub fn context(&self) -> StubContext {
+        self.context
+    }
+
+    pub fn set_time(&mut self, time: Duration) {
+        self.context.time = time;
+    }
+
+    /// Change the fundamental timestep used by subsequent calls to [`StubRuntime::tick`]
+    pub fn set_timestep(&mut self, fundamental_timestep: Duration) {
+        self.context.fundamental_timestep = fundamental_timestep;
+    }
+}
+
+/// Allows the matrix assertion macros to accept either a [`Matrix`] or a reference to one,
+/// since block outputs are usually returned as `&Matrix`.
+pub trait AsMatrix<const NROWS: usize, const NCOLS: usize, T: Scalar> {
+    fn as_matrix(&self) -> &Matrix<NROWS, NCOLS, T>;
+}
+
+impl<const NROWS: usize, const NCOLS: usize, T: Scalar> AsMatrix<NROWS, NCOLS, T>
+    for Matrix<NROWS, NCOLS, T>
+{
+    fn as_matrix(&self) -> &Matrix<NROWS, NCOLS, T> {
+        self
+    }
+}
+
+impl<const NROWS: usize, const NCOLS: usize, T: Scalar> AsMatrix<NROWS, NCOLS, T>
+    for &Matrix<NROWS, NCOLS, T>
+{
+    fn as_matrix(&self) -> &Matrix<NROWS, NCOLS, T> {
+        self
+    }
+}
+
+/// Returns the (row, col, left, right) of the first element for which `matches` returns false
+#[doc(hidden)]
+pub fn find_matrix_mismatch<const NROWS: usize, const NCOLS: usize, T: Scalar>(
+    left: &Matrix<NROWS, NCOLS, T>,
+    right: &Matrix<NROWS, NCOLS, T>,
+    matches: impl Fn(T, T) -> bool,
+) -> Option<(usize, usize, T, T)> {
+    for col in 0..NCOLS {
+        for row in 0..NROWS {
+            let (l, r) = (left.data[col][row], right.data[col][row]);
+            if !matches(l, r) {
+                return Some((row, col, l, r));
+            }
+        }
+    }
+    None
+}
+
+/// Asserts that two matrices are exactly equal, reporting the first mismatched element on failure.
+///
+/// Both arguments may be a [`Matrix`] or a `&Matrix`.
+#[macro_export]
+macro_rules! assert_matrix_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let left = $crate::AsMatrix::as_matrix(left);
+                let right = $crate::AsMatrix::as_matrix(right);
+                if let Some((row, col, l, r)) =
+                    $crate::find_matrix_mismatch(left, right, |l, r| l == r)
+                {
+                    panic!(
+                        "assertion `left == right` failed at (row {}, col {})\n  left: {:?}\n right: {:?}\n  left matrix: {:?}\n right matrix: {:?}",
+                        row, col, l, r, left, right
+                    );
+                }
+            }
+        }
+    };
+}
+
+/// Asserts that two matrices are equal within an absolute tolerance, reporting the first
+/// mismatched element on failure. The tolerance defaults to `1e-9` if not provided.
+///
+/// Both arguments may be a [`Matrix`] or a `&Matrix`.
+#[macro_export]
+macro_rules! assert_matrix_approx_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_matrix_approx_eq!($left, $right, 1e-9)
+    };
+    ($left:expr, $right:expr, $epsilon:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let left = $crate::AsMatrix::as_matrix(left);
+                let right = $crate::AsMatrix::as_matrix(right);
+                let epsilon: f64 = $epsilon;
+                if let Some((row, col, l, r)) = $crate::find_matrix_mismatch(left, right, |l, r| {
+                    let (l, r): (f64, f64) = (l.into(), r.into());
+                    l == r || (l - r).abs() <= epsilon
+                }) {
+                    panic!(
+                        "assertion `left ~= right` failed at (row {}, col {}) with epsilon {}\n  left: {:?}\n right: {:?}\n  left matrix: {:?}\n right matrix: {:?}",
+                        row, col, epsilon, l, r, left, right
+                    );
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runtime_tick() {
+        let mut runtime = StubRuntime::with_timestep(Duration::from_millis(10));
+        assert_eq!(runtime.context().timestep, None);
+
+        runtime.tick();
+        assert_eq!(runtime.context().time, Duration::from_millis(10));
+        assert_eq!(runtime.context().timestep, Some(Duration::from_millis(10)));
+
+        runtime.tick_n(4);
+        assert_eq!(runtime.context().time, Duration::from_millis(50));
+
+        runtime.tick_by(Duration::from_millis(25));
+        assert_eq!(runtime.context().time, Duration::from_millis(75));
+        assert_eq!(runtime.context().timestep, Some(Duration::from_millis(25)));
+
+        runtime.set_timestep(Duration::from_millis(1));
+        runtime.tick();
+        assert_eq!(runtime.context().time, Duration::from_millis(76));
+    }
+
+    #[test]
+    fn test_assert_matrix_eq() {
+        let a = Matrix {
+            data: [[1.0, 2.0], [3.0, 4.0]],
+        };
+        let b = a;
+        assert_matrix_eq!(a, &b);
+        assert_matrix_eq!(&a, b);
+    }
+
+    #[test]
+    #[should_panic(expected = "failed at (row 1, col 0)")]
+    fn test_assert_matrix_eq_mismatch() {
+        let a = Matrix {
+            data: [[1.0, 2.0], [3.0, 4.0]],
+        };
+        let b = Matrix {
+            data: [[1.0, 2.5], [3.0, 4.0]],
+        };
+        assert_matrix_eq!(a, b);
+    }
+
+    #[test]
+    fn test_assert_matrix_approx_eq() {
+        let a = Matrix {
+            data: [[1.0f32, 2.0], [3.0, 4.0]],
+        };
+        let b = Matrix {
+            data: [[1.0001f32, 2.0], [3.0, 3.9999]],
+        };
+        assert_matrix_approx_eq!(a, b, 1e-3);
+    }
+
+    #[test]
+    #[should_panic(expected = "failed at (row 0, col 1)")]
+    fn test_assert_matrix_approx_eq_mismatch() {
+        let a = Matrix {
+            data: [[1.0, 2.0], [3.0, 4.0]],
+        };
+        let b = Matrix {
+            data: [[1.0, 2.0], [3.1, 4.0]],
+        };
+        assert_matrix_approx_eq!(a, b, 1e-3);
+    }
+}
@@ -0,0 +1,82 @@
+//! Derive macros for `pictorus-traits`. Use them through the re-exports in `pictorus-traits`,
+//! enabled by its `derive` feature.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, GenericParam};
+
+/// Implements `Pass` for a struct of edge data, so blocks can exchange it as a single signal.
+/// See `pictorus_traits::PassStruct` for details.
+#[proc_macro_derive(PassStruct)]
+pub fn derive_pass_struct(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_pass_struct(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_pass_struct(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                input.ident.span(),
+                "PassStruct can only be derived for structs",
+            ))
+        }
+    };
+    if let Fields::Unit = fields {
+        return Err(Error::new(
+            input.ident.span(),
+            "PassStruct requires at least one field",
+        ));
+    }
+    if let Some(lifetime) = input
+        .generics
+        .params
+        .iter()
+        .find(|param| matches!(param, GenericParam::Lifetime(_)))
+    {
+        return Err(Error::new(
+            lifetime.span(),
+            "PassStruct can't borrow data, so the struct can't have lifetime parameters",
+        ));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut predicates = where_clause
+        .map(|clause| clause.predicates.iter().map(|p| quote!(#p)).collect())
+        .unwrap_or_else(Vec::new);
+    // Bound every field, spanned to the field so errors point at the offending type
+    predicates.extend(fields.iter().map(|field| {
+        let ty = &field.ty;
+        quote_spanned!(ty.span()=> #ty: ::pictorus_traits::StructField)
+    }));
+
+    Ok(quote! {
+        impl #impl_generics ::pictorus_traits::__private::Sealed for #name #ty_generics
+        where
+            #(#predicates,)*
+        {
+        }
+
+        impl #impl_generics ::pictorus_traits::Pass for #name #ty_generics
+        where
+            #(#predicates,)*
+        {
+            type By<'a> = &'a Self;
+
+            fn as_by(&self) -> Self::By<'_> {
+                self
+            }
+        }
+
+        impl #impl_generics ::pictorus_traits::StructField for #name #ty_generics
+        where
+            #(#predicates,)*
+        {
+        }
+    })
+}
@@ -0,0 +1,155 @@
+//! Reusable storage for blocks that output a [`ByteSliceSignal`](crate::ByteSliceSignal).
+//!
+//! Byte-producing blocks write into a [`BufferProvider`] instead of building a fresh `Vec` every
+//! tick. The storage is kept between ticks, so after the first few ticks no allocation happens
+//! on the hot path. `no_std` targets without an allocator can use [`FixedBuffer`], which never
+//! allocates and truncates writes that exceed its capacity.
+
+/// Backing storage for a block's byte slice output
+pub trait BufferProvider: Default {
+    /// The bytes currently held by the buffer
+    fn as_slice(&self) -> &[u8];
+
+    /// Removes all bytes while keeping the underlying storage for reuse
+    fn clear(&mut self);
+
+    /// Appends `data` to the buffer, returning the number of bytes written.
+    ///
+    /// Bounded buffers write as much of `data` as fits and drop the rest.
+    fn extend_from_slice(&mut self, data: &[u8]) -> usize;
+
+    /// The maximum number of bytes the buffer can hold, or `None` if it can grow without bound
+    fn capacity(&self) -> Option<usize>;
+
+    /// Replaces the contents of the buffer with `data`, returning the number of bytes written
+    fn copy_from_slice(&mut self, data: &[u8]) -> usize {
+        self.clear();
+        self.extend_from_slice(data)
+    }
+
+    /// Replaces the contents of the buffer by writing into it in place.
+    ///
+    /// `write` receives a zeroed slice of up to `max_len` bytes (less if the buffer's capacity is
+    /// smaller) and returns how many bytes it filled. This lets a driver read from a socket or
+    /// serial port directly into the buffer. Returns the resulting length of the buffer.
+    fn write_with(&mut self, max_len: usize, write: impl FnOnce(&mut [u8]) -> usize) -> usize;
+}
+
+/// Fixed-capacity [`BufferProvider`] that never allocates
+#[derive(Clone, Debug)]
+pub struct FixedBuffer<const N: usize> {
+    data: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> FixedBuffer<N> {
+    pub const fn new() -> Self {
+        Self {
+            data: [0; N],
+            len: 0,
+        }
+    }
+}
+
+impl<const N: usize> Default for FixedBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> BufferProvider for FixedBuffer<N> {
+    fn as_slice(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+
+    fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    fn extend_from_slice(&mut self, data: &[u8]) -> usize {
+        let n = data.len().min(N - self.len);
+        self.data[self.len..self.len + n].copy_from_slice(&data[..n]);
+        self.len += n;
+        n
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(N)
+    }
+
+    fn write_with(&mut self, max_len: usize, write: impl FnOnce(&mut [u8]) -> usize) -> usize {
+        let window = &mut self.data[..max_len.min(N)];
+        window.fill(0);
+        self.len = write(window).min(window.len());
+        self.len
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl BufferProvider for alloc::vec::Vec<u8> {
+    fn as_slice(&self) -> &[u8] {
+        self
+    }
+
+    fn clear(&mut self) {
+        alloc::vec::Vec::clear(self);
+    }
+
+    fn extend_from_slice(&mut self, data: &[u8]) -> usize {
+        alloc::vec::Vec::extend_from_slice(self, data);
+        data.len()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+
+    fn write_with(&mut self, max_len: usize, write: impl FnOnce(&mut [u8]) -> usize) -> usize {
+        self.clear();
+        self.resize(max_len, 0);
+        let len = write(self.as_mut_slice()).min(max_len);
+        self.truncate(len);
+        len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_buffer_truncates_at_capacity() {
+        let mut buffer = FixedBuffer::<4>::new();
+        assert_eq!(buffer.as_slice(), b"");
+        assert_eq!(buffer.capacity(), Some(4));
+
+        assert_eq!(buffer.extend_from_slice(b"ab"), 2);
+        assert_eq!(buffer.extend_from_slice(b"cde"), 2);
+        assert_eq!(buffer.as_slice(), b"abcd");
+
+        assert_eq!(buffer.copy_from_slice(b"xyz"), 3);
+        assert_eq!(buffer.as_slice(), b"xyz");
+
+        buffer.clear();
+        assert_eq!(buffer.as_slice(), b"");
+    }
+
+    #[test]
+    fn test_fixed_buffer_write_with() {
+        let mut buffer = FixedBuffer::<8>::new();
+        buffer.copy_from_slice(b"stale");
+
+        let len = buffer.write_with(16, |window| {
+            assert_eq!(window, &[0; 8]);
+            window[..3].copy_from_slice(b"new");
+            3
+        });
+        assert_eq!(len, 3);
+        assert_eq!(buffer.as_slice(), b"new");
+
+        // A writer claiming more than the window it was given is clamped
+        let len = buffer.write_with(2, |_| 10);
+        assert_eq!(len, 2);
+        assert_eq!(buffer.as_slice(), &[0, 0]);
+    }
+}
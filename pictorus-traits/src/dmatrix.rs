@@ -0,0 +1,133 @@
+//! Matrices whose size is only known at runtime
+use alloc::vec::Vec;
+
+use crate::{Matrix, Pass, Scalar, Sealed};
+
+/// Matrix in column-major order whose dimensions are set at runtime, for data like variable-length
+/// point lists from a sensor. Requires the `alloc` feature.
+///
+/// Prefer [`Matrix`] whenever the size is known when the model is built: it doesn't allocate,
+/// and size mismatches are caught at compile time rather than at runtime.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DMatrix<T: Scalar> {
+    nrows: usize,
+    ncols: usize,
+    data: Vec<T>,
+}
+
+impl<T: Scalar> DMatrix<T> {
+    pub fn zeroed(nrows: usize, ncols: usize) -> Self {
+        Self {
+            nrows,
+            ncols,
+            data: alloc::vec![T::default(); nrows * ncols],
+        }
+    }
+
+    /// Creates a matrix from its elements in column-major order. Returns `None` if `data`
+    /// doesn't hold exactly `nrows * ncols` elements.
+    pub fn from_column_slice(nrows: usize, ncols: usize, data: &[T]) -> Option<Self> {
+        (data.len() == nrows * ncols).then(|| Self {
+            nrows,
+            ncols,
+            data: data.to_vec(),
+        })
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    /// The elements in column-major order
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// The elements in column-major order
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<T> {
+        (row < self.nrows && col < self.ncols).then(|| self.data[col * self.nrows + row])
+    }
+
+    /// Changes the dimensions, zeroing all elements. Reuses the existing allocation when it's
+    /// large enough, so resizing every tick doesn't allocate once the matrix has grown to its
+    /// largest size.
+    pub fn reshape_zeroed(&mut self, nrows: usize, ncols: usize) {
+        self.nrows = nrows;
+        self.ncols = ncols;
+        self.data.clear();
+        self.data.resize(nrows * ncols, T::default());
+    }
+
+    /// Copies `other` into `self`, reusing `self`'s allocation when possible
+    pub fn copy_from(&mut self, other: &DMatrix<T>) {
+        self.nrows = other.nrows;
+        self.ncols = other.ncols;
+        self.data.clear();
+        self.data.extend_from_slice(&other.data);
+    }
+}
+
+impl<const NROWS: usize, const NCOLS: usize, T: Scalar> From<&Matrix<NROWS, NCOLS, T>>
+    for DMatrix<T>
+{
+    fn from(matrix: &Matrix<NROWS, NCOLS, T>) -> Self {
+        Self {
+            nrows: NROWS,
+            ncols: NCOLS,
+            data: matrix.data.as_flattened().to_vec(),
+        }
+    }
+}
+
+impl<T: Scalar> Pass for DMatrix<T> {
+    type By<'a> = &'a Self;
+
+    fn as_by(&self) -> Self::By<'_> {
+        self
+    }
+}
+
+impl<T: Scalar> Sealed for DMatrix<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dmatrix_layout() {
+        let matrix = DMatrix::from_column_slice(2, 3, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+        assert_eq!((matrix.nrows(), matrix.ncols()), (2, 3));
+        assert_eq!(matrix.get(1, 0), Some(2.0));
+        assert_eq!(matrix.get(0, 2), Some(5.0));
+        assert_eq!(matrix.get(2, 0), None);
+        assert!(DMatrix::from_column_slice(2, 2, &[1.0, 2.0, 3.0]).is_none());
+
+        let fixed = Matrix {
+            data: [[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]],
+        };
+        assert_eq!(DMatrix::from(&fixed), matrix);
+    }
+
+    #[test]
+    fn test_dmatrix_reshape() {
+        let mut matrix = DMatrix::<f32>::default();
+        assert_eq!(matrix.as_slice(), &[]);
+        matrix.reshape_zeroed(3, 1);
+        assert_eq!(matrix.as_slice(), &[0.0; 3]);
+        matrix.as_mut_slice()[2] = 1.0;
+        assert_eq!(matrix.get(2, 0), Some(1.0));
+
+        let mut copy = DMatrix::zeroed(1, 1);
+        copy.copy_from(&matrix);
+        assert_eq!(copy, matrix);
+        assert!(core::ptr::eq(copy.as_by(), &copy));
+    }
+}
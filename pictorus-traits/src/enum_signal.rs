@@ -0,0 +1,137 @@
+//! Discrete signals with named values, such as flight modes or state IDs
+//!
+//! Instead of encoding modes as magic `f64` constants, declare them with [`signal_enum!`] and pass
+//! them between blocks as [`EnumSignal`]s:
+//!
+//! ```rust
+//! use pictorus_traits::{signal_enum, EnumSignal, SignalEnum};
+//!
+//! signal_enum! {
+//!     /// Flight modes of the vehicle
+//!     pub enum FlightMode {
+//!         Manual = 0,
+//!         Stabilized = 1,
+//!         Mission = 4,
+//!     }
+//! }
+//!
+//! let mode = EnumSignal(FlightMode::Stabilized);
+//! assert_eq!(mode.0.id(), 1);
+//! assert_eq!(FlightMode::from_name("Mission"), Some(FlightMode::Mission));
+//! assert_eq!(FlightMode::from_id(2), None);
+//! // The first variant is the default
+//! assert_eq!(FlightMode::default(), FlightMode::Manual);
+//! ```
+//!
+//! The ids are what's sent over telemetry and logged, so keep them stable once a model is
+//! deployed.
+use crate::{Pass, Sealed};
+
+/// A discrete value with a fixed set of named variants. Implement it with [`signal_enum!`].
+pub trait SignalEnum: Copy + PartialEq + Default + 'static {
+    /// Every variant, in declaration order
+    const VARIANTS: &'static [Self];
+
+    /// Numeric id of the variant, used for telemetry and logging
+    fn id(self) -> u16;
+
+    fn name(self) -> &'static str;
+
+    fn from_id(id: u16) -> Option<Self> {
+        Self::VARIANTS.iter().copied().find(|v| v.id() == id)
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::VARIANTS.iter().copied().find(|v| v.name() == name)
+    }
+}
+
+/// Edge data holding one variant of a [`SignalEnum`]. It's passed between blocks by value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EnumSignal<E: SignalEnum>(pub E);
+
+impl<E: SignalEnum> Pass for EnumSignal<E> {
+    type By<'a> = E;
+
+    fn as_by(&self) -> Self::By<'_> {
+        self.0
+    }
+}
+
+impl<E: SignalEnum> Sealed for EnumSignal<E> {}
+
+/// Declares a fieldless enum with explicit `u16` ids and implements [`SignalEnum`] for it. The
+/// first variant is the enum's default.
+#[macro_export]
+macro_rules! signal_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(#[$default_meta:meta])* $default:ident = $default_id:literal
+            $(, $(#[$variant_meta:meta])* $variant:ident = $id:literal)* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[repr(u16)]
+        $vis enum $name {
+            $(#[$default_meta])* $default = $default_id,
+            $($(#[$variant_meta])* $variant = $id,)*
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::$default
+            }
+        }
+
+        impl $crate::SignalEnum for $name {
+            const VARIANTS: &'static [Self] = &[Self::$default, $(Self::$variant,)*];
+
+            fn id(self) -> u16 {
+                self as u16
+            }
+
+            fn name(self) -> &'static str {
+                match self {
+                    Self::$default => stringify!($default),
+                    $(Self::$variant => stringify!($variant),)*
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PassBy;
+
+    signal_enum! {
+        enum Gear {
+            Park = 0,
+            /// Doc comments on variants are kept
+            Reverse = 1,
+            Drive = 3,
+        }
+    }
+
+    #[test]
+    fn test_signal_enum_ids_and_names() {
+        assert_eq!(Gear::VARIANTS, &[Gear::Park, Gear::Reverse, Gear::Drive]);
+        assert_eq!(Gear::Drive.id(), 3);
+        assert_eq!(Gear::Reverse.name(), "Reverse");
+        assert_eq!(Gear::from_id(3), Some(Gear::Drive));
+        assert_eq!(Gear::from_id(2), None);
+        assert_eq!(Gear::from_name("Park"), Some(Gear::Park));
+        assert_eq!(Gear::from_name("park"), None);
+    }
+
+    #[test]
+    fn test_enum_signal_pass() {
+        let signal = EnumSignal(Gear::Drive);
+        let by: PassBy<'_, EnumSignal<Gear>> = signal.as_by();
+        assert_eq!(by, Gear::Drive);
+        assert_eq!(EnumSignal::<Gear>::default().as_by(), Gear::Park);
+    }
+}
@@ -0,0 +1,271 @@
+//! Fixed-point scalar type for targets without a floating point unit
+
+use crate::{Promote, Scalar, Sealed};
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// A signed fixed-point number stored in an `i32` with `FRAC` fractional bits.
+///
+/// All arithmetic saturates at the representable range instead of wrapping, and
+/// multiplication/division round to the nearest representable value. Division by zero
+/// saturates towards the sign of the numerator.
+///
+/// `FRAC` must be at most 31. Common formats have aliases; [`Q15`] (range ±65536 with a resolution
+/// of ~3e-5) and [`Q31`] (range [-1, 1) with a resolution of ~5e-10).
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Fixed<const FRAC: u32>(i32);
+
+/// Fixed-point with 15 fractional bits
+pub type Q15 = Fixed<15>;
+/// Fixed-point with 31 fractional bits, covering the range [-1, 1)
+pub type Q31 = Fixed<31>;
+
+impl<const FRAC: u32> Fixed<FRAC> {
+    const VALID_FRAC: () = assert!(
+        FRAC <= 31,
+        "Fixed<FRAC> supports at most 31 fractional bits"
+    );
+
+    pub const ZERO: Self = Self(0);
+    /// One, or the largest representable value if one is out of range (i.e. for [`Q31`])
+    pub const ONE: Self = Self(if FRAC >= 31 { i32::MAX } else { 1 << FRAC });
+    pub const MIN: Self = Self(i32::MIN);
+    pub const MAX: Self = Self(i32::MAX);
+
+    /// Create a value from its raw underlying representation
+    pub const fn from_bits(bits: i32) -> Self {
+        let () = Self::VALID_FRAC;
+        Self(bits)
+    }
+
+    /// The raw underlying representation
+    pub const fn to_bits(self) -> i32 {
+        self.0
+    }
+
+    /// Convert from a float, rounding to the nearest value and saturating if out of range.
+    /// NaN converts to zero.
+    pub fn from_f64(value: f64) -> Self {
+        let scaled = value * (1u64 << FRAC) as f64;
+        // Float to int `as` casts saturate, and map NaN to zero
+        let rounded = if scaled >= 0.0 {
+            scaled + 0.5
+        } else {
+            scaled - 0.5
+        };
+        Self::from_bits(rounded as i32)
+    }
+
+    pub fn from_f32(value: f32) -> Self {
+        Self::from_f64(value.into())
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / (1u64 << FRAC) as f64
+    }
+
+    pub fn to_f32(self) -> f32 {
+        self.to_f64() as f32
+    }
+
+    fn saturate(value: i64) -> Self {
+        Self(value.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+}
+
+impl<const FRAC: u32> From<Fixed<FRAC>> for f64 {
+    fn from(value: Fixed<FRAC>) -> Self {
+        value.to_f64()
+    }
+}
+
+impl<const FRAC: u32> fmt::Debug for Fixed<FRAC> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fixed<{}>({})", FRAC, self.to_f64())
+    }
+}
+
+impl<const FRAC: u32> Add for Fixed<FRAC> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl<const FRAC: u32> Sub for Fixed<FRAC> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl<const FRAC: u32> Mul for Fixed<FRAC> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let product = self.0 as i64 * rhs.0 as i64;
+        if FRAC == 0 {
+            return Self::saturate(product);
+        }
+        // Round half away from zero before dropping the extra fractional bits
+        let half = 1i64 << (FRAC - 1);
+        let rounded = if product >= 0 {
+            product + half
+        } else {
+            product - half
+        };
+        Self::saturate(rounded / (1i64 << FRAC))
+    }
+}
+
+impl<const FRAC: u32> Div for Fixed<FRAC> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        if rhs.0 == 0 {
+            return match self.0.cmp(&0) {
+                Ordering::Less => Self::MIN,
+                Ordering::Equal => Self::ZERO,
+                Ordering::Greater => Self::MAX,
+            };
+        }
+        let numerator = (self.0 as i64) << FRAC;
+        let denominator = rhs.0 as i64;
+        // Round half away from zero
+        let half = denominator.abs() / 2;
+        let rounded = if numerator >= 0 {
+            numerator + half
+        } else {
+            numerator - half
+        };
+        Self::saturate(rounded / denominator)
+    }
+}
+
+impl<const FRAC: u32> Neg for Fixed<FRAC> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(self.0.saturating_neg())
+    }
+}
+
+impl<const FRAC: u32> AddAssign for Fixed<FRAC> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const FRAC: u32> SubAssign for Fixed<FRAC> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const FRAC: u32> MulAssign for Fixed<FRAC> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const FRAC: u32> DivAssign for Fixed<FRAC> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<const FRAC: u32> Scalar for Fixed<FRAC> {}
+impl<const FRAC: u32> Sealed for Fixed<FRAC> {}
+
+impl<const FRAC: u32> Promote<Fixed<FRAC>> for Fixed<FRAC> {
+    type Output = Self;
+
+    fn promote_left(self) -> Self::Output {
+        self
+    }
+
+    fn promote_right(rhs: Self) -> Self::Output {
+        rhs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(Q15::from_f64(1.0).to_bits(), 1 << 15);
+        assert_eq!(Q15::from_f64(-0.5).to_bits(), -(1 << 14));
+        assert_eq!(Q15::from_f32(2.25).to_f64(), 2.25);
+        assert_eq!(Q31::from_f64(0.5).to_bits(), 1 << 30);
+        assert_eq!(Q31::from_f64(0.5).to_f32(), 0.5);
+
+        // Out of range values saturate, NaN maps to zero
+        assert_eq!(Q31::from_f64(1.0), Q31::MAX);
+        assert_eq!(Q31::from_f64(-2.0), Q31::MIN);
+        assert_eq!(Q15::from_f64(f64::NAN), Q15::ZERO);
+
+        let value: f64 = Q15::from_f64(3.5).into();
+        assert_eq!(value, 3.5);
+    }
+
+    #[test]
+    fn test_add_sub_saturate() {
+        let a = Q15::from_f64(1.5);
+        let b = Q15::from_f64(0.25);
+        assert_eq!(a + b, Q15::from_f64(1.75));
+        assert_eq!(a - b, Q15::from_f64(1.25));
+        assert_eq!(-a, Q15::from_f64(-1.5));
+        assert_eq!(Q15::MAX + b, Q15::MAX);
+        assert_eq!(Q15::MIN - b, Q15::MIN);
+        assert_eq!(-Q15::MIN, Q15::MAX);
+    }
+
+    #[test]
+    fn test_mul() {
+        assert_eq!(
+            Q15::from_f64(1.5) * Q15::from_f64(-2.0),
+            Q15::from_f64(-3.0)
+        );
+        assert_eq!(Q31::from_f64(0.5) * Q31::from_f64(0.5), Q31::from_f64(0.25));
+        assert_eq!(Q15::from_f64(30000.0) * Q15::from_f64(3.0), Q15::MAX);
+        assert_eq!(Q15::from_f64(30000.0) * Q15::from_f64(-3.0), Q15::MIN);
+
+        // Smallest value squared rounds to zero, half an LSB rounds away from zero
+        let lsb = Q15::from_bits(1);
+        assert_eq!(lsb * lsb, Q15::ZERO);
+        assert_eq!(Q15::from_bits(3) * Q15::from_f64(0.5), Q15::from_bits(2));
+
+        let mut value = Q15::from_f64(2.0);
+        value *= Q15::from_f64(0.25);
+        assert_eq!(value, Q15::from_f64(0.5));
+    }
+
+    #[test]
+    fn test_div() {
+        assert_eq!(
+            Q15::from_f64(3.0) / Q15::from_f64(-2.0),
+            Q15::from_f64(-1.5)
+        );
+        assert_eq!(Q31::from_f64(0.25) / Q31::from_f64(0.5), Q31::from_f64(0.5));
+        assert_eq!(
+            Q15::from_f64(1.0) / Q15::from_f64(3.0),
+            Q15::from_bits(10923)
+        );
+        assert_eq!(Q15::from_f64(1.0) / Q15::ZERO, Q15::MAX);
+        assert_eq!(Q15::from_f64(-1.0) / Q15::ZERO, Q15::MIN);
+        assert_eq!(Q15::ZERO / Q15::ZERO, Q15::ZERO);
+        assert_eq!(Q15::from_f64(60000.0) / Q15::from_f64(0.5), Q15::MAX);
+    }
+
+    #[test]
+    fn test_q31_one() {
+        assert_eq!(Q15::ONE, Q15::from_f64(1.0));
+        assert_eq!(Q31::ONE, Q31::MAX);
+    }
+}
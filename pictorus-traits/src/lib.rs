@@ -74,7 +74,7 @@
 //! ## Edges
 //! Edges in the system graph represent data traveling between blocks. Data is transmitted once per system tick.
 //! The data can take the following forms:
-//! - Scalar: A single value, through generics it can be an `f32`, `f64`, `bool`, or `u8`
+//! - Scalar: A single value, through generics it can be an `f32`, `f64`, `bool`, `u8`, or a fixed-point [`Fixed`]
 //! - A fixed size 2D matrix: All the elements of a given matrix must be the same type, that type can be any of the scalar types
 //! - A `ByteSliceSignal` which represents a 1D slice of `u8`: Used for communicating byte streams
 //!
@@ -130,12 +130,18 @@
 //! that implement them.
 //!
 //! The following types can be used as edge data:
-//! - `Scalar`s: The `Scalar` trait is implemented for; `f32`, `f64`, `u8`, `u16`, and `bool`.
+//! - `Scalar`s: The `Scalar` trait is implemented for; `f32`, `f64`, `u8`, `u16`, `bool`, and [`Fixed`].
 //!   They represent the individual values in the system
 //! - [`ByteSliceSignal`]: Used for byte streams, this is essentially a stand-in for `[u8]`.
 //!   This is necessary because `[u8]` is not a `Sized` type and therefore there are constraints on where it can show up in types definitions.
+//! - [`StrSliceSignal`]: Like `ByteSliceSignal` but for text, a stand-in for `str` that guarantees the data is valid UTF-8.
+//! - [`EnumSignal`]: A named discrete value, such as a flight mode, declared with [`signal_enum!`].
+//! - Structs deriving `PassStruct`: Records whose fields are any of the above (except slices), see the [`pass_struct`] module.
 //! - `Matrix<const NROWS: usize, const NCOLS: usize, T: Scalar>`: This type is used to model matrices of a singular scalar type
 //!   (as well as vectors; being a special case of matrices where one dimension has a size of 1). They have a fixed size that must be known at compile time and don't require on `alloc`. Every element of a given matrix must be the same scalar type
+//! - `DMatrix<T: Scalar>`: A matrix whose dimensions are only known at runtime, for `std` targets. Requires the `alloc` feature
+//! - [`UnitTagged`]: Wraps any of the above with a unit of measure, so that connecting signals with mismatched units
+//!   fails to compile. See the [`units`] module.
 //!
 //! And finally to support blocks with multiple inputs tuples composed of all types that implement `Pass` will also implement `Pass`.
 //! That is:
@@ -299,17 +305,53 @@
 #![no_std]
 // and conditionally no_alloc
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 use core::mem;
 use core::time::Duration;
 
+// lets the derive macros' `::pictorus_traits` paths resolve within this crate's own tests
+extern crate self as pictorus_traits;
+
 mod sealed;
 use sealed::Sealed;
 
+/// Items used by code generated by the derive macros. Not public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::sealed::Sealed;
+}
+
+pub mod buffer;
+pub use buffer::{BufferProvider, FixedBuffer};
+
 pub mod custom_blocks;
 pub use custom_blocks::*;
 
 pub mod tuple_array_interop;
 
+#[cfg(feature = "alloc")]
+pub mod dmatrix;
+#[cfg(feature = "alloc")]
+pub use dmatrix::DMatrix;
+
+pub mod enum_signal;
+pub use enum_signal::{EnumSignal, SignalEnum};
+
+pub mod fixed;
+pub use fixed::{Fixed, Q15, Q31};
+
+pub mod pass_struct;
+pub use pass_struct::StructField;
+/// Implements [`Pass`] for a struct whose fields are all [`StructField`]s, so it can be passed
+/// between blocks by reference. See the [`pass_struct`] module.
+#[cfg(feature = "derive")]
+pub use pictorus_traits_derive::PassStruct;
+
+pub mod units;
+pub use units::UnitTagged;
+
 /// A processing block
 pub trait ProcessBlock: Default {
     // NOTE because of the `Inputs` trait bound; all blocks must have at least *one* input
@@ -543,6 +585,20 @@ impl Pass for ByteSliceSignal {
     }
 }
 
+/// The text counterpart of [`ByteSliceSignal`]: a Zero-Size-Type used as a stand-in for `str`. It defines
+/// `By = &str`, so blocks receiving it can rely on the data being valid UTF-8
+pub struct StrSliceSignal;
+
+impl Sealed for StrSliceSignal {}
+
+impl Pass for StrSliceSignal {
+    type By<'a> = &'a str;
+
+    fn as_by(&self) -> Self::By<'_> {
+        ""
+    }
+}
+
 /// Matrix in column-major order
 // this type is only used as a "DTO" (Data Transfer Object). meaning that this type has
 // no methods and it does NOT enforce any sort of invariants (which is why its field is public).
@@ -589,8 +645,8 @@ where
 {
     pub fn zeroed() -> Self {
         // SAFETY: `T: Scalar` is "sealed" so we know all the types it could be instantiated to and
-        // we also know they are all primitives / "plain old data" so all bits set to zero is
-        // a valid representation
+        // we also know they are all primitives / "plain old data" (`Fixed` is a transparent `i32`)
+        // so all bits set to zero is a valid representation
         Self {
             data: unsafe { mem::zeroed() },
         }
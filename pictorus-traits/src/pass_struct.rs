@@ -0,0 +1,95 @@
+//! Structs as edge data
+//!
+//! Blocks that exchange several related signals, like a GPS fix or an IMU sample, can bundle them
+//! into a struct instead of a long anonymous tuple. Deriving `PassStruct` (with the `derive`
+//! feature) implements [`Pass`] for the struct, which is then passed between blocks by reference:
+//!
+//! ```rust
+//! # #[cfg(feature = "derive")]
+//! # {
+//! use pictorus_traits::{Matrix, PassBy, PassStruct};
+//!
+//! #[derive(PassStruct, Clone, Copy, Default)]
+//! pub struct ImuSample {
+//!     pub accel: Matrix<3, 1, f32>,
+//!     pub gyro: Matrix<3, 1, f32>,
+//!     pub temperature: f32,
+//! }
+//!
+//! fn is_overheating(sample: PassBy<'_, ImuSample>) -> bool {
+//!     sample.temperature > 85.0
+//! }
+//! # }
+//! ```
+//!
+//! Every field must be a [`StructField`]: a scalar, matrix or array of scalars, an
+//! [`EnumSignal`], a [`UnitTagged`] field or another `PassStruct`. Slices and other borrowed data
+//! can't be fields, since structs own their data.
+use crate::units::Unit;
+use crate::{EnumSignal, Matrix, Pass, Scalar, SignalEnum, UnitTagged};
+
+/// Edge data that can be a field of a struct deriving `PassStruct`
+pub trait StructField: Pass {}
+
+impl<T: Scalar> StructField for T {}
+
+impl<const NROWS: usize, const NCOLS: usize, T: Scalar> StructField for Matrix<NROWS, NCOLS, T> {}
+
+impl<const N: usize, T: Scalar> StructField for [T; N] {}
+
+impl<E: SignalEnum> StructField for EnumSignal<E> {}
+
+impl<T: StructField, U: Unit> StructField for UnitTagged<T, U> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::units::Meter;
+    use crate::{EnumSignal, Matrix, Pass, PassBy, UnitTagged};
+    use pictorus_traits_derive::PassStruct;
+
+    crate::signal_enum! {
+        enum FixType {
+            NoFix = 0,
+            Fix3d = 3,
+        }
+    }
+
+    #[derive(PassStruct, Default)]
+    struct GpsFix {
+        position: Matrix<3, 1, f64>,
+        altitude: UnitTagged<f64, Meter>,
+        fix_type: EnumSignal<FixType>,
+        satellites: u8,
+    }
+
+    #[derive(PassStruct, Default)]
+    struct Tagged<T: Default>(T, GpsFix);
+
+    fn satellites(fix: PassBy<'_, GpsFix>) -> u8 {
+        fix.satellites
+    }
+
+    #[test]
+    fn test_pass_struct() {
+        let fix = GpsFix {
+            satellites: 7,
+            fix_type: EnumSignal(FixType::Fix3d),
+            ..Default::default()
+        };
+        assert!(core::ptr::eq(fix.as_by(), &fix));
+        assert_eq!(satellites(fix.as_by()), 7);
+        assert_eq!(fix.as_by().position.data, [[0.0; 3]]);
+        assert_eq!(fix.as_by().altitude.symbol(), "m");
+    }
+
+    #[test]
+    fn test_pass_struct_nested_generic() {
+        let tagged = Tagged::<f32>(2.5, GpsFix::default());
+        let by = tagged.as_by();
+        assert_eq!(by.0, 2.5);
+        assert_eq!(by.1.fix_type.0, FixType::NoFix);
+        // Structs can be members of tuples like any other edge data
+        let pair: PassBy<'_, (f64, Tagged<f32>)> = (1.0, &tagged);
+        assert_eq!(pair.1 .0, 2.5);
+    }
+}
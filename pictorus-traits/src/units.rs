@@ -0,0 +1,199 @@
+//! Units of measure for edge data
+//!
+//! Wrapping a signal in [`UnitTagged`] records its unit in the signal's type, so connecting a
+//! signal in degrees to an input that expects radians fails to compile instead of silently
+//! producing wrong results. Units are zero-sized marker types and cost nothing at runtime.
+//!
+//! Every unit belongs to a dimension, such as [`Angle`] or [`Speed`], and knows its scale
+//! relative to the SI unit of that dimension. Converting between units of the same dimension is
+//! a multiplication by [`conversion_factor`]; converting between dimensions doesn't compile.
+//! Only units that are a pure scale of each other are supported, so e.g. temperatures in °C and
+//! °F can't be units of the same dimension.
+//!
+//! ```rust
+//! use pictorus_traits::units::{conversion_factor, Degree, Knot, MetersPerSecond, Radian};
+//!
+//! assert_eq!(conversion_factor::<Degree, Radian>(), core::f64::consts::PI / 180.0);
+//! assert_eq!(conversion_factor::<Knot, MetersPerSecond>(), 1852.0 / 3600.0);
+//! ```
+//!
+//! ```rust compile_fail
+//! use pictorus_traits::units::{conversion_factor, Degree, Knot};
+//!
+//! conversion_factor::<Degree, Knot>();
+//! ```
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::{Pass, PassBy, Sealed};
+
+/// A unit of measure
+pub trait Unit: 'static {
+    /// Marker type for the physical quantity the unit measures. Units can only be converted into
+    /// units of the same dimension.
+    type Dimension;
+    /// Symbol used when displaying values, e.g. "rad"
+    const SYMBOL: &'static str;
+    /// The value of one of this unit in the SI unit of its dimension
+    const SCALE: f64;
+}
+
+/// Dimension of plane angles
+pub enum Angle {}
+/// Dimension of lengths
+pub enum Length {}
+/// Dimension of speeds
+pub enum Speed {}
+
+macro_rules! units {
+    ($( $(#[$doc:meta])* $unit:ident: $dimension:ident = $scale:expr, $symbol:literal; )+) => {
+        $(
+            $(#[$doc])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            pub enum $unit {}
+
+            impl Unit for $unit {
+                type Dimension = $dimension;
+                const SYMBOL: &'static str = $symbol;
+                const SCALE: f64 = $scale;
+            }
+        )+
+    };
+}
+
+units! {
+    /// SI unit of angle
+    Radian: Angle = 1.0, "rad";
+    Degree: Angle = core::f64::consts::PI / 180.0, "deg";
+    /// SI unit of length
+    Meter: Length = 1.0, "m";
+    /// International foot
+    Foot: Length = 0.3048, "ft";
+    /// International nautical mile
+    NauticalMile: Length = 1852.0, "nmi";
+    /// SI unit of speed
+    MetersPerSecond: Speed = 1.0, "m/s";
+    KilometersPerHour: Speed = 1.0 / 3.6, "km/h";
+    /// Nautical miles per hour
+    Knot: Speed = 1852.0 / 3600.0, "kt";
+}
+
+/// What a value in `From` units is multiplied by to express it in `To` units
+pub fn conversion_factor<From: Unit, To: Unit<Dimension = From::Dimension>>() -> f64 {
+    From::SCALE / To::SCALE
+}
+
+/// A value annotated with its unit of measure.
+///
+/// `UnitTagged` is passed between blocks the same way as the value it wraps, and has the same
+/// memory layout.
+#[repr(transparent)]
+pub struct UnitTagged<T, U: Unit> {
+    pub value: T,
+    // `fn() -> U` keeps the tag from affecting auto traits like `Send`
+    unit: PhantomData<fn() -> U>,
+}
+
+impl<T, U: Unit> UnitTagged<T, U> {
+    pub const fn new(value: T) -> Self {
+        Self {
+            value,
+            unit: PhantomData,
+        }
+    }
+
+    pub fn into_value(self) -> T {
+        self.value
+    }
+
+    /// Symbol of the value's unit, e.g. "rad"
+    pub fn symbol(&self) -> &'static str {
+        U::SYMBOL
+    }
+}
+
+// Implemented manually since derives would require the unit marker to implement the traits too
+impl<T: Clone, U: Unit> Clone for UnitTagged<T, U> {
+    fn clone(&self) -> Self {
+        Self::new(self.value.clone())
+    }
+}
+
+impl<T: Copy, U: Unit> Copy for UnitTagged<T, U> {}
+
+impl<T: Default, U: Unit> Default for UnitTagged<T, U> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: PartialEq, U: Unit> PartialEq for UnitTagged<T, U> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: fmt::Debug, U: Unit> fmt::Debug for UnitTagged<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} {}", self.value, U::SYMBOL)
+    }
+}
+
+impl<T: fmt::Display, U: Unit> fmt::Display for UnitTagged<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.value, U::SYMBOL)
+    }
+}
+
+impl<T: Pass, U: Unit> Pass for UnitTagged<T, U> {
+    type By<'a> = UnitTagged<PassBy<'a, T>, U>;
+
+    fn as_by(&self) -> Self::By<'_> {
+        UnitTagged::new(self.value.as_by())
+    }
+}
+
+impl<T: Pass, U: Unit> Sealed for UnitTagged<T, U> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Matrix;
+
+    #[test]
+    fn test_conversion_factors() {
+        assert_eq!(conversion_factor::<Radian, Radian>(), 1.0);
+        assert!(
+            (180.0 * conversion_factor::<Degree, Radian>() - core::f64::consts::PI).abs() < 1e-12
+        );
+        assert!((conversion_factor::<Radian, Degree>() - 57.29577951308232).abs() < 1e-12);
+        assert!(
+            (10.0 * conversion_factor::<MetersPerSecond, Knot>() - 19.438444924406).abs() < 1e-9
+        );
+        assert!(
+            (conversion_factor::<KilometersPerHour, MetersPerSecond>() - 1.0 / 3.6).abs() < 1e-12
+        );
+        assert!((conversion_factor::<NauticalMile, Foot>() - 6076.115485564304).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unit_tagged_pass() {
+        let speed = UnitTagged::<f64, Knot>::new(12.5);
+        let by: PassBy<'_, UnitTagged<f64, Knot>> = speed.as_by();
+        assert_eq!(by, speed);
+        assert_eq!(by.into_value(), 12.5);
+
+        let position = UnitTagged::<Matrix<1, 3, f32>, Meter>::default();
+        let by = position.as_by();
+        assert!(core::ptr::eq(by.value, &position.value));
+        assert_eq!(by.symbol(), "m");
+    }
+
+    #[test]
+    fn test_unit_tagged_layout() {
+        assert_eq!(
+            core::mem::size_of::<UnitTagged<f32, Degree>>(),
+            core::mem::size_of::<f32>()
+        );
+    }
+}